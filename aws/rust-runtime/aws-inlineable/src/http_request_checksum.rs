@@ -7,7 +7,7 @@
 
 //! Interceptor for handling Smithy `@httpChecksum` request checksumming with AWS SigV4
 
-use aws_runtime::content_encoding::{AwsChunkedBody, AwsChunkedBodyOptions};
+use aws_runtime::content_encoding::{AwsChunkedBody, AwsChunkedBodyOptions, DeferredChunkSigner};
 use aws_runtime::{auth::SigV4OperationSigningConfig, content_encoding::header_value::AWS_CHUNKED};
 use aws_sigv4::http_request::SignableBody;
 use aws_smithy_checksums::ChecksumAlgorithm;
@@ -190,20 +190,44 @@ fn add_checksum_for_request_body(
         // Body is streaming: wrap the body so it will emit a checksum as a trailer.
         None => {
             tracing::debug!("applying {checksum_algorithm:?} of the request body as a trailer");
+            let mut chunk_signer = None;
             if let Some(mut signing_config) = cfg.load::<SigV4OperationSigningConfig>().cloned() {
-                signing_config.signing_options.payload_override =
-                    Some(SignableBody::StreamingUnsignedPayloadTrailer);
+                // Without TLS, nothing protects an unsigned payload, so every chunk is signed
+                signing_config.signing_options.payload_override = if is_plain_http(request) {
+                    let signer = DeferredChunkSigner::new();
+                    cfg.interceptor_state().store_put(signer.clone());
+                    chunk_signer = Some(signer);
+                    Some(SignableBody::StreamingSignedPayloadTrailer)
+                } else {
+                    Some(SignableBody::StreamingUnsignedPayloadTrailer)
+                };
                 cfg.interceptor_state().store_put(signing_config);
             }
-            wrap_streaming_request_body_in_checksum_calculating_body(request, checksum_algorithm)?;
+            wrap_streaming_request_body_in_checksum_calculating_body(
+                request,
+                checksum_algorithm,
+                chunk_signer,
+            )?;
         }
     }
     Ok(())
 }
 
+fn is_plain_http(request: &HttpRequest) -> bool {
+    request
+        .uri()
+        .get(..7)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("http://"))
+}
+
+/// Wraps the body of `request` so that its checksum is sent as a trailer.
+///
+/// When `chunk_signer` is set, every chunk of the body, and the trailer, is signed with the signer
+/// that the SigV4 signer will set on it.
 fn wrap_streaming_request_body_in_checksum_calculating_body(
     request: &mut HttpRequest,
     checksum_algorithm: ChecksumAlgorithm,
+    chunk_signer: Option<DeferredChunkSigner>,
 ) -> Result<(), BuildError> {
     let original_body_size = request
         .body()
@@ -218,8 +242,12 @@ fn wrap_streaming_request_body_in_checksum_calculating_body(
             let checksum = checksum_algorithm.into_impl();
             let trailer_len = HttpChecksum::size(checksum.as_ref());
             let body = calculate::ChecksumBody::new(body, checksum);
-            let aws_chunked_body_options =
+            let mut aws_chunked_body_options =
                 AwsChunkedBodyOptions::new(original_body_size, vec![trailer_len]);
+            if let Some(chunk_signer) = &chunk_signer {
+                aws_chunked_body_options =
+                    aws_chunked_body_options.with_signer(chunk_signer.clone());
+            }
 
             let body = AwsChunkedBody::new(body, aws_chunked_body_options);
 
@@ -261,12 +289,18 @@ fn wrap_streaming_request_body_in_checksum_calculating_body(
 
 #[cfg(test)]
 mod tests {
-    use crate::http_request_checksum::wrap_streaming_request_body_in_checksum_calculating_body;
+    use crate::http_request_checksum::{
+        add_checksum_for_request_body, wrap_streaming_request_body_in_checksum_calculating_body,
+    };
+    use aws_runtime::auth::SigV4OperationSigningConfig;
+    use aws_runtime::content_encoding::DeferredChunkSigner;
+    use aws_sigv4::http_request::SignableBody;
     use aws_smithy_checksums::ChecksumAlgorithm;
     use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
     use aws_smithy_types::base64;
     use aws_smithy_types::body::SdkBody;
     use aws_smithy_types::byte_stream::ByteStream;
+    use aws_smithy_types::config_bag::{ConfigBag, Layer};
     use bytes::BytesMut;
     use http_body::Body;
     use tempfile::NamedTempFile;
@@ -285,8 +319,12 @@ mod tests {
         assert!(request.body().try_clone().is_some());
 
         let checksum_algorithm: ChecksumAlgorithm = "crc32".parse().unwrap();
-        wrap_streaming_request_body_in_checksum_calculating_body(&mut request, checksum_algorithm)
-            .unwrap();
+        wrap_streaming_request_body_in_checksum_calculating_body(
+            &mut request,
+            checksum_algorithm,
+            None,
+        )
+        .unwrap();
 
         // ensure wrapped SdkBody is retryable
         let mut body = request.body().try_clone().expect("body is retryable");
@@ -331,8 +369,12 @@ mod tests {
         // ensure original SdkBody is retryable
         assert!(request.body().try_clone().is_some());
 
-        wrap_streaming_request_body_in_checksum_calculating_body(&mut request, checksum_algorithm)
-            .unwrap();
+        wrap_streaming_request_body_in_checksum_calculating_body(
+            &mut request,
+            checksum_algorithm,
+            None,
+        )
+        .unwrap();
 
         // ensure wrapped SdkBody is retryable
         let mut body = request.body().try_clone().expect("body is retryable");
@@ -349,4 +391,54 @@ mod tests {
             "expected {body} to end with '{expected}'"
         );
     }
+
+    async fn streaming_request(uri: &str, file: &NamedTempFile) -> HttpRequest {
+        let mut request = HttpRequest::new(
+            ByteStream::read_from()
+                .path(file)
+                .build()
+                .await
+                .unwrap()
+                .into_inner(),
+        );
+        request.set_uri(uri).unwrap();
+        request
+    }
+
+    fn signing_config() -> ConfigBag {
+        let mut layer = Layer::new("test");
+        layer.store_put(SigV4OperationSigningConfig::default());
+        ConfigBag::of_layers(vec![layer])
+    }
+
+    #[tokio::test]
+    async fn test_chunks_are_signed_over_plain_http() {
+        let file = NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "Hello world").unwrap();
+        let checksum_algorithm: ChecksumAlgorithm = "crc32".parse().unwrap();
+
+        let mut cfg = signing_config();
+        let mut request = streaming_request("http://example.com", &file).await;
+        add_checksum_for_request_body(&mut request, checksum_algorithm, &mut cfg).unwrap();
+        assert_eq!(
+            Some(SignableBody::StreamingSignedPayloadTrailer),
+            cfg.load::<SigV4OperationSigningConfig>()
+                .unwrap()
+                .signing_options
+                .payload_override
+        );
+        assert!(cfg.load::<DeferredChunkSigner>().is_some());
+
+        let mut cfg = signing_config();
+        let mut request = streaming_request("https://example.com", &file).await;
+        add_checksum_for_request_body(&mut request, checksum_algorithm, &mut cfg).unwrap();
+        assert_eq!(
+            Some(SignableBody::StreamingUnsignedPayloadTrailer),
+            cfg.load::<SigV4OperationSigningConfig>()
+                .unwrap()
+                .signing_options
+                .payload_override
+        );
+        assert!(cfg.load::<DeferredChunkSigner>().is_none());
+    }
 }
@@ -7,6 +7,9 @@
 
 //! Interceptor for handling Smithy `@httpChecksum` response checksumming
 
+use aws_smithy_checksums::composite::{
+    CompositeChecksum, CompositeChecksumPartSize, CompositeChecksumValue,
+};
 use aws_smithy_checksums::ChecksumAlgorithm;
 use aws_smithy_runtime_api::box_error::BoxError;
 use aws_smithy_runtime_api::client::interceptors::context::{
@@ -90,16 +93,13 @@ where
             let maybe_checksum_headers = check_headers_for_precalculated_checksum(
                 response.headers(),
                 self.response_algorithms,
+                cfg.load::<CompositeChecksumPartSize>().cloned(),
             );
-            if let Some((checksum_algorithm, precalculated_checksum)) = maybe_checksum_headers {
+            if let Some(precalculated_checksum) = maybe_checksum_headers {
                 let mut body = SdkBody::taken();
                 mem::swap(&mut body, response.body_mut());
 
-                let mut body = wrap_body_with_checksum_validator(
-                    body,
-                    checksum_algorithm,
-                    precalculated_checksum,
-                );
+                let mut body = wrap_body_with_checksum_validator(body, precalculated_checksum);
                 mem::swap(&mut body, response.body_mut());
             }
         }
@@ -108,32 +108,84 @@ where
     }
 }
 
-/// Given an `SdkBody`, a `aws_smithy_checksums::ChecksumAlgorithm`, and a pre-calculated checksum,
-/// return an `SdkBody` where the body will processed with the checksum algorithm and checked
-/// against the pre-calculated checksum.
+/// A checksum sent by the server, along with what's needed to validate it.
+#[derive(Debug, Clone)]
+pub(crate) struct PrecalculatedChecksum {
+    algorithm: ChecksumAlgorithm,
+    /// Set if this is a composite checksum of an object that was uploaded in multiple parts.
+    composite: Option<CompositeParts>,
+    checksum: bytes::Bytes,
+}
+
+#[derive(Debug, Clone)]
+struct CompositeParts {
+    part_sizes: CompositeChecksumPartSize,
+    part_count: u32,
+}
+
+impl PrecalculatedChecksum {
+    pub(crate) fn new(algorithm: ChecksumAlgorithm, checksum: bytes::Bytes) -> Self {
+        Self {
+            algorithm,
+            composite: None,
+            checksum,
+        }
+    }
+
+    pub(crate) fn composite(
+        algorithm: ChecksumAlgorithm,
+        part_sizes: CompositeChecksumPartSize,
+        value: CompositeChecksumValue,
+    ) -> Self {
+        Self {
+            algorithm,
+            composite: Some(CompositeParts {
+                part_sizes,
+                part_count: value.part_count(),
+            }),
+            checksum: value.checksum().clone(),
+        }
+    }
+
+    fn checksum_impl(&self) -> Box<dyn aws_smithy_checksums::http::HttpChecksum> {
+        match &self.composite {
+            Some(parts) => Box::new(
+                CompositeChecksum::new(self.algorithm, parts.part_sizes.clone())
+                    .with_expected_part_count(parts.part_count),
+            ),
+            None => self.algorithm.into_impl(),
+        }
+    }
+}
+
+/// Given an `SdkBody` and a pre-calculated checksum, return an `SdkBody` where the body will
+/// processed with the checksum algorithm and checked against the pre-calculated checksum.
 pub(crate) fn wrap_body_with_checksum_validator(
     body: SdkBody,
-    checksum_algorithm: ChecksumAlgorithm,
-    precalculated_checksum: bytes::Bytes,
+    precalculated_checksum: PrecalculatedChecksum,
 ) -> SdkBody {
     use aws_smithy_checksums::body::validate;
 
     body.map(move |body| {
         SdkBody::from_body_0_4(validate::ChecksumBody::new(
             body,
-            checksum_algorithm.into_impl(),
-            precalculated_checksum.clone(),
+            precalculated_checksum.checksum_impl(),
+            precalculated_checksum.checksum.clone(),
         ))
     })
 }
 
-/// Given a `HeaderMap`, extract any checksum included in the headers as `Some(Bytes)`.
+/// Given a `HeaderMap`, extract any checksum included in the headers.
 /// If no checksum header is set, return `None`. If multiple checksum headers are set, the one that
 /// is fastest to compute will be chosen.
+///
+/// Composite checksums (checksums of objects uploaded with `MultiPartUpload`) can only be validated
+/// when the part size of the upload is known. If it isn't, `None` is returned for them.
 pub(crate) fn check_headers_for_precalculated_checksum(
     headers: &Headers,
     response_algorithms: &[&str],
-) -> Option<(ChecksumAlgorithm, bytes::Bytes)> {
+    composite_part_size: Option<CompositeChecksumPartSize>,
+) -> Option<PrecalculatedChecksum> {
     let checksum_algorithms_to_check =
         aws_smithy_checksums::http::CHECKSUM_ALGORITHMS_IN_PRIORITY_ORDER
             .into_iter()
@@ -159,12 +211,34 @@ pub(crate) fn check_headers_for_precalculated_checksum(
         {
             // S3 needs special handling for checksums of objects uploaded with `MultiPartUpload`.
             if is_part_level_checksum(base64_encoded_precalculated_checksum) {
-                tracing::warn!(
-                      more_info = "See https://docs.aws.amazon.com/AmazonS3/latest/userguide/checking-object-integrity.html#large-object-checksums for more information.",
-                      "This checksum is a part-level checksum which can't be validated by the Rust SDK. Disable checksum validation for this request to fix this warning.",
-                  );
+                let composite = match base64_encoded_precalculated_checksum
+                    .parse::<CompositeChecksumValue>()
+                {
+                    Ok(composite) => composite,
+                    Err(_) => {
+                        tracing::error!("Checksum received from server could not be base64 decoded. No checksum validation will be performed.");
+                        return None;
+                    }
+                };
+                let part_sizes = match composite_part_size {
+                    Some(part_sizes) => part_sizes,
+                    // The only part of a single-part upload is the whole body
+                    None if composite.part_count() == 1 => CompositeChecksumPartSize::new(u64::MAX)
+                        .expect("u64::MAX is a valid part size"),
+                    None => {
+                        tracing::warn!(
+                              more_info = "See https://docs.aws.amazon.com/AmazonS3/latest/userguide/checking-object-integrity.html#large-object-checksums for more information.",
+                              "This checksum is a part-level checksum which can't be validated without knowing the part sizes of the upload. Set a `CompositeChecksumPartSize` in the config bag or disable checksum validation for this request to fix this warning.",
+                          );
+                        return None;
+                    }
+                };
 
-                return None;
+                return Some(PrecalculatedChecksum::composite(
+                    checksum_algorithm,
+                    part_sizes,
+                    composite,
+                ));
             }
 
             let precalculated_checksum = match aws_smithy_types::base64::decode(
@@ -177,7 +251,10 @@ pub(crate) fn check_headers_for_precalculated_checksum(
                 }
             };
 
-            return Some((checksum_algorithm, precalculated_checksum));
+            return Some(PrecalculatedChecksum::new(
+                checksum_algorithm,
+                precalculated_checksum,
+            ));
         }
     }
 
@@ -185,36 +262,18 @@ pub(crate) fn check_headers_for_precalculated_checksum(
 }
 
 fn is_part_level_checksum(checksum: &str) -> bool {
-    let mut found_number = false;
-    let mut found_dash = false;
-
-    for ch in checksum.chars().rev() {
-        // this could be bad
-        if ch.is_ascii_digit() {
-            found_number = true;
-            continue;
-        }
-
-        // Yup, it's a part-level checksum
-        if ch == '-' {
-            if found_dash {
-                // Found a second dash?? This isn't a part-level checksum.
-                return false;
-            }
-
-            found_dash = true;
-            continue;
-        }
-
-        break;
-    }
-
-    found_number && found_dash
+    CompositeChecksumValue::is_composite(checksum)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{is_part_level_checksum, wrap_body_with_checksum_validator};
+    use super::{
+        check_headers_for_precalculated_checksum, is_part_level_checksum,
+        wrap_body_with_checksum_validator, PrecalculatedChecksum,
+    };
+    use aws_smithy_checksums::composite::CompositeChecksumPartSize;
+    use aws_smithy_checksums::ChecksumAlgorithm;
+    use aws_smithy_runtime_api::http::Headers;
     use aws_smithy_types::body::SdkBody;
     use aws_smithy_types::byte_stream::ByteStream;
     use aws_smithy_types::error::display::DisplayErrorContext;
@@ -227,12 +286,11 @@ mod tests {
         let precalculated_checksum = Bytes::from_static(&[0x8b, 0xd6, 0x9e, 0x52]);
         let body = ByteStream::new(SdkBody::from(input_text));
 
+        let precalculated_checksum =
+            PrecalculatedChecksum::new(checksum_algorithm, precalculated_checksum);
+
         let body = body.map(move |sdk_body| {
-            wrap_body_with_checksum_validator(
-                sdk_body,
-                checksum_algorithm,
-                precalculated_checksum.clone(),
-            )
+            wrap_body_with_checksum_validator(sdk_body, precalculated_checksum.clone())
         });
 
         let mut validated_body = Vec::new();
@@ -267,4 +325,94 @@ mod tests {
         assert!(!is_part_level_checksum("abcd==--11"));
         assert!(!is_part_level_checksum("abcd==-AA"));
     }
+
+    #[tokio::test]
+    async fn test_build_composite_checksum_validated_body_works() {
+        let input_text = "Hello world";
+        // "Hello world" split into parts of 6 bytes: the CRC32s of "Hello " and "world" are
+        // 0xEA2DFCC0 and 0x3A771143, and the CRC32 of those concatenated checksums is 0x2039DA0F
+        let mut headers = Headers::new();
+        headers.insert("x-amz-checksum-crc32", "IDnaDw==-2");
+        let precalculated_checksum = check_headers_for_precalculated_checksum(
+            &headers,
+            &["crc32"],
+            Some(CompositeChecksumPartSize::new(6).unwrap()),
+        )
+        .expect("composite checksum can be validated when the part size is known");
+        let body = ByteStream::new(SdkBody::from(input_text));
+
+        let body = body.map(move |sdk_body| {
+            wrap_body_with_checksum_validator(sdk_body, precalculated_checksum.clone())
+        });
+
+        let mut validated_body = Vec::new();
+        if let Err(e) = tokio::io::copy(&mut body.into_async_read(), &mut validated_body).await {
+            tracing::error!("{}", DisplayErrorContext(&e));
+            panic!("checksum validation has failed");
+        };
+        let body = std::str::from_utf8(&validated_body).unwrap();
+
+        assert_eq!(input_text, body);
+    }
+
+    #[test]
+    fn test_composite_checksum_is_skipped_without_part_size() {
+        let mut headers = Headers::new();
+        headers.insert("x-amz-checksum-crc32", "IDnaDw==-2");
+        assert!(check_headers_for_precalculated_checksum(&headers, &["crc32"], None).is_none());
+
+        let mut headers = Headers::new();
+        headers.insert("x-amz-checksum-crc32", "i9aeUg==");
+        let precalculated_checksum =
+            check_headers_for_precalculated_checksum(&headers, &["crc32"], None).unwrap();
+        assert_eq!(ChecksumAlgorithm::Crc32, precalculated_checksum.algorithm);
+        assert!(precalculated_checksum.composite.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_single_part_composite_checksum_is_validated_without_part_size() {
+        let input_text = "Hello world";
+        // The CRC32 of "Hello world" is 0x8BD69E52, and the CRC32 of that checksum is 0xF8BF30E8
+        let mut headers = Headers::new();
+        headers.insert("x-amz-checksum-crc32", "+L8w6A==-1");
+        let precalculated_checksum =
+            check_headers_for_precalculated_checksum(&headers, &["crc32"], None)
+                .expect("single-part composite checksums don't need a part size");
+        let body = ByteStream::new(SdkBody::from(input_text));
+
+        let body = body.map(move |sdk_body| {
+            wrap_body_with_checksum_validator(sdk_body, precalculated_checksum.clone())
+        });
+
+        let mut validated_body = Vec::new();
+        if let Err(e) = tokio::io::copy(&mut body.into_async_read(), &mut validated_body).await {
+            tracing::error!("{}", DisplayErrorContext(&e));
+            panic!("checksum validation has failed");
+        };
+        assert_eq!(input_text, std::str::from_utf8(&validated_body).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_composite_checksum_with_wrong_part_count_fails_validation() {
+        // Same checksum as `test_build_composite_checksum_validated_body_works`, but with a part
+        // count that doesn't match the number of parts the body splits into
+        let mut headers = Headers::new();
+        headers.insert("x-amz-checksum-crc32", "IDnaDw==-3");
+        let precalculated_checksum = check_headers_for_precalculated_checksum(
+            &headers,
+            &["crc32"],
+            Some(CompositeChecksumPartSize::new(6).unwrap()),
+        )
+        .unwrap();
+        let body = ByteStream::new(SdkBody::from("Hello world"));
+
+        let body = body.map(move |sdk_body| {
+            wrap_body_with_checksum_validator(sdk_body, precalculated_checksum.clone())
+        });
+
+        let mut validated_body = Vec::new();
+        tokio::io::copy(&mut body.into_async_read(), &mut validated_body)
+            .await
+            .expect_err("part count mismatch");
+    }
 }
@@ -173,6 +173,12 @@ impl Sign for SigV4Signer {
 
         let signing_params =
             Self::signing_params(settings, identity, &operation_config, request_time)?;
+        let signs_chunks = matches!(
+            operation_config.signing_options.payload_override,
+            Some(
+                SignableBody::StreamingSignedPayload | SignableBody::StreamingSignedPayloadTrailer
+            )
+        );
 
         let (signing_instructions, _signature) = {
             // A body that is already in memory can be signed directly. A body that is not in memory
@@ -202,6 +208,25 @@ impl Sign for SigV4Signer {
         }
         .into_parts();
 
+        // If the body is sent with signed chunks, set up the chunk signer
+        #[cfg(feature = "http-02x")]
+        if signs_chunks {
+            use crate::content_encoding::DeferredChunkSigner;
+            use chunked::SigV4ChunkSigner;
+
+            if let Some(deferred_signer) = config_bag.load::<DeferredChunkSigner>() {
+                deferred_signer.set(Box::new(SigV4ChunkSigner::new(
+                    _signature.clone(),
+                    identity.clone(),
+                    operation_config.region.clone().unwrap(),
+                    operation_config.name.clone().unwrap(),
+                    request_time,
+                )));
+            }
+        }
+        #[cfg(not(feature = "http-02x"))]
+        let _ = signs_chunks;
+
         // If this is an event stream operation, set up the event stream signer
         #[cfg(feature = "event-stream")]
         {
@@ -228,6 +253,82 @@ impl Sign for SigV4Signer {
     }
 }
 
+#[cfg(feature = "http-02x")]
+mod chunked {
+    use crate::content_encoding::SignChunk;
+    use aws_sigv4::chunked::{sign_chunk, sign_trailer};
+    use aws_sigv4::sign::v4;
+    use aws_smithy_runtime_api::box_error::BoxError;
+    use aws_smithy_runtime_api::client::identity::Identity;
+    use aws_types::region::SigningRegion;
+    use aws_types::SigningName;
+    use http::HeaderMap;
+    use std::time::SystemTime;
+
+    /// `aws-chunked` SigV4 chunk signing implementation.
+    #[derive(Debug)]
+    pub(super) struct SigV4ChunkSigner {
+        last_signature: String,
+        identity: Identity,
+        signing_region: SigningRegion,
+        signing_name: SigningName,
+        /// Chunks must be signed with the same time as the request.
+        time: SystemTime,
+    }
+
+    impl SigV4ChunkSigner {
+        pub(super) fn new(
+            seed_signature: String,
+            identity: Identity,
+            signing_region: SigningRegion,
+            signing_name: SigningName,
+            time: SystemTime,
+        ) -> Self {
+            Self {
+                last_signature: seed_signature,
+                identity,
+                signing_region,
+                signing_name,
+                time,
+            }
+        }
+
+        fn signing_params(&self) -> v4::SigningParams<'_, ()> {
+            let builder = v4::SigningParams::builder()
+                .identity(&self.identity)
+                .region(self.signing_region.as_ref())
+                .name(self.signing_name.as_ref())
+                .time(self.time)
+                .settings(());
+            builder.build().unwrap()
+        }
+    }
+
+    impl SignChunk for SigV4ChunkSigner {
+        fn sign_chunk(&mut self, chunk: &[u8]) -> Result<String, BoxError> {
+            let (_, signature) = {
+                let params = self.signing_params();
+                sign_chunk(chunk, &self.last_signature, &params)?.into_parts()
+            };
+            self.last_signature = signature.clone();
+            Ok(signature)
+        }
+
+        fn sign_trailers(&mut self, trailers: &HeaderMap) -> Result<String, BoxError> {
+            let trailers = trailers
+                .iter()
+                .map(|(name, value)| Ok((name.as_str(), value.to_str()?)))
+                .collect::<Result<Vec<_>, http::header::ToStrError>>()?;
+            let (_, signature) = {
+                let params = self.signing_params();
+                sign_trailer(trailers, &self.last_signature, &params)?.into_parts()
+            };
+            self.last_signature = signature.clone();
+            Ok(signature)
+        }
+    }
+}
+
 #[cfg(feature = "event-stream")]
 mod event_stream {
     use aws_sigv4::event_stream::{sign_empty_message, sign_message};
@@ -440,6 +541,91 @@ mod tests {
         assert!(matches!(result, Cow::Owned(_)));
     }
 
+    #[cfg(feature = "http-02x")]
+    #[tokio::test]
+    async fn signed_chunks_start_from_the_request_signature() {
+        use crate::content_encoding::{AwsChunkedBody, AwsChunkedBodyOptions, DeferredChunkSigner};
+        use aws_smithy_async::time::StaticTimeSource;
+        use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+        use aws_smithy_types::body::SdkBody;
+
+        let deferred_signer = DeferredChunkSigner::new();
+        let mut layer = Layer::new("test");
+        layer.store_put(SigV4OperationSigningConfig {
+            region: Some(SigningRegion::from_static("us-east-1")),
+            name: Some(SigningName::from_static("s3")),
+            signing_options: SigningOptions {
+                content_sha256_header: true,
+                payload_override: Some(SignableBody::StreamingSignedPayload),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        layer.store_put(deferred_signer.clone());
+        let config_bag = ConfigBag::of_layers(vec![layer]);
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_369_353_600);
+        let runtime_components = RuntimeComponentsBuilder::for_tests()
+            .with_time_source(Some(StaticTimeSource::new(now)))
+            .build()
+            .unwrap();
+        let identity = Credentials::for_tests().into();
+
+        let options = AwsChunkedBodyOptions::new(5, Vec::new()).with_signer(deferred_signer);
+        let body = SdkBody::from_body_0_4(AwsChunkedBody::new(SdkBody::from("hello"), options));
+        let mut request: HttpRequest = http::Request::builder()
+            .method("PUT")
+            .uri("https://examplebucket.s3.amazonaws.com/chunkObject.txt")
+            .body(body)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        SigV4Signer::new()
+            .sign_http_request(
+                &mut request,
+                &identity,
+                AuthSchemeEndpointConfig::empty(),
+                &runtime_components,
+                &config_bag,
+            )
+            .unwrap();
+        assert_eq!(
+            "STREAMING-AWS4-HMAC-SHA256-PAYLOAD",
+            request.headers().get("x-amz-content-sha256").unwrap()
+        );
+        let seed_signature = request
+            .headers()
+            .get("authorization")
+            .unwrap()
+            .rsplit_once("Signature=")
+            .unwrap()
+            .1
+            .to_string();
+
+        let params = v4::SigningParams::builder()
+            .identity(&identity)
+            .region("us-east-1")
+            .name("s3")
+            .time(now)
+            .settings(())
+            .build()
+            .unwrap();
+        let first = aws_sigv4::chunked::sign_chunk(b"hello", &seed_signature, &params).unwrap();
+        let last = aws_sigv4::chunked::sign_chunk(b"", first.signature(), &params).unwrap();
+        let expected_body = format!(
+            "5;chunk-signature={}\r\nhello\r\n0;chunk-signature={}\r\n\r\n",
+            first.signature(),
+            last.signature()
+        );
+
+        let body = request.take_body();
+        let body = aws_smithy_types::byte_stream::ByteStream::new(body)
+            .collect()
+            .await
+            .unwrap()
+            .into_bytes();
+        assert_eq!(expected_body, std::str::from_utf8(&body).unwrap());
+    }
+
     #[test]
     fn endpoint_config_supports_fallback_when_region_or_service_are_unset() {
         let mut layer = Layer::new("test");
@@ -3,17 +3,25 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_types::config_bag::{Storable, StoreReplace};
 use bytes::{Bytes, BytesMut};
 use http::{HeaderMap, HeaderValue};
 use http_body::{Body, SizeHint};
 use pin_project_lite::pin_project;
 
+use std::fmt;
 use std::pin::Pin;
+use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
 
 const CRLF: &str = "\r\n";
 const CHUNK_TERMINATOR: &str = "0\r\n";
 const TRAILER_SEPARATOR: &[u8] = b":";
+const CHUNK_SIGNATURE_EXTENSION: &str = ";chunk-signature=";
+const TRAILER_SIGNATURE: &str = "x-amz-trailer-signature";
+const SIGNATURE_LENGTH: u64 = 64;
+const DEFAULT_SIGNED_CHUNK_SIZE: usize = 64 * 1024;
 
 /// Content encoding header value constants
 pub mod header_value {
@@ -21,17 +29,73 @@ pub mod header_value {
     pub const AWS_CHUNKED: &str = "aws-chunked";
 }
 
+/// Signs the chunks and trailers of an [`AwsChunkedBody`].
+///
+/// Each signature incorporates the signature that came before it, so a `SignChunk` is stateful
+/// and can only be used for a single body.
+pub trait SignChunk: fmt::Debug + Send + Sync {
+    /// Signs the next chunk, returning its signature.
+    ///
+    /// The payload is terminated by signing an empty chunk.
+    fn sign_chunk(&mut self, chunk: &[u8]) -> Result<String, BoxError>;
+
+    /// Signs the trailers that follow the final chunk, returning their signature.
+    fn sign_trailers(&mut self, trailers: &HeaderMap) -> Result<String, BoxError>;
+}
+
+/// A slot for the [`SignChunk`] of an [`AwsChunkedBody`] with signed chunks.
+///
+/// Chunk signatures start from the signature of the request, but the body of a request is
+/// established before the request is signed. The body and the request signer share a
+/// `DeferredChunkSigner`, which is placed in the config bag so that the signer can provide a
+/// [`SignChunk`] once it has signed the request. The body takes the signer when it is first
+/// polled.
+///
+/// Generated clients do this for streaming request bodies that send their checksum as a trailer
+/// over plain HTTP, where an unsigned payload wouldn't be protected by TLS.
+#[derive(Clone, Debug, Default)]
+pub struct DeferredChunkSigner(Arc<Mutex<Option<Box<dyn SignChunk>>>>);
+
+impl DeferredChunkSigner {
+    /// Creates a new, empty `DeferredChunkSigner`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the signer, replacing one that hasn't been taken yet.
+    pub fn set(&self, signer: Box<dyn SignChunk>) {
+        *self.0.lock().unwrap() = Some(signer);
+    }
+
+    fn take(&self) -> Option<Box<dyn SignChunk>> {
+        self.0.lock().unwrap().take()
+    }
+}
+
+impl Storable for DeferredChunkSigner {
+    type Storer = StoreReplace<Self>;
+}
+
 /// Options used when constructing an [`AwsChunkedBody`].
-#[derive(Debug, Default)]
+#[derive(Debug)]
 #[non_exhaustive]
 pub struct AwsChunkedBodyOptions {
-    /// The total size of the stream. Because we only support unsigned encoding
-    /// this implies that there will only be a single chunk containing the
-    /// underlying payload.
+    /// The total size of the stream. For unsigned encoding, there will only be a single chunk
+    /// containing the underlying payload.
     stream_length: u64,
     /// The length of each trailer sent within an `AwsChunkedBody`. Necessary in
     /// order to correctly calculate the total size of the body accurately.
     trailer_lengths: Vec<u64>,
+    /// When set, the payload is split into chunks of `chunk_size` bytes, each of which is signed.
+    signer: Option<DeferredChunkSigner>,
+    /// The size of each signed chunk, except for the last one.
+    chunk_size: usize,
+}
+
+impl Default for AwsChunkedBodyOptions {
+    fn default() -> Self {
+        Self::new(0, Vec::new())
+    }
 }
 
 impl AwsChunkedBodyOptions {
@@ -40,7 +104,30 @@ impl AwsChunkedBodyOptions {
         Self {
             stream_length,
             trailer_lengths,
+            signer: None,
+            chunk_size: DEFAULT_SIGNED_CHUNK_SIZE,
+        }
+    }
+
+    /// Sign every chunk, and the trailers if there are any, with the signer that will be set on
+    /// `signer`.
+    ///
+    /// The request must be signed with `STREAMING-AWS4-HMAC-SHA256-PAYLOAD`, or with
+    /// `STREAMING-AWS4-HMAC-SHA256-PAYLOAD-TRAILER` if there are trailers.
+    pub fn with_signer(mut self, signer: DeferredChunkSigner) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Set the size of signed chunks. Defaults to 64 KiB.
+    ///
+    /// Returns an error if `chunk_size` is zero.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Result<Self, BoxError> {
+        if chunk_size == 0 {
+            return Err(AwsChunkedBodyError::InvalidChunkSize.into());
         }
+        self.chunk_size = chunk_size;
+        Ok(self)
     }
 
     fn total_trailer_length(&self) -> u64 {
@@ -73,8 +160,10 @@ enum AwsChunkedBodyState {
 }
 
 pin_project! {
-    /// A request body compatible with `Content-Encoding: aws-chunked`. This implementation is only
-    /// capable of writing a single chunk and does not support signed chunks.
+    /// A request body compatible with `Content-Encoding: aws-chunked`. Unsigned bodies are written
+    /// as a single chunk. Signed bodies are split into chunks of a fixed size, each of which carries
+    /// a `chunk-signature` extension, and their trailers are followed by an
+    /// `x-amz-trailer-signature` trailer.
     ///
     /// Chunked-Body grammar is defined in [ABNF] as:
     ///
@@ -101,6 +190,8 @@ pin_project! {
         state: AwsChunkedBodyState,
         options: AwsChunkedBodyOptions,
         inner_body_bytes_read_so_far: usize,
+        signer: Option<Box<dyn SignChunk>>,
+        buffer: BytesMut,
     }
 }
 
@@ -112,17 +203,37 @@ impl<Inner> AwsChunkedBody<Inner> {
             state: AwsChunkedBodyState::WritingChunkSize,
             options,
             inner_body_bytes_read_so_far: 0,
+            signer: None,
+            buffer: BytesMut::new(),
         }
     }
 
     fn encoded_length(&self) -> u64 {
         let mut length = 0;
-        if self.options.stream_length != 0 {
-            length += get_unsigned_chunk_bytes_length(self.options.stream_length);
-        }
+        if self.options.signer.is_some() {
+            let chunk_size = self.options.chunk_size as u64;
+            let full_chunks = self.options.stream_length / chunk_size;
+            length += full_chunks * get_signed_chunk_bytes_length(chunk_size);
+            let remainder = self.options.stream_length % chunk_size;
+            if remainder != 0 {
+                length += get_signed_chunk_bytes_length(remainder);
+            }
+            // End chunk
+            length += (CHUNK_TERMINATOR.len() + CHUNK_SIGNATURE_EXTENSION.len()) as u64
+                + SIGNATURE_LENGTH;
+            // Trailer signature
+            if !self.options.trailer_lengths.is_empty() {
+                length += (TRAILER_SIGNATURE.len() + TRAILER_SEPARATOR.len() + CRLF.len()) as u64
+                    + SIGNATURE_LENGTH;
+            }
+        } else {
+            if self.options.stream_length != 0 {
+                length += get_unsigned_chunk_bytes_length(self.options.stream_length);
+            }
 
-        // End chunk
-        length += CHUNK_TERMINATOR.len() as u64;
+            // End chunk
+            length += CHUNK_TERMINATOR.len() as u64;
+        }
 
         // Trailers
         for len in self.options.trailer_lengths.iter() {
@@ -141,6 +252,34 @@ fn get_unsigned_chunk_bytes_length(payload_length: u64) -> u64 {
     hex_repr_len + CRLF.len() as u64 + payload_length + CRLF.len() as u64
 }
 
+fn get_signed_chunk_bytes_length(payload_length: u64) -> u64 {
+    get_unsigned_chunk_bytes_length(payload_length)
+        + CHUNK_SIGNATURE_EXTENSION.len() as u64
+        + SIGNATURE_LENGTH
+}
+
+/// Signs `chunk` and writes it out, along with its size and signature. An empty chunk is written
+/// out as the final chunk, without the CRLF that would follow its data.
+fn write_signed_chunk(
+    signer: &mut dyn SignChunk,
+    chunk: &[u8],
+    out: &mut BytesMut,
+) -> Result<(), BoxError> {
+    let signature = signer.sign_chunk(chunk)?;
+    out.extend_from_slice(
+        format!(
+            "{:X}{CHUNK_SIGNATURE_EXTENSION}{signature}{CRLF}",
+            chunk.len()
+        )
+        .as_bytes(),
+    );
+    if !chunk.is_empty() {
+        out.extend_from_slice(chunk);
+        out.extend_from_slice(CRLF.as_bytes());
+    }
+    Ok(())
+}
+
 /// Writes trailers out into a `string` and then converts that `String` to a `Bytes` before
 /// returning.
 ///
@@ -209,6 +348,9 @@ where
         cx: &mut Context<'_>,
     ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
         tracing::trace!(state = ?self.state, "polling AwsChunkedBody");
+        if self.options.signer.is_some() {
+            return self.poll_signed_data(cx);
+        }
         let mut this = self.project();
 
         match *this.state {
@@ -257,24 +399,7 @@ where
                 return match this.inner.poll_trailers(cx) {
                     Poll::Ready(Ok(trailers)) => {
                         *this.state = AwsChunkedBodyState::Closed;
-                        let expected_length = total_rendered_length_of_trailers(trailers.as_ref());
-                        let actual_length = this.options.total_trailer_length();
-
-                        if expected_length != actual_length {
-                            let err =
-                                Box::new(AwsChunkedBodyError::ReportedTrailerLengthMismatch {
-                                    actual: actual_length,
-                                    expected: expected_length,
-                                });
-                            return Poll::Ready(Some(Err(err)));
-                        }
-
-                        let mut trailers =
-                            trailers_as_aws_chunked_bytes(trailers, actual_length + 1);
-                        // Insert the final CRLF to close the body
-                        trailers.extend_from_slice(CRLF.as_bytes());
-
-                        Poll::Ready(Some(Ok(trailers.into())))
+                        Poll::Ready(Some(write_trailers(trailers, this.options, None)))
                     }
                     Poll::Pending => Poll::Pending,
                     Poll::Ready(Err(e)) => Poll::Ready(Some(Err(e))),
@@ -301,6 +426,137 @@ where
     }
 }
 
+impl<Inner> AwsChunkedBody<Inner>
+where
+    Inner: Body<Data = Bytes, Error = aws_smithy_types::body::Error>,
+{
+    /// Polls a body whose chunks are signed. The inner body is buffered until a full chunk can be
+    /// signed, so the chunk sizes that `encoded_length` relies on don't depend on how the inner
+    /// body yields its data.
+    fn poll_signed_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, aws_smithy_types::body::Error>>> {
+        let mut this = self.project();
+        loop {
+            match *this.state {
+                AwsChunkedBodyState::WritingChunkSize => {
+                    let signer = this.options.signer.as_ref().and_then(|s| s.take());
+                    if signer.is_none() {
+                        return Poll::Ready(Some(Err(Box::new(
+                            AwsChunkedBodyError::MissingChunkSigner,
+                        ))));
+                    }
+                    *this.signer = signer;
+                    *this.state = AwsChunkedBodyState::WritingChunk;
+                }
+                AwsChunkedBodyState::WritingChunk => {
+                    let signer = this.signer.as_mut().expect("set before writing chunks");
+                    let chunk_size = this.options.chunk_size;
+                    if this.buffer.len() >= chunk_size {
+                        let chunk = this.buffer.split_to(chunk_size);
+                        tracing::trace!(len = chunk.len(), "writing signed chunk");
+                        let mut out = BytesMut::with_capacity(chunk.len() + 100);
+                        return Poll::Ready(Some(
+                            write_signed_chunk(signer.as_mut(), &chunk, &mut out)
+                                .map(|_| out.freeze()),
+                        ));
+                    }
+                    match this.inner.as_mut().poll_data(cx) {
+                        Poll::Ready(Some(Ok(data))) => {
+                            *this.inner_body_bytes_read_so_far += data.len();
+                            this.buffer.extend_from_slice(&data);
+                        }
+                        Poll::Ready(None) => {
+                            let actual_stream_length = *this.inner_body_bytes_read_so_far as u64;
+                            let expected_stream_length = this.options.stream_length;
+                            if actual_stream_length != expected_stream_length {
+                                let err = Box::new(AwsChunkedBodyError::StreamLengthMismatch {
+                                    actual: actual_stream_length,
+                                    expected: expected_stream_length,
+                                });
+                                return Poll::Ready(Some(Err(err)));
+                            };
+
+                            tracing::trace!(
+                                len = this.buffer.len(),
+                                "no more chunk data, writing last signed chunks"
+                            );
+                            *this.state = AwsChunkedBodyState::WritingTrailers;
+                            let chunk = this.buffer.split();
+                            let mut out = BytesMut::with_capacity(chunk.len() + 200);
+                            let result = if chunk.is_empty() {
+                                Ok(())
+                            } else {
+                                write_signed_chunk(signer.as_mut(), &chunk, &mut out)
+                            }
+                            .and_then(|_| write_signed_chunk(signer.as_mut(), &[], &mut out));
+                            return Poll::Ready(Some(result.map(|_| out.freeze())));
+                        }
+                        Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                        Poll::Pending => return Poll::Pending,
+                    }
+                }
+                AwsChunkedBodyState::WritingTrailers => {
+                    return match this.inner.poll_trailers(cx) {
+                        Poll::Ready(Ok(trailers)) => {
+                            *this.state = AwsChunkedBodyState::Closed;
+                            let signer = this.signer.as_mut().expect("set before writing chunks");
+                            Poll::Ready(Some(write_trailers(
+                                trailers,
+                                this.options,
+                                Some(signer.as_mut()),
+                            )))
+                        }
+                        Poll::Pending => Poll::Pending,
+                        Poll::Ready(Err(e)) => Poll::Ready(Some(Err(e))),
+                    };
+                }
+                AwsChunkedBodyState::Closed => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+/// Writes out the trailers and the CRLF that closes the body, after checking that they're as long
+/// as `options` reported. When a `signer` is given, non-empty trailers are followed by their
+/// signature.
+fn write_trailers(
+    trailers: Option<HeaderMap>,
+    options: &AwsChunkedBodyOptions,
+    signer: Option<&mut dyn SignChunk>,
+) -> Result<Bytes, BoxError> {
+    let expected_length = total_rendered_length_of_trailers(trailers.as_ref());
+    let actual_length = options.total_trailer_length();
+
+    if expected_length != actual_length {
+        return Err(Box::new(
+            AwsChunkedBodyError::ReportedTrailerLengthMismatch {
+                actual: actual_length,
+                expected: expected_length,
+            },
+        ));
+    }
+
+    let trailer_signature = match (signer, &trailers) {
+        (Some(signer), Some(trailers)) if !trailers.is_empty() => {
+            Some(signer.sign_trailers(trailers)?)
+        }
+        _ => None,
+    };
+    let mut trailers = trailers_as_aws_chunked_bytes(trailers, actual_length + 1);
+    if let Some(signature) = trailer_signature {
+        trailers.extend_from_slice(TRAILER_SIGNATURE.as_bytes());
+        trailers.extend_from_slice(TRAILER_SEPARATOR);
+        trailers.extend_from_slice(signature.as_bytes());
+        trailers.extend_from_slice(CRLF.as_bytes());
+    }
+    // Insert the final CRLF to close the body
+    trailers.extend_from_slice(CRLF.as_bytes());
+
+    Ok(trailers.into())
+}
+
 /// Errors related to `AwsChunkedBody`
 #[derive(Debug)]
 enum AwsChunkedBodyError {
@@ -313,6 +569,11 @@ enum AwsChunkedBodyError {
     /// equal to the actual length of the body returned by the inner `http_body::Body` implementor.
     /// `stream_length` must be correct in order to set an accurate content length header.
     StreamLengthMismatch { actual: u64, expected: u64 },
+    /// Error that occurs when an `AwsChunkedBody` with signed chunks is polled before a signer was
+    /// set on its `DeferredChunkSigner`.
+    MissingChunkSigner,
+    /// Error that occurs when the size of signed chunks is set to zero.
+    InvalidChunkSize,
 }
 
 impl std::fmt::Display for AwsChunkedBodyError {
@@ -324,6 +585,12 @@ impl std::fmt::Display for AwsChunkedBodyError {
             Self::StreamLengthMismatch { actual, expected } => {
                 write!(f, "When creating this AwsChunkedBody, stream length was reported as {expected}. However, when double checking during body encoding, length was found to be {actual} instead.")
             }
+            Self::MissingChunkSigner => {
+                write!(f, "This AwsChunkedBody has signed chunks, but no chunk signer was provided. The request must be signed with SigV4 using a streaming signed payload.")
+            }
+            Self::InvalidChunkSize => {
+                write!(f, "The size of signed chunks must be greater than zero.")
+            }
         }
     }
 }
@@ -351,7 +618,7 @@ where
 mod tests {
     use super::{
         total_rendered_length_of_trailers, trailers_as_aws_chunked_bytes, AwsChunkedBody,
-        AwsChunkedBodyOptions, CHUNK_TERMINATOR, CRLF,
+        AwsChunkedBodyOptions, DeferredChunkSigner, SignChunk, CHUNK_TERMINATOR, CRLF,
     };
 
     use aws_smithy_types::body::SdkBody;
@@ -361,6 +628,7 @@ mod tests {
     use http_body::{Body, SizeHint};
     use pin_project_lite::pin_project;
 
+    use aws_smithy_runtime_api::box_error::BoxError;
     use std::io::Read;
     use std::pin::Pin;
     use std::task::{Context, Poll};
@@ -429,6 +697,165 @@ mod tests {
         }
     }
 
+    pin_project! {
+        struct TrailerBody {
+            #[pin]
+            inner: SdkBody,
+            trailers: Option<HeaderMap>,
+        }
+    }
+
+    impl Body for TrailerBody {
+        type Data = Bytes;
+        type Error = aws_smithy_types::body::Error;
+
+        fn poll_data(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+            self.project().inner.poll_data(cx)
+        }
+
+        fn poll_trailers(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<Option<HeaderMap<HeaderValue>>, Self::Error>> {
+            Poll::Ready(Ok(self.project().trailers.take()))
+        }
+    }
+
+    /// Signs each chunk with a 64 character signature derived from its length and a counter.
+    #[derive(Debug, Default)]
+    struct CountingSigner {
+        count: usize,
+    }
+
+    impl SignChunk for CountingSigner {
+        fn sign_chunk(&mut self, chunk: &[u8]) -> Result<String, BoxError> {
+            self.count += 1;
+            Ok(format!("{:0>32}{:0>32}", self.count, chunk.len()))
+        }
+
+        fn sign_trailers(&mut self, trailers: &HeaderMap) -> Result<String, BoxError> {
+            self.count += 1;
+            Ok(format!("{:0>32}{:0>32}", self.count, trailers.len()))
+        }
+    }
+
+    async fn collect_signed(
+        body: TrailerBody,
+        chunk_size: usize,
+        trailer_lengths: Vec<u64>,
+    ) -> String {
+        let signer = DeferredChunkSigner::new();
+        let len = body.inner.content_length().unwrap();
+        let opts = AwsChunkedBodyOptions::new(len, trailer_lengths)
+            .with_signer(signer.clone())
+            .with_chunk_size(chunk_size)
+            .unwrap();
+        let mut body = AwsChunkedBody::new(body, opts);
+        signer.set(Box::<CountingSigner>::default());
+
+        let expected_length = body.size_hint().exact().unwrap();
+        let mut output = SegmentedBuf::new();
+        while let Some(buf) = body.data().await {
+            output.push(buf.unwrap());
+        }
+        let mut actual_output = String::new();
+        output
+            .reader()
+            .read_to_string(&mut actual_output)
+            .expect("Doesn't cause IO errors");
+        assert_eq!(expected_length, actual_output.len() as u64);
+        actual_output
+    }
+
+    fn signature(count: usize, len: usize) -> String {
+        format!("{:0>32}{:0>32}", count, len)
+    }
+
+    #[tokio::test]
+    async fn test_aws_chunked_encoding_signed() {
+        let body = TrailerBody {
+            inner: SdkBody::from("Hello world"),
+            trailers: None,
+        };
+        let actual_output = collect_signed(body, 4, Vec::new()).await;
+
+        let expected_output = format!(
+            "4;chunk-signature={}\r\nHell\r\n\
+            4;chunk-signature={}\r\no wo\r\n\
+            3;chunk-signature={}\r\nrld\r\n\
+            0;chunk-signature={}\r\n\r\n",
+            signature(1, 4),
+            signature(2, 4),
+            signature(3, 3),
+            signature(4, 0),
+        );
+        assert_eq!(expected_output, actual_output);
+    }
+
+    #[tokio::test]
+    async fn test_aws_chunked_encoding_signed_with_trailers() {
+        let mut trailers = HeaderMap::new();
+        trailers.insert("x-amz-checksum-crc32", HeaderValue::from_static("i9aeUg=="));
+        let trailer_len = total_rendered_length_of_trailers(Some(&trailers)) - CRLF.len() as u64;
+        let body = TrailerBody {
+            inner: SdkBody::from("Hello"),
+            trailers: Some(trailers),
+        };
+        let actual_output = collect_signed(body, 5, vec![trailer_len]).await;
+
+        let expected_output = format!(
+            "5;chunk-signature={}\r\nHello\r\n\
+            0;chunk-signature={}\r\n\
+            x-amz-checksum-crc32:i9aeUg==\r\n\
+            x-amz-trailer-signature:{}\r\n\r\n",
+            signature(1, 5),
+            signature(2, 0),
+            signature(3, 1),
+        );
+        assert_eq!(expected_output, actual_output);
+    }
+
+    #[tokio::test]
+    async fn test_aws_chunked_encoding_signed_empty_body() {
+        let body = TrailerBody {
+            inner: SdkBody::empty(),
+            trailers: None,
+        };
+        let actual_output = collect_signed(body, 4, Vec::new()).await;
+
+        assert_eq!(
+            format!("0;chunk-signature={}\r\n\r\n", signature(1, 0)),
+            actual_output
+        );
+    }
+
+    #[test]
+    fn test_zero_chunk_size_is_rejected() {
+        let err = AwsChunkedBodyOptions::default()
+            .with_chunk_size(0)
+            .expect_err("chunks can't be empty");
+        assert_eq!(
+            "The size of signed chunks must be greater than zero.",
+            err.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_aws_chunked_encoding_signed_without_signer() {
+        let opts =
+            AwsChunkedBodyOptions::new(5, Vec::new()).with_signer(DeferredChunkSigner::new());
+        let mut body = AwsChunkedBody::new(SdkBody::from("Hello"), opts);
+
+        let err = body.data().await.unwrap().unwrap_err();
+        assert!(
+            err.to_string().contains("no chunk signer was provided"),
+            "{err}"
+        );
+    }
+
     #[tokio::test]
     async fn test_aws_chunked_encoding() {
         let test_fut = async {
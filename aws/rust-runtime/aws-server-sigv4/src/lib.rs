@@ -0,0 +1,897 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+/* Automatically managed default lints */
+#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+/* End of automatically managed default lints */
+#![warn(
+    missing_docs,
+    rustdoc::missing_crate_level_docs,
+    missing_debug_implementations,
+    rust_2018_idioms,
+    unreachable_pub
+)]
+
+//! A [`Plugin`](aws_smithy_http_server::plugin::Plugin) that authenticates requests signed with [SigV4] or SigV4a.
+//!
+//! [`SigV4AuthPlugin`] parses the signature of each request, looks up the credentials for the
+//! access key it was signed with using a [`CredentialsLookup`], and verifies the signature using
+//! [`aws_sigv4`]. Authenticated requests carry the [`CredentialsLookup::Principal`] returned by
+//! the lookup as a request extension, so handlers can access it with an
+//! [`Extension`](aws_smithy_http_server::Extension) extractor. Requests that fail authentication
+//! are rejected with a response appropriate to the service's protocol, without running the
+//! operation.
+//!
+//! The signature is parsed and its access key looked up before the body is read, so requests that
+//! aren't signed by a known access key are rejected without reading their body. If the request
+//! carries an `x-amz-content-sha256` header whose value is `UNSIGNED-PAYLOAD` or
+//! `STREAMING-UNSIGNED-PAYLOAD-TRAILER`, the body isn't part of the signature and is passed
+//! through as it is. Otherwise, the body is buffered, up to
+//! [`SigV4AuthPlugin::with_max_body_size`] bytes, so that its hash can be checked. Signed
+//! streaming payloads, such as `STREAMING-AWS4-HMAC-SHA256-PAYLOAD`, aren't supported: their
+//! chunk signatures can't be verified, so these requests are rejected.
+//!
+//! # Example
+//!
+//! ```
+//! # use aws_server_sigv4::*;
+//! # use aws_smithy_http_server::plugin::{HttpPlugins, Scoped};
+//! # use aws_smithy_http_server::scope;
+//! # use aws_smithy_http_server::shape_id::ShapeId;
+//! # use aws_credential_types::Credentials;
+//! # struct GetPokemonSpecies;
+//! # struct CheckHealth;
+//! #[derive(Clone, Debug)]
+//! struct User {
+//!     name: String,
+//! }
+//!
+//! struct Users;
+//!
+//! impl CredentialsLookup for Users {
+//!     type Principal = User;
+//!
+//!     fn lookup(&self, access_key_id: &str) -> LookupFuture<User> {
+//!         let account = (access_key_id == "AKIDEXAMPLE").then(|| {
+//!             let credentials = Credentials::new("AKIDEXAMPLE", "secret", None, None, "users");
+//!             Account::new(credentials, User { name: "example".into() })
+//!         });
+//!         Box::pin(async move { Ok(account) })
+//!     }
+//! }
+//!
+//! let mut settings = VerificationSettings::default();
+//! settings.name = Some("pokemon".into());
+//! let auth = SigV4AuthPlugin::new(Users).with_settings(settings);
+//!
+//! // Authenticate every operation except `CheckHealth`.
+//! scope! {
+//!     struct Authenticated {
+//!         includes: [GetPokemonSpecies],
+//!         excludes: [CheckHealth]
+//!     }
+//! }
+//! let http_plugins = HttpPlugins::new().push(Scoped::new::<Authenticated>(auth));
+//! ```
+//!
+//! [SigV4]: https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_aws-signing.html
+
+use std::{
+    error::Error as StdError,
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use aws_credential_types::Credentials;
+use aws_sigv4::http_request::{
+    parse_signature, verify_signature, SignableBody, SignableRequest, VerificationErrorKind,
+};
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use aws_smithy_json::serialize::JsonObjectWriter;
+use aws_smithy_xml::encode::XmlWriter;
+use bytes::{Buf, Bytes, BytesMut};
+use http::{header::CONTENT_LENGTH, request::Parts, StatusCode};
+use http_body::Body as HttpBody;
+use tower::{Service, ServiceExt};
+
+use aws_smithy_http_server::{
+    body::BoxBody,
+    extension::RuntimeErrorExtension,
+    plugin::{HttpMarker, Plugin},
+    protocol::{aws_json_10::AwsJson1_0, aws_json_11::AwsJson1_1, rest_json_1::RestJson1, rest_xml::RestXml},
+    rejection::PayloadTooLarge,
+    response::IntoResponse,
+    runtime_error::{InternalFailureException, INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE},
+    service::ServiceShape,
+};
+
+pub use aws_sigv4::http_request::VerificationSettings;
+
+const CONTENT_SHA_256: &str = "x-amz-content-sha256";
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+const STREAMING_UNSIGNED_PAYLOAD_TRAILER: &str = "STREAMING-UNSIGNED-PAYLOAD-TRAILER";
+const STREAMING_PREFIX: &str = "STREAMING-";
+
+/// The default for [`SigV4AuthPlugin::with_max_body_size`].
+const DEFAULT_MAX_BODY_SIZE: u64 = 10 * 1024 * 1024;
+
+/// The future returned by [`CredentialsLookup::lookup`].
+pub type LookupFuture<P> =
+    Pin<Box<dyn Future<Output = Result<Option<Account<P>>, Box<dyn StdError + Send + Sync>>> + Send>>;
+
+/// Looks up the credentials that requests signed with a given access key ID must be verified with.
+pub trait CredentialsLookup: Send + Sync + 'static {
+    /// The principal that an access key belongs to.
+    ///
+    /// This is inserted into the extensions of authenticated requests.
+    type Principal: Clone + Send + Sync + 'static;
+
+    /// Returns the account for `access_key_id`, or `None` if the access key isn't known.
+    ///
+    /// Errors are treated as internal failures.
+    fn lookup(&self, access_key_id: &str) -> LookupFuture<Self::Principal>;
+}
+
+/// The credentials and principal of an access key, as returned by a [`CredentialsLookup`].
+#[derive(Debug)]
+pub struct Account<P> {
+    credentials: Credentials,
+    principal: P,
+}
+
+impl<P> Account<P> {
+    /// Creates a new `Account`.
+    ///
+    /// The access key ID of `credentials` must be the one that was looked up, and the session
+    /// token, if any, must be the one that requests are expected to carry.
+    pub fn new(credentials: Credentials, principal: P) -> Self {
+        Self { credentials, principal }
+    }
+}
+
+/// A [`Plugin`] that authenticates SigV4 and SigV4a signed requests.
+///
+/// See the [crate](crate) documentation for more information.
+pub struct SigV4AuthPlugin<L> {
+    lookup: Arc<L>,
+    settings: Arc<VerificationSettings>,
+    max_body_size: u64,
+    time_source: SharedTimeSource,
+}
+
+impl<L> SigV4AuthPlugin<L> {
+    /// Creates a new `SigV4AuthPlugin` that verifies requests with the credentials from `lookup`.
+    ///
+    /// Requests signed for any region and service are accepted unless restricted with
+    /// [`SigV4AuthPlugin::with_settings`].
+    pub fn new(lookup: L) -> Self {
+        Self {
+            lookup: Arc::new(lookup),
+            settings: Arc::new(VerificationSettings::default()),
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            time_source: SharedTimeSource::default(),
+        }
+    }
+
+    /// Sets the settings used to verify signatures.
+    pub fn with_settings(mut self, settings: VerificationSettings) -> Self {
+        self.settings = Arc::new(settings);
+        self
+    }
+
+    /// Sets the largest body, in bytes, that is buffered to check its hash. Defaults to 10 MiB.
+    ///
+    /// Requests with larger signed bodies are rejected with a [`PayloadTooLarge`] response.
+    pub fn with_max_body_size(mut self, max_body_size: u64) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Sets the time source that the signing time of requests is checked against. Defaults to
+    /// the system clock.
+    pub fn with_time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.time_source = SharedTimeSource::new(time_source);
+        self
+    }
+}
+
+impl<L> Clone for SigV4AuthPlugin<L> {
+    fn clone(&self) -> Self {
+        Self {
+            lookup: self.lookup.clone(),
+            settings: self.settings.clone(),
+            max_body_size: self.max_body_size,
+            time_source: self.time_source.clone(),
+        }
+    }
+}
+
+impl<L> fmt::Debug for SigV4AuthPlugin<L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigV4AuthPlugin")
+            .field("settings", &self.settings)
+            .field("max_body_size", &self.max_body_size)
+            .field("time_source", &self.time_source)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Ser, Op, T, L> Plugin<Ser, Op, T> for SigV4AuthPlugin<L>
+where
+    Ser: ServiceShape,
+{
+    type Output = SigV4AuthService<Ser::Protocol, T, L>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        SigV4AuthService {
+            inner,
+            lookup: self.lookup.clone(),
+            settings: self.settings.clone(),
+            max_body_size: self.max_body_size,
+            time_source: self.time_source.clone(),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<L> HttpMarker for SigV4AuthPlugin<L> {}
+
+/// A [`Service`] that authenticates requests before passing them to the inner service.
+///
+/// See [`SigV4AuthPlugin`].
+pub struct SigV4AuthService<P, S, L> {
+    inner: S,
+    lookup: Arc<L>,
+    settings: Arc<VerificationSettings>,
+    max_body_size: u64,
+    time_source: SharedTimeSource,
+    _protocol: PhantomData<fn(P)>,
+}
+
+impl<P, S, L> Clone for SigV4AuthService<P, S, L>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            lookup: self.lookup.clone(),
+            settings: self.settings.clone(),
+            max_body_size: self.max_body_size,
+            time_source: self.time_source.clone(),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<P, S, L> fmt::Debug for SigV4AuthService<P, S, L>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigV4AuthService")
+            .field("inner", &self.inner)
+            .field("settings", &self.settings)
+            .field("max_body_size", &self.max_body_size)
+            .field("time_source", &self.time_source)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P, S, L, B> Service<http::Request<B>> for SigV4AuthService<P, S, L>
+where
+    P: 'static,
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send,
+    L: CredentialsLookup,
+    B: HttpBody<Data = Bytes> + From<Bytes> + Send + 'static,
+    B::Error: Into<Box<dyn StdError + Send + Sync>>,
+    SigV4Rejection: IntoResponse<P>,
+    PayloadTooLarge: IntoResponse<P>,
+    InternalFailureException: IntoResponse<P>,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // The check that the inner service is ready is done by `oneshot` below.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let clone = self.inner.clone();
+        let inner = std::mem::replace(&mut self.inner, clone);
+        let lookup = self.lookup.clone();
+        let settings = self.settings.clone();
+        let max_body_size = self.max_body_size;
+        let time_source = self.time_source.clone();
+
+        Box::pin(async move {
+            let req = match authenticate(req, &*lookup, &settings, max_body_size, &time_source).await {
+                Ok(req) => req,
+                Err(AuthFailure::Rejected(rejection)) => {
+                    tracing::debug!(error = %rejection, "rejecting request that failed SigV4 authentication");
+                    return Ok(IntoResponse::<P>::into_response(rejection));
+                }
+                Err(AuthFailure::TooLarge(too_large)) => {
+                    tracing::debug!(error = %too_large, "rejecting signed request with a body that is too large");
+                    return Ok(IntoResponse::<P>::into_response(too_large));
+                }
+                Err(AuthFailure::Internal(error)) => {
+                    tracing::error!(error = %error, "failed to authenticate request");
+                    return Ok(IntoResponse::<P>::into_response(InternalFailureException));
+                }
+            };
+            inner.oneshot(req).await
+        })
+    }
+}
+
+enum AuthFailure {
+    Rejected(SigV4Rejection),
+    TooLarge(PayloadTooLarge),
+    Internal(Box<dyn StdError + Send + Sync>),
+}
+
+impl From<SigV4Rejection> for AuthFailure {
+    fn from(rejection: SigV4Rejection) -> Self {
+        Self::Rejected(rejection)
+    }
+}
+
+async fn authenticate<B, L>(
+    req: http::Request<B>,
+    lookup: &L,
+    settings: &VerificationSettings,
+    max_body_size: u64,
+    time_source: &SharedTimeSource,
+) -> Result<http::Request<B>, AuthFailure>
+where
+    B: HttpBody<Data = Bytes> + From<Bytes>,
+    B::Error: Into<Box<dyn StdError + Send + Sync>>,
+    L: CredentialsLookup,
+{
+    let (mut parts, body) = req.into_parts();
+    let uri = parts.uri.to_string();
+
+    // Check the signature is well formed and its access key is known before reading the body
+    let request = signable_request(&parts, &uri, SignableBody::UnsignedPayload)?;
+    let signature = parse_signature(&request).map_err(SigV4Rejection::from)?;
+    let account = lookup
+        .lookup(signature.access_key_id())
+        .await
+        .map_err(AuthFailure::Internal)?
+        .ok_or_else(|| SigV4Rejection::new(RejectionKind::UnrecognizedClient, "the access key is not recognized"))?;
+    let identity = account.credentials.into();
+
+    let body = match parts.headers.get(CONTENT_SHA_256).and_then(|value| value.to_str().ok()) {
+        Some(UNSIGNED_PAYLOAD | STREAMING_UNSIGNED_PAYLOAD_TRAILER) => {
+            verify_signature(&request, &signature, &identity, settings, time_source.now())
+                .map_err(SigV4Rejection::from)?;
+            body
+        }
+        Some(scheme) if scheme.starts_with(STREAMING_PREFIX) => {
+            return Err(SigV4Rejection::new(
+                RejectionKind::UnsupportedPayload,
+                format!("the `{scheme}` payload signing scheme is not supported"),
+            )
+            .into());
+        }
+        _ => {
+            let bytes = read_body(body, &parts, max_body_size).await?;
+            let request = signable_request(&parts, &uri, SignableBody::Bytes(&bytes))?;
+            verify_signature(&request, &signature, &identity, settings, time_source.now())
+                .map_err(SigV4Rejection::from)?;
+            B::from(bytes)
+        }
+    };
+
+    parts.extensions.insert(account.principal);
+    Ok(http::Request::from_parts(parts, body))
+}
+
+/// Creates the [`SignableRequest`] the signature is checked against.
+///
+/// HTTP/2 requests don't have a `host` header, so it's taken from the authority of the URI.
+fn signable_request<'a>(
+    parts: &'a Parts,
+    uri: &'a str,
+    body: SignableBody<'a>,
+) -> Result<SignableRequest<'a>, AuthFailure> {
+    let host = match parts.headers.contains_key(http::header::HOST) {
+        true => None,
+        false => parts.uri.authority().map(|authority| ("host", authority.as_str())),
+    };
+    let headers = parts
+        .headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)))
+        .chain(host);
+    SignableRequest::new(parts.method.as_str(), uri, headers, body).map_err(|err| AuthFailure::Internal(err.into()))
+}
+
+/// Buffers `body`, failing with [`PayloadTooLarge`] once it's larger than `max_body_size`.
+async fn read_body<B>(body: B, parts: &Parts, max_body_size: u64) -> Result<Bytes, AuthFailure>
+where
+    B: HttpBody<Data = Bytes>,
+    B::Error: Into<Box<dyn StdError + Send + Sync>>,
+{
+    let too_large = || AuthFailure::TooLarge(PayloadTooLarge::new(max_body_size));
+    let content_length = parts
+        .headers
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if matches!(content_length, Some(content_length) if content_length > max_body_size) {
+        return Err(too_large());
+    }
+
+    let mut body = std::pin::pin!(body);
+    let mut buffer = BytesMut::new();
+    while let Some(data) = body.data().await {
+        let data = data.map_err(|err| {
+            let err = err.into();
+            SigV4Rejection::new(
+                RejectionKind::IncompleteBody,
+                format!("failed to read the request body: {err}"),
+            )
+        })?;
+        if (buffer.len() + data.remaining()) as u64 > max_body_size {
+            return Err(too_large());
+        }
+        buffer.extend_from_slice(&data);
+    }
+    Ok(buffer.freeze())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RejectionKind {
+    MissingAuthenticationToken,
+    IncompleteSignature,
+    UnrecognizedClient,
+    InvalidSignature,
+    RequestExpired,
+    UnsupportedPayload,
+    IncompleteBody,
+}
+
+/// The response to a request that failed SigV4 authentication.
+///
+/// This is rendered using the error codes AWS services use for the protocol: for example,
+/// a request with an invalid signature is rejected with an `InvalidSignatureException` for
+/// JSON protocols, and a `SignatureDoesNotMatch` error for [`RestXml`].
+#[derive(Debug)]
+pub struct SigV4Rejection {
+    kind: RejectionKind,
+    message: String,
+}
+
+impl SigV4Rejection {
+    fn new(kind: RejectionKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// The HTTP status code of the rejection.
+    pub fn status_code(&self) -> StatusCode {
+        match self.kind {
+            RejectionKind::IncompleteSignature | RejectionKind::IncompleteBody => StatusCode::BAD_REQUEST,
+            RejectionKind::UnsupportedPayload => StatusCode::NOT_IMPLEMENTED,
+            _ => StatusCode::FORBIDDEN,
+        }
+    }
+
+    fn json_code(&self) -> &'static str {
+        match self.kind {
+            RejectionKind::MissingAuthenticationToken => "MissingAuthenticationTokenException",
+            RejectionKind::IncompleteSignature => "IncompleteSignatureException",
+            RejectionKind::UnrecognizedClient => "UnrecognizedClientException",
+            RejectionKind::InvalidSignature | RejectionKind::RequestExpired => "InvalidSignatureException",
+            RejectionKind::UnsupportedPayload => "NotImplementedException",
+            RejectionKind::IncompleteBody => "IncompleteBodyException",
+        }
+    }
+
+    fn xml_code(&self) -> &'static str {
+        match self.kind {
+            RejectionKind::MissingAuthenticationToken => "MissingAuthenticationToken",
+            RejectionKind::IncompleteSignature => "IncompleteSignature",
+            RejectionKind::UnrecognizedClient => "InvalidClientTokenId",
+            RejectionKind::InvalidSignature => "SignatureDoesNotMatch",
+            RejectionKind::RequestExpired => "RequestExpired",
+            RejectionKind::UnsupportedPayload => "NotImplemented",
+            RejectionKind::IncompleteBody => "IncompleteBody",
+        }
+    }
+
+    fn json_response(self, content_type: &'static str, code_in_body: bool) -> http::Response<BoxBody> {
+        let mut body = String::new();
+        let mut object = JsonObjectWriter::new(&mut body);
+        if code_in_body {
+            object.key("__type").string(self.json_code());
+        }
+        object.key("message").string(&self.message);
+        object.finish();
+
+        http::Response::builder()
+            .status(self.status_code())
+            .header("Content-Type", content_type)
+            .header("X-Amzn-Errortype", self.json_code())
+            .extension(RuntimeErrorExtension::new(self.json_code().to_string()))
+            .body(aws_smithy_http_server::body::to_boxed(body))
+            .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+    }
+}
+
+impl fmt::Display for SigV4Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.json_code(), self.message)
+    }
+}
+
+impl StdError for SigV4Rejection {}
+
+impl From<aws_sigv4::http_request::VerificationError> for SigV4Rejection {
+    fn from(err: aws_sigv4::http_request::VerificationError) -> Self {
+        let kind = match err.kind() {
+            VerificationErrorKind::MissingSignature => RejectionKind::MissingAuthenticationToken,
+            VerificationErrorKind::MalformedSignature | VerificationErrorKind::UnsupportedAlgorithm => {
+                RejectionKind::IncompleteSignature
+            }
+            VerificationErrorKind::AccessKeyMismatch | VerificationErrorKind::InvalidSecurityToken => {
+                RejectionKind::UnrecognizedClient
+            }
+            VerificationErrorKind::RequestTimeTooSkewed | VerificationErrorKind::Expired => {
+                RejectionKind::RequestExpired
+            }
+            _ => RejectionKind::InvalidSignature,
+        };
+        Self::new(kind, err.to_string())
+    }
+}
+
+impl IntoResponse<RestJson1> for SigV4Rejection {
+    fn into_response(self) -> http::Response<BoxBody> {
+        self.json_response("application/json", false)
+    }
+}
+
+impl IntoResponse<AwsJson1_0> for SigV4Rejection {
+    fn into_response(self) -> http::Response<BoxBody> {
+        self.json_response("application/x-amz-json-1.0", true)
+    }
+}
+
+impl IntoResponse<AwsJson1_1> for SigV4Rejection {
+    fn into_response(self) -> http::Response<BoxBody> {
+        self.json_response("application/x-amz-json-1.1", true)
+    }
+}
+
+impl IntoResponse<RestXml> for SigV4Rejection {
+    fn into_response(self) -> http::Response<BoxBody> {
+        let mut body = String::new();
+        let mut writer = XmlWriter::new(&mut body);
+        let mut response = writer.start_el("ErrorResponse").finish();
+        let mut error = response.start_el("Error").finish();
+        error.start_el("Type").finish().data("Sender");
+        error.start_el("Code").finish().data(self.xml_code());
+        error.start_el("Message").finish().data(&self.message);
+        error.finish();
+        response.finish();
+
+        http::Response::builder()
+            .status(self.status_code())
+            .header("Content-Type", "application/xml")
+            .extension(RuntimeErrorExtension::new(self.xml_code().to_string()))
+            .body(aws_smithy_http_server::body::to_boxed(body))
+            .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use aws_sigv4::http_request::{sign, PayloadChecksumKind, SigningSettings};
+    use aws_sigv4::sign::v4;
+    use aws_smithy_async::time::StaticTimeSource;
+    use tower::service_fn;
+
+    use aws_smithy_http_server::operation::OperationShape;
+    use aws_smithy_http_server::plugin::PluginLayer;
+    use aws_smithy_http_server::shape_id::ShapeId;
+
+    use super::*;
+
+    const BODY: &str = r#"{"name":"pikachu"}"#;
+
+    async fn get_body_as_string(body: BoxBody) -> String {
+        let body = hyper::body::to_bytes(body).await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    struct Service<P>(PhantomData<P>);
+
+    impl<P> ServiceShape for Service<P> {
+        const ID: ShapeId = ShapeId::new("com.example#Service", "com.example", "Service");
+        const VERSION: Option<&'static str> = None;
+        type Protocol = P;
+        type Operations = ();
+    }
+
+    struct Operation;
+
+    impl OperationShape for Operation {
+        const ID: ShapeId = ShapeId::new("com.example#Operation", "com.example", "Operation");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct User(&'static str);
+
+    struct Users;
+
+    impl CredentialsLookup for Users {
+        type Principal = User;
+
+        fn lookup(&self, access_key_id: &str) -> LookupFuture<User> {
+            let result = match access_key_id {
+                "AKIDEXAMPLE" => Ok(Some(Account::new(
+                    Credentials::new("AKIDEXAMPLE", "secret", None, None, "test"),
+                    User("example"),
+                ))),
+                "AKIDBROKEN" => Err("the user database is down".into()),
+                _ => Ok(None),
+            };
+            Box::pin(async move { result })
+        }
+    }
+
+    /// The time requests are signed at.
+    fn signing_time() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+    }
+
+    fn signed_request(access_key_id: &str, secret: &str, unsigned_payload: bool) -> http::Request<hyper::Body> {
+        let identity = Credentials::new(access_key_id, secret, None, None, "test").into();
+        let mut settings = SigningSettings::default();
+        if unsigned_payload {
+            settings.payload_checksum_kind = PayloadChecksumKind::XAmzSha256;
+        }
+        let params = v4::SigningParams::builder()
+            .identity(&identity)
+            .region("us-east-1")
+            .name("pokemon")
+            .time(signing_time())
+            .settings(settings)
+            .build()
+            .unwrap()
+            .into();
+        let body = match unsigned_payload {
+            true => SignableBody::UnsignedPayload,
+            false => SignableBody::Bytes(BODY.as_bytes()),
+        };
+        let signable = SignableRequest::new(
+            "POST",
+            "https://pokemon.example.com/species?limit=10",
+            [("content-type", "application/json")].into_iter(),
+            body,
+        )
+        .unwrap();
+        let (instructions, _) = sign(signable, &params).unwrap().into_parts();
+
+        let mut request = http::Request::builder()
+            .method("POST")
+            .uri("/species?limit=10")
+            .header("host", "pokemon.example.com")
+            .header("content-type", "application/json")
+            .body(hyper::Body::from(BODY))
+            .unwrap();
+        instructions.apply_to_request_http0x(&mut request);
+        request
+    }
+
+    /// A request body that fails to be read.
+    fn failing_body() -> hyper::Body {
+        hyper::Body::wrap_stream(futures_util::stream::once(async {
+            Err::<Bytes, _>(std::io::Error::new(
+                std::io::ErrorKind::ConnectionReset,
+                "connection reset",
+            ))
+        }))
+    }
+
+    fn plugin() -> SigV4AuthPlugin<Users> {
+        let mut settings = VerificationSettings::default();
+        settings.name = Some("pokemon".into());
+        SigV4AuthPlugin::new(Users)
+            .with_settings(settings)
+            .with_time_source(StaticTimeSource::new(signing_time()))
+    }
+
+    async fn call<P>(request: http::Request<hyper::Body>) -> http::Response<BoxBody>
+    where
+        SigV4Rejection: IntoResponse<P>,
+        PayloadTooLarge: IntoResponse<P>,
+        InternalFailureException: IntoResponse<P>,
+        P: 'static,
+    {
+        call_with::<P>(plugin(), request).await
+    }
+
+    async fn call_with<P>(
+        plugin: SigV4AuthPlugin<Users>,
+        request: http::Request<hyper::Body>,
+    ) -> http::Response<BoxBody>
+    where
+        SigV4Rejection: IntoResponse<P>,
+        PayloadTooLarge: IntoResponse<P>,
+        InternalFailureException: IntoResponse<P>,
+        P: 'static,
+    {
+        let inner = service_fn(|request: http::Request<hyper::Body>| async move {
+            let user = request
+                .extensions()
+                .get::<User>()
+                .cloned()
+                .expect("principal was inserted");
+            let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+            let body = format!("{}: {}", user.0, std::str::from_utf8(&body).unwrap());
+            Ok::<_, Infallible>(http::Response::new(aws_smithy_http_server::body::to_boxed(body)))
+        });
+        let layer = PluginLayer::new::<Service<P>, Operation>(plugin);
+        tower::Layer::layer(&layer, inner).oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn authenticated_requests_carry_the_principal() {
+        let response = call::<RestJson1>(signed_request("AKIDEXAMPLE", "secret", false)).await;
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!(
+            format!("example: {BODY}"),
+            get_body_as_string(response.into_body()).await
+        );
+    }
+
+    #[tokio::test]
+    async fn unsigned_payloads_are_passed_through() {
+        let request = signed_request("AKIDEXAMPLE", "secret", true);
+        assert_eq!("UNSIGNED-PAYLOAD", request.headers()[CONTENT_SHA_256]);
+        let response = call::<RestJson1>(request).await;
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!(
+            format!("example: {BODY}"),
+            get_body_as_string(response.into_body()).await
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_signatures_are_rejected() {
+        let response = call::<RestJson1>(signed_request("AKIDEXAMPLE", "wrong", false)).await;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        assert_eq!("InvalidSignatureException", response.headers()["X-Amzn-Errortype"]);
+        assert_eq!("application/json", response.headers()["Content-Type"]);
+        assert_eq!(
+            r#"{"message":"the signature does not match the request"}"#,
+            get_body_as_string(response.into_body()).await
+        );
+
+        let mut tampered = signed_request("AKIDEXAMPLE", "secret", false);
+        *tampered.body_mut() = hyper::Body::from("{}");
+        let response = call::<RestJson1>(tampered).await;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+    }
+
+    #[tokio::test]
+    async fn unknown_access_keys_are_rejected() {
+        let response = call::<AwsJson1_0>(signed_request("AKIDUNKNOWN", "secret", false)).await;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        assert_eq!("application/x-amz-json-1.0", response.headers()["Content-Type"]);
+        assert_eq!(
+            r#"{"__type":"UnrecognizedClientException","message":"the access key is not recognized"}"#,
+            get_body_as_string(response.into_body()).await
+        );
+    }
+
+    #[tokio::test]
+    async fn unsigned_requests_are_rejected() {
+        let request = http::Request::builder()
+            .uri("/species")
+            .header("host", "pokemon.example.com")
+            .body(hyper::Body::empty())
+            .unwrap();
+        let response = call::<RestXml>(request).await;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        assert_eq!(
+            "<ErrorResponse><Error><Type>Sender</Type><Code>MissingAuthenticationToken</Code>\
+                <Message>the request is not signed</Message></Error></ErrorResponse>",
+            get_body_as_string(response.into_body()).await
+        );
+    }
+
+    #[tokio::test]
+    async fn lookup_failures_are_internal_failures() {
+        let response = call::<AwsJson1_1>(signed_request("AKIDBROKEN", "secret", false)).await;
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+    }
+
+    #[tokio::test]
+    async fn unknown_access_keys_are_rejected_before_reading_the_body() {
+        let mut request = signed_request("AKIDUNKNOWN", "secret", false);
+        *request.body_mut() = failing_body();
+        let response = call::<RestJson1>(request).await;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        assert_eq!("UnrecognizedClientException", response.headers()["X-Amzn-Errortype"]);
+    }
+
+    #[tokio::test]
+    async fn body_read_errors_are_bad_requests() {
+        let mut request = signed_request("AKIDEXAMPLE", "secret", false);
+        *request.body_mut() = failing_body();
+        let response = call::<RestJson1>(request).await;
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        assert_eq!("IncompleteBodyException", response.headers()["X-Amzn-Errortype"]);
+    }
+
+    #[tokio::test]
+    async fn bodies_larger_than_the_limit_are_rejected() {
+        let limited = plugin().with_max_body_size(BODY.len() as u64 - 1);
+        let response = call_with::<RestJson1>(limited, signed_request("AKIDEXAMPLE", "secret", false)).await;
+        assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, response.status());
+
+        let limited = plugin().with_max_body_size(BODY.len() as u64);
+        let response = call_with::<RestJson1>(limited, signed_request("AKIDEXAMPLE", "secret", false)).await;
+        assert_eq!(StatusCode::OK, response.status());
+    }
+
+    #[tokio::test]
+    async fn signed_streaming_payloads_are_rejected() {
+        let mut request = signed_request("AKIDEXAMPLE", "secret", false);
+        request.headers_mut().insert(
+            CONTENT_SHA_256,
+            http::HeaderValue::from_static("STREAMING-AWS4-HMAC-SHA256-PAYLOAD"),
+        );
+        let response = call::<RestXml>(request).await;
+        assert_eq!(StatusCode::NOT_IMPLEMENTED, response.status());
+        assert_eq!(
+            "<ErrorResponse><Error><Type>Sender</Type><Code>NotImplemented</Code>\
+                <Message>the `STREAMING-AWS4-HMAC-SHA256-PAYLOAD` payload signing scheme is not supported</Message>\
+                </Error></ErrorResponse>",
+            get_body_as_string(response.into_body()).await
+        );
+    }
+
+    #[tokio::test]
+    async fn the_host_is_taken_from_the_uri_without_a_host_header() {
+        // HTTP/2 requests carry the host in the `:authority` pseudo-header, which ends up in the URI
+        let mut request = signed_request("AKIDEXAMPLE", "secret", false);
+        request.headers_mut().remove(http::header::HOST);
+        *request.uri_mut() = "https://pokemon.example.com/species?limit=10".parse().unwrap();
+        let response = call::<RestJson1>(request).await;
+        assert_eq!(StatusCode::OK, response.status());
+    }
+
+    #[tokio::test]
+    async fn signing_time_is_checked_against_the_time_source() {
+        let an_hour_later =
+            plugin().with_time_source(StaticTimeSource::new(signing_time() + Duration::from_secs(60 * 60)));
+        let response = call_with::<RestXml>(an_hour_later, signed_request("AKIDEXAMPLE", "secret", false)).await;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        assert!(get_body_as_string(response.into_body())
+            .await
+            .contains("<Code>RequestExpired</Code>"));
+    }
+}
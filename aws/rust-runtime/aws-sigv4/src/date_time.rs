@@ -45,6 +45,37 @@ pub(crate) fn format_date_time(time: SystemTime) -> String {
     )
 }
 
+/// Parses a `YYYYMMDD'T'HHMMSS'Z'` formatted date, as found in `x-amz-date`, into a `SystemTime`.
+///
+/// Returns `None` if the string isn't in exactly that format or doesn't describe a valid time.
+pub(crate) fn parse_amz_date_time(date_time: &str) -> Option<SystemTime> {
+    let bytes = date_time.as_bytes();
+    if bytes.len() != 16 || bytes[8] != b'T' || bytes[15] != b'Z' {
+        return None;
+    }
+    let number = |range: std::ops::Range<usize>| -> Option<u32> {
+        let digits = &bytes[range];
+        if !digits.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        Some(
+            digits
+                .iter()
+                .fold(0, |acc, d| acc * 10 + u32::from(d - b'0')),
+        )
+    };
+    let month = time::Month::try_from(number(4..6)? as u8).ok()?;
+    let date =
+        time::Date::from_calendar_date(number(0..4)? as i32, month, number(6..8)? as u8).ok()?;
+    let time = Time::from_hms(
+        number(9..11)? as u8,
+        number(11..13)? as u8,
+        number(13..15)? as u8,
+    )
+    .ok()?;
+    Some(time::PrimitiveDateTime::new(date, time).assume_utc().into())
+}
+
 /// Parse functions that are only needed for unit tests.
 #[cfg(test)]
 pub(crate) mod test_parsers {
@@ -129,6 +160,25 @@ mod tests {
         assert_eq!("20150830T123600Z", format_date_time(time));
     }
 
+    #[test]
+    fn parse_amz_date_time_matches_test_parser() {
+        assert_eq!(
+            parse_date_time("20150830T123600Z").unwrap(),
+            parse_amz_date_time("20150830T123600Z").unwrap()
+        );
+        for invalid in [
+            "",
+            "20150830T123600",
+            "20150830 123600Z",
+            "2015083OT123600Z",
+            "20151330T123600Z",
+            "20150830T253600Z",
+            "+0150830T123600Z",
+        ] {
+            assert_eq!(None, parse_amz_date_time(invalid), "{invalid}");
+        }
+    }
+
     #[test]
     fn date_roundtrip() {
         let time = parse_date("20150830").unwrap();
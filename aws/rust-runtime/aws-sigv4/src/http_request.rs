@@ -54,6 +54,49 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! # Example: Verifying a signed HTTP request
+//!
+//! Servers can check the signature on an incoming request by parsing it, looking up the
+//! credentials for the access key it names, and verifying it against them.
+//!
+//! ```rust
+//! # use aws_credential_types::Credentials;
+//! use aws_sigv4::http_request::{
+//!     parse_signature, verify_signature, SignableBody, SignableRequest, VerificationError,
+//!     VerificationSettings,
+//! };
+//! use std::time::SystemTime;
+//!
+//! #[cfg(feature = "http1")]
+//! fn verify(request: &http::Request<Vec<u8>>) -> Result<(), VerificationError> {
+//!     let headers = request
+//!         .headers()
+//!         .iter()
+//!         .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)));
+//!     let signable_request = SignableRequest::new(
+//!         request.method().as_str(),
+//!         request.uri().to_string(),
+//!         headers,
+//!         SignableBody::Bytes(request.body()),
+//!     )
+//!     .expect("valid URI");
+//!
+//!     let signature = parse_signature(&signable_request)?;
+//!     // Look up the secret for `signature.access_key_id()`
+//!     let identity = Credentials::new(
+//!         signature.access_key_id(),
+//!         "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+//!         None,
+//!         None,
+//!         "example"
+//!     ).into();
+//!     let mut settings = VerificationSettings::default();
+//!     settings.name = Some("exampleservice".into());
+//!     verify_signature(&signable_request, &signature, &identity, &settings, SystemTime::now())?;
+//!     Ok(())
+//! }
+//! ```
 
 mod canonical_request;
 mod error;
@@ -61,6 +104,7 @@ mod settings;
 mod sign;
 mod uri_path_normalization;
 mod url_escape;
+mod verify;
 
 #[cfg(test)]
 pub(crate) mod test;
@@ -70,13 +114,16 @@ use crate::sign::v4;
 use crate::sign::v4a;
 use crate::SignatureVersion;
 use aws_credential_types::Credentials;
-pub use error::SigningError;
+pub use error::{SigningError, VerificationError, VerificationErrorKind};
 pub use settings::{
     PayloadChecksumKind, PercentEncodingMode, SessionTokenMode, SignatureLocation, SigningSettings,
     UriPathNormalizationMode,
 };
 pub use sign::{sign, SignableBody, SignableRequest, SigningInstructions};
 use std::time::SystemTime;
+pub use verify::{
+    parse_signature, verify_signature, ParsedSignature, VerificationSettings, VerifiedPayload,
+};
 
 // Individual Debug impls are responsible for redacting sensitive fields.
 #[derive(Debug)]
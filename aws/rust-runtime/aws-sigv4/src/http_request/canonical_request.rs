@@ -46,8 +46,10 @@ pub(crate) mod param {
 
 pub(crate) const HMAC_256: &str = "AWS4-HMAC-SHA256";
 
-const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+pub(crate) const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
 const STREAMING_UNSIGNED_PAYLOAD_TRAILER: &str = "STREAMING-UNSIGNED-PAYLOAD-TRAILER";
+const STREAMING_SIGNED_PAYLOAD: &str = "STREAMING-AWS4-HMAC-SHA256-PAYLOAD";
+const STREAMING_SIGNED_PAYLOAD_TRAILER: &str = "STREAMING-AWS4-HMAC-SHA256-PAYLOAD-TRAILER";
 
 #[derive(Debug, PartialEq)]
 pub(crate) struct HeaderValues<'a> {
@@ -141,18 +143,11 @@ impl<'a> CanonicalRequest<'a> {
         let creds = params
             .credentials()
             .map_err(|_| CanonicalRequestError::unsupported_identity_type())?;
-        // Path encoding: if specified, re-encode % as %25
-        // Set method and path into CanonicalRequest
-        let path = req.uri().path();
-        let path = match params.settings().uri_path_normalization_mode {
-            UriPathNormalizationMode::Enabled => normalize_uri_path(path),
-            UriPathNormalizationMode::Disabled => Cow::Borrowed(path),
-        };
-        let path = match params.settings().percent_encoding_mode {
-            // The string is already URI encoded, we don't need to encode everything again, just `%`
-            PercentEncodingMode::Double => Cow::Owned(percent_encode_path(&path)),
-            PercentEncodingMode::Single => path,
-        };
+        let path = Self::path(
+            req.uri(),
+            &params.settings().uri_path_normalization_mode,
+            &params.settings().percent_encoding_mode,
+        );
         let payload_hash = Self::payload_hash(req.body());
 
         let date_time = format_date_time(*params.time());
@@ -225,6 +220,76 @@ impl<'a> CanonicalRequest<'a> {
         Ok(creq)
     }
 
+    /// Construct a CanonicalRequest for a request that has already been signed.
+    ///
+    /// Unlike [`CanonicalRequest::from`], nothing is added to the request: the canonical request
+    /// is built from exactly the `signed_headers` the signer listed, the query params already in
+    /// the URI (minus `excluded_params`), and the given `payload_hash`. This is used to verify
+    /// signatures on incoming requests.
+    pub(crate) fn from_signed_request<'b>(
+        req: &'b SignableRequest<'b>,
+        signed_headers: &[&str],
+        excluded_params: &[&str],
+        payload_hash: Cow<'b, str>,
+        percent_encoding_mode: &PercentEncodingMode,
+        uri_path_normalization_mode: &UriPathNormalizationMode,
+    ) -> Result<CanonicalRequest<'b>, CanonicalRequestError> {
+        let path = Self::path(
+            req.uri(),
+            uri_path_normalization_mode,
+            percent_encoding_mode,
+        );
+
+        let mut canonical_headers = HeaderMap::with_capacity(signed_headers.len());
+        for (name, value) in req.headers().iter() {
+            let name = HeaderName::from_str(&name.to_lowercase())?;
+            if signed_headers.contains(&name.as_str()) {
+                canonical_headers.append(name, normalize_header_value(value)?);
+            }
+        }
+        let signed_headers = signed_headers
+            .iter()
+            .map(|name| HeaderName::from_str(name).map(CanonicalHeaderName))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let params = form_urlencoded::parse(req.uri().query().unwrap_or_default().as_bytes())
+            .filter(|(key, _)| !excluded_params.contains(&key.as_ref()))
+            .collect();
+
+        Ok(CanonicalRequest {
+            method: req.method(),
+            path,
+            params: Self::canonical_query(req.uri(), params),
+            headers: canonical_headers,
+            values: SignatureValues::Headers(HeaderValues {
+                content_sha256: payload_hash,
+                date_time: String::new(),
+                security_token: None,
+                signed_headers: SignedHeaders::new(signed_headers),
+                #[cfg(feature = "sigv4a")]
+                region_set: None,
+            }),
+        })
+    }
+
+    fn path<'b>(
+        uri: &'b Uri,
+        uri_path_normalization_mode: &UriPathNormalizationMode,
+        percent_encoding_mode: &PercentEncodingMode,
+    ) -> Cow<'b, str> {
+        // Path encoding: if specified, re-encode % as %25
+        let path = uri.path();
+        let path = match uri_path_normalization_mode {
+            UriPathNormalizationMode::Enabled => normalize_uri_path(path),
+            UriPathNormalizationMode::Disabled => Cow::Borrowed(path),
+        };
+        match percent_encoding_mode {
+            // The string is already URI encoded, we don't need to encode everything again, just `%`
+            PercentEncodingMode::Double => Cow::Owned(percent_encode_path(&path)),
+            PercentEncodingMode::Single => path,
+        }
+    }
+
     fn headers(
         req: &SignableRequest<'_>,
         params: &SigningParams<'_>,
@@ -315,6 +380,7 @@ impl<'a> CanonicalRequest<'a> {
         // - use `UnsignedPayload`
         // - use `UnsignedPayload` for streaming requests
         // - use `StreamingUnsignedPayloadTrailer` for streaming requests with trailers
+        // - use `StreamingSignedPayload(Trailer)` for streaming requests with signed chunks
         match body {
             SignableBody::Bytes(data) => Cow::Owned(sha256_hex_string(data)),
             SignableBody::Precomputed(digest) => Cow::Borrowed(digest.as_str()),
@@ -322,6 +388,10 @@ impl<'a> CanonicalRequest<'a> {
             SignableBody::StreamingUnsignedPayloadTrailer => {
                 Cow::Borrowed(STREAMING_UNSIGNED_PAYLOAD_TRAILER)
             }
+            SignableBody::StreamingSignedPayload => Cow::Borrowed(STREAMING_SIGNED_PAYLOAD),
+            SignableBody::StreamingSignedPayloadTrailer => {
+                Cow::Borrowed(STREAMING_SIGNED_PAYLOAD_TRAILER)
+            }
         }
     }
 
@@ -363,6 +433,10 @@ impl<'a> CanonicalRequest<'a> {
                 );
             }
         }
+        Self::canonical_query(uri, params)
+    }
+
+    fn canonical_query(uri: &Uri, mut params: Vec<(Cow<'_, str>, Cow<'_, str>)>) -> Option<String> {
         // Sort by param name, and then by param value
         params.sort();
 
@@ -5,6 +5,7 @@
 
 use http0::header::{InvalidHeaderName, InvalidHeaderValue};
 use http0::uri::InvalidUri;
+use std::borrow::Cow;
 use std::error::Error;
 use std::fmt;
 
@@ -128,3 +129,82 @@ impl From<InvalidUri> for CanonicalRequestError {
         }
     }
 }
+
+/// The reason a request failed signature verification.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum VerificationErrorKind {
+    /// The request has neither an `authorization` header nor presigned query params.
+    MissingSignature,
+    /// The signature information on the request couldn't be parsed.
+    MalformedSignature,
+    /// The request was signed with an algorithm that isn't supported.
+    UnsupportedAlgorithm,
+    /// The request was signed for a different region or service.
+    ScopeMismatch,
+    /// The access key the request was signed with doesn't match the given identity.
+    AccessKeyMismatch,
+    /// The session token on the request is missing, unexpected, or doesn't match the identity.
+    InvalidSecurityToken,
+    /// The signing time of the request is too far from the current time.
+    RequestTimeTooSkewed,
+    /// The presigned request has expired, or asked for a longer lifetime than is allowed.
+    Expired,
+    /// The request has an unsigned or streaming payload, which the verification settings forbid.
+    UnsignedPayloadNotAllowed,
+    /// The `x-amz-content-sha256` header doesn't match the request body.
+    PayloadHashMismatch,
+    /// The signature doesn't match the request.
+    SignatureMismatch,
+    /// The identity doesn't contain AWS credentials.
+    UnsupportedIdentityType,
+}
+
+/// Error verifying the signature on a request
+#[derive(Debug)]
+pub struct VerificationError {
+    kind: VerificationErrorKind,
+    message: Cow<'static, str>,
+    source: Option<CanonicalRequestError>,
+}
+
+impl VerificationError {
+    pub(crate) fn new(kind: VerificationErrorKind, message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub(crate) fn malformed(message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(VerificationErrorKind::MalformedSignature, message)
+    }
+
+    /// Returns the reason verification failed.
+    pub fn kind(&self) -> VerificationErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for VerificationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_ref().map(|source| source as _)
+    }
+}
+
+impl From<CanonicalRequestError> for VerificationError {
+    fn from(source: CanonicalRequestError) -> Self {
+        Self {
+            kind: VerificationErrorKind::MalformedSignature,
+            message: "failed to create canonical request".into(),
+            source: Some(source),
+        }
+    }
+}
@@ -91,6 +91,19 @@ pub enum SignableBody<'a> {
 
     /// Set when a streaming body has checksum trailers.
     StreamingUnsignedPayloadTrailer,
+
+    /// Set when a streaming body is sent with `aws-chunked` encoding and every chunk is signed.
+    ///
+    /// The chunks must be signed with [`sign_chunk`](crate::chunked::sign_chunk), starting from
+    /// the signature of the request.
+    StreamingSignedPayload,
+
+    /// Set when a streaming body is sent with `aws-chunked` encoding, every chunk is signed, and
+    /// the chunks are followed by signed trailers.
+    ///
+    /// The trailers must be signed with [`sign_trailer`](crate::chunked::sign_trailer), starting
+    /// from the signature of the final, empty chunk.
+    StreamingSignedPayloadTrailer,
 }
 
 /// Instructions for applying a signature to an HTTP request.
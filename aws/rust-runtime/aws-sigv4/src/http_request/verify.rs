@@ -0,0 +1,1152 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::date_time::{format_date, parse_amz_date_time};
+#[cfg(feature = "sigv4a")]
+use crate::http_request::canonical_request::sigv4a;
+use crate::http_request::canonical_request::{
+    header, param, CanonicalRequest, StringToSign, HMAC_256, UNSIGNED_PAYLOAD,
+};
+use crate::http_request::error::{VerificationError, VerificationErrorKind};
+use crate::http_request::{
+    PercentEncodingMode, SessionTokenMode, SignableBody, SignableRequest, SignatureLocation,
+    UriPathNormalizationMode,
+};
+use crate::sign::v4;
+#[cfg(feature = "sigv4a")]
+use crate::sign::v4a;
+use crate::SignatureVersion;
+use aws_credential_types::Credentials;
+use aws_smithy_runtime_api::client::identity::Identity;
+use std::borrow::Cow;
+use std::time::{Duration, SystemTime};
+
+const AUTHORIZATION: &str = "authorization";
+const STREAMING_PREFIX: &str = "STREAMING-";
+
+/// Settings for verifying the signature of an HTTP request
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub struct VerificationSettings {
+    /// The region requests must be signed for, or `None` to accept any region.
+    ///
+    /// For SigV4a, this region must be matched by the request's region set.
+    pub region: Option<Cow<'static, str>>,
+
+    /// The service name requests must be signed for, or `None` to accept any name.
+    pub name: Option<Cow<'static, str>>,
+
+    /// How the request URL was encoded when it was signed. This must match the signer's
+    /// [`SigningSettings::percent_encoding_mode`](crate::http_request::SigningSettings::percent_encoding_mode).
+    pub percent_encoding_mode: PercentEncodingMode,
+
+    /// Whether the absolute path component of the URI was normalized when it was signed.
+    pub uri_path_normalization_mode: UriPathNormalizationMode,
+
+    /// Whether the session token is expected to be part of the canonical request.
+    ///
+    /// When this is `Include`, requests signed with headers must list the session token header
+    /// in their signed headers.
+    pub session_token_mode: SessionTokenMode,
+
+    /// The session token header or query param name, if it isn't `x-amz-security-token` or
+    /// `X-Amz-Security-Token`.
+    pub session_token_name_override: Option<&'static str>,
+
+    /// How far the signing time of a request may be from the current time.
+    pub max_clock_skew: Duration,
+
+    /// The longest `X-Amz-Expires` accepted on presigned requests.
+    pub max_expires_in: Duration,
+
+    /// Whether requests may leave their payload unsigned with `UNSIGNED-PAYLOAD` or
+    /// `STREAMING-UNSIGNED-PAYLOAD-TRAILER`.
+    pub allow_unsigned_payload: bool,
+}
+
+impl Default for VerificationSettings {
+    fn default() -> Self {
+        Self {
+            region: None,
+            name: None,
+            percent_encoding_mode: PercentEncodingMode::Double,
+            uri_path_normalization_mode: UriPathNormalizationMode::Enabled,
+            session_token_mode: SessionTokenMode::Include,
+            session_token_name_override: None,
+            // These match the limits enforced by AWS services
+            max_clock_skew: Duration::from_secs(15 * 60),
+            max_expires_in: Duration::from_secs(7 * 24 * 60 * 60),
+            allow_unsigned_payload: true,
+        }
+    }
+}
+
+/// The signature of an HTTP request, parsed but not yet verified.
+///
+/// This is returned by [`parse_signature`] and gives access to the access key ID the request was
+/// signed with, so that the matching credentials can be looked up and passed to
+/// [`verify_signature`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParsedSignature {
+    signature_version: SignatureVersion,
+    location: SignatureLocation,
+    access_key_id: String,
+    region: String,
+    name: String,
+    signed_headers: Vec<String>,
+    signature: String,
+    time: SystemTime,
+    expires_in: Option<Duration>,
+}
+
+impl ParsedSignature {
+    /// Returns the algorithm the request was signed with.
+    pub fn signature_version(&self) -> SignatureVersion {
+        self.signature_version
+    }
+
+    /// Returns where the signature was placed in the request.
+    pub fn signature_location(&self) -> SignatureLocation {
+        self.location
+    }
+
+    /// Returns the access key ID the request claims to be signed with.
+    pub fn access_key_id(&self) -> &str {
+        &self.access_key_id
+    }
+
+    /// If the request was signed with SigV4, returns the region from the credential scope.
+    pub fn region(&self) -> Option<&str> {
+        match self.signature_version {
+            SignatureVersion::V4 => Some(&self.region),
+            _ => None,
+        }
+    }
+
+    /// If the request was signed with SigV4a, returns the region set it was signed for.
+    pub fn region_set(&self) -> Option<&str> {
+        match self.signature_version {
+            SignatureVersion::V4a => Some(&self.region),
+            _ => None,
+        }
+    }
+
+    /// Returns the service name from the credential scope.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the time the request was signed at.
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+
+    /// For presigned requests, returns how long the request is valid for after [`Self::time`].
+    pub fn expires_in(&self) -> Option<Duration> {
+        self.expires_in
+    }
+
+    /// Returns the hex-encoded signature.
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+}
+
+/// What [`verify_signature`] established about the body of a request.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum VerifiedPayload {
+    /// The body was checked and is covered by the signature.
+    Signed,
+    /// The signature covers the given hex-encoded SHA-256 hash of the body, but the body was
+    /// given as [`SignableBody::UnsignedPayload`] so it hasn't been checked. The caller must
+    /// compare this hash against the body before trusting it.
+    Unverified(String),
+    /// The client didn't sign the body (`UNSIGNED-PAYLOAD`).
+    Unsigned,
+    /// The body uses the given streaming payload scheme, such as
+    /// `STREAMING-AWS4-HMAC-SHA256-PAYLOAD`. Any chunk signatures must be checked separately,
+    /// using [`ParsedSignature::signature`] as the seed signature.
+    Streaming(String),
+}
+
+/// Parses the signature from the `authorization` header or, for presigned requests, the query
+/// params of the given `request`.
+///
+/// This only checks that the signature is well formed. Call [`verify_signature`] with the
+/// credentials for [`ParsedSignature::access_key_id`] to check that it's valid.
+pub fn parse_signature(
+    request: &SignableRequest<'_>,
+) -> Result<ParsedSignature, VerificationError> {
+    let authorization = find_header(request, AUTHORIZATION);
+    let query_params = query_params(request);
+    let presigned = query_params
+        .iter()
+        .any(|(name, _)| name == param::X_AMZ_SIGNATURE);
+    match (authorization, presigned) {
+        (Some(_), true) => Err(VerificationError::malformed(
+            "only one of the authorization header and presigned query params may be used",
+        )),
+        (Some(authorization), false) => parse_authorization_header(request, authorization),
+        (None, true) => parse_query_params(&query_params),
+        (None, false) => Err(VerificationError::new(
+            VerificationErrorKind::MissingSignature,
+            "the request is not signed",
+        )),
+    }
+}
+
+/// Verifies that `signature` was produced by signing `request` with the credentials in
+/// `identity`, at a time acceptable to `settings` relative to `now`.
+///
+/// The canonical request is rebuilt from the headers the signer listed as signed, so the
+/// request must be passed as it was received: the original path and query, and all headers,
+/// including `host`. When the request doesn't carry an `x-amz-content-sha256` header, the
+/// payload hash can only be recovered from the body, so the body should be given as
+/// [`SignableBody::Bytes`] or [`SignableBody::Precomputed`].
+///
+/// On success, returns how far the body of the request could be verified.
+pub fn verify_signature(
+    request: &SignableRequest<'_>,
+    signature: &ParsedSignature,
+    identity: &Identity,
+    settings: &VerificationSettings,
+    now: SystemTime,
+) -> Result<VerifiedPayload, VerificationError> {
+    let creds = identity.data::<Credentials>().ok_or_else(|| {
+        VerificationError::new(
+            VerificationErrorKind::UnsupportedIdentityType,
+            "only AWS credentials are supported for verification",
+        )
+    })?;
+    if !constant_time_eq(&signature.access_key_id, creds.access_key_id()) {
+        return Err(VerificationError::new(
+            VerificationErrorKind::AccessKeyMismatch,
+            "the request was signed with a different access key",
+        ));
+    }
+    check_scope(signature, settings)?;
+    check_time(signature, settings, now)?;
+    let token_param = check_session_token(request, signature, creds, settings)?;
+    let (payload_hash, payload) = payload_hash(request, signature.location)?;
+    if !settings.allow_unsigned_payload
+        && (payload == VerifiedPayload::Unsigned || payload_hash.contains("UNSIGNED"))
+    {
+        return Err(VerificationError::new(
+            VerificationErrorKind::UnsignedPayloadNotAllowed,
+            "unsigned payloads are not allowed",
+        ));
+    }
+
+    let signed_headers: Vec<&str> = signature
+        .signed_headers
+        .iter()
+        .map(String::as_str)
+        .collect();
+    if let Some(missing) = signed_headers
+        .iter()
+        .find(|name| find_header(request, name).is_none())
+    {
+        return Err(VerificationError::malformed(format!(
+            "signed header `{missing}` is missing from the request"
+        )));
+    }
+    let mut excluded_params = vec![param::X_AMZ_SIGNATURE];
+    excluded_params.extend(token_param);
+    let creq = CanonicalRequest::from_signed_request(
+        request,
+        &signed_headers,
+        &excluded_params,
+        payload_hash,
+        &settings.percent_encoding_mode,
+        &settings.uri_path_normalization_mode,
+    )?;
+    let encoded_creq = v4::sha256_hex_string(creq.to_string().as_bytes());
+
+    let valid = match signature.signature_version {
+        SignatureVersion::V4 => {
+            let string_to_sign = StringToSign::new_v4(
+                signature.time,
+                &signature.region,
+                &signature.name,
+                &encoded_creq,
+            )
+            .to_string();
+            tracing::trace!(canonical_request = %creq, string_to_sign = %string_to_sign, "verifying signature");
+            let signing_key = v4::generate_signing_key(
+                creds.secret_access_key(),
+                signature.time,
+                &signature.region,
+                &signature.name,
+            );
+            let expected = v4::calculate_signature(signing_key, string_to_sign.as_bytes());
+            constant_time_eq(&expected, &signature.signature)
+        }
+        #[cfg(feature = "sigv4a")]
+        SignatureVersion::V4a => {
+            let string_to_sign = StringToSign::new_v4a(
+                signature.time,
+                &signature.region,
+                &signature.name,
+                &encoded_creq,
+            )
+            .to_string();
+            tracing::trace!(canonical_request = %creq, string_to_sign = %string_to_sign, "verifying signature");
+            let signing_key =
+                v4a::generate_signing_key(creds.access_key_id(), creds.secret_access_key());
+            v4a::verify_signature(
+                &signing_key,
+                string_to_sign.as_bytes(),
+                &signature.signature,
+            )
+        }
+        #[allow(unreachable_patterns)]
+        _ => false,
+    };
+    if valid {
+        Ok(payload)
+    } else {
+        Err(VerificationError::new(
+            VerificationErrorKind::SignatureMismatch,
+            "the signature does not match the request",
+        ))
+    }
+}
+
+fn parse_authorization_header(
+    request: &SignableRequest<'_>,
+    authorization: &str,
+) -> Result<ParsedSignature, VerificationError> {
+    // Authorization: algorithm Credential=access key ID/credential scope, SignedHeaders=SignedHeaders, Signature=signature
+    let (algorithm, components) = authorization.split_once(' ').unwrap_or((authorization, ""));
+    let signature_version = parse_algorithm(algorithm)?;
+    let (mut credential, mut signed_headers, mut signature) = (None, None, None);
+    for component in components.split(',') {
+        match component.trim().split_once('=') {
+            Some(("Credential", value)) => credential = Some(value),
+            Some(("SignedHeaders", value)) => signed_headers = Some(value),
+            Some(("Signature", value)) => signature = Some(value),
+            _ => {
+                return Err(VerificationError::malformed(
+                    "the authorization header is malformed",
+                ))
+            }
+        }
+    }
+    let missing = |name| {
+        VerificationError::malformed(format!("the authorization header is missing `{name}`"))
+    };
+    let date_time = find_header(request, header::X_AMZ_DATE)
+        .ok_or_else(|| VerificationError::malformed("the request is missing `x-amz-date`"))?;
+    #[cfg(feature = "sigv4a")]
+    let region_set = find_header(request, sigv4a::header::X_AMZ_REGION_SET);
+    #[cfg(not(feature = "sigv4a"))]
+    let region_set = None;
+    ParsedSignature::new(
+        signature_version,
+        SignatureLocation::Headers,
+        credential.ok_or_else(|| missing("Credential"))?,
+        signed_headers.ok_or_else(|| missing("SignedHeaders"))?,
+        signature.ok_or_else(|| missing("Signature"))?,
+        date_time,
+        region_set,
+        None,
+    )
+}
+
+fn parse_query_params(
+    params: &[(Cow<'_, str>, Cow<'_, str>)],
+) -> Result<ParsedSignature, VerificationError> {
+    let param = |name: &'static str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_ref())
+    };
+    let required = |name: &'static str| {
+        param(name).ok_or_else(|| {
+            VerificationError::malformed(format!("the presigned request is missing `{name}`"))
+        })
+    };
+    let signature_version = parse_algorithm(required(param::X_AMZ_ALGORITHM)?)?;
+    let expires_in = required(param::X_AMZ_EXPIRES)?
+        .parse()
+        .map(Duration::from_secs)
+        .map_err(|_| VerificationError::malformed("`X-Amz-Expires` is not a number of seconds"))?;
+    #[cfg(feature = "sigv4a")]
+    let region_set = param(sigv4a::param::X_AMZ_REGION_SET);
+    #[cfg(not(feature = "sigv4a"))]
+    let region_set = None;
+    ParsedSignature::new(
+        signature_version,
+        SignatureLocation::QueryParams,
+        required(param::X_AMZ_CREDENTIAL)?,
+        required(param::X_AMZ_SIGNED_HEADERS)?,
+        required(param::X_AMZ_SIGNATURE)?,
+        required(param::X_AMZ_DATE)?,
+        region_set,
+        Some(expires_in),
+    )
+}
+
+impl ParsedSignature {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        signature_version: SignatureVersion,
+        location: SignatureLocation,
+        credential: &str,
+        signed_headers: &str,
+        signature: &str,
+        date_time: &str,
+        region_set: Option<&str>,
+        expires_in: Option<Duration>,
+    ) -> Result<Self, VerificationError> {
+        let time = parse_amz_date_time(date_time).ok_or_else(|| {
+            VerificationError::malformed(format!("`{date_time}` is not a valid signing time"))
+        })?;
+
+        // Credential: access key ID/date/region/service/aws4_request (no region for SigV4a)
+        let scope: Vec<&str> = credential.split('/').collect();
+        let (access_key_id, date, region, name) = match (signature_version, scope.as_slice()) {
+            (SignatureVersion::V4, [access_key_id, date, region, name, "aws4_request"]) => {
+                (access_key_id, date, *region, name)
+            }
+            (SignatureVersion::V4a, [access_key_id, date, name, "aws4_request"]) => (
+                access_key_id,
+                date,
+                region_set.ok_or_else(|| {
+                    VerificationError::malformed("SigV4a requests must include a region set")
+                })?,
+                name,
+            ),
+            _ => {
+                return Err(VerificationError::malformed(format!(
+                    "`{credential}` is not a valid credential scope"
+                )))
+            }
+        };
+        if access_key_id.is_empty() || region.is_empty() || name.is_empty() {
+            return Err(VerificationError::malformed(format!(
+                "`{credential}` is not a valid credential scope"
+            )));
+        }
+        if *date != format_date(time) {
+            return Err(VerificationError::malformed(
+                "the credential scope date does not match the signing time",
+            ));
+        }
+
+        let signed_headers: Vec<String> = signed_headers.split(';').map(Into::into).collect();
+        if !signed_headers.iter().any(|name| name == "host") {
+            return Err(VerificationError::malformed(
+                "the `host` header must be signed",
+            ));
+        }
+        if signature.is_empty() || !signature.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(VerificationError::malformed(
+                "the signature is not hex encoded",
+            ));
+        }
+
+        Ok(Self {
+            signature_version,
+            location,
+            access_key_id: access_key_id.to_string(),
+            region: region.to_string(),
+            name: name.to_string(),
+            signed_headers,
+            signature: signature.to_string(),
+            time,
+            expires_in,
+        })
+    }
+}
+
+fn parse_algorithm(algorithm: &str) -> Result<SignatureVersion, VerificationError> {
+    match algorithm {
+        HMAC_256 => Ok(SignatureVersion::V4),
+        #[cfg(feature = "sigv4a")]
+        v4a::ECDSA_256 => Ok(SignatureVersion::V4a),
+        _ => Err(VerificationError::new(
+            VerificationErrorKind::UnsupportedAlgorithm,
+            format!("`{algorithm}` is not a supported signing algorithm"),
+        )),
+    }
+}
+
+fn check_scope(
+    signature: &ParsedSignature,
+    settings: &VerificationSettings,
+) -> Result<(), VerificationError> {
+    let scope_mismatch = |what| {
+        VerificationError::new(
+            VerificationErrorKind::ScopeMismatch,
+            format!("the request was signed for a different {what}"),
+        )
+    };
+    if let Some(name) = &settings.name {
+        if signature.name != *name {
+            return Err(scope_mismatch("service"));
+        }
+    }
+    if let Some(region) = &settings.region {
+        let matches = match signature.signature_version {
+            SignatureVersion::V4a => region_set_matches(&signature.region, region),
+            _ => signature.region == *region,
+        };
+        if !matches {
+            return Err(scope_mismatch("region"));
+        }
+    }
+    Ok(())
+}
+
+/// Returns true if `region` is matched by the comma-separated `region_set`, where each entry is
+/// either a region, `*`, or a prefix ending with `*` such as `us-*`.
+fn region_set_matches(region_set: &str, region: &str) -> bool {
+    region_set
+        .split(',')
+        .map(str::trim)
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => region.starts_with(prefix),
+            None => pattern == region,
+        })
+}
+
+fn check_time(
+    signature: &ParsedSignature,
+    settings: &VerificationSettings,
+    now: SystemTime,
+) -> Result<(), VerificationError> {
+    let too_skewed = || {
+        VerificationError::new(
+            VerificationErrorKind::RequestTimeTooSkewed,
+            "the difference between the request time and the current time is too large",
+        )
+    };
+    // Presigned requests can be used any time before they expire, but not before they're signed
+    let earliest = signature.time.checked_sub(settings.max_clock_skew);
+    if earliest.is_some_and(|earliest| now < earliest) {
+        return Err(too_skewed());
+    }
+    match signature.expires_in {
+        None => {
+            let latest = signature.time.checked_add(settings.max_clock_skew);
+            if latest.is_some_and(|latest| now > latest) {
+                return Err(too_skewed());
+            }
+        }
+        Some(expires_in) => {
+            if expires_in > settings.max_expires_in {
+                return Err(VerificationError::new(
+                    VerificationErrorKind::Expired,
+                    format!(
+                        "presigned requests may be valid for at most {} seconds",
+                        settings.max_expires_in.as_secs()
+                    ),
+                ));
+            }
+            let expiry = signature.time.checked_add(expires_in);
+            if expiry.is_some_and(|expiry| now > expiry) {
+                return Err(VerificationError::new(
+                    VerificationErrorKind::Expired,
+                    "the presigned request has expired",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks the session token on the request against the one in `creds`.
+///
+/// Returns the name of the query param the token was found in if it must be left out of the
+/// canonical request.
+fn check_session_token(
+    request: &SignableRequest<'_>,
+    signature: &ParsedSignature,
+    creds: &Credentials,
+    settings: &VerificationSettings,
+) -> Result<Option<&'static str>, VerificationError> {
+    let (name, token) = match signature.location {
+        SignatureLocation::Headers => {
+            let name = settings
+                .session_token_name_override
+                .unwrap_or(header::X_AMZ_SECURITY_TOKEN);
+            (name, find_header(request, name).map(Cow::Borrowed))
+        }
+        SignatureLocation::QueryParams => {
+            let name = settings
+                .session_token_name_override
+                .unwrap_or(param::X_AMZ_SECURITY_TOKEN);
+            let token = query_params(request)
+                .into_iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value);
+            (name, token)
+        }
+    };
+    let invalid =
+        |message| VerificationError::new(VerificationErrorKind::InvalidSecurityToken, message);
+    match (token.as_deref(), creds.session_token()) {
+        (None, None) => return Ok(None),
+        (Some(token), Some(expected)) if constant_time_eq(token, expected) => {}
+        (None, Some(_)) => return Err(invalid("the request is missing a session token")),
+        _ => return Err(invalid("the session token does not match the credentials")),
+    }
+    match (signature.location, &settings.session_token_mode) {
+        (SignatureLocation::Headers, SessionTokenMode::Include)
+            if !signature
+                .signed_headers
+                .iter()
+                .any(|header| header.eq_ignore_ascii_case(name)) =>
+        {
+            Err(invalid("the session token header must be signed"))
+        }
+        (SignatureLocation::QueryParams, SessionTokenMode::Exclude) => Ok(Some(name)),
+        _ => Ok(None),
+    }
+}
+
+/// Determines the payload hash that was signed, checking it against the body where possible.
+fn payload_hash<'a>(
+    request: &'a SignableRequest<'a>,
+    location: SignatureLocation,
+) -> Result<(Cow<'a, str>, VerifiedPayload), VerificationError> {
+    let computed = match request.body() {
+        SignableBody::Bytes(data) => Some(Cow::Owned(v4::sha256_hex_string(data))),
+        SignableBody::Precomputed(digest) => Some(Cow::Borrowed(digest.as_str())),
+        SignableBody::UnsignedPayload
+        | SignableBody::StreamingUnsignedPayloadTrailer
+        | SignableBody::StreamingSignedPayload
+        | SignableBody::StreamingSignedPayloadTrailer => None,
+    };
+    Ok(match find_header(request, header::X_AMZ_CONTENT_SHA_256) {
+        Some(claimed) if claimed == UNSIGNED_PAYLOAD => {
+            (Cow::Borrowed(claimed), VerifiedPayload::Unsigned)
+        }
+        Some(claimed) if claimed.starts_with(STREAMING_PREFIX) => (
+            Cow::Borrowed(claimed),
+            VerifiedPayload::Streaming(claimed.to_string()),
+        ),
+        Some(claimed) => match computed {
+            Some(computed) if computed == claimed => (computed, VerifiedPayload::Signed),
+            Some(_) => {
+                return Err(VerificationError::new(
+                    VerificationErrorKind::PayloadHashMismatch,
+                    "`x-amz-content-sha256` does not match the request body",
+                ))
+            }
+            None => (
+                Cow::Borrowed(claimed),
+                VerifiedPayload::Unverified(claimed.to_string()),
+            ),
+        },
+        // Presigned URLs can't know the body they'll be used with
+        None if location == SignatureLocation::QueryParams => {
+            (Cow::Borrowed(UNSIGNED_PAYLOAD), VerifiedPayload::Unsigned)
+        }
+        None => match computed {
+            Some(computed) => (computed, VerifiedPayload::Signed),
+            None => (Cow::Borrowed(UNSIGNED_PAYLOAD), VerifiedPayload::Unsigned),
+        },
+    })
+}
+
+fn find_header<'a>(request: &'a SignableRequest<'_>, name: &str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| *value)
+}
+
+fn query_params<'a>(request: &'a SignableRequest<'_>) -> Vec<(Cow<'a, str>, Cow<'a, str>)> {
+    form_urlencoded::parse(request.uri().query().unwrap_or_default().as_bytes()).collect()
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_time::test_parsers::parse_date_time;
+    use crate::http_request::{sign, PayloadChecksumKind, SigningParams, SigningSettings};
+    use std::time::Duration;
+
+    const BODY: &[u8] = br#"{"some":"json"}"#;
+
+    fn signing_time() -> SystemTime {
+        parse_date_time("20150830T123600Z").unwrap()
+    }
+
+    fn v4_params<'a>(identity: &'a Identity, settings: SigningSettings) -> SigningParams<'a> {
+        v4::SigningParams::builder()
+            .identity(identity)
+            .region("us-east-1")
+            .name("service")
+            .time(signing_time())
+            .settings(settings)
+            .build()
+            .unwrap()
+            .into()
+    }
+
+    fn signed_request(
+        params: &SigningParams<'_>,
+        body: &'static [u8],
+    ) -> http0::Request<&'static [u8]> {
+        let mut request = http0::Request::builder()
+            .method("POST")
+            .uri("https://example.amazonaws.com/some/path?foo=bar&baz=%20qux")
+            .header("content-type", "application/json")
+            .header("x-custom", "  some   value ")
+            .body(body)
+            .unwrap();
+        let instructions = {
+            let signable = SignableRequest::new(
+                "POST",
+                request.uri().to_string(),
+                request
+                    .headers()
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.to_str().unwrap())),
+                match params.settings().signature_location {
+                    // Presigned URLs are signed before the body they'll be sent with is known
+                    SignatureLocation::QueryParams => SignableBody::UnsignedPayload,
+                    _ => SignableBody::Bytes(body),
+                },
+            )
+            .unwrap();
+            sign(signable, params).unwrap().into_parts().0
+        };
+        instructions.apply_to_request_http0x(&mut request);
+        request
+    }
+
+    /// Converts a signed request into the form a server receives it in, with a `host` header
+    /// and only the path and query in the URI.
+    fn as_received<'a>(
+        request: &'a http0::Request<&'static [u8]>,
+        body: SignableBody<'a>,
+    ) -> SignableRequest<'a> {
+        SignableRequest::new(
+            request.method().as_str(),
+            request.uri().path_and_query().unwrap().as_str(),
+            std::iter::once(("host", request.uri().host().unwrap())).chain(
+                request
+                    .headers()
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.to_str().unwrap())),
+            ),
+            body,
+        )
+        .unwrap()
+    }
+
+    fn verify(
+        request: &SignableRequest<'_>,
+        identity: &Identity,
+        settings: &VerificationSettings,
+        now: SystemTime,
+    ) -> Result<VerifiedPayload, VerificationError> {
+        let signature = parse_signature(request)?;
+        verify_signature(request, &signature, identity, settings, now)
+    }
+
+    fn kind(result: Result<VerifiedPayload, VerificationError>) -> VerificationErrorKind {
+        result.expect_err("verification should fail").kind()
+    }
+
+    #[test]
+    fn verify_signed_headers() {
+        let identity = Credentials::for_tests().into();
+        let params = v4_params(&identity, SigningSettings::default());
+        let request = signed_request(&params, BODY);
+        let received = as_received(&request, SignableBody::Bytes(BODY));
+
+        let signature = parse_signature(&received).unwrap();
+        assert_eq!(SignatureVersion::V4, signature.signature_version());
+        assert_eq!(SignatureLocation::Headers, signature.signature_location());
+        assert_eq!("ANOTREAL", signature.access_key_id());
+        assert_eq!(Some("us-east-1"), signature.region());
+        assert_eq!("service", signature.name());
+        assert_eq!(signing_time(), signature.time());
+
+        let settings = VerificationSettings {
+            region: Some("us-east-1".into()),
+            name: Some("service".into()),
+            ..Default::default()
+        };
+        let now = signing_time() + Duration::from_secs(60);
+        assert_eq!(
+            VerifiedPayload::Signed,
+            verify_signature(&received, &signature, &identity, &settings, now).unwrap()
+        );
+    }
+
+    #[test]
+    fn tampered_requests_are_rejected() {
+        let identity = Credentials::for_tests().into();
+        let params = v4_params(&identity, SigningSettings::default());
+        let settings = VerificationSettings::default();
+        let now = signing_time();
+
+        let request = signed_request(&params, BODY);
+        let received = as_received(&request, SignableBody::Bytes(b"{}"));
+        assert_eq!(
+            VerificationErrorKind::SignatureMismatch,
+            kind(verify(&received, &identity, &settings, now))
+        );
+
+        let mut tampered = signed_request(&params, BODY);
+        tampered
+            .headers_mut()
+            .insert("x-custom", "other".parse().unwrap());
+        let received = as_received(&tampered, SignableBody::Bytes(BODY));
+        assert_eq!(
+            VerificationErrorKind::SignatureMismatch,
+            kind(verify(&received, &identity, &settings, now))
+        );
+
+        let other_secret = Credentials::new("ANOTREAL", "other", None, None, "test").into();
+        let received = as_received(&request, SignableBody::Bytes(BODY));
+        assert_eq!(
+            VerificationErrorKind::SignatureMismatch,
+            kind(verify(&received, &other_secret, &settings, now))
+        );
+
+        let other_key = Credentials::new("OTHER", "secret", None, None, "test").into();
+        assert_eq!(
+            VerificationErrorKind::AccessKeyMismatch,
+            kind(verify(&received, &other_key, &settings, now))
+        );
+
+        let other_region = VerificationSettings {
+            region: Some("us-west-2".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            VerificationErrorKind::ScopeMismatch,
+            kind(verify(&received, &identity, &other_region, now))
+        );
+    }
+
+    #[test]
+    fn clock_skew_is_enforced() {
+        let identity = Credentials::for_tests().into();
+        let params = v4_params(&identity, SigningSettings::default());
+        let request = signed_request(&params, BODY);
+        let received = as_received(&request, SignableBody::Bytes(BODY));
+        let settings = VerificationSettings::default();
+
+        for skew in [Duration::from_secs(14 * 60), Duration::from_secs(15 * 60)] {
+            verify(&received, &identity, &settings, signing_time() + skew).unwrap();
+            verify(&received, &identity, &settings, signing_time() - skew).unwrap();
+        }
+        let skew = Duration::from_secs(16 * 60);
+        assert_eq!(
+            VerificationErrorKind::RequestTimeTooSkewed,
+            kind(verify(
+                &received,
+                &identity,
+                &settings,
+                signing_time() + skew
+            ))
+        );
+        assert_eq!(
+            VerificationErrorKind::RequestTimeTooSkewed,
+            kind(verify(
+                &received,
+                &identity,
+                &settings,
+                signing_time() - skew
+            ))
+        );
+    }
+
+    #[test]
+    fn verify_presigned_request() {
+        let identity = Credentials::for_tests().into();
+        let signing_settings = SigningSettings {
+            signature_location: SignatureLocation::QueryParams,
+            expires_in: Some(Duration::from_secs(3600)),
+            ..Default::default()
+        };
+        let params = v4_params(&identity, signing_settings);
+        let request = signed_request(&params, BODY);
+        // Presigned URLs don't carry a payload hash, so whatever body is sent isn't checked
+        let received = as_received(&request, SignableBody::Bytes(b"anything"));
+        let settings = VerificationSettings::default();
+
+        let signature = parse_signature(&received).unwrap();
+        assert_eq!(
+            SignatureLocation::QueryParams,
+            signature.signature_location()
+        );
+        assert_eq!(Some(Duration::from_secs(3600)), signature.expires_in());
+
+        let now = signing_time() + Duration::from_secs(3000);
+        assert_eq!(
+            VerifiedPayload::Unsigned,
+            verify(&received, &identity, &settings, now).unwrap()
+        );
+        let now = signing_time() + Duration::from_secs(3601);
+        assert_eq!(
+            VerificationErrorKind::Expired,
+            kind(verify(&received, &identity, &settings, now))
+        );
+        let short_lived = VerificationSettings {
+            max_expires_in: Duration::from_secs(60),
+            ..Default::default()
+        };
+        assert_eq!(
+            VerificationErrorKind::Expired,
+            kind(verify(&received, &identity, &short_lived, signing_time()))
+        );
+        let no_unsigned = VerificationSettings {
+            allow_unsigned_payload: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            VerificationErrorKind::UnsignedPayloadNotAllowed,
+            kind(verify(&received, &identity, &no_unsigned, signing_time()))
+        );
+    }
+
+    #[test]
+    fn verify_session_token() {
+        let identity = Credentials::for_tests_with_session_token().into();
+        let settings = VerificationSettings::default();
+        let now = signing_time();
+
+        let request = signed_request(&v4_params(&identity, SigningSettings::default()), BODY);
+        let received = as_received(&request, SignableBody::Bytes(BODY));
+        verify(&received, &identity, &settings, now).unwrap();
+
+        let other_token = Credentials::new(
+            "ANOTREAL",
+            "notrealrnrELgWzOk3IfjzDKtFBhDby",
+            Some("other".into()),
+            None,
+            "test",
+        )
+        .into();
+        assert_eq!(
+            VerificationErrorKind::InvalidSecurityToken,
+            kind(verify(&received, &other_token, &settings, now))
+        );
+        assert_eq!(
+            VerificationErrorKind::InvalidSecurityToken,
+            kind(verify(
+                &received,
+                &Credentials::for_tests().into(),
+                &settings,
+                now
+            ))
+        );
+
+        let excluded = SigningSettings {
+            session_token_mode: SessionTokenMode::Exclude,
+            ..Default::default()
+        };
+        let request = signed_request(&v4_params(&identity, excluded), BODY);
+        let received = as_received(&request, SignableBody::Bytes(BODY));
+        assert_eq!(
+            VerificationErrorKind::InvalidSecurityToken,
+            kind(verify(&received, &identity, &settings, now))
+        );
+        let settings = VerificationSettings {
+            session_token_mode: SessionTokenMode::Exclude,
+            ..Default::default()
+        };
+        verify(&received, &identity, &settings, now).unwrap();
+
+        let presigned = SigningSettings {
+            signature_location: SignatureLocation::QueryParams,
+            expires_in: Some(Duration::from_secs(60)),
+            session_token_mode: SessionTokenMode::Exclude,
+            ..Default::default()
+        };
+        let request = signed_request(&v4_params(&identity, presigned), BODY);
+        let received = as_received(&request, SignableBody::UnsignedPayload);
+        verify(&received, &identity, &settings, now).unwrap();
+    }
+
+    #[test]
+    fn verify_payload_hash_header() {
+        let identity = Credentials::for_tests().into();
+        let signing_settings = SigningSettings {
+            payload_checksum_kind: PayloadChecksumKind::XAmzSha256,
+            ..Default::default()
+        };
+        let request = signed_request(&v4_params(&identity, signing_settings), BODY);
+        let settings = VerificationSettings::default();
+        let now = signing_time();
+
+        let received = as_received(&request, SignableBody::Bytes(BODY));
+        assert_eq!(
+            VerifiedPayload::Signed,
+            verify(&received, &identity, &settings, now).unwrap()
+        );
+
+        let received = as_received(&request, SignableBody::UnsignedPayload);
+        assert_eq!(
+            VerifiedPayload::Unverified(v4::sha256_hex_string(BODY)),
+            verify(&received, &identity, &settings, now).unwrap()
+        );
+
+        let received = as_received(&request, SignableBody::Bytes(b"{}"));
+        assert_eq!(
+            VerificationErrorKind::PayloadHashMismatch,
+            kind(verify(&received, &identity, &settings, now))
+        );
+    }
+
+    #[test]
+    fn verify_streaming_payload() {
+        let identity = Credentials::for_tests().into();
+        let signing_settings = SigningSettings {
+            payload_checksum_kind: PayloadChecksumKind::XAmzSha256,
+            ..Default::default()
+        };
+        let params = v4_params(&identity, signing_settings);
+        let mut request = http0::Request::builder()
+            .uri("https://example.amazonaws.com/")
+            .body(&b""[..])
+            .unwrap();
+        let instructions = {
+            let signable = SignableRequest::new(
+                "GET",
+                request.uri().to_string(),
+                std::iter::empty(),
+                SignableBody::StreamingUnsignedPayloadTrailer,
+            )
+            .unwrap();
+            sign(signable, &params).unwrap().into_parts().0
+        };
+        instructions.apply_to_request_http0x(&mut request);
+
+        let received = as_received(&request, SignableBody::UnsignedPayload);
+        assert_eq!(
+            VerifiedPayload::Streaming("STREAMING-UNSIGNED-PAYLOAD-TRAILER".into()),
+            verify(&received, &identity, &Default::default(), signing_time()).unwrap()
+        );
+        let no_unsigned = VerificationSettings {
+            allow_unsigned_payload: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            VerificationErrorKind::UnsignedPayloadNotAllowed,
+            kind(verify(&received, &identity, &no_unsigned, signing_time()))
+        );
+    }
+
+    #[test]
+    fn malformed_signatures_are_rejected() {
+        let request = |authorization: &'static str| {
+            SignableRequest::new(
+                "GET",
+                "/",
+                [
+                    ("host", "example.amazonaws.com"),
+                    ("x-amz-date", "20150830T123600Z"),
+                    ("authorization", authorization),
+                ]
+                .into_iter(),
+                SignableBody::Bytes(&[]),
+            )
+            .unwrap()
+        };
+        let parse_error =
+            |authorization| parse_signature(&request(authorization)).unwrap_err().kind();
+
+        let unsigned =
+            SignableRequest::new("GET", "/", std::iter::empty(), SignableBody::Bytes(&[])).unwrap();
+        assert_eq!(
+            VerificationErrorKind::MissingSignature,
+            parse_signature(&unsigned).unwrap_err().kind()
+        );
+        assert_eq!(
+            VerificationErrorKind::UnsupportedAlgorithm,
+            parse_error("Bearer token")
+        );
+        for malformed in [
+            "AWS4-HMAC-SHA256",
+            "AWS4-HMAC-SHA256 Credential=AKID/20150830/us-east-1/service/aws4_request",
+            "AWS4-HMAC-SHA256 Credential=AKID/20150830/us-east-1/service/aws4_request, SignedHeaders=x-amz-date, Signature=abc",
+            "AWS4-HMAC-SHA256 Credential=AKID/20150830/service/aws4_request, SignedHeaders=host, Signature=abc",
+            "AWS4-HMAC-SHA256 Credential=AKID/20150831/us-east-1/service/aws4_request, SignedHeaders=host, Signature=abc",
+            "AWS4-HMAC-SHA256 Credential=AKID/20150830/us-east-1/service/aws4_request, SignedHeaders=host, Signature=xyz",
+        ] {
+            assert_eq!(
+                VerificationErrorKind::MalformedSignature,
+                parse_error(malformed),
+                "{malformed}"
+            );
+        }
+        parse_signature(&request(
+            "AWS4-HMAC-SHA256 Credential=AKID/20150830/us-east-1/service/aws4_request, SignedHeaders=host, Signature=abc",
+        ))
+        .unwrap();
+    }
+
+    #[test]
+    fn region_sets() {
+        assert!(region_set_matches("*", "us-east-1"));
+        assert!(region_set_matches("us-west-2, us-east-1", "us-east-1"));
+        assert!(region_set_matches("us-*", "us-east-1"));
+        assert!(!region_set_matches("eu-*", "us-east-1"));
+        assert!(!region_set_matches("us-east-2", "us-east-1"));
+    }
+
+    #[cfg(feature = "sigv4a")]
+    #[test]
+    fn verify_sigv4a() {
+        let identity = Credentials::for_tests().into();
+        let settings = VerificationSettings {
+            region: Some("us-east-1".into()),
+            ..Default::default()
+        };
+        for signing_settings in [
+            SigningSettings::default(),
+            SigningSettings {
+                signature_location: SignatureLocation::QueryParams,
+                expires_in: Some(Duration::from_secs(60)),
+                ..Default::default()
+            },
+        ] {
+            let params = v4a::SigningParams::builder()
+                .identity(&identity)
+                .region_set("us-*")
+                .name("service")
+                .time(signing_time())
+                .settings(signing_settings)
+                .build()
+                .unwrap()
+                .into();
+            let request = signed_request(&params, BODY);
+            let received = as_received(&request, SignableBody::Bytes(BODY));
+            let signature = parse_signature(&received).unwrap();
+            assert_eq!(SignatureVersion::V4a, signature.signature_version());
+            assert_eq!(Some("us-*"), signature.region_set());
+            verify_signature(&received, &signature, &identity, &settings, signing_time()).unwrap();
+
+            let mut tampered = signed_request(&params, BODY);
+            *tampered.method_mut() = http0::Method::PUT;
+            let received = as_received(&tampered, SignableBody::Bytes(BODY));
+            assert_eq!(
+                VerificationErrorKind::SignatureMismatch,
+                kind(verify(&received, &identity, &settings, signing_time()))
+            );
+        }
+    }
+}
@@ -7,7 +7,8 @@
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 /* End of automatically managed default lints */
 //! Provides functions for calculating Sigv4 signing keys, signatures, and
-//! optional utilities for signing HTTP requests and Event Stream messages.
+//! optional utilities for signing HTTP requests, `aws-chunked` payloads, and Event Stream
+//! messages.
 
 #![allow(clippy::derive_partial_eq_without_eq)]
 #![warn(
@@ -30,6 +31,9 @@ pub mod event_stream;
 #[cfg(feature = "sign-http")]
 pub mod http_request;
 
+#[cfg(feature = "sign-http")]
+pub mod chunked;
+
 /// The version of the signing algorithm to use
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 #[non_exhaustive]
@@ -7,7 +7,7 @@ use aws_smithy_runtime_api::client::identity::Identity;
 use bytes::{BufMut, BytesMut};
 use crypto_bigint::{CheckedAdd, CheckedSub, Encoding, U256};
 use once_cell::sync::Lazy;
-use p256::ecdsa::signature::Signer;
+use p256::ecdsa::signature::{Signer, Verifier};
 use p256::ecdsa::{Signature, SigningKey};
 use std::io::Write;
 use std::time::SystemTime;
@@ -35,6 +35,27 @@ pub fn calculate_signature(signing_key: impl AsRef<[u8]>, string_to_sign: &[u8])
     hex::encode(signature.as_ref())
 }
 
+/// Checks that `signature`, a hex-encoded DER signature, is a valid Sigv4a signature of
+/// `string_to_sign` for the given signing key.
+pub(crate) fn verify_signature(
+    signing_key: impl AsRef<[u8]>,
+    string_to_sign: &[u8],
+    signature: &str,
+) -> bool {
+    let signature = match hex::decode(signature)
+        .ok()
+        .and_then(|der| Signature::from_der(&der).ok())
+    {
+        Some(signature) => signature,
+        None => return false,
+    };
+    let signing_key = SigningKey::from_bytes(signing_key.as_ref()).unwrap();
+    signing_key
+        .verifying_key()
+        .verify(string_to_sign, &signature)
+        .is_ok()
+}
+
 /// Generates a signing key for Sigv4a signing.
 pub fn generate_signing_key(access_key: &str, secret_access_key: &str) -> impl AsRef<[u8]> {
     // Capacity is the secret access key length plus the length of "AWS4A"
@@ -56,8 +56,10 @@ object CrateSet {
     val SMITHY_RUNTIME_COMMON =
         listOf(
             "aws-smithy-async",
+            "aws-smithy-cbor",
             "aws-smithy-checksums",
             "aws-smithy-client",
+            "aws-smithy-compression",
             "aws-smithy-eventstream",
             "aws-smithy-http",
             "aws-smithy-http-auth",
@@ -25,6 +25,7 @@ val workingDirUnderBuildDir = "smithyprojections/codegen-client-test/"
 dependencies {
     implementation(project(":codegen-client"))
     implementation("software.amazon.smithy:smithy-aws-protocol-tests:$smithyVersion")
+    implementation("software.amazon.smithy:smithy-protocol-tests:$smithyVersion")
     implementation("software.amazon.smithy:smithy-protocol-test-traits:$smithyVersion")
     implementation("software.amazon.smithy:smithy-aws-traits:$smithyVersion")
 }
@@ -63,6 +64,7 @@ val allCodegenTests = listOf(
     ClientTest("com.amazonaws.ebs#Ebs", "ebs", dependsOn = listOf("ebs.json")),
     ClientTest("aws.protocoltests.json10#JsonRpc10", "json_rpc10"),
     ClientTest("aws.protocoltests.json#JsonProtocol", "json_rpc11"),
+    ClientTest("smithy.protocoltests.rpcv2Cbor#RpcV2Protocol", "rpcv2Cbor"),
     ClientTest("aws.protocoltests.restjson#RestJson", "rest_json"),
     ClientTest(
         "aws.protocoltests.restjson#RestJsonExtras",
@@ -17,6 +17,8 @@ import software.amazon.smithy.model.shapes.UnionShape
 import software.amazon.smithy.model.traits.EnumTrait
 import software.amazon.smithy.model.traits.ErrorTrait
 import software.amazon.smithy.model.transform.ModelTransformer
+import software.amazon.smithy.rust.codegen.client.smithy.customizations.serdeUnknownVariantAttributes
+import software.amazon.smithy.rust.codegen.client.smithy.customizations.supportsSerde
 import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
 import software.amazon.smithy.rust.codegen.client.smithy.generators.ClientEnumGenerator
 import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationGenerator
@@ -293,7 +295,20 @@ class ClientCodegenVisitor(
      */
     override fun unionShape(shape: UnionShape) {
         rustCrate.inPrivateModuleWithReexport(privateModule(shape), symbolProvider.toSymbol(shape)) {
-            UnionGenerator(model, symbolProvider, this, shape, renderUnknownVariant = true).render()
+            val unknownVariantAttributes =
+                if (settings.codegenConfig.includeSerde && shape.supportsSerde(model)) {
+                    serdeUnknownVariantAttributes()
+                } else {
+                    listOf()
+                }
+            UnionGenerator(
+                model,
+                symbolProvider,
+                this,
+                shape,
+                renderUnknownVariant = true,
+                unknownVariantAttributes = unknownVariantAttributes,
+            ).render()
         }
         if (shape.isEventStream()) {
             rustCrate.withModule(symbolProvider.moduleForEventStreamError(shape)) {
@@ -81,6 +81,10 @@ data class ClientRustSettings(
  * [includeFluentClient]: Generate a `client` module in the generated SDK (currently the AWS SDK sets this to `false`
  *   and generates its own client)
  * [addMessageToErrors]: Adds a `message` field automatically to all error shapes
+ * [includeSerde]: Derive serde `Serialize`/`Deserialize` for generated shapes behind the `serde-serialize` and
+ *   `serde-deserialize` crate features (only available when compiling with `--cfg aws_sdk_unstable`). This is a client
+ *   only setting: server SDKs don't support serde. The `Unknown` variant of unions (de)serializes as `"$unknown"`, and
+ *   union variants missing from the model fail to deserialize
  */
 data class ClientCodegenConfig(
     override val formatTimeoutSeconds: Int = defaultFormatTimeoutSeconds,
@@ -95,6 +99,7 @@ data class ClientCodegenConfig(
     /** If true, adds `endpoint_url`/`set_endpoint_url` methods to the service config */
     val includeEndpointUrlConfig: Boolean = defaultIncludeEndpointUrlConfig,
     val enableUserConfigurableRuntimePlugins: Boolean = defaultEnableUserConfigurableRuntimePlugins,
+    val includeSerde: Boolean = defaultIncludeSerde,
 ) : CoreCodegenConfig(
         formatTimeoutSeconds, debugMode, defaultFlattenAccessors,
     ) {
@@ -106,6 +111,7 @@ data class ClientCodegenConfig(
         private const val defaultIncludeEndpointUrlConfig = true
         private const val defaultEnableUserConfigurableRuntimePlugins = true
         private const val defaultNullabilityCheckMode = "CLIENT"
+        private const val defaultIncludeSerde = false
 
         // Note: only clients default to true, servers default to false
         private const val defaultFlattenAccessors = true
@@ -130,6 +136,7 @@ data class ClientCodegenConfig(
                 includeEndpointUrlConfig = node.get().getBooleanMemberOrDefault("includeEndpointUrlConfig", defaultIncludeEndpointUrlConfig),
                 enableUserConfigurableRuntimePlugins = node.get().getBooleanMemberOrDefault("enableUserConfigurableRuntimePlugins", defaultEnableUserConfigurableRuntimePlugins),
                 nullabilityCheckMode = NullableIndex.CheckMode.valueOf(node.get().getStringMemberOrDefault("nullabilityCheckMode", defaultNullabilityCheckMode)),
+                includeSerde = node.get().getBooleanMemberOrDefault("includeSerde", defaultIncludeSerde),
             )
         } else {
             ClientCodegenConfig(
@@ -14,7 +14,10 @@ import software.amazon.smithy.rust.codegen.client.smithy.customizations.HttpAuth
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.HttpConnectorConfigDecorator
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.IdempotencyTokenDecorator
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.NoAuthDecorator
+import software.amazon.smithy.rust.codegen.client.smithy.customizations.RequestCompressionDecorator
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.SensitiveOutputDecorator
+import software.amazon.smithy.rust.codegen.client.smithy.customizations.SerdeDecorator
+import software.amazon.smithy.rust.codegen.client.smithy.customizations.SerdeSymbolMetadataProvider
 import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
 import software.amazon.smithy.rust.codegen.client.smithy.customize.CombinedClientCodegenDecorator
 import software.amazon.smithy.rust.codegen.client.smithy.customize.RequiredCustomizations
@@ -70,6 +73,8 @@ class RustClientCodegenPlugin : ClientDecoratableBuildPlugin() {
                 SensitiveOutputDecorator(),
                 IdempotencyTokenDecorator(),
                 StalledStreamProtectionDecorator(),
+                RequestCompressionDecorator(),
+                SerdeDecorator(),
                 *decorator,
             )
 
@@ -102,6 +107,8 @@ class RustClientCodegenPlugin : ClientDecoratableBuildPlugin() {
             // Rename shapes that clash with Rust reserved words & and other SDK specific features e.g. `send()` cannot
             // be the name of an operation input
             .let { RustReservedWordSymbolProvider(it, ClientReservedWords) }
+            // Derive serde for generated shapes when the `includeSerde` setting is on
+            .let { if (settings.codegenConfig.includeSerde) SerdeSymbolMetadataProvider(it) else it }
             // Allows decorators to inject a custom symbol provider
             .let { codegenDecorator.symbolProvider(it) }
     }
@@ -0,0 +1,205 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import software.amazon.smithy.model.Model
+import software.amazon.smithy.model.knowledge.TopDownIndex
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.ServiceShape
+import software.amazon.smithy.model.traits.RequestCompressionTrait
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.ClientRustModule
+import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationSection
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ConfigCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ServiceConfig
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.InlineDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.RustModule
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.toType
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.util.extendIf
+import software.amazon.smithy.rust.codegen.core.util.getTrait
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
+
+/**
+ * Compresses the requests of operations with the `@requestCompression` trait, and adds settings for request
+ * compression to the service config of services that have such operations.
+ */
+class RequestCompressionDecorator : ClientCodegenDecorator {
+    override val name: String = "RequestCompression"
+    override val order: Byte = 0
+
+    override fun configCustomizations(
+        codegenContext: ClientCodegenContext,
+        baseCustomizations: List<ConfigCustomization>,
+    ): List<ConfigCustomization> =
+        baseCustomizations.extendIf(codegenContext.serviceShape.hasRequestCompression(codegenContext.model)) {
+            RequestCompressionConfigCustomization(codegenContext)
+        }
+
+    override fun operationCustomizations(
+        codegenContext: ClientCodegenContext,
+        operation: OperationShape,
+        baseCustomizations: List<OperationCustomization>,
+    ): List<OperationCustomization> = baseCustomizations + RequestCompressionOperationCustomization(codegenContext, operation)
+}
+
+private fun ServiceShape.hasRequestCompression(model: Model): Boolean =
+    TopDownIndex.of(model).getContainedOperations(this).any { it.hasTrait<RequestCompressionTrait>() }
+
+private fun requestCompression(runtimeConfig: RuntimeConfig) =
+    InlineDependency.forRustFile(
+        RustModule.pubCrate("client_request_compression", parent = ClientRustModule.root),
+        "/inlineable/src/client_request_compression.rs",
+        CargoDependency.smithyCompression(runtimeConfig),
+        CargoDependency.smithyRuntimeApiClient(runtimeConfig),
+        CargoDependency.smithyTypes(runtimeConfig).withFeature("http-body-0-4-x"),
+    ).toType()
+
+/**
+ * Add `disable_request_compression` and `request_min_compression_size_bytes` fields to the service config.
+ */
+class RequestCompressionConfigCustomization(codegenContext: ClientCodegenContext) : ConfigCustomization() {
+    private val rc = codegenContext.runtimeConfig
+    private val codegenScope =
+        arrayOf(
+            *preludeScope,
+            "DEFAULT_MIN_COMPRESSION_SIZE_BYTES" to RuntimeType.smithyCompression(rc).resolve("DEFAULT_MIN_COMPRESSION_SIZE_BYTES"),
+            "MAX_MIN_COMPRESSION_SIZE_BYTES" to RuntimeType.smithyCompression(rc).resolve("MAX_MIN_COMPRESSION_SIZE_BYTES"),
+            "DisableRequestCompression" to requestCompression(rc).resolve("DisableRequestCompression"),
+            "RequestMinCompressionSizeBytes" to requestCompression(rc).resolve("RequestMinCompressionSizeBytes"),
+        )
+
+    override fun section(section: ServiceConfig): Writable {
+        return when (section) {
+            ServiceConfig.ConfigImpl ->
+                writable {
+                    rustTemplate(
+                        """
+                        /// Returns `true` if request compression is disabled.
+                        pub fn disable_request_compression(&self) -> bool {
+                            self.config.load::<#{DisableRequestCompression}>().is_some_and(|disable| disable.0)
+                        }
+
+                        /// Returns the minimum size, in bytes, of request payloads that are compressed.
+                        pub fn request_min_compression_size_bytes(&self) -> u32 {
+                            self.config
+                                .load::<#{RequestMinCompressionSizeBytes}>()
+                                .map_or(#{DEFAULT_MIN_COMPRESSION_SIZE_BYTES}, |min| min.0)
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+            ServiceConfig.BuilderImpl ->
+                writable {
+                    rustTemplate(
+                        """
+                        /// Disables the compression of the requests of operations that support it.
+                        ///
+                        /// Request compression is enabled by default.
+                        pub fn disable_request_compression(mut self, disable_request_compression: bool) -> Self {
+                            self.set_disable_request_compression(#{Some}(disable_request_compression));
+                            self
+                        }
+
+                        /// Disables the compression of the requests of operations that support it.
+                        ///
+                        /// Request compression is enabled by default.
+                        pub fn set_disable_request_compression(&mut self, disable_request_compression: #{Option}<bool>) -> &mut Self {
+                            self.config.store_or_unset(disable_request_compression.map(#{DisableRequestCompression}));
+                            self
+                        }
+
+                        /// Sets the minimum size, in bytes, of request payloads that are compressed. Smaller
+                        /// payloads are sent uncompressed. Streaming payloads are always compressed.
+                        ///
+                        /// Defaults to 10240 bytes.
+                        ///
+                        /// ## Panics
+                        ///
+                        /// Panics if `request_min_compression_size_bytes` is larger than 10485760 bytes.
+                        pub fn request_min_compression_size_bytes(mut self, request_min_compression_size_bytes: u32) -> Self {
+                            self.set_request_min_compression_size_bytes(#{Some}(request_min_compression_size_bytes));
+                            self
+                        }
+
+                        /// Sets the minimum size, in bytes, of request payloads that are compressed. Smaller
+                        /// payloads are sent uncompressed. Streaming payloads are always compressed.
+                        ///
+                        /// Defaults to 10240 bytes.
+                        ///
+                        /// ## Panics
+                        ///
+                        /// Panics if `request_min_compression_size_bytes` is larger than 10485760 bytes.
+                        pub fn set_request_min_compression_size_bytes(
+                            &mut self,
+                            request_min_compression_size_bytes: #{Option}<u32>,
+                        ) -> &mut Self {
+                            if let #{Some}(request_min_compression_size_bytes) = request_min_compression_size_bytes {
+                                assert!(
+                                    request_min_compression_size_bytes <= #{MAX_MIN_COMPRESSION_SIZE_BYTES},
+                                    "the minimum compression size must be no larger than {} bytes",
+                                    #{MAX_MIN_COMPRESSION_SIZE_BYTES},
+                                );
+                            }
+                            self.config.store_or_unset(request_min_compression_size_bytes.map(#{RequestMinCompressionSizeBytes}));
+                            self
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+            is ServiceConfig.BuilderFromConfigBag ->
+                writable {
+                    rustTemplate(
+                        """
+                        ${section.builder}.set_disable_request_compression(
+                            ${section.config_bag}.load::<#{DisableRequestCompression}>().map(|disable| disable.0),
+                        );
+                        ${section.builder}.set_request_min_compression_size_bytes(
+                            ${section.config_bag}.load::<#{RequestMinCompressionSizeBytes}>().map(|min| min.0),
+                        );
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+            else -> emptySection
+        }
+    }
+}
+
+class RequestCompressionOperationCustomization(
+    private val codegenContext: ClientCodegenContext,
+    private val operationShape: OperationShape,
+) : OperationCustomization() {
+    override fun section(section: OperationSection): Writable {
+        val trait = operationShape.getTrait<RequestCompressionTrait>() ?: return emptySection
+        return when (section) {
+            is OperationSection.AdditionalRuntimePlugins ->
+                writable {
+                    section.addOperationRuntimePlugin(this) {
+                        rustTemplate(
+                            "#{RequestCompressionRuntimePlugin}::new(&[${trait.encodings.joinToString { "\"$it\"" }}])",
+                            "RequestCompressionRuntimePlugin" to
+                                requestCompression(codegenContext.runtimeConfig).resolve("RequestCompressionRuntimePlugin"),
+                        )
+                    }
+                }
+            else -> emptySection
+        }
+    }
+}
@@ -32,6 +32,7 @@ class ResiliencyConfigCustomization(codegenContext: ClientCodegenContext) : Conf
             "ClientRateLimiter" to retries.resolve("ClientRateLimiter"),
             "ClientRateLimiterPartition" to retries.resolve("ClientRateLimiterPartition"),
             "debug" to RuntimeType.Tracing.resolve("debug"),
+            "HedgingConfig" to RuntimeType.smithyRuntime(runtimeConfig).resolve("client::hedging::HedgingConfig"),
             "IntoShared" to RuntimeType.smithyRuntimeApi(runtimeConfig).resolve("shared::IntoShared"),
             "RetryConfig" to retryConfig.resolve("RetryConfig"),
             "RetryMode" to RuntimeType.smithyTypes(runtimeConfig).resolve("retry::RetryMode"),
@@ -65,6 +66,11 @@ class ResiliencyConfigCustomization(codegenContext: ClientCodegenContext) : Conf
                             self.config.load::<#{TimeoutConfig}>()
                         }
 
+                        /// Return a reference to the hedging configuration contained in this config, if any.
+                        pub fn hedging_config(&self) -> #{Option}<&#{HedgingConfig}> {
+                            self.config.load::<#{HedgingConfig}>()
+                        }
+
                         /// Returns a reference to the retry partition contained in this config, if any.
                         ///
                         /// WARNING: This method is unstable and may be removed at any time. Do not rely on this
@@ -263,6 +269,40 @@ class ResiliencyConfigCustomization(codegenContext: ClientCodegenContext) : Conf
                         *codegenScope,
                     )
 
+                    rustTemplate(
+                        """
+                        /// Set the hedging_config for the builder
+                        ///
+                        /// When hedging is enabled, an idempotent operation that hasn't received a response after the
+                        /// configured delay sends a second request, and uses whichever response arrives first. Hedged
+                        /// requests count towards the max attempts of the retry config. Hedging is disabled by default.
+                        ///
+                        /// ## Examples
+                        ///
+                        /// ```no_run
+                        /// ## use std::time::Duration;
+                        /// use $moduleUseName::config::Config;
+                        /// use $moduleUseName::config::retry::HedgingConfig;
+                        ///
+                        /// let hedging_config = HedgingConfig::enabled(Duration::from_millis(200));
+                        /// let config = Config::builder().hedging_config(hedging_config).build();
+                        /// ```
+                        pub fn hedging_config(mut self, hedging_config: #{HedgingConfig}) -> Self {
+                            self.set_hedging_config(Some(hedging_config));
+                            self
+                        }
+
+                        /// Set the hedging_config for the builder
+                        ///
+                        /// See [`hedging_config`](Self::hedging_config) for more information.
+                        pub fn set_hedging_config(&mut self, hedging_config: #{Option}<#{HedgingConfig}>) -> &mut Self {
+                            hedging_config.map(|h| self.config.store_put(h));
+                            self
+                        }
+                        """,
+                        *codegenScope,
+                    )
+
                     rustTemplate(
                         """
                         /// Set the partition for retry-related state. When clients share a retry partition, they will
@@ -299,6 +339,10 @@ class ResiliencyConfigCustomization(codegenContext: ClientCodegenContext) : Conf
                         "${section.builder}.set_timeout_config(${section.config_bag}.load::<#{TimeoutConfig}>().cloned());",
                         *codegenScope,
                     )
+                    rustTemplate(
+                        "${section.builder}.set_hedging_config(${section.config_bag}.load::<#{HedgingConfig}>().cloned());",
+                        *codegenScope,
+                    )
                     rustTemplate(
                         "${section.builder}.set_retry_partition(${section.config_bag}.load::<#{RetryPartition}>().cloned());",
                         *codegenScope,
@@ -330,6 +374,11 @@ class ResiliencyReExportCustomization(codegenContext: ClientCodegenContext) {
                 "pub use #{types_retry}::RetryPartition;",
                 "types_retry" to RuntimeType.smithyRuntime(runtimeConfig).resolve("client::retries"),
             )
+
+            rustTemplate(
+                "pub use #{hedging}::HedgingConfig;",
+                "hedging" to RuntimeType.smithyRuntime(runtimeConfig).resolve("client::hedging"),
+            )
         }
         rustCrate.withModule(ClientRustModule.Config.timeout) {
             rustTemplate(
@@ -0,0 +1,179 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import software.amazon.smithy.model.Model
+import software.amazon.smithy.model.shapes.BlobShape
+import software.amazon.smithy.model.shapes.CollectionShape
+import software.amazon.smithy.model.shapes.ListShape
+import software.amazon.smithy.model.shapes.MapShape
+import software.amazon.smithy.model.shapes.MemberShape
+import software.amazon.smithy.model.shapes.NumberShape
+import software.amazon.smithy.model.shapes.Shape
+import software.amazon.smithy.model.shapes.StringShape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.model.shapes.UnionShape
+import software.amazon.smithy.model.traits.ErrorTrait
+import software.amazon.smithy.model.traits.JsonNameTrait
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute.Companion.all
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute.Companion.any
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute.Companion.cfgAttr
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute.Companion.derive
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute.Companion.feature
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute.Companion.pair
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute.Companion.serde
+import software.amazon.smithy.rust.codegen.core.rustlang.Feature
+import software.amazon.smithy.rust.codegen.core.rustlang.RustMetadata
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rustInlineTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
+import software.amazon.smithy.rust.codegen.core.smithy.RustSymbolProvider
+import software.amazon.smithy.rust.codegen.core.smithy.SymbolMetadataProvider
+import software.amazon.smithy.rust.codegen.core.smithy.expectRustMetadata
+import software.amazon.smithy.rust.codegen.core.smithy.isOptional
+import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.getTrait
+import software.amazon.smithy.rust.codegen.core.util.hasStreamingMember
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
+import software.amazon.smithy.rust.codegen.core.util.isEventStream
+import software.amazon.smithy.rust.codegen.core.util.shouldRedact
+
+/**
+ * Adds the `serde-serialize` and `serde-deserialize` crate features when the `includeSerde` codegen setting is on.
+ *
+ * The serde implementations themselves are only compiled when building with `--cfg aws_sdk_unstable`, the same as
+ * the ones on the `aws-smithy-types` primitives the features forward to.
+ */
+class SerdeDecorator : ClientCodegenDecorator {
+    override val name: String = "Serde"
+    override val order: Byte = 0
+
+    override fun extras(
+        codegenContext: ClientCodegenContext,
+        rustCrate: RustCrate,
+    ) {
+        if (codegenContext.settings.codegenConfig.includeSerde) {
+            rustCrate.mergeFeature(Feature("serde-serialize", false, listOf("aws-smithy-types/serde-serialize")))
+            rustCrate.mergeFeature(Feature("serde-deserialize", false, listOf("aws-smithy-types/serde-deserialize")))
+        }
+    }
+}
+
+/**
+ * Derives `serde::Serialize` and `serde::Deserialize` for generated structures and unions.
+ *
+ * Members are (de)serialized under their modeled name, or their `@jsonName` when they have one. Optional members that
+ * are `@sensitive` are redacted by leaving them out of the serialized output. Sensitive members that can't be left
+ * out, like required members and union variants, fail to serialize instead.
+ *
+ * Errors and shapes with streaming members are skipped since some of their fields can't be serialized. See
+ * [serdeUnknownVariantAttributes] for how the `Unknown` variant of unions is handled.
+ */
+class SerdeSymbolMetadataProvider(private val base: RustSymbolProvider) : SymbolMetadataProvider(base) {
+    private val refuseSerialize =
+        RuntimeType.smithyTypes(base.config.runtimeConfig).resolve("sensitive::refuse_serialize")
+
+    override fun structureMeta(structureShape: StructureShape): RustMetadata =
+        base.toSymbol(structureShape).expectRustMetadata().withSerdeDerivesIf(structureShape.supportsSerde(model))
+
+    override fun unionMeta(unionShape: UnionShape): RustMetadata =
+        base.toSymbol(unionShape).expectRustMetadata().withSerdeDerivesIf(unionShape.supportsSerde(model))
+
+    override fun memberMeta(memberShape: MemberShape): RustMetadata {
+        val baseMetadata = base.toSymbol(memberShape).expectRustMetadata()
+        val container = model.expectShape(memberShape.container)
+        if (!container.supportsSerde(model)) {
+            return baseMetadata
+        }
+
+        val wireName = memberShape.getTrait<JsonNameTrait>()?.value ?: memberShape.memberName
+        val attributes = mutableListOf(serdeHelper(SerdeFeatures.Either, pair("rename" to wireName.dq())))
+        if (memberShape.containsSensitiveData(model)) {
+            val redaction =
+                if (container is StructureShape && !base.toSymbol(memberShape).isOptional()) {
+                    writable { rustInlineTemplate("serialize_with = \"#{refuse_serialize}\"", "refuse_serialize" to refuseSerialize) }
+                } else {
+                    // Optional members deserialize to `None` when absent. Union variants that are skipped
+                    // return an error when serialized.
+                    writable("skip_serializing")
+                }
+            attributes.add(serdeHelper(SerdeFeatures.Serialize, redaction))
+        }
+        return baseMetadata.copy(additionalAttributes = baseMetadata.additionalAttributes + attributes)
+    }
+
+    override fun enumMeta(stringShape: StringShape) = base.toSymbol(stringShape).expectRustMetadata()
+
+    override fun listMeta(listShape: ListShape) = base.toSymbol(listShape).expectRustMetadata()
+
+    override fun mapMeta(mapShape: MapShape) = base.toSymbol(mapShape).expectRustMetadata()
+
+    override fun stringMeta(stringShape: StringShape) = base.toSymbol(stringShape).expectRustMetadata()
+
+    override fun numberMeta(numberShape: NumberShape) = base.toSymbol(numberShape).expectRustMetadata()
+
+    override fun blobMeta(blobShape: BlobShape) = base.toSymbol(blobShape).expectRustMetadata()
+
+    private fun RustMetadata.withSerdeDerivesIf(condition: Boolean): RustMetadata =
+        if (condition) {
+            // These are marked as derive helpers so that builders, which don't carry the member attributes, leave them out
+            copy(
+                additionalAttributes =
+                    additionalAttributes +
+                        listOf(
+                            Attribute(cfgAttr(SerdeFeatures.Serialize.predicate, derive(RuntimeType.SerdeSerialize)), isDeriveHelper = true),
+                            Attribute(cfgAttr(SerdeFeatures.Deserialize.predicate, derive(RuntimeType.SerdeDeserialize)), isDeriveHelper = true),
+                        ),
+            )
+        } else {
+            this
+        }
+}
+
+/**
+ * Attributes for the `Unknown` variant of unions that derive serde.
+ *
+ * `Unknown` round-trips as the unit variant `"$unknown"`, a name that can't clash with a member since it isn't a valid
+ * Smithy identifier. The data of the variant the service actually sent isn't kept, so only that it was unknown is
+ * preserved. Variants that are unknown to the generated code, like `{"newVariant": 1}` from a newer model, still fail
+ * to deserialize: serde has no fallback for externally tagged enums.
+ */
+fun serdeUnknownVariantAttributes(): List<Attribute> =
+    listOf(serdeHelper(SerdeFeatures.Either, pair("rename" to "\$unknown".dq())))
+
+/** Whether [SerdeSymbolMetadataProvider] derives serde for this shape. */
+fun Shape.supportsSerde(model: Model): Boolean =
+    when (this) {
+        is StructureShape -> !hasTrait<ErrorTrait>() && !hasStreamingMember(model)
+        is UnionShape -> !isEventStream() && !hasStreamingMember(model)
+        else -> false
+    }
+
+/** `cfg` predicates that gate the generated serde support. */
+enum class SerdeFeatures(val predicate: Writable) {
+    Serialize(all(writable("aws_sdk_unstable"), feature("serde-serialize"))),
+    Deserialize(all(writable("aws_sdk_unstable"), feature("serde-deserialize"))),
+    Either(all(writable("aws_sdk_unstable"), any(feature("serde-serialize"), feature("serde-deserialize")))),
+}
+
+private fun serdeHelper(
+    features: SerdeFeatures,
+    args: Writable,
+) = Attribute(cfgAttr(features.predicate, serde(args)), isDeriveHelper = true)
+
+/** Sensitive data can also be nested in the elements of a collection or the keys and values of a map */
+private fun MemberShape.containsSensitiveData(model: Model): Boolean =
+    shouldRedact(model) ||
+        when (val target = model.expectShape(target)) {
+            is CollectionShape -> target.member.containsSensitiveData(model)
+            is MapShape -> target.key.containsSensitiveData(model) || target.value.containsSensitiveData(model)
+            else -> false
+        }
@@ -25,9 +25,14 @@ import software.amazon.smithy.rust.codegen.core.smithy.generators.EnumMemberMode
 import software.amazon.smithy.rust.codegen.core.smithy.generators.EnumType
 import software.amazon.smithy.rust.codegen.core.util.dq
 
-/** Infallible enums have an `Unknown` variant and can't fail to parse */
+/**
+ * Infallible enums have an `Unknown` variant and can't fail to parse
+ *
+ * When [includeSerde] is set, the enum is (de)serialized with serde as its string value, so unknown values round-trip.
+ */
 data class InfallibleEnumType(
     val unknownVariantModule: RustModule,
+    val includeSerde: Boolean = false,
 ) : EnumType() {
     companion object {
         /** Name of the generated unknown enum member name for enums with named members. */
@@ -143,6 +148,40 @@ data class InfallibleEnumType(
             rust("$UnknownVariant(#T)", unknownVariantValue(context))
         }
 
+    override fun additionalEnumImpls(context: EnumGeneratorContext): Writable =
+        writable {
+            if (includeSerde) {
+                rustTemplate(
+                    """
+                    ##[cfg(all(aws_sdk_unstable, feature = "serde-serialize"))]
+                    impl #{Serialize} for ${context.enumName} {
+                        fn serialize<S>(&self, serializer: S) -> #{Result}<S::Ok, S::Error>
+                        where
+                            S: #{Serde}::Serializer,
+                        {
+                            serializer.serialize_str(self.as_str())
+                        }
+                    }
+
+                    ##[cfg(all(aws_sdk_unstable, feature = "serde-deserialize"))]
+                    impl<'de> #{Deserialize}<'de> for ${context.enumName} {
+                        fn deserialize<D>(deserializer: D) -> #{Result}<Self, D::Error>
+                        where
+                            D: #{Serde}::Deserializer<'de>,
+                        {
+                            let value = <#{String} as #{Deserialize}>::deserialize(deserializer)?;
+                            #{Ok}(Self::from(value.as_str()))
+                        }
+                    }
+                    """,
+                    *preludeScope,
+                    "Serde" to RuntimeType.Serde,
+                    "Serialize" to RuntimeType.SerdeSerialize,
+                    "Deserialize" to RuntimeType.SerdeDeserialize,
+                )
+            }
+        }
+
     override fun additionalAsStrMatchArms(context: EnumGeneratorContext): Writable =
         writable {
             rust("${context.enumName}::$UnknownVariant(value) => value.as_str()")
@@ -252,6 +291,7 @@ class ClientEnumGenerator(codegenContext: ClientCodegenContext, shape: StringSha
                 visibility = Visibility.PUBCRATE,
                 parent = ClientRustModule.primitives,
             ),
+            includeSerde = codegenContext.settings.codegenConfig.includeSerde,
         ),
     )
 
@@ -6,6 +6,8 @@
 package software.amazon.smithy.rust.codegen.client.smithy.generators
 
 import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.traits.IdempotentTrait
+import software.amazon.smithy.model.traits.ReadonlyTrait
 import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
 import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
@@ -14,6 +16,7 @@ import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
 import software.amazon.smithy.rust.codegen.core.smithy.customize.writeCustomizations
 import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
 
 /**
  * Generates operation-level runtime plugins
@@ -32,6 +35,7 @@ class OperationRuntimePluginGenerator(
                 "ConfigBag" to RuntimeType.configBag(codegenContext.runtimeConfig),
                 "Cow" to RuntimeType.Cow,
                 "FrozenLayer" to smithyTypes.resolve("config_bag::FrozenLayer"),
+                "IdempotentOperation" to RuntimeType.smithyRuntime(rc).resolve("client::hedging::IdempotentOperation"),
                 "IntoShared" to runtimeApi.resolve("shared::IntoShared"),
                 "Layer" to smithyTypes.resolve("config_bag::Layer"),
                 "RetryClassifiers" to runtimeApi.resolve("client::retries::RetryClassifiers"),
@@ -63,6 +67,7 @@ class OperationRuntimePluginGenerator(
 
                     ${"" /* TODO(IdentityAndAuth): Resolve auth parameters from input for services that need this */}
                     cfg.store_put(#{AuthSchemeOptionResolverParams}::new(#{StaticAuthSchemeOptionResolverParams}::new()));
+                    #{idempotent}
 
                     #{additional_config}
 
@@ -83,6 +88,13 @@ class OperationRuntimePluginGenerator(
             """,
             *codegenScope,
             *preludeScope,
+            "idempotent" to
+                writable {
+                    // Only operations that are safe to send more than once may be hedged
+                    if (operationShape.hasTrait<ReadonlyTrait>() || operationShape.hasTrait<IdempotentTrait>()) {
+                        rustTemplate("cfg.store_put(#{IdempotentOperation}::new());", *codegenScope)
+                    }
+                },
             "additional_config" to
                 writable {
                     writeCustomizations(
@@ -52,6 +52,7 @@ class ProtocolParserGenerator(
 
     private val codegenScope =
         arrayOf(
+            "ByteStream" to RuntimeType.byteStream(codegenContext.runtimeConfig),
             "Bytes" to RuntimeType.Bytes,
             "Headers" to RuntimeType.headers(codegenContext.runtimeConfig),
             "Response" to RuntimeType.smithyRuntimeApi(codegenContext.runtimeConfig).resolve("http::Response"),
@@ -227,18 +228,67 @@ class ProtocolParserGenerator(
         }
     }
 
+    /**
+     * Generate an async parser for successful responses that reads the document from the response body as it
+     * arrives. Returns `null` if the protocol doesn't have an incremental parser for the operation's output.
+     */
+    fun parseIncrementalResponseFn(
+        operationShape: OperationShape,
+        customizations: List<OperationCustomization>,
+    ): RuntimeType? {
+        val incrementalParser = protocol.structuredDataParser().incrementalOperationParser(operationShape) ?: return null
+        val outputShape = operationShape.outputShape(model)
+        val outputSymbol = symbolProvider.toSymbol(outputShape)
+        val errorSymbol = symbolProvider.symbolForOperationError(operationShape)
+        return protocolFunctions.deserializeFn(operationShape, fnNameSuffix = "http_response_incremental") { fnName ->
+            rustBlockTemplate(
+                "pub async fn $fnName(response: &mut #{Response}) -> std::result::Result<#{O}, #{E}>",
+                *codegenScope,
+                "O" to outputSymbol,
+                "E" to errorSymbol,
+            ) {
+                rustTemplate(
+                    """
+                    let mut _response_body = #{ByteStream}::new(std::mem::replace(response.body_mut(), #{SdkBody}::taken()));
+
+                    let _response_status = response.status().as_u16();
+                    let _response_headers = response.headers();
+                    """,
+                    *codegenScope,
+                )
+                withBlock("Ok({", "})") {
+                    renderShapeParser(
+                        operationShape,
+                        outputShape,
+                        httpBindingResolver.responseBindings(operationShape),
+                        errorSymbol,
+                        customizations,
+                        incrementalParser,
+                    )
+                }
+            }
+        }
+    }
+
     private fun RustWriter.renderShapeParser(
         operationShape: OperationShape,
         outputShape: StructureShape,
         bindings: List<HttpBindingDescriptor>,
         errorSymbol: Symbol,
         customizations: List<OperationCustomization>,
+        incrementalParser: RuntimeType? = null,
     ) {
         val httpBindingGenerator = ResponseBindingGenerator(protocol, codegenContext, operationShape)
         val structuredDataParser = protocol.structuredDataParser()
         Attribute.AllowUnusedMut.render(this)
         rust("let mut output = #T::default();", symbolProvider.symbolForBuilder(outputShape))
-        if (outputShape.id == operationShape.output.get()) {
+        if (incrementalParser != null) {
+            rust(
+                "output = #T(&mut _response_body, output).await.map_err(#T::unhandled)?;",
+                incrementalParser,
+                errorSymbol,
+            )
+        } else if (outputShape.id == operationShape.output.get()) {
             structuredDataParser.operationParser(operationShape)?.also { parser ->
                 rust(
                     "output = #T(_response_body, output).map_err(#T::unhandled)?;",
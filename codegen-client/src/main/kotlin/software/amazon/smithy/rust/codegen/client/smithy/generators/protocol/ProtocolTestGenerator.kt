@@ -313,14 +313,22 @@ class DefaultProtocolTestGenerator(
         testCase.headers.forEach { (key, value) ->
             writeWithNoFormatting(".header(${key.dq()}, ${value.dq()})")
         }
-        rust(
+        // Bodies of binary media types are base64 encoded in the protocol test, and need to be decoded first
+        val body =
+            testCase.body.orNull()?.let { body ->
+                val mediaType = (testCase.bodyMediaType.orNull() ?: "unknown").dq()
+                "#{decode_body_data}(${body.dq().replace("#", "##")}.as_bytes(), #{MediaType}::from($mediaType)).into_owned()"
+            } ?: "vec![]"
+        rustTemplate(
             """
             .status(${testCase.code})
-            .body(#T::from(${testCase.body.orNull()?.dq()?.replace("#", "##") ?: "vec![]"}))
+            .body(#{SdkBody}::from($body))
             .unwrap()
             ).unwrap();
             """,
-            RT.sdkBody(runtimeConfig = rc),
+            "SdkBody" to RT.sdkBody(runtimeConfig = rc),
+            "decode_body_data" to RT.protocolTest(rc, "decode_body_data"),
+            "MediaType" to RT.protocolTest(rc, "MediaType"),
         )
         rustTemplate(
             """
@@ -331,7 +339,13 @@ class DefaultProtocolTestGenerator(
             let config = op.config().expect("the operation has config");
             let de = config.load::<#{SharedResponseDeserializer}>().expect("the config must have a deserializer");
 
-            let parsed = de.deserialize_streaming(&mut http_response);
+            let parsed = match de.deserialize_streaming(&mut http_response) {
+                Some(parsed) => Some(parsed),
+                None => match de.deserialize_incrementally(&mut http_response) {
+                    Some(future) => Some(future.await),
+                    None => None,
+                },
+            };
             let parsed = parsed.unwrap_or_else(|| {
                 let http_response = http_response.map(|body| {
                     #{SdkBody}::from(#{copy_from_slice}(body.bytes().unwrap()))
@@ -592,21 +606,6 @@ class DefaultProtocolTestGenerator(
 
         // These tests are not even attempted to be generated, either because they will not compile
         // or because they are flaky
-        private val DisableTests =
-            setOf<String>(
-                // TODO(https://github.com/smithy-lang/smithy-rs/issues/2891): Implement support for `@requestCompression`
-                "SDKAppendedGzipAfterProvidedEncoding_restJson1",
-                "SDKAppendedGzipAfterProvidedEncoding_restXml",
-                "SDKAppendsGzipAndIgnoresHttpProvidedEncoding_awsJson1_0",
-                "SDKAppendsGzipAndIgnoresHttpProvidedEncoding_awsJson1_1",
-                "SDKAppendsGzipAndIgnoresHttpProvidedEncoding_awsQuery",
-                "SDKAppendsGzipAndIgnoresHttpProvidedEncoding_ec2Query",
-                "SDKAppliedContentEncoding_awsJson1_0",
-                "SDKAppliedContentEncoding_awsJson1_1",
-                "SDKAppliedContentEncoding_awsQuery",
-                "SDKAppliedContentEncoding_ec2Query",
-                "SDKAppliedContentEncoding_restJson1",
-                "SDKAppliedContentEncoding_restXml",
-            )
+        private val DisableTests = setOf<String>()
     }
 }
@@ -45,6 +45,8 @@ class ResponseDeserializerGenerator(
             "OutputOrError" to interceptorContext.resolve("OutputOrError"),
             "OrchestratorError" to orchestrator.resolve("OrchestratorError"),
             "DeserializeResponse" to RuntimeType.smithyRuntimeApiClient(runtimeConfig).resolve("client::ser_de::DeserializeResponse"),
+            "DeserializeResponseFuture" to
+                RuntimeType.smithyRuntimeApiClient(runtimeConfig).resolve("client::ser_de::DeserializeResponseFuture"),
             "SdkBody" to RuntimeType.sdkBody(runtimeConfig),
             "SdkError" to RuntimeType.sdkError(runtimeConfig),
             "debug_span" to RuntimeType.Tracing.resolve("debug_span"),
@@ -60,6 +62,11 @@ class ResponseDeserializerGenerator(
         val outputSymbol = symbolProvider.toSymbol(operationShape.outputShape(model))
         val operationName = symbolProvider.toSymbol(operationShape).name
         val streaming = operationShape.outputShape(model).hasStreamingMember(model)
+        val parseIncrementalResponse =
+            when (streaming) {
+                true -> null
+                else -> parserGenerator.parseIncrementalResponseFn(operationShape, customizations)
+            }
 
         writer.rustTemplate(
             """
@@ -68,6 +75,8 @@ class ResponseDeserializerGenerator(
             impl #{DeserializeResponse} for ${operationName}ResponseDeserializer {
                 #{deserialize_streaming}
 
+                #{deserialize_incrementally}
+
                 fn deserialize_nonstreaming(&self, response: &#{HttpResponse}) -> #{OutputOrError} {
                     #{deserialize_nonstreaming}
                 }
@@ -82,6 +91,12 @@ class ResponseDeserializerGenerator(
                         deserializeStreaming(operationShape, customizations)
                     }
                 },
+            "deserialize_incrementally" to
+                writable {
+                    if (parseIncrementalResponse != null) {
+                        deserializeIncrementally(operationShape, customizations, parseIncrementalResponse)
+                    }
+                },
             "deserialize_nonstreaming" to
                 writable {
                     when (streaming) {
@@ -120,6 +135,37 @@ class ResponseDeserializerGenerator(
         )
     }
 
+    private fun RustWriter.deserializeIncrementally(
+        operationShape: OperationShape,
+        customizations: List<OperationCustomization>,
+        parseIncrementalResponse: RuntimeType,
+    ) {
+        val successCode = httpBindingResolver.httpTrait(operationShape).code
+        rustTemplate(
+            """
+            fn deserialize_incrementally<'a>(&'a self, response: &'a mut #{HttpResponse}) -> #{Option}<#{DeserializeResponseFuture}<'a>> {
+                ##[allow(unused_mut)]
+                let mut force_error = false;
+                #{BeforeParseResponse}
+
+                // If this is an error, defer to the non-streaming parser
+                if (!response.status().is_success() && response.status().as_u16() != $successCode) || force_error {
+                    return #{None};
+                }
+                #{Some}(#{DeserializeResponseFuture}::new(async move {
+                    #{type_erase_result}(#{parse_incremental_response}(response).await)
+                }))
+            }
+            """,
+            *codegenScope,
+            "parse_incremental_response" to parseIncrementalResponse,
+            "BeforeParseResponse" to
+                writable {
+                    writeCustomizations(customizations, OperationSection.BeforeParseResponse(customizations, "response", "force_error", body = null))
+                },
+        )
+    }
+
     private fun RustWriter.deserializeStreamingError(
         operationShape: OperationShape,
         customizations: List<OperationCustomization>,
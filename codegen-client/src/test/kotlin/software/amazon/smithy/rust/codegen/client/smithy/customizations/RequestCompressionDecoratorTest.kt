@@ -0,0 +1,56 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.client.testutil.clientIntegrationTest
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.testModule
+import software.amazon.smithy.rust.codegen.core.testutil.unitTest
+
+internal class RequestCompressionDecoratorTest {
+    private val model =
+        """
+        namespace com.example
+        use aws.protocols#awsJson1_0
+        @awsJson1_0
+        service HelloService {
+            operations: [SayHello],
+            version: "1"
+        }
+        @optionalAuth
+        @requestCompression(encodings: ["gzip"])
+        operation SayHello { input: TestInput }
+        structure TestInput {
+           foo: String,
+        }
+        """.asSmithyModel()
+
+    @Test
+    fun `the minimum compression size is validated when it is set`() {
+        clientIntegrationTest(model) { _, crate ->
+            crate.testModule {
+                unitTest("the_largest_minimum_compression_size_is_accepted") {
+                    rust(
+                        """
+                        let config = crate::Config::builder().request_min_compression_size_bytes(10485760).build();
+                        assert_eq!(config.request_min_compression_size_bytes(), 10485760);
+                        """,
+                    )
+                }
+
+                unitTest(
+                    "a_larger_minimum_compression_size_is_rejected",
+                    additionalAttributes = listOf(Attribute.shouldPanic("the minimum compression size must be no larger than 10485760 bytes")),
+                ) {
+                    rust("let _ = crate::Config::builder().request_min_compression_size_bytes(10485761);")
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.model.node.Node
+import software.amazon.smithy.rust.codegen.client.testutil.clientIntegrationTest
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.testutil.IntegrationTestParams
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.integrationTest
+import software.amazon.smithy.rust.codegen.core.util.runCommand
+
+class SerdeDecoratorTest {
+    private val model =
+        """
+        namespace com.example
+        use aws.protocols#awsJson1_0
+        @awsJson1_0
+        service HelloService {
+            operations: [SayHello],
+            version: "1"
+        }
+        @optionalAuth
+        operation SayHello { input: TestInput }
+
+        structure TestInput {
+            item: Item,
+            login: Login,
+        }
+
+        structure Item {
+            @jsonName("DisplayName")
+            name: String,
+            secret: Secret,
+            status: Status,
+            choice: Choice,
+            created: Timestamp,
+            data: Blob,
+            document: Document,
+        }
+
+        structure Login {
+            @default("")
+            password: Secret,
+        }
+
+        @sensitive
+        string Secret
+
+        enum Status {
+            ACTIVE,
+            INACTIVE,
+        }
+
+        union Choice {
+            number: Integer,
+            text: String,
+        }
+        """.asSmithyModel()
+
+    @Test
+    fun `generated shapes can be serialized with serde`() {
+        val params =
+            IntegrationTestParams(
+                additionalSettings =
+                    Node.objectNodeBuilder().withMember(
+                        "codegen",
+                        Node.objectNodeBuilder().withMember("includeSerde", true).build(),
+                    ).build(),
+                command = { dir ->
+                    "cargo test --features behavior-version-latest,serde-serialize,serde-deserialize".runCommand(
+                        dir,
+                        environment = mapOf("RUSTFLAGS" to "--deny warnings --cfg aws_sdk_unstable"),
+                    )
+                },
+            )
+        clientIntegrationTest(model, params) { codegenContext, rustCrate ->
+            rustCrate.integrationTest("serde") {
+                val moduleName = codegenContext.moduleUseName()
+                rustTemplate(
+                    """
+                    use $moduleName::types::{Choice, Item, Login, Status};
+
+                    ##[test]
+                    fn round_trip_redacts_sensitive_members() {
+                        let item = Item::builder()
+                            .name("name")
+                            .secret("hunter2")
+                            .status(Status::from("PENDING"))
+                            .choice(Choice::Text("text".into()))
+                            .build();
+
+                        let json = #{serde_json}::to_value(&item).unwrap();
+                        assert_eq!(json["DisplayName"], "name");
+                        assert_eq!(json["status"], "PENDING");
+                        assert_eq!(json["choice"], #{serde_json}::json!({ "text": "text" }));
+                        assert!(json.get("secret").is_none(), "{json}");
+
+                        let parsed: Item = #{serde_json}::from_value(json).unwrap();
+                        assert_eq!(parsed.name(), Some("name"));
+                        assert_eq!(parsed.secret(), None);
+                        assert_eq!(parsed.status().map(|s| s.as_str()), Some("PENDING"));
+                        assert_eq!(parsed.choice(), item.choice());
+                    }
+
+                    ##[test]
+                    fn required_sensitive_members_refuse_to_serialize() {
+                        let login = Login::builder().password("hunter2").build();
+                        assert!(#{serde_json}::to_string(&login).is_err());
+                    }
+
+                    ##[test]
+                    fn unknown_union_variant_round_trips() {
+                        // `Unknown` is non-exhaustive, so it can only be created by deserializing it
+                        let json = r##""${'$'}unknown""##;
+                        let choice: Choice = #{serde_json}::from_str(json).unwrap();
+                        assert!(choice.is_unknown());
+                        assert_eq!(#{serde_json}::to_string(&choice).unwrap(), json);
+
+                        // Variants missing from the model can't be deserialized
+                        assert!(#{serde_json}::from_str::<Choice>(r##"{"other": 1}"##).is_err());
+                    }
+                    """,
+                    "serde_json" to CargoDependency.SerdeJson.toType(),
+                )
+            }
+        }
+    }
+}
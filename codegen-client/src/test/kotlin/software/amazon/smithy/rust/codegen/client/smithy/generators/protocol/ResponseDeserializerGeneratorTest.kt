@@ -0,0 +1,183 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.generators.protocol
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.client.testutil.clientIntegrationTest
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.integrationTest
+
+class ResponseDeserializerGeneratorTest {
+    private val model =
+        """
+        namespace test
+
+        use aws.protocols#restJson1
+
+        @restJson1
+        service TestService {
+            version: "2019-12-16",
+            operations: [ListThings]
+        }
+
+        @http(uri: "/things", method: "GET")
+        @readonly
+        operation ListThings {
+            output: ListThingsOutput,
+            errors: [InvalidToken]
+        }
+
+        structure ListThingsOutput {
+            @httpHeader("x-count")
+            count: Integer,
+            things: ThingList,
+            nextToken: String,
+        }
+
+        list ThingList {
+            member: Thing
+        }
+
+        structure Thing {
+            name: String,
+            size: Integer,
+        }
+
+        @error("client")
+        @httpError(400)
+        structure InvalidToken {
+            message: String,
+        }
+        """.asSmithyModel(smithyVersion = "2")
+
+    private fun codegenScope(runtimeConfig: RuntimeConfig): Array<Pair<String, Any>> =
+        arrayOf(
+            "hyper" to CargoDependency.Hyper.toDevDependency().toType(),
+            "infallible_client_fn" to
+                CargoDependency.smithyRuntimeTestUtil(runtimeConfig)
+                    .toType().resolve("client::http::test_util::infallible_client_fn"),
+            "SdkBody" to
+                CargoDependency.smithyTypes(runtimeConfig)
+                    .toDevDependency().withFeature("http-body-0-4-x").toType()
+                    .resolve("body::SdkBody"),
+            "tokio" to CargoDependency.Tokio.toType(),
+        )
+
+    @Test
+    fun `list outputs are deserialized from chunked response bodies`() {
+        clientIntegrationTest(model) { codegenContext, rustCrate ->
+            rustCrate.integrationTest("incremental_deserialization") {
+                val moduleName = codegenContext.moduleUseName()
+                rustTemplate(
+                    """
+                    /// Responds with a body that is sent in `chunks`, which split tokens between them.
+                    fn chunked_response(status: u16, chunks: &'static [&'static str]) -> http::Response<#{SdkBody}> {
+                        let (mut sender, body) = #{hyper}::Body::channel();
+                        #{tokio}::spawn(async move {
+                            for chunk in chunks {
+                                if sender.send_data(#{hyper}::body::Bytes::from_static(chunk.as_bytes())).await.is_err() {
+                                    break;
+                                }
+                            }
+                        });
+                        http::Response::builder()
+                            .status(status)
+                            .header("x-count", "2")
+                            .body(#{SdkBody}::from_body_0_4(body))
+                            .unwrap()
+                    }
+
+                    fn client(status: u16, chunks: &'static [&'static str]) -> $moduleName::Client {
+                        let config = $moduleName::Config::builder()
+                            .http_client(#{infallible_client_fn}(move |_| chunked_response(status, chunks)))
+                            .endpoint_url("http://localhost:1234")
+                            .build();
+                        $moduleName::Client::from_conf(config)
+                    }
+                    """,
+                    *codegenScope(codegenContext.runtimeConfig),
+                )
+
+                Attribute.TokioTest.render(this)
+                rustTemplate(
+                    """
+                    async fn list_members_are_read_across_chunks() {
+                        let client = client(
+                            200,
+                            &[
+                                r##"{"nextToken": "to"##,
+                                r##"ken", "things": [{"name": "a", "si"##,
+                                r##"ze": 1}, {"name""##,
+                                r##": "b", "size": 2}], "unknown": {"a": [1, 2]"##,
+                                r##"}}"##,
+                            ],
+                        );
+                        let output = client.list_things().send().await.expect("success");
+                        assert_eq!(Some(2), output.count());
+                        assert_eq!(Some("token"), output.next_token());
+                        let things = output.things();
+                        assert_eq!(2, things.len());
+                        assert_eq!(Some("a"), things[0].name());
+                        assert_eq!(Some(1), things[0].size());
+                        assert_eq!(Some("b"), things[1].name());
+                        assert_eq!(Some(2), things[1].size());
+                    }
+                    """,
+                )
+
+                Attribute.TokioTest.render(this)
+                rustTemplate(
+                    """
+                    async fn empty_bodies_are_empty_outputs() {
+                        let output = client(200, &[]).list_things().send().await.expect("success");
+                        assert_eq!(Some(2), output.count());
+                        assert!(output.things().is_empty());
+                        assert_eq!(None, output.next_token());
+                    }
+                    """,
+                )
+
+                Attribute.TokioTest.render(this)
+                rustTemplate(
+                    """
+                    async fn invalid_bodies_fail() {
+                        let error = client(200, &[r##"{"things": [{"name": "a"}"##, r##", 5]}"##])
+                            .list_things()
+                            .send()
+                            .await
+                            .expect_err("the second thing isn't a structure");
+                        assert!(format!("{error:?}").contains("expected start object or null"), "{error:?}");
+                    }
+                    """,
+                )
+
+                Attribute.TokioTest.render(this)
+                rustTemplate(
+                    """
+                    async fn errors_are_parsed_from_the_buffered_body() {
+                        let error = client(400, &[r##"{"__type": "InvalidToken", "mess"##, r##"age": "the token has expired"}"##])
+                            .list_things()
+                            .send()
+                            .await
+                            .expect_err("the token is invalid")
+                            .into_service_error();
+                        match error {
+                            $moduleName::operation::list_things::ListThingsError::InvalidToken(error) => {
+                                assert_eq!(Some("the token has expired"), error.message());
+                            }
+                            other => panic!("expected InvalidToken, found: {other:?}"),
+                        }
+                    }
+                    """,
+                )
+            }
+        }
+    }
+}
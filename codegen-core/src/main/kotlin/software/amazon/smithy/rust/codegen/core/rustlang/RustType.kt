@@ -606,6 +606,8 @@ class Attribute(val inner: Writable, val isDeriveHelper: Boolean = false) {
 
         fun serde(vararg lints: String): Writable = macroWithArgs("serde", *lints)
 
+        fun serde(vararg attrMacros: Writable): Writable = macroWithArgs("serde", *attrMacros)
+
         fun any(vararg attrMacros: Writable): Writable = macroWithArgs("any", *attrMacros)
 
         fun cfg(vararg attrMacros: Writable): Writable = macroWithArgs("cfg", *attrMacros)
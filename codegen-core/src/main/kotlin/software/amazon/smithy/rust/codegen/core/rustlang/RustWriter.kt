@@ -561,6 +561,7 @@ class RustWriter private constructor(
                             )
 
                         fileName == "package.json" -> rawWriter(fileName, debugMode = debugMode)
+                        fileName == "openapi.json" -> rawWriter(fileName, debugMode = debugMode)
                         fileName == "stubgen.sh" -> rawWriter(fileName, debugMode = debugMode)
                         else -> RustWriter(fileName, namespace, debugMode = debugMode)
                     }
@@ -306,7 +306,8 @@ class BuilderGenerator(
 
     private fun renderBuilder(writer: RustWriter) {
         writer.docs("A builder for #D.", structureSymbol)
-        metadata.additionalAttributes.render(writer)
+        // Derive helpers belong to the structure's derives, which were filtered out above
+        metadata.additionalAttributes.filterNot { it.isDeriveHelper }.render(writer)
         Attribute(derive(builderDerives)).render(writer)
         writer.rustBlock("pub struct $builderName") {
             for (member in members) {
@@ -108,8 +108,24 @@ class CargoTomlGenerator(
                     dependencies.filter { it.scope == DependencyScope.Dev }
                         .associate { it.name to it.toMap() },
                 "features" to cargoFeatures.toMap(),
-            ).deepMergeWith(manifestCustomizations)
+            ).plus(cfgUnstableDependencies()).deepMergeWith(manifestCustomizations)
 
         writer.writeWithNoFormatting(TomlWriter().write(cargoToml))
     }
+
+    /** Dependencies that are only available when compiling with `--cfg aws_sdk_unstable` */
+    private fun cfgUnstableDependencies(): Map<String, Any> {
+        val unstable = dependencies.filter { it.scope == DependencyScope.CfgUnstable }
+        return if (unstable.isEmpty()) {
+            emptyMap()
+        } else {
+            mapOf(
+                "target" to
+                    mapOf(
+                        "cfg(aws_sdk_unstable)" to
+                            mapOf("dependencies" to unstable.associate { it.name to it.toMap() }),
+                    ),
+            )
+        }
+    }
 }
@@ -52,6 +52,7 @@ fun CodegenTarget.renderUnknownVariant() =
  *
  * Finally, if `[renderUnknownVariant]` is true (the default), it will render an `Unknown` variant. This is used by
  * clients to allow response parsing to succeed, even if the server has added a new variant since the client was generated.
+ * Additional attributes for that variant can be passed in with [unknownVariantAttributes].
  */
 open class UnionGenerator(
     val model: Model,
@@ -59,6 +60,7 @@ open class UnionGenerator(
     private val writer: RustWriter,
     private val shape: UnionShape,
     private val renderUnknownVariant: Boolean = true,
+    private val unknownVariantAttributes: List<Attribute> = listOf(),
 ) {
     private val sortedMembers: List<MemberShape> = shape.allMembers.values.sortedBy { symbolProvider.toMemberName(it) }
     private val unionSymbol = symbolProvider.toSymbol(shape)
@@ -102,6 +104,7 @@ open class UnionGenerator(
                 rust("/// To investigate this, consider turning on debug logging to print the raw HTTP response.")
                 // at some point in the future, we may start actually putting things like the raw data in here.
                 Attribute.NonExhaustive.render(this)
+                unknownVariantAttributes.render(this)
                 rust("Unknown,")
             }
         }
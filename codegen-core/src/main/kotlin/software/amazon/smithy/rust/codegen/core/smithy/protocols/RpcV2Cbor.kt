@@ -0,0 +1,199 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.protocols
+
+import software.amazon.smithy.codegen.core.CodegenException
+import software.amazon.smithy.model.Model
+import software.amazon.smithy.model.pattern.UriPattern
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.ServiceShape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.model.shapes.ToShapeId
+import software.amazon.smithy.model.traits.HttpTrait
+import software.amazon.smithy.model.traits.TimestampFormatTrait
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.CborParserGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.StructuredDataParserGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize.CborSerializerGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize.StructuredDataSerializerGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.traits.SyntheticInputTrait
+import software.amazon.smithy.rust.codegen.core.smithy.traits.SyntheticOutputTrait
+import software.amazon.smithy.rust.codegen.core.util.expectTrait
+import software.amazon.smithy.rust.codegen.core.util.inputShape
+import software.amazon.smithy.rust.codegen.core.util.isInputEventStream
+import software.amazon.smithy.rust.codegen.core.util.isOutputEventStream
+import software.amazon.smithy.rust.codegen.core.util.isStreaming
+import software.amazon.smithy.rust.codegen.core.util.outputShape
+
+/**
+ * The value of the `smithy-protocol` header that requests and responses of the Smithy RPC v2 CBOR protocol carry.
+ * https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html#requests
+ */
+const val RPC_V2_CBOR_PROTOCOL_HEADER_VALUE = "rpc-v2-cbor"
+
+class RpcV2CborHttpBindingResolver(
+    private val model: Model,
+    private val serviceShape: ServiceShape,
+) : HttpBindingResolver {
+    private fun bindings(shape: ToShapeId): List<HttpBindingDescriptor> {
+        val members = shape.let { model.expectShape(it.toShapeId()) }.members()
+        // TODO(https://github.com/smithy-lang/smithy-rs/issues/2237): support non-streaming members too
+        if (members.size > 1 && members.any { it.isStreaming(model) }) {
+            throw CodegenException(
+                "We only support one payload member if that payload contains a streaming member." +
+                    "Tracking issue to relax this constraint: https://github.com/smithy-lang/smithy-rs/issues/2237",
+            )
+        }
+
+        return members.map {
+            if (it.isStreaming(model)) {
+                HttpBindingDescriptor(it, HttpLocation.PAYLOAD, "document")
+            } else {
+                HttpBindingDescriptor(it, HttpLocation.DOCUMENT, "document")
+            }
+        }
+            .toList()
+    }
+
+    /**
+     * Requests are always `POST`s to `/service/{serviceName}/operation/{operationName}`, where the service name
+     * doesn't include the namespace of the service.
+     */
+    override fun httpTrait(operationShape: OperationShape): HttpTrait =
+        HttpTrait.builder()
+            .code(200)
+            .method("POST")
+            .uri(UriPattern.parse("/service/${serviceShape.id.name}/operation/${operationShape.id.name}"))
+            .build()
+
+    override fun requestBindings(operationShape: OperationShape): List<HttpBindingDescriptor> =
+        bindings(operationShape.inputShape)
+
+    override fun responseBindings(operationShape: OperationShape): List<HttpBindingDescriptor> =
+        bindings(operationShape.outputShape)
+
+    override fun errorResponseBindings(errorShape: ToShapeId): List<HttpBindingDescriptor> = bindings(errorShape)
+
+    /**
+     * Requests for operations with no modeled input are sent with an empty body and no `Content-Type` header.
+     * https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html#requests
+     */
+    override fun requestContentType(operationShape: OperationShape): String? =
+        when {
+            operationShape.isInputEventStream(model) -> "application/vnd.amazon.eventstream"
+            operationShape.inputShape(model).expectTrait<SyntheticInputTrait>().originalId == null -> null
+            else -> "application/cbor"
+        }
+
+    /**
+     * Responses for operations with no modeled output are sent with an empty body and no `Content-Type` header.
+     * https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html#responses
+     */
+    override fun responseContentType(operationShape: OperationShape): String? =
+        when {
+            operationShape.isOutputEventStream(model) -> "application/vnd.amazon.eventstream"
+            operationShape.outputShape(model).expectTrait<SyntheticOutputTrait>().originalId == null -> null
+            else -> "application/cbor"
+        }
+}
+
+open class RpcV2Cbor(val codegenContext: CodegenContext) : Protocol {
+    private val runtimeConfig = codegenContext.runtimeConfig
+    private val smithyCbor = RuntimeType.smithyCbor(runtimeConfig)
+    private val errorScope =
+        arrayOf(
+            "Bytes" to RuntimeType.Bytes,
+            "Decoder" to smithyCbor.resolve("Decoder"),
+            "DeserializeError" to smithyCbor.resolve("decode::DeserializeError"),
+            "ErrorMetadataBuilder" to RuntimeType.errorMetadataBuilder(runtimeConfig),
+            "Headers" to RuntimeType.headers(runtimeConfig),
+        )
+
+    override val httpBindingResolver: HttpBindingResolver =
+        RpcV2CborHttpBindingResolver(codegenContext.model, codegenContext.serviceShape)
+
+    // Timestamps are always tagged epoch seconds, regardless of the `@timestampFormat` trait.
+    override val defaultTimestampFormat: TimestampFormatTrait.Format = TimestampFormatTrait.Format.EPOCH_SECONDS
+
+    override fun additionalRequestHeaders(operationShape: OperationShape): List<Pair<String, String>> =
+        listOf(
+            "smithy-protocol" to RPC_V2_CBOR_PROTOCOL_HEADER_VALUE,
+            "Accept" to "application/cbor",
+        )
+
+    override fun additionalResponseHeaders(operationShape: OperationShape): List<Pair<String, String>> =
+        listOf("smithy-protocol" to RPC_V2_CBOR_PROTOCOL_HEADER_VALUE)
+
+    override fun additionalErrorResponseHeaders(errorShape: StructureShape): List<Pair<String, String>> =
+        listOf("smithy-protocol" to RPC_V2_CBOR_PROTOCOL_HEADER_VALUE)
+
+    override fun structuredDataParser(): StructuredDataParserGenerator =
+        CborParserGenerator(codegenContext, httpBindingResolver)
+
+    override fun structuredDataSerializer(): StructuredDataSerializerGenerator =
+        CborSerializerGenerator(codegenContext, httpBindingResolver)
+
+    override fun parseHttpErrorMetadata(operationShape: OperationShape): RuntimeType =
+        ProtocolFunctions.crossOperationFn("parse_http_error_metadata") { fnName ->
+            rustTemplate(
+                """
+                pub fn $fnName(_response_status: u16, _response_headers: &#{Headers}, response_body: &[u8]) -> Result<#{ErrorMetadataBuilder}, #{DeserializeError}> {
+                    #{parse_error_metadata}(response_body)
+                }
+                """,
+                *errorScope,
+                "parse_error_metadata" to parseErrorMetadata(),
+            )
+        }
+
+    override fun parseEventStreamErrorMetadata(operationShape: OperationShape): RuntimeType =
+        ProtocolFunctions.crossOperationFn("parse_event_stream_error_metadata") { fnName ->
+            rustTemplate(
+                """
+                pub fn $fnName(payload: &#{Bytes}) -> Result<#{ErrorMetadataBuilder}, #{DeserializeError}> {
+                    #{parse_error_metadata}(payload)
+                }
+                """,
+                *errorScope,
+                "parse_error_metadata" to parseErrorMetadata(),
+            )
+        }
+
+    /**
+     * Reads the error code from the `__type` key of a CBOR map, dropping the namespace of the shape ID it holds.
+     * https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html#operation-error-serialization
+     */
+    private fun parseErrorMetadata(): RuntimeType =
+        ProtocolFunctions.crossOperationFn("rpc_v2_cbor_parse_error_metadata") { fnName ->
+            rustTemplate(
+                """
+                pub(crate) fn $fnName(body: &[u8]) -> Result<#{ErrorMetadataBuilder}, #{DeserializeError}> {
+                    let mut builder = #{ErrorMetadataBuilder}::default();
+                    if body.is_empty() {
+                        return Ok(builder);
+                    }
+                    let decoder = &mut #{Decoder}::new(body);
+                    let len = decoder.map()?;
+                    let mut read = 0;
+                    while !decoder.at_end_of_collection(len, read)? {
+                        read += 1;
+                        match decoder.str()?.as_ref() {
+                            "__type" => {
+                                let code = decoder.str()?;
+                                builder = builder.code(code.rsplit('##').next().unwrap_or_default());
+                            }
+                            _ => decoder.skip()?,
+                        }
+                    }
+                    Ok(builder)
+                }
+                """,
+                *errorScope,
+            )
+        }
+}
@@ -0,0 +1,495 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.protocols.parse
+
+import software.amazon.smithy.codegen.core.Symbol
+import software.amazon.smithy.model.shapes.BlobShape
+import software.amazon.smithy.model.shapes.BooleanShape
+import software.amazon.smithy.model.shapes.ByteShape
+import software.amazon.smithy.model.shapes.CollectionShape
+import software.amazon.smithy.model.shapes.DocumentShape
+import software.amazon.smithy.model.shapes.DoubleShape
+import software.amazon.smithy.model.shapes.FloatShape
+import software.amazon.smithy.model.shapes.IntegerShape
+import software.amazon.smithy.model.shapes.LongShape
+import software.amazon.smithy.model.shapes.MapShape
+import software.amazon.smithy.model.shapes.MemberShape
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.Shape
+import software.amazon.smithy.model.shapes.ShortShape
+import software.amazon.smithy.model.shapes.StringShape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.model.shapes.TimestampShape
+import software.amazon.smithy.model.shapes.UnionShape
+import software.amazon.smithy.model.traits.EnumTrait
+import software.amazon.smithy.model.traits.SparseTrait
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustBlock
+import software.amazon.smithy.rust.codegen.core.rustlang.rustBlockTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.withBlock
+import software.amazon.smithy.rust.codegen.core.rustlang.withBlockTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.core.smithy.CodegenTarget
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.smithy.customize.NamedCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.customize.Section
+import software.amazon.smithy.rust.codegen.core.smithy.generators.UnionGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.generators.renderUnknownVariant
+import software.amazon.smithy.rust.codegen.core.smithy.generators.setterName
+import software.amazon.smithy.rust.codegen.core.smithy.isOptional
+import software.amazon.smithy.rust.codegen.core.smithy.isRustBoxed
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpBindingResolver
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpLocation
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.ProtocolFunctions
+import software.amazon.smithy.rust.codegen.core.util.PANIC
+import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
+import software.amazon.smithy.rust.codegen.core.util.inputShape
+import software.amazon.smithy.rust.codegen.core.util.isTargetUnit
+import software.amazon.smithy.rust.codegen.core.util.outputShape
+
+/**
+ * Class describing a CBOR parser section that can be used in a customization.
+ */
+sealed class CborParserSection(name: String) : Section(name) {
+    data class BeforeBoxingDeserializedMember(val shape: MemberShape) :
+        CborParserSection("BeforeBoxingDeserializedMember")
+}
+
+/**
+ * Customization for the CBOR parser.
+ */
+typealias CborParserCustomization = NamedCustomization<CborParserSection>
+
+/**
+ * Parses CBOR into shapes, as required by the Smithy RPC v2 CBOR protocol.
+ *
+ * Unlike the JSON parser, where every value may be `null`, deserializing a member yields a plain value: `null`s are
+ * dealt with before a member's value is deserialized, by leaving structure members unset and by putting `None`s in
+ * sparse collections.
+ */
+class CborParserGenerator(
+    private val codegenContext: CodegenContext,
+    private val httpBindingResolver: HttpBindingResolver,
+    /**
+     * Whether we should parse a value for a shape into its associated unconstrained type. For example, when the shape
+     * is a `StructureShape`, we should construct and return a builder instead of building into the final `struct` the
+     * user gets. This is only relevant for the server, that parses the incoming request and only after enforces
+     * constraint traits.
+     *
+     * The function returns a data class that signals the return symbol that should be parsed, and whether it's
+     * unconstrained or not.
+     */
+    private val returnSymbolToParse: (Shape) -> ReturnSymbolToParse = { shape ->
+        ReturnSymbolToParse(codegenContext.symbolProvider.toSymbol(shape), false)
+    },
+    private val customizations: List<CborParserCustomization> = listOf(),
+) : StructuredDataParserGenerator {
+    private val model = codegenContext.model
+    private val symbolProvider = codegenContext.symbolProvider
+    private val runtimeConfig = codegenContext.runtimeConfig
+    private val codegenTarget = codegenContext.target
+    private val smithyCbor = RuntimeType.smithyCbor(runtimeConfig)
+    private val protocolFunctions = ProtocolFunctions(codegenContext)
+    private val builderInstantiator = codegenContext.builderInstantiator()
+    private val codegenScope =
+        arrayOf(
+            "Decoder" to smithyCbor.resolve("Decoder"),
+            "Error" to smithyCbor.resolve("decode::DeserializeError"),
+            "set_optional" to smithyCbor.resolve("decode::set_optional"),
+            "Type" to smithyCbor.resolve("data::Type"),
+            *preludeScope,
+        )
+
+    /**
+     * Reusable structure parser implementation that can be used to generate parsing code for
+     * operation, error and structure shapes.
+     * We still generate the parser symbol even if there are no included members because the server
+     * generation requires parsers for all input structures.
+     */
+    private fun structureParser(
+        shape: Shape,
+        builderSymbol: Symbol,
+        includedMembers: List<MemberShape>,
+        fnNameSuffix: String? = null,
+    ): RuntimeType {
+        return protocolFunctions.deserializeFn(shape, fnNameSuffix) { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(value: &[u8], mut builder: #{Builder}) -> Result<#{Builder}, #{Error}>",
+                "Builder" to builderSymbol,
+                *codegenScope,
+            ) {
+                // An empty body is equivalent to an empty map.
+                rust(
+                    """
+                    if value.is_empty() {
+                        return Ok(builder);
+                    }
+                    """,
+                )
+                rustTemplate("let decoder = &mut #{Decoder}::new(value);", *codegenScope)
+                deserializeStructInner(includedMembers)
+                expectEndOfInput()
+                rust("Ok(builder)")
+            }
+        }
+    }
+
+    override fun payloadParser(member: MemberShape): RuntimeType {
+        val shape = model.expectShape(member.target)
+        val returnSymbolToParse = returnSymbolToParse(shape)
+        check(shape is UnionShape || shape is StructureShape || shape is DocumentShape) {
+            "Payload parser should only be used on structure shapes, union shapes, and document shapes."
+        }
+        return protocolFunctions.deserializeFn(shape, fnNameSuffix = "payload") { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(value: &[u8]) -> Result<#{ReturnType}, #{Error}>",
+                *codegenScope,
+                "ReturnType" to returnSymbolToParse.symbol,
+            ) {
+                rustTemplate("let decoder = &mut #{Decoder}::new(value);", *codegenScope)
+                rust("let result =")
+                deserializeMember(member)
+                rust(";")
+                expectEndOfInput()
+                rust("Ok(result)")
+            }
+        }
+    }
+
+    override fun operationParser(operationShape: OperationShape): RuntimeType? {
+        // Don't generate an operation CBOR deserializer if there is no CBOR body.
+        val httpDocumentMembers = httpBindingResolver.responseMembers(operationShape, HttpLocation.DOCUMENT)
+        if (httpDocumentMembers.isEmpty()) {
+            return null
+        }
+        val outputShape = operationShape.outputShape(model)
+        return structureParser(operationShape, symbolProvider.symbolForBuilder(outputShape), httpDocumentMembers)
+    }
+
+    override fun errorParser(errorShape: StructureShape): RuntimeType? {
+        if (errorShape.members().isEmpty()) {
+            return null
+        }
+        return structureParser(
+            errorShape,
+            symbolProvider.symbolForBuilder(errorShape),
+            errorShape.members().toList(),
+            fnNameSuffix = "cbor_err",
+        )
+    }
+
+    override fun serverInputParser(operationShape: OperationShape): RuntimeType? {
+        val includedMembers = httpBindingResolver.requestMembers(operationShape, HttpLocation.DOCUMENT)
+        if (includedMembers.isEmpty()) {
+            return null
+        }
+        val inputShape = operationShape.inputShape(model)
+        return structureParser(operationShape, symbolProvider.symbolForBuilder(inputShape), includedMembers)
+    }
+
+    private fun RustWriter.expectEndOfInput() {
+        rustBlock("if decoder.position() != value.len()") {
+            rustTemplate("return Err(#{Error}::expected_end_of_stream(decoder.position()));", *codegenScope)
+        }
+    }
+
+    /**
+     * Renders a loop over the items of the collection or map that `decoder` is positioned at, whose length, as
+     * returned by `Decoder::list` or `Decoder::map`, is bound to `len`. [inner] is rendered once per item, and may
+     * `continue` to the next one.
+     */
+    private fun RustWriter.collectionLoop(inner: Writable) {
+        rust("let mut read = 0;")
+        rustBlock("while !decoder.at_end_of_collection(len, read)?") {
+            rust("read += 1;")
+            inner()
+        }
+    }
+
+    /**
+     * Deserializes the key-value pairs of the map `decoder` is positioned at into the members of `builder`.
+     * Unknown keys are skipped, and so are `null` values, which leave the members unset.
+     */
+    private fun RustWriter.deserializeStructInner(members: Collection<MemberShape>) {
+        rust("let len = decoder.map()?;")
+        collectionLoop {
+            withBlock("builder = match decoder.str()?.as_ref() {", "};") {
+                for (member in members) {
+                    withBlockTemplate(
+                        "${member.memberName.dq()} => #{set_optional}(builder, decoder, |builder, decoder| {",
+                        "})?,",
+                        *codegenScope,
+                    ) {
+                        val optional =
+                            codegenTarget == CodegenTarget.CLIENT || symbolProvider.toSymbol(member).isOptional()
+                        withBlock(
+                            "Ok(builder.${member.setterName()}(${if (optional) "Some(" else ""}",
+                            "${if (optional) ")" else ""}))",
+                        ) {
+                            deserializeMember(member)
+                        }
+                    }
+                }
+                rust(
+                    """
+                    _ => {
+                        decoder.skip()?;
+                        builder
+                    }
+                    """,
+                )
+            }
+        }
+    }
+
+    private fun RustWriter.deserializeMember(memberShape: MemberShape) {
+        val symbol = symbolProvider.toSymbol(memberShape)
+        if (symbol.isRustBoxed()) {
+            withBlock("Box::new(", ")") {
+                deserializeMemberValue(memberShape)
+                for (customization in customizations) {
+                    customization.section(CborParserSection.BeforeBoxingDeserializedMember(memberShape))(this)
+                }
+            }
+        } else {
+            deserializeMemberValue(memberShape)
+        }
+    }
+
+    private fun RustWriter.deserializeMemberValue(memberShape: MemberShape) {
+        when (val target = model.expectShape(memberShape.target)) {
+            is StringShape -> deserializeString(target)
+            is BooleanShape -> rust("decoder.boolean()?")
+            is ByteShape -> rust("decoder.byte()?")
+            is ShortShape -> rust("decoder.short()?")
+            is IntegerShape -> rust("decoder.integer()?")
+            is LongShape -> rust("decoder.long()?")
+            is FloatShape -> rust("decoder.float()?")
+            is DoubleShape -> rust("decoder.double()?")
+            is BlobShape -> rust("decoder.blob()?")
+            is TimestampShape -> rust("decoder.timestamp()?")
+            is DocumentShape -> rust("decoder.document()?")
+            is CollectionShape -> deserializeCollection(target)
+            is MapShape -> deserializeMap(target)
+            is StructureShape -> deserializeStruct(target)
+            is UnionShape -> deserializeUnion(target)
+            else -> PANIC("unexpected shape: $target")
+        }
+    }
+
+    private fun RustWriter.deserializeString(target: StringShape) {
+        when (target.hasTrait<EnumTrait>() && !returnSymbolToParse(target).isUnconstrained) {
+            true -> rust("#T::from(decoder.str()?.as_ref())", symbolProvider.toSymbol(target))
+            false -> rust("decoder.string()?")
+        }
+    }
+
+    /**
+     * Renders the value of an item of a collection or map, which is `None` for `null`s in sparse collections and
+     * maps. `null`s in dense ones are rejected by the server and skipped by the client.
+     */
+    private fun RustWriter.deserializeItem(
+        member: MemberShape,
+        isSparse: Boolean,
+        kind: String,
+    ) {
+        rustBlockTemplate("let value = match decoder.datatype()?", *codegenScope) {
+            rustBlockTemplate("#{Type}::Null =>", *codegenScope) {
+                if (isSparse) {
+                    rust("decoder.null()?;")
+                    rust("None")
+                } else {
+                    when (codegenTarget) {
+                        CodegenTarget.CLIENT -> rust("decoder.null()?; continue;")
+                        CodegenTarget.SERVER ->
+                            rustTemplate(
+                                """return Err(#{Error}::custom("dense $kind cannot contain null values", decoder.position()));""",
+                                *codegenScope,
+                            )
+                    }
+                }
+            }
+            withBlock("_ => ", ",") {
+                if (isSparse) {
+                    withBlock("Some(", ")") { deserializeMember(member) }
+                } else {
+                    deserializeMember(member)
+                }
+            }
+        }
+        rust(";")
+    }
+
+    private fun RustWriter.deserializeCollection(shape: CollectionShape) {
+        val isSparse = shape.hasTrait<SparseTrait>()
+        val (returnSymbol, returnUnconstrainedType) = returnSymbolToParse(shape)
+        val parser =
+            protocolFunctions.deserializeFn(shape) { fnName ->
+                rustBlockTemplate(
+                    "pub(crate) fn $fnName(decoder: &mut #{Decoder}<'_>) -> Result<#{ReturnType}, #{Error}>",
+                    "ReturnType" to returnSymbol,
+                    *codegenScope,
+                ) {
+                    rust("let len = decoder.list()?;")
+                    rust("let mut items = Vec::new();")
+                    collectionLoop {
+                        deserializeItem(shape.member, isSparse, "list")
+                        rust("items.push(value);")
+                    }
+                    if (returnUnconstrainedType) {
+                        rust("Ok(#{T}(items))", returnSymbol)
+                    } else {
+                        rust("Ok(items)")
+                    }
+                }
+            }
+        rust("#T(decoder)?", parser)
+    }
+
+    private fun RustWriter.deserializeMap(shape: MapShape) {
+        val keyTarget = model.expectShape(shape.key.target) as StringShape
+        val isSparse = shape.hasTrait<SparseTrait>()
+        val returnSymbolToParse = returnSymbolToParse(shape)
+        val parser =
+            protocolFunctions.deserializeFn(shape) { fnName ->
+                rustBlockTemplate(
+                    "pub(crate) fn $fnName(decoder: &mut #{Decoder}<'_>) -> Result<#{ReturnType}, #{Error}>",
+                    "ReturnType" to returnSymbolToParse.symbol,
+                    *codegenScope,
+                ) {
+                    rust("let len = decoder.map()?;")
+                    rust("let mut map = #T::new();", RuntimeType.HashMap)
+                    collectionLoop {
+                        withBlock("let key =", ";") {
+                            deserializeString(keyTarget)
+                        }
+                        deserializeItem(shape.value, isSparse, "map")
+                        rust("map.insert(key, value);")
+                    }
+                    if (returnSymbolToParse.isUnconstrained) {
+                        rust("Ok(#{T}(map))", returnSymbolToParse.symbol)
+                    } else {
+                        rust("Ok(map)")
+                    }
+                }
+            }
+        rust("#T(decoder)?", parser)
+    }
+
+    private fun RustWriter.deserializeStruct(shape: StructureShape) {
+        val returnSymbolToParse = returnSymbolToParse(shape)
+        val nestedParser =
+            protocolFunctions.deserializeFn(shape) { fnName ->
+                rustBlockTemplate(
+                    "pub(crate) fn $fnName(decoder: &mut #{Decoder}<'_>) -> Result<#{ReturnType}, #{Error}>",
+                    "ReturnType" to returnSymbolToParse.symbol,
+                    *codegenScope,
+                ) {
+                    Attribute.AllowUnusedMut.render(this)
+                    rustTemplate(
+                        "let mut builder = #{Builder}::default();",
+                        *codegenScope,
+                        "Builder" to symbolProvider.symbolForBuilder(shape),
+                    )
+                    deserializeStructInner(shape.members())
+                    val builder =
+                        builderInstantiator.finalizeBuilder(
+                            "builder", shape,
+                        ) {
+                            rustTemplate(
+                                """|err| #{Error}::custom(err.to_string(), decoder.position())""", *codegenScope,
+                            )
+                        }
+                    rust("Ok(#T)", builder)
+                }
+            }
+        rust("#T(decoder)?", nestedParser)
+    }
+
+    private fun RustWriter.deserializeUnion(shape: UnionShape) {
+        val returnSymbolToParse = returnSymbolToParse(shape)
+        val nestedParser =
+            protocolFunctions.deserializeFn(shape) { fnName ->
+                rustBlockTemplate(
+                    "pub(crate) fn $fnName(decoder: &mut #{Decoder}<'_>) -> Result<#{Shape}, #{Error}>",
+                    *codegenScope,
+                    "Shape" to returnSymbolToParse.symbol,
+                ) {
+                    rust("let len = decoder.map()?;")
+                    rust("let mut variant = None;")
+                    collectionLoop {
+                        rustTemplate(
+                            """
+                            let position = decoder.position();
+                            let key = decoder.str()?;
+                            if key == "__type" || decoder.datatype()? == #{Type}::Null {
+                                decoder.skip()?;
+                                continue;
+                            }
+                            if variant.is_some() {
+                                return Err(#{Error}::mixed_union_variants(position));
+                            }
+                            """,
+                            *codegenScope,
+                        )
+                        withBlock("variant = Some(match key.as_ref() {", "});") {
+                            for (member in shape.members()) {
+                                val variantName = symbolProvider.toMemberName(member)
+                                if (member.isTargetUnit()) {
+                                    rustBlock("${member.memberName.dq()} =>") {
+                                        rust("decoder.skip()?;")
+                                        rust("#T::$variantName", returnSymbolToParse.symbol)
+                                    }
+                                } else {
+                                    withBlock(
+                                        "${member.memberName.dq()} => #T::$variantName(",
+                                        "),",
+                                        returnSymbolToParse.symbol,
+                                    ) {
+                                        deserializeMember(member)
+                                    }
+                                }
+                            }
+                            when (codegenTarget.renderUnknownVariant()) {
+                                // In client mode, resolve an unknown union variant to the unknown variant.
+                                true ->
+                                    rustTemplate(
+                                        """
+                                        _ => {
+                                            decoder.skip()?;
+                                            #{Union}::${UnionGenerator.UnknownVariantName}
+                                        }
+                                        """,
+                                        "Union" to returnSymbolToParse.symbol,
+                                        *codegenScope,
+                                    )
+                                // In server mode, use strict parsing.
+                                // Consultation: https://github.com/awslabs/smithy/issues/1222
+                                false ->
+                                    rustTemplate(
+                                        "variant => return Err(#{Error}::unknown_union_variant(variant, position)),",
+                                        *codegenScope,
+                                    )
+                            }
+                        }
+                    }
+                    rustTemplate(
+                        """
+                        variant.ok_or_else(|| #{Error}::custom("union did not contain a valid variant", decoder.position()))
+                        """,
+                        *codegenScope,
+                    )
+                }
+            }
+        rust("#T(decoder)?", nestedParser)
+    }
+}
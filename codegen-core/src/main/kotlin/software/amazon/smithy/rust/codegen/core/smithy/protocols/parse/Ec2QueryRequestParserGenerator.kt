@@ -0,0 +1,37 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.protocols.parse
+
+import software.amazon.smithy.aws.traits.protocols.Ec2QueryNameTrait
+import software.amazon.smithy.model.shapes.MemberShape
+import software.amazon.smithy.model.shapes.Shape
+import software.amazon.smithy.model.traits.XmlNameTrait
+import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpBindingResolver
+import software.amazon.smithy.rust.codegen.core.util.getTrait
+import software.amazon.smithy.utils.StringUtils
+
+/**
+ * Parses `ec2Query` requests. Members are named and flattened like
+ * [software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize.Ec2QuerySerializerGenerator] does.
+ */
+class Ec2QueryRequestParserGenerator(
+    codegenContext: CodegenContext,
+    httpBindingResolver: HttpBindingResolver,
+    returnSymbolToParse: (Shape) -> ReturnSymbolToParse = { shape ->
+        ReturnSymbolToParse(codegenContext.symbolProvider.toSymbol(shape), false)
+    },
+    customizations: List<QueryParserCustomization> = listOf(),
+) : QueryParserGenerator(codegenContext, httpBindingResolver, returnSymbolToParse, customizations) {
+    override val protocolName: String get() = "EC2 Query"
+
+    override fun MemberShape.queryKeyName(prioritizedFallback: String?): String =
+        getTrait<Ec2QueryNameTrait>()?.value
+            ?: getTrait<XmlNameTrait>()?.value?.let { StringUtils.capitalize(it) }
+            ?: StringUtils.capitalize(memberName)
+
+    override fun MemberShape.isFlattened(): Boolean = true
+}
@@ -33,6 +33,7 @@ import software.amazon.smithy.rust.codegen.core.rustlang.rustBlockTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.withBlock
 import software.amazon.smithy.rust.codegen.core.rustlang.withBlockTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
 import software.amazon.smithy.rust.codegen.core.smithy.CodegenTarget
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
@@ -107,6 +108,7 @@ class JsonParserGenerator(
     private val builderInstantiator = codegenContext.builderInstantiator()
     private val codegenScope =
         arrayOf(
+            "ByteStream" to RuntimeType.byteStream(runtimeConfig),
             "Error" to smithyJson.resolve("deserialize::error::DeserializeError"),
             "expect_blob_or_null" to smithyJson.resolve("deserialize::token::expect_blob_or_null"),
             "expect_bool_or_null" to smithyJson.resolve("deserialize::token::expect_bool_or_null"),
@@ -117,6 +119,7 @@ class JsonParserGenerator(
             "expect_string_or_null" to smithyJson.resolve("deserialize::token::expect_string_or_null"),
             "expect_timestamp_or_null" to smithyJson.resolve("deserialize::token::expect_timestamp_or_null"),
             "json_token_iter" to smithyJson.resolve("deserialize::json_token_iter"),
+            "JsonTokenReader" to smithyJson.resolve("deserialize::incremental::JsonTokenReader"),
             "Peekable" to RuntimeType.std.resolve("iter::Peekable"),
             "skip_value" to smithyJson.resolve("deserialize::token::skip_value"),
             "skip_to_end" to smithyJson.resolve("deserialize::token::skip_to_end"),
@@ -204,6 +207,127 @@ class JsonParserGenerator(
         return structureParser(operationShape, symbolProvider.symbolForBuilder(outputShape), httpDocumentMembers)
     }
 
+    /**
+     * Generate an async parser for operation output that reads the document from a `ByteStream` as it arrives,
+     * rather than from a fully buffered body. The elements of top-level list members are parsed one at a time, and
+     * other members are only buffered while they are parsed:
+     *
+     * ```rust
+     * async fn parse_some_operation_incremental(stream: &mut ByteStream, builder: my_operation::Builder) -> Result<my_operation::Builder, DeserializeError> {
+     *   ...
+     * }
+     * ```
+     *
+     * Only clients parse operation output, so nothing is generated for servers. Outputs without a top-level list
+     * member gain nothing from this, so they are only parsed from the buffered body.
+     */
+    override fun incrementalOperationParser(operationShape: OperationShape): RuntimeType? {
+        val httpDocumentMembers = httpBindingResolver.responseMembers(operationShape, HttpLocation.DOCUMENT)
+        if (codegenTarget != CodegenTarget.CLIENT ||
+            httpDocumentMembers.none { model.expectShape(it.target) is CollectionShape }
+        ) {
+            return null
+        }
+        val outputShape = operationShape.outputShape(model)
+        return protocolFunctions.deserializeFn(operationShape, fnNameSuffix = "incremental") { fnName ->
+            rustTemplate(
+                """
+                pub(crate) async fn $fnName(stream: &mut #{ByteStream}, mut builder: #{Builder}) -> Result<#{Builder}, #{Error}> {
+                    let mut reader = #{JsonTokenReader}::new();
+                    match reader.next_token_from(stream).await? {
+                        // An empty body is an empty object, as it is for the buffered parser.
+                        None => return Ok(builder),
+                        Some(#{Token}::StartObject { .. }) => {}
+                        other => return Err(#{Error}::custom(format!("expected start object, found: {:?}", other))),
+                    }
+                    loop {
+                        let key = match reader.next_token_from(stream).await? {
+                            Some(#{Token}::EndObject { .. }) => break,
+                            Some(#{Token}::ObjectKey { key, .. }) => key.to_unescaped()?.into_owned(),
+                            other => return Err(#{Error}::custom(format!("expected object key or end object, found: {:?}", other))),
+                        };
+                        match key.as_str() {
+                            #{members}
+                            _ => {
+                                #{next_value};
+                            }
+                        }
+                    }
+                    if reader.next_token_from(stream).await?.is_some() {
+                        return Err(#{Error}::custom("found more JSON tokens after completing parsing"));
+                    }
+                    Ok(builder)
+                }
+                """,
+                "Builder" to symbolProvider.symbolForBuilder(outputShape),
+                "members" to
+                    writable {
+                        for (member in httpDocumentMembers) {
+                            rustBlock("${jsonName(member).dq()} =>") {
+                                when (val target = model.expectShape(member.target)) {
+                                    is CollectionShape -> deserializeCollectionIncrementally(member, target)
+                                    else -> {
+                                        rustTemplate(
+                                            """
+                                            let value = #{next_value};
+                                            let mut tokens_owned = #{json_token_iter}(value).peekable();
+                                            let tokens = &mut tokens_owned;
+                                            """,
+                                            "next_value" to nextValue(),
+                                            *codegenScope,
+                                        )
+                                        deserializeMemberIntoBuilder(member)
+                                    }
+                                }
+                            }
+                        }
+                    },
+                "next_value" to nextValue(),
+                *codegenScope,
+            )
+        }
+    }
+
+    /** Reads the next value from `reader`, which must follow an object key. */
+    private fun nextValue() =
+        writable {
+            rustTemplate(
+                """
+                reader
+                    .next_value_from(stream)
+                    .await?
+                    .ok_or_else(|| #{Error}::custom("expected a value after the object key"))?
+                """,
+                *codegenScope,
+            )
+        }
+
+    /** Sets a top-level list member, reading its elements from `reader` one at a time. */
+    private fun RustWriter.deserializeCollectionIncrementally(
+        member: MemberShape,
+        shape: CollectionShape,
+    ) {
+        rustTemplate(
+            """
+            match reader.next_token_from(stream).await? {
+                Some(#{Token}::ValueNull { .. }) => builder = builder.${member.setterName()}(None),
+                Some(#{Token}::StartArray { .. }) => {
+                    let mut items = Vec::new();
+                    while let Some(value) = reader.next_value_from(stream).await? {
+                        let mut tokens_owned = #{json_token_iter}(value).peekable();
+                        let tokens = &mut tokens_owned;
+                        #{item}
+                    }
+                    builder = builder.${member.setterName()}(Some(items));
+                }
+                _ => return Err(#{Error}::custom("expected start array or null")),
+            }
+            """,
+            "item" to writable { deserializeCollectionItem(shape) },
+            *codegenScope,
+        )
+    }
+
     override fun errorParser(errorShape: StructureShape): RuntimeType? {
         if (errorShape.members().isEmpty()) {
             return null
@@ -254,31 +378,7 @@ class JsonParserGenerator(
             rustBlock("match key.to_unescaped()?.as_ref()") {
                 for (member in members) {
                     rustBlock("${jsonName(member).dq()} =>") {
-                        when (codegenTarget) {
-                            CodegenTarget.CLIENT -> {
-                                withBlock("builder = builder.${member.setterName()}(", ");") {
-                                    deserializeMember(member)
-                                }
-                            }
-
-                            CodegenTarget.SERVER -> {
-                                if (symbolProvider.toSymbol(member).isOptional()) {
-                                    withBlock("builder = builder.${member.setterName()}(", ");") {
-                                        deserializeMember(member)
-                                    }
-                                } else {
-                                    rust("if let Some(v) = ")
-                                    deserializeMember(member)
-                                    rust(
-                                        """
-                                        {
-                                            builder = builder.${member.setterName()}(v);
-                                        }
-                                        """,
-                                    )
-                                }
-                            }
-                        }
+                        deserializeMemberIntoBuilder(member)
                     }
                 }
                 rustTemplate("_ => #{skip_value}(tokens)?", *codegenScope)
@@ -286,6 +386,34 @@ class JsonParserGenerator(
         }
     }
 
+    private fun RustWriter.deserializeMemberIntoBuilder(member: MemberShape) {
+        when (codegenTarget) {
+            CodegenTarget.CLIENT -> {
+                withBlock("builder = builder.${member.setterName()}(", ");") {
+                    deserializeMember(member)
+                }
+            }
+
+            CodegenTarget.SERVER -> {
+                if (symbolProvider.toSymbol(member).isOptional()) {
+                    withBlock("builder = builder.${member.setterName()}(", ");") {
+                        deserializeMember(member)
+                    }
+                } else {
+                    rust("if let Some(v) = ")
+                    deserializeMember(member)
+                    rust(
+                        """
+                        {
+                            builder = builder.${member.setterName()}(v);
+                        }
+                        """,
+                    )
+                }
+            }
+        }
+    }
+
     private fun RustWriter.deserializeMember(memberShape: MemberShape) {
         when (val target = model.expectShape(memberShape.target)) {
             is StringShape -> deserializeString(target)
@@ -386,7 +514,6 @@ class JsonParserGenerator(
     }
 
     private fun RustWriter.deserializeCollection(shape: CollectionShape) {
-        val isSparse = shape.hasTrait<SparseTrait>()
         val (returnSymbol, returnUnconstrainedType) = returnSymbolToParse(shape)
         val parser =
             protocolFunctions.deserializeFn(shape) { fnName ->
@@ -406,32 +533,7 @@ class JsonParserGenerator(
                                     rust("tokens.next().transpose().unwrap(); break;")
                                 }
                                 rustBlock("_ => ") {
-                                    if (isSparse) {
-                                        withBlock("items.push(", ");") {
-                                            deserializeMember(shape.member)
-                                        }
-                                    } else {
-                                        withBlock("let value =", ";") {
-                                            deserializeMember(shape.member)
-                                        }
-                                        rust(
-                                            """
-                                            if let Some(value) = value {
-                                                items.push(value);
-                                            }
-                                            """,
-                                        )
-                                        codegenTarget.ifServer {
-                                            rustTemplate(
-                                                """
-                                                else {
-                                                    return Err(#{Error}::custom("dense list cannot contain null values"));
-                                                }
-                                                """,
-                                                *codegenScope,
-                                            )
-                                        }
-                                    }
+                                    deserializeCollectionItem(shape)
                                 }
                             }
                         }
@@ -446,6 +548,36 @@ class JsonParserGenerator(
         rust("#T(tokens)?", parser)
     }
 
+    /** Pushes the next element of a list of `shape` onto `items`. */
+    private fun RustWriter.deserializeCollectionItem(shape: CollectionShape) {
+        if (shape.hasTrait<SparseTrait>()) {
+            withBlock("items.push(", ");") {
+                deserializeMember(shape.member)
+            }
+        } else {
+            withBlock("let value =", ";") {
+                deserializeMember(shape.member)
+            }
+            rust(
+                """
+                if let Some(value) = value {
+                    items.push(value);
+                }
+                """,
+            )
+            codegenTarget.ifServer {
+                rustTemplate(
+                    """
+                    else {
+                        return Err(#{Error}::custom("dense list cannot contain null values"));
+                    }
+                    """,
+                    *codegenScope,
+                )
+            }
+        }
+    }
+
     private fun RustWriter.deserializeMap(shape: MapShape) {
         val keyTarget = model.expectShape(shape.key.target) as StringShape
         val isSparse = shape.hasTrait<SparseTrait>()
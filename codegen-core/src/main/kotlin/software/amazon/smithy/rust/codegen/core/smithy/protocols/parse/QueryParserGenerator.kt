@@ -0,0 +1,359 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.protocols.parse
+
+import software.amazon.smithy.model.shapes.BlobShape
+import software.amazon.smithy.model.shapes.BooleanShape
+import software.amazon.smithy.model.shapes.ByteShape
+import software.amazon.smithy.model.shapes.CollectionShape
+import software.amazon.smithy.model.shapes.DoubleShape
+import software.amazon.smithy.model.shapes.FloatShape
+import software.amazon.smithy.model.shapes.IntegerShape
+import software.amazon.smithy.model.shapes.LongShape
+import software.amazon.smithy.model.shapes.MapShape
+import software.amazon.smithy.model.shapes.MemberShape
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.Shape
+import software.amazon.smithy.model.shapes.ShortShape
+import software.amazon.smithy.model.shapes.StringShape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.model.shapes.TimestampShape
+import software.amazon.smithy.model.shapes.UnionShape
+import software.amazon.smithy.model.traits.EnumTrait
+import software.amazon.smithy.model.traits.SparseTrait
+import software.amazon.smithy.model.traits.TimestampFormatTrait
+import software.amazon.smithy.model.traits.XmlNameTrait
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustBlock
+import software.amazon.smithy.rust.codegen.core.rustlang.rustBlockTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.withBlock
+import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.core.smithy.CodegenTarget
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.smithy.customize.NamedCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.customize.Section
+import software.amazon.smithy.rust.codegen.core.smithy.generators.setterName
+import software.amazon.smithy.rust.codegen.core.smithy.isOptional
+import software.amazon.smithy.rust.codegen.core.smithy.isRustBoxed
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpBindingResolver
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpLocation
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.ProtocolFunctions
+import software.amazon.smithy.rust.codegen.core.util.PANIC
+import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.getTrait
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
+import software.amazon.smithy.rust.codegen.core.util.inputShape
+import software.amazon.smithy.rust.codegen.core.util.isTargetUnit
+
+/**
+ * Class describing a query parser section that can be used in a customization.
+ */
+sealed class QueryParserSection(name: String) : Section(name) {
+    data class BeforeBoxingDeserializedMember(val shape: MemberShape) :
+        QueryParserSection("BeforeBoxingDeserializedMember")
+}
+
+/**
+ * Customization for the query parser.
+ */
+typealias QueryParserCustomization = NamedCustomization<QueryParserSection>
+
+/**
+ * Parses the form-urlencoded bodies of `awsQuery` and `ec2Query` requests into the operation input.
+ *
+ * This is the inverse of [software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize.QuerySerializerGenerator],
+ * and subclasses must name and flatten members the same way its subclasses do. Responses of these protocols are
+ * XML, so only [serverInputParser] is supported.
+ */
+abstract class QueryParserGenerator(
+    private val codegenContext: CodegenContext,
+    private val httpBindingResolver: HttpBindingResolver,
+    /**
+     * Whether we should parse a value for a shape into its associated unconstrained type. See
+     * [CborParserGenerator] for details.
+     */
+    private val returnSymbolToParse: (Shape) -> ReturnSymbolToParse = { shape ->
+        ReturnSymbolToParse(codegenContext.symbolProvider.toSymbol(shape), false)
+    },
+    private val customizations: List<QueryParserCustomization> = listOf(),
+) : StructuredDataParserGenerator {
+    protected val model = codegenContext.model
+    private val symbolProvider = codegenContext.symbolProvider
+    private val runtimeConfig = codegenContext.runtimeConfig
+    private val codegenTarget = codegenContext.target
+    private val smithyQuery = RuntimeType.smithyQuery(runtimeConfig)
+    private val protocolFunctions = ProtocolFunctions(codegenContext)
+    private val builderInstantiator = codegenContext.builderInstantiator()
+    private val codegenScope =
+        arrayOf(
+            "Error" to smithyQuery.resolve("deserialize::DeserializeError"),
+            "QueryDocument" to smithyQuery.resolve("deserialize::QueryDocument"),
+            "QueryValue" to smithyQuery.resolve("deserialize::QueryValue"),
+            *preludeScope,
+        )
+
+    abstract val protocolName: String
+
+    abstract fun MemberShape.queryKeyName(prioritizedFallback: String? = null): String
+
+    abstract fun MemberShape.isFlattened(): Boolean
+
+    override fun payloadParser(member: MemberShape): RuntimeType {
+        TODO("$protocolName doesn't support payload deserialization")
+    }
+
+    override fun operationParser(operationShape: OperationShape): RuntimeType? {
+        TODO("$protocolName responses are deserialized as XML")
+    }
+
+    override fun errorParser(errorShape: StructureShape): RuntimeType? {
+        TODO("$protocolName errors are deserialized as XML")
+    }
+
+    override fun serverInputParser(operationShape: OperationShape): RuntimeType? {
+        val includedMembers = httpBindingResolver.requestMembers(operationShape, HttpLocation.DOCUMENT)
+        if (includedMembers.isEmpty()) {
+            return null
+        }
+        val inputShape = operationShape.inputShape(model)
+        return protocolFunctions.deserializeFn(operationShape) { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(value: &[u8], mut builder: #{Builder}) -> #{Result}<#{Builder}, #{Error}>",
+                "Builder" to symbolProvider.symbolForBuilder(inputShape),
+                *codegenScope,
+            ) {
+                // The `Action` and `Version` parameters are part of the document, but aren't members of the input.
+                rustTemplate(
+                    """
+                    let document = #{QueryDocument}::parse(value)?;
+                    let value = document.root();
+                    """,
+                    *codegenScope,
+                )
+                deserializeStructInner(includedMembers)
+                rust("Ok(builder)")
+            }
+        }
+    }
+
+    /**
+     * Sets the members of `builder` that are nested under `value`. Members with no parameter are left unset.
+     */
+    private fun RustWriter.deserializeStructInner(members: Collection<MemberShape>) {
+        for (member in members) {
+            rustBlock("if let Some(value) = value.get(${member.queryKeyName().dq()})") {
+                val optional = codegenTarget == CodegenTarget.CLIENT || symbolProvider.toSymbol(member).isOptional()
+                withBlock(
+                    "builder = builder.${member.setterName()}(${if (optional) "Some(" else ""}",
+                    "${if (optional) ")" else ""});",
+                ) {
+                    deserializeMember(member)
+                }
+            }
+        }
+    }
+
+    private fun RustWriter.deserializeMember(memberShape: MemberShape) {
+        val symbol = symbolProvider.toSymbol(memberShape)
+        if (symbol.isRustBoxed()) {
+            withBlock("Box::new(", ")") {
+                deserializeMemberValue(memberShape)
+                for (customization in customizations) {
+                    customization.section(QueryParserSection.BeforeBoxingDeserializedMember(memberShape))(this)
+                }
+            }
+        } else {
+            deserializeMemberValue(memberShape)
+        }
+    }
+
+    private fun RustWriter.deserializeMemberValue(memberShape: MemberShape) {
+        when (val target = model.expectShape(memberShape.target)) {
+            is StringShape -> deserializeString(target, "value.string()?")
+            is BooleanShape -> rust("value.primitive::<bool>()?")
+            is ByteShape -> rust("value.primitive::<i8>()?")
+            is ShortShape -> rust("value.primitive::<i16>()?")
+            is IntegerShape -> rust("value.primitive::<i32>()?")
+            is LongShape -> rust("value.primitive::<i64>()?")
+            is FloatShape -> rust("value.primitive::<f32>()?")
+            is DoubleShape -> rust("value.primitive::<f64>()?")
+            is BlobShape -> rust("value.blob()?")
+            is TimestampShape -> {
+                val timestampFormat =
+                    httpBindingResolver.timestampFormat(
+                        memberShape,
+                        HttpLocation.DOCUMENT,
+                        TimestampFormatTrait.Format.DATE_TIME,
+                        model,
+                    )
+                val timestampFormatType =
+                    RuntimeType.parseTimestampFormat(codegenTarget, runtimeConfig, timestampFormat)
+                rust("value.date_time(#T)?", timestampFormatType)
+            }
+            is CollectionShape -> rust("#T(value, ${memberShape.isFlattened()})?", collectionParser(target))
+            is MapShape -> rust("#T(value, ${memberShape.isFlattened()})?", mapParser(target))
+            is StructureShape -> rust("#T(value)?", structParser(target))
+            is UnionShape -> rust("#T(value)?", unionParser(target))
+            else -> PANIC("$protocolName doesn't support deserializing $target")
+        }
+    }
+
+    private fun RustWriter.deserializeString(
+        target: StringShape,
+        str: String,
+    ) {
+        when (target.hasTrait<EnumTrait>() && !returnSymbolToParse(target).isUnconstrained) {
+            true -> rust("#T::from($str)", symbolProvider.toSymbol(target))
+            false -> rust("$str.to_owned()")
+        }
+    }
+
+    /**
+     * Renders an item of a list or a map. Query documents have no `null`s, so the items of sparse collections are
+     * always `Some`.
+     */
+    private fun RustWriter.deserializeItem(
+        member: MemberShape,
+        isSparse: Boolean,
+    ) {
+        if (isSparse) {
+            withBlock("Some(", ")") { deserializeMember(member) }
+        } else {
+            deserializeMember(member)
+        }
+    }
+
+    private fun collectionParser(shape: CollectionShape): RuntimeType {
+        val (returnSymbol, returnUnconstrainedType) = returnSymbolToParse(shape)
+        val memberOverride =
+            when (val override = shape.member.getTrait<XmlNameTrait>()?.value) {
+                null -> "None"
+                else -> "Some(${override.dq()})"
+            }
+        return protocolFunctions.deserializeFn(shape) { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(value: #{QueryValue}<'_>, flat: bool) -> #{Result}<#{ReturnType}, #{Error}>",
+                "ReturnType" to returnSymbol,
+                *codegenScope,
+            ) {
+                rust("let mut items = Vec::new();")
+                rustBlock("for value in value.list(flat, $memberOverride)?") {
+                    withBlock("items.push(", ");") {
+                        deserializeItem(shape.member, shape.hasTrait<SparseTrait>())
+                    }
+                }
+                if (returnUnconstrainedType) {
+                    rust("Ok(#{T}(items))", returnSymbol)
+                } else {
+                    rust("Ok(items)")
+                }
+            }
+        }
+    }
+
+    private fun mapParser(shape: MapShape): RuntimeType {
+        val keyTarget = model.expectShape(shape.key.target) as StringShape
+        val returnSymbolToParse = returnSymbolToParse(shape)
+        val keyName = shape.key.queryKeyName("key").dq()
+        val valueName = shape.value.queryKeyName("value").dq()
+        return protocolFunctions.deserializeFn(shape) { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(value: #{QueryValue}<'_>, flat: bool) -> #{Result}<#{ReturnType}, #{Error}>",
+                "ReturnType" to returnSymbolToParse.symbol,
+                *codegenScope,
+            ) {
+                rust("let mut map = #T::new();", RuntimeType.HashMap)
+                rustBlock("for (key, value) in value.map(flat, $keyName, $valueName)?") {
+                    withBlock("let key =", ";") {
+                        deserializeString(keyTarget, "key")
+                    }
+                    withBlock("map.insert(key, ", ");") {
+                        deserializeItem(shape.value, shape.hasTrait<SparseTrait>())
+                    }
+                }
+                if (returnSymbolToParse.isUnconstrained) {
+                    rust("Ok(#{T}(map))", returnSymbolToParse.symbol)
+                } else {
+                    rust("Ok(map)")
+                }
+            }
+        }
+    }
+
+    private fun structParser(shape: StructureShape): RuntimeType {
+        val returnSymbolToParse = returnSymbolToParse(shape)
+        return protocolFunctions.deserializeFn(shape) { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(value: #{QueryValue}<'_>) -> #{Result}<#{ReturnType}, #{Error}>",
+                "ReturnType" to returnSymbolToParse.symbol,
+                *codegenScope,
+            ) {
+                if (shape.members().isEmpty()) {
+                    rust("let _ = value;")
+                }
+                Attribute.AllowUnusedMut.render(this)
+                rustTemplate(
+                    "let mut builder = #{Builder}::default();",
+                    "Builder" to symbolProvider.symbolForBuilder(shape),
+                )
+                deserializeStructInner(shape.members())
+                val builder =
+                    builderInstantiator.finalizeBuilder("builder", shape) {
+                        rustTemplate("""|err| #{Error}::custom_source("failed to build structure", err)""", *codegenScope)
+                    }
+                rust("Ok(#T)", builder)
+            }
+        }
+    }
+
+    private fun unionParser(shape: UnionShape): RuntimeType {
+        val returnSymbolToParse = returnSymbolToParse(shape)
+        return protocolFunctions.deserializeFn(shape) { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(value: #{QueryValue}<'_>) -> #{Result}<#{Shape}, #{Error}>",
+                "Shape" to returnSymbolToParse.symbol,
+                *codegenScope,
+            ) {
+                rust("let mut variant = None;")
+                for (member in shape.members()) {
+                    val variantName = symbolProvider.toMemberName(member)
+                    val keyName = member.queryKeyName().dq()
+                    val condition =
+                        if (member.isTargetUnit()) {
+                            "value.get($keyName).is_some()"
+                        } else {
+                            "let Some(value) = value.get($keyName)"
+                        }
+                    rustBlock("if $condition") {
+                        rustTemplate(
+                            """
+                            if variant.is_some() {
+                                return Err(#{Error}::custom("union `${shape.id.name}` has more than one variant set"));
+                            }
+                            """,
+                            *codegenScope,
+                        )
+                        if (member.isTargetUnit()) {
+                            rust("variant = Some(#T::$variantName);", returnSymbolToParse.symbol)
+                        } else {
+                            withBlock("variant = Some(#T::$variantName(", "));", returnSymbolToParse.symbol) {
+                                deserializeMember(member)
+                            }
+                        }
+                    }
+                }
+                rustTemplate(
+                    """variant.ok_or_else(|| #{Error}::custom("union `${shape.id.name}` did not contain a valid variant"))""",
+                    *codegenScope,
+                )
+            }
+        }
+    }
+}
@@ -36,6 +36,19 @@ interface StructuredDataParserGenerator {
      */
     fun operationParser(operationShape: OperationShape): RuntimeType?
 
+    /**
+     * Generate an async parser for operation output that reads the document from a `ByteStream` as it arrives,
+     * for protocols that support it. Returns `null` when the output should be parsed from the buffered body with
+     * [operationParser] instead:
+     *
+     * ```rust
+     * async fn parse_some_operation_incremental(stream: &mut ByteStream, builder: my_operation::Builder) -> Result<my_operation::Builder, Error> {
+     *   ...
+     * }
+     * ```
+     */
+    fun incrementalOperationParser(operationShape: OperationShape): RuntimeType? = null
+
     /**
      * Because only a subset of fields of the operation may be impacted by the document, a builder is passed
      * through:
@@ -0,0 +1,520 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize
+
+import software.amazon.smithy.model.shapes.BlobShape
+import software.amazon.smithy.model.shapes.BooleanShape
+import software.amazon.smithy.model.shapes.ByteShape
+import software.amazon.smithy.model.shapes.CollectionShape
+import software.amazon.smithy.model.shapes.DocumentShape
+import software.amazon.smithy.model.shapes.DoubleShape
+import software.amazon.smithy.model.shapes.FloatShape
+import software.amazon.smithy.model.shapes.IntegerShape
+import software.amazon.smithy.model.shapes.LongShape
+import software.amazon.smithy.model.shapes.MapShape
+import software.amazon.smithy.model.shapes.MemberShape
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.Shape
+import software.amazon.smithy.model.shapes.ShapeId
+import software.amazon.smithy.model.shapes.ShortShape
+import software.amazon.smithy.model.shapes.StringShape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.model.shapes.TimestampShape
+import software.amazon.smithy.model.shapes.UnionShape
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustBlock
+import software.amazon.smithy.rust.codegen.core.rustlang.rustBlockTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.withBlock
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.smithy.RustSymbolProvider
+import software.amazon.smithy.rust.codegen.core.smithy.customize.NamedCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.customize.Section
+import software.amazon.smithy.rust.codegen.core.smithy.generators.UnionGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.generators.renderUnknownVariant
+import software.amazon.smithy.rust.codegen.core.smithy.generators.serializationError
+import software.amazon.smithy.rust.codegen.core.smithy.isOptional
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpBindingResolver
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpLocation
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.ProtocolFunctions
+import software.amazon.smithy.rust.codegen.core.smithy.traits.SyntheticInputTrait
+import software.amazon.smithy.rust.codegen.core.smithy.traits.SyntheticOutputTrait
+import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.expectTrait
+import software.amazon.smithy.rust.codegen.core.util.inputShape
+import software.amazon.smithy.rust.codegen.core.util.isTargetUnit
+import software.amazon.smithy.rust.codegen.core.util.outputShape
+
+/**
+ * Class describing a CBOR serializer section that can be used in a customization.
+ */
+sealed class CborSerializerSection(name: String) : Section(name) {
+    /** Mutate the server error map prior to finalization. Eg: this can be used to inject `__type` to record the error type. */
+    data class ServerError(val structureShape: StructureShape, val encoderBindingName: String) :
+        CborSerializerSection("ServerError")
+
+    /** Manipulate the serializer context for a map prior to it being serialized. **/
+    data class BeforeIteratingOverMapOrCollection(val shape: Shape, val context: CborSerializerGenerator.Context<Shape>) :
+        CborSerializerSection("BeforeIteratingOverMapOrCollection")
+
+    /** Manipulate the serializer context for a non-null member prior to it being serialized. **/
+    data class BeforeSerializingNonNullMember(val shape: Shape, val context: CborSerializerGenerator.MemberContext) :
+        CborSerializerSection("BeforeSerializingNonNullMember")
+
+    /** Mutate the input map prior to finalization. */
+    data class InputStruct(val structureShape: StructureShape, val encoderBindingName: String) :
+        CborSerializerSection("InputStruct")
+
+    /** Mutate the output map prior to finalization. */
+    data class OutputStruct(val structureShape: StructureShape, val encoderBindingName: String) :
+        CborSerializerSection("OutputStruct")
+}
+
+/**
+ * Customization for the CBOR serializer.
+ */
+typealias CborSerializerCustomization = NamedCustomization<CborSerializerSection>
+
+/**
+ * Serializes shapes into CBOR, as required by the Smithy RPC v2 CBOR protocol.
+ *
+ * Structures are written as indefinite-length maps keyed by member name, so that unset optional members can be
+ * skipped without counting the set ones first. Collections and maps are written with a definite length.
+ */
+class CborSerializerGenerator(
+    codegenContext: CodegenContext,
+    private val httpBindingResolver: HttpBindingResolver,
+    private val customizations: List<CborSerializerCustomization> = listOf(),
+) : StructuredDataSerializerGenerator {
+    data class Context<out T : Shape>(
+        /** Expression representing the value to write to the encoder */
+        var valueExpression: ValueExpression,
+        val shape: T,
+    )
+
+    data class MemberContext(
+        /** Expression representing the map key to write before the value, if the member has one */
+        val keyExpression: String?,
+        /** Expression representing the value to write to the encoder */
+        var valueExpression: ValueExpression,
+        val shape: MemberShape,
+        /** Whether to serialize null values if the type is optional */
+        val writeNulls: Boolean = false,
+    ) {
+        companion object {
+            fun collectionMember(
+                context: Context<CollectionShape>,
+                itemName: String,
+            ): MemberContext =
+                MemberContext(
+                    null,
+                    ValueExpression.Reference(itemName),
+                    context.shape.member,
+                    writeNulls = true,
+                )
+
+            fun mapMember(
+                context: Context<MapShape>,
+                key: String,
+                value: String,
+            ): MemberContext =
+                MemberContext(
+                    key,
+                    ValueExpression.Reference(value),
+                    context.shape.value,
+                    writeNulls = true,
+                )
+
+            fun structMember(
+                context: StructContext,
+                member: MemberShape,
+                symProvider: RustSymbolProvider,
+            ): MemberContext =
+                MemberContext(
+                    member.memberName.dq(),
+                    ValueExpression.Value("${context.localName}.${symProvider.toMemberName(member)}"),
+                    member,
+                )
+
+            fun unionMember(
+                variantReference: String,
+                member: MemberShape,
+            ): MemberContext =
+                MemberContext(
+                    member.memberName.dq(),
+                    ValueExpression.Reference(variantReference),
+                    member,
+                )
+        }
+    }
+
+    data class StructContext(
+        /** Name of the variable that holds the struct */
+        val localName: String,
+        val shape: StructureShape,
+    )
+
+    private val model = codegenContext.model
+    private val symbolProvider = codegenContext.symbolProvider
+    private val codegenTarget = codegenContext.target
+    private val runtimeConfig = codegenContext.runtimeConfig
+    private val protocolFunctions = ProtocolFunctions(codegenContext)
+    private val codegenScope =
+        arrayOf(
+            *preludeScope,
+            "Error" to runtimeConfig.serializationError(),
+            "Encoder" to RuntimeType.smithyCbor(runtimeConfig).resolve("Encoder"),
+            "SdkBody" to RuntimeType.sdkBody(runtimeConfig),
+            "ByteSlab" to RuntimeType.ByteSlab,
+        )
+    private val serializerUtil = SerializerUtil(model, symbolProvider)
+
+    /**
+     * Reusable structure serializer implementation that can be used to generate serializing code for
+     * operation outputs or errors.
+     * This function is only used by the server, the client uses directly [serializeStructure].
+     */
+    private fun serverSerializer(
+        structureShape: StructureShape,
+        includedMembers: List<MemberShape>,
+        makeSection: (StructureShape, String) -> CborSerializerSection,
+        error: Boolean,
+    ): RuntimeType {
+        val suffix =
+            when (error) {
+                true -> "error"
+                else -> "output"
+            }
+        return protocolFunctions.serializeFn(structureShape, fnNameSuffix = suffix) { fnName ->
+            rustBlockTemplate(
+                "pub fn $fnName(value: &#{target}) -> Result<#{ByteSlab}, #{Error}>",
+                *codegenScope,
+                "target" to symbolProvider.toSymbol(structureShape),
+            ) {
+                encodeInto { encoder ->
+                    rust("$encoder.begin_map();")
+                    serializeStructure(StructContext("value", structureShape), includedMembers)
+                    customizations.forEach { it.section(makeSection(structureShape, encoder))(this) }
+                    rust("$encoder.end();")
+                }
+                rust("Ok(encoder.into_writer())")
+            }
+        }
+    }
+
+    /**
+     * Renders a block in which `encoder` is bound to a `&mut Encoder` writing into a new buffer. The encoder can be
+     * turned into the encoded bytes with `encoder.into_writer()` after the block.
+     */
+    private fun RustWriter.encodeInto(inner: RustWriter.(String) -> Unit) {
+        rustTemplate("let mut encoder = #{Encoder}::new(#{Vec}::new());", *codegenScope)
+        rustBlock("") {
+            rust("let encoder = &mut encoder;")
+            inner("encoder")
+        }
+    }
+
+    override fun payloadSerializer(member: MemberShape): RuntimeType {
+        val target = model.expectShape(member.target)
+        return protocolFunctions.serializeFn(member, fnNameSuffix = "payload") { fnName ->
+            rustBlockTemplate(
+                "pub fn $fnName(input: &#{target}) -> std::result::Result<#{ByteSlab}, #{Error}>",
+                *codegenScope,
+                "target" to symbolProvider.toSymbol(target),
+            ) {
+                encodeInto { encoder ->
+                    when (target) {
+                        is StructureShape -> {
+                            rust("$encoder.begin_map();")
+                            serializeStructure(StructContext("input", target))
+                            rust("$encoder.end();")
+                        }
+
+                        is UnionShape -> {
+                            rust("$encoder.map(1);")
+                            serializeUnion(Context(ValueExpression.Reference("input"), target))
+                        }
+
+                        else -> throw IllegalStateException("CBOR payloadSerializer only supports structs and unions")
+                    }
+                }
+                rust("Ok(encoder.into_writer())")
+            }
+        }
+    }
+
+    override fun unsetStructure(structure: StructureShape): RuntimeType =
+        ProtocolFunctions.crossOperationFn("rpc_v2_cbor_unset_struct_payload") { fnName ->
+            rustTemplate(
+                """
+                pub fn $fnName() -> #{ByteSlab} {
+                    let mut encoder = #{Encoder}::new(#{Vec}::new());
+                    encoder.map(0);
+                    encoder.into_writer()
+                }
+                """,
+                *codegenScope,
+            )
+        }
+
+    override fun unsetUnion(union: UnionShape): RuntimeType =
+        ProtocolFunctions.crossOperationFn("rpc_v2_cbor_unset_union_payload") { fnName ->
+            rustTemplate(
+                "pub fn $fnName() -> #{ByteSlab} { #{Vec}::new() }",
+                *codegenScope,
+            )
+        }
+
+    override fun operationInputSerializer(operationShape: OperationShape): RuntimeType? {
+        // Don't generate an operation CBOR serializer if there was no operation input shape in the original
+        // (untransformed) model: such requests are sent with an empty body. Unlike other protocols, an input shape
+        // with no members is still serialized, into an empty map.
+        val syntheticInputTrait = operationShape.inputShape(model).expectTrait<SyntheticInputTrait>()
+        if (syntheticInputTrait.originalId == null) {
+            return null
+        }
+        if (httpBindingResolver.requestMembers(operationShape, HttpLocation.PAYLOAD).isNotEmpty()) {
+            return null
+        }
+
+        val httpDocumentMembers = httpBindingResolver.requestMembers(operationShape, HttpLocation.DOCUMENT)
+        val inputShape = operationShape.inputShape(model)
+        return protocolFunctions.serializeFn(operationShape, fnNameSuffix = "input") { fnName ->
+            rustBlockTemplate(
+                "pub fn $fnName(input: &#{target}) -> Result<#{SdkBody}, #{Error}>",
+                *codegenScope, "target" to symbolProvider.toSymbol(inputShape),
+            ) {
+                encodeInto { encoder ->
+                    rust("$encoder.begin_map();")
+                    serializeStructure(StructContext("input", inputShape), httpDocumentMembers)
+                    customizations.forEach { it.section(CborSerializerSection.InputStruct(inputShape, encoder))(this) }
+                    rust("$encoder.end();")
+                }
+                rustTemplate("Ok(#{SdkBody}::from(encoder.into_writer()))", *codegenScope)
+            }
+        }
+    }
+
+    override fun documentSerializer(): RuntimeType {
+        return ProtocolFunctions.crossOperationFn("serialize_document") { fnName ->
+            rustTemplate(
+                """
+                pub fn $fnName(input: &#{Document}) -> #{ByteSlab} {
+                    let mut encoder = #{Encoder}::new(#{Vec}::new());
+                    encoder.document(input);
+                    encoder.into_writer()
+                }
+                """,
+                "Document" to RuntimeType.document(runtimeConfig), *codegenScope,
+            )
+        }
+    }
+
+    override fun operationOutputSerializer(operationShape: OperationShape): RuntimeType? {
+        // Don't generate an operation CBOR serializer if there was no operation output shape in the
+        // original (untransformed) model: such responses are sent with an empty body.
+        val syntheticOutputTrait = operationShape.outputShape(model).expectTrait<SyntheticOutputTrait>()
+        if (syntheticOutputTrait.originalId == null) {
+            return null
+        }
+
+        val httpDocumentMembers = httpBindingResolver.responseMembers(operationShape, HttpLocation.DOCUMENT)
+        val outputShape = operationShape.outputShape(model)
+        return serverSerializer(outputShape, httpDocumentMembers, CborSerializerSection::OutputStruct, error = false)
+    }
+
+    override fun serverErrorSerializer(shape: ShapeId): RuntimeType {
+        val errorShape = model.expectShape(shape, StructureShape::class.java)
+        val includedMembers =
+            httpBindingResolver.errorResponseBindings(shape).filter { it.location == HttpLocation.DOCUMENT }
+                .map { it.member }
+        return serverSerializer(errorShape, includedMembers, CborSerializerSection::ServerError, error = true)
+    }
+
+    /**
+     * Writes the members of a structure as the key-value pairs of a map that the caller has begun and will end.
+     */
+    private fun RustWriter.serializeStructure(
+        context: StructContext,
+        includedMembers: List<MemberShape>? = null,
+    ) {
+        val structureSerializer =
+            protocolFunctions.serializeFn(context.shape) { fnName ->
+                val inner = context.copy(localName = "input")
+                val members = includedMembers ?: inner.shape.members()
+                val allowUnusedVariables =
+                    writable {
+                        if (members.isEmpty()) {
+                            Attribute.AllowUnusedVariables.render(this)
+                        }
+                    }
+                rustBlockTemplate(
+                    """
+                    pub fn $fnName(
+                        #{AllowUnusedVariables:W} encoder: &mut #{Encoder},
+                        #{AllowUnusedVariables:W} input: &#{StructureSymbol},
+                    ) -> Result<(), #{Error}>
+                    """,
+                    "StructureSymbol" to symbolProvider.toSymbol(context.shape),
+                    "AllowUnusedVariables" to allowUnusedVariables,
+                    *codegenScope,
+                ) {
+                    for (member in members) {
+                        serializeMember(MemberContext.structMember(inner, member, symbolProvider))
+                    }
+                    rust("Ok(())")
+                }
+            }
+        rust("#T(encoder, ${context.localName})?;", structureSerializer)
+    }
+
+    private fun RustWriter.serializeMember(context: MemberContext) {
+        val targetShape = model.expectShape(context.shape.target)
+        if (symbolProvider.toSymbol(context.shape).isOptional()) {
+            safeName().also { local ->
+                rustBlock("if let Some($local) = ${context.valueExpression.asRef()}") {
+                    context.valueExpression = ValueExpression.Reference(local)
+                    for (customization in customizations) {
+                        customization.section(
+                            CborSerializerSection.BeforeSerializingNonNullMember(
+                                targetShape,
+                                context,
+                            ),
+                        )(this)
+                    }
+                    serializeMemberValue(context, targetShape)
+                }
+                if (context.writeNulls) {
+                    rustBlock("else") {
+                        context.keyExpression?.also { rust("encoder.str($it);") }
+                        rust("encoder.null();")
+                    }
+                }
+            }
+        } else {
+            for (customization in customizations) {
+                customization.section(CborSerializerSection.BeforeSerializingNonNullMember(targetShape, context))(
+                    this,
+                )
+            }
+
+            with(serializerUtil) {
+                ignoreDefaultsForNumbersAndBools(context.shape, context.valueExpression) {
+                    serializeMemberValue(context, targetShape)
+                }
+            }
+        }
+    }
+
+    private fun RustWriter.serializeMemberValue(
+        context: MemberContext,
+        target: Shape,
+    ) {
+        val value = context.valueExpression
+
+        context.keyExpression?.also { rust("encoder.str($it);") }
+        when (target) {
+            is StringShape -> rust("encoder.str(${value.name}.as_str());")
+            is BooleanShape -> rust("encoder.boolean(${value.asValue()});")
+            is ByteShape -> rust("encoder.byte(${value.asValue()});")
+            is ShortShape -> rust("encoder.short(${value.asValue()});")
+            is IntegerShape -> rust("encoder.integer(${value.asValue()});")
+            is LongShape -> rust("encoder.long(${value.asValue()});")
+            is FloatShape -> rust("encoder.float(${value.asValue()});")
+            is DoubleShape -> rust("encoder.double(${value.asValue()});")
+            is BlobShape -> rust("encoder.blob(${value.asRef()});")
+            is TimestampShape -> rust("encoder.timestamp(${value.asRef()});")
+            is DocumentShape -> rust("encoder.document(${value.asRef()});")
+            is CollectionShape -> serializeCollection(Context(value, target))
+            is MapShape -> serializeMap(Context(value, target))
+            is StructureShape -> {
+                rust("encoder.begin_map();")
+                // We don't call [serializeStructure] when the member targets the Unit type, since there would be no
+                // data to extract out of the Unit type at the call site.
+                if (!context.shape.isTargetUnit()) {
+                    serializeStructure(StructContext(value.asRef(), target))
+                }
+                rust("encoder.end();")
+            }
+
+            is UnionShape -> {
+                rust("encoder.map(1);")
+                serializeUnion(Context(value, target))
+            }
+
+            else -> TODO(target.toString())
+        }
+    }
+
+    private fun RustWriter.serializeCollection(context: Context<CollectionShape>) {
+        val itemName = safeName("item")
+        for (customization in customizations) {
+            customization.section(CborSerializerSection.BeforeIteratingOverMapOrCollection(context.shape, context))(this)
+        }
+        rust("encoder.array((${context.valueExpression.asRef()}).len());")
+        rustBlock("for $itemName in ${context.valueExpression.asRef()}") {
+            serializeMember(MemberContext.collectionMember(context, itemName))
+        }
+    }
+
+    private fun RustWriter.serializeMap(context: Context<MapShape>) {
+        val keyName = safeName("key")
+        val valueName = safeName("value")
+        for (customization in customizations) {
+            customization.section(CborSerializerSection.BeforeIteratingOverMapOrCollection(context.shape, context))(
+                this,
+            )
+        }
+        rust("encoder.map((${context.valueExpression.asRef()}).len());")
+        rustBlock("for ($keyName, $valueName) in ${context.valueExpression.asRef()}") {
+            val keyExpression = "$keyName.as_str()"
+            serializeMember(MemberContext.mapMember(context, keyExpression, valueName))
+        }
+    }
+
+    /**
+     * Writes the single key-value pair of the union's set variant into a map of length 1 that the caller has begun.
+     */
+    private fun RustWriter.serializeUnion(context: Context<UnionShape>) {
+        val unionSymbol = symbolProvider.toSymbol(context.shape)
+        val unionSerializer =
+            protocolFunctions.serializeFn(context.shape) { fnName ->
+                rustBlockTemplate(
+                    "pub fn $fnName(encoder: &mut #{Encoder}, input: &#{Input}) -> Result<(), #{Error}>",
+                    "Input" to unionSymbol,
+                    *codegenScope,
+                ) {
+                    rustBlock("match input") {
+                        for (member in context.shape.members()) {
+                            val variantName =
+                                if (member.isTargetUnit()) {
+                                    "${symbolProvider.toMemberName(member)}"
+                                } else {
+                                    "${symbolProvider.toMemberName(member)}(inner)"
+                                }
+                            withBlock("#T::$variantName => {", "},", unionSymbol) {
+                                serializeMember(MemberContext.unionMember("inner", member))
+                            }
+                        }
+                        if (codegenTarget.renderUnknownVariant()) {
+                            rustTemplate(
+                                "#{Union}::${UnionGenerator.UnknownVariantName} => return Err(#{Error}::unknown_variant(${unionSymbol.name.dq()}))",
+                                "Union" to unionSymbol,
+                                *codegenScope,
+                            )
+                        }
+                    }
+                    rust("Ok(())")
+                }
+            }
+        rust("#T(encoder, ${context.valueExpression.asRef()})?;", unionSerializer)
+    }
+}
@@ -184,7 +184,9 @@ class JsonSerializerGenerator(
             *preludeScope,
             "Error" to runtimeConfig.serializationError(),
             "SdkBody" to RuntimeType.sdkBody(runtimeConfig),
+            "BytesMut" to RuntimeType.BytesMut,
             "JsonObjectWriter" to RuntimeType.smithyJson(runtimeConfig).resolve("serialize::JsonObjectWriter"),
+            "JsonOutput" to RuntimeType.smithyJson(runtimeConfig).resolve("serialize::JsonOutput"),
             "JsonValueWriter" to RuntimeType.smithyJson(runtimeConfig).resolve("serialize::JsonValueWriter"),
             "ByteSlab" to RuntimeType.ByteSlab,
         )
@@ -276,12 +278,13 @@ class JsonSerializerGenerator(
                 "pub fn $fnName(input: &#{target}) -> Result<#{SdkBody}, #{Error}>",
                 *codegenScope, "target" to symbolProvider.toSymbol(inputShape),
             ) {
-                rust("let mut out = String::new();")
+                // Written to `BytesMut` so that the body can be frozen into `Bytes` without a copy
+                rustTemplate("let mut out = #{BytesMut}::new();", *codegenScope)
                 rustTemplate("let mut object = #{JsonObjectWriter}::new(&mut out);", *codegenScope)
                 serializeStructure(StructContext("object", "input", inputShape), httpDocumentMembers)
                 customizations.forEach { it.section(JsonSerializerSection.InputStruct(inputShape, "object"))(this) }
                 rust("object.finish();")
-                rustTemplate("Ok(#{SdkBody}::from(out))", *codegenScope)
+                rustTemplate("Ok(#{SdkBody}::from(out.freeze()))", *codegenScope)
             }
         }
     }
@@ -346,8 +349,8 @@ class JsonSerializerGenerator(
                     }
                 rustBlockTemplate(
                     """
-                    pub fn $fnName(
-                        #{AllowUnusedVariables:W} object: &mut #{JsonObjectWriter},
+                    pub fn $fnName<O: #{JsonOutput}>(
+                        #{AllowUnusedVariables:W} object: &mut #{JsonObjectWriter}<'_, O>,
                         #{AllowUnusedVariables:W} input: &#{StructureSymbol},
                     ) -> Result<(), #{Error}>
                     """,
@@ -530,7 +533,7 @@ class JsonSerializerGenerator(
         val unionSerializer =
             protocolFunctions.serializeFn(context.shape) { fnName ->
                 rustBlockTemplate(
-                    "pub fn $fnName(${context.writerExpression}: &mut #{JsonObjectWriter}, input: &#{Input}) -> Result<(), #{Error}>",
+                    "pub fn $fnName<O: #{JsonOutput}>(${context.writerExpression}: &mut #{JsonObjectWriter}<'_, O>, input: &#{Input}) -> Result<(), #{Error}>",
                     "Input" to unionSymbol,
                     *codegenScope,
                 ) {
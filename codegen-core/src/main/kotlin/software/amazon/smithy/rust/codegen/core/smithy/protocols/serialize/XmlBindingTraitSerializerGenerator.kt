@@ -54,9 +54,32 @@ import software.amazon.smithy.rust.codegen.core.util.isTargetUnit
 import software.amazon.smithy.rust.codegen.core.util.letIf
 import software.amazon.smithy.rust.codegen.core.util.outputShape
 
+// The string argument is the name of the XML ScopeWriter to write the members of the response into
+typealias ResponseInnerWriteable = RustWriter.(String) -> Unit
+
+data class ResponseWrapperContext(
+    /** The operation output or the error being serialized */
+    val shape: StructureShape,
+    /** The operation whose output is being serialized, or `null` when serializing an error */
+    val operationShape: OperationShape?,
+    /** The name of the `XmlWriter` to start the root element from */
+    val writerName: String,
+    /** A `.write_ns(..)` call setting the namespace of the root element, or an empty string */
+    val rootNamespace: String,
+)
+
+/**
+ * Serializes XML bodies according to the XML binding traits.
+ *
+ * Protocols that wrap server responses in additional elements, like `awsQuery` and `ec2Query`, can pass
+ * [writeOutputWrapper] and [writeErrorWrapper] to write the wrapping elements around the members of operation outputs
+ * and errors, respectively.
+ */
 class XmlBindingTraitSerializerGenerator(
     codegenContext: CodegenContext,
     private val httpBindingResolver: HttpBindingResolver,
+    private val writeOutputWrapper: (RustWriter.(ResponseWrapperContext, ResponseInnerWriteable) -> Unit)? = null,
+    private val writeErrorWrapper: (RustWriter.(ResponseWrapperContext, ResponseInnerWriteable) -> Unit)? = null,
 ) : StructuredDataSerializerGenerator {
     private val symbolProvider = codegenContext.symbolProvider
     private val runtimeConfig = codegenContext.runtimeConfig
@@ -207,6 +230,15 @@ class XmlBindingTraitSerializerGenerator(
     override fun operationOutputSerializer(operationShape: OperationShape): RuntimeType? {
         val outputShape = operationShape.outputShape(model)
         val xmlMembers = operationShape.responseBodyMembers()
+        if (writeOutputWrapper != null) {
+            // The wrapping elements are written even when there are no members.
+            return wrappedResponseSerializer(
+                ResponseWrapperContext(outputShape, operationShape, "writer", outputShape.xmlNamespace(root = true).apply()),
+                xmlMembers,
+                "output",
+                writeOutputWrapper,
+            )
+        }
         if (xmlMembers.isEmpty()) {
             return null
         }
@@ -246,6 +278,14 @@ class XmlBindingTraitSerializerGenerator(
             httpBindingResolver.errorResponseBindings(shape)
                 .filter { it.location == HttpLocation.DOCUMENT }
                 .map { it.member }
+        if (writeErrorWrapper != null) {
+            return wrappedResponseSerializer(
+                ResponseWrapperContext(errorShape, null, "writer", errorShape.xmlNamespace(root = true).apply()),
+                XmlMemberIndex.fromMembers(xmlMembers),
+                "error",
+                writeErrorWrapper,
+            )
+        }
         return protocolFunctions.serializeFn(errorShape, fnNameSuffix = "error") { fnName ->
             rustBlockTemplate(
                 "pub fn $fnName(error: &#{target}) -> Result<String, #{Error}>",
@@ -275,6 +315,46 @@ class XmlBindingTraitSerializerGenerator(
         }
     }
 
+    /**
+     * Renders a serializer for an operation output or an error whose members are written inside the elements written
+     * by [writeWrapper], rather than inside a root element named after the shape.
+     */
+    private fun wrappedResponseSerializer(
+        context: ResponseWrapperContext,
+        members: XmlMemberIndex,
+        input: String,
+        writeWrapper: RustWriter.(ResponseWrapperContext, ResponseInnerWriteable) -> Unit,
+    ): RuntimeType {
+        if (members.attributeMembers.isNotEmpty()) {
+            throw CodegenException("${context.shape.id} cannot bind members to XML attributes of a wrapped response")
+        }
+        val fnNameSuffix = if (context.operationShape != null) "output" else "error"
+        return protocolFunctions.serializeFn(context.operationShape ?: context.shape, fnNameSuffix = fnNameSuffix) { fnName ->
+            rustBlockTemplate(
+                "pub fn $fnName($input: &#{target}) -> Result<String, #{Error}>",
+                *codegenScope, "target" to symbolProvider.toSymbol(context.shape),
+            ) {
+                if (members.dataMembers.isEmpty()) {
+                    rust("let _ = $input;")
+                }
+                rust("let mut out = String::new();")
+                // Create a scope for writer. This ensures that:
+                // - The writer is dropped before returning the string
+                // - All closing tags get written
+                rustBlock("") {
+                    rustTemplate("let mut ${context.writerName} = #{XmlWriter}::new(&mut out);", *codegenScope)
+                    writeWrapper(context) { scopeWriter ->
+                        val scopeCtx = Ctx.Scope(scopeWriter, input)
+                        members.dataMembers.forEach { member ->
+                            serializeMember(member, scopeCtx.scopedTo(member), null)
+                        }
+                    }
+                }
+                rust("Ok(out)")
+            }
+        }
+    }
+
     private fun XmlNamespaceTrait?.apply(): String {
         this ?: return ""
         val prefix = prefix.map { prefix -> "Some(${prefix.dq()})" }.orElse("None")
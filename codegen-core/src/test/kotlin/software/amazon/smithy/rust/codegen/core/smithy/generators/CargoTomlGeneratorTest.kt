@@ -5,10 +5,13 @@
 
 package software.amazon.smithy.rust.codegen.core.smithy.generators
 
+import io.kotest.matchers.string.shouldContain
+import io.kotest.matchers.string.shouldNotContain
 import org.junit.jupiter.api.Test
 import software.amazon.smithy.rust.codegen.core.Version
 import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
 import software.amazon.smithy.rust.codegen.core.rustlang.CratesIo
+import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
 import software.amazon.smithy.rust.codegen.core.testutil.TestWorkspace
 import software.amazon.smithy.rust.codegen.core.testutil.compileAndTest
 import software.amazon.smithy.rust.codegen.core.testutil.unitTest
@@ -42,4 +45,24 @@ class CargoTomlGeneratorTest {
         }
         project.compileAndTest()
     }
+
+    @Test
+    fun `renders cfg unstable dependencies under the aws_sdk_unstable target`() {
+        val cargoToml =
+            RustWriter.toml("Cargo.toml").apply {
+                CargoTomlGenerator(
+                    moduleName = "test",
+                    moduleVersion = "0.0.1",
+                    moduleAuthors = listOf("Testy McTesterson"),
+                    moduleDescription = null,
+                    moduleLicense = null,
+                    moduleRepository = null,
+                    writer = this,
+                    dependencies = listOf(CargoDependency.Serde),
+                ).render()
+            }.toString()
+
+        cargoToml shouldContain """[target."cfg(aws_sdk_unstable)".dependencies.serde]"""
+        cargoToml shouldNotContain "[dependencies.serde]"
+    }
 }
@@ -0,0 +1,254 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.protocols.parse
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.StringShape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.rust.codegen.core.smithy.generators.EnumGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.generators.TestEnumType
+import software.amazon.smithy.rust.codegen.core.smithy.generators.UnionGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.RpcV2CborHttpBindingResolver
+import software.amazon.smithy.rust.codegen.core.smithy.transformers.OperationNormalizer
+import software.amazon.smithy.rust.codegen.core.smithy.transformers.RecursiveShapeBoxer
+import software.amazon.smithy.rust.codegen.core.testutil.TestWorkspace
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.compileAndTest
+import software.amazon.smithy.rust.codegen.core.testutil.renderWithModelBuilder
+import software.amazon.smithy.rust.codegen.core.testutil.testCodegenContext
+import software.amazon.smithy.rust.codegen.core.testutil.unitTest
+import software.amazon.smithy.rust.codegen.core.util.lookup
+import software.amazon.smithy.rust.codegen.core.util.outputShape
+
+class CborParserGeneratorTest {
+    private val baseModel =
+        """
+        namespace test
+        use smithy.protocols#rpcv2Cbor
+
+        @rpcv2Cbor
+        service TestService {
+            operations: [Op]
+        }
+
+        union Choice {
+            blob: Blob,
+            boolean: Boolean,
+            date: Timestamp,
+            document: Document,
+            enum: FooEnum,
+            int: Integer,
+            list: SomeList,
+            listSparse: SomeSparseList,
+            long: Long,
+            map: MyMap,
+            mapSparse: MySparseMap,
+            number: Double,
+            s: String,
+            top: Top,
+            unit: Unit,
+        }
+
+        @enum([{name: "FOO", value: "FOO"}])
+        string FooEnum
+
+        map MyMap {
+            key: String,
+            value: Choice,
+        }
+
+        @sparse
+        map MySparseMap {
+            key: String,
+            value: Choice,
+        }
+
+        list SomeList {
+            member: Choice
+        }
+
+        @sparse
+        list SomeSparseList {
+            member: Choice
+        }
+
+        structure Top {
+            @required
+            choice: Choice,
+            field: String,
+            extra: Integer,
+            recursive: TopList,
+        }
+
+        list TopList {
+            member: Top
+        }
+
+        @output
+        structure OpOutput {
+            top: Top
+        }
+
+        @error("client")
+        structure Error {
+            message: String,
+            reason: String
+        }
+
+        operation Op {
+            output: OpOutput,
+            errors: [Error]
+        }
+        """.asSmithyModel()
+
+    @Test
+    fun `generates valid deserializers`() {
+        val model = RecursiveShapeBoxer().transform(OperationNormalizer.transform(baseModel))
+        val codegenContext = testCodegenContext(model)
+        val symbolProvider = codegenContext.symbolProvider
+
+        val parserGenerator =
+            CborParserGenerator(
+                codegenContext,
+                RpcV2CborHttpBindingResolver(model, codegenContext.serviceShape),
+            )
+        val operationGenerator = parserGenerator.operationParser(model.lookup("test#Op"))
+        val errorParser = parserGenerator.errorParser(model.lookup("test#Error"))
+
+        val project = TestWorkspace.testProject(symbolProvider)
+        project.lib {
+            unitTest(
+                "cbor_parser",
+                """
+                use test_model::Choice;
+
+                // `{"top": {"extra": 45, "field": "something", "choice": {"int": 5}}}`, with an indefinite length
+                // outer map.
+                let mut encoder = aws_smithy_cbor::Encoder::new(Vec::new());
+                encoder
+                    .begin_map()
+                    .str("top")
+                    .map(3)
+                    .str("extra")
+                    .integer(45)
+                    .str("field")
+                    .str("something")
+                    .str("choice")
+                    .map(1)
+                    .str("int")
+                    .integer(5)
+                    .end();
+                let cbor = encoder.into_writer();
+
+                let output = ${format(operationGenerator!!)}(&cbor, test_output::OpOutput::builder()).unwrap().build();
+                let top = output.top.expect("top");
+                assert_eq!(Some(45), top.extra);
+                assert_eq!(Some("something".to_string()), top.field);
+                assert_eq!(Choice::Int(5), top.choice);
+                """,
+            )
+            unitTest(
+                "empty_body",
+                """
+                // empty body
+                let output = ${format(operationGenerator)}(b"", test_output::OpOutput::builder()).unwrap().build();
+                assert_eq!(output.top, None);
+                """,
+            )
+            unitTest(
+                "unknown_variant",
+                """
+                // unknown variant
+                let mut encoder = aws_smithy_cbor::Encoder::new(Vec::new());
+                encoder.map(1).str("top").map(1).str("choice").map(1).str("somenewvariant").str("data");
+                let cbor = encoder.into_writer();
+                let output = ${format(operationGenerator)}(&cbor, test_output::OpOutput::builder()).unwrap().build();
+                assert!(output.top.unwrap().choice.is_unknown());
+                """,
+            )
+            unitTest(
+                "null_variants_are_ignored",
+                """
+                // `null` variants and the `__type` key should be ignored during deserialization
+                let mut encoder = aws_smithy_cbor::Encoder::new(Vec::new());
+                encoder
+                    .map(1)
+                    .str("top")
+                    .map(1)
+                    .str("choice")
+                    .map(4)
+                    .str("blob")
+                    .null()
+                    .str("int")
+                    .integer(5)
+                    .str("long")
+                    .null()
+                    .str("__type")
+                    .str("value-should-be-ignored-anyway");
+                let cbor = encoder.into_writer();
+                let output = ${format(operationGenerator)}(&cbor, test_output::OpOutput::builder()).unwrap().build();
+                use test_model::Choice;
+                assert_eq!(Choice::Int(5), output.top.unwrap().choice);
+                """,
+            )
+            unitTest(
+                "mixed_variants",
+                """
+                // a union can't have more than one variant set
+                let mut encoder = aws_smithy_cbor::Encoder::new(Vec::new());
+                encoder.map(1).str("top").map(1).str("choice").map(2).str("int").integer(5).str("s").str("five");
+                let cbor = encoder.into_writer();
+                let _err = ${format(operationGenerator)}(&cbor, test_output::OpOutput::builder()).expect_err("invalid union");
+                """,
+            )
+            unitTest(
+                "trailing_data",
+                """
+                // data after the top-level map is rejected
+                let mut encoder = aws_smithy_cbor::Encoder::new(Vec::new());
+                encoder.map(0).map(0);
+                let cbor = encoder.into_writer();
+                let _err = ${format(operationGenerator)}(&cbor, test_output::OpOutput::builder()).expect_err("trailing data");
+                """,
+            )
+            unitTest(
+                "empty_error",
+                """
+                // empty error
+                let error_output = ${format(errorParser!!)}(b"", test_error::Error::builder()).unwrap().build();
+                assert_eq!(error_output.message, None);
+                """,
+            )
+            unitTest(
+                "error_with_message",
+                """
+                // error with message
+                let mut encoder = aws_smithy_cbor::Encoder::new(Vec::new());
+                encoder.map(1).str("message").str("hello");
+                let cbor = encoder.into_writer();
+                let error_output = ${format(errorParser)}(&cbor, test_error::Error::builder()).unwrap().build();
+                assert_eq!(error_output.message.expect("message should be set"), "hello");
+                """,
+            )
+        }
+        model.lookup<StructureShape>("test#Top").also { top ->
+            top.renderWithModelBuilder(model, symbolProvider, project)
+            project.moduleFor(top) {
+                UnionGenerator(model, symbolProvider, this, model.lookup("test#Choice")).render()
+                val enum = model.lookup<StringShape>("test#FooEnum")
+                EnumGenerator(model, symbolProvider, enum, TestEnumType).render(this)
+            }
+        }
+        model.lookup<OperationShape>("test#Op").outputShape(model).also { output ->
+            output.renderWithModelBuilder(model, symbolProvider, project)
+        }
+        model.lookup<StructureShape>("test#Error").also { error ->
+            error.renderWithModelBuilder(model, symbolProvider, project)
+        }
+        project.compileAndTest()
+    }
+}
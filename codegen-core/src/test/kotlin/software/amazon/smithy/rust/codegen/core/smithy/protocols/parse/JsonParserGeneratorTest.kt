@@ -9,6 +9,8 @@ import org.junit.jupiter.api.Test
 import software.amazon.smithy.model.shapes.OperationShape
 import software.amazon.smithy.model.shapes.StringShape
 import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.smithy.generators.EnumGenerator
 import software.amazon.smithy.rust.codegen.core.smithy.generators.TestEnumType
 import software.amazon.smithy.rust.codegen.core.smithy.generators.UnionGenerator
@@ -17,12 +19,14 @@ import software.amazon.smithy.rust.codegen.core.smithy.protocols.ProtocolContent
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.restJsonFieldName
 import software.amazon.smithy.rust.codegen.core.smithy.transformers.OperationNormalizer
 import software.amazon.smithy.rust.codegen.core.smithy.transformers.RecursiveShapeBoxer
+import software.amazon.smithy.rust.codegen.core.testutil.TestRuntimeConfig
 import software.amazon.smithy.rust.codegen.core.testutil.TestWorkspace
 import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
 import software.amazon.smithy.rust.codegen.core.testutil.compileAndTest
 import software.amazon.smithy.rust.codegen.core.testutil.renderWithModelBuilder
 import software.amazon.smithy.rust.codegen.core.testutil.testCodegenContext
 import software.amazon.smithy.rust.codegen.core.testutil.testSymbolProvider
+import software.amazon.smithy.rust.codegen.core.testutil.tokioTest
 import software.amazon.smithy.rust.codegen.core.testutil.unitTest
 import software.amazon.smithy.rust.codegen.core.util.lookup
 import software.amazon.smithy.rust.codegen.core.util.outputShape
@@ -95,7 +99,8 @@ class JsonParserGeneratorTest {
             @httpHeader("x-test")
             someHeader: String,
 
-            top: Top
+            top: Top,
+            items: TopList,
         }
 
         @error("client")
@@ -126,6 +131,7 @@ class JsonParserGeneratorTest {
         val operationGenerator = parserGenerator.operationParser(model.lookup("test#Op"))
         val payloadGenerator = parserGenerator.payloadParser(model.lookup("test#OpOutput\$top"))
         val errorParser = parserGenerator.errorParser(model.lookup("test#Error"))
+        val incrementalParser = parserGenerator.incrementalOperationParser(model.lookup("test#Op"))
 
         val project = TestWorkspace.testProject(testSymbolProvider(model))
         project.lib {
@@ -220,6 +226,40 @@ class JsonParserGeneratorTest {
                 assert_eq!(error_output.message.expect("message should be set"), "hello");
                 """,
             )
+
+            tokioTest("incremental_json_parser") {
+                rustTemplate(
+                    """
+                    use test_model::Choice;
+
+                    let json = br##"
+                        { "items": [{ "choice": { "int": 1 } }, null, { "choice": { "s": "two" } }],
+                          "unknown": [1, { "a": 2 }],
+                          "top": { "field": "something", "choice": { "int": 5 } }
+                        }
+                    "##;
+                    let mut stream = #{ByteStream}::from_static(json);
+                    let output = #{parser}(&mut stream, test_output::OpOutput::builder()).await.unwrap().build();
+                    let items = output.items.expect("items");
+                    // Null elements of dense lists are skipped.
+                    assert_eq!(2, items.len());
+                    assert_eq!(Choice::Int(1), items[0].choice);
+                    assert_eq!(Choice::S("two".to_string()), items[1].choice);
+                    assert_eq!(Some("something".to_string()), output.top.expect("top").field);
+
+                    // empty body
+                    let mut stream = #{ByteStream}::from_static(b"");
+                    let output = #{parser}(&mut stream, test_output::OpOutput::builder()).await.unwrap().build();
+                    assert_eq!(output.items, None);
+
+                    // truncated body
+                    let mut stream = #{ByteStream}::from_static(br##"{ "items": [{ "choice": "##);
+                    let _err = #{parser}(&mut stream, test_output::OpOutput::builder()).await.expect_err("truncated body");
+                    """,
+                    "ByteStream" to RuntimeType.byteStream(TestRuntimeConfig),
+                    "parser" to incrementalParser!!,
+                )
+            }
         }
         model.lookup<StructureShape>("test#Top").also { top ->
             top.renderWithModelBuilder(model, symbolProvider, project)
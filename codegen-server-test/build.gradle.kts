@@ -25,6 +25,7 @@ val workingDirUnderBuildDir = "smithyprojections/codegen-server-test/"
 dependencies {
     implementation(project(":codegen-server"))
     implementation("software.amazon.smithy:smithy-aws-protocol-tests:$smithyVersion")
+    implementation("software.amazon.smithy:smithy-protocol-tests:$smithyVersion")
     implementation("software.amazon.smithy:smithy-protocol-test-traits:$smithyVersion")
     implementation("software.amazon.smithy:smithy-aws-traits:$smithyVersion")
     implementation("software.amazon.smithy:smithy-validation-model:$smithyVersion")
@@ -43,7 +44,12 @@ val allCodegenTests = "../codegen-core/common-test-models".let { commonModels ->
             "naming_test_structs",
             imports = listOf("$commonModels/naming-obstacle-course-structs.smithy"),
         ),
-        CodegenTest("com.amazonaws.simple#SimpleService", "simple", imports = listOf("$commonModels/simple.smithy")),
+        CodegenTest(
+            "com.amazonaws.simple#SimpleService",
+            "simple",
+            imports = listOf("$commonModels/simple.smithy"),
+            extraConfig = """, "codegen": { "openApi": true } """,
+        ),
         CodegenTest(
             "com.amazonaws.constraints#ConstraintsService",
             "constraints_without_public_constrained_types",
@@ -75,6 +81,9 @@ val allCodegenTests = "../codegen-core/common-test-models".let { commonModels ->
         ),
         CodegenTest("aws.protocoltests.json10#JsonRpc10", "json_rpc10"),
         CodegenTest("aws.protocoltests.json#JsonProtocol", "json_rpc11"),
+        CodegenTest("smithy.protocoltests.rpcv2Cbor#RpcV2Protocol", "rpcv2Cbor"),
+        CodegenTest("aws.protocoltests.query#AwsQuery", "aws_query"),
+        CodegenTest("aws.protocoltests.ec2#AwsEc2", "ec2_query"),
         CodegenTest(
             "aws.protocoltests.misc#MiscService",
             "misc",
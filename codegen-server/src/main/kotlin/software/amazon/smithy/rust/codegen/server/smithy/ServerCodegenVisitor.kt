@@ -69,6 +69,7 @@ import software.amazon.smithy.rust.codegen.server.smithy.generators.ScopeMacroGe
 import software.amazon.smithy.rust.codegen.server.smithy.generators.ServerBuilderGenerator
 import software.amazon.smithy.rust.codegen.server.smithy.generators.ServerBuilderGeneratorWithoutPublicConstrainedTypes
 import software.amazon.smithy.rust.codegen.server.smithy.generators.ServerEnumGenerator
+import software.amazon.smithy.rust.codegen.server.smithy.generators.ServerOpenApiGenerator
 import software.amazon.smithy.rust.codegen.server.smithy.generators.ServerOperationErrorGenerator
 import software.amazon.smithy.rust.codegen.server.smithy.generators.ServerOperationGenerator
 import software.amazon.smithy.rust.codegen.server.smithy.generators.ServerRootGenerator
@@ -106,6 +107,7 @@ open class ServerCodegenVisitor(
 
     protected var rustCrate: RustCrate
     private val fileManifest = context.fileManifest
+    private val originalModel = context.model
     protected var model: Model
     protected var codegenContext: ServerCodegenContext
     protected var protocolGeneratorFactory: ProtocolGeneratorFactory<ServerProtocolGenerator, ServerCodegenContext>
@@ -664,6 +666,11 @@ open class ServerCodegenVisitor(
             ScopeMacroGenerator(codegenContext).render(this)
         }
 
+        // Generate the OpenAPI document.
+        if (settings.codegenConfig.openApi) {
+            ServerOpenApiGenerator(codegenContext, originalModel).render(rustCrate)
+        }
+
         codegenDecorator.postprocessServiceGenerateAdditionalStructures(shape)
             .forEach { structureShape -> this.structureShape(structureShape) }
     }
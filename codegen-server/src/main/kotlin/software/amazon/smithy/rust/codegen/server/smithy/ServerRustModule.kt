@@ -43,6 +43,7 @@ object ServerRustModule {
     val Types = RustModule.public("types")
     val Server = RustModule.public("server")
     val Service = RustModule.private("service")
+    val OpenApi = RustModule.public("openapi")
 
     val UnconstrainedModule =
         software.amazon.smithy.rust.codegen.core.smithy.UnconstrainedModule
@@ -62,6 +63,7 @@ class ServerModuleDocProvider(private val codegenContext: ServerCodegenContext)
             ServerRustModule.Output -> strDoc("Output structures for operations. Documentation on these types is copied from the model.")
             ServerRustModule.Types -> strDoc("Data primitives referenced by other data types.")
             ServerRustModule.Server -> strDoc("Contains the types that are re-exported from the `aws-smithy-http-server` crate.")
+            ServerRustModule.OpenApi -> strDoc("The OpenAPI 3 document of the service.")
             ServerRustModule.UnconstrainedModule -> strDoc("Unconstrained types for constrained shapes.")
             ServerRustModule.ConstrainedModule -> strDoc("Constrained types for constrained shapes.")
             else -> TODO("Document this module: $module")
@@ -80,12 +80,14 @@ data class ServerRustSettings(
 /**
  * [publicConstrainedTypes]: Generate constrained wrapper newtypes for constrained shapes
  * [ignoreUnsupportedConstraints]: Generate model even though unsupported constraints are present
+ * [openApi]: Generate the OpenAPI 3 document of the service, and a method on the service to serve it (restJson1 only)
  */
 data class ServerCodegenConfig(
     override val formatTimeoutSeconds: Int = defaultFormatTimeoutSeconds,
     override val debugMode: Boolean = defaultDebugMode,
     val publicConstrainedTypes: Boolean = defaultPublicConstrainedTypes,
     val ignoreUnsupportedConstraints: Boolean = defaultIgnoreUnsupportedConstraints,
+    val openApi: Boolean = defaultOpenApi,
     /**
      * A flag to enable _experimental_ support for custom validation exceptions via the
      * [CustomValidationExceptionWithReasonDecorator] decorator.
@@ -99,6 +101,7 @@ data class ServerCodegenConfig(
     companion object {
         private const val defaultPublicConstrainedTypes = true
         private const val defaultIgnoreUnsupportedConstraints = false
+        private const val defaultOpenApi = false
         private val defaultExperimentalCustomValidationExceptionWithReasonPleaseDoNotUse = null
 
         fun fromCodegenConfigAndNode(
@@ -110,6 +113,7 @@ data class ServerCodegenConfig(
                 debugMode = coreCodegenConfig.debugMode,
                 publicConstrainedTypes = node.get().getBooleanMemberOrDefault("publicConstrainedTypes", defaultPublicConstrainedTypes),
                 ignoreUnsupportedConstraints = node.get().getBooleanMemberOrDefault("ignoreUnsupportedConstraints", defaultIgnoreUnsupportedConstraints),
+                openApi = node.get().getBooleanMemberOrDefault("openApi", defaultOpenApi),
                 experimentalCustomValidationExceptionWithReasonPleaseDoNotUse = node.get().getStringMemberOrDefault("experimentalCustomValidationExceptionWithReasonPleaseDoNotUse", defaultExperimentalCustomValidationExceptionWithReasonPleaseDoNotUse),
             )
         } else {
@@ -0,0 +1,90 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.server.smithy.generators
+
+import software.amazon.smithy.aws.traits.protocols.RestJson1Trait
+import software.amazon.smithy.codegen.core.CodegenException
+import software.amazon.smithy.model.Model
+import software.amazon.smithy.model.node.Node
+import software.amazon.smithy.openapi.OpenApiConfig
+import software.amazon.smithy.openapi.fromsmithy.OpenApiConverter
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
+import software.amazon.smithy.rust.codegen.core.util.toPascalCase
+import software.amazon.smithy.rust.codegen.server.smithy.ServerCargoDependency
+import software.amazon.smithy.rust.codegen.server.smithy.ServerCodegenContext
+import software.amazon.smithy.rust.codegen.server.smithy.ServerRustModule
+
+/**
+ * Generates the OpenAPI 3 document of a `restJson1` service with `smithy-openapi`, and a `serve_openapi` method on
+ * the service that serves it.
+ *
+ * The document is converted from [model], the model as it was given to the plugin, rather than the model in the
+ * [codegenContext], so that it doesn't contain the synthetic shapes that code generation adds. It is written to
+ * `openapi.json` at the root of the crate, and included in the crate as `openapi::OPENAPI_JSON`.
+ */
+class ServerOpenApiGenerator(
+    private val codegenContext: ServerCodegenContext,
+    private val model: Model,
+) {
+    private val service = codegenContext.serviceShape
+    private val serviceName = service.id.name.toPascalCase()
+    private val smithyHttpServer = ServerCargoDependency.smithyHttpServer(codegenContext.runtimeConfig).toType()
+
+    fun render(rustCrate: RustCrate) {
+        if (codegenContext.protocol != RestJson1Trait.ID) {
+            throw CodegenException(
+                "OpenAPI documents can only be generated for `restJson1` services, but `${service.id}` uses " +
+                    "`${codegenContext.protocol}`; disable the `openApi` codegen setting",
+            )
+        }
+
+        val config =
+            OpenApiConfig().apply {
+                service = this@ServerOpenApiGenerator.service.id
+                protocol = RestJson1Trait.ID
+            }
+        val document = OpenApiConverter.create().config(config).convertToNode(model)
+
+        rustCrate.withFile("openapi.json") {
+            writeWithNoFormatting(Node.prettyPrintJson(document))
+        }
+
+        rustCrate.withModule(ServerRustModule.OpenApi) {
+            rust(
+                """
+                /// The OpenAPI 3 document of [`$serviceName`](crate::$serviceName), as JSON.
+                pub const OPENAPI_JSON: &str = include_str!("../openapi.json");
+                """,
+            )
+        }
+
+        rustCrate.withModule(ServerRustModule.Service) {
+            rustTemplate(
+                """
+                impl<S> $serviceName<S> {
+                    /// Serves the [OpenAPI 3 document](crate::openapi::OPENAPI_JSON) of the service in response to
+                    /// `GET` requests for `path`, and passes all other requests to the service.
+                    pub fn serve_openapi(
+                        self,
+                        path: impl Into<std::borrow::Cow<'static, str>>,
+                    ) -> $serviceName<#{OpenApiService}<S>> {
+                        let layer = #{OpenApiLayer}::new(path, crate::openapi::OPENAPI_JSON);
+                        $serviceName {
+                            svc: #{Tower}::Layer::layer(&layer, self.svc),
+                        }
+                    }
+                }
+                """,
+                "OpenApiLayer" to smithyHttpServer.resolve("layer::openapi::OpenApiLayer"),
+                "OpenApiService" to smithyHttpServer.resolve("layer::openapi::OpenApiService"),
+                "Tower" to RuntimeType.Tower,
+            )
+        }
+    }
+}
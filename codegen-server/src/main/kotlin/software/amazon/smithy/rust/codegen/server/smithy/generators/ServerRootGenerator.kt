@@ -254,6 +254,13 @@ open class ServerRootGenerator(
             } else {
                 ""
             }
+        // Only export the build error if the routes of the service can conflict.
+        val buildErrorReExport =
+            if (protocol.routeConflictsError() != null) {
+                "BuildError,"
+            } else {
+                ""
+            }
         rustWriter.rust(
             """
             pub use crate::service::{
@@ -262,6 +269,7 @@ open class ServerRootGenerator(
                 ${serviceName}ConfigBuilder,
                 $configErrorReExport
                 ${serviceName}Builder,
+                $buildErrorReExport
                 MissingOperationsError
             };
             """,
@@ -55,6 +55,12 @@ class ServerServiceGenerator(
     private val serviceName = serviceId.name.toPascalCase()
     private val builderName = "${serviceName}Builder"
 
+    /** The error returned by the router when the routes of the service conflict, if they can. */
+    private val routeConflictsError = protocol.routeConflictsError()
+
+    /** The error returned by the builder's `build` method. */
+    private val buildErrorName = if (routeConflictsError != null) "BuildError" else "MissingOperationsError"
+
     /** Calculate all `operationShape`s contained within the `ServiceShape`. */
     private val index = TopDownIndex.of(codegenContext.model)
     private val operations = index.getContainedOperations(codegenContext.serviceShape).toSortedSet(compareBy { it.id })
@@ -274,6 +280,7 @@ class ServerServiceGenerator(
         writable {
             val missingOperationsVariableName = "missing_operation_names"
             val expectMessageVariableName = "unexpected_error_msg"
+            val intoBuildError = if (routeConflictsError != null) ".into()" else ""
 
             val nullabilityChecks =
                 writable {
@@ -307,6 +314,7 @@ class ServerServiceGenerator(
                 /// Constructs a [`$serviceName`] from the arguments provided to the builder.
                 ///
                 /// Forgetting to register a handler for one or more operations will result in an error.
+                #{RouteConflictsDocs:W}
                 ///
                 /// Check out [`$builderName::build_unchecked`] if you'd prefer the service to return status code 500 when an
                 /// unspecified route is requested.
@@ -317,7 +325,7 @@ class ServerServiceGenerator(
                             #{Protocol},
                         >,
                     >,
-                    MissingOperationsError,
+                    $buildErrorName,
                 >
                 where
                     L: #{Tower}::Layer<#{SmithyHttpServer}::routing::Route<Body>>,
@@ -329,13 +337,15 @@ class ServerServiceGenerator(
                         if !$missingOperationsVariableName.is_empty() {
                             return Err(MissingOperationsError {
                                 operation_names2setter_methods: $missingOperationsVariableName,
-                            });
+                            }$intoBuildError);
                         }
                         let $expectMessageVariableName = "this should never panic since we are supposed to check beforehand that a handler has been registered for this operation; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues";
 
                         #{PatternInitializations:W}
 
-                        #{Router}::from_iter([#{RoutesArrayElements:W}])
+                        let router = #{Router}::from_iter([#{RoutesArrayElements:W}]);
+                        #{CheckRouteConflicts:W}
+                        router
                     };
                     let svc = #{SmithyHttpServer}::routing::RoutingService::new(router);
                     let svc = svc.map(|s| s.layer(self.layer));
@@ -348,6 +358,24 @@ class ServerServiceGenerator(
                 "NullabilityChecks" to nullabilityChecks,
                 "RoutesArrayElements" to routesArrayElements,
                 "PatternInitializations" to patternInitializations(),
+                "RouteConflictsDocs" to
+                    writable {
+                        if (routeConflictsError != null) {
+                            rust(
+                                """
+                                ///
+                                /// Operations whose URI patterns conflict, so that some requests can't be routed to the operation
+                                /// they're meant for, will result in an error too.
+                                """,
+                            )
+                        }
+                    },
+                "CheckRouteConflicts" to
+                    writable {
+                        if (routeConflictsError != null) {
+                            rust("router.check_conflicts()?;")
+                        }
+                    },
             )
         }
 
@@ -603,6 +631,22 @@ class ServerServiceGenerator(
                         >,
                     >
                 {
+                    /// Returns the router of the service, e.g. to list its routes.
+                    pub fn router(&self) -> &#{Router}<S> {
+                        self.svc.router()
+                    }
+
+                    /// Maps the router of the service, e.g. to change the maximum request body size of the AWS Query
+                    /// and EC2 Query routers, which buffer the body before routing.
+                    pub fn map_router<F>(self, f: F) -> Self
+                    where
+                        F: FnOnce(#{Router}<S>) -> #{Router}<S>,
+                    {
+                        $serviceName {
+                            svc: self.svc.map(f),
+                        }
+                    }
+
                     /// Applies a [`Layer`](#{Tower}::Layer) uniformly to all routes.
                     ##[deprecated(
                         since = "0.57.0",
@@ -714,6 +758,50 @@ class ServerServiceGenerator(
             )
         }
 
+    private fun buildError(): Writable =
+        writable {
+            if (routeConflictsError == null) {
+                return@writable
+            }
+            rustTemplate(
+                """
+                /// The error encountered when calling the [`$builderName::build`] method.
+                ##[derive(Debug)]
+                pub enum BuildError {
+                    /// One or more operation handlers are not specified.
+                    MissingOperations(MissingOperationsError),
+                    /// The URI patterns of some operations conflict, so some requests can't be routed to the operation
+                    /// they're meant for.
+                    RouteConflicts(#{RouteConflictsError}),
+                }
+
+                impl std::fmt::Display for BuildError {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        match self {
+                            BuildError::MissingOperations(error) => std::fmt::Display::fmt(error, f),
+                            BuildError::RouteConflicts(error) => std::fmt::Display::fmt(error, f),
+                        }
+                    }
+                }
+
+                impl std::error::Error for BuildError {}
+
+                impl From<MissingOperationsError> for BuildError {
+                    fn from(error: MissingOperationsError) -> Self {
+                        BuildError::MissingOperations(error)
+                    }
+                }
+
+                impl From<#{RouteConflictsError}> for BuildError {
+                    fn from(error: #{RouteConflictsError}) -> Self {
+                        BuildError::RouteConflicts(error)
+                    }
+                }
+                """,
+                "RouteConflictsError" to routeConflictsError,
+            )
+        }
+
     private fun serviceShapeImpl(): Writable =
         writable {
             val namespace = serviceId.namespace
@@ -795,6 +883,8 @@ class ServerServiceGenerator(
 
             #{MissingOperationsError:W}
 
+            #{BuildError:W}
+
             #{RequestSpecs:W}
 
             #{Struct:W}
@@ -805,6 +895,7 @@ class ServerServiceGenerator(
             """,
             "Builder" to builder(),
             "MissingOperationsError" to missingOperationsError(),
+            "BuildError" to buildError(),
             "RequestSpecs" to requestSpecsModule(),
             "Struct" to serviceStruct(),
             "Operations" to operationEnum(),
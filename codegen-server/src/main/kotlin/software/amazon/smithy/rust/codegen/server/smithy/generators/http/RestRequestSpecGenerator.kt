@@ -10,18 +10,25 @@ import software.amazon.smithy.rust.codegen.core.rustlang.Writable
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.withBlock
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpBindingResolver
+import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.server.smithy.ServerCargoDependency
 
 /**
  * [RestRequestSpecGenerator] generates a restJson1 or restXml specific `RequestSpec`. Both protocols are routed the same.
+ * The `RequestSpec` carries the operation's shape ID, so that the router can name operations when listing its routes.
  */
 class RestRequestSpecGenerator(
     private val httpBindingResolver: HttpBindingResolver,
     private val requestSpecModule: RuntimeType,
+    private val runtimeConfig: RuntimeConfig,
 ) {
     fun generate(operationShape: OperationShape): Writable {
         val httpTrait = httpBindingResolver.httpTrait(operationShape)
+        val operationId = operationShape.id
+        val operationIdAbsolute = operationId.toString().replace("#", "##")
         val extraCodegenScope =
             arrayOf(
                 "RequestSpec",
@@ -81,11 +88,13 @@ class RestRequestSpecGenerator(
                         )
                     ),
                 )
+                .with_shape_id(#{ShapeId}::new(${operationIdAbsolute.dq()}, ${operationId.namespace.dq()}, ${operationId.name.dq()}))
                 """,
                 *extraCodegenScope,
                 "PathSegmentsVec" to pathSegmentsVec,
                 "QuerySegmentsVec" to querySegmentsVec,
                 "Method" to RuntimeType.Http.resolve("Method"),
+                "ShapeId" to ServerCargoDependency.smithyHttpServer(runtimeConfig).toType().resolve("shape_id::ShapeId"),
             )
         }
     }
@@ -17,14 +17,24 @@ import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.AwsJson
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.AwsJsonVersion
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.AwsQueryProtocol
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.Ec2QueryProtocol
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpBindingResolver
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.Protocol
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.RestJson
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.RestXml
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.RpcV2Cbor
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.awsJsonFieldName
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.AwsQueryRequestParserGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.CborParserCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.CborParserGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.CborParserSection
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.Ec2QueryRequestParserGenerator
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.JsonParserCustomization
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.JsonParserGenerator
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.JsonParserSection
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.QueryParserCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.QueryParserSection
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.ReturnSymbolToParse
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.StructuredDataParserGenerator
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.restJsonFieldName
@@ -35,7 +45,10 @@ import software.amazon.smithy.rust.codegen.server.smithy.ServerRuntimeType
 import software.amazon.smithy.rust.codegen.server.smithy.canReachConstrainedShape
 import software.amazon.smithy.rust.codegen.server.smithy.generators.http.RestRequestSpecGenerator
 import software.amazon.smithy.rust.codegen.server.smithy.protocols.ServerAwsJsonSerializerGenerator
+import software.amazon.smithy.rust.codegen.server.smithy.protocols.ServerAwsQuerySerializerGenerator
+import software.amazon.smithy.rust.codegen.server.smithy.protocols.ServerEc2QuerySerializerGenerator
 import software.amazon.smithy.rust.codegen.server.smithy.protocols.ServerRestJsonSerializerGenerator
+import software.amazon.smithy.rust.codegen.server.smithy.protocols.ServerRpcV2CborSerializerGenerator
 import software.amazon.smithy.rust.codegen.server.smithy.targetCanReachConstrainedShape
 
 interface ServerProtocol : Protocol {
@@ -48,6 +61,12 @@ interface ServerProtocol : Protocol {
     /** Returns the Rust router type. */
     fun routerType(): RuntimeType
 
+    /**
+     * Returns the error returned by the router's `check_conflicts` method, or `null` if the routes of the protocol
+     * can't conflict.
+     */
+    fun routeConflictsError(): RuntimeType? = null
+
     /**
      * Returns the name of the constructor to be used on the `Router` type, to instantiate a `Router` using this
      * protocol.
@@ -191,6 +210,10 @@ private fun restRouterType(runtimeConfig: RuntimeConfig) =
     ServerCargoDependency.smithyHttpServer(runtimeConfig).toType()
         .resolve("protocol::rest::router::RestRouter")
 
+private fun restRouteConflictsError(runtimeConfig: RuntimeConfig) =
+    ServerCargoDependency.smithyHttpServer(runtimeConfig).toType()
+        .resolve("protocol::rest::router::RouteConflictsError")
+
 class ServerRestJsonProtocol(
     private val serverCodegenContext: ServerCodegenContext,
     private val additionalParserCustomizations: List<JsonParserCustomization> = listOf(),
@@ -214,12 +237,14 @@ class ServerRestJsonProtocol(
 
     override fun routerType() = restRouterType(runtimeConfig)
 
+    override fun routeConflictsError() = restRouteConflictsError(runtimeConfig)
+
     override fun serverRouterRequestSpec(
         operationShape: OperationShape,
         operationName: String,
         serviceName: String,
         requestSpecModule: RuntimeType,
-    ): Writable = RestRequestSpecGenerator(httpBindingResolver, requestSpecModule).generate(operationShape)
+    ): Writable = RestRequestSpecGenerator(httpBindingResolver, requestSpecModule, runtimeConfig).generate(operationShape)
 
     override fun serverRouterRequestSpecType(requestSpecModule: RuntimeType): RuntimeType =
         requestSpecModule.resolve("RequestSpec")
@@ -239,12 +264,14 @@ class ServerRestXmlProtocol(
 
     override fun routerType() = restRouterType(runtimeConfig)
 
+    override fun routeConflictsError() = restRouteConflictsError(runtimeConfig)
+
     override fun serverRouterRequestSpec(
         operationShape: OperationShape,
         operationName: String,
         serviceName: String,
         requestSpecModule: RuntimeType,
-    ): Writable = RestRequestSpecGenerator(httpBindingResolver, requestSpecModule).generate(operationShape)
+    ): Writable = RestRequestSpecGenerator(httpBindingResolver, requestSpecModule, runtimeConfig).generate(operationShape)
 
     override fun serverRouterRequestSpecType(requestSpecModule: RuntimeType): RuntimeType =
         requestSpecModule.resolve("RequestSpec")
@@ -254,6 +281,190 @@ class ServerRestXmlProtocol(
     override fun serverContentTypeCheckNoModeledInput() = true
 }
 
+/**
+ * The `rpcv2Cbor` protocol is gated behind the `rpc-v2-cbor` feature of `aws-smithy-http-server`.
+ */
+private fun smithyHttpServerWithRpcV2Cbor(runtimeConfig: RuntimeConfig) =
+    ServerCargoDependency.smithyHttpServer(runtimeConfig).withFeature("rpc-v2-cbor").toType()
+
+class ServerRpcV2CborProtocol(
+    private val serverCodegenContext: ServerCodegenContext,
+) : RpcV2Cbor(serverCodegenContext), ServerProtocol {
+    val runtimeConfig = codegenContext.runtimeConfig
+
+    override val protocolModulePath = "rpc_v2_cbor"
+
+    override fun structuredDataParser(): StructuredDataParserGenerator =
+        CborParserGenerator(
+            serverCodegenContext,
+            httpBindingResolver,
+            returnSymbolToParseFn(serverCodegenContext),
+            listOf(
+                ServerRequestBeforeBoxingDeserializedMemberConvertToMaybeConstrainedCborParserCustomization(
+                    serverCodegenContext,
+                ),
+            ),
+        )
+
+    override fun structuredDataSerializer(): StructuredDataSerializerGenerator =
+        ServerRpcV2CborSerializerGenerator(serverCodegenContext, httpBindingResolver)
+
+    override fun markerStruct() =
+        smithyHttpServerWithRpcV2Cbor(runtimeConfig).resolve("protocol::rpc_v2_cbor::RpcV2Cbor")
+
+    override fun routerType() =
+        smithyHttpServerWithRpcV2Cbor(runtimeConfig).resolve("protocol::rpc_v2_cbor::router::RpcV2CborRouter")
+
+    override fun requestRejection(runtimeConfig: RuntimeConfig): RuntimeType =
+        smithyHttpServerWithRpcV2Cbor(runtimeConfig).resolve("protocol::rpc_v2_cbor::rejection::RequestRejection")
+
+    override fun responseRejection(runtimeConfig: RuntimeConfig): RuntimeType =
+        smithyHttpServerWithRpcV2Cbor(runtimeConfig).resolve("protocol::rpc_v2_cbor::rejection::ResponseRejection")
+
+    override fun runtimeError(runtimeConfig: RuntimeConfig): RuntimeType =
+        smithyHttpServerWithRpcV2Cbor(runtimeConfig).resolve("protocol::rpc_v2_cbor::runtime_error::RuntimeError")
+
+    /**
+     * Returns the service and operation names, which the router matches against the
+     * `/service/{serviceName}/operation/{operationName}` path of requests.
+     */
+    override fun serverRouterRequestSpec(
+        operationShape: OperationShape,
+        operationName: String,
+        serviceName: String,
+        requestSpecModule: RuntimeType,
+    ) = writable {
+        rust("""String::from("$serviceName.$operationName")""")
+    }
+
+    override fun serverRouterRequestSpecType(requestSpecModule: RuntimeType): RuntimeType = RuntimeType.String
+
+    override fun serverRouterRuntimeConstructor() = "new_rpc_v2_cbor_router"
+}
+
+/**
+ * The `awsQuery` and `ec2Query` protocols are gated behind the `query-protocols` feature of `aws-smithy-http-server`.
+ */
+private fun smithyHttpServerWithQueryProtocols(runtimeConfig: RuntimeConfig) =
+    ServerCargoDependency.smithyHttpServer(runtimeConfig).withFeature("query-protocols").toType()
+
+private fun queryRouterType(runtimeConfig: RuntimeConfig) =
+    smithyHttpServerWithQueryProtocols(runtimeConfig).resolve("protocol::query::router::QueryRouter")
+
+/**
+ * The rejection and runtime error types of the `awsQuery` and `ec2Query` protocols, which are shared by both.
+ */
+private fun queryRuntimeType(
+    runtimeConfig: RuntimeConfig,
+    path: String,
+) = smithyHttpServerWithQueryProtocols(runtimeConfig).resolve("protocol::query::$path")
+
+class ServerAwsQueryProtocol(
+    private val serverCodegenContext: ServerCodegenContext,
+) : AwsQueryProtocol(serverCodegenContext), ServerProtocol {
+    private val runtimeConfig = serverCodegenContext.runtimeConfig
+
+    override val protocolModulePath = "aws_query"
+
+    override fun structuredDataParser(): StructuredDataParserGenerator =
+        AwsQueryRequestParserGenerator(
+            serverCodegenContext,
+            httpBindingResolver,
+            returnSymbolToParseFn(serverCodegenContext),
+            listOf(
+                ServerRequestBeforeBoxingDeserializedMemberConvertToMaybeConstrainedQueryParserCustomization(
+                    serverCodegenContext,
+                ),
+            ),
+        )
+
+    override fun structuredDataSerializer(): StructuredDataSerializerGenerator =
+        ServerAwsQuerySerializerGenerator(serverCodegenContext, httpBindingResolver)
+
+    override fun markerStruct() =
+        smithyHttpServerWithQueryProtocols(runtimeConfig).resolve("protocol::$protocolModulePath::AwsQuery")
+
+    override fun routerType() = queryRouterType(runtimeConfig)
+
+    /**
+     * Returns the operation name, which the router matches against the `Action` parameter of requests.
+     */
+    override fun serverRouterRequestSpec(
+        operationShape: OperationShape,
+        operationName: String,
+        serviceName: String,
+        requestSpecModule: RuntimeType,
+    ) = writable {
+        rust("""String::from("$operationName")""")
+    }
+
+    override fun serverRouterRequestSpecType(requestSpecModule: RuntimeType): RuntimeType = RuntimeType.String
+
+    override fun serverRouterRuntimeConstructor() = "new_aws_query_router"
+
+    override fun requestRejection(runtimeConfig: RuntimeConfig): RuntimeType =
+        queryRuntimeType(runtimeConfig, "rejection::RequestRejection")
+
+    override fun responseRejection(runtimeConfig: RuntimeConfig): RuntimeType =
+        queryRuntimeType(runtimeConfig, "rejection::ResponseRejection")
+
+    override fun runtimeError(runtimeConfig: RuntimeConfig): RuntimeType =
+        queryRuntimeType(runtimeConfig, "runtime_error::RuntimeError")
+}
+
+class ServerEc2QueryProtocol(
+    private val serverCodegenContext: ServerCodegenContext,
+) : Ec2QueryProtocol(serverCodegenContext), ServerProtocol {
+    private val runtimeConfig = serverCodegenContext.runtimeConfig
+
+    override val protocolModulePath = "ec2_query"
+
+    override fun structuredDataParser(): StructuredDataParserGenerator =
+        Ec2QueryRequestParserGenerator(
+            serverCodegenContext,
+            httpBindingResolver,
+            returnSymbolToParseFn(serverCodegenContext),
+            listOf(
+                ServerRequestBeforeBoxingDeserializedMemberConvertToMaybeConstrainedQueryParserCustomization(
+                    serverCodegenContext,
+                ),
+            ),
+        )
+
+    override fun structuredDataSerializer(): StructuredDataSerializerGenerator =
+        ServerEc2QuerySerializerGenerator(serverCodegenContext, httpBindingResolver)
+
+    override fun markerStruct() =
+        smithyHttpServerWithQueryProtocols(runtimeConfig).resolve("protocol::$protocolModulePath::Ec2Query")
+
+    override fun routerType() = queryRouterType(runtimeConfig)
+
+    /**
+     * Returns the operation name, which the router matches against the `Action` parameter of requests.
+     */
+    override fun serverRouterRequestSpec(
+        operationShape: OperationShape,
+        operationName: String,
+        serviceName: String,
+        requestSpecModule: RuntimeType,
+    ) = writable {
+        rust("""String::from("$operationName")""")
+    }
+
+    override fun serverRouterRequestSpecType(requestSpecModule: RuntimeType): RuntimeType = RuntimeType.String
+
+    override fun serverRouterRuntimeConstructor() = "new_ec2_query_router"
+
+    override fun requestRejection(runtimeConfig: RuntimeConfig): RuntimeType =
+        queryRuntimeType(runtimeConfig, "rejection::RequestRejection")
+
+    override fun responseRejection(runtimeConfig: RuntimeConfig): RuntimeType =
+        queryRuntimeType(runtimeConfig, "rejection::ResponseRejection")
+
+    override fun runtimeError(runtimeConfig: RuntimeConfig): RuntimeType =
+        queryRuntimeType(runtimeConfig, "runtime_error::RuntimeError")
+}
+
 /**
  * A customization to, just before we box a recursive member that we've deserialized into `Option<T>`, convert it into
  * `MaybeConstrained` if the target shape can reach a constrained shape.
@@ -276,3 +487,45 @@ class ServerRequestBeforeBoxingDeserializedMemberConvertToMaybeConstrainedJsonPa
             else -> emptySection
         }
 }
+
+/**
+ * A customization to, just before we box a recursive member that we've deserialized from CBOR, convert it into
+ * `MaybeConstrained` if the target shape can reach a constrained shape.
+ */
+class ServerRequestBeforeBoxingDeserializedMemberConvertToMaybeConstrainedCborParserCustomization(val codegenContext: ServerCodegenContext) :
+    CborParserCustomization() {
+    override fun section(section: CborParserSection): Writable =
+        when (section) {
+            is CborParserSection.BeforeBoxingDeserializedMember ->
+                writable {
+                    // We're only interested in _structure_ member shapes that can reach constrained shapes.
+                    if (
+                        codegenContext.model.expectShape(section.shape.container) is StructureShape &&
+                        section.shape.targetCanReachConstrainedShape(codegenContext.model, codegenContext.symbolProvider)
+                    ) {
+                        rust(".into()")
+                    }
+                }
+        }
+}
+
+/**
+ * A customization to, just before we box a recursive member that we've deserialized from a query, convert it into
+ * `MaybeConstrained` if the target shape can reach a constrained shape.
+ */
+class ServerRequestBeforeBoxingDeserializedMemberConvertToMaybeConstrainedQueryParserCustomization(val codegenContext: ServerCodegenContext) :
+    QueryParserCustomization() {
+    override fun section(section: QueryParserSection): Writable =
+        when (section) {
+            is QueryParserSection.BeforeBoxingDeserializedMember ->
+                writable {
+                    // We're only interested in _structure_ member shapes that can reach constrained shapes.
+                    if (
+                        codegenContext.model.expectShape(section.shape.container) is StructureShape &&
+                        section.shape.targetCanReachConstrainedShape(codegenContext.model, codegenContext.symbolProvider)
+                    ) {
+                        rust(".into()")
+                    }
+                }
+        }
+}
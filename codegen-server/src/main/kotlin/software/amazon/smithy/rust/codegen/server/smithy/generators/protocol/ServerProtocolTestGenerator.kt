@@ -309,7 +309,7 @@ class ServerProtocolTestGenerator(
         }
 
         with(httpRequestTestCase) {
-            renderHttpRequest(uri, method, headers, body.orNull(), queryParams, host.orNull())
+            renderHttpRequest(uri, method, headers, body.orNull(), bodyMediaType.orNull(), queryParams, host.orNull())
         }
         if (protocolSupport.requestBodyDeserialization) {
             makeRequest(operationShape, operationSymbol, this, checkRequestHandler(operationShape, httpRequestTestCase))
@@ -387,7 +387,7 @@ class ServerProtocolTestGenerator(
         rustBlock("") {
             with(testCase.request) {
                 // TODO(https://github.com/awslabs/smithy/issues/1102): `uri` should probably not be an `Optional`.
-                renderHttpRequest(uri.get(), method, headers, body.orNull(), queryParams, host.orNull())
+                renderHttpRequest(uri.get(), method, headers, body.orNull(), null, queryParams, host.orNull())
             }
 
             makeRequest(
@@ -405,6 +405,7 @@ class ServerProtocolTestGenerator(
         method: String,
         headers: Map<String, String>,
         body: String?,
+        bodyMediaType: String?,
         queryParams: List<String>,
         host: String?,
     ) {
@@ -434,14 +435,18 @@ class ServerProtocolTestGenerator(
                     //
                     // We also escape to avoid interactions with templating in the case where the body contains `#`.
                     val sanitizedBody = escape(body.replace("\u000c", "\\u{000c}")).dq()
+                    // Bodies of binary media types are base64 encoded in the protocol test, and need to be decoded first.
+                    val mediaType = (bodyMediaType ?: "unknown").dq()
 
-                    "#{SmithyHttpServer}::body::Body::from(#{Bytes}::from_static($sanitizedBody.as_bytes()))"
+                    "#{SmithyHttpServer}::body::Body::from(#{decode_body_data}($sanitizedBody.as_bytes(), #{MediaType}::from($mediaType)).into_owned())"
                 } else {
                     "#{SmithyHttpServer}::body::Body::empty()"
                 }
             }).unwrap();
             """,
             *codegenScope,
+            "decode_body_data" to RuntimeType.protocolTest(codegenContext.runtimeConfig, "decode_body_data"),
+            "MediaType" to RuntimeType.protocolTest(codegenContext.runtimeConfig, "MediaType"),
         )
         if (queryParams.isNotEmpty()) {
             val queryParamsString = queryParams.joinToString(separator = "&")
@@ -805,6 +810,9 @@ class ServerProtocolTestGenerator(
         private const val AwsJson11 = "aws.protocoltests.json#JsonProtocol"
         private const val AwsJson10 = "aws.protocoltests.json10#JsonRpc10"
         private const val RestJson = "aws.protocoltests.restjson#RestJson"
+        private const val RestXml = "aws.protocoltests.restxml#RestXml"
+        private const val AwsQuery = "aws.protocoltests.query#AwsQuery"
+        private const val Ec2Query = "aws.protocoltests.ec2#AwsEc2"
         private const val RestJsonExtras = "aws.protocoltests.restjson#RestJsonExtras"
         private const val RestJsonValidation = "aws.protocoltests.restjson.validation#RestJsonValidation"
         private val ExpectFail: Set<FailingTest> =
@@ -892,6 +900,20 @@ class ServerProtocolTestGenerator(
                 // Response defaults are not set when builders are not used https://github.com/smithy-lang/smithy-rs/issues/3339
                 FailingTest(AwsJson10, "AwsJson10ServerPopulatesDefaultsInResponseWhenMissingInParams", TestType.Response),
                 FailingTest(AwsJson10, "AwsJson10ServerPopulatesNestedDefaultValuesWhenMissingInInResponseParams", TestType.Response),
+                // The `@requestCompression` tests only define the parameters that a client compresses, and not the
+                // compressed body, so the server receives an empty body and can't deserialize the expected input.
+                FailingTest(RestJson, "SDKAppendedGzipAfterProvidedEncoding_restJson1", TestType.Request),
+                FailingTest(RestJson, "SDKAppliedContentEncoding_restJson1", TestType.Request),
+                FailingTest(RestXml, "SDKAppendedGzipAfterProvidedEncoding_restXml", TestType.Request),
+                FailingTest(RestXml, "SDKAppliedContentEncoding_restXml", TestType.Request),
+                FailingTest(AwsJson10, "SDKAppendsGzipAndIgnoresHttpProvidedEncoding_awsJson1_0", TestType.Request),
+                FailingTest(AwsJson10, "SDKAppliedContentEncoding_awsJson1_0", TestType.Request),
+                FailingTest(AwsJson11, "SDKAppendsGzipAndIgnoresHttpProvidedEncoding_awsJson1_1", TestType.Request),
+                FailingTest(AwsJson11, "SDKAppliedContentEncoding_awsJson1_1", TestType.Request),
+                FailingTest(AwsQuery, "SDKAppendsGzipAndIgnoresHttpProvidedEncoding_awsQuery", TestType.Request),
+                FailingTest(AwsQuery, "SDKAppliedContentEncoding_awsQuery", TestType.Request),
+                FailingTest(Ec2Query, "SDKAppendsGzipAndIgnoresHttpProvidedEncoding_ec2Query", TestType.Request),
+                FailingTest(Ec2Query, "SDKAppliedContentEncoding_ec2Query", TestType.Request),
             )
         private val RunOnly: Set<String>? = null
 
@@ -899,19 +921,6 @@ class ServerProtocolTestGenerator(
         // or because they are flaky
         private val DisableTests =
             setOf<String>(
-                // TODO(https://github.com/smithy-lang/smithy-rs/issues/2891): Implement support for `@requestCompression`
-                "SDKAppendedGzipAfterProvidedEncoding_restJson1",
-                "SDKAppendedGzipAfterProvidedEncoding_restXml",
-                "SDKAppendsGzipAndIgnoresHttpProvidedEncoding_awsJson1_0",
-                "SDKAppendsGzipAndIgnoresHttpProvidedEncoding_awsJson1_1",
-                "SDKAppendsGzipAndIgnoresHttpProvidedEncoding_awsQuery",
-                "SDKAppendsGzipAndIgnoresHttpProvidedEncoding_ec2Query",
-                "SDKAppliedContentEncoding_awsJson1_0",
-                "SDKAppliedContentEncoding_awsJson1_1",
-                "SDKAppliedContentEncoding_awsQuery",
-                "SDKAppliedContentEncoding_ec2Query",
-                "SDKAppliedContentEncoding_restJson1",
-                "SDKAppliedContentEncoding_restXml",
                 // RestXml S3 tests that fail to compile
                 "S3EscapeObjectKeyInUriLabel",
                 "S3EscapePathObjectKeyInUriLabel",
@@ -7,6 +7,7 @@ package software.amazon.smithy.rust.codegen.server.smithy.protocols
 
 import software.amazon.smithy.aws.traits.protocols.AwsJson1_0Trait
 import software.amazon.smithy.aws.traits.protocols.AwsJson1_1Trait
+import software.amazon.smithy.aws.traits.protocols.AwsQueryErrorTrait
 import software.amazon.smithy.aws.traits.protocols.RestJson1Trait
 import software.amazon.smithy.aws.traits.protocols.RestXmlTrait
 import software.amazon.smithy.codegen.core.Symbol
@@ -27,6 +28,7 @@ import software.amazon.smithy.model.traits.HttpErrorTrait
 import software.amazon.smithy.model.traits.HttpPayloadTrait
 import software.amazon.smithy.model.traits.HttpTrait
 import software.amazon.smithy.model.traits.MediaTypeTrait
+import software.amazon.smithy.protocol.traits.Rpcv2CborTrait
 import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
 import software.amazon.smithy.rust.codegen.core.rustlang.RustType
 import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
@@ -77,6 +79,7 @@ import software.amazon.smithy.rust.codegen.server.smithy.canReachConstrainedShap
 import software.amazon.smithy.rust.codegen.server.smithy.generators.ServerBuilderGenerator
 import software.amazon.smithy.rust.codegen.server.smithy.generators.http.ServerRequestBindingGenerator
 import software.amazon.smithy.rust.codegen.server.smithy.generators.http.ServerResponseBindingGenerator
+import software.amazon.smithy.rust.codegen.server.smithy.generators.protocol.ServerAwsQueryProtocol
 import software.amazon.smithy.rust.codegen.server.smithy.generators.protocol.ServerProtocol
 import software.amazon.smithy.rust.codegen.server.smithy.generators.protocol.ServerProtocolGenerator
 import software.amazon.smithy.rust.codegen.server.smithy.generators.serverBuilderSymbol
@@ -524,6 +527,8 @@ class ServerHttpBoundProtocolTraitImplGenerator(
                     }
                     val status =
                         variantShape.getTrait<HttpErrorTrait>()?.code
+                            ?: variantShape.getTrait<AwsQueryErrorTrait>()
+                                ?.takeIf { protocol is ServerAwsQueryProtocol }?.httpResponseCode
                             ?: errorTrait.defaultHttpStatusCode
 
                     serverRenderContentLengthHeader()
@@ -612,7 +617,8 @@ class ServerHttpBoundProtocolTraitImplGenerator(
      * It sets three groups of headers in order. Headers from one group take precedence over headers in a later group.
      *     1. Headers bound by the `httpHeader` and `httpPrefixHeader` traits. = null
      *     2. The protocol-specific `Content-Type` header for the operation.
-     *     3. Additional protocol-specific headers for errors, if [errorShape] is non-null.
+     *     3. Additional protocol-specific headers for errors, if [errorShape] is non-null, or for the operation's
+     *        output otherwise.
      */
     private fun RustWriter.serverRenderResponseHeaders(
         operationShape: OperationShape,
@@ -649,19 +655,23 @@ class ServerHttpBoundProtocolTraitImplGenerator(
             )
         }
 
-        if (errorShape != null) {
-            for ((headerName, headerValue) in protocol.additionalErrorResponseHeaders(errorShape)) {
-                rustTemplate(
-                    """
-                    builder = #{header_util}::set_response_header_if_absent(
-                        builder,
-                        http::header::HeaderName::from_static("$headerName"),
-                        "${escape(headerValue)}"
-                    );
-                    """,
-                    *codegenScope,
-                )
+        val additionalHeaders =
+            if (errorShape != null) {
+                protocol.additionalErrorResponseHeaders(errorShape)
+            } else {
+                protocol.additionalResponseHeaders(operationShape)
             }
+        for ((headerName, headerValue) in additionalHeaders) {
+            rustTemplate(
+                """
+                builder = #{header_util}::set_response_header_if_absent(
+                    builder,
+                    http::header::HeaderName::from_static("$headerName"),
+                    "${escape(headerValue)}"
+                );
+                """,
+                *codegenScope,
+            )
         }
     }
 
@@ -1313,6 +1323,9 @@ class ServerHttpBoundProtocolTraitImplGenerator(
             RestXmlTrait.ID -> {
                 RuntimeType.smithyXml(runtimeConfig).resolve("decode::XmlDecodeError").toSymbol()
             }
+            Rpcv2CborTrait.ID -> {
+                RuntimeType.smithyCbor(runtimeConfig).resolve("decode::DeserializeError").toSymbol()
+            }
             else -> {
                 TODO("Protocol ${codegenContext.protocol} not supported yet")
             }
@@ -7,8 +7,11 @@ package software.amazon.smithy.rust.codegen.server.smithy.protocols
 
 import software.amazon.smithy.aws.traits.protocols.AwsJson1_0Trait
 import software.amazon.smithy.aws.traits.protocols.AwsJson1_1Trait
+import software.amazon.smithy.aws.traits.protocols.AwsQueryTrait
+import software.amazon.smithy.aws.traits.protocols.Ec2QueryTrait
 import software.amazon.smithy.aws.traits.protocols.RestJson1Trait
 import software.amazon.smithy.aws.traits.protocols.RestXmlTrait
+import software.amazon.smithy.protocol.traits.Rpcv2CborTrait
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
 import software.amazon.smithy.rust.codegen.core.rustlang.withBlockTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
@@ -79,6 +82,12 @@ class ServerProtocolLoader(supportedProtocols: ProtocolMap<ServerProtocolGenerat
                         AwsJsonVersion.Json11,
                         additionalServerHttpBoundProtocolCustomizations = listOf(StreamPayloadSerializerCustomization()),
                     ),
+                Rpcv2CborTrait.ID to
+                    ServerRpcV2CborFactory(
+                        additionalServerHttpBoundProtocolCustomizations = listOf(StreamPayloadSerializerCustomization()),
+                    ),
+                AwsQueryTrait.ID to ServerAwsQueryFactory(),
+                Ec2QueryTrait.ID to ServerEc2QueryFactory(),
             )
     }
 }
@@ -0,0 +1,167 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.server.smithy.protocols
+
+import software.amazon.smithy.model.traits.ErrorTrait
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.core.rustlang.escape
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.smithy.generators.http.HttpBindingCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.generators.protocol.ProtocolSupport
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpBindingResolver
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.ProtocolGeneratorFactory
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize.StructuredDataSerializerGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize.XmlBindingTraitSerializerGenerator
+import software.amazon.smithy.rust.codegen.core.util.expectTrait
+import software.amazon.smithy.rust.codegen.server.smithy.ServerCodegenContext
+import software.amazon.smithy.rust.codegen.server.smithy.generators.protocol.ServerAwsQueryProtocol
+import software.amazon.smithy.rust.codegen.server.smithy.generators.protocol.ServerEc2QueryProtocol
+import software.amazon.smithy.rust.codegen.server.smithy.generators.protocol.ServerProtocol
+
+private val querySupport =
+    ProtocolSupport(
+        // Client support
+        requestSerialization = false,
+        requestBodySerialization = false,
+        responseDeserialization = false,
+        errorDeserialization = false,
+        // Server support
+        requestDeserialization = true,
+        requestBodyDeserialization = true,
+        responseSerialization = true,
+        errorSerialization = true,
+    )
+
+/**
+ * AWS Query server-side protocol factory. This factory creates the [ServerHttpBoundProtocolGenerator]
+ * with AWS Query specific configurations.
+ */
+class ServerAwsQueryFactory(
+    private val additionalServerHttpBoundProtocolCustomizations: List<ServerHttpBoundProtocolCustomization> = listOf(),
+    private val additionalHttpBindingCustomizations: List<HttpBindingCustomization> = listOf(),
+) : ProtocolGeneratorFactory<ServerHttpBoundProtocolGenerator, ServerCodegenContext> {
+    override fun protocol(codegenContext: ServerCodegenContext): ServerProtocol = ServerAwsQueryProtocol(codegenContext)
+
+    override fun buildProtocolGenerator(codegenContext: ServerCodegenContext): ServerHttpBoundProtocolGenerator =
+        ServerHttpBoundProtocolGenerator(
+            codegenContext,
+            protocol(codegenContext),
+            additionalServerHttpBoundProtocolCustomizations,
+            additionalHttpBindingCustomizations,
+        )
+
+    override fun support(): ProtocolSupport = querySupport
+}
+
+/**
+ * EC2 Query server-side protocol factory. This factory creates the [ServerHttpBoundProtocolGenerator]
+ * with EC2 Query specific configurations.
+ */
+class ServerEc2QueryFactory(
+    private val additionalServerHttpBoundProtocolCustomizations: List<ServerHttpBoundProtocolCustomization> = listOf(),
+    private val additionalHttpBindingCustomizations: List<HttpBindingCustomization> = listOf(),
+) : ProtocolGeneratorFactory<ServerHttpBoundProtocolGenerator, ServerCodegenContext> {
+    override fun protocol(codegenContext: ServerCodegenContext): ServerProtocol = ServerEc2QueryProtocol(codegenContext)
+
+    override fun buildProtocolGenerator(codegenContext: ServerCodegenContext): ServerHttpBoundProtocolGenerator =
+        ServerHttpBoundProtocolGenerator(
+            codegenContext,
+            protocol(codegenContext),
+            additionalServerHttpBoundProtocolCustomizations,
+            additionalHttpBindingCustomizations,
+        )
+
+    override fun support(): ProtocolSupport = querySupport
+}
+
+/**
+ * AWS Query responses are XML documents that wrap the members of the output in `<OperationNameResponse>` and
+ * `<OperationNameResult>` elements, and the members of errors in `<ErrorResponse>` and `<Error>` elements, along with
+ * the `Type` and `Code` of the error.
+ *
+ * - https://smithy.io/2.0/aws/protocols/aws-query-protocol.html#response-serialization
+ * - https://smithy.io/2.0/aws/protocols/aws-query-protocol.html#operation-error-serialization
+ */
+class ServerAwsQuerySerializerGenerator(
+    private val codegenContext: ServerCodegenContext,
+    private val httpBindingResolver: HttpBindingResolver,
+    private val xmlBindingTraitSerializerGenerator: XmlBindingTraitSerializerGenerator =
+        XmlBindingTraitSerializerGenerator(
+            codegenContext,
+            httpBindingResolver,
+            writeOutputWrapper = { context, inner ->
+                val operationName = context.operationShape!!.id.name
+                rust(
+                    """
+                    let mut response_scope = ${context.writerName}.start_el("${operationName}Response")${context.rootNamespace}.finish();
+                    """,
+                )
+                Attribute.AllowUnusedMut.render(this)
+                rust("""let mut result_scope = response_scope.start_el("${operationName}Result").finish();""")
+                inner("result_scope")
+                rust("result_scope.finish();")
+                rust("response_scope.finish();")
+            },
+            writeErrorWrapper = { context, inner ->
+                val fault = if (context.shape.expectTrait<ErrorTrait>().isClientError) "Sender" else "Receiver"
+                val code = escape(httpBindingResolver.errorCode(context.shape))
+                rust(
+                    """
+                    let mut response_scope = ${context.writerName}.start_el("ErrorResponse")${context.rootNamespace}.finish();
+                    let mut error_scope = response_scope.start_el("Error").finish();
+                    error_scope.start_el("Type").finish().data("$fault");
+                    error_scope.start_el("Code").finish().data("$code");
+                    """,
+                )
+                inner("error_scope")
+                rust("error_scope.finish();")
+                rust("response_scope.finish();")
+            },
+        ),
+) : StructuredDataSerializerGenerator by xmlBindingTraitSerializerGenerator
+
+/**
+ * EC2 Query responses are XML documents that wrap the members of the output in an `<OperationNameResponse>` element,
+ * and the members of errors in `<Response>`, `<Errors>` and `<Error>` elements, along with the `Code` of the error.
+ *
+ * - https://smithy.io/2.0/aws/protocols/aws-ec2-query-protocol.html#response-serialization
+ * - https://smithy.io/2.0/aws/protocols/aws-ec2-query-protocol.html#operation-error-serialization
+ */
+class ServerEc2QuerySerializerGenerator(
+    private val codegenContext: ServerCodegenContext,
+    private val httpBindingResolver: HttpBindingResolver,
+    private val xmlBindingTraitSerializerGenerator: XmlBindingTraitSerializerGenerator =
+        XmlBindingTraitSerializerGenerator(
+            codegenContext,
+            httpBindingResolver,
+            writeOutputWrapper = { context, inner ->
+                val operationName = context.operationShape!!.id.name
+                Attribute.AllowUnusedMut.render(this)
+                rust(
+                    """
+                    let mut response_scope = ${context.writerName}.start_el("${operationName}Response")${context.rootNamespace}.finish();
+                    """,
+                )
+                inner("response_scope")
+                rust("response_scope.finish();")
+            },
+            writeErrorWrapper = { context, inner ->
+                val code = escape(httpBindingResolver.errorCode(context.shape))
+                rust(
+                    """
+                    let mut response_scope = ${context.writerName}.start_el("Response")${context.rootNamespace}.finish();
+                    let mut errors_scope = response_scope.start_el("Errors").finish();
+                    let mut error_scope = errors_scope.start_el("Error").finish();
+                    error_scope.start_el("Code").finish().data("$code");
+                    """,
+                )
+                inner("error_scope")
+                rust("error_scope.finish();")
+                rust("errors_scope.finish();")
+                rust("response_scope.finish();")
+            },
+        ),
+) : StructuredDataSerializerGenerator by xmlBindingTraitSerializerGenerator
@@ -0,0 +1,85 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.server.smithy.generators
+
+import io.kotest.assertions.throwables.shouldThrow
+import io.kotest.matchers.string.shouldContain
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.codegen.core.CodegenException
+import software.amazon.smithy.model.node.Node
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.testutil.IntegrationTestParams
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.testModule
+import software.amazon.smithy.rust.codegen.core.testutil.unitTest
+import software.amazon.smithy.rust.codegen.server.smithy.testutil.serverIntegrationTest
+import java.io.File
+
+internal class ServerOpenApiGeneratorTest {
+    private val openApiSettings =
+        IntegrationTestParams(
+            additionalSettings =
+                Node.objectNodeBuilder().withMember(
+                    "codegen",
+                    Node.objectNodeBuilder().withMember("openApi", true).build(),
+                ).build(),
+        )
+
+    @Test
+    fun `it should generate and serve the OpenAPI document of restJson1 services`() {
+        val model = File("../codegen-core/common-test-models/simple.smithy").readText().asSmithyModel()
+
+        serverIntegrationTest(model, openApiSettings) { _, rustCrate ->
+            rustCrate.testModule {
+                unitTest("openapi_document_describes_the_service") {
+                    rust(
+                        """
+                        let document = crate::openapi::OPENAPI_JSON;
+                        assert!(document.contains(r##""openapi""##));
+                        assert!(document.contains(r##""/operation""##));
+                        """,
+                    )
+                }
+
+                unitTest("service_can_serve_openapi_document") {
+                    rust(
+                        """
+                        let config = crate::SimpleServiceConfig::builder().build();
+                        let _: crate::SimpleService<aws_smithy_http_server::layer::openapi::OpenApiService<_>> =
+                            crate::SimpleService::builder(config)
+                                .build_unchecked()
+                                .serve_openapi("/openapi.json");
+                        """,
+                    )
+                }
+            }
+        }
+    }
+
+    @Test
+    fun `it should reject services that don't use restJson1`() {
+        val model =
+            """
+            namespace test
+
+            use aws.protocols#awsJson1_0
+
+            @awsJson1_0
+            service JsonService {
+                operations: [Ping]
+            }
+
+            operation Ping {}
+            """.asSmithyModel()
+
+        val codegenException =
+            shouldThrow<CodegenException> {
+                serverIntegrationTest(model, openApiSettings) { _, _ -> }
+            }
+
+        codegenException.message.shouldContain("OpenAPI documents can only be generated for `restJson1` services")
+    }
+}
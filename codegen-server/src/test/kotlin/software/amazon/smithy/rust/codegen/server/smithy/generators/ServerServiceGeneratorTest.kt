@@ -9,6 +9,7 @@ import org.junit.jupiter.api.Test
 import software.amazon.smithy.rust.codegen.core.rustlang.rust
 import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
 import software.amazon.smithy.rust.codegen.core.testutil.testModule
+import software.amazon.smithy.rust.codegen.core.testutil.unitTest
 import software.amazon.smithy.rust.codegen.server.smithy.testutil.serverIntegrationTest
 import java.io.File
 
@@ -36,4 +37,81 @@ internal class ServerServiceGeneratorTest {
             }
         }
     }
+
+    @Test
+    fun `the routes of a built service should be listed with their operations`() {
+        val model = File("../codegen-core/common-test-models/simple.smithy").readText().asSmithyModel()
+
+        serverIntegrationTest(model) { _, rustCrate ->
+            rustCrate.testModule {
+                unitTest("routes_are_listed") {
+                    rust(
+                        """
+                        let config = crate::SimpleServiceConfig::builder().build();
+                        let service = crate::SimpleService::builder(config).build_unchecked();
+
+                        let routes: Vec<_> = service
+                            .router()
+                            .routes()
+                            .map(|route| (route.shape_id().unwrap().absolute(), route.to_string()))
+                            .collect();
+                        assert_eq!(routes, vec![("com.amazonaws.simple##Operation", String::from("POST /operation"))]);
+                        assert!(service.router().conflicts().is_empty());
+                        """,
+                    )
+                }
+            }
+        }
+    }
+
+    @Test
+    fun `building a service whose routes conflict should fail`() {
+        // Smithy rejects models with conflicting URI patterns, so validation is disabled.
+        val model =
+            """
+            namespace test
+
+            use aws.protocols#restJson1
+
+            @restJson1
+            service ConflictService {
+                operations: [First, Second],
+            }
+
+            @http(method: "GET", uri: "/conflict")
+            @readonly
+            operation First {}
+
+            @http(method: "GET", uri: "/conflict")
+            @readonly
+            operation Second {}
+            """.asSmithyModel(smithyVersion = "2", disableValidation = true)
+
+        serverIntegrationTest(model) { _, rustCrate ->
+            rustCrate.testModule {
+                unitTest("route_conflicts_are_build_errors") {
+                    rust(
+                        """
+                        let config = crate::ConflictServiceConfig::builder().build();
+                        let error = crate::ConflictService::builder(config)
+                            .first(|_input: crate::input::FirstInput| async { crate::output::FirstOutput {} })
+                            .second(|_input: crate::input::SecondInput| async { crate::output::SecondOutput {} })
+                            .build()
+                            .unwrap_err();
+
+                        let crate::BuildError::RouteConflicts(error) = error else {
+                            panic!("expected a route conflict, got {error:?}");
+                        };
+                        assert_eq!(
+                            error.conflicts(),
+                            [String::from(
+                                "route `test##Second` (GET /conflict) is shadowed by route `test##First` (GET /conflict), so no requests are routed to it",
+                            )],
+                        );
+                        """,
+                    )
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,257 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.server.smithy.protocols
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.testModule
+import software.amazon.smithy.rust.codegen.core.testutil.tokioTest
+import software.amazon.smithy.rust.codegen.server.smithy.testutil.serverIntegrationTest
+
+internal class ServerQueryTest {
+    private fun model(protocol: String) =
+        """
+        namespace test
+
+        use aws.protocols#$protocol
+        use aws.protocols#ec2QueryName
+
+        @$protocol
+        @xmlNamespace(uri: "https://example.com/")
+        service QueryService {
+            version: "2020-01-08",
+            operations: [Echo],
+        }
+
+        operation Echo {
+            input: EchoInput,
+            output: EchoOutput,
+            errors: [InvalidEcho],
+        }
+
+        structure EchoInput {
+            string: String,
+            integer: Integer,
+            boolean: Boolean,
+            list: StringList,
+            @xmlFlattened
+            flatList: StringList,
+            map: StringMap,
+            nested: Nested,
+            @ec2QueryName("Renamed")
+            @xmlName("renamed")
+            renamedMember: String,
+        }
+
+        structure EchoOutput {
+            string: String,
+        }
+
+        structure Nested {
+            value: String,
+        }
+
+        list StringList {
+            member: String,
+        }
+
+        map StringMap {
+            key: String,
+            value: String,
+        }
+
+        @error("client")
+        structure InvalidEcho {
+            message: String,
+        }
+        """.asSmithyModel(smithyVersion = "2")
+
+    /**
+     * Renders a `service` function, returning a service whose `Echo` operation echoes its `string` member, or fails
+     * with `InvalidEcho` when it is `fail`, and a `call` function that sends a request with `body` to the service.
+     */
+    private fun echoService() =
+        """
+        async fn echo(input: crate::input::EchoInput) -> Result<crate::output::EchoOutput, crate::error::EchoError> {
+            match input.string.as_deref() {
+                Some("fail") => Err(crate::error::EchoError::InvalidEcho(crate::error::InvalidEcho {
+                    message: Some("invalid".to_owned()),
+                })),
+                _ => Ok(crate::output::EchoOutput { string: input.string }),
+            }
+        }
+
+        fn service() -> crate::QueryService {
+            let config = crate::QueryServiceConfig::builder().build();
+            crate::QueryService::builder(config).echo(echo).build().unwrap()
+        }
+
+        async fn call<S>(service: S, body: &'static str) -> (http::StatusCode, String)
+        where
+            S: tower::Service<
+                http::Request<hyper::Body>,
+                Response = http::Response<aws_smithy_http_server::body::BoxBody>,
+                Error = std::convert::Infallible,
+            >,
+        {
+            use tower::ServiceExt;
+            let request = http::Request::builder()
+                .method("POST")
+                .uri("/")
+                .header("Content-Type", "application/x-www-form-urlencoded")
+                .body(hyper::Body::from(body))
+                .unwrap();
+            let response = service.oneshot(request).await.unwrap();
+            let status = response.status();
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            (status, String::from_utf8(body.to_vec()).unwrap())
+        }
+        """
+
+    @Test
+    fun `awsQuery requests are routed, parsed, and answered`() {
+        serverIntegrationTest(model("awsQuery")) { _, rustCrate ->
+            rustCrate.testModule {
+                rust(echoService())
+
+                tokioTest("input_is_parsed") {
+                    rust(
+                        """
+                        async fn check(input: crate::input::EchoInput) -> Result<crate::output::EchoOutput, crate::error::EchoError> {
+                            assert_eq!(input.string.as_deref(), Some("a b"));
+                            assert_eq!(input.integer, Some(5));
+                            assert_eq!(input.boolean, Some(true));
+                            assert_eq!(input.list, Some(vec!["a".to_owned(), "b".to_owned()]));
+                            assert_eq!(input.flat_list, Some(vec!["c".to_owned()]));
+                            let map = input.map.unwrap();
+                            assert_eq!(map.len(), 1);
+                            assert_eq!(map.get("k").map(String::as_str), Some("v"));
+                            assert_eq!(input.nested.unwrap().value.as_deref(), Some("n"));
+                            assert_eq!(input.renamed_member.as_deref(), Some("r"));
+                            Ok(crate::output::EchoOutput { string: None })
+                        }
+
+                        let config = crate::QueryServiceConfig::builder().build();
+                        let service = crate::QueryService::builder(config).echo(check).build().unwrap();
+                        let (status, _) = call(
+                            service,
+                            "Action=Echo&Version=2020-01-08&string=a+b&integer=5&boolean=true\
+                            &list.member.2=b&list.member.1=a&flatList.1=c\
+                            &map.entry.1.key=k&map.entry.1.value=v&nested.value=n&renamed=r",
+                        )
+                        .await;
+                        assert_eq!(status, http::StatusCode::OK);
+                        """,
+                    )
+                }
+
+                tokioTest("output_and_errors_are_wrapped") {
+                    rust(
+                        """
+                        let (status, body) = call(service(), "Action=Echo&Version=2020-01-08&string=hello").await;
+                        assert_eq!(status, http::StatusCode::OK);
+                        assert!(body.contains("<EchoResponse"), "{body}");
+                        assert!(body.contains("<EchoResult><string>hello</string></EchoResult></EchoResponse>"), "{body}");
+
+                        let (status, body) = call(service(), "Action=Echo&Version=2020-01-08&string=fail").await;
+                        assert_eq!(status, http::StatusCode::BAD_REQUEST);
+                        assert!(body.contains("<ErrorResponse"), "{body}");
+                        assert!(body.contains("<Error><Type>Sender</Type><Code>InvalidEcho</Code><message>invalid</message></Error>"), "{body}");
+                        """,
+                    )
+                }
+
+                tokioTest("unknown_actions_and_invalid_inputs_are_rejected") {
+                    rust(
+                        """
+                        let (status, _) = call(service(), "Action=Unknown&Version=2020-01-08").await;
+                        assert_eq!(status, http::StatusCode::NOT_FOUND);
+
+                        let (status, _) = call(service(), "Version=2020-01-08").await;
+                        assert_eq!(status, http::StatusCode::NOT_FOUND);
+
+                        let (status, _) = call(service(), "Action=Echo&Version=2020-01-08&integer=five").await;
+                        assert_eq!(status, http::StatusCode::BAD_REQUEST);
+                        """,
+                    )
+                }
+
+                tokioTest("the_body_size_is_limited") {
+                    rust(
+                        """
+                        let body = "Action=Echo&Version=2020-01-08&string=hello";
+                        let service = service().map_router(|router| router.with_max_body_size(body.len() as u64 - 1));
+                        let (status, _) = call(service, body).await;
+                        assert_eq!(status, http::StatusCode::PAYLOAD_TOO_LARGE);
+                        """,
+                    )
+                }
+            }
+        }
+    }
+
+    @Test
+    fun `ec2Query requests are routed, parsed, and answered`() {
+        serverIntegrationTest(model("ec2Query")) { _, rustCrate ->
+            rustCrate.testModule {
+                rust(echoService())
+
+                tokioTest("input_is_parsed") {
+                    rust(
+                        """
+                        async fn check(input: crate::input::EchoInput) -> Result<crate::output::EchoOutput, crate::error::EchoError> {
+                            assert_eq!(input.string.as_deref(), Some("a b"));
+                            assert_eq!(input.integer, Some(5));
+                            // Lists are always flattened.
+                            assert_eq!(input.list, Some(vec!["a".to_owned(), "b".to_owned()]));
+                            assert_eq!(input.flat_list, Some(vec!["c".to_owned()]));
+                            assert_eq!(input.nested.unwrap().value.as_deref(), Some("n"));
+                            assert_eq!(input.renamed_member.as_deref(), Some("r"));
+                            Ok(crate::output::EchoOutput { string: None })
+                        }
+
+                        let config = crate::QueryServiceConfig::builder().build();
+                        let service = crate::QueryService::builder(config).echo(check).build().unwrap();
+                        let (status, _) = call(
+                            service,
+                            "Action=Echo&Version=2020-01-08&String=a%20b&Integer=5\
+                            &List.1=a&List.2=b&FlatList.1=c&Nested.Value=n&Renamed=r",
+                        )
+                        .await;
+                        assert_eq!(status, http::StatusCode::OK);
+                        """,
+                    )
+                }
+
+                tokioTest("output_and_errors_are_wrapped") {
+                    rust(
+                        """
+                        let (status, body) = call(service(), "Action=Echo&Version=2020-01-08&String=hello").await;
+                        assert_eq!(status, http::StatusCode::OK);
+                        assert!(body.contains("<EchoResponse"), "{body}");
+                        assert!(body.contains("<string>hello</string></EchoResponse>"), "{body}");
+
+                        let (status, body) = call(service(), "Action=Echo&Version=2020-01-08&String=fail").await;
+                        assert_eq!(status, http::StatusCode::BAD_REQUEST);
+                        assert!(body.contains("<Response"), "{body}");
+                        assert!(body.contains("<Errors><Error><Code>InvalidEcho</Code><message>invalid</message></Error></Errors>"), "{body}");
+                        """,
+                    )
+                }
+
+                tokioTest("unknown_actions_are_rejected") {
+                    rust(
+                        """
+                        let (status, _) = call(service(), "Action=Unknown&Version=2020-01-08").await;
+                        assert_eq!(status, http::StatusCode::NOT_FOUND);
+                        """,
+                    )
+                }
+            }
+        }
+    }
+}
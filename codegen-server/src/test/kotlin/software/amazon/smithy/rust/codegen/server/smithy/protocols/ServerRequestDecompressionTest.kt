@@ -0,0 +1,154 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.server.smithy.protocols
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.testModule
+import software.amazon.smithy.rust.codegen.core.testutil.tokioTest
+import software.amazon.smithy.rust.codegen.server.smithy.ServerCargoDependency
+import software.amazon.smithy.rust.codegen.server.smithy.testutil.serverIntegrationTest
+
+internal class ServerRequestDecompressionTest {
+    private val model =
+        """
+        namespace test
+
+        use aws.protocols#restJson1
+
+        @restJson1
+        service CompressionService {
+            version: "2020-01-08",
+            operations: [Echo],
+        }
+
+        @http(method: "POST", uri: "/echo")
+        @requestCompression(encodings: ["gzip"])
+        operation Echo {
+            input: EchoInput,
+            output: EchoOutput,
+        }
+
+        structure EchoInput {
+            string: String,
+        }
+
+        structure EchoOutput {
+            string: String,
+        }
+        """.asSmithyModel(smithyVersion = "2")
+
+    @Test
+    fun `gzip request bodies are decompressed by RequestDecompressionPlugin`() {
+        serverIntegrationTest(model) { codegenContext, rustCrate ->
+            val runtimeConfig = codegenContext.runtimeConfig
+            val codegenScope =
+                arrayOf(
+                    "RequestDecompressionPlugin" to
+                        ServerCargoDependency.smithyHttpServer(runtimeConfig).withFeature("compression").toType()
+                            .resolve("plugin::compression::RequestDecompressionPlugin"),
+                    "CompressionAlgorithm" to
+                        CargoDependency.smithyCompression(runtimeConfig).toDevDependency().toType()
+                            .resolve("CompressionAlgorithm"),
+                    "CompressionOptions" to
+                        CargoDependency.smithyCompression(runtimeConfig).toDevDependency().toType()
+                            .resolve("CompressionOptions"),
+                )
+
+            rustCrate.testModule {
+                rustTemplate(
+                    """
+                    async fn echo(input: crate::input::EchoInput) -> crate::output::EchoOutput {
+                        crate::output::EchoOutput { string: input.string }
+                    }
+
+                    fn gzip(body: &[u8]) -> Vec<u8> {
+                        #{CompressionAlgorithm}::Gzip
+                            .compress_bytes(body, &#{CompressionOptions}::default())
+                            .unwrap()
+                            .to_vec()
+                    }
+
+                    async fn call(
+                        plugin: #{RequestDecompressionPlugin},
+                        content_encoding: Option<&str>,
+                        body: Vec<u8>,
+                    ) -> (http::StatusCode, String) {
+                        use tower::ServiceExt;
+                        let config = crate::CompressionServiceConfig::builder().http_plugin(plugin).build();
+                        let service = crate::CompressionService::builder(config).echo(echo).build().unwrap();
+                        let mut request = http::Request::builder()
+                            .method("POST")
+                            .uri("/echo")
+                            .header("Content-Type", "application/json");
+                        if let Some(content_encoding) = content_encoding {
+                            request = request.header("Content-Encoding", content_encoding);
+                        }
+                        let request = request.body(hyper::Body::from(body)).unwrap();
+                        let response = service.oneshot(request).await.unwrap();
+                        let status = response.status();
+                        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+                        (status, String::from_utf8(body.to_vec()).unwrap())
+                    }
+                    """,
+                    *codegenScope,
+                )
+
+                tokioTest("gzip_bodies_are_decompressed") {
+                    rustTemplate(
+                        """
+                        let plugin = #{RequestDecompressionPlugin}::new();
+                        let (status, body) = call(plugin, Some("gzip"), gzip(br##"{"string":"hello"}"##)).await;
+                        assert_eq!(status, http::StatusCode::OK);
+                        assert_eq!(body, r##"{"string":"hello"}"##);
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                tokioTest("only_the_last_encoding_is_decompressed") {
+                    rustTemplate(
+                        """
+                        // The body was compressed twice, and only the second compression is undone, so the body
+                        // can't be deserialized.
+                        let body = gzip(&gzip(br##"{"string":"hello"}"##));
+                        let plugin = #{RequestDecompressionPlugin}::new();
+                        let (status, _) = call(plugin, Some("gzip, gzip"), body).await;
+                        assert_eq!(status, http::StatusCode::BAD_REQUEST);
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                tokioTest("uncompressed_bodies_are_unchanged") {
+                    rustTemplate(
+                        """
+                        let plugin = #{RequestDecompressionPlugin}::new();
+                        let (status, body) = call(plugin, None, br##"{"string":"hello"}"##.to_vec()).await;
+                        assert_eq!(status, http::StatusCode::OK);
+                        assert_eq!(body, r##"{"string":"hello"}"##);
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                tokioTest("the_decompressed_size_is_limited") {
+                    rustTemplate(
+                        """
+                        let json = format!(r##"{{"string":"{}"}}"##, "a".repeat(1024));
+                        let plugin = #{RequestDecompressionPlugin}::new().with_max_decompressed_size(1024);
+                        let (status, _) = call(plugin, Some("gzip"), gzip(json.as_bytes())).await;
+                        assert_eq!(status, http::StatusCode::PAYLOAD_TOO_LARGE);
+                        """,
+                        *codegenScope,
+                    )
+                }
+            }
+        }
+    }
+}
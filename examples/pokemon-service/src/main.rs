@@ -14,11 +14,11 @@ use aws_smithy_http_server::{
     layer::alb_health_check::AlbHealthCheckLayer,
     plugin::{HttpPlugins, ModelPlugins, Scoped},
     request::request_id::ServerRequestIdProviderLayer,
+    serve::{shutdown_signal, GracefulShutdown},
     AddExtensionLayer,
 };
 use clap::Parser;
 
-use hyper::StatusCode;
 use plugin::PrintExt;
 
 use pokemon_service::{
@@ -70,13 +70,17 @@ pub async fn main() {
     let authz_plugin = AuthorizationPlugin::new();
     let model_plugins = ModelPlugins::new().push(authz_plugin);
 
+    // Drain in-flight requests before shutting down.
+    let shutdown = GracefulShutdown::new();
+
     let config = PokemonServiceConfig::builder()
         // Set up shared state and middlewares.
         .layer(AddExtensionLayer::new(Arc::new(State::default())))
-        // Handle `/ping` health check requests.
-        .layer(AlbHealthCheckLayer::from_handler("/ping", |_req| async {
-            StatusCode::OK
-        }))
+        // Handle `/ping` health check requests, which fail once the server starts shutting down.
+        .layer(AlbHealthCheckLayer::from_handler(
+            "/ping",
+            shutdown.health_check_handler(),
+        ))
         // Add server request IDs.
         .layer(ServerRequestIdProviderLayer::new())
         .http_plugin(http_plugins)
@@ -105,9 +109,10 @@ pub async fn main() {
     let bind: SocketAddr = format!("{}:{}", args.address, args.port)
         .parse()
         .expect("unable to parse the server bind address and port");
-    let server = hyper::Server::bind(&bind).serve(make_app);
+    let server =
+        shutdown.serve_with_connect_info(hyper::Server::bind(&bind), make_app, shutdown_signal());
 
-    // Run forever-ish...
+    // Run until `SIGTERM` or Ctrl+C.
     if let Err(err) = server.await {
         eprintln!("server error: {}", err);
     }
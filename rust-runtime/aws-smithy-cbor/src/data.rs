@@ -0,0 +1,170 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! CBOR data types.
+
+use std::fmt;
+
+/// The type of a CBOR data item, as seen by a [`Decoder`](crate::Decoder) before decoding it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Type {
+    /// A boolean.
+    Bool,
+    /// The `null` simple value.
+    Null,
+    /// The `undefined` simple value.
+    Undefined,
+    /// An unsigned integer that fits in a `u8`.
+    U8,
+    /// An unsigned integer that fits in a `u16`.
+    U16,
+    /// An unsigned integer that fits in a `u32`.
+    U32,
+    /// An unsigned integer that fits in a `u64`.
+    U64,
+    /// A negative integer that fits in an `i8`.
+    I8,
+    /// A negative integer that fits in an `i16`.
+    I16,
+    /// A negative integer that fits in an `i32`.
+    I32,
+    /// A negative integer that fits in an `i64`.
+    I64,
+    /// A negative integer that does not fit in an `i64`.
+    Int,
+    /// A half-precision float.
+    F16,
+    /// A single-precision float.
+    F32,
+    /// A double-precision float.
+    F64,
+    /// A simple value other than `false`, `true`, `null` and `undefined`.
+    Simple,
+    /// A definite-length byte string.
+    Bytes,
+    /// An indefinite-length byte string.
+    BytesIndef,
+    /// A definite-length text string.
+    String,
+    /// An indefinite-length text string.
+    StringIndef,
+    /// A definite-length array.
+    Array,
+    /// An indefinite-length array.
+    ArrayIndef,
+    /// A definite-length map.
+    Map,
+    /// A map of indefinite length.
+    MapIndef,
+    /// A tag.
+    Tag,
+    /// The "break" stop code, which ends indefinite-length items.
+    Break,
+    /// An unknown initial byte.
+    Unknown(u8),
+}
+
+impl Type {
+    pub(crate) fn new(ty: minicbor::data::Type) -> Self {
+        use minicbor::data::Type as T;
+        match ty {
+            T::Bool => Type::Bool,
+            T::Null => Type::Null,
+            T::Undefined => Type::Undefined,
+            T::U8 => Type::U8,
+            T::U16 => Type::U16,
+            T::U32 => Type::U32,
+            T::U64 => Type::U64,
+            T::I8 => Type::I8,
+            T::I16 => Type::I16,
+            T::I32 => Type::I32,
+            T::I64 => Type::I64,
+            T::Int => Type::Int,
+            T::F16 => Type::F16,
+            T::F32 => Type::F32,
+            T::F64 => Type::F64,
+            T::Simple => Type::Simple,
+            T::Bytes => Type::Bytes,
+            T::BytesIndef => Type::BytesIndef,
+            T::String => Type::String,
+            T::StringIndef => Type::StringIndef,
+            T::Array => Type::Array,
+            T::ArrayIndef => Type::ArrayIndef,
+            T::Map => Type::Map,
+            T::MapIndef => Type::MapIndef,
+            T::Tag => Type::Tag,
+            T::Break => Type::Break,
+            T::Unknown(byte) => Type::Unknown(byte),
+        }
+    }
+
+    pub(crate) fn into_minicbor(self) -> minicbor::data::Type {
+        use minicbor::data::Type as T;
+        match self {
+            Type::Bool => T::Bool,
+            Type::Null => T::Null,
+            Type::Undefined => T::Undefined,
+            Type::U8 => T::U8,
+            Type::U16 => T::U16,
+            Type::U32 => T::U32,
+            Type::U64 => T::U64,
+            Type::I8 => T::I8,
+            Type::I16 => T::I16,
+            Type::I32 => T::I32,
+            Type::I64 => T::I64,
+            Type::Int => T::Int,
+            Type::F16 => T::F16,
+            Type::F32 => T::F32,
+            Type::F64 => T::F64,
+            Type::Simple => T::Simple,
+            Type::Bytes => T::Bytes,
+            Type::BytesIndef => T::BytesIndef,
+            Type::String => T::String,
+            Type::StringIndef => T::StringIndef,
+            Type::Array => T::Array,
+            Type::ArrayIndef => T::ArrayIndef,
+            Type::Map => T::Map,
+            Type::MapIndef => T::MapIndef,
+            Type::Tag => T::Tag,
+            Type::Break => T::Break,
+            Type::Unknown(byte) => T::Unknown(byte),
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Bool => write!(f, "bool"),
+            Type::Null => write!(f, "null"),
+            Type::Undefined => write!(f, "undefined"),
+            Type::U8 => write!(f, "u8"),
+            Type::U16 => write!(f, "u16"),
+            Type::U32 => write!(f, "u32"),
+            Type::U64 => write!(f, "u64"),
+            Type::I8 => write!(f, "i8"),
+            Type::I16 => write!(f, "i16"),
+            Type::I32 => write!(f, "i32"),
+            Type::I64 => write!(f, "i64"),
+            Type::Int => write!(f, "int"),
+            Type::F16 => write!(f, "f16"),
+            Type::F32 => write!(f, "f32"),
+            Type::F64 => write!(f, "f64"),
+            Type::Simple => write!(f, "simple"),
+            Type::Bytes => write!(f, "bytes"),
+            Type::BytesIndef => write!(f, "indefinite bytes"),
+            Type::String => write!(f, "string"),
+            Type::StringIndef => write!(f, "indefinite string"),
+            Type::Array => write!(f, "array"),
+            Type::ArrayIndef => write!(f, "indefinite array"),
+            Type::Map => write!(f, "map"),
+            Type::MapIndef => write!(f, "indefinite map"),
+            Type::Tag => write!(f, "tag"),
+            Type::Break => write!(f, "break"),
+            Type::Unknown(byte) => write!(f, "unknown initial byte {byte:#x}"),
+        }
+    }
+}
@@ -0,0 +1,459 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! CBOR decoding.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fmt;
+
+use aws_smithy_types::{Blob, DateTime, Document, Number};
+use minicbor::decode::Error;
+
+use crate::data::Type;
+
+/// An error that occurred while decoding CBOR.
+#[derive(Debug)]
+pub struct DeserializeError {
+    inner: Error,
+}
+
+impl DeserializeError {
+    fn new(inner: Error) -> Self {
+        Self { inner }
+    }
+
+    /// Returns a custom error for the data item at position `at`.
+    pub fn custom(message: impl Into<Cow<'static, str>>, at: usize) -> Self {
+        Self::new(Error::message(message.into()).at(at))
+    }
+
+    /// Returns an error for a union variant of an unexpected type, e.g. a variant that is not a
+    /// map key.
+    pub fn unexpected_union_variant(unexpected_type: Type, at: usize) -> Self {
+        Self::new(
+            Error::type_mismatch(unexpected_type.into_minicbor())
+                .with_message("encountered unexpected union variant; expected end of union or another union variant")
+                .at(at),
+        )
+    }
+
+    /// Returns an error for a union variant that is not modeled.
+    pub fn unknown_union_variant(variant_name: &str, at: usize) -> Self {
+        Self::new(
+            Error::message(format!("encountered unknown union variant {variant_name}")).at(at),
+        )
+    }
+
+    /// Returns an error for a union in which more than one variant is set.
+    pub fn mixed_union_variants(at: usize) -> Self {
+        Self::new(
+            Error::message(
+                "encountered mixed variants in union; expected a single union variant to be set",
+            )
+            .at(at),
+        )
+    }
+
+    /// Returns an error for input that continues after the top-level data item, which ends at
+    /// position `at`.
+    pub fn expected_end_of_stream(at: usize) -> Self {
+        Self::new(Error::message("encountered additional data at the end of the input").at(at))
+    }
+}
+
+impl StdError for DeserializeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&self.inner)
+    }
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to deserialize CBOR: {}", self.inner)
+    }
+}
+
+impl From<Error> for DeserializeError {
+    fn from(inner: Error) -> Self {
+        Self::new(inner)
+    }
+}
+
+const MAX_DOCUMENT_RECURSION: usize = 256;
+
+/// Decodes Smithy values from a CBOR buffer.
+///
+/// Decoding is lenient where the Smithy RPC v2 CBOR protocol asks it to be: integers are accepted
+/// in any encoding that fits the target type, floating point numbers in any precision, and strings
+/// and byte strings in both their definite and indefinite-length forms.
+#[derive(Debug, Clone)]
+pub struct Decoder<'b> {
+    decoder: minicbor::Decoder<'b>,
+}
+
+macro_rules! delegate_method {
+    ($($(#[$meta:meta])* $decoder_name:ident => $minicbor_name:ident($result_type:ty);)+) => {
+        $(
+            $(#[$meta])*
+            pub fn $decoder_name(&mut self) -> Result<$result_type, DeserializeError> {
+                Ok(self.decoder.$minicbor_name()?)
+            }
+        )+
+    };
+}
+
+impl<'b> Decoder<'b> {
+    /// Creates a decoder that reads from `bytes`.
+    pub fn new(bytes: &'b [u8]) -> Self {
+        Self {
+            decoder: minicbor::Decoder::new(bytes),
+        }
+    }
+
+    /// Returns the type of the next data item, without consuming it.
+    pub fn datatype(&self) -> Result<Type, DeserializeError> {
+        Ok(Type::new(self.decoder.datatype()?))
+    }
+
+    delegate_method! {
+        /// Skips over the next data item, including all the items nested in it.
+        skip => skip(());
+        /// Reads a `boolean`.
+        boolean => bool(bool);
+        /// Reads a `byte`.
+        byte => i8(i8);
+        /// Reads a `short`.
+        short => i16(i16);
+        /// Reads an `integer`.
+        integer => i32(i32);
+        /// Reads a `long`.
+        long => i64(i64);
+        /// Reads a `double`.
+        double => f64(f64);
+        /// Reads a `null`.
+        null => null(());
+        /// Begins reading an array, returning its length if it is not of indefinite length.
+        list => array(Option<u64>);
+        /// Begins reading a map, returning its number of key-value pairs if it is not of
+        /// indefinite length.
+        map => map(Option<u64>);
+    }
+
+    /// Reads a `float`. Doubles are accepted, and narrowed to single precision.
+    pub fn float(&mut self) -> Result<f32, DeserializeError> {
+        match self.datatype()? {
+            Type::F64 => Ok(self.decoder.f64()? as f32),
+            _ => Ok(self.decoder.f32()?),
+        }
+    }
+
+    /// Reads a string, without copying it if it is of definite length.
+    pub fn str(&mut self) -> Result<Cow<'b, str>, DeserializeError> {
+        if self.datatype()? != Type::StringIndef {
+            return Ok(Cow::Borrowed(self.decoder.str()?));
+        }
+        let mut string = String::new();
+        for chunk in self.decoder.str_iter()? {
+            string.push_str(chunk?);
+        }
+        Ok(Cow::Owned(string))
+    }
+
+    /// Reads a `string`.
+    pub fn string(&mut self) -> Result<String, DeserializeError> {
+        Ok(self.str()?.into_owned())
+    }
+
+    /// Reads a `blob`.
+    pub fn blob(&mut self) -> Result<Blob, DeserializeError> {
+        if self.datatype()? != Type::BytesIndef {
+            return Ok(Blob::new(self.decoder.bytes()?));
+        }
+        let mut bytes = Vec::new();
+        for chunk in self.decoder.bytes_iter()? {
+            bytes.extend_from_slice(chunk?);
+        }
+        Ok(Blob::new(bytes))
+    }
+
+    /// Reads a `timestamp`: tag 1 (epoch-based date/time) followed by the number of seconds since
+    /// the Unix epoch, either as an integer or as a floating point number.
+    pub fn timestamp(&mut self) -> Result<DateTime, DeserializeError> {
+        let position = self.position();
+        let tag = self.decoder.tag()?;
+        if tag != minicbor::data::Tag::Timestamp {
+            return Err(DeserializeError::custom(
+                format!("expected timestamp tag 1, found {tag:?}"),
+                position,
+            ));
+        }
+        match self.datatype()? {
+            Type::F16 | Type::F32 | Type::F64 => Ok(DateTime::from_secs_f64(self.decoder.f64()?)),
+            _ => Ok(DateTime::from_secs(self.decoder.i64()?)),
+        }
+    }
+
+    /// Reads a `document`.
+    pub fn document(&mut self) -> Result<Document, DeserializeError> {
+        self.document_inner(0)
+    }
+
+    fn document_inner(&mut self, depth: usize) -> Result<Document, DeserializeError> {
+        let position = self.position();
+        if depth >= MAX_DOCUMENT_RECURSION {
+            return Err(DeserializeError::custom(
+                "exceeded max recursion depth while decoding document",
+                position,
+            ));
+        }
+        match self.datatype()? {
+            Type::Map | Type::MapIndef => {
+                let len = self.map()?;
+                let mut object = HashMap::new();
+                let mut read = 0;
+                while !self.at_end_of_collection(len, read)? {
+                    let key = self.string()?;
+                    let value = self.document_inner(depth + 1)?;
+                    object.insert(key, value);
+                    read += 1;
+                }
+                Ok(Document::Object(object))
+            }
+            Type::Array | Type::ArrayIndef => {
+                let len = self.list()?;
+                let mut array = Vec::new();
+                while !self.at_end_of_collection(len, array.len() as u64)? {
+                    array.push(self.document_inner(depth + 1)?);
+                }
+                Ok(Document::Array(array))
+            }
+            Type::U8 | Type::U16 | Type::U32 | Type::U64 => {
+                Ok(Document::Number(Number::PosInt(self.decoder.u64()?)))
+            }
+            Type::I8 | Type::I16 | Type::I32 | Type::I64 => {
+                Ok(Document::Number(Number::NegInt(self.decoder.i64()?)))
+            }
+            Type::F16 | Type::F32 | Type::F64 => {
+                Ok(Document::Number(Number::Float(self.decoder.f64()?)))
+            }
+            Type::String | Type::StringIndef => Ok(Document::String(self.string()?)),
+            Type::Bool => Ok(Document::Bool(self.boolean()?)),
+            Type::Null => {
+                self.null()?;
+                Ok(Document::Null)
+            }
+            ty => Err(DeserializeError::custom(
+                format!("{ty} can't be deserialized into a document"),
+                position,
+            )),
+        }
+    }
+
+    /// Returns whether all the items of a collection of length `len`, as returned by
+    /// [`Decoder::list`] or [`Decoder::map`], have been read, given that `read` of them have been
+    /// read so far. Consumes the "break" stop code of indefinite-length collections.
+    ///
+    /// Used by generated code to loop over both definite and indefinite-length collections.
+    pub fn at_end_of_collection(
+        &mut self,
+        len: Option<u64>,
+        read: u64,
+    ) -> Result<bool, DeserializeError> {
+        match len {
+            Some(len) => Ok(read >= len),
+            None if self.datatype()? == Type::Break => {
+                self.skip()?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Returns the position of the decoder in the input.
+    pub fn position(&self) -> usize {
+        self.decoder.position()
+    }
+
+    /// Sets the position of the decoder in the input.
+    pub fn set_position(&mut self, position: usize) {
+        self.decoder.set_position(position)
+    }
+}
+
+/// Calls `f` to deserialize a member into `builder`, unless the member's value is `null`, in which
+/// case the `null` is consumed and the member is left unset.
+///
+/// Used by generated code for members of structures.
+pub fn set_optional<B, F>(
+    builder: B,
+    decoder: &mut Decoder<'_>,
+    f: F,
+) -> Result<B, DeserializeError>
+where
+    F: Fn(B, &mut Decoder<'_>) -> Result<B, DeserializeError>,
+{
+    match decoder.datatype()? {
+        Type::Null => {
+            decoder.null()?;
+            Ok(builder)
+        }
+        _ => f(builder, decoder),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{set_optional, Decoder};
+    use crate::data::Type;
+    use crate::Encoder;
+    use aws_smithy_types::{Blob, DateTime, Document, Number};
+    use std::collections::HashMap;
+
+    #[test]
+    fn integers_of_any_width_are_accepted() {
+        // A `u8`-sized integer read as a `long`, and an `i32`-sized one read as an `integer`.
+        assert_eq!(23, Decoder::new(&[0x17]).long().unwrap());
+        assert_eq!(-500, Decoder::new(&[0x39, 0x01, 0xf3]).integer().unwrap());
+        // 300 doesn't fit in a `byte`.
+        assert!(Decoder::new(&[0x19, 0x01, 0x2c]).byte().is_err());
+    }
+
+    #[test]
+    fn floats_of_any_precision_are_accepted() {
+        // Half-precision 1.5.
+        assert_eq!(1.5, Decoder::new(&[0xf9, 0x3e, 0x00]).double().unwrap());
+        // Double-precision 1.5.
+        assert_eq!(
+            1.5,
+            Decoder::new(&[0xfb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0])
+                .float()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn indefinite_length_strings_and_blobs() {
+        let string = [0x7f, 0x62, b'a', b'b', 0x61, b'c', 0xff];
+        assert_eq!("abc", Decoder::new(&string).string().unwrap());
+        let blob = [0x5f, 0x41, 1, 0x42, 2, 3, 0xff];
+        assert_eq!(
+            Blob::new(vec![1, 2, 3]),
+            Decoder::new(&blob).blob().unwrap()
+        );
+    }
+
+    #[test]
+    fn timestamps() {
+        let mut encoder = Encoder::new(Vec::new());
+        encoder.timestamp(&DateTime::from_secs_f64(1.5));
+        let bytes = encoder.into_writer();
+        assert_eq!(
+            DateTime::from_secs_f64(1.5),
+            Decoder::new(&bytes).timestamp().unwrap()
+        );
+
+        // Integral epoch seconds.
+        assert_eq!(
+            DateTime::from_secs(23),
+            Decoder::new(&[0xc1, 0x17]).timestamp().unwrap()
+        );
+
+        // Tag 0 (date/time string) is not supported.
+        assert!(Decoder::new(&[0xc0, 0x17]).timestamp().is_err());
+    }
+
+    #[test]
+    fn documents_round_trip() {
+        let document = Document::Object(HashMap::from([
+            (
+                "array".to_string(),
+                Document::Array(vec![
+                    Document::Number(Number::PosInt(1)),
+                    Document::Number(Number::NegInt(-1)),
+                    Document::Number(Number::Float(0.5)),
+                ]),
+            ),
+            ("string".to_string(), Document::String("a".into())),
+            ("bool".to_string(), Document::Bool(true)),
+            ("null".to_string(), Document::Null),
+        ]));
+        let mut encoder = Encoder::new(Vec::new());
+        encoder.document(&document);
+        let bytes = encoder.into_writer();
+
+        let mut decoder = Decoder::new(&bytes);
+        assert_eq!(document, decoder.document().unwrap());
+        assert_eq!(bytes.len(), decoder.position());
+    }
+
+    #[test]
+    fn indefinite_length_documents() {
+        // `{"a": [1]}`, with both collections of indefinite length.
+        let bytes = [0xbf, 0x61, b'a', 0x9f, 0x01, 0xff, 0xff];
+        let expected = Document::Object(HashMap::from([(
+            "a".to_string(),
+            Document::Array(vec![Document::Number(Number::PosInt(1))]),
+        )]));
+        assert_eq!(expected, Decoder::new(&bytes).document().unwrap());
+    }
+
+    #[test]
+    fn deeply_nested_documents_are_rejected() {
+        // 256 nested arrays, and one more than that.
+        let nested = |depth| {
+            let mut bytes = vec![0x81; depth];
+            bytes.push(0xf6);
+            bytes
+        };
+        assert!(Decoder::new(&nested(255)).document().is_ok());
+        let err = Decoder::new(&nested(256)).document().unwrap_err();
+        assert!(
+            err.to_string().contains("exceeded max recursion depth"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn collections_of_any_length() {
+        // `[1, 2]`, of definite and of indefinite length.
+        for bytes in [&[0x82, 0x01, 0x02][..], &[0x9f, 0x01, 0x02, 0xff][..]] {
+            let mut decoder = Decoder::new(bytes);
+            let len = decoder.list().unwrap();
+            let mut items = Vec::new();
+            while !decoder
+                .at_end_of_collection(len, items.len() as u64)
+                .unwrap()
+            {
+                items.push(decoder.long().unwrap());
+            }
+            assert_eq!(vec![1, 2], items);
+            assert_eq!(bytes.len(), decoder.position());
+        }
+    }
+
+    #[test]
+    fn set_optional_skips_nulls() {
+        let set = |_builder: Option<String>, decoder: &mut Decoder<'_>| Ok(Some(decoder.string()?));
+
+        let mut decoder = Decoder::new(&[0xf6]);
+        assert_eq!(None, set_optional(None, &mut decoder, set).unwrap());
+        assert_eq!(1, decoder.position());
+
+        let mut decoder = Decoder::new(&[0x61, b'a']);
+        assert_eq!(
+            Some("a".to_string()),
+            set_optional(None, &mut decoder, set).unwrap()
+        );
+    }
+
+    #[test]
+    fn datatype_does_not_consume() {
+        let decoder = Decoder::new(&[0xf6]);
+        assert_eq!(Type::Null, decoder.datatype().unwrap());
+        assert_eq!(0, decoder.position());
+    }
+}
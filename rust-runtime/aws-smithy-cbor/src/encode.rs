@@ -0,0 +1,208 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! CBOR encoding.
+
+use aws_smithy_types::{Blob, DateTime, Document, Number};
+
+/// Writing to a `Vec<u8>` can't fail, so neither can encoding.
+const INFALLIBLE_WRITE: &str = "write failed";
+
+/// Encodes Smithy values into a CBOR buffer.
+///
+/// Integers are written with the shortest encoding that can hold their value, and collections are
+/// either written with a definite length ([`Encoder::array`], [`Encoder::map`]) or left open until
+/// [`Encoder::end`] is called ([`Encoder::begin_array`], [`Encoder::begin_map`]).
+#[derive(Debug)]
+pub struct Encoder {
+    encoder: minicbor::Encoder<Vec<u8>>,
+}
+
+macro_rules! delegate_method {
+    ($($(#[$meta:meta])* $encoder_name:ident => $minicbor_name:ident($typ:ty);)+) => {
+        $(
+            $(#[$meta])*
+            pub fn $encoder_name(&mut self, x: $typ) -> &mut Self {
+                self.encoder.$minicbor_name(x).expect(INFALLIBLE_WRITE);
+                self
+            }
+        )+
+    };
+}
+
+impl Encoder {
+    /// Creates an encoder that appends to `writer`.
+    pub fn new(writer: Vec<u8>) -> Self {
+        Self {
+            encoder: minicbor::Encoder::new(writer),
+        }
+    }
+
+    delegate_method! {
+        /// Writes a `boolean`.
+        boolean => bool(bool);
+        /// Writes a `byte`.
+        byte => i8(i8);
+        /// Writes a `short`.
+        short => i16(i16);
+        /// Writes an `integer`.
+        integer => i32(i32);
+        /// Writes a `long`.
+        long => i64(i64);
+        /// Writes a `float`.
+        float => f32(f32);
+        /// Writes a `double`.
+        double => f64(f64);
+        /// Writes a `string`.
+        str => str(&str);
+    }
+
+    /// Writes a `blob` as a byte string.
+    pub fn blob(&mut self, x: &Blob) -> &mut Self {
+        self.encoder.bytes(x.as_ref()).expect(INFALLIBLE_WRITE);
+        self
+    }
+
+    /// Writes a `timestamp` as tag 1 (epoch-based date/time) followed by its number of seconds
+    /// since the Unix epoch, as a double.
+    pub fn timestamp(&mut self, x: &DateTime) -> &mut Self {
+        self.encoder
+            .tag(minicbor::data::Tag::Timestamp)
+            .expect(INFALLIBLE_WRITE)
+            .f64(x.as_secs_f64())
+            .expect(INFALLIBLE_WRITE);
+        self
+    }
+
+    /// Writes a `null`.
+    pub fn null(&mut self) -> &mut Self {
+        self.encoder.null().expect(INFALLIBLE_WRITE);
+        self
+    }
+
+    /// Begins an array of `len` items.
+    pub fn array(&mut self, len: usize) -> &mut Self {
+        self.encoder.array(len as u64).expect(INFALLIBLE_WRITE);
+        self
+    }
+
+    /// Begins a map of `len` key-value pairs.
+    pub fn map(&mut self, len: usize) -> &mut Self {
+        self.encoder.map(len as u64).expect(INFALLIBLE_WRITE);
+        self
+    }
+
+    /// Begins an array of indefinite length, which must be ended with [`Encoder::end`].
+    pub fn begin_array(&mut self) -> &mut Self {
+        self.encoder.begin_array().expect(INFALLIBLE_WRITE);
+        self
+    }
+
+    /// Begins a map of indefinite length, which must be ended with [`Encoder::end`].
+    ///
+    /// Structures are encoded this way so that optional members can be skipped without counting
+    /// the members that are set beforehand.
+    pub fn begin_map(&mut self) -> &mut Self {
+        self.encoder.begin_map().expect(INFALLIBLE_WRITE);
+        self
+    }
+
+    /// Ends the array or map that was begun with [`Encoder::begin_array`] or
+    /// [`Encoder::begin_map`].
+    pub fn end(&mut self) -> &mut Self {
+        self.encoder.end().expect(INFALLIBLE_WRITE);
+        self
+    }
+
+    /// Writes a `document`.
+    pub fn document(&mut self, x: &Document) -> &mut Self {
+        match x {
+            Document::Object(object) => {
+                self.map(object.len());
+                for (key, value) in object {
+                    self.str(key).document(value);
+                }
+                self
+            }
+            Document::Array(array) => {
+                self.array(array.len());
+                for value in array {
+                    self.document(value);
+                }
+                self
+            }
+            Document::Number(number) => self.number(*number),
+            Document::String(string) => self.str(string),
+            Document::Bool(boolean) => self.boolean(*boolean),
+            Document::Null => self.null(),
+        }
+    }
+
+    /// Writes a document [`Number`].
+    pub fn number(&mut self, x: Number) -> &mut Self {
+        match x {
+            Number::PosInt(n) => self.encoder.u64(n).expect(INFALLIBLE_WRITE),
+            Number::NegInt(n) => self.encoder.i64(n).expect(INFALLIBLE_WRITE),
+            Number::Float(n) => self.encoder.f64(n).expect(INFALLIBLE_WRITE),
+        };
+        self
+    }
+
+    /// Returns the encoded bytes.
+    pub fn into_writer(self) -> Vec<u8> {
+        self.encoder.into_writer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Encoder;
+    use aws_smithy_types::{Blob, DateTime, Document, Number};
+
+    fn encode(f: impl FnOnce(&mut Encoder) -> &mut Encoder) -> Vec<u8> {
+        let mut encoder = Encoder::new(Vec::new());
+        f(&mut encoder);
+        encoder.into_writer()
+    }
+
+    #[test]
+    fn integers_use_the_shortest_encoding() {
+        assert_eq!(vec![0x17], encode(|e| e.long(23)));
+        assert_eq!(vec![0x18, 0x18], encode(|e| e.long(24)));
+        assert_eq!(vec![0x20], encode(|e| e.byte(-1)));
+        assert_eq!(vec![0x39, 0x01, 0xf3], encode(|e| e.integer(-500)));
+    }
+
+    #[test]
+    fn timestamps_are_tagged_epoch_seconds() {
+        let bytes = encode(|e| e.timestamp(&DateTime::from_secs_f64(1.5)));
+        assert_eq!(vec![0xc1, 0xfb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0], bytes);
+    }
+
+    #[test]
+    fn blobs_are_byte_strings() {
+        let bytes = encode(|e| e.blob(&Blob::new(b"hi".to_vec())));
+        assert_eq!(vec![0x42, b'h', b'i'], bytes);
+    }
+
+    #[test]
+    fn structures_are_indefinite_length_maps() {
+        let bytes = encode(|e| e.begin_map().str("a").boolean(true).end());
+        assert_eq!(vec![0xbf, 0x61, b'a', 0xf5, 0xff], bytes);
+    }
+
+    #[test]
+    fn documents() {
+        let document = Document::Array(vec![
+            Document::Number(Number::PosInt(1)),
+            Document::Number(Number::NegInt(-1)),
+            Document::String("a".into()),
+            Document::Bool(false),
+            Document::Null,
+        ]);
+        let bytes = encode(|e| e.document(&document));
+        assert_eq!(vec![0x85, 0x01, 0x20, 0x61, b'a', 0xf4, 0xf6], bytes);
+    }
+}
@@ -0,0 +1,32 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+/* Automatically managed default lints */
+#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+/* End of automatically managed default lints */
+#![warn(
+    missing_docs,
+    rustdoc::missing_crate_level_docs,
+    unreachable_pub,
+    rust_2018_idioms
+)]
+
+//! CBOR abstractions for Smithy.
+//!
+//! This crate maps the Smithy data model onto [CBOR](https://www.rfc-editor.org/rfc/rfc8949.html)
+//! as required by the [Smithy RPC v2 CBOR](https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html)
+//! protocol:
+//!
+//! - `blob`s are encoded as byte strings;
+//! - `timestamp`s are encoded as tag 1 (epoch-based date/time) followed by the number of seconds
+//!   since the Unix epoch, as a double;
+//! - `document`s and their `Number`s are encoded as the CBOR data items they hold.
+
+pub mod data;
+pub mod decode;
+pub mod encode;
+
+pub use decode::Decoder;
+pub use encode::Encoder;
@@ -83,7 +83,10 @@ impl ChecksumBody<SdkBody> {
                     }
                 };
 
-                let actual_checksum = checksum.finalize();
+                let actual_checksum = match checksum.finalize_checked() {
+                    Ok(actual_checksum) => actual_checksum,
+                    Err(err) => return Poll::Ready(Some(Err(err))),
+                };
                 if *this.precalculated_checksum == actual_checksum {
                     Poll::Ready(None)
                 } else {
@@ -154,6 +157,8 @@ impl http_body::Body for ChecksumBody<SdkBody> {
 #[cfg(test)]
 mod tests {
     use crate::body::validate::{ChecksumBody, Error};
+    use crate::composite::CompositeChecksumPartSize;
+    use crate::error::PartCountMismatchError;
     use crate::ChecksumAlgorithm;
     use aws_smithy_types::body::SdkBody;
     use bytes::{Buf, Bytes};
@@ -197,6 +202,29 @@ mod tests {
         panic!("didn't hit expected error condition");
     }
 
+    #[tokio::test]
+    async fn test_composite_checksum_validated_body_errors_on_part_count_mismatch() {
+        let input_text = "This is some test text for an SdkBody";
+        let part_sizes = CompositeChecksumPartSize::new(10).unwrap();
+        // The text splits into four parts, but the checksum value says it has two
+        let checksum = ChecksumAlgorithm::Crc32.into_composite_impl(part_sizes, 2);
+        let mut body = ChecksumBody::new(
+            SdkBody::from(input_text),
+            checksum,
+            Bytes::from_static(&[0x00, 0x00, 0x00, 0x00]),
+        );
+
+        while let Some(data) = body.data().await {
+            if let Err(e) = data {
+                let e = e.downcast_ref::<PartCountMismatchError>().unwrap();
+                assert_eq!((2, 4), (e.expected(), e.actual()));
+                return;
+            }
+        }
+
+        panic!("didn't hit expected error condition");
+    }
+
     #[tokio::test]
     async fn test_checksum_validated_body_succeeds_on_match() {
         let input_text = "This is some test text for an SdkBody";
@@ -218,4 +246,24 @@ mod tests {
         // Verify data is complete and unaltered
         assert_eq!(input_text, output_text);
     }
+
+    #[tokio::test]
+    async fn test_sha512_checksum_validated_body_succeeds_on_match() {
+        let input_text = "This is some test text for an SdkBody";
+        // Known correct SHA-512 checksum for the input "This is some test text for an SdkBody"
+        let actual_checksum = Bytes::from_static(&[
+            0x4d, 0x57, 0x28, 0xef, 0xfe, 0x1a, 0x86, 0xff, 0xe0, 0x20, 0xcf, 0x35, 0xfc, 0xa3,
+            0x77, 0x51, 0x70, 0x11, 0xe8, 0x8b, 0xc8, 0x7a, 0xc0, 0x59, 0x36, 0x9d, 0xdd, 0x3d,
+            0xc7, 0x10, 0x22, 0x6b, 0xea, 0x19, 0x57, 0xc6, 0x84, 0x46, 0x56, 0xf7, 0xeb, 0xf7,
+            0xff, 0xf7, 0x44, 0x1e, 0xbd, 0x14, 0x2a, 0x80, 0xcc, 0xa6, 0x9a, 0x1a, 0xea, 0x80,
+            0xbe, 0xa6, 0x21, 0xfb, 0x09, 0xd5, 0x5e, 0xf3,
+        ]);
+        let body = SdkBody::from(input_text);
+        let http_checksum = "sha512".parse::<ChecksumAlgorithm>().unwrap().into_impl();
+        let mut body = ChecksumBody::new(body, http_checksum, actual_checksum);
+
+        while let Some(buf) = body.data().await {
+            buf.expect("checksum should match");
+        }
+    }
 }
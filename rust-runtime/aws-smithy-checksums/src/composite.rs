@@ -0,0 +1,578 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Checksums of objects that were uploaded in multiple parts.
+//!
+//! When an object is uploaded with a multipart upload, services like S3 don't return a checksum of
+//! the whole object. Instead, they return a _composite_ checksum: the checksum of the concatenated
+//! checksums of each part, followed by a dash and the number of parts (e.g. `abc==-12`).
+//!
+//! This module provides:
+//! - [`CompositeChecksum`], a checksum calculator that splits the data it's fed into parts of known
+//!   sizes and computes the checksum-of-checksums for them. Because it implements [`HttpChecksum`],
+//!   it can be used with [`crate::body::validate::ChecksumBody`] to validate the body of a
+//!   multipart object.
+//! - [`CompositeChecksumValue`], which parses and formats composite checksum header values.
+//! - [`combine_crcs`], which mathematically combines the CRCs of consecutive parts into the CRC of
+//!   the full object, without needing to read the data again.
+
+use crate::error::{
+    CombineChecksumsError, InvalidCompositeChecksumError, InvalidPartSizeError,
+    PartCountMismatchError,
+};
+use crate::http::HttpChecksum;
+use crate::{Checksum, ChecksumAlgorithm};
+use aws_smithy_types::base64;
+use aws_smithy_types::body::Error as BoxError;
+use aws_smithy_types::config_bag::{Storable, StoreReplace};
+use bytes::Bytes;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// The sizes of the parts that an object was uploaded in with a multipart upload.
+///
+/// A composite checksum can only be validated if the size of each part is known, and services like
+/// S3 don't send part sizes along with the object. When this is stored in the config bag, response
+/// checksum validation will validate composite checksums by splitting the response body into parts
+/// of these sizes. Composite checksums of objects with a single part are validated without it.
+///
+/// If the parts were uploaded with different sizes, use [`CompositeChecksumPartSize::from_part_sizes`]
+/// with the size of every part, e.g. as returned by S3's `GetObjectAttributes` operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositeChecksumPartSize {
+    inner: PartSizes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PartSizes {
+    /// Every part has the same size, except for the last part which may be smaller.
+    Uniform(u64),
+    /// The size of each part, in order.
+    Explicit(Arc<[u64]>),
+}
+
+impl CompositeChecksumPartSize {
+    /// Create a new `CompositeChecksumPartSize` for an object whose parts all have the same size,
+    /// except for the last part which may be smaller.
+    ///
+    /// Returns an error if `part_size` is zero.
+    pub fn new(part_size: u64) -> Result<Self, InvalidPartSizeError> {
+        if part_size == 0 {
+            return Err(InvalidPartSizeError::zero_part_size(None));
+        }
+        Ok(Self {
+            inner: PartSizes::Uniform(part_size),
+        })
+    }
+
+    /// Create a new `CompositeChecksumPartSize` from the size of each part, in the order the parts
+    /// appear in the object.
+    ///
+    /// Returns an error if there are no parts, or if any part has a size of zero.
+    pub fn from_part_sizes(
+        part_sizes: impl IntoIterator<Item = u64>,
+    ) -> Result<Self, InvalidPartSizeError> {
+        let part_sizes: Arc<[u64]> = part_sizes.into_iter().collect();
+        if part_sizes.is_empty() {
+            return Err(InvalidPartSizeError::no_parts());
+        }
+        if let Some(index) = part_sizes.iter().position(|size| *size == 0) {
+            return Err(InvalidPartSizeError::zero_part_size(Some(index)));
+        }
+        Ok(Self {
+            inner: PartSizes::Explicit(part_sizes),
+        })
+    }
+
+    /// The size in bytes of the part at `index`, or `None` if the object has fewer parts.
+    ///
+    /// For uniformly sized parts, this returns the full part size for every index.
+    pub fn part_size(&self, index: usize) -> Option<u64> {
+        match &self.inner {
+            PartSizes::Uniform(part_size) => Some(*part_size),
+            PartSizes::Explicit(part_sizes) => part_sizes.get(index).copied(),
+        }
+    }
+
+    /// The number of parts, if the size of each part was given.
+    pub fn part_count(&self) -> Option<usize> {
+        match &self.inner {
+            PartSizes::Uniform(_) => None,
+            PartSizes::Explicit(part_sizes) => Some(part_sizes.len()),
+        }
+    }
+}
+
+impl Storable for CompositeChecksumPartSize {
+    type Storer = StoreReplace<Self>;
+}
+
+/// A parsed composite checksum value, e.g. `abc==-12`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositeChecksumValue {
+    checksum: Bytes,
+    part_count: u32,
+}
+
+impl CompositeChecksumValue {
+    /// Create a new `CompositeChecksumValue` from a checksum-of-checksums and its part count.
+    pub fn new(checksum: impl Into<Bytes>, part_count: u32) -> Self {
+        Self {
+            checksum: checksum.into(),
+            part_count,
+        }
+    }
+
+    /// The checksum-of-checksums, base64-decoded.
+    pub fn checksum(&self) -> &Bytes {
+        &self.checksum
+    }
+
+    /// The number of parts that were combined to form this checksum.
+    pub fn part_count(&self) -> u32 {
+        self.part_count
+    }
+
+    /// Returns true if `value` looks like a composite checksum, i.e. it ends with a single dash
+    /// followed by a number.
+    pub fn is_composite(value: &str) -> bool {
+        split_part_count(value).is_some()
+    }
+}
+
+fn split_part_count(value: &str) -> Option<(&str, u32)> {
+    let (checksum, part_count) = value.rsplit_once('-')?;
+    if part_count.is_empty() || !part_count.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    // A second dash means this isn't a composite checksum
+    if checksum.ends_with('-') {
+        return None;
+    }
+    Some((checksum, part_count.parse().ok()?))
+}
+
+impl FromStr for CompositeChecksumValue {
+    type Err = InvalidCompositeChecksumError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (checksum, part_count) =
+            split_part_count(value).ok_or_else(|| InvalidCompositeChecksumError::new(value))?;
+        let checksum =
+            base64::decode(checksum).map_err(|_| InvalidCompositeChecksumError::new(value))?;
+        Ok(Self::new(checksum, part_count))
+    }
+}
+
+impl fmt::Display for CompositeChecksumValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", base64::encode(&self.checksum), self.part_count)
+    }
+}
+
+/// A checksum calculator for composite (checksum-of-checksums) checksums.
+///
+/// Data passed to [`Checksum::update`] is split into parts of the given sizes. The checksum of
+/// each part is calculated with the given algorithm, and the resulting part checksums are fed into
+/// another checksum of the same algorithm. [`Checksum::finalize`] returns that outer checksum,
+/// which can be compared to the decoded checksum portion of a [`CompositeChecksumValue`].
+///
+/// Data beyond the last of a list of explicit part sizes is treated as one more part. When the data
+/// splits into a different number of parts than expected, either from the part count set with
+/// [`CompositeChecksum::with_expected_part_count`] or from a list of explicit part sizes,
+/// [`HttpChecksum::finalize_checked`] returns a [`PartCountMismatchError`]. [`Checksum::finalize`]
+/// can't return an error, and returns an empty checksum instead.
+pub struct CompositeChecksum {
+    algorithm: ChecksumAlgorithm,
+    part_sizes: CompositeChecksumPartSize,
+    expected_part_count: Option<u32>,
+    current_part: Box<dyn HttpChecksum>,
+    current_part_len: u64,
+    part_count: u32,
+    checksum_of_checksums: Box<dyn HttpChecksum>,
+}
+
+impl fmt::Debug for CompositeChecksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompositeChecksum")
+            .field("algorithm", &self.algorithm)
+            .field("part_sizes", &self.part_sizes)
+            .field("expected_part_count", &self.expected_part_count)
+            .field("part_count", &self.part_count)
+            .finish()
+    }
+}
+
+impl CompositeChecksum {
+    /// Create a new `CompositeChecksum` for parts of the given sizes.
+    pub fn new(algorithm: ChecksumAlgorithm, part_sizes: CompositeChecksumPartSize) -> Self {
+        Self {
+            algorithm,
+            part_sizes,
+            expected_part_count: None,
+            current_part: algorithm.into_impl(),
+            current_part_len: 0,
+            part_count: 0,
+            checksum_of_checksums: algorithm.into_impl(),
+        }
+    }
+
+    /// Require the data to split into exactly `part_count` parts, like the part count of a
+    /// [`CompositeChecksumValue`].
+    pub fn with_expected_part_count(mut self, part_count: u32) -> Self {
+        self.expected_part_count = Some(part_count);
+        self
+    }
+
+    /// The number of parts that the data has been split into so far, including the part that is
+    /// currently being calculated.
+    pub fn part_count(&self) -> u32 {
+        if self.current_part_len > 0 {
+            self.part_count + 1
+        } else {
+            self.part_count
+        }
+    }
+
+    fn current_part_size(&self) -> u64 {
+        self.part_sizes
+            .part_size(self.part_count as usize)
+            .unwrap_or(u64::MAX)
+    }
+
+    fn finish_part(&mut self) {
+        let part = std::mem::replace(&mut self.current_part, self.algorithm.into_impl());
+        self.checksum_of_checksums.update(&part.finalize());
+        self.current_part_len = 0;
+        self.part_count += 1;
+    }
+}
+
+impl Checksum for CompositeChecksum {
+    fn update(&mut self, mut bytes: &[u8]) {
+        while !bytes.is_empty() {
+            let part_size = self.current_part_size();
+            let remaining_in_part =
+                usize::try_from(part_size - self.current_part_len).unwrap_or(usize::MAX);
+            let len = bytes.len().min(remaining_in_part);
+            self.current_part.update(&bytes[..len]);
+            self.current_part_len += len as u64;
+            bytes = &bytes[len..];
+
+            if self.current_part_len == part_size {
+                self.finish_part();
+            }
+        }
+    }
+
+    fn finalize(self: Box<Self>) -> Bytes {
+        self.finalize_checked().unwrap_or_default()
+    }
+
+    fn size(&self) -> u64 {
+        Checksum::size(&*self.checksum_of_checksums)
+    }
+}
+
+impl HttpChecksum for CompositeChecksum {
+    fn header_name(&self) -> &'static str {
+        self.checksum_of_checksums.header_name()
+    }
+
+    fn finalize_checked(mut self: Box<Self>) -> Result<Bytes, BoxError> {
+        // An empty object still has a single (empty) part
+        if self.current_part_len > 0 || self.part_count == 0 {
+            self.finish_part();
+        }
+        let explicit_part_count = self
+            .part_sizes
+            .part_count()
+            .map(|count| u32::try_from(count).unwrap_or(u32::MAX));
+        for expected in self
+            .expected_part_count
+            .into_iter()
+            .chain(explicit_part_count)
+        {
+            if expected != self.part_count {
+                return Err(PartCountMismatchError::new(expected, self.part_count).into());
+            }
+        }
+        Ok(self.checksum_of_checksums.finalize())
+    }
+}
+
+/// Combine the CRCs of consecutive parts into the CRC of the full object.
+///
+/// `parts` yields the big-endian CRC of each part along with the length of that part in bytes, in
+/// the order the parts appear in the object. Only the CRC family of algorithms (CRC32, CRC32C and
+/// CRC64NVME) can be combined this way.
+pub fn combine_crcs(
+    algorithm: ChecksumAlgorithm,
+    parts: impl IntoIterator<Item = (Bytes, u64)>,
+) -> Result<Bytes, CombineChecksumsError> {
+    let (width, polynomial) = match algorithm {
+        // Polynomials are in reversed (LSB-first) form
+        ChecksumAlgorithm::Crc32 => (4, 0xEDB8_8320),
+        ChecksumAlgorithm::Crc32c => (4, 0x82F6_3B78),
+        ChecksumAlgorithm::Crc64Nvme => (8, 0x9A6C_9329_AC4B_C9B5),
+        _ => return Err(CombineChecksumsError::unsupported_algorithm(algorithm)),
+    };
+
+    let mut combined = 0u64;
+    for (index, (crc, len)) in parts.into_iter().enumerate() {
+        if crc.len() != width {
+            return Err(CombineChecksumsError::invalid_part_checksum(index));
+        }
+        let crc = crc.iter().fold(0u64, |acc, byte| (acc << 8) | *byte as u64);
+        combined = if index == 0 {
+            crc
+        } else {
+            crc_combine(combined, crc, len, polynomial, width * 8)
+        };
+    }
+
+    Ok(Bytes::copy_from_slice(
+        &combined.to_be_bytes()[(8 - width)..],
+    ))
+}
+
+/// Multiply a `width`-bit GF(2) matrix by a vector.
+fn gf2_matrix_times(matrix: &[u64], mut vector: u64) -> u64 {
+    let mut sum = 0;
+    let mut index = 0;
+    while vector != 0 {
+        if vector & 1 == 1 {
+            sum ^= matrix[index];
+        }
+        vector >>= 1;
+        index += 1;
+    }
+    sum
+}
+
+fn gf2_matrix_square(square: &mut [u64], matrix: &[u64]) {
+    for (index, row) in square.iter_mut().enumerate() {
+        *row = gf2_matrix_times(matrix, matrix[index]);
+    }
+}
+
+/// Combine `crc1` (the CRC of the first block) with `crc2` (the CRC of the following `len2` bytes).
+///
+/// This is the technique used by zlib's `crc32_combine`, generalized over the CRC width. It applies
+/// to reflected CRCs whose initial value and final XOR are all ones, which is true for CRC32,
+/// CRC32C and CRC64NVME.
+fn crc_combine(mut crc1: u64, crc2: u64, mut len2: u64, polynomial: u64, width: usize) -> u64 {
+    if len2 == 0 {
+        return crc1;
+    }
+
+    let mut even = vec![0u64; width];
+    let mut odd = vec![0u64; width];
+
+    // Put the operator for one zero bit in `odd`
+    odd[0] = polynomial;
+    let mut row = 1;
+    for entry in odd.iter_mut().skip(1) {
+        *entry = row;
+        row <<= 1;
+    }
+
+    // Put the operator for two zero bits in `even`, then four zero bits in `odd`
+    gf2_matrix_square(&mut even, &odd);
+    gf2_matrix_square(&mut odd, &even);
+
+    // Apply `len2` zeros to `crc1`. The first square puts the operator for one zero byte (eight
+    // zero bits) in `even`.
+    loop {
+        gf2_matrix_square(&mut even, &odd);
+        if len2 & 1 == 1 {
+            crc1 = gf2_matrix_times(&even, crc1);
+        }
+        len2 >>= 1;
+        if len2 == 0 {
+            break;
+        }
+
+        gf2_matrix_square(&mut odd, &even);
+        if len2 & 1 == 1 {
+            crc1 = gf2_matrix_times(&odd, crc1);
+        }
+        len2 >>= 1;
+        if len2 == 0 {
+            break;
+        }
+    }
+
+    crc1 ^ crc2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        combine_crcs, CompositeChecksum, CompositeChecksumPartSize, CompositeChecksumValue,
+    };
+    use crate::error::PartCountMismatchError;
+    use crate::http::HttpChecksum;
+    use crate::{Checksum, ChecksumAlgorithm};
+    use bytes::Bytes;
+
+    fn checksum_of(algorithm: ChecksumAlgorithm, data: &[u8]) -> Bytes {
+        let mut checksum = algorithm.into_impl();
+        checksum.update(data);
+        checksum.finalize()
+    }
+
+    #[test]
+    fn parse_composite_checksum_value() {
+        let value: CompositeChecksumValue = "AAAAAA==-12".parse().unwrap();
+        assert_eq!(&[0, 0, 0, 0][..], &value.checksum()[..]);
+        assert_eq!(12, value.part_count());
+        assert_eq!("AAAAAA==-12", value.to_string());
+    }
+
+    #[test]
+    fn detect_composite_checksums() {
+        // These ARE NOT composite checksums
+        assert!(!CompositeChecksumValue::is_composite("abcd"));
+        assert!(!CompositeChecksumValue::is_composite("abcd="));
+        assert!(!CompositeChecksumValue::is_composite("abcd=="));
+        assert!(!CompositeChecksumValue::is_composite("1234"));
+        assert!(!CompositeChecksumValue::is_composite("1234="));
+        assert!(!CompositeChecksumValue::is_composite("1234=="));
+        // These ARE composite checksums
+        assert!(CompositeChecksumValue::is_composite("abcd-1"));
+        assert!(CompositeChecksumValue::is_composite("abcd=-12"));
+        assert!(CompositeChecksumValue::is_composite("abcd12-134"));
+        assert!(CompositeChecksumValue::is_composite("abcd==-10000"));
+        // These are gibberish and shouldn't be regarded as composite checksums
+        assert!(!CompositeChecksumValue::is_composite(""));
+        assert!(!CompositeChecksumValue::is_composite(
+            "Spaces? In my header values?"
+        ));
+        assert!(!CompositeChecksumValue::is_composite("abcd==-134!#{!#"));
+        assert!(!CompositeChecksumValue::is_composite("abcd==-"));
+        assert!(!CompositeChecksumValue::is_composite("abcd==--11"));
+        assert!(!CompositeChecksumValue::is_composite("abcd==-AA"));
+    }
+
+    #[test]
+    fn composite_checksum_is_checksum_of_part_checksums() {
+        let data = b"This is some test text for an SdkBody";
+        for algorithm in [
+            ChecksumAlgorithm::Crc32,
+            ChecksumAlgorithm::Crc64Nvme,
+            ChecksumAlgorithm::Sha256,
+        ] {
+            let mut expected = algorithm.into_impl();
+            for part in data.chunks(10) {
+                expected.update(&checksum_of(algorithm, part));
+            }
+            let expected = expected.finalize();
+
+            let mut composite = Box::new(CompositeChecksum::new(
+                algorithm,
+                CompositeChecksumPartSize::new(10).unwrap(),
+            ));
+            // Feed the data in chunks that don't line up with part boundaries
+            for chunk in data.chunks(7) {
+                composite.update(chunk);
+            }
+            assert_eq!(4, composite.part_count());
+            assert_eq!(expected, composite.finalize(), "{algorithm:?}");
+        }
+    }
+
+    #[test]
+    fn part_sizes_must_be_greater_than_zero() {
+        CompositeChecksumPartSize::new(0).expect_err("zero part size");
+        CompositeChecksumPartSize::from_part_sizes([5, 0, 3]).expect_err("zero part size");
+        CompositeChecksumPartSize::from_part_sizes([]).expect_err("no parts");
+    }
+
+    #[test]
+    fn composite_checksum_with_uneven_part_sizes() {
+        let data = b"This is some test text for an SdkBody";
+        let algorithm = ChecksumAlgorithm::Crc32c;
+        let mut expected = algorithm.into_impl();
+        for part in [&data[..20], &data[20..25], &data[25..]] {
+            expected.update(&checksum_of(algorithm, part));
+        }
+        let expected = expected.finalize();
+
+        let part_sizes = CompositeChecksumPartSize::from_part_sizes([20, 5, 12]).unwrap();
+        let mut composite = Box::new(CompositeChecksum::new(algorithm, part_sizes));
+        for chunk in data.chunks(3) {
+            composite.update(chunk);
+        }
+        assert_eq!(3, composite.part_count());
+        assert_eq!(expected, composite.finalize());
+    }
+
+    #[test]
+    fn composite_checksum_fails_on_part_count_mismatch() {
+        let data = b"This is some test text for an SdkBody";
+        let algorithm = ChecksumAlgorithm::Crc32;
+
+        // More data than the given part sizes account for
+        let part_sizes = CompositeChecksumPartSize::from_part_sizes([20, 5]).unwrap();
+        let mut composite = Box::new(CompositeChecksum::new(algorithm, part_sizes.clone()));
+        composite.update(data);
+        assert_eq!(3, composite.part_count());
+        let err = composite.finalize_checked().expect_err("wrong part count");
+        let err = err.downcast_ref::<PartCountMismatchError>().unwrap();
+        assert_eq!((2, 3), (err.expected(), err.actual()));
+
+        // `finalize` can't return the error, so it returns a checksum that never matches
+        let mut composite = Box::new(CompositeChecksum::new(algorithm, part_sizes));
+        composite.update(data);
+        assert!(composite.finalize().is_empty());
+
+        // Fewer parts than the composite checksum value says
+        let part_sizes = CompositeChecksumPartSize::new(10).unwrap();
+        let mut composite = algorithm.into_composite_impl(part_sizes, 5);
+        composite.update(data);
+        let err = composite.finalize_checked().expect_err("wrong part count");
+        let err = err.downcast_ref::<PartCountMismatchError>().unwrap();
+        assert_eq!((5, 4), (err.expected(), err.actual()));
+
+        // The expected part count is checked along with the part sizes
+        let part_sizes = CompositeChecksumPartSize::from_part_sizes([20, 5, 12]).unwrap();
+        let mut composite = algorithm.into_composite_impl(part_sizes.clone(), 2);
+        composite.update(data);
+        composite.finalize_checked().expect_err("wrong part count");
+        let mut composite = algorithm.into_composite_impl(part_sizes, 3);
+        composite.update(data);
+        composite.finalize_checked().expect("right part count");
+    }
+
+    #[test]
+    fn combined_crcs_match_full_object_crc() {
+        let data = b"This is some test text for an SdkBody";
+        for algorithm in [
+            ChecksumAlgorithm::Crc32,
+            ChecksumAlgorithm::Crc32c,
+            ChecksumAlgorithm::Crc64Nvme,
+        ] {
+            let parts = data
+                .chunks(5)
+                .map(|part| (checksum_of(algorithm, part), part.len() as u64));
+            let combined = combine_crcs(algorithm, parts).unwrap();
+            assert_eq!(checksum_of(algorithm, data), combined, "{algorithm:?}");
+        }
+    }
+
+    #[test]
+    fn combine_crcs_rejects_non_crc_algorithms() {
+        let parts = [(checksum_of(ChecksumAlgorithm::Sha1, b"abc"), 3)];
+        combine_crcs(ChecksumAlgorithm::Sha1, parts).expect_err("SHA-1 can't be combined");
+    }
+
+    #[test]
+    fn combine_crcs_rejects_wrong_width() {
+        let parts = [(checksum_of(ChecksumAlgorithm::Crc32, b"abc"), 3)];
+        combine_crcs(ChecksumAlgorithm::Crc64Nvme, parts).expect_err("CRC32 is too short");
+    }
+}
@@ -3,6 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::ChecksumAlgorithm;
 use std::error::Error;
 use std::fmt;
 
@@ -29,10 +30,167 @@ impl fmt::Display for UnknownChecksumAlgorithmError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            r#"unknown checksum algorithm "{}", please pass a known algorithm name ("crc32", "crc32c", "sha1", "sha256", "md5")"#,
+            r#"unknown checksum algorithm "{}", please pass a known algorithm name ("crc32", "crc32c", "crc64nvme", "sha1", "sha256", "sha512", "md5")"#,
             self.checksum_algorithm
         )
     }
 }
 
 impl Error for UnknownChecksumAlgorithmError {}
+
+/// A composite checksum value couldn't be parsed
+#[derive(Debug)]
+pub struct InvalidCompositeChecksumError {
+    value: String,
+}
+
+impl InvalidCompositeChecksumError {
+    pub(crate) fn new(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+        }
+    }
+
+    /// The value that couldn't be parsed
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl fmt::Display for InvalidCompositeChecksumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            r#"invalid composite checksum "{}", expected a base64-encoded checksum followed by a dash and a part count"#,
+            self.value
+        )
+    }
+}
+
+impl Error for InvalidCompositeChecksumError {}
+
+#[derive(Debug)]
+enum InvalidPartSizeErrorKind {
+    ZeroPartSize { index: Option<usize> },
+    NoParts,
+}
+
+/// The part sizes of a multipart object were invalid
+#[derive(Debug)]
+pub struct InvalidPartSizeError {
+    kind: InvalidPartSizeErrorKind,
+}
+
+impl InvalidPartSizeError {
+    pub(crate) fn zero_part_size(index: Option<usize>) -> Self {
+        Self {
+            kind: InvalidPartSizeErrorKind::ZeroPartSize { index },
+        }
+    }
+
+    pub(crate) fn no_parts() -> Self {
+        Self {
+            kind: InvalidPartSizeErrorKind::NoParts,
+        }
+    }
+}
+
+impl fmt::Display for InvalidPartSizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            InvalidPartSizeErrorKind::ZeroPartSize { index: None } => {
+                write!(f, "part size must be greater than zero")
+            }
+            InvalidPartSizeErrorKind::ZeroPartSize { index: Some(index) } => {
+                write!(f, "the size of part {index} must be greater than zero")
+            }
+            InvalidPartSizeErrorKind::NoParts => {
+                write!(f, "at least one part size must be given")
+            }
+        }
+    }
+}
+
+impl Error for InvalidPartSizeError {}
+
+/// The data of a multipart object split into a different number of parts than expected
+///
+/// This is returned when validating a composite checksum whose part count (the `-N` suffix of the
+/// checksum value), or list of part sizes, doesn't match the parts that the data was split into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartCountMismatchError {
+    expected: u32,
+    actual: u32,
+}
+
+impl PartCountMismatchError {
+    pub(crate) fn new(expected: u32, actual: u32) -> Self {
+        Self { expected, actual }
+    }
+
+    /// The number of parts that the object was expected to have
+    pub fn expected(&self) -> u32 {
+        self.expected
+    }
+
+    /// The number of parts that the data was split into
+    pub fn actual(&self) -> u32 {
+        self.actual
+    }
+}
+
+impl fmt::Display for PartCountMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected the object to have {} parts, but the data was split into {} parts",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl Error for PartCountMismatchError {}
+
+#[derive(Debug)]
+enum CombineChecksumsErrorKind {
+    UnsupportedAlgorithm(ChecksumAlgorithm),
+    InvalidPartChecksum { index: usize },
+}
+
+/// The checksums of multiple parts couldn't be combined into a full object checksum
+#[derive(Debug)]
+pub struct CombineChecksumsError {
+    kind: CombineChecksumsErrorKind,
+}
+
+impl CombineChecksumsError {
+    pub(crate) fn unsupported_algorithm(algorithm: ChecksumAlgorithm) -> Self {
+        Self {
+            kind: CombineChecksumsErrorKind::UnsupportedAlgorithm(algorithm),
+        }
+    }
+
+    pub(crate) fn invalid_part_checksum(index: usize) -> Self {
+        Self {
+            kind: CombineChecksumsErrorKind::InvalidPartChecksum { index },
+        }
+    }
+}
+
+impl fmt::Display for CombineChecksumsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            CombineChecksumsErrorKind::UnsupportedAlgorithm(algorithm) => write!(
+                f,
+                "the {} checksum algorithm doesn't support combining part checksums into a full object checksum",
+                algorithm.as_str()
+            ),
+            CombineChecksumsErrorKind::InvalidPartChecksum { index } => write!(
+                f,
+                "the checksum of part {index} has the wrong length for the checksum algorithm"
+            ),
+        }
+    }
+}
+
+impl Error for CombineChecksumsError {}
@@ -21,14 +21,17 @@ use bytes::Bytes;
 use std::str::FromStr;
 
 pub mod body;
+pub mod composite;
 pub mod error;
 pub mod http;
 
 // Valid checksum algorithm names
 pub const CRC_32_NAME: &str = "crc32";
 pub const CRC_32_C_NAME: &str = "crc32c";
+pub const CRC_64_NVME_NAME: &str = "crc64nvme";
 pub const SHA_1_NAME: &str = "sha1";
 pub const SHA_256_NAME: &str = "sha256";
+pub const SHA_512_NAME: &str = "sha512";
 pub const MD5_NAME: &str = "md5";
 
 /// We only support checksum calculation and validation for these checksum algorithms.
@@ -36,9 +39,11 @@ pub const MD5_NAME: &str = "md5";
 pub enum ChecksumAlgorithm {
     Crc32,
     Crc32c,
+    Crc64Nvme,
     Md5,
     Sha1,
     Sha256,
+    Sha512,
 }
 
 impl FromStr for ChecksumAlgorithm {
@@ -47,8 +52,10 @@ impl FromStr for ChecksumAlgorithm {
     /// Create a new `ChecksumAlgorithm` from an algorithm name. Valid algorithm names are:
     /// - "crc32"
     /// - "crc32c"
+    /// - "crc64nvme"
     /// - "sha1"
     /// - "sha256"
+    /// - "sha512"
     /// - "md5"
     ///
     /// Passing an invalid name will return an error.
@@ -57,10 +64,14 @@ impl FromStr for ChecksumAlgorithm {
             Ok(Self::Crc32)
         } else if checksum_algorithm.eq_ignore_ascii_case(CRC_32_C_NAME) {
             Ok(Self::Crc32c)
+        } else if checksum_algorithm.eq_ignore_ascii_case(CRC_64_NVME_NAME) {
+            Ok(Self::Crc64Nvme)
         } else if checksum_algorithm.eq_ignore_ascii_case(SHA_1_NAME) {
             Ok(Self::Sha1)
         } else if checksum_algorithm.eq_ignore_ascii_case(SHA_256_NAME) {
             Ok(Self::Sha256)
+        } else if checksum_algorithm.eq_ignore_ascii_case(SHA_512_NAME) {
+            Ok(Self::Sha512)
         } else if checksum_algorithm.eq_ignore_ascii_case(MD5_NAME) {
             Ok(Self::Md5)
         } else {
@@ -75,20 +86,40 @@ impl ChecksumAlgorithm {
         match self {
             Self::Crc32 => Box::<Crc32>::default(),
             Self::Crc32c => Box::<Crc32c>::default(),
+            Self::Crc64Nvme => Box::<Crc64Nvme>::default(),
             Self::Md5 => Box::<Md5>::default(),
             Self::Sha1 => Box::<Sha1>::default(),
             Self::Sha256 => Box::<Sha256>::default(),
+            Self::Sha512 => Box::<Sha512>::default(),
         }
     }
 
+    /// Return a [`composite::CompositeChecksum`] for this algorithm
+    ///
+    /// Composite checksums are the checksum of the checksums of each part of an object that was
+    /// uploaded with a multipart upload. `part_count` is the number of parts that the composite
+    /// checksum value says the object has (the `N` of its `-N` suffix), which is validated along
+    /// with the checksum.
+    pub fn into_composite_impl(
+        self,
+        part_size: composite::CompositeChecksumPartSize,
+        part_count: u32,
+    ) -> Box<dyn http::HttpChecksum> {
+        Box::new(
+            composite::CompositeChecksum::new(self, part_size).with_expected_part_count(part_count),
+        )
+    }
+
     /// Return the name of this algorithm in string form
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Crc32 => CRC_32_NAME,
             Self::Crc32c => CRC_32_C_NAME,
+            Self::Crc64Nvme => CRC_64_NVME_NAME,
             Self::Md5 => MD5_NAME,
             Self::Sha1 => SHA_1_NAME,
             Self::Sha256 => SHA_256_NAME,
+            Self::Sha512 => SHA_512_NAME,
         }
     }
 }
@@ -183,6 +214,47 @@ impl Checksum for Crc32c {
     }
 }
 
+#[derive(Default)]
+struct Crc64Nvme {
+    hasher: crc64fast_nvme::Digest,
+}
+
+// `crc64fast_nvme::Digest` doesn't implement `Debug`, so this can't be derived
+impl std::fmt::Debug for Crc64Nvme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Crc64Nvme")
+            .field("sum", &self.hasher.sum64())
+            .finish()
+    }
+}
+
+impl Crc64Nvme {
+    fn update(&mut self, bytes: &[u8]) {
+        self.hasher.write(bytes);
+    }
+
+    fn finalize(self) -> Bytes {
+        Bytes::copy_from_slice(self.hasher.sum64().to_be_bytes().as_slice())
+    }
+
+    // Size of the checksum in bytes
+    fn size() -> u64 {
+        8
+    }
+}
+
+impl Checksum for Crc64Nvme {
+    fn update(&mut self, bytes: &[u8]) {
+        Self::update(self, bytes)
+    }
+    fn finalize(self: Box<Self>) -> Bytes {
+        Self::finalize(*self)
+    }
+    fn size(&self) -> u64 {
+        Self::size()
+    }
+}
+
 #[derive(Debug, Default)]
 struct Sha1 {
     hasher: sha1::Sha1,
@@ -254,6 +326,41 @@ impl Checksum for Sha256 {
     }
 }
 
+#[derive(Debug, Default)]
+struct Sha512 {
+    hasher: sha2::Sha512,
+}
+
+impl Sha512 {
+    fn update(&mut self, bytes: &[u8]) {
+        use sha2::Digest;
+        self.hasher.update(bytes);
+    }
+
+    fn finalize(self) -> Bytes {
+        use sha2::Digest;
+        Bytes::copy_from_slice(self.hasher.finalize().as_slice())
+    }
+
+    // Size of the checksum in bytes
+    fn size() -> u64 {
+        use sha2::Digest;
+        sha2::Sha512::output_size() as u64
+    }
+}
+
+impl Checksum for Sha512 {
+    fn update(&mut self, bytes: &[u8]) {
+        Self::update(self, bytes);
+    }
+    fn finalize(self: Box<Self>) -> Bytes {
+        Self::finalize(*self)
+    }
+    fn size(&self) -> u64 {
+        Self::size()
+    }
+}
+
 #[derive(Debug, Default)]
 struct Md5 {
     hasher: md5::Md5,
@@ -293,10 +400,10 @@ impl Checksum for Md5 {
 mod tests {
     use super::{
         http::{
-            CRC_32_C_HEADER_NAME, CRC_32_HEADER_NAME, MD5_HEADER_NAME, SHA_1_HEADER_NAME,
-            SHA_256_HEADER_NAME,
+            CRC_32_C_HEADER_NAME, CRC_32_HEADER_NAME, CRC_64_NVME_HEADER_NAME, MD5_HEADER_NAME,
+            SHA_1_HEADER_NAME, SHA_256_HEADER_NAME, SHA_512_HEADER_NAME,
         },
-        Crc32, Crc32c, Md5, Sha1, Sha256,
+        Crc32, Crc32c, Crc64Nvme, Md5, Sha1, Sha256, Sha512,
     };
 
     use crate::http::HttpChecksum;
@@ -349,6 +456,19 @@ mod tests {
         assert_eq!(decoded_checksum, expected_checksum);
     }
 
+    #[test]
+    fn test_crc64nvme_checksum() {
+        let mut checksum = Crc64Nvme::default();
+        checksum.update(TEST_DATA.as_bytes());
+        let checksum_result = Box::new(checksum).headers();
+        let encoded_checksum = checksum_result.get(CRC_64_NVME_HEADER_NAME).unwrap();
+        let decoded_checksum = base64_encoded_checksum_to_hex_string(encoded_checksum);
+
+        let expected_checksum = "0xAECAF3AF9C98A855";
+
+        assert_eq!(decoded_checksum, expected_checksum);
+    }
+
     #[test]
     fn test_sha1_checksum() {
         let mut checksum = Sha1::default();
@@ -376,6 +496,19 @@ mod tests {
         assert_eq!(decoded_checksum, expected_checksum);
     }
 
+    #[test]
+    fn test_sha512_checksum() {
+        let mut checksum = Sha512::default();
+        checksum.update(TEST_DATA.as_bytes());
+        let checksum_result = Box::new(checksum).headers();
+        let encoded_checksum = checksum_result.get(SHA_512_HEADER_NAME).unwrap();
+        let decoded_checksum = base64_encoded_checksum_to_hex_string(encoded_checksum);
+
+        let expected_checksum = "0x0E1E21ECF105EC853D24D728867AD70613C21663A4693074B2A3619C1BD39D66B588C33723BB466C72424E80E3CA63C249078AB347BAB9428500E7EE43059D0D";
+
+        assert_eq!(decoded_checksum, expected_checksum);
+    }
+
     #[test]
     fn test_md5_checksum() {
         let mut checksum = Md5::default();
@@ -0,0 +1,294 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! HTTP body-wrappers that compress or decompress the data of the body they wrap.
+
+use crate::{Decoder, Encoder};
+use bytes::{Buf, Bytes};
+use http::HeaderMap;
+use http_body::{Body, SizeHint};
+use pin_project_lite::pin_project;
+use std::error::Error as StdError;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+type BoxError = Box<dyn StdError + Send + Sync>;
+
+/// Compresses or decompresses data incrementally.
+trait Transcode {
+    fn update(&mut self, data: &[u8]) -> io::Result<Bytes>;
+    fn finish(self) -> io::Result<Bytes>;
+}
+
+impl Transcode for Encoder {
+    fn update(&mut self, data: &[u8]) -> io::Result<Bytes> {
+        self.encode(data)
+    }
+
+    fn finish(self) -> io::Result<Bytes> {
+        Encoder::finish(self)
+    }
+}
+
+impl Transcode for Decoder {
+    fn update(&mut self, data: &[u8]) -> io::Result<Bytes> {
+        self.decode(data)
+    }
+
+    fn finish(self) -> io::Result<Bytes> {
+        Decoder::finish(self)
+    }
+}
+
+/// Polls `body` for data until `transcoder` produces output, or the body ends.
+///
+/// `transcoder` is taken once the body ends, so that its remaining output is emitted exactly once.
+fn poll_transcoded<B, T>(
+    mut body: Pin<&mut B>,
+    transcoder: &mut Option<T>,
+    cx: &mut Context<'_>,
+) -> Poll<Option<Result<Bytes, BoxError>>>
+where
+    B: Body,
+    B::Error: Into<BoxError>,
+    T: Transcode,
+{
+    loop {
+        let Some(active) = transcoder.as_mut() else {
+            return Poll::Ready(None);
+        };
+        let output = match ready!(body.as_mut().poll_data(cx)) {
+            Some(Ok(mut data)) => {
+                let mut output = Vec::new();
+                while data.has_remaining() {
+                    let chunk = data.chunk();
+                    let len = chunk.len();
+                    output.extend_from_slice(&active.update(chunk)?);
+                    data.advance(len);
+                }
+                Bytes::from(output)
+            }
+            Some(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
+            None => transcoder.take().expect("checked above").finish()?,
+        };
+        if !output.is_empty() {
+            return Poll::Ready(Some(Ok(output)));
+        }
+    }
+}
+
+pin_project! {
+    /// A body-wrapper that compresses the data of the body it wraps.
+    ///
+    /// The trailers of the wrapped body are passed through unchanged. Because the compressed
+    /// length isn't known in advance, any `Content-Length` header describing the wrapped body must
+    /// be removed or replaced.
+    #[derive(Debug)]
+    pub struct CompressedBody<InnerBody> {
+        #[pin]
+        body: InnerBody,
+        encoder: Option<Encoder>,
+    }
+}
+
+impl<InnerBody> CompressedBody<InnerBody> {
+    /// Given a body and an [`Encoder`], create a new `CompressedBody`.
+    pub fn new(body: InnerBody, encoder: Encoder) -> Self {
+        Self {
+            body,
+            encoder: Some(encoder),
+        }
+    }
+}
+
+impl<InnerBody> Body for CompressedBody<InnerBody>
+where
+    InnerBody: Body,
+    InnerBody::Error: Into<BoxError>,
+{
+    type Data = Bytes;
+    type Error = BoxError;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = self.project();
+        poll_transcoded(this.body, this.encoder, cx)
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        self.project().body.poll_trailers(cx).map_err(Into::into)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.encoder.is_none() && self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::default()
+    }
+}
+
+pin_project! {
+    /// A body-wrapper that decompresses the data of the body it wraps.
+    ///
+    /// If the data of the wrapped body isn't validly compressed, polling this body returns an
+    /// error. The trailers of the wrapped body are passed through unchanged.
+    #[derive(Debug)]
+    pub struct DecompressedBody<InnerBody> {
+        #[pin]
+        body: InnerBody,
+        decoder: Option<Decoder>,
+    }
+}
+
+impl<InnerBody> DecompressedBody<InnerBody> {
+    /// Given a body and a [`Decoder`], create a new `DecompressedBody`.
+    pub fn new(body: InnerBody, decoder: Decoder) -> Self {
+        Self {
+            body,
+            decoder: Some(decoder),
+        }
+    }
+}
+
+impl<InnerBody> Body for DecompressedBody<InnerBody>
+where
+    InnerBody: Body,
+    InnerBody::Error: Into<BoxError>,
+{
+    type Data = Bytes;
+    type Error = BoxError;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = self.project();
+        poll_transcoded(this.body, this.decoder, cx)
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        self.project().body.poll_trailers(cx).map_err(Into::into)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.decoder.is_none() && self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CompressionAlgorithm, CompressionOptions};
+    use std::convert::Infallible;
+
+    /// A body that yields its chunks one at a time, followed by its trailers.
+    struct ChunkedBody {
+        chunks: Vec<Bytes>,
+        trailers: Option<HeaderMap>,
+    }
+
+    impl Body for ChunkedBody {
+        type Data = Bytes;
+        type Error = Infallible;
+
+        fn poll_data(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+            if self.chunks.is_empty() {
+                Poll::Ready(None)
+            } else {
+                Poll::Ready(Some(Ok(self.chunks.remove(0))))
+            }
+        }
+
+        fn poll_trailers(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+            Poll::Ready(Ok(self.trailers.take()))
+        }
+    }
+
+    async fn collect<B: Body<Data = Bytes> + Unpin>(mut body: B) -> Result<Vec<u8>, B::Error> {
+        let mut output = Vec::new();
+        while let Some(data) = body.data().await {
+            output.extend_from_slice(&data?);
+        }
+        Ok(output)
+    }
+
+    fn chunks(data: &[u8], size: usize) -> Vec<Bytes> {
+        data.chunks(size).map(Bytes::copy_from_slice).collect()
+    }
+
+    #[tokio::test]
+    async fn compressed_body_round_trip() {
+        let text = "Hello, world! ".repeat(100);
+        let mut trailers = HeaderMap::new();
+        trailers.insert("x-amz-checksum-crc32", "AAAAAA==".parse().unwrap());
+        let body = ChunkedBody {
+            chunks: chunks(text.as_bytes(), 7),
+            trailers: Some(trailers.clone()),
+        };
+        let mut body = CompressedBody::new(
+            body,
+            CompressionAlgorithm::Gzip.encoder(&CompressionOptions::default()),
+        );
+
+        let compressed = collect(&mut body).await.unwrap();
+        assert!(compressed.len() < text.len());
+        assert_eq!(Some(trailers), body.trailers().await.unwrap());
+
+        let body = ChunkedBody {
+            chunks: chunks(&compressed, 5),
+            trailers: None,
+        };
+        let body = DecompressedBody::new(body, CompressionAlgorithm::Gzip.decoder());
+        let decompressed = collect(body).await.unwrap();
+        assert_eq!(text.as_bytes(), &decompressed[..]);
+    }
+
+    #[tokio::test]
+    async fn empty_body_is_compressed() {
+        let body = ChunkedBody {
+            chunks: vec![],
+            trailers: None,
+        };
+        let body = CompressedBody::new(
+            body,
+            CompressionAlgorithm::Gzip.encoder(&CompressionOptions::default()),
+        );
+        let compressed = collect(body).await.unwrap();
+        let decompressed = CompressionAlgorithm::Gzip
+            .decompress_bytes(&compressed)
+            .unwrap();
+        assert!(decompressed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn invalid_data_fails_to_decompress() {
+        let body = ChunkedBody {
+            chunks: chunks(b"this isn't compressed", 4),
+            trailers: None,
+        };
+        let body = DecompressedBody::new(body, CompressionAlgorithm::Gzip.decoder());
+        collect(body).await.expect_err("invalid gzip data");
+    }
+}
@@ -0,0 +1,355 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+/* Automatically managed default lints */
+#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+/* End of automatically managed default lints */
+#![warn(
+    missing_docs,
+    rustdoc::missing_crate_level_docs,
+    unreachable_pub,
+    rust_2018_idioms
+)]
+
+//! Compression and decompression of HTTP payloads.
+//!
+//! Clients use this crate to compress the requests of operations with the Smithy
+//! [`@requestCompression`](https://smithy.io/2.0/spec/behavior-traits.html#requestcompression-trait)
+//! trait. Servers use it to decompress those requests and to compress their responses.
+
+use crate::error::{InvalidCompressionOptionsError, UnknownCompressionAlgorithmError};
+use bytes::Bytes;
+use flate2::write::{GzDecoder, GzEncoder};
+use flate2::Compression;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+pub mod body;
+pub mod error;
+
+/// The name of the gzip compression algorithm, as used in `Content-Encoding` headers.
+pub const GZIP_NAME: &str = "gzip";
+
+/// The minimum size, in bytes, of request payloads that clients compress by default.
+pub const DEFAULT_MIN_COMPRESSION_SIZE_BYTES: u32 = 10_240;
+
+/// The largest minimum compression size, in bytes, that clients can be configured with.
+pub const MAX_MIN_COMPRESSION_SIZE_BYTES: u32 = 10_485_760;
+
+/// The compression level used by default.
+const DEFAULT_LEVEL: u32 = 6;
+
+/// The highest compression level.
+const MAX_LEVEL: u32 = 9;
+
+/// We only support compression and decompression with these algorithms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CompressionAlgorithm {
+    /// The [gzip](https://datatracker.ietf.org/doc/html/rfc1952) algorithm.
+    Gzip,
+}
+
+impl FromStr for CompressionAlgorithm {
+    type Err = UnknownCompressionAlgorithmError;
+
+    /// Create a new `CompressionAlgorithm` from an algorithm name.
+    ///
+    /// Valid algorithm names are:
+    /// - "gzip"
+    ///
+    /// Passing an invalid name will return an error.
+    fn from_str(compression_algorithm: &str) -> Result<Self, Self::Err> {
+        if compression_algorithm.eq_ignore_ascii_case(GZIP_NAME) {
+            Ok(Self::Gzip)
+        } else {
+            Err(UnknownCompressionAlgorithmError::new(compression_algorithm))
+        }
+    }
+}
+
+impl fmt::Display for CompressionAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl CompressionAlgorithm {
+    /// Return the name of this algorithm, as used in `Content-Encoding` headers.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gzip => GZIP_NAME,
+        }
+    }
+
+    /// Create a new [`Encoder`] that compresses data with this algorithm.
+    pub fn encoder(&self, options: &CompressionOptions) -> Encoder {
+        match self {
+            Self::Gzip => Encoder(EncoderInner::Gzip(GzEncoder::new(
+                Vec::new(),
+                Compression::new(options.level),
+            ))),
+        }
+    }
+
+    /// Create a new [`Decoder`] that decompresses data compressed with this algorithm.
+    pub fn decoder(&self) -> Decoder {
+        match self {
+            Self::Gzip => Decoder(DecoderInner::Gzip(GzDecoder::new(Vec::new()))),
+        }
+    }
+
+    /// Compress `bytes` with this algorithm.
+    pub fn compress_bytes(&self, bytes: &[u8], options: &CompressionOptions) -> io::Result<Bytes> {
+        let mut encoder = self.encoder(options);
+        let head = encoder.encode(bytes)?;
+        let tail = encoder.finish()?;
+        Ok(concat(head, tail))
+    }
+
+    /// Decompress `bytes`, which were compressed with this algorithm.
+    pub fn decompress_bytes(&self, bytes: &[u8]) -> io::Result<Bytes> {
+        let mut decoder = self.decoder();
+        let head = decoder.decode(bytes)?;
+        let tail = decoder.finish()?;
+        Ok(concat(head, tail))
+    }
+}
+
+fn concat(head: Bytes, tail: Bytes) -> Bytes {
+    if tail.is_empty() {
+        head
+    } else {
+        let mut bytes = Vec::with_capacity(head.len() + tail.len());
+        bytes.extend_from_slice(&head);
+        bytes.extend_from_slice(&tail);
+        bytes.into()
+    }
+}
+
+/// Options for compressing payloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionOptions {
+    level: u32,
+    min_compression_size_bytes: u32,
+}
+
+impl Default for CompressionOptions {
+    fn default() -> Self {
+        Self {
+            level: DEFAULT_LEVEL,
+            min_compression_size_bytes: DEFAULT_MIN_COMPRESSION_SIZE_BYTES,
+        }
+    }
+}
+
+impl CompressionOptions {
+    /// The compression level, from `0` (no compression) to `9` (best compression).
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    /// The minimum size, in bytes, of payloads that should be compressed. Smaller payloads are
+    /// sent as they are.
+    pub fn min_compression_size_bytes(&self) -> u32 {
+        self.min_compression_size_bytes
+    }
+
+    /// Set the compression level, from `0` (no compression) to `9` (best compression).
+    /// Defaults to `6`.
+    pub fn with_level(self, level: u32) -> Result<Self, InvalidCompressionOptionsError> {
+        if level > MAX_LEVEL {
+            return Err(InvalidCompressionOptionsError::new(format!(
+                "compression level must be between 0 and {MAX_LEVEL}, but was {level}"
+            )));
+        }
+        Ok(Self { level, ..self })
+    }
+
+    /// Set the minimum size, in bytes, of payloads that should be compressed. It must be no larger
+    /// than [`MAX_MIN_COMPRESSION_SIZE_BYTES`]. Defaults to [`DEFAULT_MIN_COMPRESSION_SIZE_BYTES`].
+    pub fn with_min_compression_size_bytes(
+        self,
+        min_compression_size_bytes: u32,
+    ) -> Result<Self, InvalidCompressionOptionsError> {
+        if min_compression_size_bytes > MAX_MIN_COMPRESSION_SIZE_BYTES {
+            return Err(InvalidCompressionOptionsError::new(format!(
+                "minimum compression size must be between 0 and {MAX_MIN_COMPRESSION_SIZE_BYTES} bytes, \
+                 but was {min_compression_size_bytes}"
+            )));
+        }
+        Ok(Self {
+            min_compression_size_bytes,
+            ..self
+        })
+    }
+}
+
+/// Compresses data incrementally.
+///
+/// Data is passed to [`Encoder::encode`] as it becomes available, and [`Encoder::finish`] must be
+/// called once all of it has been passed. Together, the bytes they return are the compressed data.
+pub struct Encoder(EncoderInner);
+
+enum EncoderInner {
+    Gzip(GzEncoder<Vec<u8>>),
+}
+
+impl Encoder {
+    /// Compress `data`, returning the compressed bytes that are ready so far. These may be empty.
+    pub fn encode(&mut self, data: &[u8]) -> io::Result<Bytes> {
+        match &mut self.0 {
+            EncoderInner::Gzip(encoder) => {
+                encoder.write_all(data)?;
+                Ok(std::mem::take(encoder.get_mut()).into())
+            }
+        }
+    }
+
+    /// Finish compressing, returning the remaining compressed bytes.
+    pub fn finish(self) -> io::Result<Bytes> {
+        match self.0 {
+            EncoderInner::Gzip(encoder) => Ok(encoder.finish()?.into()),
+        }
+    }
+}
+
+impl fmt::Debug for Encoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let algorithm = match self.0 {
+            EncoderInner::Gzip(_) => CompressionAlgorithm::Gzip,
+        };
+        f.debug_struct("Encoder")
+            .field("algorithm", &algorithm)
+            .finish()
+    }
+}
+
+/// Decompresses data incrementally.
+///
+/// Data is passed to [`Decoder::decode`] as it becomes available, and [`Decoder::finish`] must be
+/// called once all of it has been passed. Together, the bytes they return are the decompressed
+/// data. Data that isn't validly compressed results in an error.
+pub struct Decoder(DecoderInner);
+
+enum DecoderInner {
+    Gzip(GzDecoder<Vec<u8>>),
+}
+
+impl Decoder {
+    /// Decompress `data`, returning the decompressed bytes that are ready so far. These may be
+    /// empty.
+    pub fn decode(&mut self, data: &[u8]) -> io::Result<Bytes> {
+        match &mut self.0 {
+            DecoderInner::Gzip(decoder) => {
+                decoder.write_all(data)?;
+                Ok(std::mem::take(decoder.get_mut()).into())
+            }
+        }
+    }
+
+    /// Finish decompressing, returning the remaining decompressed bytes.
+    ///
+    /// This fails if the data passed to [`Decoder::decode`] was incomplete.
+    pub fn finish(self) -> io::Result<Bytes> {
+        match self.0 {
+            DecoderInner::Gzip(decoder) => Ok(decoder.finish()?.into()),
+        }
+    }
+}
+
+impl fmt::Debug for Decoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let algorithm = match self.0 {
+            DecoderInner::Gzip(_) => CompressionAlgorithm::Gzip,
+        };
+        f.debug_struct("Decoder")
+            .field("algorithm", &algorithm)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const TEXT: &str = "Hello, world! Hello, world! Hello, world! Hello, world! Hello, world!";
+
+    #[test]
+    fn algorithm_names_are_case_insensitive() {
+        assert_eq!(
+            CompressionAlgorithm::Gzip,
+            "GZIP".parse::<CompressionAlgorithm>().unwrap()
+        );
+        assert_eq!("gzip", CompressionAlgorithm::Gzip.to_string());
+        let err = "brotli".parse::<CompressionAlgorithm>().unwrap_err();
+        assert_eq!("brotli", err.compression_algorithm());
+    }
+
+    #[test]
+    fn gzip_round_trip() {
+        let options = CompressionOptions::default();
+        let compressed = CompressionAlgorithm::Gzip
+            .compress_bytes(TEXT.as_bytes(), &options)
+            .unwrap();
+        assert!(compressed.len() < TEXT.len());
+        // gzip magic number
+        assert_eq!(&[0x1f, 0x8b], &compressed[..2]);
+
+        let decompressed = CompressionAlgorithm::Gzip
+            .decompress_bytes(&compressed)
+            .unwrap();
+        assert_eq!(TEXT.as_bytes(), &decompressed[..]);
+    }
+
+    #[test]
+    fn incremental_decoding() {
+        let compressed = CompressionAlgorithm::Gzip
+            .compress_bytes(TEXT.as_bytes(), &CompressionOptions::default())
+            .unwrap();
+        let mut decoder = CompressionAlgorithm::Gzip.decoder();
+        let mut decompressed = Vec::new();
+        for chunk in compressed.chunks(3) {
+            decompressed.extend_from_slice(&decoder.decode(chunk).unwrap());
+        }
+        decompressed.extend_from_slice(&decoder.finish().unwrap());
+        assert_eq!(TEXT.as_bytes(), &decompressed[..]);
+    }
+
+    #[test]
+    fn invalid_data_fails_to_decompress() {
+        let compressed = CompressionAlgorithm::Gzip
+            .compress_bytes(TEXT.as_bytes(), &CompressionOptions::default())
+            .unwrap();
+        let truncated = &compressed[..compressed.len() - 4];
+        CompressionAlgorithm::Gzip
+            .decompress_bytes(truncated)
+            .expect_err("truncated data");
+        CompressionAlgorithm::Gzip
+            .decompress_bytes(TEXT.as_bytes())
+            .expect_err("uncompressed data");
+    }
+
+    #[test]
+    fn options_are_validated() {
+        let options = CompressionOptions::default();
+        assert_eq!(6, options.level());
+        assert_eq!(10_240, options.min_compression_size_bytes());
+        assert_eq!(9, options.with_level(9).unwrap().level());
+        options.with_level(10).expect_err("level too high");
+        assert_eq!(
+            0,
+            options
+                .with_min_compression_size_bytes(0)
+                .unwrap()
+                .min_compression_size_bytes()
+        );
+        options
+            .with_min_compression_size_bytes(MAX_MIN_COMPRESSION_SIZE_BYTES + 1)
+            .expect_err("minimum size too large");
+    }
+}
@@ -7,4 +7,9 @@
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 /* End of automatically managed default lints */
 
+/// Experimental hyper 1.x HTTP client.
+///
+/// A stable hyper 1.x client is now available in `aws-smithy-runtime` under the
+/// `connector-hyper-1-x` feature, as `aws_smithy_runtime::client::http::hyper_1`. Prefer it over
+/// this module, which will be removed in a future release.
 pub mod hyper_1_0;
@@ -18,6 +18,12 @@ use crate::error::{BoxError, Error};
 /// The primary [`Body`] returned by the generated `smithy-rs` service.
 pub type BoxBody = http_body::combinators::UnsyncBoxBody<Bytes, Error>;
 
+/// A stream of body data that [`Body`] can be created from.
+///
+/// Plugins that wrap request bodies convert them into this stream, so that the body type of the
+/// request doesn't change.
+pub type BodyStream = Box<dyn futures_util::Stream<Item = Result<Bytes, BoxError>> + Send>;
+
 // `boxed` is used in the codegen of the implementation of the operation `Handler` trait.
 /// Convert a [`http_body::Body`] into a [`BoxBody`].
 pub fn boxed<B>(body: B) -> BoxBody
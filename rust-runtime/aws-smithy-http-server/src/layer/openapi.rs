@@ -0,0 +1,149 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Middleware for serving the [OpenAPI 3](https://spec.openapis.org/oas/v3.0.3) document of a
+//! service.
+//!
+//! Servers generated with the `openApi` codegen setting include their OpenAPI document as
+//! `crate::openapi::OPENAPI_JSON`, and their service has a `serve_openapi` method that applies
+//! [`OpenApiLayer`] with it.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::layer::openapi::OpenApiLayer;
+//! use tower::Layer;
+//!
+//! // Serve the document of the service at `/openapi.json`.
+//! let openapi_layer = OpenApiLayer::new("/openapi.json", r#"{"openapi": "3.0.2"}"#);
+//! # async fn handle() { }
+//! let app = tower::service_fn(handle);
+//! let app = openapi_layer.layer(app);
+//! ```
+
+use std::borrow::Cow;
+use std::task::{Context, Poll};
+
+use futures_util::future::{ready, Either, Ready};
+use http::{header::CONTENT_TYPE, HeaderValue, Method, Request, Response, StatusCode};
+use tower::{Layer, Service};
+
+use crate::body::BoxBody;
+
+/// A [`tower::Layer`] used to apply [`OpenApiService`].
+#[derive(Clone, Debug)]
+pub struct OpenApiLayer {
+    path: Cow<'static, str>,
+    document: &'static str,
+}
+
+impl OpenApiLayer {
+    /// Serves `document` in response to `GET` requests for `path`.
+    pub fn new(path: impl Into<Cow<'static, str>>, document: &'static str) -> Self {
+        Self {
+            path: path.into(),
+            document,
+        }
+    }
+}
+
+impl<S> Layer<S> for OpenApiLayer {
+    type Service = OpenApiService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        OpenApiService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// A middleware [`Service`] that serves an OpenAPI document, and passes all other requests to the
+/// inner service.
+#[derive(Clone, Debug)]
+pub struct OpenApiService<S> {
+    inner: S,
+    layer: OpenApiLayer,
+}
+
+impl<S, B> Service<Request<B>> for OpenApiService<S>
+where
+    S: Service<Request<B>, Response = Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Either<Ready<Result<Self::Response, Self::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        if request.method() != Method::GET || request.uri().path() != self.layer.path {
+            return Either::Right(self.inner.call(request));
+        }
+
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+            .body(crate::body::to_boxed(self.layer.document))
+            .expect("valid response");
+        Either::Left(ready(Ok(response)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::ServiceExt;
+
+    use super::*;
+
+    const DOCUMENT: &str = r#"{"openapi":"3.0.2"}"#;
+
+    fn service() -> impl Service<Request<hyper::Body>, Response = Response<BoxBody>, Error = Infallible> {
+        OpenApiLayer::new("/openapi.json", DOCUMENT).layer(tower::service_fn(|_request| async {
+            Ok::<_, Infallible>(
+                Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(crate::body::empty())
+                    .unwrap(),
+            )
+        }))
+    }
+
+    fn request(method: Method, uri: &str) -> Request<hyper::Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(hyper::Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn document_is_served() {
+        let response = service()
+            .oneshot(request(Method::GET, "/openapi.json?format=json"))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!("application/json", response.headers()[CONTENT_TYPE]);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(DOCUMENT.as_bytes(), &body[..]);
+    }
+
+    #[tokio::test]
+    async fn other_requests_are_passed_through() {
+        for request in [
+            request(Method::POST, "/openapi.json"),
+            request(Method::GET, "/openapi"),
+            request(Method::GET, "/things/openapi.json"),
+        ] {
+            let response = service().oneshot(request).await.unwrap();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+        }
+    }
+}
@@ -83,11 +83,18 @@ macro_rules! opaque_future {
     };
 }
 
-macro_rules! convert_to_request_rejection {
-    ($from:ty, $to:ident) => {
+/// Implements `From<$from> for RequestRejection` for errors that are encountered while buffering
+/// the request body. Errors caused by the body exceeding its size limit are converted into
+/// `RequestRejection::PayloadTooLarge`, and all others into `RequestRejection::BufferHttpBodyBytes`.
+macro_rules! convert_body_error_to_request_rejection {
+    ($from:ty) => {
         impl From<$from> for RequestRejection {
             fn from(err: $from) -> Self {
-                Self::$to(crate::Error::new(err))
+                let err: crate::error::BoxError = err.into();
+                match crate::rejection::PayloadTooLarge::find(&*err) {
+                    Some(too_large) => Self::PayloadTooLarge(too_large.clone()),
+                    None => Self::BufferHttpBodyBytes(crate::Error::new(err)),
+                }
             }
         }
     };
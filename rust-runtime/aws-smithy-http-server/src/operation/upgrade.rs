@@ -17,8 +17,13 @@ use tower::{util::Oneshot, Service, ServiceExt};
 use tracing::error;
 
 use crate::{
-    body::BoxBody, plugin::Plugin, request::FromRequest, response::IntoResponse,
-    runtime_error::InternalFailureException, service::ServiceShape,
+    body::BoxBody,
+    plugin::Plugin,
+    protocol::rest::router::{method_not_allowed, Preflight},
+    request::FromRequest,
+    response::IntoResponse,
+    runtime_error::InternalFailureException,
+    service::ServiceShape,
 };
 
 use super::OperationShape;
@@ -95,6 +100,9 @@ pin_project! {
         Inner {
             #[pin]
             call: HandlerFut
+        },
+        Rejected {
+            response: Option<http::Response<BoxBody>>
         }
     }
 }
@@ -148,6 +156,10 @@ where
                     };
                     return Poll::Ready(Ok(output));
                 }
+                InnerProj::Rejected { response } => {
+                    let response = response.take().expect("futures cannot be polled after completion");
+                    return Poll::Ready(Ok(response));
+                }
             };
 
             this.inner.as_mut().project_replace(Inner::Inner { call });
@@ -170,7 +182,17 @@ where
         Poll::Ready(Ok(()))
     }
 
-    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        // CORS preflight requests are only routed to operations for `CorsPlugin` to answer them.
+        if let Some(Preflight(allowed_methods)) = req.extensions_mut().remove::<Preflight>() {
+            return UpgradeFuture {
+                service: None,
+                inner: Inner::Rejected {
+                    response: Some(method_not_allowed(allowed_methods)),
+                },
+            };
+        }
+
         let clone = self.inner.clone();
         let service = std::mem::replace(&mut self.inner, clone);
         UpgradeFuture {
@@ -0,0 +1,365 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] that limits the size of request bodies.
+//!
+//! Without a limit, a generated server buffers request bodies of any size before deserializing
+//! them, so a single large request can exhaust the server's memory. [`RequestBodyLimitPlugin`]
+//! rejects requests whose body is larger than the operation allows with a
+//! `413 Payload Too Large` response, rendered by the service's protocol from a [`PayloadTooLarge`]
+//! rejection.
+//!
+//! Requests whose `Content-Length` header exceeds the limit are rejected before their body is
+//! read. Requests without a `Content-Length` header are rejected as soon as the limit is exceeded
+//! while their body is being buffered.
+//!
+//! # Example
+//!
+//! ```
+//! # use aws_smithy_http_server::plugin::HttpPlugins;
+//! # use aws_smithy_http_server::plugin::body_limit::RequestBodyLimitPlugin;
+//! # use aws_smithy_http_server::shape_id::ShapeId;
+//! # const UPLOAD_PHOTO: ShapeId = ShapeId::new("com.aws.example#UploadPhoto", "com.aws.example", "UploadPhoto");
+//! # const STREAM_VIDEO: ShapeId = ShapeId::new("com.aws.example#StreamVideo", "com.aws.example", "StreamVideo");
+//! // Allow request bodies of up to 1 MiB, except for `UploadPhoto`, which accepts up to 10 MiB,
+//! // and `StreamVideo`, which streams its body and isn't limited at all.
+//! let body_limit = RequestBodyLimitPlugin::new(1024 * 1024)
+//!     .with_operation_limit(UPLOAD_PHOTO, 10 * 1024 * 1024)
+//!     .without_limit(STREAM_VIDEO);
+//! let http_plugins = HttpPlugins::new().push(body_limit);
+//! ```
+//!
+//! Bodies without a `Content-Length` header are converted into a stream to be limited, which
+//! discards their trailers. Operations with streaming bodies or that rely on trailers should be
+//! exempted with [`RequestBodyLimitPlugin::without_limit`].
+
+use std::{
+    collections::HashMap,
+    fmt,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::{Buf, Bytes};
+use futures_util::{
+    future::{ready, Either, Ready},
+    Stream,
+};
+use http::header::CONTENT_LENGTH;
+use http_body::Body;
+use tower::Service;
+
+use crate::{
+    body::BoxBody, error::BoxError, operation::OperationShape, rejection::PayloadTooLarge, response::IntoResponse,
+    shape_id::ShapeId,
+};
+
+use super::{HttpMarker, Plugin};
+
+/// The stream that request bodies without a `Content-Length` header are converted into, so that
+/// they can be limited without changing their type.
+pub type LimitedBodyStream = Box<dyn Stream<Item = Result<Bytes, Box<dyn std::error::Error + Send + Sync>>> + Send>;
+
+/// A [`Plugin`] that rejects requests whose body is larger than the operation allows.
+///
+/// See the [module](crate::plugin::body_limit) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct RequestBodyLimitPlugin {
+    default_limit: Option<u64>,
+    operation_limits: HashMap<ShapeId, Option<u64>>,
+}
+
+impl RequestBodyLimitPlugin {
+    /// Creates a new `RequestBodyLimitPlugin` that limits request bodies to `default_limit`
+    /// bytes, unless the operation has its own limit.
+    pub fn new(default_limit: u64) -> Self {
+        Self {
+            default_limit: Some(default_limit),
+            operation_limits: HashMap::new(),
+        }
+    }
+
+    /// Sets the limit, in bytes, for the operation with the given [`ShapeId`].
+    pub fn with_operation_limit(mut self, operation: ShapeId, limit: u64) -> Self {
+        self.operation_limits.insert(operation, Some(limit));
+        self
+    }
+
+    /// Exempts the operation with the given [`ShapeId`] from the default limit.
+    pub fn without_limit(mut self, operation: ShapeId) -> Self {
+        self.operation_limits.insert(operation, None);
+        self
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for RequestBodyLimitPlugin
+where
+    Ser: crate::service::ServiceShape,
+    Op: OperationShape,
+{
+    type Output = RequestBodyLimitService<Ser::Protocol, T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        let limit = match self.operation_limits.get(&Op::ID) {
+            Some(limit) => *limit,
+            None => self.default_limit,
+        };
+        RequestBodyLimitService {
+            inner,
+            limit,
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl HttpMarker for RequestBodyLimitPlugin {}
+
+/// A [`Service`] that rejects requests whose body is larger than the operation allows.
+///
+/// See [`RequestBodyLimitPlugin`].
+pub struct RequestBodyLimitService<P, S> {
+    inner: S,
+    limit: Option<u64>,
+    _protocol: PhantomData<fn(P)>,
+}
+
+impl<P, S> Clone for RequestBodyLimitService<P, S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            limit: self.limit,
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<P, S> fmt::Debug for RequestBodyLimitService<P, S>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestBodyLimitService")
+            .field("inner", &self.inner)
+            .field("limit", &self.limit)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P, S, B> Service<http::Request<B>> for RequestBodyLimitService<P, S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+    B: Body + From<LimitedBodyStream> + Send + Unpin + 'static,
+    B::Error: Into<BoxError>,
+    PayloadTooLarge: IntoResponse<P>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Either<Ready<Result<Self::Response, Self::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let Some(limit) = self.limit else {
+            return Either::Right(self.inner.call(request));
+        };
+
+        let content_length = request
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+        match content_length {
+            Some(content_length) if content_length > limit => {
+                let response = PayloadTooLarge::new(limit).into_response();
+                Either::Left(ready(Ok(response)))
+            }
+            // hyper rejects bodies that don't match their `Content-Length`, so they don't need to be
+            // counted.
+            Some(_) => Either::Right(self.inner.call(request)),
+            None => {
+                let request = request.map(|body| {
+                    let stream: LimitedBodyStream = Box::new(LimitedBody::new(body, limit));
+                    B::from(stream)
+                });
+                Either::Right(self.inner.call(request))
+            }
+        }
+    }
+}
+
+/// A stream of the data in a body, which fails with [`PayloadTooLarge`] once more than `limit`
+/// bytes have been read.
+pub(crate) struct LimitedBody<B> {
+    inner: B,
+    remaining: u64,
+    limit: u64,
+}
+
+impl<B> LimitedBody<B> {
+    pub(crate) fn new(inner: B, limit: u64) -> Self {
+        Self {
+            inner,
+            remaining: limit,
+            limit,
+        }
+    }
+}
+
+impl<B> Stream for LimitedBody<B>
+where
+    B: Body + Unpin,
+    B::Error: Into<BoxError>,
+{
+    type Item = Result<Bytes, BoxError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut data = match futures_util::ready!(Pin::new(&mut self.inner).poll_data(cx)) {
+            Some(Ok(data)) => data,
+            Some(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
+            None => return Poll::Ready(None),
+        };
+        let len = data.remaining() as u64;
+        if len > self.remaining {
+            self.remaining = 0;
+            return Poll::Ready(Some(Err(Box::new(PayloadTooLarge::new(self.limit)))));
+        }
+        self.remaining -= len;
+        Poll::Ready(Some(Ok(data.copy_to_bytes(data.remaining()))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::StatusCode;
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+    use crate::extension::RuntimeErrorExtension;
+    use crate::plugin::PluginLayer;
+    use crate::protocol::rest_json_1::{rejection::RequestRejection, RestJson1};
+    use crate::protocol::rest_xml::RestXml;
+    use crate::protocol::test_helpers::get_body_as_string;
+
+    struct Service<P>(PhantomData<P>);
+
+    impl<P> crate::service::ServiceShape for Service<P> {
+        const ID: ShapeId = ShapeId::new("com.example#Service", "com.example", "Service");
+        const VERSION: Option<&'static str> = None;
+        type Protocol = P;
+        type Operations = ();
+    }
+
+    struct Limited;
+
+    impl OperationShape for Limited {
+        const ID: ShapeId = ShapeId::new("com.example#Limited", "com.example", "Limited");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    struct Unlimited;
+
+    impl OperationShape for Unlimited {
+        const ID: ShapeId = ShapeId::new("com.example#Unlimited", "com.example", "Unlimited");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    /// Applies the plugin to a service that buffers the request body the way generated servers
+    /// do, and renders its rejections with the `restJson1` protocol.
+    fn apply<Op: OperationShape>(
+        plugin: &RequestBodyLimitPlugin,
+    ) -> impl tower::Service<http::Request<hyper::Body>, Response = http::Response<BoxBody>, Error = Infallible> {
+        let inner = service_fn(|request: http::Request<hyper::Body>| async {
+            let response = match hyper::body::to_bytes(request.into_body()).await {
+                Ok(bytes) => http::Response::new(crate::body::to_boxed(bytes)),
+                Err(err) => {
+                    let rejection = RequestRejection::from(err);
+                    crate::protocol::rest_json_1::runtime_error::RuntimeError::from(rejection).into_response()
+                }
+            };
+            Ok::<_, Infallible>(response)
+        });
+        tower::Layer::layer(&PluginLayer::new::<Service<RestJson1>, Op>(plugin), inner)
+    }
+
+    fn request(body: &'static str, content_length: Option<usize>) -> http::Request<hyper::Body> {
+        let mut request = http::Request::new(hyper::Body::from(body));
+        if let Some(content_length) = content_length {
+            request.headers_mut().insert(CONTENT_LENGTH, content_length.into());
+        }
+        request
+    }
+
+    fn plugin() -> RequestBodyLimitPlugin {
+        RequestBodyLimitPlugin::new(5).without_limit(Unlimited::ID)
+    }
+
+    #[tokio::test]
+    async fn bodies_within_the_limit_are_accepted() {
+        for content_length in [Some(5), None] {
+            let response = apply::<Limited>(&plugin())
+                .oneshot(request("hello", content_length))
+                .await
+                .unwrap();
+            assert_eq!(StatusCode::OK, response.status());
+            assert_eq!("hello", get_body_as_string(response.into_body()).await);
+        }
+    }
+
+    #[tokio::test]
+    async fn bodies_over_the_limit_are_rejected() {
+        for content_length in [Some(11), None] {
+            let response = apply::<Limited>(&plugin())
+                .oneshot(request("hello world", content_length))
+                .await
+                .unwrap();
+            assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, response.status());
+            assert_eq!("PayloadTooLargeException", response.headers()["X-Amzn-Errortype"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn operations_can_be_exempted() {
+        let response = apply::<Unlimited>(&plugin())
+            .oneshot(request("hello world", None))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+    }
+
+    #[tokio::test]
+    async fn operations_can_have_their_own_limit() {
+        let plugin = plugin().with_operation_limit(Limited::ID, 20);
+        let response = apply::<Limited>(&plugin)
+            .oneshot(request("hello world", None))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+    }
+
+    #[tokio::test]
+    async fn rejections_are_rendered_by_the_protocol() {
+        let inner = service_fn(|_request: http::Request<hyper::Body>| async {
+            Ok::<_, Infallible>(http::Response::new(crate::body::to_boxed("")))
+        });
+        let service = tower::Layer::layer(&PluginLayer::new::<Service<RestXml>, Limited>(plugin()), inner);
+        let response = service.oneshot(request("hello world", Some(11))).await.unwrap();
+        assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, response.status());
+        assert_eq!("application/xml", response.headers()["content-type"]);
+        let extension = response.extensions().get::<RuntimeErrorExtension>().unwrap();
+        assert_eq!("PayloadTooLargeException", extension.to_string());
+    }
+}
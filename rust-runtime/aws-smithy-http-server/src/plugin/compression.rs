@@ -0,0 +1,561 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! [`Plugin`]s that decompress request bodies and compress response bodies.
+//!
+//! Clients compress the payloads of operations with the Smithy [`@requestCompression`] trait, and
+//! list the algorithms they used in the `Content-Encoding` header. [`RequestDecompressionPlugin`]
+//! decompresses these payloads before they are deserialized.
+//!
+//! [`ResponseCompressionPlugin`] compresses response bodies with an algorithm that the client
+//! accepts, according to the `Accept-Encoding` header of its request.
+//!
+//! Only `gzip` is supported.
+//!
+//! # Example
+//!
+//! ```
+//! # use aws_smithy_http_server::plugin::HttpPlugins;
+//! # use aws_smithy_http_server::plugin::compression::{RequestDecompressionPlugin, ResponseCompressionPlugin};
+//! let http_plugins = HttpPlugins::new()
+//!     .push(RequestDecompressionPlugin::new())
+//!     .push(ResponseCompressionPlugin::new());
+//! ```
+//!
+//! Only the last algorithm in the `Content-Encoding` header is undone, so that a small request
+//! can't expand into an arbitrarily large one by being compressed repeatedly. Decompressed request
+//! bodies are also limited to [`DEFAULT_MAX_DECOMPRESSED_SIZE`] bytes, which can be changed with
+//! [`RequestDecompressionPlugin::with_max_decompressed_size`]. Requests that exceed the limit are
+//! rejected with a `413 Payload Too Large` response once it is reached, in the same way as
+//! [`RequestBodyLimitPlugin`] rejects them.
+//!
+//! Decompressed request bodies are converted into a stream, which discards their trailers.
+//!
+//! # Ordering with [`RequestBodyLimitPlugin`]
+//!
+//! Plugins are applied in the order that they are pushed, so the first one sees the request
+//! first. When [`RequestBodyLimitPlugin`] is pushed before [`RequestDecompressionPlugin`], it
+//! limits the size of the compressed body, as it was sent. When it is pushed after, it limits the
+//! size of the decompressed body instead: the `Content-Length` header of decompressed requests is
+//! removed, so the limit is enforced while the body is read.
+//!
+//! ```
+//! # use aws_smithy_http_server::plugin::HttpPlugins;
+//! # use aws_smithy_http_server::plugin::body_limit::RequestBodyLimitPlugin;
+//! # use aws_smithy_http_server::plugin::compression::RequestDecompressionPlugin;
+//! // Accept up to 1 MiB of compressed data, which can decompress into up to 10 MiB.
+//! let http_plugins = HttpPlugins::new()
+//!     .push(RequestBodyLimitPlugin::new(1024 * 1024))
+//!     .push(RequestDecompressionPlugin::new().with_max_decompressed_size(10 * 1024 * 1024));
+//! ```
+//!
+//! [`RequestBodyLimitPlugin`]: crate::plugin::body_limit::RequestBodyLimitPlugin
+//!
+//! [`@requestCompression`]: https://smithy.io/2.0/spec/behavior-traits.html#requestcompression-trait
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use aws_smithy_compression::{body::CompressedBody, body::DecompressedBody, CompressionAlgorithm, CompressionOptions};
+use futures_util::ready;
+use http::{
+    header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, VARY},
+    HeaderMap, HeaderValue,
+};
+use http_body::Body;
+use tower::Service;
+
+use crate::{
+    body::{BodyStream, BoxBody},
+    error::BoxError,
+};
+
+use super::{body_limit::LimitedBody, HttpMarker, Plugin};
+
+/// The default limit, in bytes, of decompressed request bodies.
+pub const DEFAULT_MAX_DECOMPRESSED_SIZE: u64 = 10 * 1024 * 1024;
+
+const EVENT_STREAM_CONTENT_TYPE: &str = "application/vnd.amazon.eventstream";
+
+/// Returns the comma-separated values of all the `name` headers, in order.
+fn header_values<'a>(headers: &'a HeaderMap, name: &http::header::HeaderName) -> Vec<&'a str> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// A [`Plugin`] that decompresses request bodies that were compressed with a supported algorithm.
+///
+/// See the [module](crate::plugin::compression) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct RequestDecompressionPlugin {
+    max_decompressed_size: u64,
+}
+
+impl Default for RequestDecompressionPlugin {
+    fn default() -> Self {
+        Self {
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
+        }
+    }
+}
+
+impl RequestDecompressionPlugin {
+    /// Creates a new `RequestDecompressionPlugin`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum size, in bytes, of decompressed request bodies. Defaults to
+    /// [`DEFAULT_MAX_DECOMPRESSED_SIZE`].
+    pub fn with_max_decompressed_size(mut self, max_decompressed_size: u64) -> Self {
+        self.max_decompressed_size = max_decompressed_size;
+        self
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for RequestDecompressionPlugin {
+    type Output = RequestDecompressionService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        RequestDecompressionService {
+            inner,
+            max_decompressed_size: self.max_decompressed_size,
+        }
+    }
+}
+
+impl HttpMarker for RequestDecompressionPlugin {}
+
+/// A [`Service`] that decompresses request bodies that were compressed with a supported algorithm.
+///
+/// Only the last algorithm in the `Content-Encoding` header is undone, and it is removed from the
+/// header. Requests whose last algorithm is unsupported are left as they are.
+///
+/// See [`RequestDecompressionPlugin`].
+#[derive(Debug, Clone)]
+pub struct RequestDecompressionService<S> {
+    inner: S,
+    max_decompressed_size: u64,
+}
+
+impl<S, B> Service<http::Request<B>> for RequestDecompressionService<S>
+where
+    S: Service<http::Request<B>>,
+    B: Body + From<BodyStream> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let mut encodings = header_values(request.headers(), &CONTENT_ENCODING);
+        let Some(algorithm) = encodings
+            .last()
+            .and_then(|encoding| encoding.parse::<CompressionAlgorithm>().ok())
+        else {
+            return self.inner.call(request);
+        };
+        encodings.pop();
+
+        let remaining = encodings.join(", ");
+        let (mut parts, body) = request.into_parts();
+        parts.headers.remove(CONTENT_LENGTH);
+        parts.headers.remove(CONTENT_ENCODING);
+        if !remaining.is_empty() {
+            let remaining = HeaderValue::from_str(&remaining).expect("joined from valid header values");
+            parts.headers.insert(CONTENT_ENCODING, remaining);
+        }
+
+        let body = DecompressedBody::new(body, algorithm.decoder()).boxed_unsync();
+        let stream: BodyStream = Box::new(LimitedBody::new(body, self.max_decompressed_size));
+        self.inner.call(http::Request::from_parts(parts, B::from(stream)))
+    }
+}
+
+/// A [`Plugin`] that compresses response bodies with an algorithm that the client accepts.
+///
+/// See the [module](crate::plugin::compression) documentation for more information.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseCompressionPlugin {
+    options: CompressionOptions,
+}
+
+impl ResponseCompressionPlugin {
+    /// Creates a new `ResponseCompressionPlugin` with the default [`CompressionOptions`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the [`CompressionOptions`] that responses are compressed with.
+    ///
+    /// Responses whose size is known to be smaller than
+    /// [`CompressionOptions::min_compression_size_bytes`] are sent uncompressed.
+    pub fn with_options(mut self, options: CompressionOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for ResponseCompressionPlugin {
+    type Output = ResponseCompressionService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        ResponseCompressionService {
+            inner,
+            options: self.options,
+        }
+    }
+}
+
+impl HttpMarker for ResponseCompressionPlugin {}
+
+/// A [`Service`] that compresses response bodies with an algorithm that the client accepts.
+///
+/// Responses that already have a `Content-Encoding`, event streams, and responses whose size is
+/// known to be smaller than the minimum compression size are sent uncompressed.
+///
+/// See [`ResponseCompressionPlugin`].
+#[derive(Debug, Clone)]
+pub struct ResponseCompressionService<S> {
+    inner: S,
+    options: CompressionOptions,
+}
+
+impl<S, B> Service<http::Request<B>> for ResponseCompressionService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseCompressionFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let algorithm = negotiate(request.headers());
+        ResponseCompressionFuture {
+            inner: self.inner.call(request),
+            algorithm,
+            options: self.options,
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The future returned by [`ResponseCompressionService`].
+    #[derive(Debug)]
+    pub struct ResponseCompressionFuture<F> {
+        #[pin]
+        inner: F,
+        algorithm: Option<CompressionAlgorithm>,
+        options: CompressionOptions,
+    }
+}
+
+impl<F, E> std::future::Future for ResponseCompressionFuture<F>
+where
+    F: std::future::Future<Output = Result<http::Response<BoxBody>, E>>,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let response = ready!(this.inner.poll(cx))?;
+        Poll::Ready(Ok(compress(response, *this.algorithm, *this.options)))
+    }
+}
+
+/// Returns the supported algorithm that the `Accept-Encoding` header allows, if there is one.
+fn negotiate(headers: &HeaderMap) -> Option<CompressionAlgorithm> {
+    let mut gzip = None;
+    let mut wildcard = None;
+    for value in header_values(headers, &ACCEPT_ENCODING) {
+        let mut params = value.split(';').map(str::trim);
+        let coding = params.next().unwrap_or_default();
+        let quality = params
+            .find_map(|param| param.strip_prefix("q=").or_else(|| param.strip_prefix("Q=")))
+            .map_or(Some(1.0), |quality| quality.parse::<f32>().ok());
+        let Some(quality) = quality else {
+            continue;
+        };
+        if coding.eq_ignore_ascii_case(CompressionAlgorithm::Gzip.as_str()) {
+            gzip = Some(quality);
+        } else if coding == "*" {
+            wildcard = Some(quality);
+        }
+    }
+    // An explicit entry takes precedence over the wildcard.
+    match gzip.or(wildcard) {
+        Some(quality) if quality > 0.0 => Some(CompressionAlgorithm::Gzip),
+        _ => None,
+    }
+}
+
+fn compress(
+    response: http::Response<BoxBody>,
+    algorithm: Option<CompressionAlgorithm>,
+    options: CompressionOptions,
+) -> http::Response<BoxBody> {
+    let is_event_stream = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with(EVENT_STREAM_CONTENT_TYPE));
+    if response.headers().contains_key(CONTENT_ENCODING) || is_event_stream {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let varies = header_values(&parts.headers, &VARY)
+        .iter()
+        .any(|value| *value == "*" || value.eq_ignore_ascii_case(ACCEPT_ENCODING.as_str()));
+    if !varies {
+        parts.headers.append(VARY, HeaderValue::from_static("accept-encoding"));
+    }
+
+    let size = body.size_hint().exact().or_else(|| {
+        parts
+            .headers
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+    });
+    let too_small = size.is_some_and(|size| size < u64::from(options.min_compression_size_bytes()));
+    let Some(algorithm) = algorithm.filter(|_| !too_small && !body.is_end_stream()) else {
+        return http::Response::from_parts(parts, body);
+    };
+
+    parts.headers.remove(CONTENT_LENGTH);
+    parts
+        .headers
+        .insert(CONTENT_ENCODING, HeaderValue::from_static(algorithm.as_str()));
+    let body = crate::body::boxed(CompressedBody::new(body, algorithm.encoder(&options)));
+    http::Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use bytes::Bytes;
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+    use crate::plugin::PluginLayer;
+    use crate::protocol::test_helpers::get_body_as_string;
+    use crate::rejection::PayloadTooLarge;
+    use crate::shape_id::ShapeId;
+
+    struct Service;
+
+    impl crate::service::ServiceShape for Service {
+        const ID: ShapeId = ShapeId::new("com.example#Service", "com.example", "Service");
+        const VERSION: Option<&'static str> = None;
+        type Protocol = ();
+        type Operations = ();
+    }
+
+    struct Operation;
+
+    impl crate::operation::OperationShape for Operation {
+        const ID: ShapeId = ShapeId::new("com.example#Operation", "com.example", "Operation");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    const TEXT: &str = "Hello, world! Hello, world! Hello, world! Hello, world!";
+
+    fn gzip(data: &[u8]) -> Bytes {
+        CompressionAlgorithm::Gzip
+            .compress_bytes(data, &CompressionOptions::default())
+            .unwrap()
+    }
+
+    /// Applies the decompression plugin to a service that echoes the request body, with the
+    /// request's `Content-Encoding` in the response.
+    async fn decompress(content_encoding: &str, body: Bytes) -> http::Response<BoxBody> {
+        decompress_with(RequestDecompressionPlugin::new(), content_encoding, body).await
+    }
+
+    async fn decompress_with(
+        plugin: RequestDecompressionPlugin,
+        content_encoding: &str,
+        body: Bytes,
+    ) -> http::Response<BoxBody> {
+        let inner = service_fn(|request: http::Request<hyper::Body>| async move {
+            let content_encoding = request.headers().get(CONTENT_ENCODING).cloned();
+            let mut response = match hyper::body::to_bytes(request.into_body()).await {
+                Ok(bytes) => http::Response::new(crate::body::to_boxed(bytes)),
+                Err(err) => {
+                    let status = if PayloadTooLarge::find(&err).is_some() {
+                        http::StatusCode::PAYLOAD_TOO_LARGE
+                    } else {
+                        http::StatusCode::BAD_REQUEST
+                    };
+                    http::Response::builder()
+                        .status(status)
+                        .body(crate::body::empty())
+                        .unwrap()
+                }
+            };
+            if let Some(content_encoding) = content_encoding {
+                response.headers_mut().insert(CONTENT_ENCODING, content_encoding);
+            }
+            Ok::<_, Infallible>(response)
+        });
+        let service = tower::Layer::layer(&PluginLayer::new::<Service, Operation>(&plugin), inner);
+        let request = http::Request::builder()
+            .header(CONTENT_ENCODING, content_encoding)
+            .header(CONTENT_LENGTH, body.len())
+            .body(hyper::Body::from(body))
+            .unwrap();
+        service.oneshot(request).await.unwrap()
+    }
+
+    /// Applies the compression plugin to a service that responds with `response`.
+    async fn respond(
+        plugin: &ResponseCompressionPlugin,
+        accept_encoding: Option<&str>,
+        response: http::Response<BoxBody>,
+    ) -> http::Response<BoxBody> {
+        let mut response = Some(response);
+        let inner = service_fn(move |_request: http::Request<hyper::Body>| {
+            let response = response.take().unwrap();
+            async move { Ok::<_, Infallible>(response) }
+        });
+        let service = tower::Layer::layer(&PluginLayer::new::<Service, Operation>(plugin), inner);
+        let mut request = http::Request::new(hyper::Body::empty());
+        if let Some(accept_encoding) = accept_encoding {
+            request
+                .headers_mut()
+                .insert(ACCEPT_ENCODING, HeaderValue::from_str(accept_encoding).unwrap());
+        }
+        service.oneshot(request).await.unwrap()
+    }
+
+    fn plugin_without_min_size() -> ResponseCompressionPlugin {
+        ResponseCompressionPlugin::new().with_options(
+            CompressionOptions::default()
+                .with_min_compression_size_bytes(0)
+                .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn compressed_requests_are_decompressed() {
+        let response = decompress("gzip", gzip(TEXT.as_bytes())).await;
+        assert_eq!(None, response.headers().get(CONTENT_ENCODING));
+        assert_eq!(TEXT, get_body_as_string(response.into_body()).await);
+    }
+
+    #[tokio::test]
+    async fn only_supported_encodings_are_removed() {
+        let response = decompress("custom, GZIP", gzip(TEXT.as_bytes())).await;
+        assert_eq!("custom", response.headers()[CONTENT_ENCODING]);
+        assert_eq!(TEXT, get_body_as_string(response.into_body()).await);
+
+        let response = decompress("gzip, custom", Bytes::from(TEXT)).await;
+        assert_eq!("gzip, custom", response.headers()[CONTENT_ENCODING]);
+        assert_eq!(TEXT, get_body_as_string(response.into_body()).await);
+    }
+
+    #[tokio::test]
+    async fn only_the_last_encoding_is_decompressed() {
+        let compressed = gzip(TEXT.as_bytes());
+        let response = decompress("gzip, gzip", gzip(&compressed)).await;
+        assert_eq!("gzip", response.headers()[CONTENT_ENCODING]);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(compressed, body);
+    }
+
+    #[tokio::test]
+    async fn decompressed_bodies_are_limited() {
+        let plugin = RequestDecompressionPlugin::new().with_max_decompressed_size(TEXT.len() as u64);
+        let response = decompress_with(plugin.clone(), "gzip", gzip(TEXT.as_bytes())).await;
+        assert_eq!(TEXT, get_body_as_string(response.into_body()).await);
+
+        let too_large = TEXT.repeat(2);
+        let response = decompress_with(plugin, "gzip", gzip(too_large.as_bytes())).await;
+        assert_eq!(http::StatusCode::PAYLOAD_TOO_LARGE, response.status());
+    }
+
+    #[tokio::test]
+    async fn invalid_compressed_requests_fail() {
+        let response = decompress("gzip", Bytes::from(TEXT)).await;
+        assert_eq!(http::StatusCode::BAD_REQUEST, response.status());
+    }
+
+    #[tokio::test]
+    async fn responses_are_compressed_when_accepted() {
+        for accept_encoding in ["gzip", "deflate, gzip;q=0.5", "*", "br;q=1.0, *;q=0.1"] {
+            let response = http::Response::new(crate::body::to_boxed(TEXT));
+            let response = respond(&plugin_without_min_size(), Some(accept_encoding), response).await;
+            assert_eq!("gzip", response.headers()[CONTENT_ENCODING], "{accept_encoding}");
+            assert_eq!("accept-encoding", response.headers()[VARY]);
+            let compressed = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            let decompressed = CompressionAlgorithm::Gzip.decompress_bytes(&compressed).unwrap();
+            assert_eq!(TEXT.as_bytes(), &decompressed[..]);
+        }
+    }
+
+    #[tokio::test]
+    async fn responses_are_not_compressed_when_not_accepted() {
+        for accept_encoding in [
+            None,
+            Some("br"),
+            Some("gzip;q=0"),
+            Some("*, gzip;q=0"),
+            Some("identity"),
+        ] {
+            let response = http::Response::new(crate::body::to_boxed(TEXT));
+            let response = respond(&plugin_without_min_size(), accept_encoding, response).await;
+            assert_eq!(None, response.headers().get(CONTENT_ENCODING), "{accept_encoding:?}");
+            assert_eq!("accept-encoding", response.headers()[VARY]);
+            assert_eq!(TEXT, get_body_as_string(response.into_body()).await);
+        }
+    }
+
+    #[tokio::test]
+    async fn small_responses_are_not_compressed() {
+        let response = http::Response::new(crate::body::to_boxed(TEXT));
+        let response = respond(&ResponseCompressionPlugin::new(), Some("gzip"), response).await;
+        assert_eq!(None, response.headers().get(CONTENT_ENCODING));
+        assert_eq!(TEXT, get_body_as_string(response.into_body()).await);
+    }
+
+    #[tokio::test]
+    async fn encoded_responses_and_event_streams_are_not_compressed() {
+        let encoded = http::Response::builder()
+            .header(CONTENT_ENCODING, "custom")
+            .body(crate::body::to_boxed(TEXT))
+            .unwrap();
+        let event_stream = http::Response::builder()
+            .header(CONTENT_TYPE, EVENT_STREAM_CONTENT_TYPE)
+            .body(crate::body::to_boxed(TEXT))
+            .unwrap();
+        for response in [encoded, event_stream] {
+            let expected = response.headers().get(CONTENT_ENCODING).cloned();
+            let response = respond(&plugin_without_min_size(), Some("gzip"), response).await;
+            assert_eq!(expected.as_ref(), response.headers().get(CONTENT_ENCODING));
+            assert_eq!(TEXT, get_body_as_string(response.into_body()).await);
+        }
+    }
+}
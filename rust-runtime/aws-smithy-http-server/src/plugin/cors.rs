@@ -0,0 +1,545 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] that handles [Cross-Origin Resource Sharing
+//! (CORS)](https://developer.mozilla.org/en-US/docs/Web/HTTP/CORS) requests from browser clients.
+//!
+//! Before a browser sends a cross-origin request, it sends a preflight `OPTIONS` request to the
+//! same URI, asking whether the request is allowed. Generated services don't have an operation
+//! for these requests, so the `restJson1` and `restXml` routers route a preflight request to the
+//! operation of the method that it asks for, along with the methods of all the operations that
+//! its URI routes to. [`CorsPlugin`] answers it with a preflight response that allows those
+//! methods, and adds the CORS headers to the responses of the requests that follow.
+//!
+//! Operations that [`CorsPlugin`] isn't applied to reject preflight requests with
+//! `405 Method Not Allowed`, as do all operations of services of other protocols, which only get
+//! the CORS headers added to their responses. Preflight requests for URIs that don't route to
+//! any operation are rejected with `404 Not Found`.
+//!
+//! [`CorsPlugin`] should be the first HTTP plugin, so that preflight requests, which don't carry
+//! credentials, are answered before other plugins, such as authentication plugins, see them.
+//! Responses to requests that fail to be routed don't get the CORS headers.
+//!
+//! # Example
+//!
+//! ```
+//! use aws_smithy_http_server::plugin::{cors::CorsPlugin, HttpPlugins};
+//! use http::{header::CONTENT_TYPE, HeaderValue};
+//! use std::time::Duration;
+//!
+//! // Allow browser clients served from `https://example.com` to call the service.
+//! let cors = CorsPlugin::new()
+//!     .with_allowed_origins([HeaderValue::from_static("https://example.com")])
+//!     .with_allowed_headers([CONTENT_TYPE])
+//!     .with_max_age(Duration::from_secs(3600));
+//! let http_plugins = HttpPlugins::new().push(cors);
+//! ```
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_util::{
+    future::{ready, Either, Ready},
+    ready,
+};
+use http::{
+    header::{
+        ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+        ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE,
+        ACCESS_CONTROL_REQUEST_HEADERS, ORIGIN, VARY,
+    },
+    HeaderMap, HeaderName, HeaderValue, StatusCode,
+};
+use tower::Service;
+
+use crate::{
+    body::BoxBody,
+    protocol::rest::router::{method_not_allowed, AllowedMethods, Preflight},
+};
+
+use super::{HttpMarker, Plugin};
+
+#[derive(Debug, Clone)]
+enum AllowOrigin {
+    Any,
+    List(Vec<HeaderValue>),
+}
+
+#[derive(Debug, Clone)]
+enum AllowHeaders {
+    Any,
+    List(Vec<HeaderName>),
+}
+
+#[derive(Debug, Clone)]
+struct CorsConfig {
+    allow_origin: AllowOrigin,
+    allow_headers: AllowHeaders,
+    expose_headers: Vec<HeaderName>,
+    allow_credentials: bool,
+    max_age: Option<Duration>,
+}
+
+impl CorsConfig {
+    /// Returns the value of the `Access-Control-Allow-Origin` header for requests from `origin`,
+    /// or `None` if `origin` isn't allowed.
+    fn allow_origin(&self, origin: &HeaderValue) -> Option<HeaderValue> {
+        match &self.allow_origin {
+            // Browsers reject the `*` wildcard for requests with credentials.
+            AllowOrigin::Any if self.allow_credentials => Some(origin.clone()),
+            AllowOrigin::Any => Some(HeaderValue::from_static("*")),
+            AllowOrigin::List(origins) => origins.contains(origin).then(|| origin.clone()),
+        }
+    }
+
+    fn insert_common_headers(&self, headers: &mut HeaderMap, allow_origin: HeaderValue) {
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+        if self.allow_credentials {
+            headers.insert(ACCESS_CONTROL_ALLOW_CREDENTIALS, HeaderValue::from_static("true"));
+        }
+        if !matches!(self.allow_origin, AllowOrigin::Any) || self.allow_credentials {
+            headers.append(VARY, HeaderValue::from_static("origin"));
+        }
+    }
+
+    fn preflight_response(
+        &self,
+        allow_origin: HeaderValue,
+        allowed_methods: &AllowedMethods,
+        request_headers: Option<HeaderValue>,
+    ) -> http::Response<BoxBody> {
+        let mut response = http::Response::new(crate::body::empty());
+        *response.status_mut() = StatusCode::NO_CONTENT;
+        let headers = response.headers_mut();
+        self.insert_common_headers(headers, allow_origin);
+        headers.append(VARY, HeaderValue::from_static("access-control-request-method"));
+        headers.append(VARY, HeaderValue::from_static("access-control-request-headers"));
+        if let Ok(methods) = HeaderValue::from_str(&allowed_methods.to_string()) {
+            headers.insert(ACCESS_CONTROL_ALLOW_METHODS, methods);
+        }
+        let allow_headers = match &self.allow_headers {
+            AllowHeaders::Any => request_headers,
+            AllowHeaders::List(names) => join(names.iter().map(HeaderName::as_str)),
+        };
+        if let Some(allow_headers) = allow_headers {
+            headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, allow_headers);
+        }
+        if let Some(max_age) = self.max_age {
+            headers.insert(ACCESS_CONTROL_MAX_AGE, max_age.as_secs().into());
+        }
+        response
+    }
+
+    fn actual_response(
+        &self,
+        mut response: http::Response<BoxBody>,
+        allow_origin: HeaderValue,
+    ) -> http::Response<BoxBody> {
+        let headers = response.headers_mut();
+        self.insert_common_headers(headers, allow_origin);
+        if let Some(expose_headers) = join(self.expose_headers.iter().map(HeaderName::as_str)) {
+            headers.insert(ACCESS_CONTROL_EXPOSE_HEADERS, expose_headers);
+        }
+        response
+    }
+}
+
+/// Joins `values` into a comma-separated header value, or returns `None` if there are none.
+fn join<'a>(values: impl Iterator<Item = &'a str>) -> Option<HeaderValue> {
+    let joined = values.collect::<Vec<_>>().join(", ");
+    if joined.is_empty() {
+        None
+    } else {
+        HeaderValue::from_str(&joined).ok()
+    }
+}
+
+/// A [`Plugin`] that answers CORS preflight requests, and adds CORS headers to the responses of
+/// requests from allowed origins.
+///
+/// By default, no origins are allowed. See the [module](crate::plugin::cors) documentation for
+/// more information.
+#[derive(Debug, Clone)]
+pub struct CorsPlugin {
+    config: Arc<CorsConfig>,
+}
+
+impl Default for CorsPlugin {
+    fn default() -> Self {
+        Self {
+            config: Arc::new(CorsConfig {
+                allow_origin: AllowOrigin::List(Vec::new()),
+                allow_headers: AllowHeaders::List(Vec::new()),
+                expose_headers: Vec::new(),
+                allow_credentials: false,
+                max_age: None,
+            }),
+        }
+    }
+}
+
+impl CorsPlugin {
+    /// Creates a new `CorsPlugin` that doesn't allow any origins.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows requests from `origins`, in addition to the origins that are already allowed.
+    pub fn with_allowed_origins(mut self, origins: impl IntoIterator<Item = HeaderValue>) -> Self {
+        match &mut Arc::make_mut(&mut self.config).allow_origin {
+            AllowOrigin::Any => {}
+            AllowOrigin::List(list) => list.extend(origins),
+        }
+        self
+    }
+
+    /// Allows requests from any origin.
+    pub fn with_any_origin(mut self) -> Self {
+        Arc::make_mut(&mut self.config).allow_origin = AllowOrigin::Any;
+        self
+    }
+
+    /// Allows requests to send `headers`, in addition to the headers that are already allowed.
+    ///
+    /// [CORS-safelisted request headers] are always allowed.
+    ///
+    /// [CORS-safelisted request headers]: https://developer.mozilla.org/en-US/docs/Glossary/CORS-safelisted_request_header
+    pub fn with_allowed_headers(mut self, headers: impl IntoIterator<Item = HeaderName>) -> Self {
+        match &mut Arc::make_mut(&mut self.config).allow_headers {
+            AllowHeaders::Any => {}
+            AllowHeaders::List(list) => list.extend(headers),
+        }
+        self
+    }
+
+    /// Allows requests to send any header.
+    pub fn with_any_header(mut self) -> Self {
+        Arc::make_mut(&mut self.config).allow_headers = AllowHeaders::Any;
+        self
+    }
+
+    /// Allows browser clients to read `headers` from responses, in addition to the
+    /// [CORS-safelisted response headers].
+    ///
+    /// [CORS-safelisted response headers]: https://developer.mozilla.org/en-US/docs/Glossary/CORS-safelisted_response_header
+    pub fn with_exposed_headers(mut self, headers: impl IntoIterator<Item = HeaderName>) -> Self {
+        Arc::make_mut(&mut self.config).expose_headers.extend(headers);
+        self
+    }
+
+    /// Sets whether requests may include credentials, such as cookies.
+    pub fn with_credentials(mut self, allow_credentials: bool) -> Self {
+        Arc::make_mut(&mut self.config).allow_credentials = allow_credentials;
+        self
+    }
+
+    /// Sets how long browsers may cache preflight responses.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        Arc::make_mut(&mut self.config).max_age = Some(max_age);
+        self
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for CorsPlugin {
+    type Output = CorsService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        CorsService {
+            inner,
+            config: self.config.clone(),
+        }
+    }
+}
+
+impl HttpMarker for CorsPlugin {}
+
+/// A [`Service`] that answers CORS preflight requests, and adds CORS headers to the responses of
+/// requests from allowed origins.
+///
+/// See [`CorsPlugin`].
+#[derive(Debug, Clone)]
+pub struct CorsService<S> {
+    inner: S,
+    config: Arc<CorsConfig>,
+}
+
+impl<S, B> Service<http::Request<B>> for CorsService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Either<Ready<Result<Self::Response, Self::Error>>, CorsFuture<S::Future>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<B>) -> Self::Future {
+        let allow_origin = request
+            .headers()
+            .get(ORIGIN)
+            .and_then(|origin| self.config.allow_origin(origin));
+        if let Some(Preflight(allowed_methods)) = request.extensions_mut().remove::<Preflight>() {
+            let response = match allow_origin {
+                Some(allow_origin) => self.config.preflight_response(
+                    allow_origin,
+                    &allowed_methods,
+                    request.headers().get(ACCESS_CONTROL_REQUEST_HEADERS).cloned(),
+                ),
+                None => method_not_allowed(allowed_methods),
+            };
+            return Either::Left(ready(Ok(response)));
+        }
+
+        Either::Right(CorsFuture {
+            inner: self.inner.call(request),
+            allow_origin,
+            config: self.config.clone(),
+        })
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The future returned by [`CorsService`] for requests that aren't preflight requests.
+    pub struct CorsFuture<F> {
+        #[pin]
+        inner: F,
+        allow_origin: Option<HeaderValue>,
+        config: Arc<CorsConfig>,
+    }
+}
+
+impl<F, E> Future for CorsFuture<F>
+where
+    F: Future<Output = Result<http::Response<BoxBody>, E>>,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let response = ready!(this.inner.poll(cx))?;
+        let response = match this.allow_origin.take() {
+            Some(allow_origin) => this.config.actual_response(response, allow_origin),
+            None => response,
+        };
+        Poll::Ready(Ok(response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{
+        header::{ACCESS_CONTROL_REQUEST_METHOD, ALLOW, CONTENT_TYPE},
+        Method,
+    };
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{
+        operation::{OperationShape, Upgrade, UpgradePlugin},
+        plugin::IdentityPlugin,
+        protocol::{rest::router::RestRouter, rest_json_1::RestJson1},
+        request::FromRequest,
+        response::IntoResponse,
+        routing::{
+            request_spec::{PathAndQuerySpec, PathSegment, PathSpec, QuerySpec, RequestSpec, UriSpec},
+            Route, RoutingService,
+        },
+        service::ServiceShape,
+        shape_id::ShapeId,
+    };
+
+    struct TestService;
+
+    impl ServiceShape for TestService {
+        const ID: ShapeId = ShapeId::new("com.example#Service", "com.example", "Service");
+        const VERSION: Option<&'static str> = None;
+        type Protocol = RestJson1;
+        type Operations = ();
+    }
+
+    struct TestOperation;
+
+    impl OperationShape for TestOperation {
+        const ID: ShapeId = ShapeId::new("com.example#Operation", "com.example", "Operation");
+        type Input = Thing;
+        type Output = Thing;
+        type Error = Infallible;
+    }
+
+    struct Thing;
+
+    impl<B> FromRequest<RestJson1, B> for Thing {
+        type Rejection = Infallible;
+        type Future = Ready<Result<Self, Infallible>>;
+
+        fn from_request(_request: http::Request<B>) -> Self::Future {
+            ready(Ok(Thing))
+        }
+    }
+
+    impl IntoResponse<RestJson1> for Thing {
+        fn into_response(self) -> http::Response<BoxBody> {
+            http::Response::new(crate::body::to_boxed("thing"))
+        }
+    }
+
+    fn handle(_input: (Thing, ())) -> Ready<Result<Thing, Infallible>> {
+        ready(Ok(Thing))
+    }
+
+    type Handler = tower::util::ServiceFn<fn((Thing, ())) -> Ready<Result<Thing, Infallible>>>;
+
+    /// A `restJson1` service with `GET /things/{id}` and `DELETE /things/{id}` operations, which
+    /// `plugin` is applied to.
+    fn service<Pl>(
+        plugin: Pl,
+    ) -> impl Service<http::Request<hyper::Body>, Response = http::Response<BoxBody>, Error = Infallible>
+    where
+        Pl: Plugin<TestService, TestOperation, Upgrade<RestJson1, (Thing, ()), Handler>>,
+        Pl::Output: Service<http::Request<hyper::Body>, Response = http::Response<BoxBody>, Error = Infallible>
+            + Clone
+            + Send
+            + 'static,
+        <Pl::Output as Service<http::Request<hyper::Body>>>::Future: Send + 'static,
+    {
+        let spec = |method| {
+            let path =
+                PathSpec::from_vector_unchecked(vec![PathSegment::Literal(String::from("things")), PathSegment::Label]);
+            RequestSpec::new(
+                method,
+                UriSpec::new(PathAndQuerySpec::new(
+                    path,
+                    QuerySpec::from_vector_unchecked(Vec::new()),
+                )),
+            )
+        };
+        let route = || {
+            let handler: Handler = tower::service_fn(handle as fn(_) -> _);
+            let upgrade = Plugin::<TestService, TestOperation, _>::apply(&UpgradePlugin::<()>::new(), handler);
+            Route::new(plugin.apply(upgrade))
+        };
+        let router: RestRouter<_> = [(spec(Method::GET), route()), (spec(Method::DELETE), route())]
+            .into_iter()
+            .collect();
+        RoutingService::<_, RestJson1>::new(router)
+    }
+
+    fn plugin() -> CorsPlugin {
+        CorsPlugin::new()
+            .with_allowed_origins([HeaderValue::from_static("https://example.com")])
+            .with_allowed_headers([CONTENT_TYPE])
+            .with_exposed_headers([HeaderName::from_static("x-request-id")])
+            .with_max_age(Duration::from_secs(60))
+    }
+
+    fn preflight(uri: &str, origin: &str, method: &str) -> http::Request<hyper::Body> {
+        http::Request::builder()
+            .method(Method::OPTIONS)
+            .uri(uri)
+            .header(ORIGIN, origin)
+            .header(ACCESS_CONTROL_REQUEST_METHOD, method)
+            .header(ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+            .body(hyper::Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn preflights_are_answered_with_the_allowed_methods() {
+        let response = service(plugin())
+            .oneshot(preflight("/things/1", "https://example.com", "DELETE"))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        let headers = response.headers();
+        assert_eq!("https://example.com", headers[ACCESS_CONTROL_ALLOW_ORIGIN]);
+        assert_eq!("GET, DELETE", headers[ACCESS_CONTROL_ALLOW_METHODS]);
+        assert_eq!("content-type", headers[ACCESS_CONTROL_ALLOW_HEADERS]);
+        assert_eq!("60", headers[ACCESS_CONTROL_MAX_AGE]);
+        assert_eq!(3, headers.get_all(VARY).iter().count());
+    }
+
+    #[tokio::test]
+    async fn preflights_for_unknown_uris_or_methods_are_rejected() {
+        let response = service(plugin())
+            .oneshot(preflight("/unknown", "https://example.com", "DELETE"))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+        assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        let response = service(plugin())
+            .oneshot(preflight("/things/1", "https://example.com", "PUT"))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::METHOD_NOT_ALLOWED, response.status());
+        assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[tokio::test]
+    async fn disallowed_origins_are_rejected() {
+        let response = service(plugin())
+            .oneshot(preflight("/things/1", "https://attacker.com", "DELETE"))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::METHOD_NOT_ALLOWED, response.status());
+        assert_eq!("GET, DELETE", response.headers()[ALLOW]);
+        assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[tokio::test]
+    async fn preflights_are_rejected_by_operations_without_the_plugin() {
+        let response = service(IdentityPlugin)
+            .oneshot(preflight("/things/1", "https://example.com", "DELETE"))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::METHOD_NOT_ALLOWED, response.status());
+        assert_eq!("GET, DELETE", response.headers()[ALLOW]);
+    }
+
+    #[tokio::test]
+    async fn actual_requests_get_cors_headers() {
+        let request = http::Request::builder()
+            .method(Method::DELETE)
+            .uri("/things/1")
+            .header(ORIGIN, "https://example.com")
+            .body(hyper::Body::empty())
+            .unwrap();
+        let response = service(plugin()).oneshot(request).await.unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+        let headers = response.headers();
+        assert_eq!("https://example.com", headers[ACCESS_CONTROL_ALLOW_ORIGIN]);
+        assert_eq!("x-request-id", headers[ACCESS_CONTROL_EXPOSE_HEADERS]);
+        assert_eq!("origin", headers[VARY]);
+    }
+
+    #[tokio::test]
+    async fn any_origin_and_header_are_allowed() {
+        let plugin = CorsPlugin::new().with_any_origin().with_any_header();
+        let response = service(plugin)
+            .oneshot(preflight("/things/1", "https://anywhere.com", "GET"))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        assert_eq!("*", response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN]);
+        assert_eq!("content-type", response.headers()[ACCESS_CONTROL_ALLOW_HEADERS]);
+
+        let plugin = CorsPlugin::new().with_any_origin().with_credentials(true);
+        let response = service(plugin)
+            .oneshot(preflight("/things/1", "https://anywhere.com", "GET"))
+            .await
+            .unwrap();
+        assert_eq!("https://anywhere.com", response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN]);
+        assert_eq!("true", response.headers()[ACCESS_CONTROL_ALLOW_CREDENTIALS]);
+    }
+}
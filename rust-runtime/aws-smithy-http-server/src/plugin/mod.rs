@@ -194,24 +194,32 @@
 //! impl ModelMarker for PrintPlugin { }
 //! ```
 
+pub mod body_limit;
 mod closure;
+#[cfg(feature = "compression")]
+pub mod compression;
+pub mod cors;
 pub(crate) mod either;
 mod filter;
 mod http_plugins;
 mod identity;
 mod layer;
 mod model_plugins;
+pub mod rate_limit;
 #[doc(hidden)]
 pub mod scoped;
 mod stack;
 
+pub use body_limit::RequestBodyLimitPlugin;
 pub use closure::{plugin_from_operation_fn, OperationFn};
+pub use cors::CorsPlugin;
 pub use either::Either;
 pub use filter::{filter_by_operation, FilterByOperation};
 pub use http_plugins::HttpPlugins;
 pub use identity::IdentityPlugin;
 pub use layer::{LayerPlugin, PluginLayer};
 pub use model_plugins::ModelPlugins;
+pub use rate_limit::RateLimitPlugin;
 pub use scoped::Scoped;
 pub use stack::PluginStack;
 
@@ -0,0 +1,768 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] that limits the rate at which clients can call operations.
+//!
+//! [`RateLimitPlugin`] keeps a [token bucket] per operation and per client. Clients are told apart
+//! by a [`KeyExtractor`], such as [`RemoteAddr`] or [`Header`], and each operation's bucket is
+//! configured by a [`RateLimit`]. Requests that exceed the limit are rejected before they are
+//! deserialized, with a `429 Too Many Requests` response carrying a `Retry-After` header.
+//!
+//! By default, rejected requests are answered with a [`ThrottlingException`], which is rendered
+//! by the service's protocol like other runtime errors. A service that models its own throttling
+//! error can use [`RateLimitPlugin::with_rejection`] to respond with that instead.
+//!
+//! # Example
+//!
+//! ```
+//! # use std::time::Duration;
+//! # use aws_smithy_http_server::plugin::HttpPlugins;
+//! # use aws_smithy_http_server::plugin::rate_limit::*;
+//! # use aws_smithy_http_server::shape_id::ShapeId;
+//! # const GET_POKEMON_SPECIES: ShapeId = ShapeId::new("com.aws.example#GetPokemonSpecies", "com.aws.example", "GetPokemonSpecies");
+//! # const CHECK_HEALTH: ShapeId = ShapeId::new("com.aws.example#CheckHealth", "com.aws.example", "CheckHealth");
+//! // Allow each client 100 requests per second, with bursts of up to 100 requests, to all
+//! // operations except `GetPokemonSpecies`, which is more expensive, and `CheckHealth`, which
+//! // isn't limited at all.
+//! let rate_limit = RateLimitPlugin::new(RemoteAddr)
+//!     .with_default_limit(RateLimit::per_second(100))
+//!     .with_operation_limit(GET_POKEMON_SPECIES, RateLimit::new(10, Duration::from_secs(1)))
+//!     .without_limit(CHECK_HEALTH);
+//! let http_plugins = HttpPlugins::new().push(rate_limit);
+//! ```
+//!
+//! [`RemoteAddr`] requires the service to be run with
+//! [`into_make_service_with_connect_info`](crate::routing::IntoMakeServiceWithConnectInfo).
+//!
+//! [token bucket]: https://en.wikipedia.org/wiki/Token_bucket
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    hash::Hash,
+    marker::PhantomData,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, SystemTime},
+};
+
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use futures_util::future::{ready, Either, Ready};
+use http::{header::RETRY_AFTER, request::Parts, HeaderName, HeaderValue};
+use tower::Service;
+
+use crate::{
+    body::BoxBody, operation::OperationShape, request::connect_info::ConnectInfo, response::IntoResponse,
+    runtime_error::ThrottlingException, shape_id::ShapeId,
+};
+
+use super::{HttpMarker, Plugin};
+
+/// The number of clients per operation above which idle clients are forgotten.
+const DEFAULT_MAX_KEYS: usize = 10_000;
+
+/// The number of clients that are checked for idleness when a new client needs room.
+const EVICTION_BATCH_SIZE: usize = 8;
+
+/// A token bucket rate limit.
+///
+/// A client can make up to `capacity` requests in a burst. After that, it regains the ability to
+/// make one request every `refill_interval`, up to `capacity` requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    capacity: u32,
+    refill_interval: Duration,
+}
+
+impl RateLimit {
+    /// Creates a new `RateLimit` that allows bursts of `capacity` requests, and one more request
+    /// every `refill_interval`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: u32, refill_interval: Duration) -> Self {
+        assert!(capacity > 0, "a rate limit must allow at least one request");
+        Self {
+            capacity,
+            refill_interval,
+        }
+    }
+
+    /// Creates a new `RateLimit` that allows `requests` requests per second, in bursts of up to
+    /// `requests` requests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests` is zero.
+    pub fn per_second(requests: u32) -> Self {
+        Self::per(requests, Duration::from_secs(1))
+    }
+
+    /// Creates a new `RateLimit` that allows `requests` requests per minute, in bursts of up to
+    /// `requests` requests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests` is zero.
+    pub fn per_minute(requests: u32) -> Self {
+        Self::per(requests, Duration::from_secs(60))
+    }
+
+    fn per(requests: u32, period: Duration) -> Self {
+        assert!(requests > 0, "a rate limit must allow at least one request");
+        Self::new(requests, period / requests)
+    }
+
+    /// Returns the maximum number of requests in a burst.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Returns the time it takes to regain the ability to make one request.
+    pub fn refill_interval(&self) -> Duration {
+        self.refill_interval
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: u32,
+    last_refill: SystemTime,
+}
+
+impl TokenBucket {
+    fn new(limit: &RateLimit, now: SystemTime) -> Self {
+        Self {
+            tokens: limit.capacity,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, limit: &RateLimit, now: SystemTime) {
+        if self.tokens == limit.capacity {
+            self.last_refill = now;
+            return;
+        }
+        // The clock may go backwards, in which case no time has passed
+        let elapsed = now.duration_since(self.last_refill).unwrap_or_default();
+        let refilled = match limit.refill_interval.as_nanos() {
+            0 => u128::from(limit.capacity),
+            interval => elapsed.as_nanos() / interval,
+        };
+        if refilled == 0 {
+            return;
+        }
+        let missing = limit.capacity - self.tokens;
+        if refilled >= u128::from(missing) {
+            self.tokens = limit.capacity;
+            self.last_refill = now;
+        } else {
+            // `refilled < missing`, so this fits in a `u32`
+            let refilled = refilled as u32;
+            self.tokens += refilled;
+            self.last_refill += limit.refill_interval * refilled;
+        }
+    }
+
+    /// Takes a token, or returns how long it will be until one is available.
+    fn try_acquire(&mut self, limit: &RateLimit, now: SystemTime) -> Result<(), Duration> {
+        self.refill(limit, now);
+        if self.tokens > 0 {
+            self.tokens -= 1;
+            Ok(())
+        } else {
+            let next_refill = self.last_refill + limit.refill_interval;
+            Err(next_refill.duration_since(now).unwrap_or_default())
+        }
+    }
+
+    fn is_full(&mut self, limit: &RateLimit, now: SystemTime) -> bool {
+        self.refill(limit, now);
+        self.tokens == limit.capacity
+    }
+}
+
+/// The token buckets of the clients of a single operation.
+#[derive(Debug)]
+struct Limiter<K> {
+    limit: RateLimit,
+    max_keys: usize,
+    buckets: Mutex<Buckets<K>>,
+}
+
+#[derive(Debug)]
+struct Buckets<K> {
+    by_key: HashMap<K, TokenBucket>,
+    /// The keys of `by_key`, from the one that was checked for idleness longest ago.
+    queue: VecDeque<K>,
+}
+
+impl<K> Default for Buckets<K> {
+    fn default() -> Self {
+        Self {
+            by_key: HashMap::new(),
+            queue: VecDeque::new(),
+        }
+    }
+}
+
+impl<K> Limiter<K>
+where
+    K: Hash + Eq + Clone,
+{
+    fn new(limit: RateLimit, max_keys: usize) -> Self {
+        Self {
+            limit,
+            max_keys,
+            buckets: Mutex::new(Buckets::default()),
+        }
+    }
+
+    fn try_acquire(&self, key: K, now: SystemTime) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let Buckets { by_key, queue } = &mut *buckets;
+        if let Some(bucket) = by_key.get_mut(&key) {
+            return bucket.try_acquire(&self.limit, now);
+        }
+
+        if by_key.len() >= self.max_keys {
+            // Clients whose buckets are full would start over with a full bucket anyway. Only a
+            // few clients are checked, so that the work done while holding the lock is bounded.
+            for _ in 0..EVICTION_BATCH_SIZE.min(queue.len()) {
+                let oldest = queue.pop_front().expect("checked the length");
+                let bucket = by_key.get_mut(&oldest).expect("queued keys have a bucket");
+                if bucket.is_full(&self.limit, now) {
+                    by_key.remove(&oldest);
+                } else {
+                    queue.push_back(oldest);
+                }
+            }
+            if by_key.len() >= self.max_keys {
+                // There's no room for a new client until one of the others has been idle
+                return Err(self.limit.refill_interval);
+            }
+        }
+
+        let mut bucket = TokenBucket::new(&self.limit, now);
+        let result = bucket.try_acquire(&self.limit, now);
+        by_key.insert(key.clone(), bucket);
+        queue.push_back(key);
+        result
+    }
+}
+
+/// Extracts the key that identifies the client of a request.
+///
+/// Requests that have the same key share a rate limit. Requests for which no key can be extracted
+/// aren't limited.
+///
+/// This is implemented for closures that take the request's [`Parts`] and return an `Option` of a
+/// key.
+pub trait KeyExtractor: Send + Sync + 'static {
+    /// The type of key.
+    type Key: Hash + Eq + Clone + Send + 'static;
+
+    /// Extracts the key from a request.
+    fn extract(&self, parts: &Parts) -> Option<Self::Key>;
+}
+
+impl<F, K> KeyExtractor for F
+where
+    F: Fn(&Parts) -> Option<K> + Send + Sync + 'static,
+    K: Hash + Eq + Clone + Send + 'static,
+{
+    type Key = K;
+
+    fn extract(&self, parts: &Parts) -> Option<K> {
+        self(parts)
+    }
+}
+
+/// A [`KeyExtractor`] that applies a single rate limit to all clients together.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Global;
+
+impl KeyExtractor for Global {
+    type Key = ();
+
+    fn extract(&self, _parts: &Parts) -> Option<()> {
+        Some(())
+    }
+}
+
+/// A [`KeyExtractor`] that identifies clients by the IP address they're connecting from.
+///
+/// This requires [`ConnectInfo<SocketAddr>`] to be present in the request extensions. Requests
+/// without it aren't limited.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RemoteAddr;
+
+impl KeyExtractor for RemoteAddr {
+    type Key = IpAddr;
+
+    fn extract(&self, parts: &Parts) -> Option<IpAddr> {
+        parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+    }
+}
+
+/// A [`KeyExtractor`] that identifies clients by the value of a header, such as an API key.
+///
+/// Requests without the header aren't limited.
+#[derive(Debug, Clone)]
+pub struct Header(HeaderName);
+
+impl Header {
+    /// Creates a new `Header` that identifies clients by the value of the header `name`.
+    pub fn new(name: HeaderName) -> Self {
+        Self(name)
+    }
+}
+
+impl KeyExtractor for Header {
+    type Key = HeaderValue;
+
+    fn extract(&self, parts: &Parts) -> Option<HeaderValue> {
+        parts.headers.get(&self.0).cloned()
+    }
+}
+
+/// A [`Plugin`] that limits the rate at which clients can call operations.
+///
+/// See the [module](crate::plugin::rate_limit) documentation for more information.
+pub struct RateLimitPlugin<X, R = ThrottlingException> {
+    extractor: Arc<X>,
+    default_limit: Option<RateLimit>,
+    operation_limits: HashMap<ShapeId, Option<RateLimit>>,
+    max_keys: usize,
+    time_source: SharedTimeSource,
+    rejection: R,
+}
+
+impl<X> RateLimitPlugin<X> {
+    /// Creates a new `RateLimitPlugin` that tells clients apart with `extractor`.
+    ///
+    /// No operation is limited until limits are configured with
+    /// [`RateLimitPlugin::with_default_limit`] or [`RateLimitPlugin::with_operation_limit`].
+    pub fn new(extractor: X) -> Self {
+        Self {
+            extractor: Arc::new(extractor),
+            default_limit: None,
+            operation_limits: HashMap::new(),
+            max_keys: DEFAULT_MAX_KEYS,
+            time_source: SharedTimeSource::default(),
+            rejection: ThrottlingException,
+        }
+    }
+}
+
+impl<X, R> RateLimitPlugin<X, R> {
+    /// Sets the limit for operations that don't have their own.
+    pub fn with_default_limit(mut self, limit: RateLimit) -> Self {
+        self.default_limit = Some(limit);
+        self
+    }
+
+    /// Sets the limit for the operation with the given [`ShapeId`].
+    pub fn with_operation_limit(mut self, operation: ShapeId, limit: RateLimit) -> Self {
+        self.operation_limits.insert(operation, Some(limit));
+        self
+    }
+
+    /// Exempts the operation with the given [`ShapeId`] from the default limit.
+    pub fn without_limit(mut self, operation: ShapeId) -> Self {
+        self.operation_limits.insert(operation, None);
+        self
+    }
+
+    /// Sets the maximum number of clients per operation whose state is kept. Defaults to 10,000.
+    ///
+    /// Once this many clients are tracked, the state of idle clients is discarded to make room
+    /// for new ones. Clients are idle once they've regained their full capacity, so discarding
+    /// their state doesn't loosen their limits. Requests from new clients are rejected while
+    /// there's no room for them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_keys` is zero, since no client could ever be served.
+    pub fn with_max_keys(mut self, max_keys: usize) -> Self {
+        assert!(max_keys > 0, "a rate limit must keep the state of at least one client");
+        self.max_keys = max_keys;
+        self
+    }
+
+    /// Sets the time source that token buckets are refilled with. Defaults to the system clock.
+    pub fn with_time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.time_source = SharedTimeSource::new(time_source);
+        self
+    }
+
+    /// Sets the response that rejected requests receive.
+    ///
+    /// `rejection` is cloned for every rejected request and converted into a response with
+    /// [`IntoResponse`] for the service's protocol. A `Retry-After` header is added to the
+    /// response.
+    pub fn with_rejection<NewR>(self, rejection: NewR) -> RateLimitPlugin<X, NewR> {
+        RateLimitPlugin {
+            extractor: self.extractor,
+            default_limit: self.default_limit,
+            operation_limits: self.operation_limits,
+            max_keys: self.max_keys,
+            time_source: self.time_source,
+            rejection,
+        }
+    }
+}
+
+impl<X, R> fmt::Debug for RateLimitPlugin<X, R>
+where
+    X: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimitPlugin")
+            .field("extractor", &self.extractor)
+            .field("default_limit", &self.default_limit)
+            .field("operation_limits", &self.operation_limits)
+            .field("max_keys", &self.max_keys)
+            .field("time_source", &self.time_source)
+            .field("rejection", &self.rejection)
+            .finish()
+    }
+}
+
+impl<Ser, Op, T, X, R> Plugin<Ser, Op, T> for RateLimitPlugin<X, R>
+where
+    Ser: crate::service::ServiceShape,
+    Op: OperationShape,
+    X: KeyExtractor,
+    R: Clone,
+{
+    type Output = RateLimitService<Ser::Protocol, T, X, R>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        let limit = match self.operation_limits.get(&Op::ID) {
+            Some(limit) => *limit,
+            None => self.default_limit,
+        };
+        RateLimitService {
+            inner,
+            extractor: self.extractor.clone(),
+            limiter: limit.map(|limit| Arc::new(Limiter::new(limit, self.max_keys))),
+            time_source: self.time_source.clone(),
+            rejection: self.rejection.clone(),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<X, R> HttpMarker for RateLimitPlugin<X, R> {}
+
+/// A [`Service`] that rejects requests that exceed an operation's rate limit.
+///
+/// See [`RateLimitPlugin`].
+pub struct RateLimitService<P, S, X: KeyExtractor, R> {
+    inner: S,
+    extractor: Arc<X>,
+    limiter: Option<Arc<Limiter<X::Key>>>,
+    time_source: SharedTimeSource,
+    rejection: R,
+    _protocol: PhantomData<fn(P)>,
+}
+
+impl<P, S, X, R> Clone for RateLimitService<P, S, X, R>
+where
+    S: Clone,
+    X: KeyExtractor,
+    R: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            extractor: self.extractor.clone(),
+            limiter: self.limiter.clone(),
+            time_source: self.time_source.clone(),
+            rejection: self.rejection.clone(),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<P, S, X, R> fmt::Debug for RateLimitService<P, S, X, R>
+where
+    S: fmt::Debug,
+    X: KeyExtractor,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimitService")
+            .field("inner", &self.inner)
+            .field("limit", &self.limiter.as_ref().map(|limiter| limiter.limit))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P, S, X, R, B> Service<http::Request<B>> for RateLimitService<P, S, X, R>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+    X: KeyExtractor,
+    R: Clone + IntoResponse<P>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Either<Ready<Result<Self::Response, Self::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let Some(limiter) = &self.limiter else {
+            return Either::Right(self.inner.call(request));
+        };
+        let (parts, body) = request.into_parts();
+        let key = self.extractor.extract(&parts);
+        if let Some(key) = key {
+            if let Err(retry_after) = limiter.try_acquire(key, self.time_source.now()) {
+                tracing::debug!(?retry_after, "rejecting request that exceeds the rate limit");
+                let mut response = self.rejection.clone().into_response();
+                // `Retry-After` is in whole seconds, so round up
+                let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+                response
+                    .headers_mut()
+                    .insert(RETRY_AFTER, HeaderValue::from(seconds.max(1)));
+                return Either::Left(ready(Ok(response)));
+            }
+        }
+        Either::Right(self.inner.call(http::Request::from_parts(parts, body)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::StatusCode;
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+    use crate::plugin::PluginLayer;
+    use crate::protocol::rest_json_1::RestJson1;
+    use crate::protocol::rest_xml::RestXml;
+    use crate::protocol::test_helpers::get_body_as_string;
+
+    struct Service<P>(PhantomData<P>);
+
+    impl<P> crate::service::ServiceShape for Service<P> {
+        const ID: ShapeId = ShapeId::new("com.example#Service", "com.example", "Service");
+        const VERSION: Option<&'static str> = None;
+        type Protocol = P;
+        type Operations = ();
+    }
+
+    struct Limited;
+
+    impl OperationShape for Limited {
+        const ID: ShapeId = ShapeId::new("com.example#Limited", "com.example", "Limited");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    struct Unlimited;
+
+    impl OperationShape for Unlimited {
+        const ID: ShapeId = ShapeId::new("com.example#Unlimited", "com.example", "Unlimited");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    fn apply<P, Op, R>(
+        plugin: &RateLimitPlugin<Header, R>,
+    ) -> impl tower::Service<http::Request<()>, Response = http::Response<BoxBody>, Error = Infallible> + Clone
+    where
+        P: 'static,
+        Op: OperationShape,
+        R: Clone + IntoResponse<P>,
+    {
+        let inner = service_fn(|_request: http::Request<()>| async {
+            Ok::<_, Infallible>(http::Response::new(crate::body::to_boxed("")))
+        });
+        tower::Layer::layer(&PluginLayer::new::<Service<P>, Op>(plugin), inner)
+    }
+
+    fn request(api_key: Option<&'static str>) -> http::Request<()> {
+        let mut request = http::Request::new(());
+        if let Some(api_key) = api_key {
+            request
+                .headers_mut()
+                .insert("x-api-key", HeaderValue::from_static(api_key));
+        }
+        request
+    }
+
+    fn plugin() -> RateLimitPlugin<Header> {
+        RateLimitPlugin::new(Header::new(HeaderName::from_static("x-api-key")))
+            .with_default_limit(RateLimit::new(2, Duration::from_secs(3600)))
+            .without_limit(Unlimited::ID)
+    }
+
+    #[test]
+    fn token_bucket_refills_over_time() {
+        let limit = RateLimit::per_second(2);
+        assert_eq!(Duration::from_millis(500), limit.refill_interval());
+        let start = SystemTime::UNIX_EPOCH;
+        let mut bucket = TokenBucket::new(&limit, start);
+
+        assert_eq!(Ok(()), bucket.try_acquire(&limit, start));
+        assert_eq!(Ok(()), bucket.try_acquire(&limit, start));
+        assert_eq!(
+            Err(Duration::from_millis(400)),
+            bucket.try_acquire(&limit, start + Duration::from_millis(100))
+        );
+
+        // One token is regained after 500ms, and the partial interval carries over
+        let later = start + Duration::from_millis(700);
+        assert_eq!(Ok(()), bucket.try_acquire(&limit, later));
+        assert_eq!(Err(Duration::from_millis(300)), bucket.try_acquire(&limit, later));
+
+        // Tokens don't accumulate beyond the capacity
+        let much_later = start + Duration::from_secs(60);
+        assert!(bucket.is_full(&limit, much_later));
+        assert_eq!(Ok(()), bucket.try_acquire(&limit, much_later));
+        assert_eq!(Ok(()), bucket.try_acquire(&limit, much_later));
+        assert!(bucket.try_acquire(&limit, much_later).is_err());
+    }
+
+    #[test]
+    fn idle_clients_are_forgotten() {
+        let limiter = Limiter::new(RateLimit::new(1, Duration::from_secs(1)), 2);
+        let start = SystemTime::UNIX_EPOCH;
+        assert!(limiter.try_acquire("a", start).is_ok());
+        assert!(limiter.try_acquire("b", start + Duration::from_millis(500)).is_ok());
+        // `a` has regained its token, so its bucket is discarded to make room for `c`
+        assert!(limiter.try_acquire("c", start + Duration::from_millis(1200)).is_ok());
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(2, buckets.by_key.len());
+        assert!(buckets.by_key.contains_key("b") && buckets.by_key.contains_key("c"));
+        assert_eq!(vec!["b", "c"], Vec::from(buckets.queue.clone()));
+    }
+
+    #[test]
+    fn new_clients_are_rejected_when_there_is_no_room() {
+        let limiter = Limiter::new(RateLimit::new(1, Duration::from_secs(1)), 2);
+        let start = SystemTime::UNIX_EPOCH;
+        assert!(limiter.try_acquire("a", start).is_ok());
+        assert!(limiter.try_acquire("b", start).is_ok());
+        assert_eq!(Err(Duration::from_secs(1)), limiter.try_acquire("c", start));
+        // Known clients are still served
+        assert!(limiter.try_acquire("a", start + Duration::from_secs(1)).is_ok());
+        assert_eq!(2, limiter.buckets.lock().unwrap().by_key.len());
+    }
+
+    #[test]
+    #[should_panic(expected = "a rate limit must keep the state of at least one client")]
+    fn zero_max_keys_is_rejected() {
+        let _ = plugin().with_max_keys(0);
+    }
+
+    #[derive(Debug, Clone)]
+    struct ManualTimeSource(Arc<Mutex<SystemTime>>);
+
+    impl TimeSource for ManualTimeSource {
+        fn now(&self) -> SystemTime {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn buckets_are_refilled_by_the_time_source() {
+        let time = ManualTimeSource(Arc::new(Mutex::new(SystemTime::UNIX_EPOCH)));
+        let plugin = plugin().with_time_source(time.clone());
+        let svc = apply::<RestJson1, Limited, _>(&plugin);
+
+        for _ in 0..2 {
+            let response = svc.clone().oneshot(request(Some("alice"))).await.unwrap();
+            assert_eq!(StatusCode::OK, response.status());
+        }
+        let response = svc.clone().oneshot(request(Some("alice"))).await.unwrap();
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, response.status());
+
+        *time.0.lock().unwrap() += Duration::from_secs(3600);
+        let response = svc.clone().oneshot(request(Some("alice"))).await.unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+    }
+
+    #[tokio::test]
+    async fn requests_over_the_limit_are_throttled() {
+        let plugin = plugin();
+        let svc = apply::<RestJson1, Limited, _>(&plugin);
+
+        for _ in 0..2 {
+            let response = svc.clone().oneshot(request(Some("alice"))).await.unwrap();
+            assert_eq!(StatusCode::OK, response.status());
+        }
+        let response = svc.clone().oneshot(request(Some("alice"))).await.unwrap();
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, response.status());
+        assert_eq!("ThrottlingException", response.headers()["X-Amzn-Errortype"]);
+        assert_eq!("3600", response.headers()[RETRY_AFTER]);
+        assert_eq!("{}", get_body_as_string(response.into_body()).await);
+
+        // Other clients have their own limit, and requests without a key aren't limited
+        let response = svc.clone().oneshot(request(Some("bob"))).await.unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+        for _ in 0..3 {
+            let response = svc.clone().oneshot(request(None)).await.unwrap();
+            assert_eq!(StatusCode::OK, response.status());
+        }
+    }
+
+    #[tokio::test]
+    async fn operations_have_separate_limits() {
+        let plugin = plugin().with_operation_limit(Limited::ID, RateLimit::new(1, Duration::from_secs(3600)));
+        let limited = apply::<RestXml, Limited, _>(&plugin);
+        let unlimited = apply::<RestXml, Unlimited, _>(&plugin);
+
+        let response = limited.clone().oneshot(request(Some("alice"))).await.unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+        let response = limited.clone().oneshot(request(Some("alice"))).await.unwrap();
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, response.status());
+        assert_eq!("application/xml", response.headers()["Content-Type"]);
+
+        for _ in 0..3 {
+            let response = unlimited.clone().oneshot(request(Some("alice"))).await.unwrap();
+            assert_eq!(StatusCode::OK, response.status());
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_rejections_are_rendered() {
+        #[derive(Clone)]
+        struct SlowDown;
+
+        impl IntoResponse<RestJson1> for SlowDown {
+            fn into_response(self) -> http::Response<BoxBody> {
+                http::Response::builder()
+                    .status(StatusCode::SERVICE_UNAVAILABLE)
+                    .body(crate::body::to_boxed("slow down"))
+                    .unwrap()
+            }
+        }
+
+        let plugin = plugin().with_rejection(SlowDown);
+        let svc = apply::<RestJson1, Limited, _>(&plugin);
+        for _ in 0..2 {
+            svc.clone().oneshot(request(Some("alice"))).await.unwrap();
+        }
+        let response = svc.clone().oneshot(request(Some("alice"))).await.unwrap();
+        assert_eq!(StatusCode::SERVICE_UNAVAILABLE, response.status());
+        assert!(response.headers().contains_key(RETRY_AFTER));
+        assert_eq!("slow down", get_body_as_string(response.into_body()).await);
+    }
+}
@@ -19,6 +19,8 @@ pub enum ResponseRejection {
 pub enum RequestRejection {
     #[error("error converting non-streaming body to bytes: {0}")]
     BufferHttpBodyBytes(crate::Error),
+    #[error("{0}")]
+    PayloadTooLarge(crate::rejection::PayloadTooLarge),
     #[error("request contains invalid value for `Accept` header")]
     NotAcceptable,
     #[error("expected `Content-Type` header not found: {0}")]
@@ -39,5 +41,5 @@ impl From<std::convert::Infallible> for RequestRejection {
     }
 }
 
-convert_to_request_rejection!(hyper::Error, BufferHttpBodyBytes);
-convert_to_request_rejection!(Box<dyn std::error::Error + Send + Sync + 'static>, BufferHttpBodyBytes);
+convert_body_error_to_request_rejection!(hyper::Error);
+convert_body_error_to_request_rejection!(Box<dyn std::error::Error + Send + Sync + 'static>);
@@ -4,8 +4,11 @@
  */
 
 use crate::protocol::aws_json_11::AwsJson1_1;
+use crate::rejection::PayloadTooLarge;
 use crate::response::IntoResponse;
-use crate::runtime_error::{InternalFailureException, INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE};
+use crate::runtime_error::{
+    InternalFailureException, ThrottlingException, INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE,
+};
 use crate::{extension::RuntimeErrorExtension, protocol::aws_json_10::AwsJson1_0};
 use http::StatusCode;
 
@@ -18,6 +21,8 @@ pub enum RuntimeError {
     NotAcceptable,
     UnsupportedMediaType,
     Validation(String),
+    Throttling,
+    PayloadTooLarge,
 }
 
 impl RuntimeError {
@@ -28,6 +33,8 @@ impl RuntimeError {
             Self::NotAcceptable => "NotAcceptableException",
             Self::UnsupportedMediaType => "UnsupportedMediaTypeException",
             Self::Validation(_) => "ValidationException",
+            Self::Throttling => "ThrottlingException",
+            Self::PayloadTooLarge => "PayloadTooLargeException",
         }
     }
 
@@ -38,6 +45,8 @@ impl RuntimeError {
             Self::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
             Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
             Self::Validation(_) => StatusCode::BAD_REQUEST,
+            Self::Throttling => StatusCode::TOO_MANY_REQUESTS,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
         }
     }
 }
@@ -48,12 +57,36 @@ impl IntoResponse<AwsJson1_0> for InternalFailureException {
     }
 }
 
+impl IntoResponse<AwsJson1_0> for ThrottlingException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<AwsJson1_0>::into_response(RuntimeError::Throttling)
+    }
+}
+
+impl IntoResponse<AwsJson1_0> for PayloadTooLarge {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<AwsJson1_0>::into_response(RuntimeError::PayloadTooLarge)
+    }
+}
+
 impl IntoResponse<AwsJson1_1> for InternalFailureException {
     fn into_response(self) -> http::Response<crate::body::BoxBody> {
         IntoResponse::<AwsJson1_1>::into_response(RuntimeError::InternalFailure(crate::Error::new(String::new())))
     }
 }
 
+impl IntoResponse<AwsJson1_1> for ThrottlingException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<AwsJson1_1>::into_response(RuntimeError::Throttling)
+    }
+}
+
+impl IntoResponse<AwsJson1_1> for PayloadTooLarge {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<AwsJson1_1>::into_response(RuntimeError::PayloadTooLarge)
+    }
+}
+
 impl IntoResponse<AwsJson1_0> for RuntimeError {
     fn into_response(self) -> http::Response<crate::body::BoxBody> {
         let res = http::Response::builder()
@@ -99,6 +132,7 @@ impl From<RequestRejection> for RuntimeError {
     fn from(err: RequestRejection) -> Self {
         match err {
             RequestRejection::ConstraintViolation(reason) => Self::Validation(reason),
+            RequestRejection::PayloadTooLarge(_) => Self::PayloadTooLarge,
             _ => Self::Serialization(crate::Error::new(err)),
         }
     }
@@ -0,0 +1,9 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+pub mod router;
+
+/// [AWS Query Protocol](https://smithy.io/2.0/aws/protocols/aws-query-protocol.html).
+pub struct AwsQuery;
@@ -0,0 +1,17 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::body::BoxBody;
+use crate::response::IntoResponse;
+
+use super::AwsQuery;
+
+pub use crate::protocol::query::router::*;
+
+impl IntoResponse<AwsQuery> for Error {
+    fn into_response(self) -> http::Response<BoxBody> {
+        self.into_query_response()
+    }
+}
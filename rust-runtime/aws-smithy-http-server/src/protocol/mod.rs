@@ -6,9 +6,17 @@
 pub mod aws_json;
 pub mod aws_json_10;
 pub mod aws_json_11;
+#[cfg(feature = "query-protocols")]
+pub mod aws_query;
+#[cfg(feature = "query-protocols")]
+pub mod ec2_query;
+#[cfg(feature = "query-protocols")]
+pub mod query;
 pub mod rest;
 pub mod rest_json_1;
 pub mod rest_xml;
+#[cfg(feature = "rpc-v2-cbor")]
+pub mod rpc_v2_cbor;
 
 use crate::rejection::MissingContentTypeReason;
 use aws_smithy_runtime_api::http::Headers as SmithyHeaders;
@@ -0,0 +1,11 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Routing, rejections and runtime errors shared by the [`AwsQuery`](crate::protocol::aws_query::AwsQuery)
+//! and [`Ec2Query`](crate::protocol::ec2_query::Ec2Query) protocols.
+
+pub mod rejection;
+pub mod router;
+pub mod runtime_error;
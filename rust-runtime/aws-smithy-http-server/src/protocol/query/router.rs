@@ -0,0 +1,417 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::convert::Infallible;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::task::{Context, Poll};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use http_body::Body as HttpBody;
+use tower::Layer;
+use tower::Service;
+use tower::ServiceExt;
+
+use crate::body::{boxed, empty, BoxBody};
+use crate::error::BoxError;
+use crate::extension::RuntimeErrorExtension;
+use crate::rejection::PayloadTooLarge;
+use crate::routing::tiny_map::TinyMap;
+use crate::routing::Route;
+use crate::routing::Router;
+use crate::routing::{method_disallowed, UNKNOWN_OPERATION_EXCEPTION};
+
+use thiserror::Error;
+
+/// An AWS Query or EC2 Query routing error.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Relative URI was not "/".
+    #[error("relative URI is not \"/\"")]
+    NotRootUrl,
+    /// Method was not `POST`.
+    #[error("method not POST")]
+    MethodNotAllowed,
+    /// Unable to buffer the request body.
+    #[error("failed to read the request body: {0}")]
+    InvalidBody(crate::Error),
+    /// The request body is larger than [`QueryRouter::with_max_body_size`].
+    #[error(transparent)]
+    PayloadTooLarge(PayloadTooLarge),
+    /// Unable to decode the request body as a form-urlencoded query.
+    #[error("failed to decode the request body: {0}")]
+    InvalidQuery(aws_smithy_query::deserialize::DeserializeError),
+    /// Missing the `Action` parameter.
+    #[error("missing the \"Action\" parameter")]
+    MissingAction,
+    /// Operation not found.
+    #[error("operation not found")]
+    NotFound,
+}
+
+impl Error {
+    /// Builds the routing error response shared by the AWS Query and EC2 Query protocols.
+    pub(crate) fn into_query_response(self) -> http::Response<BoxBody> {
+        match self {
+            Error::MethodNotAllowed => method_disallowed(),
+            Error::InvalidBody(_) => http::Response::builder()
+                .status(http::StatusCode::BAD_REQUEST)
+                .body(empty())
+                .expect("invalid HTTP response for query routing error; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues"),
+            Error::PayloadTooLarge(_) => http::Response::builder()
+                .status(http::StatusCode::PAYLOAD_TOO_LARGE)
+                .header(http::header::CONTENT_TYPE, "text/xml")
+                .extension(RuntimeErrorExtension::new("PayloadTooLargeException".to_string()))
+                .body(empty())
+                .expect("invalid HTTP response for query routing error; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues"),
+            _ => http::Response::builder()
+                .status(http::StatusCode::NOT_FOUND)
+                .header(http::header::CONTENT_TYPE, "text/xml")
+                .extension(RuntimeErrorExtension::new(
+                    UNKNOWN_OPERATION_EXCEPTION.to_string(),
+                ))
+                .body(empty())
+                .expect("invalid HTTP response for query routing error; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues"),
+        }
+    }
+}
+
+// This constant determines when the `TinyMap` implementation switches from being a `Vec` to a
+// `HashMap`. This is chosen to be 15 as a result of the discussion around
+// https://github.com/smithy-lang/smithy-rs/pull/1429#issuecomment-1147516546
+const ROUTE_CUTOFF: usize = 15;
+
+type SharedRoutes<S> = Arc<TinyMap<String, Mutex<S>, ROUTE_CUTOFF>>;
+
+/// The default for [`QueryRouter::with_max_body_size`].
+pub const DEFAULT_MAX_BODY_SIZE: u64 = 10 * 1024 * 1024;
+
+/// A [`Router`] supporting the [AWS Query] and [EC2 Query] protocols.
+///
+/// These protocols name the invoked operation in the `Action` parameter of the form-urlencoded
+/// request body, rather than in the URI or in a header. Requests are therefore matched in two
+/// steps: [`Router::match_route`] only checks that the request is a `POST` to `/`, and returns a
+/// [`QueryDispatch`] service which buffers the body, reads the `Action` parameter, and calls the
+/// route registered for it.
+///
+/// Since the body is buffered before the operation is known, and therefore before any plugin is
+/// applied, its size is limited to [`DEFAULT_MAX_BODY_SIZE`] bytes, which can be changed with
+/// [`QueryRouter::with_max_body_size`]. Larger requests are rejected with a
+/// `413 Payload Too Large` response.
+///
+/// [AWS Query]: https://smithy.io/2.0/aws/protocols/aws-query-protocol.html
+/// [EC2 Query]: https://smithy.io/2.0/aws/protocols/aws-ec2-query-protocol.html
+#[derive(Debug, Clone)]
+pub struct QueryRouter<S> {
+    routes: TinyMap<String, S, ROUTE_CUTOFF>,
+    // Routes shared with every `QueryDispatch`, built on the first match. Routes are usually not
+    // `Sync`, hence the `Mutex`, which is only held while a matched route is cloned.
+    shared: OnceLock<SharedRoutes<S>>,
+    max_body_size: u64,
+}
+
+impl<S> QueryRouter<S> {
+    /// Sets the maximum number of bytes of a request body, which defaults to
+    /// [`DEFAULT_MAX_BODY_SIZE`].
+    pub fn with_max_body_size(mut self, max_body_size: u64) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Applies a [`Layer`] uniformly to all routes.
+    pub fn layer<L>(self, layer: L) -> QueryRouter<L::Service>
+    where
+        L: Layer<S>,
+    {
+        QueryRouter {
+            routes: self
+                .routes
+                .into_iter()
+                .map(|(key, route)| (key, layer.layer(route)))
+                .collect(),
+            shared: OnceLock::new(),
+            max_body_size: self.max_body_size,
+        }
+    }
+
+    /// Applies type erasure to the inner route using [`Route::new`].
+    pub fn boxed<B>(self) -> QueryRouter<Route<B>>
+    where
+        S: Service<http::Request<B>, Response = http::Response<BoxBody>, Error = Infallible>,
+        S: Send + Clone + 'static,
+        S::Future: Send + 'static,
+    {
+        QueryRouter {
+            routes: self.routes.into_iter().map(|(key, s)| (key, Route::new(s))).collect(),
+            shared: OnceLock::new(),
+            max_body_size: self.max_body_size,
+        }
+    }
+}
+
+impl<B, S> Router<B> for QueryRouter<S>
+where
+    S: Clone,
+{
+    type Service = QueryDispatch<S>;
+    type Error = Error;
+
+    fn match_route(&self, request: &http::Request<B>) -> Result<Self::Service, Self::Error> {
+        // The URI must be root,
+        if request.uri() != "/" {
+            return Err(Error::NotRootUrl);
+        }
+
+        // Only `Method::POST` is allowed.
+        if request.method() != http::Method::POST {
+            return Err(Error::MethodNotAllowed);
+        }
+
+        let routes = self.shared.get_or_init(|| {
+            Arc::new(
+                self.routes
+                    .iter()
+                    .map(|(key, route)| (key.clone(), Mutex::new(route.clone())))
+                    .collect(),
+            )
+        });
+        Ok(QueryDispatch {
+            routes: routes.clone(),
+            max_body_size: self.max_body_size,
+        })
+    }
+}
+
+impl<S> FromIterator<(String, S)> for QueryRouter<S> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = (String, S)>>(iter: T) -> Self {
+        Self {
+            routes: iter.into_iter().collect(),
+            shared: OnceLock::new(),
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+}
+
+/// A [`Service`] calling the route of a [`QueryRouter`] named by the `Action` parameter of the
+/// request body.
+pub struct QueryDispatch<S> {
+    routes: SharedRoutes<S>,
+    max_body_size: u64,
+}
+
+impl<S> Clone for QueryDispatch<S> {
+    fn clone(&self) -> Self {
+        Self {
+            routes: self.routes.clone(),
+            max_body_size: self.max_body_size,
+        }
+    }
+}
+
+impl<S> fmt::Debug for QueryDispatch<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryDispatch")
+            .field("max_body_size", &self.max_body_size)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Buffers `body`, failing with [`Error::PayloadTooLarge`] as soon as it is known to exceed
+/// `max_body_size` bytes.
+async fn read_body<B>(body: B, headers: &http::HeaderMap, max_body_size: u64) -> Result<Bytes, Error>
+where
+    B: HttpBody,
+    B::Error: Into<BoxError>,
+{
+    let too_large = || Error::PayloadTooLarge(PayloadTooLarge::new(max_body_size));
+    let content_length = headers
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if matches!(content_length, Some(content_length) if content_length > max_body_size) {
+        return Err(too_large());
+    }
+
+    let mut body = std::pin::pin!(body);
+    let mut buffer = BytesMut::new();
+    while let Some(data) = body.data().await {
+        let data = data.map_err(|err| Error::InvalidBody(crate::Error::new(err)))?;
+        if (buffer.len() + data.remaining()) as u64 > max_body_size {
+            return Err(too_large());
+        }
+        buffer.put(data);
+    }
+    Ok(buffer.freeze())
+}
+
+impl<S> QueryDispatch<S>
+where
+    S: Clone,
+{
+    fn route(&self, body: &[u8]) -> Result<S, Error> {
+        let action = aws_smithy_query::deserialize::action(body)
+            .map_err(Error::InvalidQuery)?
+            .ok_or(Error::MissingAction)?;
+        let route = self.routes.get(action.as_str()).ok_or(Error::NotFound)?;
+        let route = route.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        Ok(route)
+    }
+}
+
+impl<B, S, RespB> Service<http::Request<B>> for QueryDispatch<S>
+where
+    B: HttpBody + From<Bytes> + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+    S: Service<http::Request<B>, Response = http::Response<RespB>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    RespB: HttpBody<Data = Bytes> + Send + 'static,
+    RespB::Error: Into<BoxError>,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let bytes = match read_body(body, &parts.headers, this.max_body_size).await {
+                Ok(bytes) => bytes,
+                Err(error) => {
+                    tracing::debug!(%error, "failed to read the request body");
+                    return Ok(error.into_query_response());
+                }
+            };
+            match this.route(&bytes) {
+                Ok(route) => {
+                    let request = http::Request::from_parts(parts, B::from(bytes));
+                    route.oneshot(request).await.map(|response| response.map(boxed))
+                }
+                Err(error) => {
+                    tracing::debug!(%error, "failed to route");
+                    Ok(error.into_query_response())
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{protocol::test_helpers::req, routing::Router};
+
+    use http::Method;
+    use pretty_assertions::assert_eq;
+
+    fn request(body: &'static str) -> http::Request<hyper::Body> {
+        http::Request::builder()
+            .method(Method::POST)
+            .uri("/")
+            .body(hyper::Body::from(body))
+            .unwrap()
+    }
+
+    fn router() -> QueryRouter<Route<hyper::Body>> {
+        ["GetWidget", "PutWidget"]
+            .into_iter()
+            .map(|action| {
+                let route = Route::new(tower::service_fn(
+                    move |request: http::Request<hyper::Body>| async move {
+                        let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+                        let body = format!("{action}: {}", std::str::from_utf8(&body).unwrap());
+                        Ok::<_, Infallible>(http::Response::new(crate::body::to_boxed(body)))
+                    },
+                ));
+                (action.to_string(), route)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn simple_routing() {
+        let router: QueryRouter<_> = [("GetWidget".to_string(), ())].into_iter().collect();
+
+        // Valid request, should match.
+        router.match_route(&req(&Method::POST, "/", None)).unwrap();
+
+        // Wrong HTTP method, should return `MethodNotAllowed`.
+        let res = router.match_route(&req(&Method::GET, "/", None));
+        assert_eq!(res.unwrap_err().to_string(), Error::MethodNotAllowed.to_string());
+
+        // Wrong URI, should return `NotRootUrl`.
+        let res = router.match_route(&req(&Method::POST, "/something", None));
+        assert_eq!(res.unwrap_err().to_string(), Error::NotRootUrl.to_string());
+    }
+
+    #[tokio::test]
+    async fn dispatches_on_action() {
+        let router = router();
+
+        let dispatch = router.match_route(&request("")).unwrap();
+        let res = dispatch
+            .oneshot(request("Action=PutWidget&Version=2020-01-08&Name=foo"))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), http::StatusCode::OK);
+        // The route reads the same body the dispatcher buffered.
+        assert_eq!(
+            crate::protocol::test_helpers::get_body_as_string(res.into_body()).await,
+            "PutWidget: Action=PutWidget&Version=2020-01-08&Name=foo"
+        );
+
+        for body in ["Action=DeleteWidget", "Version=2020-01-08", "Action=%FF"] {
+            let dispatch = router.match_route(&request("")).unwrap();
+            let res = dispatch.oneshot(request(body)).await.unwrap();
+            assert_eq!(res.status(), http::StatusCode::NOT_FOUND, "{body}");
+            assert_eq!(
+                res.extensions().get::<RuntimeErrorExtension>().unwrap().as_str(),
+                UNKNOWN_OPERATION_EXCEPTION
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn limits_the_body_size() {
+        let body = "Action=PutWidget&Name=foo";
+        let router = router().with_max_body_size(body.len() as u64);
+
+        let dispatch = router.match_route(&request("")).unwrap();
+        let res = dispatch.oneshot(request(body)).await.unwrap();
+        assert_eq!(res.status(), http::StatusCode::OK);
+
+        // Rejected while streaming the body.
+        let too_large = "Action=PutWidget&Name=foo&";
+        let dispatch = router.match_route(&request("")).unwrap();
+        let res = dispatch.oneshot(request(too_large)).await.unwrap();
+        assert_eq!(res.status(), http::StatusCode::PAYLOAD_TOO_LARGE);
+
+        // Rejected from the `Content-Length` header, without reading the body.
+        let mut req = request(body);
+        req.headers_mut()
+            .insert(http::header::CONTENT_LENGTH, (body.len() + 1).into());
+        let dispatch = router.match_route(&request("")).unwrap();
+        let res = dispatch.oneshot(req).await.unwrap();
+        assert_eq!(res.status(), http::StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(
+            res.extensions().get::<RuntimeErrorExtension>().unwrap().as_str(),
+            "PayloadTooLargeException"
+        );
+
+        // The limit is kept when layering the routes.
+        let router = router.layer(tower::layer::util::Identity::new());
+        let dispatch = router.match_route(&request("")).unwrap();
+        let res = dispatch.oneshot(request(too_large)).await.unwrap();
+        assert_eq!(res.status(), http::StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -0,0 +1,186 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::protocol::aws_query::AwsQuery;
+use crate::protocol::ec2_query::Ec2Query;
+use crate::rejection::PayloadTooLarge;
+use crate::response::IntoResponse;
+use crate::runtime_error::{
+    InternalFailureException, ThrottlingException, INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE,
+};
+use crate::{body::BoxBody, extension::RuntimeErrorExtension};
+use http::StatusCode;
+
+use super::rejection::{RequestRejection, ResponseRejection};
+
+#[derive(Debug)]
+pub enum RuntimeError {
+    Serialization(crate::Error),
+    InternalFailure(crate::Error),
+    NotAcceptable,
+    UnsupportedMediaType,
+    Validation(String),
+    Throttling,
+    PayloadTooLarge,
+}
+
+impl RuntimeError {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Serialization(_) => "SerializationException",
+            Self::InternalFailure(_) => "InternalFailureException",
+            Self::NotAcceptable => "NotAcceptableException",
+            Self::UnsupportedMediaType => "UnsupportedMediaTypeException",
+            Self::Validation(_) => "ValidationException",
+            Self::Throttling => "ThrottlingException",
+            Self::PayloadTooLarge => "PayloadTooLargeException",
+        }
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::Serialization(_) => StatusCode::BAD_REQUEST,
+            Self::InternalFailure(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
+            Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::Validation(_) => StatusCode::BAD_REQUEST,
+            Self::Throttling => StatusCode::TOO_MANY_REQUESTS,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+        }
+    }
+
+    /// Builds the response, whose body is the serialized validation exception for
+    /// [`RuntimeError::Validation`] and `envelope` applied to the error name otherwise.
+    fn into_response_with(self, envelope: impl FnOnce(&str, StatusCode) -> String) -> http::Response<BoxBody> {
+        let res = http::Response::builder()
+            .status(self.status_code())
+            .header("Content-Type", "text/xml")
+            .extension(RuntimeErrorExtension::new(self.name().to_string()));
+
+        let body = match self {
+            RuntimeError::Validation(reason) => crate::body::to_boxed(reason),
+            _ => crate::body::to_boxed(envelope(self.name(), self.status_code())),
+        };
+
+        res.body(body)
+            .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+    }
+}
+
+/// Whether the client or the server is at fault for an error, as reported in the `Type` element of
+/// AWS Query errors.
+fn fault(status: StatusCode) -> &'static str {
+    if status.is_server_error() {
+        "Receiver"
+    } else {
+        "Sender"
+    }
+}
+
+impl IntoResponse<AwsQuery> for InternalFailureException {
+    fn into_response(self) -> http::Response<BoxBody> {
+        IntoResponse::<AwsQuery>::into_response(RuntimeError::InternalFailure(crate::Error::new(String::new())))
+    }
+}
+
+impl IntoResponse<AwsQuery> for ThrottlingException {
+    fn into_response(self) -> http::Response<BoxBody> {
+        IntoResponse::<AwsQuery>::into_response(RuntimeError::Throttling)
+    }
+}
+
+impl IntoResponse<AwsQuery> for PayloadTooLarge {
+    fn into_response(self) -> http::Response<BoxBody> {
+        IntoResponse::<AwsQuery>::into_response(RuntimeError::PayloadTooLarge)
+    }
+}
+
+impl IntoResponse<Ec2Query> for InternalFailureException {
+    fn into_response(self) -> http::Response<BoxBody> {
+        IntoResponse::<Ec2Query>::into_response(RuntimeError::InternalFailure(crate::Error::new(String::new())))
+    }
+}
+
+impl IntoResponse<Ec2Query> for ThrottlingException {
+    fn into_response(self) -> http::Response<BoxBody> {
+        IntoResponse::<Ec2Query>::into_response(RuntimeError::Throttling)
+    }
+}
+
+impl IntoResponse<Ec2Query> for PayloadTooLarge {
+    fn into_response(self) -> http::Response<BoxBody> {
+        IntoResponse::<Ec2Query>::into_response(RuntimeError::PayloadTooLarge)
+    }
+}
+
+impl IntoResponse<AwsQuery> for RuntimeError {
+    fn into_response(self) -> http::Response<BoxBody> {
+        // See https://smithy.io/2.0/aws/protocols/aws-query-protocol.html#operation-error-serialization
+        self.into_response_with(|code, status| {
+            format!(
+                "<ErrorResponse><Error><Type>{}</Type><Code>{code}</Code></Error></ErrorResponse>",
+                fault(status)
+            )
+        })
+    }
+}
+
+impl IntoResponse<Ec2Query> for RuntimeError {
+    fn into_response(self) -> http::Response<BoxBody> {
+        // See https://smithy.io/2.0/aws/protocols/aws-ec2-query-protocol.html#operation-error-serialization
+        self.into_response_with(|code, _| {
+            format!("<Response><Errors><Error><Code>{code}</Code></Error></Errors></Response>")
+        })
+    }
+}
+
+impl From<ResponseRejection> for RuntimeError {
+    fn from(err: ResponseRejection) -> Self {
+        Self::Serialization(crate::Error::new(err))
+    }
+}
+
+impl From<RequestRejection> for RuntimeError {
+    fn from(err: RequestRejection) -> Self {
+        match err {
+            RequestRejection::MissingContentType(_reason) => Self::UnsupportedMediaType,
+            RequestRejection::ConstraintViolation(reason) => Self::Validation(reason),
+            RequestRejection::PayloadTooLarge(_) => Self::PayloadTooLarge,
+            _ => Self::Serialization(crate::Error::new(err)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RuntimeError;
+    use crate::protocol::{aws_query::AwsQuery, ec2_query::Ec2Query, test_helpers::get_body_as_string};
+    use crate::response::IntoResponse;
+
+    #[tokio::test]
+    async fn runtime_errors_are_wrapped_in_the_protocol_envelope() {
+        let res = IntoResponse::<AwsQuery>::into_response(RuntimeError::Throttling);
+        assert_eq!(res.status(), http::StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(res.headers()["Content-Type"], "text/xml");
+        assert_eq!(
+            get_body_as_string(res.into_body()).await,
+            "<ErrorResponse><Error><Type>Sender</Type><Code>ThrottlingException</Code></Error></ErrorResponse>"
+        );
+
+        let res =
+            IntoResponse::<AwsQuery>::into_response(RuntimeError::InternalFailure(crate::Error::new(String::new())));
+        assert_eq!(
+            get_body_as_string(res.into_body()).await,
+            "<ErrorResponse><Error><Type>Receiver</Type><Code>InternalFailureException</Code></Error></ErrorResponse>"
+        );
+
+        let res = IntoResponse::<Ec2Query>::into_response(RuntimeError::PayloadTooLarge);
+        assert_eq!(res.status(), http::StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(
+            get_body_as_string(res.into_body()).await,
+            "<Response><Errors><Error><Code>PayloadTooLargeException</Code></Error></Errors></Response>"
+        );
+    }
+}
@@ -4,12 +4,16 @@
  */
 
 use std::convert::Infallible;
+use std::fmt;
 
 use crate::body::BoxBody;
+use crate::routing::method_disallowed;
 use crate::routing::request_spec::Match;
+use crate::routing::request_spec::Mismatch;
 use crate::routing::request_spec::RequestSpec;
 use crate::routing::Route;
 use crate::routing::Router;
+use http::Method;
 use tower::Layer;
 use tower::Service;
 
@@ -23,11 +27,163 @@ pub enum Error {
     NotFound,
     /// Method was not allowed.
     #[error("method was not allowed")]
-    MethodNotAllowed,
+    MethodNotAllowed(AllowedMethods),
 }
 
+/// The HTTP methods of the operations whose URI pattern matches a request.
+///
+/// This is inserted into the extensions of `405 Method Not Allowed` responses, and listed in their
+/// `Allow` header.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AllowedMethods(Vec<Method>);
+
+impl AllowedMethods {
+    /// Returns the allowed methods, in the order in which their operations are routed to.
+    pub fn methods(&self) -> &[Method] {
+        &self.0
+    }
+
+    /// Returns `true` if `method` is allowed.
+    pub fn contains(&self, method: &Method) -> bool {
+        self.0.contains(method)
+    }
+
+    fn push(&mut self, method: &Method) {
+        if !self.contains(method) {
+            self.0.push(method.clone());
+        }
+    }
+}
+
+impl fmt::Display for AllowedMethods {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, method) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(method.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+/// Constructs the `405 Method Not Allowed` response for a request to a URI whose operations only
+/// allow `allowed_methods`.
+pub(crate) fn method_not_allowed(allowed_methods: AllowedMethods) -> http::Response<BoxBody> {
+    let mut response = method_disallowed();
+    if let Ok(allow) = http::HeaderValue::from_str(&allowed_methods.to_string()) {
+        response.headers_mut().insert(http::header::ALLOW, allow);
+    }
+    response.extensions_mut().insert(allowed_methods);
+    response
+}
+
+/// A CORS preflight request that [`RestRouter`] routed to the operation of the method it asks
+/// for, with the methods that its URI allows.
+///
+/// [`CorsPlugin`](crate::plugin::cors::CorsPlugin) answers these requests. Operations that it
+/// isn't applied to reject them with `405 Method Not Allowed`, as if they hadn't been routed.
+#[derive(Debug, Clone)]
+pub(crate) struct Preflight(pub(crate) AllowedMethods);
+
+impl Preflight {
+    /// Returns `true` if `request` is a CORS preflight request.
+    fn is_preflight<B>(request: &http::Request<B>) -> bool {
+        request.method() == Method::OPTIONS
+            && request.headers().contains_key(http::header::ORIGIN)
+            && request
+                .headers()
+                .contains_key(http::header::ACCESS_CONTROL_REQUEST_METHOD)
+    }
+}
+
+/// Two routes of a [`RestRouter`] whose URI patterns conflict.
+///
+/// Routes are tried in order of [specificity](RestRouter::routes), so a request that matches the
+/// URI patterns of both routes is always routed to `first`.
+#[derive(Debug, Clone, Copy)]
+pub enum RouteConflict<'a> {
+    /// Every request that matches `second` also matches `first`, so `second` is never routed to.
+    Shadowed {
+        /// The route that requests are routed to.
+        first: &'a RequestSpec,
+        /// The unreachable route.
+        second: &'a RequestSpec,
+    },
+    /// Some requests match both routes, and the routes are equally specific, so which one
+    /// they're routed to depends on the order in which the routes were registered.
+    Ambiguous {
+        /// The route that requests are routed to.
+        first: &'a RequestSpec,
+        /// The other route.
+        second: &'a RequestSpec,
+    },
+}
+
+/// Formats a route as its operation's shape ID followed by its URI pattern.
+struct RouteDisplay<'a>(&'a RequestSpec);
+
+impl fmt::Display for RouteDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.shape_id() {
+            Some(shape_id) => write!(f, "`{}` ({})", shape_id.absolute(), self.0),
+            None => write!(f, "`{}`", self.0),
+        }
+    }
+}
+
+impl fmt::Display for RouteConflict<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteConflict::Shadowed { first, second } => write!(
+                f,
+                "route {} is shadowed by route {}, so no requests are routed to it",
+                RouteDisplay(second),
+                RouteDisplay(first)
+            ),
+            RouteConflict::Ambiguous { first, second } => write!(
+                f,
+                "routes {} and {} are equally specific and match some of the same requests, which are routed to the former",
+                RouteDisplay(first),
+                RouteDisplay(second)
+            ),
+        }
+    }
+}
+
+/// The error returned by [`RestRouter::check_conflicts`] when the URI patterns of some routes
+/// [conflict](RouteConflict).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteConflictsError {
+    conflicts: Vec<String>,
+}
+
+impl RouteConflictsError {
+    /// Returns a description of each conflict.
+    pub fn conflicts(&self) -> &[String] {
+        &self.conflicts
+    }
+}
+
+impl fmt::Display for RouteConflictsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "the URI patterns of some routes conflict:")?;
+        for conflict in &self.conflicts {
+            writeln!(f, "- {conflict}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RouteConflictsError {}
+
 /// A [`Router`] supporting [`AWS REST JSON 1.0`] and [`AWS REST XML`] protocols.
 ///
+/// Routes whose URI patterns [conflict](RouteConflict) are logged as warnings when the router is
+/// constructed, and [`RestRouter::check_conflicts`] returns them as an error. When a request
+/// doesn't match any route, the reason why it doesn't match each route is logged at the `DEBUG`
+/// level; see [`RestRouter::explain`].
+///
 /// [AWS REST JSON 1.0]: https://awslabs.github.io/smithy/2.0/aws/protocols/aws-restjson1-protocol.html
 /// [AWS REST XML]: https://awslabs.github.io/smithy/2.0/aws/protocols/aws-restxml-protocol.html
 #[derive(Debug, Clone)]
@@ -36,6 +192,55 @@ pub struct RestRouter<S> {
 }
 
 impl<S> RestRouter<S> {
+    /// Returns the [`RequestSpec`]s of the routes, in the order in which requests are matched
+    /// against them: more specific URI patterns first, and otherwise in the order in which they
+    /// were registered.
+    pub fn routes(&self) -> impl Iterator<Item = &RequestSpec> {
+        self.routes.iter().map(|(request_spec, _route)| request_spec)
+    }
+
+    /// Returns the pairs of routes with the same HTTP method whose URI patterns conflict.
+    pub fn conflicts(&self) -> Vec<RouteConflict<'_>> {
+        let mut conflicts = Vec::new();
+        for (i, (first, _)) in self.routes.iter().enumerate() {
+            for (second, _) in &self.routes[i + 1..] {
+                if first.method() != second.method() {
+                    continue;
+                }
+                if first.covers(second) {
+                    conflicts.push(RouteConflict::Shadowed { first, second });
+                } else if first.rank() == second.rank() && first.overlaps(second) {
+                    conflicts.push(RouteConflict::Ambiguous { first, second });
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Returns an error describing the [`conflicts`](RestRouter::conflicts) of the routes, if
+    /// there are any.
+    pub fn check_conflicts(&self) -> Result<(), RouteConflictsError> {
+        let conflicts: Vec<_> = self.conflicts().iter().map(ToString::to_string).collect();
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(RouteConflictsError { conflicts })
+        }
+    }
+
+    /// Returns the reason why `request` doesn't match each route that it doesn't match, in the
+    /// order in which the routes are tried.
+    pub fn explain<'a, B>(&'a self, request: &http::Request<B>) -> Vec<(&'a RequestSpec, Mismatch<'a>)> {
+        self.routes()
+            .filter_map(|request_spec| {
+                request_spec
+                    .check(request)
+                    .err()
+                    .map(|mismatch| (request_spec, mismatch))
+            })
+            .collect()
+    }
+
     /// Applies a [`Layer`] uniformly to all routes.
     pub fn layer<L>(self, layer: L) -> RestRouter<L::Service>
     where
@@ -71,23 +276,56 @@ where
     type Error = Error;
 
     fn match_route(&self, request: &http::Request<B>) -> Result<S, Self::Error> {
-        let mut method_allowed = true;
+        let mut allowed_methods = AllowedMethods::default();
 
         for (request_spec, route) in &self.routes {
             match request_spec.matches(request) {
                 // Match found.
                 Match::Yes => return Ok(route.clone()),
                 // Match found, but method disallowed.
-                Match::MethodNotAllowed => method_allowed = false,
+                Match::MethodNotAllowed => allowed_methods.push(request_spec.method()),
                 // Continue looping to see if another route matches.
                 Match::No => continue,
             }
         }
 
-        if method_allowed {
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            for (request_spec, mismatch) in self.explain(request) {
+                tracing::debug!(route = %RouteDisplay(request_spec), %mismatch, "request did not match route");
+            }
+        }
+
+        if allowed_methods.0.is_empty() {
             Err(Error::NotFound)
         } else {
-            Err(Error::MethodNotAllowed)
+            Err(Error::MethodNotAllowed(allowed_methods))
+        }
+    }
+
+    /// Routes CORS preflight requests to the operation of the method that they ask for, with a
+    /// [`Preflight`] extension, so that [`CorsPlugin`](crate::plugin::cors::CorsPlugin) can
+    /// answer them.
+    fn match_request(&self, request: &mut http::Request<B>) -> Result<S, Self::Error> {
+        let allowed_methods = match self.match_route(request) {
+            Err(Error::MethodNotAllowed(allowed_methods)) if Preflight::is_preflight(request) => allowed_methods,
+            result => return result,
+        };
+        let requested_method = request
+            .headers()
+            .get(http::header::ACCESS_CONTROL_REQUEST_METHOD)
+            .and_then(|value| Method::from_bytes(value.as_bytes()).ok());
+        let route = requested_method.and_then(|requested_method| {
+            self.routes.iter().find_map(|(request_spec, route)| {
+                (request_spec.method() == requested_method && request_spec.matches(request) == Match::MethodNotAllowed)
+                    .then(|| route.clone())
+            })
+        });
+        match route {
+            Some(route) => {
+                request.extensions_mut().insert(Preflight(allowed_methods));
+                Ok(route)
+            }
+            None => Err(Error::MethodNotAllowed(allowed_methods)),
         }
     }
 }
@@ -102,14 +340,18 @@ impl<S> FromIterator<(RequestSpec, S)> for RestRouter<S> {
         // and pick the first one that matches.
         routes.sort_by_key(|(request_spec, _route)| std::cmp::Reverse(request_spec.rank()));
 
-        Self { routes }
+        let router = Self { routes };
+        for conflict in router.conflicts() {
+            tracing::warn!(%conflict, "conflicting routes");
+        }
+        router
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{protocol::test_helpers::req, routing::request_spec::*};
+    use crate::{protocol::test_helpers::req, routing::request_spec::*, shape_id::ShapeId};
 
     use http::Method;
 
@@ -183,9 +425,9 @@ mod tests {
             assert_eq!(router.match_route(&req(method, uri, None)).unwrap(), *svc_name);
         }
 
-        for (_, _, uri) in hits {
+        for (_, method, uri) in hits {
             let res = router.match_route(&req(&Method::PATCH, uri, None));
-            assert_eq!(res.unwrap_err(), Error::MethodNotAllowed);
+            assert_eq!(res.unwrap_err(), Error::MethodNotAllowed(AllowedMethods(vec![method])));
         }
 
         let misses = vec![
@@ -261,4 +503,190 @@ mod tests {
             assert_eq!(router.match_route(&req(&method, uri, None)).unwrap(), svc_name);
         }
     }
+
+    fn spec(method: Method, path_segments: Vec<PathSegment>, query_segments: Vec<QuerySegment>) -> RequestSpec {
+        RequestSpec::from_parts(method, path_segments, query_segments)
+    }
+
+    fn literal(literal: &str) -> PathSegment {
+        PathSegment::Literal(String::from(literal))
+    }
+
+    #[test]
+    fn routes_are_listed_in_routing_order() {
+        let request_specs = vec![
+            (
+                spec(Method::GET, vec![literal("a")], Vec::new()).with_shape_id(ShapeId::new("test#A", "test", "A")),
+                "A",
+            ),
+            (
+                spec(Method::GET, vec![literal("b"), PathSegment::Label], Vec::new())
+                    .with_shape_id(ShapeId::new("test#B", "test", "B")),
+                "B",
+            ),
+        ];
+        let router: RestRouter<_> = request_specs.into_iter().collect();
+
+        let routes: Vec<_> = router
+            .routes()
+            .map(|request_spec| (request_spec.shape_id().unwrap().name(), request_spec.to_string()))
+            .collect();
+        assert_eq!(
+            routes,
+            vec![("B", String::from("GET /b/{label}")), ("A", String::from("GET /a"))]
+        );
+    }
+
+    #[test]
+    fn conflicting_routes_are_detected() {
+        let request_specs = vec![
+            (
+                spec(Method::GET, vec![literal("a"), PathSegment::Label], Vec::new()),
+                "A1",
+            ),
+            (spec(Method::GET, vec![literal("a"), literal("b")], Vec::new()), "A2"),
+            (
+                spec(Method::GET, vec![PathSegment::Label, literal("c")], Vec::new()),
+                "C",
+            ),
+            (
+                spec(Method::POST, vec![literal("a"), literal("b")], Vec::new()),
+                "PostA",
+            ),
+            (
+                spec(Method::GET, vec![literal("g"), PathSegment::Greedy], Vec::new()),
+                "G",
+            ),
+            (
+                spec(Method::GET, vec![literal("g"), literal("h"), literal("i")], Vec::new()),
+                "GHI",
+            ),
+            (
+                spec(
+                    Method::GET,
+                    vec![literal("q")],
+                    vec![QuerySegment::KeyValue(String::from("k"), String::from("1"))],
+                ),
+                "Q1",
+            ),
+            (
+                spec(
+                    Method::GET,
+                    vec![literal("q")],
+                    vec![QuerySegment::KeyValue(String::from("k"), String::from("2"))],
+                ),
+                "Q2",
+            ),
+        ];
+        let router: RestRouter<_> = request_specs.into_iter().collect();
+
+        let conflicts: Vec<_> = router
+            .conflicts()
+            .into_iter()
+            .map(|conflict| match conflict {
+                RouteConflict::Shadowed { first, second } => ("shadowed", first.to_string(), second.to_string()),
+                RouteConflict::Ambiguous { first, second } => ("ambiguous", first.to_string(), second.to_string()),
+            })
+            .collect();
+        assert_eq!(
+            conflicts,
+            vec![
+                ("shadowed", String::from("GET /a/{label}"), String::from("GET /a/b")),
+                (
+                    "ambiguous",
+                    String::from("GET /a/{label}"),
+                    String::from("GET /{label}/c")
+                ),
+                (
+                    "ambiguous",
+                    String::from("GET /{label}/c"),
+                    String::from("GET /g/{greedy+}")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn conflicts_are_checked() {
+        let router: RestRouter<_> = vec![
+            (spec(Method::GET, vec![literal("a")], Vec::new()), "A"),
+            (spec(Method::GET, vec![literal("b")], Vec::new()), "B"),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(router.check_conflicts(), Ok(()));
+
+        let router: RestRouter<_> = vec![
+            (spec(Method::GET, vec![PathSegment::Label], Vec::new()), "Label"),
+            (spec(Method::GET, vec![literal("a")], Vec::new()), "A"),
+        ]
+        .into_iter()
+        .collect();
+        let error = router.check_conflicts().unwrap_err();
+        assert_eq!(
+            error.conflicts(),
+            [String::from(
+                "route `GET /a` is shadowed by route `GET /{label}`, so no requests are routed to it"
+            )]
+        );
+        assert_eq!(
+            error.to_string(),
+            "the URI patterns of some routes conflict:\n\
+            - route `GET /a` is shadowed by route `GET /{label}`, so no requests are routed to it\n"
+        );
+    }
+
+    #[test]
+    fn mismatches_are_explained() {
+        let request_specs = vec![
+            (spec(Method::GET, vec![literal("a")], Vec::new()), "A"),
+            (
+                spec(
+                    Method::GET,
+                    vec![literal("q")],
+                    vec![
+                        QuerySegment::Key(String::from("key")),
+                        QuerySegment::KeyValue(String::from("k"), String::from("v")),
+                    ],
+                ),
+                "Q",
+            ),
+        ];
+        let router: RestRouter<_> = request_specs.into_iter().collect();
+
+        let explain = |method, uri| {
+            router
+                .explain(&req(&method, uri, None))
+                .into_iter()
+                .map(|(request_spec, mismatch)| (request_spec.to_string(), mismatch))
+                .collect::<Vec<_>>()
+        };
+        let get = Method::GET;
+        assert_eq!(
+            explain(Method::POST, "/a"),
+            vec![
+                (String::from("GET /q?key&k=v"), Mismatch::Path),
+                (String::from("GET /a"), Mismatch::Method(&get)),
+            ]
+        );
+        assert_eq!(
+            explain(Method::GET, "/q")[0],
+            (String::from("GET /q?key&k=v"), Mismatch::MissingQuery)
+        );
+        assert_eq!(
+            explain(Method::GET, "/q?k=v")[0],
+            (String::from("GET /q?key&k=v"), Mismatch::MissingQueryKey("key"))
+        );
+        assert_eq!(
+            explain(Method::GET, "/q?key&k=w")[0],
+            (
+                String::from("GET /q?key&k=v"),
+                Mismatch::QueryValue {
+                    key: "k",
+                    expected: "v"
+                }
+            )
+        );
+        assert_eq!(explain(Method::GET, "/q?key&k=v").len(), 1);
+    }
 }
@@ -110,6 +110,12 @@ pub enum RequestRejection {
     #[error("error converting non-streaming body to bytes: {0}")]
     BufferHttpBodyBytes(crate::Error),
 
+    /// Used when the request body is larger than the operation allows. This is detected while
+    /// the body is being buffered, but it can also be returned directly by
+    /// [`crate::plugin::RequestBodyLimitPlugin`] when the `Content-Length` header exceeds the limit.
+    #[error("{0}")]
+    PayloadTooLarge(crate::rejection::PayloadTooLarge),
+
     /// Used when the request contained an `Accept` header with a MIME type, and the server cannot
     /// return a response body adhering to that MIME type.
     #[error("request contains invalid value for `Accept` header")]
@@ -205,8 +211,8 @@ impl From<nom::Err<nom::error::Error<&str>>> for RequestRejection {
 // need this converter for when we convert the body into bytes in the framework, since protocol
 // tests use `[crate::body::Body]` as their body type when constructing requests (and almost
 // everyone will run a Hyper-based server in their services).
-convert_to_request_rejection!(hyper::Error, BufferHttpBodyBytes);
+convert_body_error_to_request_rejection!(hyper::Error);
 
 // Useful in general, but it also required in order to accept Lambda HTTP requests using
 // `Router<lambda_http::Body>` since `lambda_http::Error` is a type alias for `Box<dyn Error + ..>`.
-convert_to_request_rejection!(Box<dyn std::error::Error + Send + Sync + 'static>, BufferHttpBodyBytes);
+convert_body_error_to_request_rejection!(Box<dyn std::error::Error + Send + Sync + 'static>);
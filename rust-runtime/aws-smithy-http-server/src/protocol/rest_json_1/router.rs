@@ -5,8 +5,9 @@
 
 use crate::body::BoxBody;
 use crate::extension::RuntimeErrorExtension;
+use crate::protocol::rest::router::method_not_allowed;
 use crate::response::IntoResponse;
-use crate::routing::{method_disallowed, UNKNOWN_OPERATION_EXCEPTION};
+use crate::routing::UNKNOWN_OPERATION_EXCEPTION;
 
 use super::RestJson1;
 
@@ -24,7 +25,7 @@ impl IntoResponse<RestJson1> for Error {
                 ))
                 .body(crate::body::to_boxed("{}"))
                 .expect("invalid HTTP response for REST JSON 1 routing error; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues"),
-            Error::MethodNotAllowed => method_disallowed(),
+            Error::MethodNotAllowed(allowed_methods) => method_not_allowed(allowed_methods),
         }
     }
 }
@@ -33,9 +33,10 @@ use super::rejection::RequestRejection;
 use super::rejection::ResponseRejection;
 use super::RestJson1;
 use crate::extension::RuntimeErrorExtension;
+use crate::rejection::PayloadTooLarge;
 use crate::response::IntoResponse;
-use crate::runtime_error::InternalFailureException;
 use crate::runtime_error::INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE;
+use crate::runtime_error::{InternalFailureException, ThrottlingException};
 use http::StatusCode;
 
 #[derive(Debug)]
@@ -56,6 +57,11 @@ pub enum RuntimeError {
     /// Operation input contains data that does not adhere to the modeled [constraint traits].
     /// [constraint traits]: <https://awslabs.github.io/smithy/2.0/spec/constraint-traits.html>
     Validation(String),
+    /// The client exceeded a rate limit. This is returned directly (i.e. without going through a
+    /// [`RequestRejection`] first) by [`crate::plugin::RateLimitPlugin`].
+    Throttling,
+    /// The request body is larger than the operation allows.
+    PayloadTooLarge,
 }
 
 impl RuntimeError {
@@ -69,6 +75,8 @@ impl RuntimeError {
             Self::NotAcceptable => "NotAcceptableException",
             Self::UnsupportedMediaType => "UnsupportedMediaTypeException",
             Self::Validation(_) => "ValidationException",
+            Self::Throttling => "ThrottlingException",
+            Self::PayloadTooLarge => "PayloadTooLargeException",
         }
     }
 
@@ -79,6 +87,8 @@ impl RuntimeError {
             Self::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
             Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
             Self::Validation(_) => StatusCode::BAD_REQUEST,
+            Self::Throttling => StatusCode::TOO_MANY_REQUESTS,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
         }
     }
 }
@@ -89,6 +99,18 @@ impl IntoResponse<RestJson1> for InternalFailureException {
     }
 }
 
+impl IntoResponse<RestJson1> for ThrottlingException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<RestJson1>::into_response(RuntimeError::Throttling)
+    }
+}
+
+impl IntoResponse<RestJson1> for PayloadTooLarge {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<RestJson1>::into_response(RuntimeError::PayloadTooLarge)
+    }
+}
+
 impl IntoResponse<RestJson1> for RuntimeError {
     fn into_response(self) -> http::Response<crate::body::BoxBody> {
         let res = http::Response::builder()
@@ -118,6 +140,7 @@ impl From<RequestRejection> for RuntimeError {
         match err {
             RequestRejection::MissingContentType(_reason) => Self::UnsupportedMediaType,
             RequestRejection::ConstraintViolation(reason) => Self::Validation(reason),
+            RequestRejection::PayloadTooLarge(_) => Self::PayloadTooLarge,
             RequestRejection::NotAcceptable => Self::NotAcceptable,
             _ => Self::Serialization(crate::Error::new(err)),
         }
@@ -29,6 +29,9 @@ pub enum RequestRejection {
     #[error("error converting non-streaming body to bytes: {0}")]
     BufferHttpBodyBytes(crate::Error),
 
+    #[error("{0}")]
+    PayloadTooLarge(crate::rejection::PayloadTooLarge),
+
     #[error("request contains invalid value for `Accept` header")]
     NotAcceptable,
 
@@ -77,5 +80,5 @@ impl From<nom::Err<nom::error::Error<&str>>> for RequestRejection {
     }
 }
 
-convert_to_request_rejection!(hyper::Error, BufferHttpBodyBytes);
-convert_to_request_rejection!(Box<dyn std::error::Error + Send + Sync + 'static>, BufferHttpBodyBytes);
+convert_body_error_to_request_rejection!(hyper::Error);
+convert_body_error_to_request_rejection!(Box<dyn std::error::Error + Send + Sync + 'static>);
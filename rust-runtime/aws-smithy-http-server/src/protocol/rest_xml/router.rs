@@ -6,8 +6,9 @@
 use crate::body::empty;
 use crate::body::BoxBody;
 use crate::extension::RuntimeErrorExtension;
+use crate::protocol::rest::router::method_not_allowed;
 use crate::response::IntoResponse;
-use crate::routing::{method_disallowed, UNKNOWN_OPERATION_EXCEPTION};
+use crate::routing::UNKNOWN_OPERATION_EXCEPTION;
 
 use super::RestXml;
 
@@ -25,7 +26,7 @@ impl IntoResponse<RestXml> for Error {
                 ))
                 .body(empty())
                 .expect("invalid HTTP response for REST XML routing error; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues"),
-            Error::MethodNotAllowed => method_disallowed(),
+            Error::MethodNotAllowed(allowed_methods) => method_not_allowed(allowed_methods),
         }
     }
 }
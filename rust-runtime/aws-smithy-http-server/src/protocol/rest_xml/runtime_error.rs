@@ -4,8 +4,9 @@
  */
 
 use crate::protocol::rest_xml::RestXml;
+use crate::rejection::PayloadTooLarge;
 use crate::response::IntoResponse;
-use crate::runtime_error::InternalFailureException;
+use crate::runtime_error::{InternalFailureException, ThrottlingException};
 use crate::{extension::RuntimeErrorExtension, runtime_error::INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE};
 use http::StatusCode;
 
@@ -18,6 +19,8 @@ pub enum RuntimeError {
     NotAcceptable,
     UnsupportedMediaType,
     Validation(String),
+    Throttling,
+    PayloadTooLarge,
 }
 
 impl RuntimeError {
@@ -28,6 +31,8 @@ impl RuntimeError {
             Self::NotAcceptable => "NotAcceptableException",
             Self::UnsupportedMediaType => "UnsupportedMediaTypeException",
             Self::Validation(_) => "ValidationException",
+            Self::Throttling => "ThrottlingException",
+            Self::PayloadTooLarge => "PayloadTooLargeException",
         }
     }
 
@@ -38,6 +43,8 @@ impl RuntimeError {
             Self::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
             Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
             Self::Validation(_) => StatusCode::BAD_REQUEST,
+            Self::Throttling => StatusCode::TOO_MANY_REQUESTS,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
         }
     }
 }
@@ -48,6 +55,18 @@ impl IntoResponse<RestXml> for InternalFailureException {
     }
 }
 
+impl IntoResponse<RestXml> for ThrottlingException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<RestXml>::into_response(RuntimeError::Throttling)
+    }
+}
+
+impl IntoResponse<RestXml> for PayloadTooLarge {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<RestXml>::into_response(RuntimeError::PayloadTooLarge)
+    }
+}
+
 impl IntoResponse<RestXml> for RuntimeError {
     fn into_response(self) -> http::Response<crate::body::BoxBody> {
         let res = http::Response::builder()
@@ -73,6 +92,7 @@ impl From<RequestRejection> for RuntimeError {
         match err {
             RequestRejection::MissingContentType(_reason) => Self::UnsupportedMediaType,
             RequestRejection::ConstraintViolation(reason) => Self::Validation(reason),
+            RequestRejection::PayloadTooLarge(_) => Self::PayloadTooLarge,
             _ => Self::Serialization(crate::Error::new(err)),
         }
     }
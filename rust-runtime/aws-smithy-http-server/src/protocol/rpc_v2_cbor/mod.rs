@@ -0,0 +1,17 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+pub mod rejection;
+pub mod router;
+pub mod runtime_error;
+
+/// The name of the header that identifies the protocol of requests and responses.
+pub const SMITHY_PROTOCOL_HEADER: &str = "smithy-protocol";
+
+/// The value of the [`SMITHY_PROTOCOL_HEADER`] header in the Smithy RPC v2 CBOR protocol.
+pub const SMITHY_PROTOCOL_VALUE: &str = "rpc-v2-cbor";
+
+/// [Smithy RPC v2 CBOR Protocol](https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html).
+pub struct RpcV2Cbor;
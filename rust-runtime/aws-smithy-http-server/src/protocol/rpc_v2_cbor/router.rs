@@ -0,0 +1,261 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::convert::Infallible;
+
+use tower::Layer;
+use tower::Service;
+
+use crate::body::{empty, BoxBody};
+use crate::extension::RuntimeErrorExtension;
+use crate::response::IntoResponse;
+use crate::routing::tiny_map::TinyMap;
+use crate::routing::Route;
+use crate::routing::Router;
+use crate::routing::{method_disallowed, UNKNOWN_OPERATION_EXCEPTION};
+
+use http::header::ToStrError;
+use thiserror::Error;
+
+use super::{RpcV2Cbor, SMITHY_PROTOCOL_HEADER, SMITHY_PROTOCOL_VALUE};
+
+/// An RPC v2 CBOR routing error.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Method was not `POST`.
+    #[error("method not POST")]
+    MethodNotAllowed,
+    /// Missing the `smithy-protocol` header.
+    #[error("missing the \"smithy-protocol\" header")]
+    MissingHeader,
+    /// Unable to parse header into UTF-8.
+    #[error("failed to parse header: {0}")]
+    InvalidHeader(ToStrError),
+    /// The `smithy-protocol` header was not `rpc-v2-cbor`.
+    #[error("the \"smithy-protocol\" header is not \"rpc-v2-cbor\"")]
+    InvalidProtocol,
+    /// The path did not end in `/service/{serviceName}/operation/{operationName}`.
+    #[error("path is not of the form `/service/{{serviceName}}/operation/{{operationName}}`")]
+    InvalidPath,
+    /// Operation not found.
+    #[error("operation not found")]
+    NotFound,
+}
+
+// This constant determines when the `TinyMap` implementation switches from being a `Vec` to a
+// `HashMap`. This is chosen to be 15 as a result of the discussion around
+// https://github.com/smithy-lang/smithy-rs/pull/1429#issuecomment-1147516546
+const ROUTE_CUTOFF: usize = 15;
+
+/// A [`Router`] supporting the [Smithy RPC v2 CBOR] protocol.
+///
+/// Operations are routed to by the path of requests, which must end in
+/// `/service/{serviceName}/operation/{operationName}`. The path may have a prefix, and the service
+/// name may be qualified by the namespace of the service, e.g. `com.example.Weather`. Routes are
+/// keyed by `{serviceName}.{operationName}`.
+///
+/// [Smithy RPC v2 CBOR]: https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html
+#[derive(Debug, Clone)]
+pub struct RpcV2CborRouter<S> {
+    routes: TinyMap<String, S, ROUTE_CUTOFF>,
+}
+
+impl<S> RpcV2CborRouter<S> {
+    /// Applies a [`Layer`] uniformly to all routes.
+    pub fn layer<L>(self, layer: L) -> RpcV2CborRouter<L::Service>
+    where
+        L: Layer<S>,
+    {
+        RpcV2CborRouter {
+            routes: self
+                .routes
+                .into_iter()
+                .map(|(key, route)| (key, layer.layer(route)))
+                .collect(),
+        }
+    }
+
+    /// Applies type erasure to the inner route using [`Route::new`].
+    pub fn boxed<B>(self) -> RpcV2CborRouter<Route<B>>
+    where
+        S: Service<http::Request<B>, Response = http::Response<BoxBody>, Error = Infallible>,
+        S: Send + Clone + 'static,
+        S::Future: Send + 'static,
+    {
+        RpcV2CborRouter {
+            routes: self.routes.into_iter().map(|(key, s)| (key, Route::new(s))).collect(),
+        }
+    }
+}
+
+/// Returns the service name, without its namespace, and the operation name of a request path of
+/// the form `{prefix}/service/{serviceName}/operation/{operationName}`.
+fn parse_path(path: &str) -> Option<(&str, &str)> {
+    let mut segments = path.rsplit('/');
+    let operation = segments.next()?;
+    if segments.next()? != "operation" {
+        return None;
+    }
+    let service = segments.next()?;
+    if segments.next()? != "service" {
+        return None;
+    }
+    let service = service.rsplit('.').next()?;
+    if service.is_empty() || operation.is_empty() {
+        return None;
+    }
+    Some((service, operation))
+}
+
+impl<B, S> Router<B> for RpcV2CborRouter<S>
+where
+    S: Clone,
+{
+    type Service = S;
+    type Error = Error;
+
+    fn match_route(&self, request: &http::Request<B>) -> Result<S, Self::Error> {
+        // Only `Method::POST` is allowed.
+        if request.method() != http::Method::POST {
+            return Err(Error::MethodNotAllowed);
+        }
+
+        // The `smithy-protocol` header must identify the protocol.
+        let protocol = request
+            .headers()
+            .get(SMITHY_PROTOCOL_HEADER)
+            .ok_or(Error::MissingHeader)?;
+        if protocol.to_str().map_err(Error::InvalidHeader)? != SMITHY_PROTOCOL_VALUE {
+            return Err(Error::InvalidProtocol);
+        }
+
+        let (service, operation) = parse_path(request.uri().path()).ok_or(Error::InvalidPath)?;
+
+        // Lookup in the `TinyMap` for a route for the operation.
+        let route = self
+            .routes
+            .get(format!("{service}.{operation}").as_str())
+            .ok_or(Error::NotFound)?;
+        Ok(route.clone())
+    }
+}
+
+impl<S> FromIterator<(String, S)> for RpcV2CborRouter<S> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = (String, S)>>(iter: T) -> Self {
+        Self {
+            routes: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoResponse<RpcV2Cbor> for Error {
+    fn into_response(self) -> http::Response<BoxBody> {
+        match self {
+            Error::MethodNotAllowed => method_disallowed(),
+            _ => http::Response::builder()
+                .status(http::StatusCode::NOT_FOUND)
+                .header(http::header::CONTENT_TYPE, "application/cbor")
+                .header(SMITHY_PROTOCOL_HEADER, SMITHY_PROTOCOL_VALUE)
+                .extension(RuntimeErrorExtension::new(
+                    UNKNOWN_OPERATION_EXCEPTION.to_string(),
+                ))
+                .body(empty())
+                .expect("invalid HTTP response for RPC v2 CBOR routing error; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{protocol::test_helpers::req, routing::Router};
+
+    use http::{HeaderMap, HeaderValue, Method};
+    use pretty_assertions::assert_eq;
+
+    fn headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(SMITHY_PROTOCOL_HEADER, HeaderValue::from_static(SMITHY_PROTOCOL_VALUE));
+        headers
+    }
+
+    #[test]
+    fn path_parsing() {
+        for (path, expected) in [
+            ("/service/Service/operation/Operation", Some(("Service", "Operation"))),
+            (
+                "/prefix/service/Service/operation/Operation",
+                Some(("Service", "Operation")),
+            ),
+            (
+                "/service/com.example.Service/operation/Operation",
+                Some(("Service", "Operation")),
+            ),
+            ("/service/Service/operation/", None),
+            ("/service//operation/Operation", None),
+            ("/service/Service/operations/Operation", None),
+            ("/Service/operation/Operation", None),
+            ("/", None),
+        ] {
+            assert_eq!(expected, parse_path(path), "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn simple_routing() {
+        let router: RpcV2CborRouter<_> = [("Service.Operation".to_string(), ())].into_iter().collect();
+
+        // Valid requests, should match.
+        router
+            .match_route(&req(
+                &Method::POST,
+                "/service/Service/operation/Operation",
+                Some(headers()),
+            ))
+            .unwrap();
+        router
+            .match_route(&req(
+                &Method::POST,
+                "/prefix/service/com.example.Service/operation/Operation",
+                Some(headers()),
+            ))
+            .unwrap();
+
+        // No headers, should return `MissingHeader`.
+        let res = router.match_route(&req(&Method::POST, "/service/Service/operation/Operation", None));
+        assert_eq!(res.unwrap_err().to_string(), Error::MissingHeader.to_string());
+
+        // Another protocol, should return `InvalidProtocol`.
+        let mut json_headers = HeaderMap::new();
+        json_headers.insert(SMITHY_PROTOCOL_HEADER, HeaderValue::from_static("rpc-v2-json"));
+        let res = router.match_route(&req(
+            &Method::POST,
+            "/service/Service/operation/Operation",
+            Some(json_headers),
+        ));
+        assert_eq!(res.unwrap_err().to_string(), Error::InvalidProtocol.to_string());
+
+        // Wrong HTTP method, should return `MethodNotAllowed`.
+        let res = router.match_route(&req(
+            &Method::GET,
+            "/service/Service/operation/Operation",
+            Some(headers()),
+        ));
+        assert_eq!(res.unwrap_err().to_string(), Error::MethodNotAllowed.to_string());
+
+        // Malformed path, should return `InvalidPath`.
+        let res = router.match_route(&req(&Method::POST, "/Service.Operation", Some(headers())));
+        assert_eq!(res.unwrap_err().to_string(), Error::InvalidPath.to_string());
+
+        // Unknown operation, should return `NotFound`.
+        let res = router.match_route(&req(
+            &Method::POST,
+            "/service/Service/operation/Unknown",
+            Some(headers()),
+        ));
+        assert_eq!(res.unwrap_err().to_string(), Error::NotFound.to_string());
+    }
+}
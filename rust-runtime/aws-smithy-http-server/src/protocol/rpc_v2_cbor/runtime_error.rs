@@ -0,0 +1,110 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::extension::RuntimeErrorExtension;
+use crate::rejection::PayloadTooLarge;
+use crate::response::IntoResponse;
+use crate::runtime_error::{
+    InternalFailureException, ThrottlingException, INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE,
+};
+use http::StatusCode;
+
+use super::rejection::{RequestRejection, ResponseRejection};
+use super::{RpcV2Cbor, SMITHY_PROTOCOL_HEADER, SMITHY_PROTOCOL_VALUE};
+
+/// An empty CBOR map, the body of errors that have no members to serialize.
+const EMPTY_CBOR_MAP: &[u8] = &[0xa0];
+
+#[derive(Debug)]
+pub enum RuntimeError {
+    Serialization(crate::Error),
+    InternalFailure(crate::Error),
+    NotAcceptable,
+    UnsupportedMediaType,
+    /// The CBOR-encoded `ValidationException`.
+    Validation(Vec<u8>),
+    Throttling,
+    PayloadTooLarge,
+}
+
+impl RuntimeError {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Serialization(_) => "SerializationException",
+            Self::InternalFailure(_) => "InternalFailureException",
+            Self::NotAcceptable => "NotAcceptableException",
+            Self::UnsupportedMediaType => "UnsupportedMediaTypeException",
+            Self::Validation(_) => "ValidationException",
+            Self::Throttling => "ThrottlingException",
+            Self::PayloadTooLarge => "PayloadTooLargeException",
+        }
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::Serialization(_) => StatusCode::BAD_REQUEST,
+            Self::InternalFailure(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
+            Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::Validation(_) => StatusCode::BAD_REQUEST,
+            Self::Throttling => StatusCode::TOO_MANY_REQUESTS,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+        }
+    }
+}
+
+impl IntoResponse<RpcV2Cbor> for InternalFailureException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<RpcV2Cbor>::into_response(RuntimeError::InternalFailure(crate::Error::new(String::new())))
+    }
+}
+
+impl IntoResponse<RpcV2Cbor> for ThrottlingException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<RpcV2Cbor>::into_response(RuntimeError::Throttling)
+    }
+}
+
+impl IntoResponse<RpcV2Cbor> for PayloadTooLarge {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<RpcV2Cbor>::into_response(RuntimeError::PayloadTooLarge)
+    }
+}
+
+impl IntoResponse<RpcV2Cbor> for RuntimeError {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        let res = http::Response::builder()
+            .status(self.status_code())
+            .header("Content-Type", "application/cbor")
+            .header(SMITHY_PROTOCOL_HEADER, SMITHY_PROTOCOL_VALUE)
+            .extension(RuntimeErrorExtension::new(self.name().to_string()));
+
+        let body = match self {
+            RuntimeError::Validation(reason) => crate::body::to_boxed(reason),
+            _ => crate::body::to_boxed(EMPTY_CBOR_MAP),
+        };
+
+        res.body(body)
+            .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+    }
+}
+
+impl From<ResponseRejection> for RuntimeError {
+    fn from(err: ResponseRejection) -> Self {
+        Self::Serialization(crate::Error::new(err))
+    }
+}
+
+impl From<RequestRejection> for RuntimeError {
+    fn from(err: RequestRejection) -> Self {
+        match err {
+            RequestRejection::MissingContentType(_reason) => Self::UnsupportedMediaType,
+            RequestRejection::ConstraintViolation(reason) => Self::Validation(reason),
+            RequestRejection::PayloadTooLarge(_) => Self::PayloadTooLarge,
+            RequestRejection::NotAcceptable => Self::NotAcceptable,
+            _ => Self::Serialization(crate::Error::new(err)),
+        }
+    }
+}
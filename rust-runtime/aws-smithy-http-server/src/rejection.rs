@@ -24,6 +24,41 @@ pub enum MissingContentTypeReason {
     },
 }
 
+/// The request body exceeded the maximum size allowed for the operation, such as one enforced by
+/// [`RequestBodyLimitPlugin`](crate::plugin::RequestBodyLimitPlugin).
+///
+/// This is a _protocol-agnostic_ error, which is converted into a protocol-specific
+/// `RequestRejection` when it's encountered while the request body is being buffered.
+#[derive(Debug, Error, Clone)]
+#[error("request body exceeds the limit of {limit} bytes")]
+pub struct PayloadTooLarge {
+    limit: u64,
+}
+
+impl PayloadTooLarge {
+    /// Creates a new `PayloadTooLarge` for a request body larger than `limit` bytes.
+    pub fn new(limit: u64) -> Self {
+        Self { limit }
+    }
+
+    /// Returns the maximum number of bytes that the request body was allowed to have.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Finds a [`PayloadTooLarge`] in `err` or its sources.
+    pub(crate) fn find<'a>(err: &'a (dyn std::error::Error + 'static)) -> Option<&'a Self> {
+        let mut next = Some(err);
+        while let Some(err) = next {
+            if let Some(too_large) = err.downcast_ref::<Self>() {
+                return Some(too_large);
+            }
+            next = err.source();
+        }
+        None
+    }
+}
+
 pub mod any_rejections {
     //! This module hosts enums, up to size 8, which implement [`IntoResponse`] when their variants implement
     //! [`IntoResponse`].
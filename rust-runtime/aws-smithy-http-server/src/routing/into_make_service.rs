@@ -51,6 +51,10 @@ impl<S> IntoMakeService<S> {
     pub fn new(service: S) -> Self {
         Self { service }
     }
+
+    pub(crate) fn map<T>(self, f: impl FnOnce(S) -> T) -> IntoMakeService<T> {
+        IntoMakeService::new(f(self.service))
+    }
 }
 
 impl<S, T> Service<T> for IntoMakeService<S>
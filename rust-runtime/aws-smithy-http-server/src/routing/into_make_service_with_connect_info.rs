@@ -66,6 +66,10 @@ impl<S, C> IntoMakeServiceWithConnectInfo<S, C> {
             _connect_info: PhantomData,
         }
     }
+
+    pub(crate) fn map<T>(self, f: impl FnOnce(S) -> T) -> IntoMakeServiceWithConnectInfo<T, C> {
+        IntoMakeServiceWithConnectInfo::new(f(self.inner))
+    }
 }
 
 impl<S, C> fmt::Debug for IntoMakeServiceWithConnectInfo<S, C>
@@ -72,6 +72,15 @@ pub trait Router<B> {
 
     /// Matches a [`http::Request`] to a target [`Service`].
     fn match_route(&self, request: &http::Request<B>) -> Result<Self::Service, Self::Error>;
+
+    /// Matches a [`http::Request`] to a target [`Service`], and prepares the request to be sent
+    /// to it.
+    ///
+    /// This is what [`RoutingService`] calls. It defaults to [`Router::match_route`]; routers can
+    /// override it to insert extensions into the request.
+    fn match_request(&self, request: &mut http::Request<B>) -> Result<Self::Service, Self::Error> {
+        self.match_route(request)
+    }
 }
 
 /// A [`Service`] using the [`Router`] `R` to redirect messages to specific routes.
@@ -115,6 +124,11 @@ impl<R, P> RoutingService<R, P> {
         }
     }
 
+    /// Returns the [`Router`].
+    pub fn router(&self) -> &R {
+        &self.router
+    }
+
     /// Maps a [`Router`] using a closure.
     pub fn map<RNew, F>(self, f: F) -> RoutingService<RNew, P>
     where
@@ -190,8 +204,8 @@ where
         Poll::Ready(Ok(()))
     }
 
-    fn call(&mut self, req: http::Request<B>) -> Self::Future {
-        match self.router.match_route(&req) {
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        match self.router.match_request(&mut req) {
             // Successfully routed, use the routes `Service::call`.
             Ok(ok) => RoutingFuture::from_oneshot(ok.oneshot(req)),
             // Failed to route, use the `R::Error`s `IntoResponse<P>`.
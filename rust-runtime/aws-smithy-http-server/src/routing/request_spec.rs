@@ -3,11 +3,13 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use std::borrow::Cow;
+use std::{borrow::Cow, fmt};
 
 use http::Request;
 use regex::Regex;
 
+use crate::shape_id::ShapeId;
+
 #[derive(Debug, Clone)]
 pub enum PathSegment {
     Literal(String),
@@ -82,6 +84,7 @@ pub struct RequestSpec {
     method: http::Method,
     uri_spec: UriSpec,
     uri_path_regex: Regex,
+    shape_id: Option<ShapeId>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -96,6 +99,43 @@ pub(crate) enum Match {
     No,
 }
 
+/// The reason why a request does not match a [`RequestSpec`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch<'a> {
+    /// The path of the request does not match the URI pattern.
+    Path,
+    /// The URI pattern has query string literals, but the request has no query string.
+    MissingQuery,
+    /// The query string of the request could not be parsed.
+    InvalidQuery,
+    /// The query string of the request is missing a key of the URI pattern.
+    MissingQueryKey(&'a str),
+    /// A key of the query string of the request does not have the value of the URI pattern.
+    QueryValue {
+        /// The query string key.
+        key: &'a str,
+        /// The value that the URI pattern requires.
+        expected: &'a str,
+    },
+    /// The request matches the URI pattern, but uses a different HTTP method.
+    Method(&'a http::Method),
+}
+
+impl fmt::Display for Mismatch<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::Path => write!(f, "path does not match the URI pattern"),
+            Mismatch::MissingQuery => write!(f, "request has no query string"),
+            Mismatch::InvalidQuery => write!(f, "query string could not be parsed"),
+            Mismatch::MissingQueryKey(key) => write!(f, "query string is missing key `{key}`"),
+            Mismatch::QueryValue { key, expected } => {
+                write!(f, "query string key `{key}` does not have value `{expected}`")
+            }
+            Mismatch::Method(method) => write!(f, "method is not `{method}`"),
+        }
+    }
+}
+
 impl From<&PathSpec> for Regex {
     fn from(uri_path_spec: &PathSpec) -> Self {
         let sep = "/";
@@ -126,9 +166,26 @@ impl RequestSpec {
             method,
             uri_spec,
             uri_path_regex,
+            shape_id: None,
         }
     }
 
+    /// Sets the [`ShapeId`] of the operation that this `RequestSpec` routes to.
+    pub fn with_shape_id(mut self, shape_id: ShapeId) -> Self {
+        self.shape_id = Some(shape_id);
+        self
+    }
+
+    /// The [`ShapeId`] of the operation that this `RequestSpec` routes to, if it was set.
+    pub fn shape_id(&self) -> Option<&ShapeId> {
+        self.shape_id.as_ref()
+    }
+
+    /// The HTTP method that requests must use to match this `RequestSpec`.
+    pub fn method(&self) -> &http::Method {
+        &self.method
+    }
+
     /// A measure of how "important" a `RequestSpec` is. The more specific a `RequestSpec` is, the
     /// higher it ranks in importance. Specificity is measured by the number of segments plus the
     /// number of query string literals in its URI pattern, so `/{Bucket}/{Key}?query` is more
@@ -162,69 +219,116 @@ impl RequestSpec {
     }
 
     pub(crate) fn matches<B>(&self, req: &Request<B>) -> Match {
+        match self.check(req) {
+            Ok(()) => Match::Yes,
+            Err(Mismatch::Method(_)) => Match::MethodNotAllowed,
+            Err(_) => Match::No,
+        }
+    }
+
+    /// Checks whether `req` matches this `RequestSpec`, returning the reason why it doesn't.
+    ///
+    /// Requests that match the URI pattern but use the wrong HTTP method fail with
+    /// [`Mismatch::Method`]; `405 Method Not Allowed` should be returned in the response. All other
+    /// failures should result in `404 Not Found`.
+    pub(crate) fn check<B>(&self, req: &Request<B>) -> Result<(), Mismatch<'_>> {
         if let Some(_host_prefix) = &self.uri_spec.host_prefix {
             todo!("Look at host prefix");
         }
 
         if !self.uri_path_regex.is_match(req.uri().path()) {
-            return Match::No;
+            return Err(Mismatch::Path);
         }
 
-        if self.uri_spec.path_and_query.query_segments.0.is_empty() {
-            if self.method == req.method() {
-                return Match::Yes;
-            } else {
-                return Match::MethodNotAllowed;
-            }
-        }
+        if !self.uri_spec.path_and_query.query_segments.0.is_empty() {
+            let Some(query) = req.uri().query() else {
+                return Err(Mismatch::MissingQuery);
+            };
+
+            // We can't use `HashMap<Cow<str>, Cow<str>>` because a query string key can appear more
+            // than once e.g. `/?foo=bar&foo=baz`. We _could_ use a multiset e.g. the `hashbag`
+            // crate.
+            // We must deserialize into `Cow<str>`s because `serde_urlencoded` might need to
+            // return an owned allocated `String` if it has to percent-decode a slice of the query string.
+            let query_map = match serde_urlencoded::from_str::<Vec<(Cow<str>, Cow<str>)>>(query) {
+                Ok(query_map) => query_map,
+                Err(error) => {
+                    tracing::debug!(query, %error, "failed to deserialize query string");
+                    return Err(Mismatch::InvalidQuery);
+                }
+            };
 
-        match req.uri().query() {
-            Some(query) => {
-                // We can't use `HashMap<Cow<str>, Cow<str>>` because a query string key can appear more
-                // than once e.g. `/?foo=bar&foo=baz`. We _could_ use a multiset e.g. the `hashbag`
-                // crate.
-                // We must deserialize into `Cow<str>`s because `serde_urlencoded` might need to
-                // return an owned allocated `String` if it has to percent-decode a slice of the query string.
-                let res = serde_urlencoded::from_str::<Vec<(Cow<str>, Cow<str>)>>(query);
-
-                match res {
-                    Err(error) => {
-                        tracing::debug!(query, %error, "failed to deserialize query string");
-                        Match::No
+            for query_segment in self.uri_spec.path_and_query.query_segments.0.iter() {
+                match query_segment {
+                    QuerySegment::Key(key) => {
+                        if !query_map.iter().any(|(k, _v)| k == key) {
+                            return Err(Mismatch::MissingQueryKey(key));
+                        }
                     }
-                    Ok(query_map) => {
-                        for query_segment in self.uri_spec.path_and_query.query_segments.0.iter() {
-                            match query_segment {
-                                QuerySegment::Key(key) => {
-                                    if !query_map.iter().any(|(k, _v)| k == key) {
-                                        return Match::No;
-                                    }
-                                }
-                                QuerySegment::KeyValue(key, expected_value) => {
-                                    let mut it = query_map.iter().filter(|(k, _v)| k == key).peekable();
-                                    if it.peek().is_none() {
-                                        return Match::No;
-                                    }
-
-                                    // The query key appears more than once. All of its values must
-                                    // coincide and be equal to the expected value.
-                                    if it.any(|(_k, v)| v != expected_value) {
-                                        return Match::No;
-                                    }
-                                }
-                            }
+                    QuerySegment::KeyValue(key, expected_value) => {
+                        let mut it = query_map.iter().filter(|(k, _v)| k == key).peekable();
+                        if it.peek().is_none() {
+                            return Err(Mismatch::MissingQueryKey(key));
                         }
 
-                        if self.method == req.method() {
-                            Match::Yes
-                        } else {
-                            Match::MethodNotAllowed
+                        // The query key appears more than once. All of its values must
+                        // coincide and be equal to the expected value.
+                        if it.any(|(_k, v)| v != expected_value) {
+                            return Err(Mismatch::QueryValue {
+                                key,
+                                expected: expected_value,
+                            });
                         }
                     }
                 }
             }
-            None => Match::No,
         }
+
+        if self.method != req.method() {
+            return Err(Mismatch::Method(&self.method));
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if some request matches the URI patterns of both `self` and `other`.
+    ///
+    /// HTTP methods aren't compared. Greedy labels are assumed to match whole path segments.
+    pub(crate) fn overlaps(&self, other: &RequestSpec) -> bool {
+        let (path, other_path) = (
+            &self.uri_spec.path_and_query.path_segments.0[..],
+            &other.uri_spec.path_and_query.path_segments.0[..],
+        );
+        // A query string key can't have two different values in a matching request.
+        let query_overlaps = self.uri_spec.path_and_query.query_segments.0.iter().all(|segment| {
+            let QuerySegment::KeyValue(key, value) = segment else {
+                return true;
+            };
+            other.uri_spec.path_and_query.query_segments.0.iter().all(|other_segment| {
+                !matches!(other_segment, QuerySegment::KeyValue(other_key, other_value) if key == other_key && value != other_value)
+            })
+        });
+        query_overlaps && paths_overlap(path, other_path)
+    }
+
+    /// Returns `true` if every request that matches the URI pattern of `other` also matches the
+    /// URI pattern of `self`.
+    ///
+    /// HTTP methods aren't compared. Greedy labels are assumed to match whole path segments.
+    pub(crate) fn covers(&self, other: &RequestSpec) -> bool {
+        let (path, other_path) = (
+            &self.uri_spec.path_and_query.path_segments.0[..],
+            &other.uri_spec.path_and_query.path_segments.0[..],
+        );
+        let other_query = &other.uri_spec.path_and_query.query_segments.0;
+        let query_covers = self.uri_spec.path_and_query.query_segments.0.iter().all(|segment| match segment {
+            QuerySegment::Key(key) => other_query.iter().any(|other_segment| match other_segment {
+                QuerySegment::Key(other_key) | QuerySegment::KeyValue(other_key, _) => key == other_key,
+            }),
+            QuerySegment::KeyValue(key, value) => other_query.iter().any(|other_segment| {
+                matches!(other_segment, QuerySegment::KeyValue(other_key, other_value) if key == other_key && value == other_value)
+            }),
+        });
+        query_covers && path_covers(path, other_path)
     }
 
     // Helper function to build a `RequestSpec`.
@@ -247,6 +351,72 @@ impl RequestSpec {
     }
 }
 
+/// Returns `true` if some path matches both path patterns.
+fn paths_overlap(a: &[PathSegment], b: &[PathSegment]) -> bool {
+    match (a.split_first(), b.split_first()) {
+        (None, None) => true,
+        (None, Some(_)) | (Some(_), None) => false,
+        (Some((PathSegment::Greedy, a_rest)), Some((PathSegment::Greedy, b_rest))) => {
+            paths_overlap(a_rest, b_rest) || paths_overlap(a, b_rest) || paths_overlap(a_rest, b)
+        }
+        // A greedy label matches one or more segments.
+        (Some((PathSegment::Greedy, a_rest)), Some((_, b_rest))) => {
+            paths_overlap(a_rest, b_rest) || paths_overlap(a, b_rest)
+        }
+        (Some((_, a_rest)), Some((PathSegment::Greedy, b_rest))) => {
+            paths_overlap(a_rest, b_rest) || paths_overlap(a_rest, b)
+        }
+        (Some((PathSegment::Literal(a_literal), a_rest)), Some((PathSegment::Literal(b_literal), b_rest))) => {
+            a_literal == b_literal && paths_overlap(a_rest, b_rest)
+        }
+        (Some((_, a_rest)), Some((_, b_rest))) => paths_overlap(a_rest, b_rest),
+    }
+}
+
+/// Returns `true` if every path that matches path pattern `b` also matches path pattern `a`.
+fn path_covers(a: &[PathSegment], b: &[PathSegment]) -> bool {
+    match (a.split_first(), b.split_first()) {
+        (None, None) => true,
+        (None, Some(_)) | (Some(_), None) => false,
+        (Some((PathSegment::Greedy, a_rest)), Some((_, b_rest))) => {
+            path_covers(a_rest, b_rest) || path_covers(a, b_rest)
+        }
+        (Some((PathSegment::Label, a_rest)), Some((PathSegment::Label | PathSegment::Literal(_), b_rest))) => {
+            path_covers(a_rest, b_rest)
+        }
+        (Some((PathSegment::Literal(a_literal), a_rest)), Some((PathSegment::Literal(b_literal), b_rest))) => {
+            a_literal == b_literal && path_covers(a_rest, b_rest)
+        }
+        _ => false,
+    }
+}
+
+/// Formats the HTTP method and URI pattern, e.g. `GET /things/{label}/{greedy+}?key=value`.
+impl fmt::Display for RequestSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.method)?;
+        let path_segments = &self.uri_spec.path_and_query.path_segments.0;
+        if path_segments.is_empty() {
+            f.write_str("/")?;
+        }
+        for segment in path_segments {
+            match segment {
+                PathSegment::Literal(literal) => write!(f, "/{literal}")?,
+                PathSegment::Label => f.write_str("/{label}")?,
+                PathSegment::Greedy => f.write_str("/{greedy+}")?,
+            }
+        }
+        for (i, segment) in self.uri_spec.path_and_query.query_segments.0.iter().enumerate() {
+            f.write_str(if i == 0 { "?" } else { "&" })?;
+            match segment {
+                QuerySegment::Key(key) => f.write_str(key)?,
+                QuerySegment::KeyValue(key, value) => write!(f, "{key}={value}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -10,4 +10,12 @@
 /// [`crate::protocol::rest_json_1::runtime_error::RuntimeError::InternalFailure`] variant.
 pub struct InternalFailureException;
 
+/// A _protocol-agnostic_ type representing a request that was rejected because the client exceeded
+/// a rate limit, such as one enforced by [`RateLimitPlugin`](crate::plugin::RateLimitPlugin).
+/// This type is converted into protocol-specific error variants. For example, in the
+/// [`crate::protocol::rest_json_1`] protocol, it is converted to the
+/// [`crate::protocol::rest_json_1::runtime_error::RuntimeError::Throttling`] variant.
+#[derive(Clone, Debug, Default)]
+pub struct ThrottlingException;
+
 pub const INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE: &str = "invalid HTTP response for `RuntimeError`; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues";
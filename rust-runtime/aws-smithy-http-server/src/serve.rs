@@ -0,0 +1,510 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Serving a service with graceful shutdown.
+//!
+//! When a service behind a load balancer is asked to stop, it should stop receiving new requests
+//! before it stops accepting connections, and let the requests it is handling finish.
+//! [`GracefulShutdown`] coordinates these steps once its shutdown signal resolves:
+//!
+//! 1. Its [health check handler](GracefulShutdown::health_check_handler) starts reporting the
+//!    service as unhealthy, so that the load balancer stops routing requests to it.
+//! 2. The service keeps serving requests for the [drain delay](GracefulShutdown::with_drain_delay),
+//!    to give the load balancer time to notice.
+//! 3. The server stops accepting connections, and closes idle ones.
+//! 4. In-flight requests are given until the [timeout](GracefulShutdown::with_timeout) to finish,
+//!    after which [`GracefulShutdown::serve`] returns without waiting for them.
+//!
+//! hyper serves each connection on its own task, so connections that are still open when the
+//! timeout is reached aren't closed by returning: they are dropped when the Tokio runtime shuts
+//! down, which happens when `main` returns if it runs on `#[tokio::main]`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use std::{convert::Infallible, net::SocketAddr, time::Duration};
+//! # use aws_smithy_http_server::{body::BoxBody, layer::alb_health_check::AlbHealthCheckLayer, routing::IntoMakeService};
+//! use aws_smithy_http_server::serve::{shutdown_signal, GracefulShutdown};
+//! use tower::Layer;
+//!
+//! # async fn handle(_req: http::Request<hyper::Body>) -> Result<http::Response<BoxBody>, Infallible> {
+//! #     Ok(http::Response::new(aws_smithy_http_server::body::to_boxed("Hello, world!")))
+//! # }
+//! # async fn run() -> Result<(), hyper::Error> {
+//! let shutdown = GracefulShutdown::new()
+//!     .with_drain_delay(Duration::from_secs(15))
+//!     .with_timeout(Duration::from_secs(30));
+//!
+//! // Report the service as unhealthy at `/ping` once shutdown starts.
+//! let ping_layer = AlbHealthCheckLayer::from_handler("/ping", shutdown.health_check_handler());
+//! # let app = tower::service_fn(handle);
+//! let app = ping_layer.layer(app);
+//!
+//! let addr: SocketAddr = "127.0.0.1:13734".parse().unwrap();
+//! // Serve until the process receives `SIGTERM` or Ctrl+C, then shut down gracefully.
+//! shutdown
+//!     .serve(hyper::Server::bind(&addr), IntoMakeService::new(app), shutdown_signal())
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    fmt,
+    future::{ready, Future, Ready},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use http::StatusCode;
+use http_body::Body as HttpBody;
+use hyper::{server::accept::Accept, Body};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    sync::oneshot,
+};
+use tower::Service;
+
+use crate::{
+    error::BoxError,
+    routing::{Connected, IntoMakeService, IntoMakeServiceWithConnectInfo},
+};
+
+/// The default time that in-flight requests are given to finish once the server stops accepting
+/// connections.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default)]
+struct State {
+    draining: AtomicBool,
+    in_flight: AtomicUsize,
+}
+
+/// Serves a service until a shutdown signal resolves, and then shuts it down gracefully.
+///
+/// See the [module](crate::serve) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct GracefulShutdown {
+    state: Arc<State>,
+    drain_delay: Duration,
+    timeout: Option<Duration>,
+}
+
+impl Default for GracefulShutdown {
+    fn default() -> Self {
+        Self {
+            state: Default::default(),
+            drain_delay: Duration::ZERO,
+            timeout: Some(DEFAULT_SHUTDOWN_TIMEOUT),
+        }
+    }
+}
+
+impl GracefulShutdown {
+    /// Creates a new `GracefulShutdown` without a drain delay, which gives in-flight requests
+    /// [`DEFAULT_SHUTDOWN_TIMEOUT`] to finish.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how long the service keeps accepting connections after the shutdown signal resolves,
+    /// while its health check reports it as unhealthy.
+    ///
+    /// This should be long enough for the load balancer to notice the failing health check.
+    pub fn with_drain_delay(mut self, drain_delay: Duration) -> Self {
+        self.drain_delay = drain_delay;
+        self
+    }
+
+    /// Sets how long in-flight requests are given to finish once the server stops accepting
+    /// connections.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Waits for in-flight requests to finish for as long as they take.
+    pub fn without_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// Returns `true` once the shutdown signal has resolved.
+    pub fn is_draining(&self) -> bool {
+        self.state.draining.load(Ordering::Acquire)
+    }
+
+    /// Returns a counter of the requests that are being handled by the served service.
+    pub fn in_flight_requests(&self) -> InFlightRequests {
+        InFlightRequests {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Returns a health check handler, to be used with
+    /// [`AlbHealthCheckLayer::from_handler`](crate::layer::alb_health_check::AlbHealthCheckLayer::from_handler),
+    /// which responds with `200 OK` until the shutdown signal resolves, and with
+    /// `503 Service Unavailable` afterwards.
+    pub fn health_check_handler(&self) -> impl Fn(http::Request<Body>) -> Ready<StatusCode> + Clone + Send + Sync {
+        let state = self.state.clone();
+        move |_request| {
+            if state.draining.load(Ordering::Acquire) {
+                ready(StatusCode::SERVICE_UNAVAILABLE)
+            } else {
+                ready(StatusCode::OK)
+            }
+        }
+    }
+
+    /// Serves `make_service` with `builder` until `signal` resolves, and then shuts it down
+    /// gracefully.
+    ///
+    /// Returns an error if the server fails. Reaching the timeout isn't an error: it is logged,
+    /// and this returns without waiting for the remaining connections, which keep being served
+    /// until the Tokio runtime shuts down.
+    pub async fn serve<I, S, ResBody>(
+        self,
+        builder: hyper::server::Builder<I>,
+        make_service: IntoMakeService<S>,
+        signal: impl Future<Output = ()>,
+    ) -> Result<(), hyper::Error>
+    where
+        I: Accept,
+        I::Error: Into<BoxError>,
+        I::Conn: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+        S: Service<http::Request<Body>, Response = http::Response<ResBody>> + Clone + Send + 'static,
+        S::Error: Into<BoxError>,
+        S::Future: Send + 'static,
+        ResBody: HttpBody + Send + 'static,
+        ResBody::Data: Send,
+        ResBody::Error: Into<BoxError>,
+    {
+        let state = self.state.clone();
+        let make_service = make_service.map(|inner| InFlightService { inner, state });
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        let server = builder.serve(make_service).with_graceful_shutdown(async {
+            let _ = shutdown_rx.await;
+        });
+        self.drain(server, shutdown, signal).await
+    }
+
+    /// Serves `make_service` with `builder` until `signal` resolves, and then shuts it down
+    /// gracefully.
+    ///
+    /// This is the same as [`GracefulShutdown::serve`], for services that use
+    /// [`ConnectInfo`](crate::request::connect_info::ConnectInfo).
+    pub async fn serve_with_connect_info<I, S, C, ResBody>(
+        self,
+        builder: hyper::server::Builder<I>,
+        make_service: IntoMakeServiceWithConnectInfo<S, C>,
+        signal: impl Future<Output = ()>,
+    ) -> Result<(), hyper::Error>
+    where
+        I: Accept,
+        I::Error: Into<BoxError>,
+        I::Conn: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+        S: Service<http::Request<Body>, Response = http::Response<ResBody>> + Clone + Send + 'static,
+        S::Error: Into<BoxError>,
+        S::Future: Send + 'static,
+        C: for<'a> Connected<&'a I::Conn> + Send + Sync + 'static,
+        ResBody: HttpBody + Send + 'static,
+        ResBody::Data: Send,
+        ResBody::Error: Into<BoxError>,
+    {
+        let state = self.state.clone();
+        let make_service = make_service.map(|inner| InFlightService { inner, state });
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        let server = builder.serve(make_service).with_graceful_shutdown(async {
+            let _ = shutdown_rx.await;
+        });
+        self.drain(server, shutdown, signal).await
+    }
+
+    async fn drain(
+        self,
+        server: impl Future<Output = Result<(), hyper::Error>>,
+        shutdown: oneshot::Sender<()>,
+        signal: impl Future<Output = ()>,
+    ) -> Result<(), hyper::Error> {
+        tokio::pin!(server);
+        tokio::select! {
+            result = &mut server => return result,
+            _ = signal => {}
+        }
+
+        tracing::info!("shutdown signal received, draining");
+        self.state.draining.store(true, Ordering::Release);
+        if !self.drain_delay.is_zero() {
+            tokio::select! {
+                result = &mut server => return result,
+                _ = tokio::time::sleep(self.drain_delay) => {}
+            }
+        }
+
+        tracing::info!(
+            in_flight_requests = self.state.in_flight.load(Ordering::Acquire),
+            "no longer accepting connections, waiting for in-flight requests"
+        );
+        let _ = shutdown.send(());
+        let Some(timeout) = self.timeout else {
+            return server.await;
+        };
+        match tokio::time::timeout(timeout, server).await {
+            Ok(result) => result,
+            Err(_) => {
+                tracing::warn!(
+                    in_flight_requests = self.state.in_flight.load(Ordering::Acquire),
+                    "graceful shutdown timed out, no longer waiting for in-flight requests"
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A counter of the requests that are being handled by a service served by [`GracefulShutdown`].
+#[derive(Clone)]
+pub struct InFlightRequests {
+    state: Arc<State>,
+}
+
+impl InFlightRequests {
+    /// Returns the number of requests whose response hasn't been produced yet.
+    ///
+    /// Requests are counted until the service returns their response, which may be before the
+    /// response body has been sent.
+    pub fn count(&self) -> usize {
+        self.state.in_flight.load(Ordering::Acquire)
+    }
+}
+
+impl fmt::Debug for InFlightRequests {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InFlightRequests")
+            .field("count", &self.count())
+            .finish()
+    }
+}
+
+/// Counts the requests that are being handled by `inner`.
+#[derive(Clone)]
+struct InFlightService<S> {
+    inner: S,
+    state: Arc<State>,
+}
+
+impl<S, B> Service<http::Request<B>> for InFlightService<S>
+where
+    S: Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = InFlightFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        self.state.in_flight.fetch_add(1, Ordering::AcqRel);
+        InFlightFuture {
+            inner: self.inner.call(request),
+            _guard: InFlightGuard {
+                state: self.state.clone(),
+            },
+        }
+    }
+}
+
+/// Decrements the in-flight request counter when dropped.
+struct InFlightGuard {
+    state: Arc<State>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.state.in_flight.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+pin_project_lite::pin_project! {
+    struct InFlightFuture<F> {
+        #[pin]
+        inner: F,
+        _guard: InFlightGuard,
+    }
+}
+
+impl<F: Future> Future for InFlightFuture<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().inner.poll(cx)
+    }
+}
+
+/// Resolves when the process receives `SIGTERM` or Ctrl+C, whichever comes first.
+///
+/// Only Ctrl+C is handled on platforms other than Unix.
+///
+/// # Panics
+///
+/// Panics if the signal handlers can't be installed.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install the Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install the SIGTERM handler")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::Infallible, net::SocketAddr};
+
+    use hyper::server::conn::AddrIncoming;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+        sync::Notify,
+        task::JoinHandle,
+    };
+
+    use super::*;
+    use crate::body::BoxBody;
+
+    /// Serves a service whose responses wait for `release` to be notified, and returns the address
+    /// it is bound to.
+    fn serve(
+        shutdown: GracefulShutdown,
+        release: Arc<Notify>,
+        signal: oneshot::Receiver<()>,
+    ) -> (SocketAddr, JoinHandle<Result<(), hyper::Error>>) {
+        let incoming = AddrIncoming::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+        let addr = incoming.local_addr();
+        let service = tower::service_fn(move |_request: http::Request<Body>| {
+            let release = release.clone();
+            async move {
+                release.notified().await;
+                Ok::<_, Infallible>(http::Response::new(crate::body::to_boxed("done")))
+            }
+        });
+        let make_service = IntoMakeService::new(service);
+        let signal = async {
+            let _ = signal.await;
+        };
+        let server = tokio::spawn(shutdown.serve(hyper::Server::builder(incoming), make_service, signal));
+        (addr, server)
+    }
+
+    async fn send_request(addr: SocketAddr) -> TcpStream {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        stream
+    }
+
+    async fn wait_for(in_flight_requests: &InFlightRequests, count: usize) {
+        while in_flight_requests.count() != count {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn in_flight_requests_finish_before_shutdown() {
+        let shutdown = GracefulShutdown::new();
+        let in_flight_requests = shutdown.in_flight_requests();
+        let health_check = shutdown.health_check_handler();
+        let release = Arc::new(Notify::new());
+        let (signal, signal_rx) = oneshot::channel();
+        let (addr, server) = serve(shutdown, release.clone(), signal_rx);
+
+        let mut stream = send_request(addr).await;
+        wait_for(&in_flight_requests, 1).await;
+        assert_eq!(StatusCode::OK, health_check(http::Request::new(Body::empty())).await);
+
+        signal.send(()).unwrap();
+        while health_check(http::Request::new(Body::empty())).await == StatusCode::OK {
+            tokio::task::yield_now().await;
+        }
+        assert!(!server.is_finished());
+
+        release.notify_one();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.ends_with("done"), "{response}");
+        server.await.unwrap().unwrap();
+        assert_eq!(0, in_flight_requests.count());
+    }
+
+    #[tokio::test]
+    async fn shutdown_times_out() {
+        let shutdown = GracefulShutdown::new().with_timeout(Duration::from_millis(10));
+        let in_flight_requests = shutdown.in_flight_requests();
+        let (signal, signal_rx) = oneshot::channel();
+        let (addr, server) = serve(shutdown, Arc::new(Notify::new()), signal_rx);
+
+        let _stream = send_request(addr).await;
+        wait_for(&in_flight_requests, 1).await;
+        signal.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn connections_are_accepted_during_the_drain_delay() {
+        let shutdown = GracefulShutdown::new().with_drain_delay(Duration::from_secs(60));
+        let in_flight_requests = shutdown.in_flight_requests();
+        let draining = shutdown.clone();
+        let (signal, signal_rx) = oneshot::channel();
+        let (addr, server) = serve(shutdown, Arc::new(Notify::new()), signal_rx);
+
+        signal.send(()).unwrap();
+        while !draining.is_draining() {
+            tokio::task::yield_now().await;
+        }
+        let _stream = send_request(addr).await;
+        wait_for(&in_flight_requests, 1).await;
+        server.abort();
+    }
+
+    #[test]
+    fn in_flight_service_counts_requests() {
+        let state = Arc::new(State::default());
+        let mut service = InFlightService {
+            inner: tower::service_fn(|_request: http::Request<Body>| async {
+                Ok::<_, Infallible>(http::Response::new(crate::body::empty()))
+            }),
+            state: state.clone(),
+        };
+        let future = service.call(http::Request::new(Body::empty()));
+        assert_eq!(1, state.in_flight.load(Ordering::Acquire));
+        let response: http::Response<BoxBody> = futures_util::FutureExt::now_or_never(future).unwrap().unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!(0, state.in_flight.load(Ordering::Acquire));
+    }
+}
@@ -0,0 +1,102 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_smithy_json::serialize::{ByteCounter, JsonObjectWriter, JsonOutput};
+use aws_smithy_types::Number;
+use bytes::{Bytes, BytesMut};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+/// An item of a batch write, similar to those of DynamoDB's `BatchWriteItem`.
+struct Item {
+    id: String,
+    name: String,
+    description: String,
+    count: u64,
+    price: f64,
+    tags: Vec<String>,
+}
+
+fn items(count: usize) -> Vec<Item> {
+    (0..count)
+        .map(|i| Item {
+            id: format!("item-{i:08}"),
+            name: format!("Item number {i}"),
+            description: "A \"quoted\" description\nspanning two lines".to_string(),
+            count: i as u64 * 31,
+            price: i as f64 * 1.25,
+            tags: vec![
+                "alpha".to_string(),
+                "beta".to_string(),
+                format!("tag-{}", i % 7),
+            ],
+        })
+        .collect()
+}
+
+/// Serializes `items` the way generated serializers do.
+fn write_items<O: JsonOutput>(output: &mut O, items: &[Item]) {
+    let mut object = JsonObjectWriter::new(output);
+    let mut array = object.key("Items").start_array();
+    for item in items {
+        let mut item_object = array.value().start_object();
+        item_object.key("Id").string(&item.id);
+        item_object.key("Name").string(&item.name);
+        item_object.key("Description").string(&item.description);
+        item_object.key("Count").number(Number::PosInt(item.count));
+        item_object.key("Price").number(Number::Float(item.price));
+        let mut tags = item_object.key("Tags").start_array();
+        for tag in &item.tags {
+            tags.value().string(tag);
+        }
+        tags.finish();
+        item_object.finish();
+    }
+    array.finish();
+    object.finish();
+}
+
+fn bench_serialize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Serialize");
+
+    for count in [1, 25, 1_000] {
+        let items = &items(count);
+        let mut counter = ByteCounter::new();
+        write_items(&mut counter, items);
+        group.throughput(Throughput::Bytes(counter.count() as u64));
+
+        // The current path: serialize into a `String`, then convert it into the body's `Bytes`.
+        group.bench_with_input(BenchmarkId::new("string", count), items, |b, items| {
+            b.iter(|| {
+                let mut output = String::new();
+                write_items(&mut output, items);
+                Bytes::from(output)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("bytes_mut", count), items, |b, items| {
+            b.iter(|| {
+                let mut output = BytesMut::new();
+                write_items(&mut output, items);
+                output.freeze()
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("bytes_mut_precomputed_capacity", count),
+            items,
+            |b, items| {
+                b.iter(|| {
+                    let mut counter = ByteCounter::new();
+                    write_items(&mut counter, items);
+                    let mut output = BytesMut::with_capacity(counter.count());
+                    write_items(&mut output, items);
+                    output.freeze()
+                })
+            },
+        );
+    }
+    group.finish()
+}
+
+criterion_group!(benches, bench_serialize);
+criterion_main!(benches);
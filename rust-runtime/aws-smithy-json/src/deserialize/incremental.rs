@@ -0,0 +1,792 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Incremental JSON tokenization for payloads that arrive in chunks.
+//!
+//! [`json_token_iter`](crate::deserialize::json_token_iter) requires the entire document as a
+//! contiguous slice. [`JsonTokenReader`] instead is fed the document chunk by chunk, and only
+//! buffers the bytes of the token (or value) it is currently reading, so a large document, such
+//! as a response with a long list, can be deserialized without holding all of it in memory.
+//!
+//! Values read with [`JsonTokenReader::next_value`] are complete JSON documents, which can be
+//! handed to [`json_token_iter`](crate::deserialize::json_token_iter) and the deserializers
+//! built on top of it:
+//!
+//! ```
+//! use aws_smithy_json::deserialize::incremental::{JsonTokenReader, Pull};
+//! use aws_smithy_json::deserialize::token::expect_string_or_null;
+//! use aws_smithy_json::deserialize::{json_token_iter, Token};
+//!
+//! # fn main() -> Result<(), aws_smithy_json::deserialize::error::DeserializeError> {
+//! let mut chunks = [&b"{\"Names\": [\"a\", \"b"[..], b"c\", \"d\"]}"].into_iter();
+//! let mut reader = JsonTokenReader::new();
+//! let (mut in_names, mut names) = (false, Vec::new());
+//! loop {
+//!     let needs_input = if in_names {
+//!         match reader.next_value()? {
+//!             Pull::Ready(Some(value)) => {
+//!                 // Deserialize one element at a time.
+//!                 let mut tokens = json_token_iter(value).peekable();
+//!                 let name = expect_string_or_null(tokens.next())?.unwrap();
+//!                 names.push(name.to_unescaped().unwrap().into_owned());
+//!                 false
+//!             }
+//!             Pull::Ready(None) => {
+//!                 in_names = false;
+//!                 false
+//!             }
+//!             Pull::NeedMoreInput => true,
+//!         }
+//!     } else {
+//!         match reader.next_token()? {
+//!             Pull::Ready(Some(Token::StartArray { .. })) => {
+//!                 in_names = true;
+//!                 false
+//!             }
+//!             Pull::Ready(Some(_)) => false,
+//!             Pull::Ready(None) => break,
+//!             Pull::NeedMoreInput => true,
+//!         }
+//!     };
+//!     if needs_input {
+//!         match chunks.next() {
+//!             Some(chunk) => reader.push(chunk),
+//!             None => reader.finish(),
+//!         }
+//!     }
+//! }
+//! assert_eq!(vec!["a", "bc", "d"], names);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`JsonTokenReader::next_token_from`] and [`JsonTokenReader::next_value_from`] do the same,
+//! pulling chunks from a [`ByteStream`] as they are needed.
+
+use crate::deserialize::error::{DeserializeError as Error, DeserializeErrorKind as ErrorKind};
+use crate::deserialize::{EscapedStr, JsonTokenIterator, Offset, State, Token};
+use aws_smithy_types::byte_stream::ByteStream;
+use std::ops::Range;
+
+/// The result of reading from a [`JsonTokenReader`].
+#[derive(Debug, PartialEq)]
+pub enum Pull<T> {
+    /// The next item was read.
+    Ready(T),
+    /// The buffered input ends in the middle of the next item. Another chunk must be pushed with
+    /// [`JsonTokenReader::push`], or the input marked as finished with
+    /// [`JsonTokenReader::finish`], before it can be read.
+    NeedMoreInput,
+}
+
+/// A token that was read, but not yet returned.
+///
+/// Strings are held as ranges of the buffer so that this doesn't borrow the reader.
+#[derive(Debug)]
+enum Scanned {
+    End,
+    Token(Token<'static>),
+    ObjectKey { offset: Offset, key: Range<usize> },
+    ValueString { offset: Offset, value: Range<usize> },
+}
+
+/// A value being read by [`JsonTokenReader::next_value`].
+#[derive(Debug)]
+struct PartialValue {
+    /// Start of the value in the buffer.
+    start: usize,
+    /// Number of arrays and objects opened but not yet closed.
+    depth: usize,
+}
+
+/// How far a token that the buffered input ends in the middle of has been read.
+///
+/// This lets the reader wait until the bytes pushed could complete the token, rather than
+/// tokenizing it again from its start after every chunk.
+#[derive(Debug)]
+struct PartialToken {
+    /// Position in the buffer of the next byte to examine.
+    cursor: usize,
+    kind: PartialTokenKind,
+}
+
+#[derive(Debug)]
+enum PartialTokenKind {
+    /// Whitespace and separators before the next token.
+    Space,
+    /// A string. `escaped` is true if the last byte examined started an escape sequence.
+    String {
+        escaped: bool,
+    },
+    Number,
+}
+
+impl PartialToken {
+    /// Returns the partial token at `index`, or `None` if the token is short enough to simply be
+    /// tokenized again, or its end is already buffered.
+    fn starting_at(buffer: &[u8], index: usize) -> Option<Self> {
+        let start = buffer[index..]
+            .iter()
+            .position(|byte| !is_separator(*byte))
+            .map_or(buffer.len(), |position| index + position);
+        let (cursor, kind) = match buffer.get(start) {
+            None => (start, PartialTokenKind::Space),
+            Some(b'"') => (start + 1, PartialTokenKind::String { escaped: false }),
+            Some(b'-' | b'0'..=b'9') => (start, PartialTokenKind::Number),
+            Some(_) => return None,
+        };
+        let mut partial = Self { cursor, kind };
+        (!partial.advance(buffer)).then_some(partial)
+    }
+
+    /// Examines the bytes pushed since the last call, and returns true if the token may now be
+    /// complete.
+    fn advance(&mut self, buffer: &[u8]) -> bool {
+        while let Some(&byte) = buffer.get(self.cursor) {
+            self.cursor += 1;
+            let ends = match &mut self.kind {
+                PartialTokenKind::Space => !is_separator(byte),
+                PartialTokenKind::String {
+                    escaped: escaped @ true,
+                } => {
+                    *escaped = false;
+                    false
+                }
+                PartialTokenKind::String { escaped } => {
+                    *escaped = byte == b'\\';
+                    byte == b'"'
+                }
+                PartialTokenKind::Number => !is_number_byte(byte),
+            };
+            if ends {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+fn is_separator(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\r' | b'\n' | b',' | b':')
+}
+
+fn is_number_byte(byte: u8) -> bool {
+    matches!(byte, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E')
+}
+
+/// Pull-based JSON tokenizer that is fed the document in chunks.
+///
+/// The reader yields the same tokens as [`json_token_iter`](crate::deserialize::json_token_iter)
+/// would for the concatenation of all pushed chunks, with offsets relative to the start of the
+/// document. When the buffered input ends partway through a token, [`Pull::NeedMoreInput`] is
+/// returned, and reading resumes where it left off once more input is pushed.
+///
+/// Bytes are dropped from the buffer once the tokens holding them have been read, so memory use
+/// is bounded by the size of the pushed chunks plus that of the largest token, or of the largest
+/// value read with [`next_value`](Self::next_value). This only holds if chunks are pushed as they
+/// are needed, rather than all at once.
+#[derive(Debug)]
+pub struct JsonTokenReader {
+    buffer: Vec<u8>,
+    /// Position of the next unread byte in `buffer`.
+    index: usize,
+    /// Offset of `buffer[0]` in the document.
+    base: usize,
+    state_stack: Vec<State>,
+    finished: bool,
+    failed: bool,
+    scanned: Option<Scanned>,
+    value: Option<PartialValue>,
+    partial: Option<PartialToken>,
+}
+
+impl Default for JsonTokenReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsonTokenReader {
+    /// Creates a reader with no input.
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::new(),
+            index: 0,
+            base: 0,
+            state_stack: vec![State::Initial],
+            finished: false,
+            failed: false,
+            scanned: None,
+            value: None,
+            partial: None,
+        }
+    }
+
+    /// Appends the next chunk of the document to the input.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the input was already marked as finished.
+    pub fn push(&mut self, chunk: &[u8]) {
+        assert!(
+            !self.finished,
+            "cannot push input after `finish` was called"
+        );
+        // Drop the bytes that have been read, keeping those of a value that is still being read.
+        let keep_from = match &self.value {
+            Some(value) => value.start,
+            None => self.index,
+        };
+        if keep_from > 0 {
+            self.buffer.drain(..keep_from);
+            self.base += keep_from;
+            self.index -= keep_from;
+            if let Some(value) = &mut self.value {
+                value.start -= keep_from;
+            }
+            if let Some(partial) = &mut self.partial {
+                partial.cursor -= keep_from;
+            }
+        }
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Marks the end of the input. Tokens still buffered can then be read.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Returns true if [`finish`](Self::finish) was called.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Reads the next token.
+    ///
+    /// Returns `Ready(None)` once the input is finished and all of its tokens have been read,
+    /// mirroring the end of [`json_token_iter`](crate::deserialize::json_token_iter).
+    pub fn next_token(&mut self) -> Result<Pull<Option<Token<'_>>>, Error> {
+        if let Pull::NeedMoreInput = self.scan()? {
+            return Ok(Pull::NeedMoreInput);
+        }
+        Ok(Pull::Ready(self.take_scanned()))
+    }
+
+    /// Reads the next JSON value as a complete document, which can be tokenized with
+    /// [`json_token_iter`](crate::deserialize::json_token_iter).
+    ///
+    /// This is meant to be called where a value is expected: at the start of the document, for
+    /// the elements of an array, or after an object key. If the enclosing array or object ends
+    /// instead, its end token is consumed and `Ready(None)` is returned. `Ready(None)` is also
+    /// returned once the input is finished and has been read.
+    ///
+    /// When more input is needed, the part of the value read so far is kept, and reading
+    /// continues with it on the next call.
+    pub fn next_value(&mut self) -> Result<Pull<Option<&[u8]>>, Error> {
+        Ok(match self.scan_value()? {
+            Pull::Ready(range) => Pull::Ready(range.map(|range| &self.buffer[range])),
+            Pull::NeedMoreInput => Pull::NeedMoreInput,
+        })
+    }
+
+    /// Reads the next token, pulling chunks from `stream` as they are needed.
+    ///
+    /// The input is marked as finished once `stream` is exhausted.
+    pub async fn next_token_from(
+        &mut self,
+        stream: &mut ByteStream,
+    ) -> Result<Option<Token<'_>>, Error> {
+        while let Pull::NeedMoreInput = self.scan()? {
+            self.pull(stream).await?;
+        }
+        Ok(self.take_scanned())
+    }
+
+    /// Reads the next value with [`next_value`](Self::next_value), pulling chunks from `stream`
+    /// as they are needed.
+    ///
+    /// The input is marked as finished once `stream` is exhausted.
+    pub async fn next_value_from(
+        &mut self,
+        stream: &mut ByteStream,
+    ) -> Result<Option<&[u8]>, Error> {
+        loop {
+            match self.scan_value()? {
+                Pull::Ready(range) => return Ok(range.map(|range| &self.buffer[range])),
+                Pull::NeedMoreInput => self.pull(stream).await?,
+            }
+        }
+    }
+
+    /// Pushes the next chunk of `stream`, or finishes the input if there is none.
+    async fn pull(&mut self, stream: &mut ByteStream) -> Result<(), Error> {
+        match stream.next().await {
+            Some(Ok(chunk)) => self.push(&chunk),
+            Some(Err(err)) => {
+                self.failed = true;
+                return Err(Error::custom_source(
+                    "failed to read the next chunk of the JSON document",
+                    err,
+                ));
+            }
+            None => self.finish(),
+        }
+        Ok(())
+    }
+
+    /// Reads tokens until the next value is complete, and returns its range in the buffer.
+    fn scan_value(&mut self) -> Result<Pull<Option<Range<usize>>>, Error> {
+        loop {
+            if let Pull::NeedMoreInput = self.scan()? {
+                return Ok(Pull::NeedMoreInput);
+            }
+            let scanned = self
+                .scanned
+                .take()
+                .expect("`scan` returned `Ready`, so a token is available");
+            let offset = match &scanned {
+                Scanned::End if self.value.is_some() => {
+                    return Err(self.fail(ErrorKind::UnexpectedEos, self.base + self.index));
+                }
+                Scanned::End => return Ok(Pull::Ready(None)),
+                Scanned::ObjectKey { offset, .. } | Scanned::ValueString { offset, .. } => *offset,
+                Scanned::Token(token) => token.offset(),
+            };
+            let (opens, closes) = match &scanned {
+                Scanned::Token(Token::StartArray { .. } | Token::StartObject { .. }) => {
+                    (true, false)
+                }
+                Scanned::Token(Token::EndArray { .. } | Token::EndObject { .. }) => (false, true),
+                _ => (false, false),
+            };
+            match &mut self.value {
+                // The enclosing array or object ended.
+                None if closes => return Ok(Pull::Ready(None)),
+                None if matches!(scanned, Scanned::ObjectKey { .. }) => {
+                    return Err(self.fail(
+                        ErrorKind::Custom {
+                            message: "expected a value but found an object key".into(),
+                            source: None,
+                        },
+                        offset.0,
+                    ));
+                }
+                None if opens => {
+                    self.value = Some(PartialValue {
+                        start: offset.0 - self.base,
+                        depth: 1,
+                    })
+                }
+                None => return Ok(Pull::Ready(Some(offset.0 - self.base..self.index))),
+                Some(value) if opens => value.depth += 1,
+                Some(value) if closes => {
+                    value.depth -= 1;
+                    if value.depth == 0 {
+                        let start = value.start;
+                        self.value = None;
+                        return Ok(Pull::Ready(Some(start..self.index)));
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    /// Reads the next token into `self.scanned`, unless there is one already.
+    fn scan(&mut self) -> Result<Pull<()>, Error> {
+        if self.failed {
+            return Err(Error::custom(
+                "the JSON document was invalid, or could not be read",
+            ));
+        }
+        if self.scanned.is_some() {
+            return Ok(Pull::Ready(()));
+        }
+        if let Some(partial) = &mut self.partial {
+            if !self.finished && !partial.advance(&self.buffer) {
+                return Ok(Pull::NeedMoreInput);
+            }
+            self.partial = None;
+        }
+
+        // A single `JsonTokenIterator::next` only ever changes the top of the state stack, pushes a
+        // state, or pops one. Pops only happen on success, so the stack can be restored from its
+        // length and top.
+        let (stack_len, stack_top) = (self.state_stack.len(), *self.state_stack.last().unwrap());
+        let mut iter = JsonTokenIterator {
+            input: &self.buffer,
+            index: self.index,
+            state_stack: std::mem::take(&mut self.state_stack),
+        };
+        let result = iter.next();
+        let (index, mut state_stack) = (iter.index, iter.state_stack);
+        let at_end_of_input = index == self.buffer.len();
+        let restore = |state_stack: &mut Vec<State>| {
+            state_stack.truncate(stack_len);
+            state_stack[stack_len - 1] = stack_top;
+        };
+
+        let scanned = match result {
+            None if !self.finished => return Ok(self.need_more_input(state_stack)),
+            None => Scanned::End,
+            Some(Err(err)) if matches!(err.kind, ErrorKind::UnexpectedEos) && !self.finished => {
+                restore(&mut state_stack);
+                return Ok(self.need_more_input(state_stack));
+            }
+            // A number cut off by the end of the buffer, such as `-` or `1e`, may be completed by the
+            // next chunk.
+            Some(Err(err))
+                if matches!(err.kind, ErrorKind::InvalidNumber)
+                    && !self.finished
+                    && err.offset.is_some_and(|offset| {
+                        self.buffer[offset..]
+                            .iter()
+                            .all(|byte| is_number_byte(*byte))
+                    }) =>
+            {
+                restore(&mut state_stack);
+                return Ok(self.need_more_input(state_stack));
+            }
+            Some(Err(mut err)) => {
+                self.state_stack = state_stack;
+                self.failed = true;
+                err.offset = err.offset.map(|offset| self.base + offset);
+                return Err(err);
+            }
+            Some(Ok(token)) => match token {
+                // These may continue in the next chunk, or be followed by an invalid trailer.
+                Token::ValueBool { .. } | Token::ValueNull { .. } | Token::ValueNumber { .. }
+                    if at_end_of_input && !self.finished =>
+                {
+                    restore(&mut state_stack);
+                    return Ok(self.need_more_input(state_stack));
+                }
+                Token::ObjectKey { offset, .. } => Scanned::ObjectKey {
+                    offset: Offset(self.base + offset.0),
+                    key: offset.0 + 1..index - 1,
+                },
+                Token::ValueString { offset, .. } => Scanned::ValueString {
+                    offset: Offset(self.base + offset.0),
+                    value: offset.0 + 1..index - 1,
+                },
+                token => Scanned::Token(rebase(token, self.base)),
+            },
+        };
+        self.index = index;
+        self.state_stack = state_stack;
+        self.scanned = Some(scanned);
+        Ok(Pull::Ready(()))
+    }
+
+    /// Records how far the token that the buffered input ends in the middle of was read.
+    fn need_more_input(&mut self, state_stack: Vec<State>) -> Pull<()> {
+        self.state_stack = state_stack;
+        self.partial = PartialToken::starting_at(&self.buffer, self.index);
+        Pull::NeedMoreInput
+    }
+
+    /// Returns the token read by `scan`.
+    fn take_scanned(&mut self) -> Option<Token<'_>> {
+        match self
+            .scanned
+            .take()
+            .expect("`scan` returned `Ready`, so a token is available")
+        {
+            Scanned::End => None,
+            Scanned::Token(token) => Some(token),
+            Scanned::ObjectKey { offset, key } => Some(Token::ObjectKey {
+                offset,
+                key: EscapedStr::new(self.buffer_str(key)),
+            }),
+            Scanned::ValueString { offset, value } => Some(Token::ValueString {
+                offset,
+                value: EscapedStr::new(self.buffer_str(value)),
+            }),
+        }
+    }
+
+    fn buffer_str(&self, range: Range<usize>) -> &str {
+        let bytes = &self.buffer[range];
+        // Unsafe: the tokenizer validated the string as UTF-8 when it read it
+        debug_assert!(std::str::from_utf8(bytes).is_ok());
+        unsafe { std::str::from_utf8_unchecked(bytes) }
+    }
+
+    /// Marks the reader as failed and returns an error at the given document offset.
+    fn fail(&mut self, kind: ErrorKind, offset: usize) -> Error {
+        self.failed = true;
+        Error::new(kind, Some(offset))
+    }
+}
+
+/// Moves a token that doesn't borrow the input to the given document offset.
+fn rebase(token: Token<'_>, base: usize) -> Token<'static> {
+    let at = |offset: Offset| Offset(base + offset.0);
+    match token {
+        Token::StartArray { offset } => Token::StartArray { offset: at(offset) },
+        Token::EndArray { offset } => Token::EndArray { offset: at(offset) },
+        Token::StartObject { offset } => Token::StartObject { offset: at(offset) },
+        Token::EndObject { offset } => Token::EndObject { offset: at(offset) },
+        Token::ValueBool { offset, value } => Token::ValueBool {
+            offset: at(offset),
+            value,
+        },
+        Token::ValueNull { offset } => Token::ValueNull { offset: at(offset) },
+        Token::ValueNumber { offset, value } => Token::ValueNumber {
+            offset: at(offset),
+            value,
+        },
+        Token::ObjectKey { .. } | Token::ValueString { .. } => {
+            unreachable!("strings borrow the input and are stored as ranges")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JsonTokenReader, Pull};
+    use crate::deserialize::json_token_iter;
+    use aws_smithy_types::byte_stream::ByteStream;
+
+    const DOCUMENTS: &[&str] = &[
+        "null",
+        "  true ",
+        "-12.5e3",
+        r#""a \"string\" é""#,
+        r#"{"foo": [1, 2.5, "three", null, false], "bar": {"baz": {}}, "qux": []}"#,
+        r#"[{"a": "ü"}, [[-1]], 123456789, "", {}]"#,
+        r#"{"a\\": ["\\", "x\\\"y", -0.5E+2]}"#,
+    ];
+
+    /// Reads every token of `input`, pushing it in chunks split at `splits`, and returns their
+    /// `Debug` representations.
+    fn read_tokens(input: &[u8], splits: &[usize]) -> Vec<String> {
+        let mut reader = JsonTokenReader::new();
+        let mut chunks = splits
+            .iter()
+            .copied()
+            .chain(Some(input.len()))
+            .scan(0, |start, end| {
+                let chunk = &input[*start..end];
+                *start = end;
+                Some(chunk)
+            });
+        let mut tokens = Vec::new();
+        loop {
+            match reader.next_token() {
+                Ok(Pull::Ready(Some(token))) => tokens.push(format!("{:?}", token)),
+                Ok(Pull::Ready(None)) => return tokens,
+                Ok(Pull::NeedMoreInput) => match chunks.next() {
+                    Some(chunk) => reader.push(chunk),
+                    None => reader.finish(),
+                },
+                Err(err) => {
+                    tokens.push(format!("{:?}", err));
+                    return tokens;
+                }
+            }
+        }
+    }
+
+    fn expected_tokens(input: &[u8]) -> Vec<String> {
+        json_token_iter(input)
+            .map(|token| match token {
+                Ok(token) => format!("{:?}", token),
+                Err(err) => format!("{:?}", err),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tokens_match_across_any_split() {
+        for document in DOCUMENTS {
+            let input = document.as_bytes();
+            let expected = expected_tokens(input);
+            assert_eq!(expected, read_tokens(input, &[]), "{document}");
+            for first in 0..=input.len() {
+                for second in first..=input.len() {
+                    assert_eq!(
+                        expected,
+                        read_tokens(input, &[first, second]),
+                        "{document} split at {first} and {second}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn errors_have_document_offsets() {
+        for document in [
+            "[1, 2, tru]",
+            r#"{"a": 1 "b"}"#,
+            "[1, 2",
+            r#"["\q"]"#,
+            "[1, 2]x",
+        ] {
+            let input = document.as_bytes();
+            let expected = expected_tokens(input);
+            for split in 0..=input.len() {
+                assert_eq!(
+                    expected,
+                    read_tokens(input, &[split]),
+                    "{document} split at {split}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn values_are_read_across_chunks() {
+        let input =
+            br#"{"items": [{"name": "a", "tags": ["x", "y"]}, 2, "three", []], "done": true}"#;
+        let mut reader = JsonTokenReader::new();
+        let mut chunks = input.chunks(3);
+        let mut values = Vec::new();
+        let mut in_items = false;
+        loop {
+            if in_items {
+                match reader.next_value().unwrap() {
+                    Pull::Ready(Some(value)) => {
+                        values.push(String::from_utf8(value.to_vec()).unwrap());
+                        continue;
+                    }
+                    Pull::Ready(None) => {
+                        in_items = false;
+                        continue;
+                    }
+                    Pull::NeedMoreInput => {}
+                }
+            } else {
+                match reader.next_token().unwrap() {
+                    Pull::Ready(Some(token)) => {
+                        in_items = matches!(token, crate::deserialize::Token::StartArray { .. });
+                        continue;
+                    }
+                    Pull::Ready(None) => break,
+                    Pull::NeedMoreInput => {}
+                }
+            }
+            match chunks.next() {
+                Some(chunk) => reader.push(chunk),
+                None => reader.finish(),
+            }
+        }
+        assert_eq!(
+            vec![
+                r#"{"name": "a", "tags": ["x", "y"]}"#,
+                "2",
+                r#""three""#,
+                "[]"
+            ],
+            values
+        );
+    }
+
+    #[test]
+    fn object_key_is_not_a_value() {
+        let mut reader = JsonTokenReader::new();
+        reader.push(br#"{"a": 1}"#);
+        reader.finish();
+        assert!(matches!(reader.next_token(), Ok(Pull::Ready(Some(_)))));
+        assert!(reader.next_value().is_err());
+    }
+
+    #[test]
+    fn truncated_value_is_an_error() {
+        let mut reader = JsonTokenReader::new();
+        reader.push(br#"[{"a": 1}, {"b": "#);
+        assert!(matches!(reader.next_token(), Ok(Pull::Ready(Some(_)))));
+        assert_eq!(
+            Pull::Ready(Some(&br#"{"a": 1}"#[..])),
+            reader.next_value().unwrap()
+        );
+        assert_eq!(Pull::NeedMoreInput, reader.next_value().unwrap());
+        reader.finish();
+        assert!(reader.next_value().is_err());
+    }
+
+    #[test]
+    fn buffer_only_holds_unread_input() {
+        let mut reader = JsonTokenReader::new();
+        reader.push(b"[");
+        assert!(matches!(reader.next_token(), Ok(Pull::Ready(Some(_)))));
+        let mut read = 0;
+        for _ in 0..1000 {
+            reader.push(br#"{"name": "some name"}, "#);
+            while let Pull::Ready(Some(_)) = reader.next_value().unwrap() {
+                read += 1;
+            }
+            assert!(reader.buffer.len() < 64, "{}", reader.buffer.len());
+        }
+        reader.push(b"null]");
+        reader.finish();
+        while let Pull::Ready(Some(_)) = reader.next_value().unwrap() {
+            read += 1;
+        }
+        assert_eq!(1001, read);
+    }
+
+    #[test]
+    fn long_tokens_are_not_tokenized_again_for_every_chunk() {
+        fn read_values(reader: &mut JsonTokenReader) -> usize {
+            let mut values = 0;
+            while let Pull::Ready(Some(_)) = reader.next_value().unwrap() {
+                values += 1;
+            }
+            values
+        }
+
+        for (start, body, end, expected_values) in [
+            ("[\"", "a\\\"", "\"]", 1),
+            ("[-0.", "12", "5]", 1),
+            ("[1,", " ", "2]", 2),
+        ] {
+            let mut reader = JsonTokenReader::new();
+            reader.push(start.as_bytes());
+            assert!(matches!(reader.next_token(), Ok(Pull::Ready(Some(_)))));
+            let mut values = 0;
+            for _ in 0..1000 {
+                values += read_values(&mut reader);
+                // The bytes examined so far are remembered.
+                let partial = reader.partial.as_ref().expect("progress is saved");
+                assert_eq!(reader.buffer.len(), partial.cursor, "{start}");
+                reader.push(body.as_bytes());
+            }
+            reader.push(end.as_bytes());
+            reader.finish();
+            values += read_values(&mut reader);
+            assert_eq!(expected_values, values, "{start}");
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_from_byte_stream() {
+        let mut stream = ByteStream::from_static(br#"{"items": [1, {"a": [2]}]}"#);
+        let mut reader = JsonTokenReader::new();
+        let mut tokens = 0;
+        while let Some(token) = reader.next_token_from(&mut stream).await.unwrap() {
+            tokens += 1;
+            if let crate::deserialize::Token::StartArray { .. } = token {
+                break;
+            }
+        }
+        assert_eq!(3, tokens);
+        assert_eq!(
+            Some(&b"1"[..]),
+            reader.next_value_from(&mut stream).await.unwrap()
+        );
+        assert_eq!(
+            Some(&br#"{"a": [2]}"#[..]),
+            reader.next_value_from(&mut stream).await.unwrap()
+        );
+        assert_eq!(None, reader.next_value_from(&mut stream).await.unwrap());
+        assert!(matches!(
+            reader.next_token_from(&mut stream).await,
+            Ok(Some(crate::deserialize::Token::EndObject { .. }))
+        ));
+        assert!(reader.next_token_from(&mut stream).await.unwrap().is_none());
+    }
+}
@@ -3,6 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::serialize::JsonOutput;
 use std::borrow::Cow;
 use std::fmt;
 
@@ -79,7 +80,7 @@ fn escape_string_inner(start: &[u8], rest: &[u8]) -> String {
             b'\n' => escaped.extend(b"\\n"),
             b'\r' => escaped.extend(b"\\r"),
             b'\t' => escaped.extend(b"\\t"),
-            0..=0x1F => escaped.extend(format!("\\u{:04x}", byte).bytes()),
+            0..=0x1F => escaped.extend(escape_control_character(*byte)),
             _ => escaped.push(*byte),
         }
     }
@@ -92,6 +93,47 @@ fn escape_string_inner(start: &[u8], rest: &[u8]) -> String {
     unsafe { String::from_utf8_unchecked(escaped) }
 }
 
+/// Returns the `\u00XX` escape sequence for a control character.
+fn escape_control_character(byte: u8) -> [u8; 6] {
+    const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+    [
+        b'\\',
+        b'u',
+        b'0',
+        b'0',
+        HEX_DIGITS[usize::from(byte >> 4)],
+        HEX_DIGITS[usize::from(byte & 0xF)],
+    ]
+}
+
+/// Escapes a string for embedding in a JSON string value, writing it directly to `output`.
+pub(crate) fn escape_string_into<O: JsonOutput>(value: &str, output: &mut O) {
+    let bytes = value.as_bytes();
+    let mut unescaped_from = 0;
+    for (index, byte) in bytes.iter().enumerate() {
+        let control_character;
+        let escaped: &str = match byte {
+            b'"' => "\\\"",
+            b'\\' => "\\\\",
+            0x08 => "\\b",
+            0x0C => "\\f",
+            b'\n' => "\\n",
+            b'\r' => "\\r",
+            b'\t' => "\\t",
+            0..=0x1F => {
+                control_character = escape_control_character(*byte);
+                std::str::from_utf8(&control_character).expect("escape sequences are ASCII")
+            }
+            _ => continue,
+        };
+        // Escaped bytes are ASCII, so `index` is always on a character boundary
+        output.push_str(&value[unescaped_from..index]);
+        output.push_str(escaped);
+        unescaped_from = index + 1;
+    }
+    output.push_str(&value[unescaped_from..]);
+}
+
 /// Unescapes a JSON-escaped string.
 /// If there are no escape sequences, it directly returns the reference.
 pub(crate) fn unescape_string(value: &str) -> Result<Cow<'_, str>, EscapeError> {
@@ -212,7 +254,7 @@ fn read_unicode_escapes(bytes: &[u8], into: &mut Vec<u8>) -> Result<usize, Escap
 
 #[cfg(test)]
 mod test {
-    use super::escape_string;
+    use super::{escape_string, escape_string_into};
     use crate::escape::{unescape_string, EscapeErrorKind};
     use std::borrow::Cow;
 
@@ -299,6 +341,13 @@ mod test {
             assert_eq!(serde_escaped,escape_string(&s))
         }
 
+        #[test]
+        fn escape_into_matches_escape(s in ".*") {
+            let mut escaped = String::new();
+            escape_string_into(&s, &mut escaped);
+            assert_eq!(escape_string(&s), escaped)
+        }
+
         #[test]
         fn round_trip(chr in proptest::char::any()) {
             let mut original = String::new();
@@ -3,18 +3,145 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use crate::escape::escape_string;
+use crate::escape::{escape_string, escape_string_into};
 use aws_smithy_types::date_time::{DateTimeFormatError, Format};
 use aws_smithy_types::primitive::Encoder;
 use aws_smithy_types::{DateTime, Document, Number};
+use bytes::{BufMut, BytesMut};
 use std::borrow::Cow;
 
-pub struct JsonValueWriter<'a> {
-    output: &'a mut String,
+/// Destination of the JSON writers.
+///
+/// JSON is written as UTF-8 text, so anything that can be appended to, not just [`String`], can
+/// be written to. Writing into [`BytesMut`] avoids copying the document once it's complete,
+/// since it can be frozen into [`Bytes`](bytes::Bytes) for a request body directly.
+pub trait JsonOutput {
+    /// Appends `value`.
+    fn push_str(&mut self, value: &str);
+
+    /// Appends a single ASCII `byte`, such as a quote or a delimiter.
+    fn push_ascii(&mut self, byte: u8);
 }
 
-impl<'a> JsonValueWriter<'a> {
-    pub fn new(output: &'a mut String) -> Self {
+impl JsonOutput for String {
+    fn push_str(&mut self, value: &str) {
+        String::push_str(self, value);
+    }
+
+    fn push_ascii(&mut self, byte: u8) {
+        debug_assert!(byte.is_ascii());
+        self.push(char::from(byte));
+    }
+}
+
+impl JsonOutput for Vec<u8> {
+    fn push_str(&mut self, value: &str) {
+        self.extend_from_slice(value.as_bytes());
+    }
+
+    fn push_ascii(&mut self, byte: u8) {
+        debug_assert!(byte.is_ascii());
+        self.push(byte);
+    }
+}
+
+impl JsonOutput for BytesMut {
+    fn push_str(&mut self, value: &str) {
+        self.extend_from_slice(value.as_bytes());
+    }
+
+    fn push_ascii(&mut self, byte: u8) {
+        debug_assert!(byte.is_ascii());
+        self.put_u8(byte);
+    }
+}
+
+/// [`JsonOutput`] writing to any [`BufMut`].
+///
+/// [`BytesMut`] and `Vec<u8>` can be written to directly; this wraps other buffers.
+#[derive(Debug)]
+pub struct BufMutOutput<B>(B);
+
+impl<B: BufMut> BufMutOutput<B> {
+    /// Wraps `buf`.
+    pub fn new(buf: B) -> Self {
+        Self(buf)
+    }
+
+    /// Returns the wrapped buffer.
+    pub fn into_inner(self) -> B {
+        self.0
+    }
+}
+
+impl<B: BufMut> JsonOutput for BufMutOutput<B> {
+    fn push_str(&mut self, value: &str) {
+        self.0.put_slice(value.as_bytes());
+    }
+
+    fn push_ascii(&mut self, byte: u8) {
+        debug_assert!(byte.is_ascii());
+        self.0.put_u8(byte);
+    }
+}
+
+/// [`JsonOutput`] that only counts the bytes written to it.
+///
+/// Serializing a value into a `ByteCounter` first gives the exact length of its JSON, so the
+/// buffer it's then serialized into can be allocated once with the right capacity. This costs a
+/// second serialization pass, but avoids growing the buffer, which can otherwise leave it with up
+/// to twice the capacity it needs:
+///
+/// ```
+/// use aws_smithy_json::serialize::{ByteCounter, JsonObjectWriter, JsonOutput};
+/// use bytes::BytesMut;
+///
+/// fn write<O: JsonOutput>(output: &mut O) {
+///     let mut object = JsonObjectWriter::new(output);
+///     object.key("name").string("some name");
+///     object.finish();
+/// }
+///
+/// let mut counter = ByteCounter::new();
+/// write(&mut counter);
+/// let mut output = BytesMut::with_capacity(counter.count());
+/// write(&mut output);
+/// assert_eq!(counter.count(), output.len());
+/// assert_eq!(counter.count(), output.capacity());
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ByteCounter {
+    count: usize,
+}
+
+impl ByteCounter {
+    /// Creates a counter at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl JsonOutput for ByteCounter {
+    fn push_str(&mut self, value: &str) {
+        self.count += value.len();
+    }
+
+    fn push_ascii(&mut self, _byte: u8) {
+        self.count += 1;
+    }
+}
+
+pub struct JsonValueWriter<'a, O = String> {
+    output: &'a mut O,
+}
+
+impl<'a, O: JsonOutput> JsonValueWriter<'a, O> {
+    pub fn new(output: &'a mut O) -> Self {
         JsonValueWriter { output }
     }
 
@@ -57,9 +184,9 @@ impl<'a> JsonValueWriter<'a> {
 
     /// Writes a string `value`.
     pub fn string(self, value: &str) {
-        self.output.push('"');
-        self.output.push_str(&escape_string(value));
-        self.output.push('"');
+        self.output.push_ascii(b'"');
+        escape_string_into(value, self.output);
+        self.output.push_ascii(b'"');
     }
 
     /// Writes a string `value` without escaping it.
@@ -67,9 +194,9 @@ impl<'a> JsonValueWriter<'a> {
         // Verify in debug builds that we don't actually need to escape the string
         debug_assert!(matches!(escape_string(value), Cow::Borrowed(_)));
 
-        self.output.push('"');
+        self.output.push_ascii(b'"');
         self.output.push_str(value);
-        self.output.push('"');
+        self.output.push_ascii(b'"');
     }
 
     /// Writes a number `value`.
@@ -109,24 +236,24 @@ impl<'a> JsonValueWriter<'a> {
     }
 
     /// Starts an array.
-    pub fn start_array(self) -> JsonArrayWriter<'a> {
+    pub fn start_array(self) -> JsonArrayWriter<'a, O> {
         JsonArrayWriter::new(self.output)
     }
 
     /// Starts an object.
-    pub fn start_object(self) -> JsonObjectWriter<'a> {
+    pub fn start_object(self) -> JsonObjectWriter<'a, O> {
         JsonObjectWriter::new(self.output)
     }
 }
 
-pub struct JsonObjectWriter<'a> {
-    json: &'a mut String,
+pub struct JsonObjectWriter<'a, O = String> {
+    json: &'a mut O,
     started: bool,
 }
 
-impl<'a> JsonObjectWriter<'a> {
-    pub fn new(output: &'a mut String) -> Self {
-        output.push('{');
+impl<'a, O: JsonOutput> JsonObjectWriter<'a, O> {
+    pub fn new(output: &'a mut O) -> Self {
+        output.push_ascii(b'{');
         Self {
             json: output,
             started: false,
@@ -134,14 +261,14 @@ impl<'a> JsonObjectWriter<'a> {
     }
 
     /// Starts a value with the given `key`.
-    pub fn key(&mut self, key: &str) -> JsonValueWriter<'_> {
+    pub fn key(&mut self, key: &str) -> JsonValueWriter<'_, O> {
         if self.started {
-            self.json.push(',');
+            self.json.push_ascii(b',');
         }
         self.started = true;
 
-        self.json.push('"');
-        self.json.push_str(&escape_string(key));
+        self.json.push_ascii(b'"');
+        escape_string_into(key, self.json);
         self.json.push_str("\":");
 
         JsonValueWriter::new(self.json)
@@ -149,18 +276,18 @@ impl<'a> JsonObjectWriter<'a> {
 
     /// Finishes the object.
     pub fn finish(self) {
-        self.json.push('}');
+        self.json.push_ascii(b'}');
     }
 }
 
-pub struct JsonArrayWriter<'a> {
-    json: &'a mut String,
+pub struct JsonArrayWriter<'a, O = String> {
+    json: &'a mut O,
     started: bool,
 }
 
-impl<'a> JsonArrayWriter<'a> {
-    pub fn new(output: &'a mut String) -> Self {
-        output.push('[');
+impl<'a, O: JsonOutput> JsonArrayWriter<'a, O> {
+    pub fn new(output: &'a mut O) -> Self {
+        output.push_ascii(b'[');
         Self {
             json: output,
             started: false,
@@ -168,19 +295,19 @@ impl<'a> JsonArrayWriter<'a> {
     }
 
     /// Starts a new value in the array.
-    pub fn value(&mut self) -> JsonValueWriter<'_> {
+    pub fn value(&mut self) -> JsonValueWriter<'_, O> {
         self.comma_delimit();
         JsonValueWriter::new(self.json)
     }
 
     /// Finishes the array.
     pub fn finish(self) {
-        self.json.push(']');
+        self.json.push_ascii(b']');
     }
 
     fn comma_delimit(&mut self) {
         if self.started {
-            self.json.push(',');
+            self.json.push_ascii(b',');
         }
         self.started = true;
     }
@@ -189,7 +316,7 @@ impl<'a> JsonArrayWriter<'a> {
 #[cfg(test)]
 mod tests {
     use super::{JsonArrayWriter, JsonObjectWriter};
-    use crate::serialize::JsonValueWriter;
+    use crate::serialize::{BufMutOutput, ByteCounter, JsonOutput, JsonValueWriter};
     use aws_smithy_types::date_time::Format;
     use aws_smithy_types::{DateTime, Document, Number};
     use proptest::proptest;
@@ -345,6 +472,45 @@ mod tests {
         )
     }
 
+    fn write_mixed<O: JsonOutput>(output: &mut O) {
+        let mut object = JsonObjectWriter::new(output);
+        object.key("string").string("some\n\"string\" é");
+        object.key("number").number(Number::Float(-1.5));
+        let mut array = object.key("array").start_array();
+        array.value().boolean(true);
+        array.value().null();
+        array.value().number(Number::Float(f64::NAN));
+        array.value().start_object().finish();
+        array.finish();
+        object.finish();
+    }
+
+    #[test]
+    fn outputs_match_string() {
+        let mut expected = String::new();
+        write_mixed(&mut expected);
+        assert_eq!(
+            r#"{"string":"some\n\"string\" é","number":-1.5,"array":[true,null,"NaN",{}]}"#,
+            expected
+        );
+
+        let mut vec = Vec::new();
+        write_mixed(&mut vec);
+        assert_eq!(expected.as_bytes(), &vec[..]);
+
+        let mut bytes = bytes::BytesMut::new();
+        write_mixed(&mut bytes);
+        assert_eq!(expected.as_bytes(), &bytes[..]);
+
+        let mut buf = BufMutOutput::new(Vec::new());
+        write_mixed(&mut buf);
+        assert_eq!(expected.as_bytes(), &buf.into_inner()[..]);
+
+        let mut counter = ByteCounter::new();
+        write_mixed(&mut counter);
+        assert_eq!(expected.len(), counter.count());
+    }
+
     fn format_document(document: Document) -> String {
         let mut output = String::new();
         JsonValueWriter::new(&mut output).document(&document);
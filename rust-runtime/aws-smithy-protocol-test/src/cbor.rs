@@ -0,0 +1,241 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::{pretty_comparison, FloatEquals, ProtocolTestFailure};
+use minicbor::data::{Tag, Type};
+use minicbor::decode::Error;
+use minicbor::Decoder;
+
+/// A decoded CBOR data item.
+///
+/// Equality differs from a structural comparison in that floats compare equal regardless of
+/// their encoded width (and all `NaN`s are equal), definite and indefinite length items compare
+/// equal, and maps are compared without regard to the order of their entries.
+#[derive(Debug)]
+enum Value {
+    Null,
+    Undefined,
+    Bool(bool),
+    Integer(i128),
+    Float(f64),
+    Simple(u8),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Value>),
+    Map(Vec<(Value, Value)>),
+    Tag(Tag, Box<Value>),
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Null, Value::Null) => true,
+            (Value::Undefined, Value::Undefined) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Integer(a), Value::Integer(b)) => a == b,
+            (Value::Float(a), Value::Float(b)) => a.float_equals(b),
+            (Value::Simple(a), Value::Simple(b)) => a == b,
+            (Value::Bytes(a), Value::Bytes(b)) => a == b,
+            (Value::Text(a), Value::Text(b)) => a == b,
+            (Value::Array(a), Value::Array(b)) => a == b,
+            (Value::Map(a), Value::Map(b)) => {
+                a.len() == b.len()
+                    && a.iter().all(|(key, value)| {
+                        b.iter()
+                            .find(|(other_key, _)| key == other_key)
+                            .is_some_and(|(_, other_value)| value == other_value)
+                    })
+            }
+            (Value::Tag(a, a_value), Value::Tag(b, b_value)) => a == b && a_value == b_value,
+            _ => false,
+        }
+    }
+}
+
+fn is_break(decoder: &Decoder<'_>) -> Result<bool, Error> {
+    Ok(decoder.datatype()? == Type::Break)
+}
+
+fn skip_break(decoder: &mut Decoder<'_>) {
+    decoder.set_position(decoder.position() + 1);
+}
+
+fn decode_value(decoder: &mut Decoder<'_>) -> Result<Value, Error> {
+    Ok(match decoder.datatype()? {
+        Type::Null => {
+            decoder.null()?;
+            Value::Null
+        }
+        Type::Undefined => {
+            decoder.undefined()?;
+            Value::Undefined
+        }
+        Type::Bool => Value::Bool(decoder.bool()?),
+        Type::U8
+        | Type::U16
+        | Type::U32
+        | Type::U64
+        | Type::I8
+        | Type::I16
+        | Type::I32
+        | Type::I64
+        | Type::Int => Value::Integer(decoder.int()?.into()),
+        Type::F16 | Type::F32 | Type::F64 => Value::Float(decoder.f64()?),
+        Type::Simple => Value::Simple(decoder.simple()?),
+        Type::Bytes | Type::BytesIndef => {
+            let mut bytes = Vec::new();
+            for chunk in decoder.bytes_iter()? {
+                bytes.extend_from_slice(chunk?);
+            }
+            Value::Bytes(bytes)
+        }
+        Type::String | Type::StringIndef => {
+            let mut text = String::new();
+            for chunk in decoder.str_iter()? {
+                text.push_str(chunk?);
+            }
+            Value::Text(text)
+        }
+        Type::Array | Type::ArrayIndef => {
+            let mut items = Vec::new();
+            match decoder.array()? {
+                Some(len) => {
+                    for _ in 0..len {
+                        items.push(decode_value(decoder)?);
+                    }
+                }
+                None => {
+                    while !is_break(decoder)? {
+                        items.push(decode_value(decoder)?);
+                    }
+                    skip_break(decoder);
+                }
+            }
+            Value::Array(items)
+        }
+        Type::Map | Type::MapIndef => {
+            let mut entries = Vec::new();
+            match decoder.map()? {
+                Some(len) => {
+                    for _ in 0..len {
+                        entries.push((decode_value(decoder)?, decode_value(decoder)?));
+                    }
+                }
+                None => {
+                    while !is_break(decoder)? {
+                        entries.push((decode_value(decoder)?, decode_value(decoder)?));
+                    }
+                    skip_break(decoder);
+                }
+            }
+            Value::Map(entries)
+        }
+        Type::Tag => {
+            let tag = decoder.tag()?;
+            Value::Tag(tag, Box::new(decode_value(decoder)?))
+        }
+        ty @ (Type::Break | Type::Unknown(_)) => {
+            return Err(Error::type_mismatch(ty)
+                .at(decoder.position())
+                .with_message("unexpected CBOR data item"))
+        }
+    })
+}
+
+fn decode(body: &[u8]) -> Result<Value, Error> {
+    let mut decoder = Decoder::new(body);
+    let value = decode_value(&mut decoder)?;
+    if decoder.position() != body.len() {
+        return Err(Error::message("trailing data after CBOR data item").at(decoder.position()));
+    }
+    Ok(value)
+}
+
+/// Compares two CBOR bodies for semantic equivalence.
+///
+/// An empty body is only equivalent to another empty body.
+pub(crate) fn try_cbor_eq(expected: &[u8], actual: &[u8]) -> Result<(), ProtocolTestFailure> {
+    if expected.is_empty() || actual.is_empty() {
+        return if expected.is_empty() && actual.is_empty() {
+            Ok(())
+        } else {
+            Err(ProtocolTestFailure::BodyDidNotMatch {
+                comparison: pretty_comparison(
+                    &format!("{:02x?}", expected),
+                    &format!("{:02x?}", actual),
+                ),
+                hint: "one of the bodies was empty".to_owned(),
+            })
+        };
+    }
+    let expected_value = decode(expected).expect("expected value must be valid CBOR");
+    let actual_value = decode(actual).map_err(|e| ProtocolTestFailure::InvalidBodyFormat {
+        expected: "cbor".to_owned(),
+        found: format!("{} {:02x?}", e, actual),
+    })?;
+    if expected_value == actual_value {
+        Ok(())
+    } else {
+        Err(ProtocolTestFailure::BodyDidNotMatch {
+            comparison: pretty_comparison(
+                &format!("{:#?}", expected_value),
+                &format!("{:#?}", actual_value),
+            ),
+            hint: "media type: application/cbor".to_owned(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::try_cbor_eq;
+
+    #[test]
+    fn maps_are_compared_without_regard_to_order() {
+        // {"a": 1, "b": 2}
+        let expected = [0xa2, 0x61, b'a', 0x01, 0x61, b'b', 0x02];
+        // {"b": 2, "a": 1}
+        let actual = [0xa2, 0x61, b'b', 0x02, 0x61, b'a', 0x01];
+        try_cbor_eq(&expected, &actual).expect("maps are equivalent");
+
+        // {"b": 3, "a": 1}
+        let actual = [0xa2, 0x61, b'b', 0x03, 0x61, b'a', 0x01];
+        try_cbor_eq(&expected, &actual).expect_err("values differ");
+    }
+
+    #[test]
+    fn indefinite_and_definite_lengths_are_equivalent() {
+        // ["a", h'01']
+        let expected = [0x82, 0x61, b'a', 0x41, 0x01];
+        // [_ (_ "a"), (_ h'01')]
+        let actual = [0x9f, 0x7f, 0x61, b'a', 0xff, 0x5f, 0x41, 0x01, 0xff, 0xff];
+        try_cbor_eq(&expected, &actual).expect("arrays are equivalent");
+    }
+
+    #[test]
+    fn floats_are_compared_by_value() {
+        // 1.5 as a half, single and double precision float
+        let half = [0xf9, 0x3e, 0x00];
+        let single = [0xfa, 0x3f, 0xc0, 0x00, 0x00];
+        let double = [0xfb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0];
+        try_cbor_eq(&half, &single).expect("floats are equal");
+        try_cbor_eq(&single, &double).expect("floats are equal");
+
+        // NaN as a half and a double precision float
+        let half_nan = [0xf9, 0x7e, 0x00];
+        let double_nan = [0xfb, 0x7f, 0xf8, 0, 0, 0, 0, 0, 0];
+        try_cbor_eq(&half_nan, &double_nan).expect("NaNs are equal");
+
+        try_cbor_eq(&half, &half_nan).expect_err("floats differ");
+    }
+
+    #[test]
+    fn invalid_actual_body_is_rejected() {
+        try_cbor_eq(&[0xa0], &[0xa1]).expect_err("truncated map");
+        try_cbor_eq(&[0xa0], &[0xa0, 0xa0]).expect_err("trailing data");
+        try_cbor_eq(&[0xa0], &[]).expect_err("empty body");
+        try_cbor_eq(&[], &[]).expect("both bodies empty");
+    }
+}
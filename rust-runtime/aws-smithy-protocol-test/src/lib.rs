@@ -13,9 +13,11 @@
     rust_2018_idioms
 )]
 
+mod cbor;
 mod urlencoded;
 mod xml;
 
+use crate::cbor::try_cbor_eq;
 use crate::sealed::GetNormalizedHeader;
 use crate::xml::try_xml_equivalent;
 use assert_json_diff::assert_json_eq_no_panic;
@@ -23,6 +25,7 @@ use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
 use aws_smithy_runtime_api::http::Headers;
 use http::{HeaderMap, Uri};
 use pretty_assertions::Comparison;
+use std::borrow::Cow;
 use std::collections::HashSet;
 use std::fmt::{self, Debug};
 use thiserror::Error;
@@ -312,6 +315,8 @@ pub enum MediaType {
     Xml,
     /// For x-www-form-urlencoded, do some map order comparison shenanigans
     UrlEncodedForm,
+    /// CBOR media types are base64 encoded in protocol tests, and are decoded and compared
+    Cbor,
     /// Other media types are compared literally
     Other(String),
 }
@@ -323,11 +328,27 @@ impl<T: AsRef<str>> From<T> for MediaType {
             "application/x-amz-json-1.1" => MediaType::Json,
             "application/xml" => MediaType::Xml,
             "application/x-www-form-urlencoded" => MediaType::UrlEncodedForm,
+            "application/cbor" => MediaType::Cbor,
             other => MediaType::Other(other.to_string()),
         }
     }
 }
 
+/// Returns the bytes that a protocol test `body` of the given media type stands for
+///
+/// Protocol tests for binary media types like CBOR base64 encode the body; it is decoded here.
+/// All other bodies are returned as-is.
+pub fn decode_body_data(body: &[u8], media_type: MediaType) -> Cow<'_, [u8]> {
+    match media_type {
+        MediaType::Cbor => Cow::Owned(
+            base64_simd::STANDARD
+                .decode_to_vec(body)
+                .expect("smithy protocol test `body` property is not properly base64 encoded"),
+        ),
+        _ => Cow::Borrowed(body),
+    }
+}
+
 pub fn validate_body<T: AsRef<[u8]>>(
     actual_body: T,
     expected_body: &str,
@@ -335,6 +356,10 @@ pub fn validate_body<T: AsRef<[u8]>>(
 ) -> Result<(), ProtocolTestFailure> {
     let body_str = std::str::from_utf8(actual_body.as_ref());
     match (media_type, body_str) {
+        (MediaType::Cbor, _) => try_cbor_eq(
+            &decode_body_data(expected_body.as_bytes(), MediaType::Cbor),
+            actual_body.as_ref(),
+        ),
         (MediaType::Json, Ok(actual_body)) => try_json_eq(expected_body, actual_body),
         (MediaType::Xml, Ok(actual_body)) => try_xml_equivalent(expected_body, actual_body),
         (MediaType::Json, Err(_)) => Err(ProtocolTestFailure::InvalidBodyFormat {
@@ -413,8 +438,9 @@ fn try_json_eq(expected: &str, actual: &str) -> Result<(), ProtocolTestFailure>
 #[cfg(test)]
 mod tests {
     use crate::{
-        forbid_headers, forbid_query_params, require_headers, require_query_params, validate_body,
-        validate_headers, validate_query_string, FloatEquals, MediaType, ProtocolTestFailure,
+        decode_body_data, forbid_headers, forbid_query_params, require_headers,
+        require_query_params, validate_body, validate_headers, validate_query_string, FloatEquals,
+        MediaType, ProtocolTestFailure,
     };
     use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
     use aws_smithy_runtime_api::http::Headers;
@@ -548,6 +574,30 @@ mod tests {
             .expect("inputs matched exactly")
     }
 
+    #[test]
+    fn test_validate_cbor_body() {
+        // `{"abc": 5}`, compared against the same map encoded with an indefinite length
+        let expected = "oWNhYmMF";
+        let actual = [0xbf, 0x63, b'a', b'b', b'c', 0x05, 0xff];
+        validate_body(actual, expected, MediaType::from("application/cbor"))
+            .expect("inputs matched as CBOR");
+
+        let actual = [0xa1, 0x63, b'a', b'b', b'c', 0x06];
+        validate_body(actual, expected, MediaType::Cbor).expect_err("bodies do not match");
+    }
+
+    #[test]
+    fn test_decode_body_data() {
+        assert_eq!(
+            &[0xa1, 0x63, b'a', b'b', b'c', 0x05][..],
+            &*decode_body_data(b"oWNhYmMF", MediaType::Cbor)
+        );
+        assert_eq!(
+            &b"oWNhYmMF"[..],
+            &*decode_body_data(b"oWNhYmMF", MediaType::Json)
+        );
+    }
+
     #[test]
     fn test_validate_headers_http0x() {
         let request = http::Request::builder().header("a", "b").body(()).unwrap();
@@ -0,0 +1,539 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Deserialization of `application/x-www-form-urlencoded` query requests.
+//!
+//! A query request flattens its input into key-value pairs whose keys are `.`-separated paths, e.g.
+//! `Nested.List.member.1=foo`. [`QueryDocument`] parses these pairs into a tree, and [`QueryValue`]
+//! navigates it the same way [`QueryWriter`](crate::QueryWriter) builds the paths when
+//! serializing.
+
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::primitive::Parse;
+use aws_smithy_types::{base64, Blob, DateTime};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+enum DeserializeErrorKind {
+    InvalidEncoding(std::string::FromUtf8Error),
+    Custom {
+        message: Cow<'static, str>,
+        source: Option<Box<dyn Error + Send + Sync + 'static>>,
+    },
+}
+
+/// An error that occurred while deserializing a query request.
+#[derive(Debug)]
+pub struct DeserializeError {
+    kind: DeserializeErrorKind,
+}
+
+impl DeserializeError {
+    /// Returns a custom error.
+    pub fn custom(message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            kind: DeserializeErrorKind::Custom {
+                message: message.into(),
+                source: None,
+            },
+        }
+    }
+
+    /// Returns a custom error with an error source.
+    pub fn custom_source(
+        message: impl Into<Cow<'static, str>>,
+        source: impl Into<Box<dyn Error + Send + Sync + 'static>>,
+    ) -> Self {
+        Self {
+            kind: DeserializeErrorKind::Custom {
+                message: message.into(),
+                source: Some(source.into()),
+            },
+        }
+    }
+
+    fn invalid_encoding(source: std::string::FromUtf8Error) -> Self {
+        Self {
+            kind: DeserializeErrorKind::InvalidEncoding(source),
+        }
+    }
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            DeserializeErrorKind::InvalidEncoding(_) => {
+                write!(f, "query parameter is not percent-encoded UTF-8")
+            }
+            DeserializeErrorKind::Custom { message, .. } => {
+                write!(f, "failed to deserialize query: {message}")
+            }
+        }
+    }
+}
+
+impl Error for DeserializeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            DeserializeErrorKind::InvalidEncoding(source) => Some(source as _),
+            DeserializeErrorKind::Custom { source, .. } => {
+                source.as_ref().map(|source| source.as_ref() as _)
+            }
+        }
+    }
+}
+
+/// Decodes a key or a value of a form-urlencoded pair, in which spaces may be encoded as `+`.
+fn decode(input: &[u8]) -> Result<Cow<'_, str>, DeserializeError> {
+    let input = match std::str::from_utf8(input) {
+        Ok(input) => input,
+        Err(_) => {
+            return String::from_utf8(input.to_vec())
+                .map(Cow::Owned)
+                .map_err(DeserializeError::invalid_encoding)
+        }
+    };
+    if input.contains('+') {
+        urlencoding::decode(&input.replace('+', " "))
+            .map(|decoded| Cow::Owned(decoded.into_owned()))
+            .map_err(DeserializeError::invalid_encoding)
+    } else {
+        urlencoding::decode(input).map_err(DeserializeError::invalid_encoding)
+    }
+}
+
+/// Returns the decoded key-value pairs of a form-urlencoded body.
+fn pairs(
+    body: &[u8],
+) -> impl Iterator<Item = Result<(Cow<'_, str>, Cow<'_, str>), DeserializeError>> {
+    body.split(|b| *b == b'&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = match pair.iter().position(|b| *b == b'=') {
+                Some(index) => (&pair[..index], &pair[index + 1..]),
+                None => (pair, &[][..]),
+            };
+            Ok((decode(key)?, decode(value)?))
+        })
+}
+
+/// Returns the value of the `Action` parameter of a form-urlencoded body, which names the
+/// operation a query request invokes.
+///
+/// As in [`QueryDocument::parse`], when `Action` appears more than once, the last value wins, so
+/// that a request is routed to the operation whose input is then parsed from it.
+pub fn action(body: &[u8]) -> Result<Option<String>, DeserializeError> {
+    let mut action = None;
+    for pair in pairs(body) {
+        let (key, value) = pair?;
+        if key == "Action" {
+            action = Some(value);
+        }
+    }
+    Ok(action.map(Cow::into_owned))
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    value: Option<String>,
+    children: BTreeMap<String, Node>,
+}
+
+/// The parameters of a query request, parsed into a tree of [`QueryValue`]s.
+#[derive(Debug)]
+pub struct QueryDocument {
+    root: Node,
+}
+
+impl QueryDocument {
+    /// Parses a form-urlencoded request body.
+    ///
+    /// Keys are split into paths at every `.`. When a key appears more than once, the last value
+    /// wins.
+    pub fn parse(body: &[u8]) -> Result<Self, DeserializeError> {
+        let mut root = Node::default();
+        for pair in pairs(body) {
+            let (key, value) = pair?;
+            let node = key.split('.').fold(&mut root, |node, segment| {
+                node.children.entry(segment.to_owned()).or_default()
+            });
+            node.value = Some(value.into_owned());
+        }
+        Ok(Self { root })
+    }
+
+    /// Returns the value of the `Action` parameter, which names the invoked operation.
+    pub fn action(&self) -> Option<&str> {
+        self.root().get("Action")?.node.value.as_deref()
+    }
+
+    /// Returns the value of the `Version` parameter, which holds the version of the service.
+    pub fn version(&self) -> Option<&str> {
+        self.root().get("Version")?.node.value.as_deref()
+    }
+
+    /// Returns the root of the tree of parameters, which holds the members of the operation input.
+    pub fn root(&self) -> QueryValue<'_> {
+        QueryValue {
+            name: "",
+            node: &self.root,
+        }
+    }
+}
+
+/// A parameter of a query request, which may hold a scalar value, nested parameters, or both.
+#[derive(Debug, Clone, Copy)]
+pub struct QueryValue<'a> {
+    name: &'a str,
+    node: &'a Node,
+}
+
+impl<'a> QueryValue<'a> {
+    /// Returns the nested parameter `name`, e.g. a structure member.
+    pub fn get(&self, name: &str) -> Option<QueryValue<'a>> {
+        self.node
+            .children
+            .get_key_value(name)
+            .map(|(name, node)| QueryValue { name, node })
+    }
+
+    /// Returns the scalar value of the parameter.
+    pub fn string(&self) -> Result<&'a str, DeserializeError> {
+        self.node.value.as_deref().ok_or_else(|| {
+            DeserializeError::custom(format!("expected a value for `{}`", self.name))
+        })
+    }
+
+    /// Parses the scalar value of the parameter into a boolean or a number.
+    pub fn primitive<T: Parse>(&self) -> Result<T, DeserializeError> {
+        let value = self.string()?;
+        T::parse_smithy_primitive(value).map_err(|err| {
+            DeserializeError::custom_source(
+                format!("invalid value `{value}` for `{}`", self.name),
+                err,
+            )
+        })
+    }
+
+    /// Decodes the base64-encoded scalar value of the parameter.
+    pub fn blob(&self) -> Result<Blob, DeserializeError> {
+        let value = self.string()?;
+        base64::decode(value).map(Blob::new).map_err(|err| {
+            DeserializeError::custom_source(format!("invalid base64 for `{}`", self.name), err)
+        })
+    }
+
+    /// Parses the scalar value of the parameter into a date-time of the given `format`.
+    pub fn date_time(&self, format: Format) -> Result<DateTime, DeserializeError> {
+        let value = self.string()?;
+        DateTime::from_str(value, format).map_err(|err| {
+            DeserializeError::custom_source(
+                format!("invalid timestamp `{value}` for `{}`", self.name),
+                err,
+            )
+        })
+    }
+
+    /// Returns the nested parameters whose names are indices, ordered by index.
+    fn indexed(&self) -> Result<Vec<QueryValue<'a>>, DeserializeError> {
+        let mut items = self
+            .node
+            .children
+            .iter()
+            .map(|(name, node)| match name.parse::<usize>() {
+                Ok(index) => Ok((index, QueryValue { name, node })),
+                Err(_) => Err(DeserializeError::custom(format!(
+                    "expected an index in `{}`, found `{name}`",
+                    self.name
+                ))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        items.sort_by_key(|(index, _)| *index);
+        Ok(items.into_iter().map(|(_, item)| item).collect())
+    }
+
+    /// Returns the items of a list, ordered by their index.
+    ///
+    /// Items are nested under `member`, or under `member_override` if it is set, unless the list
+    /// is `flat`, in which case they are nested directly under the parameter. A parameter with no
+    /// items is an empty list.
+    pub fn list(
+        &self,
+        flat: bool,
+        member_override: Option<&str>,
+    ) -> Result<Vec<QueryValue<'a>>, DeserializeError> {
+        if flat {
+            return self.indexed();
+        }
+        match self.get(member_override.unwrap_or("member")) {
+            Some(items) => items.indexed(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Returns the entries of a map, ordered by their index.
+    ///
+    /// Entries are nested under `entry` unless the map is `flat`, in which case they are nested
+    /// directly under the parameter. Each entry holds its key and value under `key_name` and
+    /// `value_name`. A parameter with no entries is an empty map.
+    pub fn map(
+        &self,
+        flat: bool,
+        key_name: &str,
+        value_name: &str,
+    ) -> Result<Vec<(&'a str, QueryValue<'a>)>, DeserializeError> {
+        let entries = if flat {
+            self.indexed()?
+        } else {
+            match self.get("entry") {
+                Some(entries) => entries.indexed()?,
+                None => return Ok(Vec::new()),
+            }
+        };
+        entries
+            .into_iter()
+            .map(|entry| {
+                let missing = |name: &str| {
+                    DeserializeError::custom(format!(
+                        "map entry `{}` of `{}` has no `{name}`",
+                        entry.name, self.name
+                    ))
+                };
+                let key = entry.get(key_name).ok_or_else(|| missing(key_name))?;
+                let value = entry.get(value_name).ok_or_else(|| missing(value_name))?;
+                Ok((key.string()?, value))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{action, QueryDocument};
+    use crate::QueryWriter;
+    use aws_smithy_types::date_time::Format;
+    use aws_smithy_types::{Blob, DateTime, Number};
+
+    #[test]
+    fn action_and_version() {
+        let document = QueryDocument::parse(b"Action=SomeAction&Version=2020-01-08").unwrap();
+        assert_eq!(Some("SomeAction"), document.action());
+        assert_eq!(Some("2020-01-08"), document.version());
+
+        assert_eq!(
+            Some("SomeAction".to_owned()),
+            action(b"Version=1.0&Action=SomeAction&Foo=bar").unwrap()
+        );
+        assert_eq!(None, action(b"Version=1.0").unwrap());
+        assert_eq!(None, action(b"").unwrap());
+
+        // The last `Action` wins, for both.
+        let body = b"Action=First&Version=1.0&Action=Second";
+        assert_eq!(Some("Second".to_owned()), action(body).unwrap());
+        assert_eq!(Some("Second"), QueryDocument::parse(body).unwrap().action());
+    }
+
+    #[test]
+    fn percent_and_plus_decoding() {
+        let document =
+            QueryDocument::parse(b"Action=Some%20Action&Greeting=Hello+World%21&Key%2E1=v")
+                .unwrap();
+        let root = document.root();
+        assert_eq!(Some("Some Action"), document.action());
+        assert_eq!(
+            "Hello World!",
+            root.get("Greeting").unwrap().string().unwrap()
+        );
+        // Encoded dots still separate path segments once decoded.
+        assert_eq!(
+            "v",
+            root.get("Key").unwrap().get("1").unwrap().string().unwrap()
+        );
+
+        QueryDocument::parse(b"Invalid=%FF").expect_err("not UTF-8");
+    }
+
+    #[test]
+    fn scalars() {
+        let document = QueryDocument::parse(
+            b"Bool=true&Int=-5&Float=5.2&NaN=NaN&Blob=aGVsbG8%3D\
+            &Date=2021-05-24T15%3A34%3A50.123Z&Epoch=5.2&Empty=",
+        )
+        .unwrap();
+        let root = document.root();
+        assert!(root.get("Bool").unwrap().primitive::<bool>().unwrap());
+        assert_eq!(-5, root.get("Int").unwrap().primitive::<i32>().unwrap());
+        assert_eq!(5.2, root.get("Float").unwrap().primitive::<f64>().unwrap());
+        assert!(root
+            .get("NaN")
+            .unwrap()
+            .primitive::<f32>()
+            .unwrap()
+            .is_nan());
+        assert_eq!(
+            Blob::new("hello"),
+            root.get("Blob").unwrap().blob().unwrap()
+        );
+        assert_eq!(
+            DateTime::from_str("2021-05-24T15:34:50.123Z", Format::DateTime).unwrap(),
+            root.get("Date")
+                .unwrap()
+                .date_time(Format::DateTime)
+                .unwrap()
+        );
+        assert_eq!(
+            DateTime::from_secs_f64(5.2),
+            root.get("Epoch")
+                .unwrap()
+                .date_time(Format::EpochSeconds)
+                .unwrap()
+        );
+        assert_eq!("", root.get("Empty").unwrap().string().unwrap());
+        assert!(root.get("Missing").is_none());
+
+        root.get("Int")
+            .unwrap()
+            .primitive::<bool>()
+            .expect_err("not a boolean");
+        root.get("Bool")
+            .unwrap()
+            .primitive::<i8>()
+            .expect_err("not a number");
+    }
+
+    #[test]
+    fn nested_parameters_without_a_value() {
+        let document = QueryDocument::parse(b"Top.Nested=foo").unwrap();
+        let top = document.root().get("Top").unwrap();
+        assert_eq!("foo", top.get("Nested").unwrap().string().unwrap());
+        let err = top.string().expect_err("`Top` only has nested parameters");
+        assert_eq!(
+            "failed to deserialize query: expected a value for `Top`",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn lists_round_trip() {
+        let mut out = String::new();
+        let mut writer = QueryWriter::new(&mut out, "SomeAction", "1.0");
+        let mut list = writer.prefix("ListArg").start_list(false, None);
+        for item in [
+            "foo", "bar", "baz", "qux", "a", "b", "c", "d", "e", "f", "g",
+        ] {
+            list.entry().string(item);
+        }
+        list.finish();
+        let mut list = writer.prefix("FlattenedListArg").start_list(true, None);
+        list.entry().string("A");
+        list.entry().string("B");
+        list.finish();
+        let mut list = writer.prefix("ItemList").start_list(false, Some("item"));
+        list.entry().number(Number::PosInt(1));
+        list.finish();
+        writer.prefix("EmptyList").start_list(false, None).finish();
+        writer.finish();
+
+        let document = QueryDocument::parse(out.as_bytes()).unwrap();
+        let root = document.root();
+        let strings = |items: Vec<super::QueryValue<'_>>| {
+            items
+                .iter()
+                .map(|item| item.string().unwrap().to_owned())
+                .collect::<Vec<_>>()
+        };
+        // Items are ordered by their index, rather than lexicographically.
+        assert_eq!(
+            vec!["foo", "bar", "baz", "qux", "a", "b", "c", "d", "e", "f", "g"],
+            strings(root.get("ListArg").unwrap().list(false, None).unwrap())
+        );
+        assert_eq!(
+            vec!["A", "B"],
+            strings(
+                root.get("FlattenedListArg")
+                    .unwrap()
+                    .list(true, None)
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            vec!["1"],
+            strings(
+                root.get("ItemList")
+                    .unwrap()
+                    .list(false, Some("item"))
+                    .unwrap()
+            )
+        );
+        assert!(root
+            .get("EmptyList")
+            .unwrap()
+            .list(false, None)
+            .unwrap()
+            .is_empty());
+
+        let document = QueryDocument::parse(b"List.member.one=foo").unwrap();
+        document
+            .root()
+            .get("List")
+            .unwrap()
+            .list(false, None)
+            .expect_err("`one` is not an index");
+    }
+
+    #[test]
+    fn maps_round_trip() {
+        let mut out = String::new();
+        let mut writer = QueryWriter::new(&mut out, "SomeAction", "1.0");
+        let mut map = writer.prefix("MapArg").start_map(false, "key", "value");
+        map.entry("bar").string("Bar");
+        map.entry("foo").string("Foo");
+        map.finish();
+        let mut map = writer.prefix("Flattened").start_map(true, "K", "V");
+        map.entry("baz").string("Baz");
+        map.finish();
+        writer.finish();
+
+        let document = QueryDocument::parse(out.as_bytes()).unwrap();
+        let root = document.root();
+        let entries = |entries: Vec<(&str, super::QueryValue<'_>)>| {
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.to_owned(), value.string().unwrap().to_owned()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec![
+                ("bar".to_owned(), "Bar".to_owned()),
+                ("foo".to_owned(), "Foo".to_owned())
+            ],
+            entries(
+                root.get("MapArg")
+                    .unwrap()
+                    .map(false, "key", "value")
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            vec![("baz".to_owned(), "Baz".to_owned())],
+            entries(root.get("Flattened").unwrap().map(true, "K", "V").unwrap())
+        );
+
+        let document = QueryDocument::parse(b"Map.entry.1.key=foo").unwrap();
+        let err = document
+            .root()
+            .get("Map")
+            .unwrap()
+            .map(false, "key", "value")
+            .expect_err("the entry has no value");
+        assert_eq!(
+            "failed to deserialize query: map entry `1` of `Map` has no `value`",
+            err.to_string()
+        );
+    }
+}
@@ -23,6 +23,8 @@ use std::borrow::Cow;
 use std::fmt::Write;
 use urlencoding::encode;
 
+pub mod deserialize;
+
 pub struct QueryWriter<'a> {
     output: &'a mut String,
 }
@@ -5,9 +5,29 @@
 
 //! Types related to connection monitoring and management.
 
-use std::fmt::{Debug, Formatter};
+use std::fmt::{Debug, Display, Formatter};
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
+
+/// The HTTP protocol version spoken on a connection.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HttpVersion {
+    /// HTTP/1.1
+    Http11,
+    /// HTTP/2, negotiated with ALPN or assumed with prior knowledge.
+    Http2,
+}
+
+impl Display for HttpVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpVersion::Http11 => f.write_str("HTTP/1.1"),
+            HttpVersion::Http2 => f.write_str("HTTP/2"),
+        }
+    }
+}
 
 /// Metadata that tracks the state of an active connection.
 #[derive(Clone)]
@@ -15,6 +35,8 @@ pub struct ConnectionMetadata {
     is_proxied: bool,
     remote_addr: Option<SocketAddr>,
     local_addr: Option<SocketAddr>,
+    acquire_duration: Option<Duration>,
+    http_version: Option<HttpVersion>,
     poison_fn: Arc<dyn Fn() + Send + Sync>,
 }
 
@@ -38,8 +60,10 @@ impl ConnectionMetadata {
         Self {
             is_proxied,
             remote_addr,
-            // need to use builder to set this field
+            // need to use builder to set these fields
             local_addr: None,
+            acquire_duration: None,
+            http_version: None,
             poison_fn: Arc::new(poison),
         }
     }
@@ -58,6 +82,19 @@ impl ConnectionMetadata {
     pub fn local_addr(&self) -> Option<SocketAddr> {
         self.local_addr
     }
+
+    /// Get the time the request spent acquiring this connection, if it is known.
+    ///
+    /// This is the time taken to establish the connection when a new one was made for the request,
+    /// and zero when an idle pooled connection was reused.
+    pub fn acquire_duration(&self) -> Option<Duration> {
+        self.acquire_duration
+    }
+
+    /// Get the HTTP protocol version spoken on this connection, if it is known.
+    pub fn http_version(&self) -> Option<HttpVersion> {
+        self.http_version
+    }
 }
 
 impl Debug for ConnectionMetadata {
@@ -66,6 +103,8 @@ impl Debug for ConnectionMetadata {
             .field("is_proxied", &self.is_proxied)
             .field("remote_addr", &self.remote_addr)
             .field("local_addr", &self.local_addr)
+            .field("acquire_duration", &self.acquire_duration)
+            .field("http_version", &self.http_version)
             .finish()
     }
 }
@@ -76,6 +115,8 @@ pub struct ConnectionMetadataBuilder {
     is_proxied: Option<bool>,
     remote_addr: Option<SocketAddr>,
     local_addr: Option<SocketAddr>,
+    acquire_duration: Option<Duration>,
+    http_version: Option<HttpVersion>,
     poison_fn: Option<Arc<dyn Fn() + Send + Sync>>,
 }
 
@@ -85,6 +126,8 @@ impl Debug for ConnectionMetadataBuilder {
             .field("is_proxied", &self.is_proxied)
             .field("remote_addr", &self.remote_addr)
             .field("local_addr", &self.local_addr)
+            .field("acquire_duration", &self.acquire_duration)
+            .field("http_version", &self.http_version)
             .finish()
     }
 }
@@ -131,6 +174,30 @@ impl ConnectionMetadataBuilder {
         self
     }
 
+    /// Set the time the request spent acquiring the connection.
+    pub fn acquire_duration(mut self, acquire_duration: Duration) -> Self {
+        self.set_acquire_duration(Some(acquire_duration));
+        self
+    }
+
+    /// Set the time the request spent acquiring the connection.
+    pub fn set_acquire_duration(&mut self, acquire_duration: Option<Duration>) -> &mut Self {
+        self.acquire_duration = acquire_duration;
+        self
+    }
+
+    /// Set the HTTP protocol version spoken on the connection.
+    pub fn http_version(mut self, http_version: HttpVersion) -> Self {
+        self.set_http_version(Some(http_version));
+        self
+    }
+
+    /// Set the HTTP protocol version spoken on the connection.
+    pub fn set_http_version(&mut self, http_version: Option<HttpVersion>) -> &mut Self {
+        self.http_version = http_version;
+        self
+    }
+
     /// Set a closure which will poison the associated connection.
     ///
     /// A poisoned connection will not be reused for subsequent requests by the pool
@@ -163,6 +230,8 @@ impl ConnectionMetadataBuilder {
                 .expect("is_proxied should be set for ConnectionMetadata"),
             remote_addr: self.remote_addr,
             local_addr: self.local_addr,
+            acquire_duration: self.acquire_duration,
+            http_version: self.http_version,
             poison_fn: self
                 .poison_fn
                 .expect("poison_fn should be set for ConnectionMetadata"),
@@ -209,6 +278,8 @@ mod tests {
             .proxied(true)
             .local_addr(TEST_SOCKET_ADDR)
             .remote_addr(TEST_SOCKET_ADDR)
+            .acquire_duration(Duration::from_millis(5))
+            .http_version(HttpVersion::Http2)
             .poison_fn({
                 let mutable_flag = Arc::clone(&mutable_flag);
                 move || {
@@ -221,6 +292,11 @@ mod tests {
         assert!(connection_metadata.is_proxied);
         assert_eq!(connection_metadata.remote_addr(), Some(TEST_SOCKET_ADDR));
         assert_eq!(connection_metadata.local_addr(), Some(TEST_SOCKET_ADDR));
+        assert_eq!(
+            connection_metadata.acquire_duration(),
+            Some(Duration::from_millis(5))
+        );
+        assert_eq!(connection_metadata.http_version(), Some(HttpVersion::Http2));
         assert!(!(*mutable_flag.lock().unwrap()));
         connection_metadata.poison();
         assert!(*mutable_flag.lock().unwrap());
@@ -235,6 +311,8 @@ mod tests {
 
         assert_eq!(metadata1.local_addr(), None);
         assert_eq!(metadata1.remote_addr(), None);
+        assert_eq!(metadata1.acquire_duration(), None);
+        assert_eq!(metadata1.http_version(), None);
 
         let metadata2 = ConnectionMetadataBuilder::new()
             .proxied(true)
@@ -354,6 +354,25 @@ impl<I, O, E> InterceptorContext<I, O, E> {
         }
     }
 
+    /// Create a context for another attempt of the same request, rewound to the saved request
+    /// checkpoint. This allows sending more than one attempt at the same time.
+    ///
+    /// Returns `None` if the request can't be cloned.
+    ///
+    /// Note: This method is intended for internal use only.
+    pub fn fork_attempt(&self) -> Option<Self> {
+        let request = self.request_checkpoint.as_ref()?.try_clone()?;
+        Some(Self {
+            input: None,
+            output_or_error: None,
+            request_checkpoint: request.try_clone(),
+            request: Some(request),
+            response: None,
+            phase: Phase::BeforeTransmit,
+            tainted: true,
+        })
+    }
+
     /// Returns false if rewinding isn't possible
     ///
     /// Note: This method is intended for internal use only.
@@ -596,6 +615,41 @@ mod tests {
         assert_eq!("output", output.downcast_ref::<String>().unwrap());
     }
 
+    #[test]
+    fn fork_attempt_starts_from_the_checkpoint() {
+        let mut context = InterceptorContext::new(Input::doesnt_matter());
+        context.enter_serialization_phase();
+        let _ = context.take_input();
+        context.set_request(
+            http::Request::builder()
+                .header("test", "original")
+                .body(SdkBody::empty())
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        );
+        context.enter_before_transmit_phase();
+        context.save_checkpoint();
+        context
+            .request_mut()
+            .unwrap()
+            .headers_mut()
+            .insert("test", HeaderValue::from_static("signed"));
+
+        let mut fork = context.fork_attempt().expect("request is cloneable");
+        assert_eq!(
+            "original",
+            fork.request().unwrap().headers().get("test").unwrap()
+        );
+        // The fork can go through a whole attempt of its own
+        fork.enter_transmit_phase();
+        let _ = fork.take_request();
+        assert_eq!(
+            "signed",
+            context.request().unwrap().headers().get("test").unwrap()
+        );
+    }
+
     #[test]
     fn try_clone_clones_all_data() {
         let request: HttpRequest = http::Request::builder()
@@ -51,12 +51,17 @@ impl Storable for SharedRequestSerializer {
 
 impl_shared_conversions!(convert SharedRequestSerializer from SerializeRequest using SharedRequestSerializer::new);
 
+new_type_future! {
+    #[doc = "New-type for the future returned by [`DeserializeResponse::deserialize_incrementally`]."]
+    pub struct DeserializeResponseFuture<'a, Output, OrchestratorError<Error>>;
+}
+
 /// Deserialization implementation that converts an [`HttpResponse`] into an [`Output`] or [`Error`].
 pub trait DeserializeResponse: Send + Sync + fmt::Debug {
     /// For streaming requests, deserializes the response headers.
     ///
     /// The orchestrator will call `deserialize_streaming` first, and if it returns `None`,
-    /// then it will continue onto `deserialize_nonstreaming`. This method should only be
+    /// then it will continue onto `deserialize_incrementally`. This method should only be
     /// implemented for streaming requests where the streaming response body needs to be a part
     /// of the deserialized output.
     fn deserialize_streaming(
@@ -67,6 +72,20 @@ pub trait DeserializeResponse: Send + Sync + fmt::Debug {
         None
     }
 
+    /// Deserializes the response while its body is read, rather than once it has been read in full.
+    ///
+    /// The orchestrator will call `deserialize_incrementally` if `deserialize_streaming` returns
+    /// `None`, and if it returns `None` too, then it will read the whole body and continue onto
+    /// `deserialize_nonstreaming`. This method should only be implemented for responses that are
+    /// large enough for buffering their body to be a concern, such as responses with long lists.
+    fn deserialize_incrementally<'a>(
+        &'a self,
+        response: &'a mut HttpResponse,
+    ) -> Option<DeserializeResponseFuture<'a>> {
+        let _ = response;
+        None
+    }
+
     /// Deserialize the entire response including its body into an output or error.
     fn deserialize_nonstreaming(
         &self,
@@ -101,6 +120,13 @@ impl DeserializeResponse for SharedResponseDeserializer {
     ) -> Option<Result<Output, OrchestratorError<Error>>> {
         self.0.deserialize_streaming(response)
     }
+
+    fn deserialize_incrementally<'a>(
+        &'a self,
+        response: &'a mut HttpResponse,
+    ) -> Option<DeserializeResponseFuture<'a>> {
+        self.0.deserialize_incrementally(response)
+    }
 }
 
 impl Storable for SharedResponseDeserializer {
@@ -6,12 +6,19 @@
 /// Smithy auth scheme implementations.
 pub mod auth;
 
+pub mod circuit_breaker;
+
 pub mod defaults;
 
 pub mod dns;
 
 pub mod endpoint;
 
+pub mod hedging;
+
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
 /// Built-in Smithy HTTP clients and connectors.
 ///
 /// See the [module docs in `aws-smithy-runtime-api`](aws_smithy_runtime_api::client::http)
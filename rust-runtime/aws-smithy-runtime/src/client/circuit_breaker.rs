@@ -0,0 +1,552 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A circuit breaker that stops sending requests to an endpoint that keeps failing.
+//!
+//! The [`CircuitBreaker`] tracks the outcome of every request attempt per endpoint host, using the
+//! client's retry classifiers to decide whether an attempt failed. Server errors and transient
+//! errors (such as timeouts and IO errors) count as failures.
+//!
+//! - While the circuit is **closed**, requests are sent normally. After
+//!   [`failure_threshold`](CircuitBreakerBuilder::failure_threshold) consecutive failures, the
+//!   circuit opens.
+//! - While the circuit is **open**, requests fail immediately with a [`CircuitBreakerOpenError`]
+//!   without being sent. After the [`reset_timeout`](CircuitBreakerBuilder::reset_timeout) has
+//!   elapsed, the circuit becomes half-open.
+//! - While the circuit is **half-open**, a single trial request is sent. If it succeeds, the
+//!   circuit closes; if it fails, the circuit opens again.
+//!
+//! Requests that are short-circuited are not retried.
+//!
+//! The circuit breaker can be installed with the [`CircuitBreakerRuntimePlugin`], or by adding a
+//! [`CircuitBreakerInterceptor`] to a
+//! [`RuntimeComponentsBuilder`](aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder).
+//! Clones of a `CircuitBreaker` share their state, so a single breaker can protect several clients.
+
+use crate::client::retries::classifiers::run_classifiers_on_ctx;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::interceptors::context::{
+    BeforeTransmitInterceptorContextRef, FinalizerInterceptorContextRef,
+};
+use aws_smithy_runtime_api::client::interceptors::{Intercept, SharedInterceptor};
+use aws_smithy_runtime_api::client::retries::classifiers::{RetryAction, RetryReason};
+use aws_smithy_runtime_api::client::runtime_components::{
+    RuntimeComponents, RuntimeComponentsBuilder,
+};
+use aws_smithy_runtime_api::client::runtime_plugin::RuntimePlugin;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+use aws_smithy_types::retry::ErrorKind;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tracing::debug;
+
+const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+const DEFAULT_RESET_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The state of a circuit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CircuitState {
+    /// Requests are sent normally.
+    Closed,
+    /// Requests fail without being sent.
+    Open,
+    /// A trial request may be sent to find out if the endpoint has recovered.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+enum Circuit {
+    Closed { consecutive_failures: u32 },
+    Open { opened_at: SystemTime },
+    HalfOpen { trial_sent_at: SystemTime },
+}
+
+impl Circuit {
+    fn state(&self) -> CircuitState {
+        match self {
+            Circuit::Closed { .. } => CircuitState::Closed,
+            Circuit::Open { .. } => CircuitState::Open,
+            Circuit::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+}
+
+/// A circuit breaker with a circuit for each endpoint host.
+///
+/// See the [module docs](crate::client::circuit_breaker) for more information.
+#[derive(Clone, Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    reset_timeout: Duration,
+    circuits: Arc<Mutex<HashMap<String, Circuit>>>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            reset_timeout: DEFAULT_RESET_TIMEOUT,
+            circuits: Default::default(),
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// Creates a new `CircuitBreaker` that opens after 5 consecutive failures and stays open
+    /// for 30 seconds.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns a builder for a `CircuitBreaker` with a custom configuration.
+    pub fn builder() -> CircuitBreakerBuilder {
+        CircuitBreakerBuilder::default()
+    }
+
+    /// Returns the state of the circuit for the given endpoint host.
+    ///
+    /// Hosts that haven't been sent any requests are [`CircuitState::Closed`].
+    pub fn state(&self, host: &str) -> CircuitState {
+        self.circuits
+            .lock()
+            .unwrap()
+            .get(host)
+            .map(Circuit::state)
+            .unwrap_or(CircuitState::Closed)
+    }
+
+    /// Returns true if a request to `host` may be sent.
+    fn try_acquire(&self, host: &str, now: SystemTime) -> bool {
+        let mut circuits = self.circuits.lock().unwrap();
+        let circuit = match circuits.get_mut(host) {
+            Some(circuit) => circuit,
+            None => return true,
+        };
+        match *circuit {
+            Circuit::Closed { .. } => true,
+            // If the trial request never finished (e.g. because it was cancelled), allow another.
+            Circuit::Open { opened_at: since }
+            | Circuit::HalfOpen {
+                trial_sent_at: since,
+            } if elapsed(since, now) >= self.reset_timeout => {
+                debug!(host, "circuit is half-open; sending a trial request");
+                *circuit = Circuit::HalfOpen { trial_sent_at: now };
+                true
+            }
+            Circuit::Open { .. } | Circuit::HalfOpen { .. } => false,
+        }
+    }
+
+    /// Records the outcome of a request to `host`.
+    fn record(&self, host: &str, failed: bool, now: SystemTime) {
+        let mut circuits = self.circuits.lock().unwrap();
+        let circuit = circuits.entry(host.to_string()).or_insert(Circuit::Closed {
+            consecutive_failures: 0,
+        });
+        match (&*circuit, failed) {
+            (Circuit::Closed { .. }, false) | (Circuit::HalfOpen { .. }, false) => {
+                *circuit = Circuit::Closed {
+                    consecutive_failures: 0,
+                };
+            }
+            (
+                Circuit::Closed {
+                    consecutive_failures,
+                },
+                true,
+            ) => {
+                let consecutive_failures = consecutive_failures + 1;
+                *circuit = if consecutive_failures >= self.failure_threshold {
+                    debug!(host, consecutive_failures, "opening circuit");
+                    Circuit::Open { opened_at: now }
+                } else {
+                    Circuit::Closed {
+                        consecutive_failures,
+                    }
+                };
+            }
+            (Circuit::HalfOpen { .. }, true) => {
+                debug!(host, "trial request failed; opening circuit");
+                *circuit = Circuit::Open { opened_at: now };
+            }
+            // A request sent before the circuit opened; it doesn't change anything
+            (Circuit::Open { .. }, _) => {}
+        }
+    }
+}
+
+/// Builder for [`CircuitBreaker`].
+#[derive(Clone, Debug, Default)]
+pub struct CircuitBreakerBuilder {
+    failure_threshold: Option<u32>,
+    reset_timeout: Option<Duration>,
+}
+
+impl CircuitBreakerBuilder {
+    /// Set the number of consecutive failures that open a circuit. Defaults to 5.
+    ///
+    /// The threshold must be at least 1.
+    pub fn failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.set_failure_threshold(Some(failure_threshold));
+        self
+    }
+
+    /// Set the number of consecutive failures that open a circuit. Defaults to 5.
+    ///
+    /// The threshold must be at least 1.
+    pub fn set_failure_threshold(&mut self, failure_threshold: Option<u32>) -> &mut Self {
+        self.failure_threshold = failure_threshold;
+        self
+    }
+
+    /// Set how long a circuit stays open before a trial request is allowed. Defaults to 30 seconds.
+    pub fn reset_timeout(mut self, reset_timeout: Duration) -> Self {
+        self.set_reset_timeout(Some(reset_timeout));
+        self
+    }
+
+    /// Set how long a circuit stays open before a trial request is allowed. Defaults to 30 seconds.
+    pub fn set_reset_timeout(&mut self, reset_timeout: Option<Duration>) -> &mut Self {
+        self.reset_timeout = reset_timeout;
+        self
+    }
+
+    /// Builds the [`CircuitBreaker`].
+    ///
+    /// Returns an error if the failure threshold is zero.
+    pub fn build(self) -> Result<CircuitBreaker, BuildError> {
+        let failure_threshold = self.failure_threshold.unwrap_or(DEFAULT_FAILURE_THRESHOLD);
+        if failure_threshold == 0 {
+            return Err(BuildError("the failure threshold must be at least 1"));
+        }
+        Ok(CircuitBreaker {
+            failure_threshold,
+            reset_timeout: self.reset_timeout.unwrap_or(DEFAULT_RESET_TIMEOUT),
+            circuits: Default::default(),
+        })
+    }
+}
+
+/// Error returned when a [`CircuitBreakerBuilder`] has an invalid configuration.
+#[derive(Debug)]
+pub struct BuildError(&'static str);
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid circuit breaker configuration: {}", self.0)
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+fn elapsed(since: SystemTime, now: SystemTime) -> Duration {
+    now.duration_since(since).unwrap_or_default()
+}
+
+/// Error returned when a request isn't sent because the circuit for its endpoint host is open.
+#[derive(Debug)]
+pub struct CircuitBreakerOpenError {
+    host: String,
+}
+
+impl CircuitBreakerOpenError {
+    /// The endpoint host whose circuit is open.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+}
+
+impl fmt::Display for CircuitBreakerOpenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the circuit breaker for `{}` is open, so the request was not sent",
+            self.host
+        )
+    }
+}
+
+impl std::error::Error for CircuitBreakerOpenError {}
+
+/// The host of the request sent by the current attempt.
+#[derive(Clone, Debug)]
+struct CircuitHost(String);
+
+impl Storable for CircuitHost {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Interceptor that applies a [`CircuitBreaker`] to every request attempt.
+#[derive(Debug)]
+pub struct CircuitBreakerInterceptor {
+    circuit_breaker: CircuitBreaker,
+}
+
+impl CircuitBreakerInterceptor {
+    /// Creates a new `CircuitBreakerInterceptor` using the given circuit breaker.
+    pub fn new(circuit_breaker: CircuitBreaker) -> Self {
+        Self { circuit_breaker }
+    }
+}
+
+fn now(runtime_components: &RuntimeComponents) -> Result<SystemTime, BoxError> {
+    Ok(runtime_components
+        .time_source()
+        .ok_or("A time source is required when a circuit breaker is used")?
+        .now())
+}
+
+impl Intercept for CircuitBreakerInterceptor {
+    fn name(&self) -> &'static str {
+        "CircuitBreakerInterceptor"
+    }
+
+    fn read_before_transmit(
+        &self,
+        context: &BeforeTransmitInterceptorContextRef<'_>,
+        runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let uri = context.request().uri();
+        let host = match uri.parse::<http::Uri>() {
+            Ok(uri) => match uri.authority() {
+                Some(authority) => authority.as_str().to_string(),
+                None => uri.to_string(),
+            },
+            Err(_) => uri.to_string(),
+        };
+
+        if !self
+            .circuit_breaker
+            .try_acquire(&host, now(runtime_components)?)
+        {
+            return Err(CircuitBreakerOpenError { host }.into());
+        }
+        cfg.interceptor_state().store_put(CircuitHost(host));
+        Ok(())
+    }
+
+    fn read_after_attempt(
+        &self,
+        context: &FinalizerInterceptorContextRef<'_>,
+        runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        // Attempts that were short-circuited or never got as far as sending a request aren't counted
+        let host = match cfg.load::<CircuitHost>() {
+            Some(CircuitHost(host)) => host.clone(),
+            None => return Ok(()),
+        };
+        cfg.interceptor_state().unset::<CircuitHost>();
+
+        let failed = matches!(
+            run_classifiers_on_ctx(runtime_components.retry_classifiers(), context.inner()),
+            RetryAction::RetryIndicated(RetryReason::RetryableError {
+                kind: ErrorKind::ServerError | ErrorKind::TransientError,
+                ..
+            })
+        );
+        self.circuit_breaker
+            .record(&host, failed, now(runtime_components)?);
+        Ok(())
+    }
+}
+
+/// Runtime plugin that installs a [`CircuitBreakerInterceptor`].
+#[derive(Debug)]
+pub struct CircuitBreakerRuntimePlugin {
+    runtime_components: RuntimeComponentsBuilder,
+}
+
+impl CircuitBreakerRuntimePlugin {
+    /// Creates a new `CircuitBreakerRuntimePlugin` using the given circuit breaker.
+    pub fn new(circuit_breaker: CircuitBreaker) -> Self {
+        Self {
+            runtime_components: RuntimeComponentsBuilder::new("CircuitBreakerRuntimePlugin")
+                .with_interceptor(SharedInterceptor::new(CircuitBreakerInterceptor::new(
+                    circuit_breaker,
+                ))),
+        }
+    }
+}
+
+impl RuntimePlugin for CircuitBreakerRuntimePlugin {
+    fn runtime_components(
+        &self,
+        _: &RuntimeComponentsBuilder,
+    ) -> Cow<'_, RuntimeComponentsBuilder> {
+        Cow::Borrowed(&self.runtime_components)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CircuitBreaker, CircuitState};
+    use std::time::{Duration, SystemTime};
+
+    const HOST: &str = "example.com";
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn opens_after_consecutive_failures() {
+        let breaker = CircuitBreaker::builder()
+            .failure_threshold(3)
+            .build()
+            .unwrap();
+        breaker.record(HOST, true, at(0));
+        breaker.record(HOST, true, at(0));
+        // A success resets the count
+        breaker.record(HOST, false, at(0));
+        breaker.record(HOST, true, at(0));
+        breaker.record(HOST, true, at(0));
+        assert_eq!(CircuitState::Closed, breaker.state(HOST));
+        assert!(breaker.try_acquire(HOST, at(0)));
+
+        breaker.record(HOST, true, at(0));
+        assert_eq!(CircuitState::Open, breaker.state(HOST));
+        assert!(!breaker.try_acquire(HOST, at(1)));
+    }
+
+    #[test]
+    fn zero_failure_threshold_is_rejected() {
+        let err = CircuitBreaker::builder()
+            .failure_threshold(0)
+            .build()
+            .expect_err("invalid threshold");
+        assert!(err.to_string().contains("at least 1"), "{err}");
+    }
+
+    #[test]
+    fn circuits_are_per_host() {
+        let breaker = CircuitBreaker::builder()
+            .failure_threshold(1)
+            .build()
+            .unwrap();
+        breaker.record(HOST, true, at(0));
+        assert!(!breaker.try_acquire(HOST, at(0)));
+        assert!(breaker.try_acquire("other.example.com", at(0)));
+        assert_eq!(CircuitState::Closed, breaker.state("other.example.com"));
+    }
+
+    #[test]
+    fn half_open_allows_a_single_trial() {
+        let breaker = CircuitBreaker::builder()
+            .failure_threshold(1)
+            .reset_timeout(Duration::from_secs(10))
+            .build()
+            .unwrap();
+        breaker.record(HOST, true, at(0));
+        assert!(!breaker.try_acquire(HOST, at(9)));
+
+        assert!(breaker.try_acquire(HOST, at(10)));
+        assert_eq!(CircuitState::HalfOpen, breaker.state(HOST));
+        assert!(!breaker.try_acquire(HOST, at(11)));
+
+        // The trial failed, so the circuit opens again
+        breaker.record(HOST, true, at(12));
+        assert_eq!(CircuitState::Open, breaker.state(HOST));
+        assert!(!breaker.try_acquire(HOST, at(21)));
+
+        // The next trial succeeds, so the circuit closes
+        assert!(breaker.try_acquire(HOST, at(22)));
+        breaker.record(HOST, false, at(23));
+        assert_eq!(CircuitState::Closed, breaker.state(HOST));
+        assert!(breaker.try_acquire(HOST, at(23)));
+    }
+
+    #[test]
+    fn abandoned_trials_are_replaced() {
+        let breaker = CircuitBreaker::builder()
+            .failure_threshold(1)
+            .reset_timeout(Duration::from_secs(10))
+            .build()
+            .unwrap();
+        breaker.record(HOST, true, at(0));
+        assert!(breaker.try_acquire(HOST, at(10)));
+        assert!(!breaker.try_acquire(HOST, at(19)));
+        assert!(breaker.try_acquire(HOST, at(20)));
+    }
+
+    #[test]
+    fn clones_share_state() {
+        let breaker = CircuitBreaker::builder()
+            .failure_threshold(1)
+            .build()
+            .unwrap();
+        let clone = breaker.clone();
+        clone.record(HOST, true, at(0));
+        assert_eq!(CircuitState::Open, breaker.state(HOST));
+    }
+
+    #[cfg(feature = "test-util")]
+    #[tokio::test]
+    async fn open_circuits_short_circuit_operations() {
+        use super::{CircuitBreakerOpenError, CircuitBreakerRuntimePlugin};
+        use crate::client::http::test_util::infallible_client_fn;
+        use crate::client::orchestrator::operation::Operation;
+        use crate::client::retries::classifiers::HttpStatusCodeClassifier;
+        use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, OrchestratorError};
+        use aws_smithy_types::body::SdkBody;
+        use aws_smithy_types::timeout::TimeoutConfig;
+        use std::convert::Infallible;
+        use std::error::Error as _;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let http_client = infallible_client_fn({
+            let requests = requests.clone();
+            move |_| {
+                requests.fetch_add(1, Ordering::SeqCst);
+                http::Response::builder()
+                    .status(503)
+                    .body(SdkBody::empty())
+                    .unwrap()
+            }
+        });
+        let breaker = CircuitBreaker::builder()
+            .failure_threshold(2)
+            .build()
+            .unwrap();
+        let operation = Operation::builder()
+            .service_name("test")
+            .operation_name("test")
+            .http_client(http_client)
+            .endpoint_url("http://localhost:1234")
+            .no_auth()
+            .no_retry()
+            .retry_classifier(HttpStatusCodeClassifier::default())
+            .timeout_config(TimeoutConfig::disabled())
+            .runtime_plugin(CircuitBreakerRuntimePlugin::new(breaker.clone()))
+            .serializer(|_: ()| Ok(HttpRequest::empty()))
+            .deserializer::<(), Infallible>(|_| {
+                Err(OrchestratorError::other("service unavailable"))
+            })
+            .build();
+
+        operation.invoke(()).await.expect_err("503");
+        operation.invoke(()).await.expect_err("503");
+        assert_eq!(CircuitState::Open, breaker.state("localhost:1234"));
+
+        let err = operation.invoke(()).await.expect_err("circuit is open");
+        assert_eq!(2, requests.load(Ordering::SeqCst));
+        let mut source = err.source();
+        while let Some(err) = source {
+            if let Some(err) = err.downcast_ref::<CircuitBreakerOpenError>() {
+                assert_eq!("localhost:1234", err.host());
+                return;
+            }
+            source = err.source();
+        }
+        panic!("expected a CircuitBreakerOpenError, got {err:?}");
+    }
+}
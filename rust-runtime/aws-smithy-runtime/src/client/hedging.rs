@@ -0,0 +1,329 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Hedged requests reduce tail latency by sending a second copy of a slow request.
+//!
+//! When hedging is enabled with [`HedgingConfig::enabled`] and an attempt hasn't received a
+//! response after the configured delay, the orchestrator starts another attempt of the same
+//! request. Hedged attempts go through the same steps as any other attempt, so interceptors,
+//! endpoint resolution, signing, and deserialization all run for them. Whichever attempt succeeds
+//! first is used, and the other one is cancelled.
+//!
+//! Hedging only applies to operations marked with [`IdempotentOperation`], since the service may
+//! receive both requests. Generated clients mark operations with the `@readonly` or `@idempotent`
+//! traits automatically.
+//!
+//! A hedged attempt counts towards the [`RequestAttempts`] of the operation, so it uses up one of
+//! the attempts allowed by the retry config. If a [`TokenBucket`] is configured, a hedged attempt
+//! costs the same as a retry. Those permits are returned once an attempt succeeds, but are kept
+//! if both attempts fail, so that hedging stops when a service is unhealthy.
+
+use crate::client::retries::TokenBucket;
+use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep};
+use aws_smithy_runtime_api::client::retries::RequestAttempts;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+use aws_smithy_types::retry::{ErrorKind, RetryConfig};
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::task::Poll;
+use std::time::Duration;
+use tracing::debug;
+
+/// Configuration for hedged requests.
+///
+/// Hedging is disabled by default.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HedgingConfig {
+    delay: Option<Duration>,
+}
+
+impl HedgingConfig {
+    /// Send a hedged request if an attempt hasn't received a response after `delay`.
+    pub fn enabled(delay: Duration) -> Self {
+        Self { delay: Some(delay) }
+    }
+
+    /// Never send hedged requests.
+    pub fn disabled() -> Self {
+        Self { delay: None }
+    }
+
+    /// Returns true if hedging is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.delay.is_some()
+    }
+
+    /// Returns how long an attempt waits for a response before a hedged request is sent.
+    pub fn delay(&self) -> Option<Duration> {
+        self.delay
+    }
+}
+
+impl Storable for HedgingConfig {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Marks an operation as safe to send more than once, which is required for hedging.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct IdempotentOperation;
+
+impl IdempotentOperation {
+    /// Creates a new `IdempotentOperation` marker.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Storable for IdempotentOperation {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Which attempt a [`HedgedCall`] ended with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HedgeOutcome {
+    /// The primary attempt finished before a hedged attempt was started.
+    NotHedged,
+    /// A hedged attempt was started, but the primary attempt's result is used.
+    PrimaryWon,
+    /// The hedged attempt's result is used.
+    HedgeWon,
+}
+
+/// A request attempt that may be hedged.
+#[derive(Debug)]
+pub(crate) struct HedgedCall {
+    delay: Duration,
+    sleep_impl: SharedAsyncSleep,
+    token_bucket: Option<TokenBucket>,
+}
+
+impl HedgedCall {
+    /// Returns a `HedgedCall` if hedging is enabled for the current operation and attempt.
+    pub(crate) fn from_config(
+        runtime_components: &RuntimeComponents,
+        cfg: &ConfigBag,
+    ) -> Option<Self> {
+        let delay = cfg.load::<HedgingConfig>()?.delay()?;
+        cfg.load::<IdempotentOperation>()?;
+        let request_attempts = cfg
+            .load::<RequestAttempts>()
+            .map(RequestAttempts::attempts)
+            .unwrap_or(1);
+        if let Some(retry_config) = cfg.load::<RetryConfig>() {
+            if request_attempts >= retry_config.max_attempts() {
+                debug!("not hedging because there are no attempts left");
+                return None;
+            }
+        }
+        let sleep_impl = runtime_components.sleep_impl()?;
+        Some(Self {
+            delay,
+            sleep_impl,
+            token_bucket: cfg.load::<TokenBucket>().cloned(),
+        })
+    }
+
+    /// Runs the `primary` attempt, starting the `hedge` attempt if the primary attempt hasn't
+    /// finished after the delay.
+    ///
+    /// Both futures resolve to whether their attempt succeeded. The first attempt to succeed wins.
+    /// If one attempt fails, the other one is awaited, and if both fail, the one that failed last
+    /// wins. The attempt that didn't win is cancelled when its future is dropped.
+    pub(crate) async fn race(
+        self,
+        primary: impl Future<Output = bool>,
+        hedge: impl Future<Output = bool>,
+    ) -> HedgeOutcome {
+        let mut primary = pin!(primary);
+        let mut sleep = pin!(self.sleep_impl.sleep(self.delay));
+        let primary_finished = poll_fn(|cx| {
+            if primary.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(true);
+            }
+            sleep.as_mut().poll(cx).map(|_| false)
+        })
+        .await;
+        if primary_finished {
+            return HedgeOutcome::NotHedged;
+        }
+
+        let permit = match &self.token_bucket {
+            Some(token_bucket) => match token_bucket.acquire(&ErrorKind::ServerError) {
+                Some(permit) => Some(permit),
+                None => {
+                    debug!("not hedging because no retry permits are available");
+                    primary.await;
+                    return HedgeOutcome::NotHedged;
+                }
+            },
+            None => None,
+        };
+        debug!(delay = ?self.delay, "no response received yet; starting a hedged attempt");
+        let mut hedge = pin!(hedge);
+
+        let (mut primary_done, mut hedge_done) = (false, false);
+        let (outcome, succeeded) = poll_fn(|cx| {
+            if !primary_done {
+                if let Poll::Ready(succeeded) = primary.as_mut().poll(cx) {
+                    primary_done = true;
+                    if succeeded || hedge_done {
+                        return Poll::Ready((HedgeOutcome::PrimaryWon, succeeded));
+                    }
+                    debug!("primary attempt failed; waiting for the hedged attempt");
+                }
+            }
+            if !hedge_done {
+                if let Poll::Ready(succeeded) = hedge.as_mut().poll(cx) {
+                    hedge_done = true;
+                    if succeeded || primary_done {
+                        return Poll::Ready((HedgeOutcome::HedgeWon, succeeded));
+                    }
+                    debug!("hedged attempt failed; waiting for the primary attempt");
+                }
+            }
+            Poll::Pending
+        })
+        .await;
+
+        if let (Some(token_bucket), Some(permit)) = (&self.token_bucket, permit) {
+            if succeeded {
+                token_bucket.release(permit);
+            } else {
+                permit.forget();
+            }
+        }
+        outcome
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::{HedgeOutcome, HedgedCall, HedgingConfig, IdempotentOperation};
+    use crate::client::retries::TokenBucket;
+    use aws_smithy_async::rt::sleep::{SharedAsyncSleep, TokioSleep};
+    use aws_smithy_runtime_api::client::retries::RequestAttempts;
+    use aws_smithy_runtime_api::client::runtime_components::{
+        RuntimeComponents, RuntimeComponentsBuilder,
+    };
+    use aws_smithy_types::config_bag::{ConfigBag, Layer};
+    use aws_smithy_types::retry::RetryConfig;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// An attempt that finishes after `delay_millis`, counting how many attempts were started.
+    async fn attempt(started: &AtomicUsize, delay_millis: u64, succeeds: bool) -> bool {
+        started.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(delay_millis)).await;
+        succeeds
+    }
+
+    fn set_up(layer: Layer) -> (RuntimeComponents, ConfigBag) {
+        let rc = RuntimeComponentsBuilder::for_tests()
+            .with_sleep_impl(Some(SharedAsyncSleep::new(TokioSleep::new())))
+            .build()
+            .unwrap();
+        let mut base = Layer::new("hedging");
+        base.store_put(HedgingConfig::enabled(Duration::from_millis(100)));
+        base.store_put(IdempotentOperation::new());
+        base.store_put(RetryConfig::standard());
+        base.store_put(RequestAttempts::new(1));
+        (rc, ConfigBag::of_layers(vec![base, layer]))
+    }
+
+    fn with_token_bucket(token_bucket: &TokenBucket) -> HedgedCall {
+        let mut layer = Layer::new("test");
+        layer.store_put(token_bucket.clone());
+        let (rc, cfg) = set_up(layer);
+        HedgedCall::from_config(&rc, &cfg).unwrap()
+    }
+
+    #[test]
+    fn hedging_requires_config_idempotency_and_remaining_attempts() {
+        let (rc, cfg) = set_up(Layer::new("enabled"));
+        assert!(HedgedCall::from_config(&rc, &cfg).is_some());
+
+        let mut layer = Layer::new("disabled");
+        layer.store_put(HedgingConfig::disabled());
+        let (rc, cfg) = set_up(layer);
+        assert!(HedgedCall::from_config(&rc, &cfg).is_none());
+
+        let mut layer = Layer::new("not idempotent");
+        layer.unset::<IdempotentOperation>();
+        let (rc, cfg) = set_up(layer);
+        assert!(HedgedCall::from_config(&rc, &cfg).is_none());
+
+        let mut layer = Layer::new("out of attempts");
+        layer.store_put(RequestAttempts::new(3));
+        let (rc, cfg) = set_up(layer);
+        assert!(HedgedCall::from_config(&rc, &cfg).is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fast_attempts_are_not_hedged() {
+        let (rc, cfg) = set_up(Layer::new("test"));
+        let started = AtomicUsize::new(0);
+
+        let call = HedgedCall::from_config(&rc, &cfg).unwrap();
+        let outcome = call
+            .race(attempt(&started, 50, true), attempt(&started, 50, true))
+            .await;
+        assert_eq!(HedgeOutcome::NotHedged, outcome);
+        assert_eq!(1, started.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn hedged_attempt_wins_when_the_first_is_slow() {
+        let token_bucket = TokenBucket::new(10);
+        let started = AtomicUsize::new(0);
+
+        let outcome = with_token_bucket(&token_bucket)
+            .race(attempt(&started, 10_000, true), attempt(&started, 50, true))
+            .await;
+        assert_eq!(HedgeOutcome::HedgeWon, outcome);
+        assert_eq!(2, started.load(Ordering::SeqCst));
+        // The permits are returned once an attempt succeeds
+        assert_eq!(10, token_bucket.available_permits());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failed_attempt_waits_for_the_other() {
+        let token_bucket = TokenBucket::new(10);
+        let started = AtomicUsize::new(0);
+
+        let outcome = with_token_bucket(&token_bucket)
+            .race(attempt(&started, 500, true), attempt(&started, 0, false))
+            .await;
+        assert_eq!(HedgeOutcome::PrimaryWon, outcome);
+        assert_eq!(10, token_bucket.available_permits());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failed_hedges_keep_their_permits() {
+        let token_bucket = TokenBucket::new(10);
+        let started = AtomicUsize::new(0);
+
+        let outcome = with_token_bucket(&token_bucket)
+            .race(attempt(&started, 200, false), attempt(&started, 50, false))
+            .await;
+        // The attempt that failed last is used
+        assert_eq!(HedgeOutcome::PrimaryWon, outcome);
+        assert_eq!(2, started.load(Ordering::SeqCst));
+        assert_eq!(5, token_bucket.available_permits());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn no_hedge_without_retry_quota() {
+        let token_bucket = TokenBucket::new(4);
+        let started = AtomicUsize::new(0);
+
+        let outcome = with_token_bucket(&token_bucket)
+            .race(attempt(&started, 1_000, true), attempt(&started, 50, true))
+            .await;
+        assert_eq!(HedgeOutcome::NotHedged, outcome);
+        assert_eq!(1, started.load(Ordering::SeqCst));
+    }
+}
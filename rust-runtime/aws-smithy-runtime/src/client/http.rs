@@ -11,10 +11,21 @@ pub mod test_util;
 
 /// Default HTTP and TLS connectors that use hyper 0.14.x and rustls.
 ///
-/// This module is named after the hyper version number since we anticipate
-/// needing to provide equivalent functionality for hyper 1.x in the future.
+/// This module is named after the hyper version number since equivalent functionality
+/// for hyper 1.x is provided by [`hyper_1`].
 #[cfg(feature = "connector-hyper-0-14-x")]
 pub mod hyper_014;
 
+/// HTTP clients and connectors that use hyper 1.x, with rustls or native TLS.
+///
+/// Custom client builders and TCP connectors are `hyper_util` 0.1, `hyper` 1.x and `http` 1.x
+/// types, so these versions are part of the public API of this module.
+#[cfg(feature = "connector-hyper-1-x")]
+pub mod hyper_1;
+
+/// HTTP/2 settings shared by the hyper 0.14.x and 1.x clients.
+#[cfg(any(feature = "connector-hyper-0-14-x", feature = "connector-hyper-1-x"))]
+mod http2;
+
 /// HTTP body and body-wrapper types
 pub mod body;
@@ -0,0 +1,141 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::time::Duration;
+
+/// HTTP/2 settings for a hyper client.
+///
+/// HTTPS connectors that advertise HTTP/2 with ALPN, like the default rustls connector, speak
+/// HTTP/2 with servers that select it and HTTP/1.1 with everyone else. These settings tune the
+/// HTTP/2 connections, and can force HTTP/2 on every connection with
+/// [`prior_knowledge`](Http2SettingsBuilder::prior_knowledge).
+#[derive(Clone, Default, Debug)]
+pub struct Http2Settings {
+    prior_knowledge: bool,
+    keep_alive_interval: Option<Duration>,
+    keep_alive_timeout: Option<Duration>,
+    keep_alive_while_idle: bool,
+}
+
+impl Http2Settings {
+    /// Returns a builder for `Http2Settings`.
+    pub fn builder() -> Http2SettingsBuilder {
+        Default::default()
+    }
+
+    /// Returns whether every connection speaks HTTP/2 without negotiating it first.
+    pub fn prior_knowledge(&self) -> bool {
+        self.prior_knowledge
+    }
+
+    /// Returns the interval between HTTP/2 keep-alive pings.
+    pub fn keep_alive_interval(&self) -> Option<Duration> {
+        self.keep_alive_interval
+    }
+
+    /// Returns how long to wait for a keep-alive ping to be acknowledged.
+    pub fn keep_alive_timeout(&self) -> Option<Duration> {
+        self.keep_alive_timeout
+    }
+
+    /// Returns whether keep-alive pings are sent on idle connections.
+    pub fn keep_alive_while_idle(&self) -> bool {
+        self.keep_alive_while_idle
+    }
+}
+
+/// Builder for [`Http2Settings`].
+#[derive(Default, Debug)]
+pub struct Http2SettingsBuilder {
+    prior_knowledge: bool,
+    keep_alive_interval: Option<Duration>,
+    keep_alive_timeout: Option<Duration>,
+    keep_alive_while_idle: bool,
+}
+
+impl Http2SettingsBuilder {
+    /// Creates a new builder.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Speak HTTP/2 on every connection without negotiating it first.
+    ///
+    /// This is needed for HTTP/2 over plaintext connections (h2c), such as to a local test server.
+    /// Servers that don't speak HTTP/2 will fail every request.
+    pub fn prior_knowledge(mut self, prior_knowledge: bool) -> Self {
+        self.prior_knowledge = prior_knowledge;
+        self
+    }
+
+    /// Speak HTTP/2 on every connection without negotiating it first.
+    ///
+    /// This is needed for HTTP/2 over plaintext connections (h2c), such as to a local test server.
+    /// Servers that don't speak HTTP/2 will fail every request.
+    pub fn set_prior_knowledge(&mut self, prior_knowledge: bool) -> &mut Self {
+        self.prior_knowledge = prior_knowledge;
+        self
+    }
+
+    /// Send HTTP/2 keep-alive pings at this interval.
+    ///
+    /// Keep-alive pings are disabled by default.
+    pub fn keep_alive_interval(mut self, keep_alive_interval: Duration) -> Self {
+        self.keep_alive_interval = Some(keep_alive_interval);
+        self
+    }
+
+    /// Send HTTP/2 keep-alive pings at this interval.
+    ///
+    /// Keep-alive pings are disabled by default.
+    pub fn set_keep_alive_interval(&mut self, keep_alive_interval: Option<Duration>) -> &mut Self {
+        self.keep_alive_interval = keep_alive_interval;
+        self
+    }
+
+    /// Close the connection if a keep-alive ping isn't acknowledged within this timeout.
+    ///
+    /// Defaults to 20 seconds. Has no effect unless a keep-alive interval is set.
+    pub fn keep_alive_timeout(mut self, keep_alive_timeout: Duration) -> Self {
+        self.keep_alive_timeout = Some(keep_alive_timeout);
+        self
+    }
+
+    /// Close the connection if a keep-alive ping isn't acknowledged within this timeout.
+    ///
+    /// Defaults to 20 seconds. Has no effect unless a keep-alive interval is set.
+    pub fn set_keep_alive_timeout(&mut self, keep_alive_timeout: Option<Duration>) -> &mut Self {
+        self.keep_alive_timeout = keep_alive_timeout;
+        self
+    }
+
+    /// Send keep-alive pings on connections with no open streams.
+    ///
+    /// By default, pings are only sent while a request is in flight. Has no effect unless a
+    /// keep-alive interval is set.
+    pub fn keep_alive_while_idle(mut self, keep_alive_while_idle: bool) -> Self {
+        self.keep_alive_while_idle = keep_alive_while_idle;
+        self
+    }
+
+    /// Send keep-alive pings on connections with no open streams.
+    ///
+    /// By default, pings are only sent while a request is in flight. Has no effect unless a
+    /// keep-alive interval is set.
+    pub fn set_keep_alive_while_idle(&mut self, keep_alive_while_idle: bool) -> &mut Self {
+        self.keep_alive_while_idle = keep_alive_while_idle;
+        self
+    }
+
+    /// Builds the [`Http2Settings`].
+    pub fn build(self) -> Http2Settings {
+        Http2Settings {
+            prior_knowledge: self.prior_knowledge,
+            keep_alive_interval: self.keep_alive_interval,
+            keep_alive_timeout: self.keep_alive_timeout,
+            keep_alive_while_idle: self.keep_alive_while_idle,
+        }
+    }
+}
@@ -7,8 +7,9 @@ use crate::client::http::connection_poisoning::CaptureSmithyConnection;
 use crate::client::http::hyper_014::timeout_middleware::HttpTimeoutError;
 use aws_smithy_async::future::timeout::TimedOutError;
 use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep, SharedAsyncSleep};
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
 use aws_smithy_runtime_api::box_error::BoxError;
-use aws_smithy_runtime_api::client::connection::ConnectionMetadata;
+use aws_smithy_runtime_api::client::connection::{ConnectionMetadata, HttpVersion};
 use aws_smithy_runtime_api::client::http::{
     HttpClient, HttpConnector, HttpConnectorFuture, HttpConnectorSettings, SharedHttpClient,
     SharedHttpConnector,
@@ -31,9 +32,13 @@ use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 use std::fmt::Debug;
-use std::sync::RwLock;
-use std::time::Duration;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
 use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::Semaphore;
+
+pub use crate::client::http::http2::{Http2Settings, Http2SettingsBuilder};
 
 #[cfg(feature = "tls-rustls")]
 mod default_connector {
@@ -158,7 +163,10 @@ impl HttpConnector for HyperConnector {
 pub struct HyperConnectorBuilder {
     connector_settings: Option<HttpConnectorSettings>,
     sleep_impl: Option<SharedAsyncSleep>,
+    time_source: Option<SharedTimeSource>,
     client_builder: Option<hyper_0_14::client::Builder>,
+    http2_settings: Option<Http2Settings>,
+    request_limit: Option<Arc<Semaphore>>,
 }
 
 impl HyperConnectorBuilder {
@@ -171,13 +179,17 @@ impl HyperConnectorBuilder {
         C::Future: Unpin + Send + 'static,
         C::Error: Into<BoxError>,
     {
-        let client_builder = self.client_builder.unwrap_or_default();
+        let mut client_builder = self.client_builder.unwrap_or_default();
+        let http2_settings = self.http2_settings.unwrap_or_default();
+        apply_http2_settings(&http2_settings, &mut client_builder);
         let sleep_impl = self.sleep_impl.or_else(default_async_sleep);
         let (connect_timeout, read_timeout) = self
             .connector_settings
             .map(|c| (c.connect_timeout(), c.read_timeout()))
             .unwrap_or((None, None));
 
+        let time_source = self.time_source.unwrap_or_default();
+        let tcp_connector = connect_timing::TimedConnector::new(tcp_connector, time_source.clone());
         let connector = match connect_timeout {
             Some(duration) => timeout_middleware::ConnectTimeout::new(
                 tcp_connector,
@@ -200,6 +212,9 @@ impl HyperConnectorBuilder {
         HyperConnector {
             adapter: Box::new(Adapter {
                 client: read_timeout,
+                time_source,
+                http2_prior_knowledge: http2_settings.prior_knowledge(),
+                request_limit: self.request_limit,
             }),
         }
     }
@@ -228,6 +243,22 @@ impl HyperConnectorBuilder {
         self
     }
 
+    /// Set the time source used to measure how long requests wait for a connection
+    ///
+    /// Defaults to the system clock.
+    pub fn time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.time_source = Some(time_source.into_shared());
+        self
+    }
+
+    /// Set the time source used to measure how long requests wait for a connection
+    ///
+    /// Defaults to the system clock.
+    pub fn set_time_source(&mut self, time_source: Option<SharedTimeSource>) -> &mut Self {
+        self.time_source = time_source;
+        self
+    }
+
     /// Configure the HTTP settings for the `HyperAdapter`
     pub fn connector_settings(mut self, connector_settings: HttpConnectorSettings) -> Self {
         self.connector_settings = Some(connector_settings);
@@ -261,6 +292,60 @@ impl HyperConnectorBuilder {
         self.client_builder = hyper_builder;
         self
     }
+
+    /// Configure HTTP/2 for this connector.
+    ///
+    /// These settings are applied on top of the [`hyper_builder`](Self::hyper_builder).
+    pub fn http2_settings(mut self, http2_settings: Http2Settings) -> Self {
+        self.http2_settings = Some(http2_settings);
+        self
+    }
+
+    /// Configure HTTP/2 for this connector.
+    ///
+    /// These settings are applied on top of the [`hyper_builder`](Self::hyper_builder).
+    pub fn set_http2_settings(&mut self, http2_settings: Option<Http2Settings>) -> &mut Self {
+        self.http2_settings = http2_settings;
+        self
+    }
+
+    /// Limit the number of requests this connector has in flight at once.
+    ///
+    /// The limit applies to every request sent by the connector, over HTTP/1.1 and HTTP/2 alike.
+    /// Requests over the limit wait until an earlier response body has been dropped. There is no
+    /// limit by default.
+    pub fn max_concurrent_requests(mut self, max_concurrent_requests: NonZeroUsize) -> Self {
+        self.set_max_concurrent_requests(Some(max_concurrent_requests));
+        self
+    }
+
+    /// Limit the number of requests this connector has in flight at once.
+    ///
+    /// The limit applies to every request sent by the connector, over HTTP/1.1 and HTTP/2 alike.
+    /// Requests over the limit wait until an earlier response body has been dropped. There is no
+    /// limit by default.
+    pub fn set_max_concurrent_requests(
+        &mut self,
+        max_concurrent_requests: Option<NonZeroUsize>,
+    ) -> &mut Self {
+        self.request_limit = max_concurrent_requests.map(request_limit::semaphore);
+        self
+    }
+}
+
+fn apply_http2_settings(settings: &Http2Settings, builder: &mut hyper_0_14::client::Builder) {
+    if settings.prior_knowledge() {
+        builder.http2_only(true);
+    }
+    if let Some(interval) = settings.keep_alive_interval() {
+        builder.http2_keep_alive_interval(interval);
+    }
+    if let Some(timeout) = settings.keep_alive_timeout() {
+        builder.http2_keep_alive_timeout(timeout);
+    }
+    if settings.keep_alive_while_idle() {
+        builder.http2_keep_alive_while_idle(true);
+    }
 }
 
 /// Adapter from a [`hyper_0_14::Client`] to [`HttpConnector`].
@@ -268,8 +353,14 @@ impl HyperConnectorBuilder {
 /// This adapter also enables TCP `CONNECT` and HTTP `READ` timeouts via [`HyperConnector::builder`].
 struct Adapter<C> {
     client: timeout_middleware::HttpReadTimeout<
-        hyper_0_14::Client<timeout_middleware::ConnectTimeout<C>, SdkBody>,
+        hyper_0_14::Client<
+            timeout_middleware::ConnectTimeout<connect_timing::TimedConnector<C>>,
+            SdkBody,
+        >,
     >,
+    http2_prior_knowledge: bool,
+    request_limit: Option<Arc<Semaphore>>,
+    time_source: SharedTimeSource,
 }
 
 impl<C> fmt::Debug for Adapter<C> {
@@ -281,14 +372,29 @@ impl<C> fmt::Debug for Adapter<C> {
 }
 
 /// Extract a smithy connection from a hyper CaptureConnection
-fn extract_smithy_connection(capture_conn: &CaptureConnection) -> Option<ConnectionMetadata> {
+///
+/// `request_started` is when the request was handed to hyper, and is used to work out how long the
+/// request waited for its connection. `http2_prior_knowledge` is whether the client speaks HTTP/2
+/// without negotiating it.
+fn extract_smithy_connection(
+    capture_conn: &CaptureConnection,
+    request_started: SystemTime,
+    http2_prior_knowledge: bool,
+) -> Option<ConnectionMetadata> {
     let capture_conn = capture_conn.clone();
     if let Some(conn) = capture_conn.clone().connection_metadata().as_ref() {
         let mut extensions = Extensions::new();
         conn.get_extras(&mut extensions);
         let http_info = extensions.get::<HttpInfo>();
+        let established = extensions.get::<connect_timing::ConnectionEstablished>();
+        let http_version = if http2_prior_knowledge || conn.is_negotiated_h2() {
+            HttpVersion::Http2
+        } else {
+            HttpVersion::Http11
+        };
         let mut builder = ConnectionMetadata::builder()
             .proxied(conn.is_proxied())
+            .http_version(http_version)
             .poison_fn(move || match capture_conn.connection_metadata().as_ref() {
                 Some(conn) => conn.poison(),
                 None => tracing::trace!("no connection existed to poison"),
@@ -296,7 +402,10 @@ fn extract_smithy_connection(capture_conn: &CaptureConnection) -> Option<Connect
 
         builder
             .set_local_addr(http_info.map(|info| info.local_addr()))
-            .set_remote_addr(http_info.map(|info| info.remote_addr()));
+            .set_remote_addr(http_info.map(|info| info.remote_addr()))
+            .set_acquire_duration(
+                established.map(|established| established.acquire_duration(request_started)),
+            );
 
         let smithy_connection = builder.build();
 
@@ -325,16 +434,39 @@ where
         if let Some(capture_smithy_connection) =
             request.extensions().get::<CaptureSmithyConnection>()
         {
-            capture_smithy_connection
-                .set_connection_retriever(move || extract_smithy_connection(&capture_connection));
+            let request_started = self.time_source.now();
+            let http2_prior_knowledge = self.http2_prior_knowledge;
+            capture_smithy_connection.set_connection_retriever(move || {
+                extract_smithy_connection(
+                    &capture_connection,
+                    request_started,
+                    http2_prior_knowledge,
+                )
+            });
         }
         let mut client = self.client.clone();
-        let fut = client.call(request);
+        let request_limit = self.request_limit.clone();
         HttpConnectorFuture::new(async move {
-            let response = fut
+            // Wait until another request may be sent before starting the read timeout
+            let permit = match request_limit {
+                Some(request_limit) => Some(
+                    request_limit
+                        .acquire_owned()
+                        .await
+                        .expect("the request limit semaphore is never closed"),
+                ),
+                None => None,
+            };
+            let response = client
+                .call(request)
                 .await
                 .map_err(downcast_error)?
-                .map(SdkBody::from_body_0_4);
+                .map(|body| match permit {
+                    Some(permit) => {
+                        SdkBody::from_body_0_4(request_limit::PermitBody::new(body, permit))
+                    }
+                    None => SdkBody::from_body_0_4(body),
+                });
             match HttpResponse::try_from(response) {
                 Ok(response) => Ok(response),
                 Err(err) => Err(ConnectorError::other(err.into(), None)),
@@ -421,6 +553,8 @@ impl From<&HttpConnectorSettings> for CacheKey {
 struct HyperClient<F> {
     connector_cache: RwLock<HashMap<CacheKey, SharedHttpConnector>>,
     client_builder: hyper_0_14::client::Builder,
+    http2_settings: Option<Http2Settings>,
+    request_limit: Option<Arc<Semaphore>>,
     tcp_connector_fn: F,
 }
 
@@ -429,6 +563,8 @@ impl<F> fmt::Debug for HyperClient<F> {
         f.debug_struct("HyperClient")
             .field("connector_cache", &self.connector_cache)
             .field("client_builder", &self.client_builder)
+            .field("http2_settings", &self.http2_settings)
+            .field("request_limit", &self.request_limit)
             .finish()
     }
 }
@@ -470,7 +606,12 @@ where
                 let mut builder = HyperConnector::builder()
                     .hyper_builder(self.client_builder.clone())
                     .connector_settings(settings.clone());
-                builder.set_sleep_impl(components.sleep_impl());
+                builder
+                    .set_sleep_impl(components.sleep_impl())
+                    .set_time_source(components.time_source())
+                    .set_http2_settings(self.http2_settings.clone());
+                // Every connector shares the client's request limit
+                builder.request_limit = self.request_limit.clone();
 
                 let start = components.time_source().map(|ts| ts.now());
                 let tcp_connector = (self.tcp_connector_fn)();
@@ -514,6 +655,23 @@ where
 /// let client = my_service_client::Client::from_conf(config);
 /// ```
 ///
+/// ## Tune HTTP/2
+///
+/// ```no_run,ignore
+/// use aws_smithy_runtime::client::http::hyper_014::{Http2Settings, HyperClientBuilder};
+/// use std::num::NonZeroUsize;
+/// use std::time::Duration;
+///
+/// let http_client = HyperClientBuilder::new()
+///     .http2_settings(
+///         Http2Settings::builder()
+///             .keep_alive_interval(Duration::from_secs(30))
+///             .build(),
+///     )
+///     .max_concurrent_requests(NonZeroUsize::new(100).unwrap())
+///     .build_https();
+/// ```
+///
 /// ## Use a Hyper client with WebPKI roots
 ///
 /// A use case for where you may want to use the [`HyperClientBuilder`] is when
@@ -546,6 +704,8 @@ where
 #[derive(Clone, Default, Debug)]
 pub struct HyperClientBuilder {
     client_builder: Option<hyper_0_14::client::Builder>,
+    http2_settings: Option<Http2Settings>,
+    max_concurrent_requests: Option<NonZeroUsize>,
 }
 
 impl HyperClientBuilder {
@@ -573,6 +733,45 @@ impl HyperClientBuilder {
         self
     }
 
+    /// Configure HTTP/2 for this client.
+    ///
+    /// These settings are applied on top of the [`hyper_builder`](Self::hyper_builder).
+    pub fn http2_settings(mut self, http2_settings: Http2Settings) -> Self {
+        self.http2_settings = Some(http2_settings);
+        self
+    }
+
+    /// Configure HTTP/2 for this client.
+    ///
+    /// These settings are applied on top of the [`hyper_builder`](Self::hyper_builder).
+    pub fn set_http2_settings(&mut self, http2_settings: Option<Http2Settings>) -> &mut Self {
+        self.http2_settings = http2_settings;
+        self
+    }
+
+    /// Limit the number of requests this client has in flight at once.
+    ///
+    /// The limit applies to every request sent by the client, over HTTP/1.1 and HTTP/2 alike.
+    /// Requests over the limit wait until an earlier response body has been dropped. There is no
+    /// limit by default.
+    pub fn max_concurrent_requests(mut self, max_concurrent_requests: NonZeroUsize) -> Self {
+        self.max_concurrent_requests = Some(max_concurrent_requests);
+        self
+    }
+
+    /// Limit the number of requests this client has in flight at once.
+    ///
+    /// The limit applies to every request sent by the client, over HTTP/1.1 and HTTP/2 alike.
+    /// Requests over the limit wait until an earlier response body has been dropped. There is no
+    /// limit by default.
+    pub fn set_max_concurrent_requests(
+        &mut self,
+        max_concurrent_requests: Option<NonZeroUsize>,
+    ) -> &mut Self {
+        self.max_concurrent_requests = max_concurrent_requests;
+        self
+    }
+
     /// Create a hyper client with the default rustls HTTPS implementation.
     ///
     /// The trusted certificates will be loaded later when this becomes the selected
@@ -611,11 +810,213 @@ impl HyperClientBuilder {
         SharedHttpClient::new(HyperClient {
             connector_cache: RwLock::new(HashMap::new()),
             client_builder: self.client_builder.unwrap_or_default(),
+            http2_settings: self.http2_settings,
+            request_limit: self.max_concurrent_requests.map(request_limit::semaphore),
             tcp_connector_fn,
         })
     }
 }
 
+mod request_limit {
+    use bytes::Bytes;
+    use http_body_0_4::{Body, SizeHint};
+    use pin_project_lite::pin_project;
+    use std::num::NonZeroUsize;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+    use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+    pub(super) fn semaphore(max_concurrent_requests: NonZeroUsize) -> Arc<Semaphore> {
+        Arc::new(Semaphore::new(max_concurrent_requests.get()))
+    }
+
+    pin_project! {
+        /// Response body that holds one of the request limit's permits until it is dropped.
+        pub(super) struct PermitBody<B> {
+            #[pin]
+            inner: B,
+            _permit: OwnedSemaphorePermit,
+        }
+    }
+
+    impl<B> PermitBody<B> {
+        pub(super) fn new(inner: B, permit: OwnedSemaphorePermit) -> Self {
+            Self {
+                inner,
+                _permit: permit,
+            }
+        }
+    }
+
+    impl<B: Body<Data = Bytes>> Body for PermitBody<B> {
+        type Data = Bytes;
+        type Error = B::Error;
+
+        fn poll_data(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+            self.project().inner.poll_data(cx)
+        }
+
+        fn poll_trailers(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Result<Option<http::HeaderMap>, Self::Error>> {
+            self.project().inner.poll_trailers(cx)
+        }
+
+        fn is_end_stream(&self) -> bool {
+            self.inner.is_end_stream()
+        }
+
+        fn size_hint(&self) -> SizeHint {
+            self.inner.size_hint()
+        }
+    }
+}
+
+mod connect_timing {
+    use aws_smithy_async::time::SharedTimeSource;
+    use aws_smithy_runtime_api::box_error::BoxError;
+    use http::Uri;
+    use hyper_0_14::client::connect::{Connected, Connection};
+    use pin_project_lite::pin_project;
+    use std::future::Future;
+    use std::io::IoSlice;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use std::time::{Duration, SystemTime};
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    /// Connection extra recording when a connection finished being established.
+    #[derive(Clone, Copy, Debug)]
+    pub(super) struct ConnectionEstablished(SystemTime);
+
+    impl ConnectionEstablished {
+        /// How long a request that started at `request_started` waited for this connection.
+        ///
+        /// Connections that were established before the request started were reused from the pool,
+        /// so the request didn't wait for them at all.
+        pub(super) fn acquire_duration(&self, request_started: SystemTime) -> Duration {
+            self.0.duration_since(request_started).unwrap_or_default()
+        }
+    }
+
+    /// Connector wrapper that tags every new connection with the time it was established.
+    #[derive(Clone, Debug)]
+    pub(super) struct TimedConnector<C> {
+        inner: C,
+        time_source: SharedTimeSource,
+    }
+
+    impl<C> TimedConnector<C> {
+        pub(super) fn new(inner: C, time_source: SharedTimeSource) -> Self {
+            Self { inner, time_source }
+        }
+    }
+
+    impl<C> hyper_0_14::service::Service<Uri> for TimedConnector<C>
+    where
+        C: hyper_0_14::service::Service<Uri>,
+        C::Error: Into<BoxError>,
+    {
+        type Response = TimedConnection<C::Response>;
+        type Error = C::Error;
+        type Future = TimedConnectFuture<C::Future>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.inner.poll_ready(cx)
+        }
+
+        fn call(&mut self, req: Uri) -> Self::Future {
+            TimedConnectFuture {
+                inner: self.inner.call(req),
+                time_source: self.time_source.clone(),
+            }
+        }
+    }
+
+    pin_project! {
+        pub(super) struct TimedConnectFuture<F> {
+            #[pin]
+            inner: F,
+            time_source: SharedTimeSource,
+        }
+    }
+
+    impl<F, T, E> Future for TimedConnectFuture<F>
+    where
+        F: Future<Output = Result<T, E>>,
+    {
+        type Output = Result<TimedConnection<T>, E>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = self.project();
+            let time_source = this.time_source;
+            this.inner.poll(cx).map_ok(|inner| TimedConnection {
+                inner,
+                established: ConnectionEstablished(time_source.now()),
+            })
+        }
+    }
+
+    pin_project! {
+        pub(super) struct TimedConnection<T> {
+            #[pin]
+            inner: T,
+            established: ConnectionEstablished,
+        }
+    }
+
+    impl<T: Connection> Connection for TimedConnection<T> {
+        fn connected(&self) -> Connected {
+            self.inner.connected().extra(self.established)
+        }
+    }
+
+    impl<T: AsyncRead> AsyncRead for TimedConnection<T> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            self.project().inner.poll_read(cx, buf)
+        }
+    }
+
+    impl<T: AsyncWrite> AsyncWrite for TimedConnection<T> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            self.project().inner.poll_write(cx, buf)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            self.project().inner.poll_flush(cx)
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            self.project().inner.poll_shutdown(cx)
+        }
+
+        fn poll_write_vectored(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[IoSlice<'_>],
+        ) -> Poll<std::io::Result<usize>> {
+            self.project().inner.poll_write_vectored(cx, bufs)
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            self.inner.is_write_vectored()
+        }
+    }
+}
+
 mod timeout_middleware {
     use aws_smithy_async::future::timeout::{TimedOutError, Timeout};
     use aws_smithy_async::rt::sleep::Sleep;
@@ -995,8 +1396,8 @@ mod test {
     use std::io::{Error, ErrorKind};
     use std::pin::Pin;
     use std::sync::atomic::{AtomicU32, Ordering};
-    use std::sync::Arc;
-    use std::task::{Context, Poll};
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Waker};
     use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 
     #[tokio::test]
@@ -1068,6 +1469,105 @@ mod test {
         assert!(err.is_io(), "{:?}", err);
     }
 
+    #[tokio::test]
+    async fn connection_metadata_reports_acquire_duration() {
+        let connector = TestConnection {
+            inner: CannedResponseStream::default(),
+        };
+        let adapter = HyperConnector::builder().build(connector).adapter;
+        let capture = CaptureSmithyConnection::new();
+        let mut request = HttpRequest::get("http://example.com").unwrap();
+        request.add_extension(capture.clone());
+        let response = adapter.call(request).await.expect("success");
+        assert_eq!(200, response.status().as_u16());
+
+        let metadata = capture
+            .get()
+            .expect("the connection should have been captured");
+        assert!(metadata.acquire_duration().is_some());
+        assert_eq!(Some(HttpVersion::Http11), metadata.http_version());
+    }
+
+    /// Starts a plaintext HTTP/2-only server that responds to every request with `handler`
+    async fn h2c_server<F, Fut>(handler: F) -> std::net::SocketAddr
+    where
+        F: Fn() -> Fut + Clone + Send + Sync + 'static,
+        Fut: std::future::Future<Output = &'static str> + Send + 'static,
+    {
+        use hyper_0_14::service::{make_service_fn, service_fn};
+        use hyper_0_14::{Body, Response, Server};
+        use std::convert::Infallible;
+
+        let make_service = make_service_fn(move |_conn| {
+            let handler = handler.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |_req| {
+                    let handler = handler.clone();
+                    async move { Ok::<_, Infallible>(Response::new(Body::from(handler().await))) }
+                }))
+            }
+        });
+        let server = Server::bind(&([127, 0, 0, 1], 0).into())
+            .http2_only(true)
+            .serve(make_service);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        addr
+    }
+
+    #[tokio::test]
+    async fn http2_prior_knowledge() {
+        let addr = h2c_server(|| async { "hello" }).await;
+        let adapter = HyperConnector::builder()
+            .http2_settings(Http2Settings::builder().prior_knowledge(true).build())
+            .build(hyper_0_14::client::HttpConnector::new())
+            .adapter;
+        let capture = CaptureSmithyConnection::new();
+        let mut request = HttpRequest::get(format!("http://{addr}")).unwrap();
+        request.add_extension(capture.clone());
+        let response = adapter.call(request).await.expect("success");
+        assert_eq!(200, response.status().as_u16());
+
+        let metadata = capture
+            .get()
+            .expect("the connection should have been captured");
+        assert_eq!(Some(HttpVersion::Http2), metadata.http_version());
+    }
+
+    #[tokio::test]
+    async fn max_concurrent_requests() {
+        let in_flight = Arc::new(AtomicU32::new(0));
+        let max_in_flight = Arc::new(AtomicU32::new(0));
+        let addr = h2c_server({
+            let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+            move || {
+                let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+                async move {
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_in_flight.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    "hello"
+                }
+            }
+        })
+        .await;
+        let connector = HyperConnector::builder()
+            .http2_settings(Http2Settings::builder().prior_knowledge(true).build())
+            .max_concurrent_requests(NonZeroUsize::new(2).unwrap())
+            .build(hyper_0_14::client::HttpConnector::new());
+
+        let requests = (0..6).map(|_| async {
+            let response = connector
+                .call(HttpRequest::get(format!("http://{addr}")).unwrap())
+                .await
+                .expect("success");
+            assert_eq!(200, response.status().as_u16());
+        });
+        futures_util::future::join_all(requests).await;
+        assert_eq!(2, max_in_flight.load(Ordering::SeqCst));
+    }
+
     // ---- machinery to make a Hyper connector that responds with an IO Error
     #[derive(Clone)]
     struct HangupStream;
@@ -1109,6 +1609,66 @@ mod test {
         }
     }
 
+    // ---- machinery to make a Hyper connector that responds to every request with an empty 200
+    #[derive(Clone, Default)]
+    struct CannedResponseStream {
+        state: Arc<Mutex<CannedResponseState>>,
+    }
+
+    #[derive(Default)]
+    struct CannedResponseState {
+        requested: bool,
+        responded: bool,
+        read_waker: Option<Waker>,
+    }
+
+    impl Connection for CannedResponseStream {
+        fn connected(&self) -> Connected {
+            Connected::new()
+        }
+    }
+
+    impl AsyncRead for CannedResponseStream {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let mut state = self.state.lock().unwrap();
+            if state.requested && !state.responded {
+                state.responded = true;
+                buf.put_slice(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n");
+                Poll::Ready(Ok(()))
+            } else {
+                state.read_waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    impl AsyncWrite for CannedResponseStream {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<Result<usize, Error>> {
+            let mut state = self.state.lock().unwrap();
+            state.requested = true;
+            if let Some(waker) = state.read_waker.take() {
+                waker.wake();
+            }
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
     #[derive(Clone)]
     struct TestConnection<T> {
         inner: T,
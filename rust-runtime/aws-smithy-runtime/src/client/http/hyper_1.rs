@@ -0,0 +1,1756 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::client::http::connection_poisoning::CaptureSmithyConnection;
+use crate::client::http::hyper_1::timeout_middleware::HttpTimeoutError;
+use aws_smithy_async::future::timeout::TimedOutError;
+use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep, SharedAsyncSleep};
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::connection::{ConnectionMetadata, HttpVersion};
+use aws_smithy_runtime_api::client::http::{
+    HttpClient, HttpConnector, HttpConnectorFuture, HttpConnectorSettings, SharedHttpClient,
+    SharedHttpConnector,
+};
+use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, HttpResponse};
+use aws_smithy_runtime_api::client::result::ConnectorError;
+use aws_smithy_runtime_api::client::runtime_components::{
+    RuntimeComponents, RuntimeComponentsBuilder,
+};
+use aws_smithy_runtime_api::shared::IntoShared;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::config_bag::ConfigBag;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use aws_smithy_types::retry::ErrorKind;
+use h2_0_4::Reason;
+use http_1x::{Extensions, Uri};
+use hyper_1::rt::{Read, Write};
+use hyper_util::client::legacy as client;
+use hyper_util::client::legacy::connect::{
+    capture_connection, CaptureConnection, Connection, HttpInfo,
+};
+use hyper_util::rt::TokioExecutor;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime};
+use tower_service::Service;
+
+pub use crate::client::http::http2::{Http2Settings, Http2SettingsBuilder};
+
+/// TLS implementations that can be used by a hyper 1.x client.
+pub mod tls {
+    /// The TLS implementation used by [`HyperClientBuilder::build_https`](super::HyperClientBuilder::build_https).
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[non_exhaustive]
+    pub enum Provider {
+        /// [rustls](https://docs.rs/rustls) with the `ring` crypto backend, trusting the
+        /// platform's native root certificates.
+        #[cfg(feature = "tls-rustls-hyper-1-x")]
+        Rustls,
+        /// The platform's native TLS implementation, via [native-tls](https://docs.rs/native-tls).
+        #[cfg(feature = "tls-native-tls-hyper-1-x")]
+        NativeTls,
+    }
+
+    #[cfg(any(feature = "tls-rustls-hyper-1-x", feature = "tls-native-tls-hyper-1-x"))]
+    impl Default for Provider {
+        fn default() -> Self {
+            #[cfg(feature = "tls-rustls-hyper-1-x")]
+            return Provider::Rustls;
+            #[cfg(not(feature = "tls-rustls-hyper-1-x"))]
+            return Provider::NativeTls;
+        }
+    }
+
+    #[cfg(feature = "tls-rustls-hyper-1-x")]
+    pub(super) mod rustls_provider {
+        use hyper_util::client::legacy::connect::HttpConnector;
+        use rustls_0_22::crypto::CryptoProvider;
+        use rustls_0_22::ClientConfig;
+        use std::sync::Arc;
+
+        // Loading the native root certificates takes 300ms on OS X. Cache the TLS config so that
+        // we don't need to repeatedly incur that cost.
+        static TLS_CONFIG: once_cell::sync::Lazy<ClientConfig> =
+            once_cell::sync::Lazy::new(tls_config);
+
+        fn restrict_ciphers(base: CryptoProvider) -> CryptoProvider {
+            let suites = &[
+                // TLS1.3 suites
+                rustls_0_22::CipherSuite::TLS13_AES_256_GCM_SHA384,
+                rustls_0_22::CipherSuite::TLS13_AES_128_GCM_SHA256,
+                // TLS1.2 suites
+                rustls_0_22::CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384,
+                rustls_0_22::CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
+                rustls_0_22::CipherSuite::TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384,
+                rustls_0_22::CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
+                rustls_0_22::CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+            ];
+            let supported_suites = suites
+                .iter()
+                .flat_map(|suite| {
+                    base.cipher_suites
+                        .iter()
+                        .find(|s| &s.suite() == suite)
+                        .cloned()
+                })
+                .collect::<Vec<_>>();
+            CryptoProvider {
+                cipher_suites: supported_suites,
+                ..base
+            }
+        }
+
+        fn tls_config() -> ClientConfig {
+            use hyper_rustls_0_26::ConfigBuilderExt;
+            let provider = restrict_ciphers(rustls_0_22::crypto::ring::default_provider());
+            ClientConfig::builder_with_provider(Arc::new(provider))
+                .with_safe_default_protocol_versions()
+                .expect("Error with the TLS configuration. Please file a bug report under https://github.com/smithy-lang/smithy-rs/issues.")
+                .with_native_roots()
+                .expect("error with TLS configuration.")
+                .with_no_client_auth()
+        }
+
+        /// Wraps `http` in a rustls HTTPS connector.
+        ///
+        /// It requires a minimum TLS version of 1.2.
+        /// It allows you to connect to both `http` and `https` URLs.
+        pub(crate) fn https<R>(
+            http: HttpConnector<R>,
+        ) -> hyper_rustls_0_26::HttpsConnector<HttpConnector<R>> {
+            hyper_rustls_0_26::HttpsConnectorBuilder::new()
+                .with_tls_config(TLS_CONFIG.clone())
+                .https_or_http()
+                .enable_http1()
+                .enable_http2()
+                .wrap_connector(http)
+        }
+    }
+
+    #[cfg(feature = "tls-native-tls-hyper-1-x")]
+    pub(super) mod native_tls_provider {
+        use hyper_util::client::legacy::connect::HttpConnector;
+
+        /// Wraps `http` in a native-tls HTTPS connector.
+        ///
+        /// It allows you to connect to both `http` and `https` URLs.
+        pub(crate) fn https<R>(
+            http: HttpConnector<R>,
+        ) -> hyper_tls::HttpsConnector<HttpConnector<R>> {
+            hyper_tls::HttpsConnector::new_with_connector(http)
+        }
+    }
+}
+
+#[cfg(any(feature = "tls-rustls-hyper-1-x", feature = "tls-native-tls-hyper-1-x"))]
+mod dns {
+    use aws_smithy_runtime_api::box_error::BoxError;
+    use aws_smithy_runtime_api::client::dns::ResolveDns;
+    use hyper_util::client::legacy::connect::HttpConnector;
+    use std::future::Future;
+    use std::net::SocketAddr;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use std::vec;
+
+    pub(super) use hyper_util::client::legacy::connect::dns::{GaiResolver, Name};
+
+    /// Creates an `HttpConnector` that leaves enforcing the URL scheme to the TLS connector wrapping it.
+    pub(super) fn http_connector<R>(resolver: R) -> HttpConnector<R> {
+        let mut http = HttpConnector::new_with_resolver(resolver);
+        http.enforce_http(false);
+        http
+    }
+
+    /// A bridge that allows our [`ResolveDns`] trait to work with hyper's resolver interface.
+    #[derive(Clone)]
+    pub(super) struct HyperUtilResolver<R> {
+        pub(super) resolver: R,
+    }
+
+    impl<R: ResolveDns + Clone + 'static> tower_service::Service<Name> for HyperUtilResolver<R> {
+        type Response = vec::IntoIter<SocketAddr>;
+        type Error = BoxError;
+        type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, req: Name) -> Self::Future {
+            let resolver = self.resolver.clone();
+            Box::pin(async move {
+                let dns_entries = resolver.resolve_dns(req.as_str()).await?;
+                Ok(dns_entries
+                    .into_iter()
+                    .map(|ip_addr| SocketAddr::new(ip_addr, 0))
+                    .collect::<Vec<_>>()
+                    .into_iter())
+            })
+        }
+    }
+}
+
+#[cfg(any(feature = "tls-rustls-hyper-1-x", feature = "tls-native-tls-hyper-1-x"))]
+mod default_connector {
+    use aws_smithy_async::rt::sleep::SharedAsyncSleep;
+    use aws_smithy_runtime_api::client::http::HttpConnectorSettings;
+
+    pub(super) fn base(
+        settings: &HttpConnectorSettings,
+        sleep: Option<SharedAsyncSleep>,
+    ) -> super::HyperConnectorBuilder {
+        let mut hyper = super::HyperConnector::builder().connector_settings(settings.clone());
+        if let Some(sleep) = sleep {
+            hyper = hyper.sleep_impl(sleep);
+        }
+        hyper
+    }
+}
+
+/// Given `HttpConnectorSettings` and an `SharedAsyncSleep`, create a `SharedHttpConnector` from defaults depending on what cargo features are activated.
+pub fn default_connector(
+    settings: &HttpConnectorSettings,
+    sleep: Option<SharedAsyncSleep>,
+) -> Option<SharedHttpConnector> {
+    #[cfg(any(feature = "tls-rustls-hyper-1-x", feature = "tls-native-tls-hyper-1-x"))]
+    {
+        tracing::trace!(settings = ?settings, sleep = ?sleep, "creating a new default connector");
+        let hyper = default_connector::base(settings, sleep).build_https();
+        Some(SharedHttpConnector::new(hyper))
+    }
+    #[cfg(not(any(feature = "tls-rustls-hyper-1-x", feature = "tls-native-tls-hyper-1-x")))]
+    {
+        tracing::trace!(settings = ?settings, sleep = ?sleep, "no default connector available");
+        None
+    }
+}
+
+/// Creates a hyper-backed HTTPS client from defaults depending on what cargo features are activated.
+pub fn default_client() -> Option<SharedHttpClient> {
+    #[cfg(any(feature = "tls-rustls-hyper-1-x", feature = "tls-native-tls-hyper-1-x"))]
+    {
+        tracing::trace!("creating a new default hyper 1.x client");
+        Some(HyperClientBuilder::new().build_https())
+    }
+    #[cfg(not(any(feature = "tls-rustls-hyper-1-x", feature = "tls-native-tls-hyper-1-x")))]
+    {
+        tracing::trace!("no default connector available");
+        None
+    }
+}
+
+/// [`HttpConnector`] that uses [`hyper_1`] to make HTTP requests.
+///
+/// This connector also implements socket connect and read timeouts.
+///
+/// This shouldn't be used directly in most cases.
+/// See the docs on [`HyperClientBuilder`] for examples of how
+/// to customize the Hyper client.
+#[derive(Debug)]
+pub struct HyperConnector {
+    adapter: Box<dyn HttpConnector>,
+}
+
+impl HyperConnector {
+    /// Builder for a Hyper connector.
+    pub fn builder() -> HyperConnectorBuilder {
+        Default::default()
+    }
+}
+
+impl HttpConnector for HyperConnector {
+    fn call(&self, request: HttpRequest) -> HttpConnectorFuture {
+        self.adapter.call(request)
+    }
+}
+
+/// Builder for [`HyperConnector`].
+#[derive(Default, Debug)]
+pub struct HyperConnectorBuilder {
+    connector_settings: Option<HttpConnectorSettings>,
+    sleep_impl: Option<SharedAsyncSleep>,
+    time_source: Option<SharedTimeSource>,
+    client_builder: Option<client::Builder>,
+    tls_provider: Option<tls::Provider>,
+    http2_settings: Option<Http2Settings>,
+}
+
+impl HyperConnectorBuilder {
+    /// Create a [`HyperConnector`] from this builder and a given connector.
+    pub fn build<C>(self, tcp_connector: C) -> HyperConnector
+    where
+        C: Clone + Send + Sync + 'static,
+        C: Service<Uri>,
+        C::Response: Connection + Read + Write + Send + Unpin + 'static,
+        C::Future: Unpin + Send + 'static,
+        C::Error: Into<BoxError>,
+    {
+        let mut client_builder = self
+            .client_builder
+            .unwrap_or_else(|| client::Builder::new(TokioExecutor::new()));
+        let http2_settings = self.http2_settings.unwrap_or_default();
+        apply_http2_settings(&http2_settings, &mut client_builder);
+        let sleep_impl = self.sleep_impl.or_else(default_async_sleep);
+        let (connect_timeout, read_timeout) = self
+            .connector_settings
+            .map(|c| (c.connect_timeout(), c.read_timeout()))
+            .unwrap_or((None, None));
+
+        let time_source = self.time_source.unwrap_or_default();
+        let tcp_connector = connect_timing::TimedConnector::new(tcp_connector, time_source.clone());
+        let connector = match connect_timeout {
+            Some(duration) => timeout_middleware::ConnectTimeout::new(
+                tcp_connector,
+                sleep_impl
+                    .clone()
+                    .expect("a sleep impl must be provided in order to have a connect timeout"),
+                duration,
+            ),
+            None => timeout_middleware::ConnectTimeout::no_timeout(tcp_connector),
+        };
+        let base = client_builder.build(connector);
+        let read_timeout = match read_timeout {
+            Some(duration) => timeout_middleware::HttpReadTimeout::new(
+                base,
+                sleep_impl.expect("a sleep impl must be provided in order to have a read timeout"),
+                duration,
+            ),
+            None => timeout_middleware::HttpReadTimeout::no_timeout(base),
+        };
+        HyperConnector {
+            adapter: Box::new(Adapter {
+                client: read_timeout,
+                http2_prior_knowledge: http2_settings.prior_knowledge(),
+                time_source,
+            }),
+        }
+    }
+
+    /// Create a [`HyperConnector`] with the selected HTTPS implementation, or the default one if
+    /// none was selected.
+    #[cfg(any(feature = "tls-rustls-hyper-1-x", feature = "tls-native-tls-hyper-1-x"))]
+    pub fn build_https(self) -> HyperConnector {
+        match self.tls_provider.unwrap_or_default() {
+            #[cfg(feature = "tls-rustls-hyper-1-x")]
+            tls::Provider::Rustls => self.build(tls::rustls_provider::https(dns::http_connector(
+                dns::GaiResolver::new(),
+            ))),
+            #[cfg(feature = "tls-native-tls-hyper-1-x")]
+            tls::Provider::NativeTls => self.build(tls::native_tls_provider::https(
+                dns::http_connector(dns::GaiResolver::new()),
+            )),
+        }
+    }
+
+    /// Set the async sleep implementation used for timeouts
+    ///
+    /// Calling this is only necessary for testing or to use something other than
+    /// [`default_async_sleep`].
+    pub fn sleep_impl(mut self, sleep_impl: impl AsyncSleep + 'static) -> Self {
+        self.sleep_impl = Some(sleep_impl.into_shared());
+        self
+    }
+
+    /// Set the async sleep implementation used for timeouts
+    ///
+    /// Calling this is only necessary for testing or to use something other than
+    /// [`default_async_sleep`].
+    pub fn set_sleep_impl(&mut self, sleep_impl: Option<SharedAsyncSleep>) -> &mut Self {
+        self.sleep_impl = sleep_impl;
+        self
+    }
+
+    /// Set the time source used to measure how long requests wait for a connection
+    ///
+    /// Defaults to the system clock.
+    pub fn time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.time_source = Some(time_source.into_shared());
+        self
+    }
+
+    /// Set the time source used to measure how long requests wait for a connection
+    ///
+    /// Defaults to the system clock.
+    pub fn set_time_source(&mut self, time_source: Option<SharedTimeSource>) -> &mut Self {
+        self.time_source = time_source;
+        self
+    }
+
+    /// Configure the HTTP settings for the `HyperAdapter`
+    pub fn connector_settings(mut self, connector_settings: HttpConnectorSettings) -> Self {
+        self.connector_settings = Some(connector_settings);
+        self
+    }
+
+    /// Configure the HTTP settings for the `HyperAdapter`
+    pub fn set_connector_settings(
+        &mut self,
+        connector_settings: Option<HttpConnectorSettings>,
+    ) -> &mut Self {
+        self.connector_settings = connector_settings;
+        self
+    }
+
+    /// Override the Hyper client [`Builder`](client::Builder) used to construct this client.
+    ///
+    /// This enables changing settings like forcing HTTP2 and modifying other default client behavior.
+    pub fn hyper_builder(mut self, hyper_builder: client::Builder) -> Self {
+        self.client_builder = Some(hyper_builder);
+        self
+    }
+
+    /// Override the Hyper client [`Builder`](client::Builder) used to construct this client.
+    ///
+    /// This enables changing settings like forcing HTTP2 and modifying other default client behavior.
+    pub fn set_hyper_builder(&mut self, hyper_builder: Option<client::Builder>) -> &mut Self {
+        self.client_builder = hyper_builder;
+        self
+    }
+
+    /// Select the TLS implementation used by `build_https`.
+    pub fn tls_provider(mut self, tls_provider: tls::Provider) -> Self {
+        self.tls_provider = Some(tls_provider);
+        self
+    }
+
+    /// Select the TLS implementation used by `build_https`.
+    pub fn set_tls_provider(&mut self, tls_provider: Option<tls::Provider>) -> &mut Self {
+        self.tls_provider = tls_provider;
+        self
+    }
+
+    /// Configure HTTP/2 for this connector.
+    ///
+    /// These settings are applied on top of the [`hyper_builder`](Self::hyper_builder).
+    pub fn http2_settings(mut self, http2_settings: Http2Settings) -> Self {
+        self.http2_settings = Some(http2_settings);
+        self
+    }
+
+    /// Configure HTTP/2 for this connector.
+    ///
+    /// These settings are applied on top of the [`hyper_builder`](Self::hyper_builder).
+    pub fn set_http2_settings(&mut self, http2_settings: Option<Http2Settings>) -> &mut Self {
+        self.http2_settings = http2_settings;
+        self
+    }
+}
+
+fn apply_http2_settings(settings: &Http2Settings, builder: &mut client::Builder) {
+    if settings.prior_knowledge() {
+        builder.http2_only(true);
+    }
+    if let Some(interval) = settings.keep_alive_interval() {
+        // HTTP/2 keep-alive pings need a timer
+        builder
+            .timer(hyper_util::rt::TokioTimer::new())
+            .http2_keep_alive_interval(interval);
+    }
+    if let Some(timeout) = settings.keep_alive_timeout() {
+        builder.http2_keep_alive_timeout(timeout);
+    }
+    if settings.keep_alive_while_idle() {
+        builder.http2_keep_alive_while_idle(true);
+    }
+}
+
+/// Adapter from a [`hyper_util`] client to [`HttpConnector`].
+///
+/// This adapter also enables TCP `CONNECT` and HTTP `READ` timeouts via [`HyperConnector::builder`].
+struct Adapter<C> {
+    client: timeout_middleware::HttpReadTimeout<
+        client::Client<
+            timeout_middleware::ConnectTimeout<connect_timing::TimedConnector<C>>,
+            SdkBody,
+        >,
+    >,
+    http2_prior_knowledge: bool,
+    time_source: SharedTimeSource,
+}
+
+impl<C> fmt::Debug for Adapter<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Adapter")
+            .field("client", &"** hyper client **")
+            .finish()
+    }
+}
+
+/// Extract a smithy connection from a hyper CaptureConnection
+///
+/// `request_started` is when the request was handed to hyper, and is used to work out how long the
+/// request waited for its connection. `http2_prior_knowledge` is whether the client speaks HTTP/2
+/// without negotiating it.
+fn extract_smithy_connection(
+    capture_conn: &CaptureConnection,
+    request_started: SystemTime,
+    http2_prior_knowledge: bool,
+) -> Option<ConnectionMetadata> {
+    let capture_conn = capture_conn.clone();
+    if let Some(conn) = capture_conn.clone().connection_metadata().as_ref() {
+        let mut extensions = Extensions::new();
+        conn.get_extras(&mut extensions);
+        let http_info = extensions.get::<HttpInfo>();
+        let established = extensions.get::<connect_timing::ConnectionEstablished>();
+        let http_version = if http2_prior_knowledge || conn.is_negotiated_h2() {
+            HttpVersion::Http2
+        } else {
+            HttpVersion::Http11
+        };
+        let mut builder = ConnectionMetadata::builder()
+            .proxied(conn.is_proxied())
+            .http_version(http_version)
+            .poison_fn(move || match capture_conn.connection_metadata().as_ref() {
+                Some(conn) => conn.poison(),
+                None => tracing::trace!("no connection existed to poison"),
+            });
+
+        builder
+            .set_local_addr(http_info.map(|info| info.local_addr()))
+            .set_remote_addr(http_info.map(|info| info.remote_addr()))
+            .set_acquire_duration(
+                established.map(|established| established.acquire_duration(request_started)),
+            );
+
+        let smithy_connection = builder.build();
+
+        Some(smithy_connection)
+    } else {
+        None
+    }
+}
+
+impl<C> HttpConnector for Adapter<C>
+where
+    C: Clone + Send + Sync + 'static,
+    C: Service<Uri>,
+    C::Response: Connection + Read + Write + Send + Unpin + 'static,
+    C::Future: Unpin + Send + 'static,
+    C::Error: Into<BoxError>,
+{
+    fn call(&self, request: HttpRequest) -> HttpConnectorFuture {
+        let mut request = match request.try_into_http1x() {
+            Ok(request) => request,
+            Err(err) => {
+                return HttpConnectorFuture::ready(Err(ConnectorError::user(err.into())));
+            }
+        };
+        let capture_connection = capture_connection(&mut request);
+        if let Some(capture_smithy_connection) =
+            request.extensions().get::<CaptureSmithyConnection>()
+        {
+            let request_started = self.time_source.now();
+            let http2_prior_knowledge = self.http2_prior_knowledge;
+            capture_smithy_connection.set_connection_retriever(move || {
+                extract_smithy_connection(
+                    &capture_connection,
+                    request_started,
+                    http2_prior_knowledge,
+                )
+            });
+        }
+        let mut client = self.client.clone();
+        let fut = client.call(request);
+        HttpConnectorFuture::new(async move {
+            let response = fut
+                .await
+                .map_err(downcast_error)?
+                .map(SdkBody::from_body_1_x);
+            match HttpResponse::try_from(response) {
+                Ok(response) => Ok(response),
+                Err(err) => Err(ConnectorError::other(err.into(), None)),
+            }
+        })
+    }
+}
+
+/// Downcast errors coming out of hyper into an appropriate `ConnectorError`
+fn downcast_error(err: BoxError) -> ConnectorError {
+    // is a `TimedOutError` (from aws_smithy_async::timeout) in the chain? if it is, this is a timeout
+    if find_source::<TimedOutError>(err.as_ref()).is_some() {
+        return ConnectorError::timeout(err);
+    }
+    // is the top of chain error actually already a `ConnectorError`? return that directly
+    let err = match err.downcast::<ConnectorError>() {
+        Ok(connector_error) => return *connector_error,
+        Err(box_error) => box_error,
+    };
+    // connection failures are reported by hyper-util rather than by hyper itself
+    if let Some(client_error) = find_source::<client::Error>(err.as_ref()) {
+        if client_error.is_connect() {
+            return ConnectorError::io(err);
+        }
+    }
+    // generally, the chain will contain a hyper error. Go through a set of hyper specific
+    // error classifications
+    let err = match find_source::<hyper_1::Error>(err.as_ref()) {
+        Some(hyper_error) => return to_connector_error(hyper_error)(err),
+        None => err,
+    };
+
+    // otherwise, we have no idea!
+    ConnectorError::other(err, None)
+}
+
+/// Classify a [`hyper_1::Error`], returning a constructor for the matching [`ConnectorError`]
+fn to_connector_error(err: &hyper_1::Error) -> fn(BoxError) -> ConnectorError {
+    if err.is_timeout() || find_source::<HttpTimeoutError>(err).is_some() {
+        return ConnectorError::timeout;
+    }
+    if err.is_user() {
+        return ConnectorError::user;
+    }
+    if err.is_closed() || err.is_canceled() || find_source::<std::io::Error>(err).is_some() {
+        return ConnectorError::io;
+    }
+    // We sometimes receive this from S3: hyper::Error(IncompleteMessage)
+    if err.is_incomplete_message() {
+        return |err: BoxError| ConnectorError::other(err, Some(ErrorKind::TransientError));
+    }
+    if let Some(h2_err) = find_source::<h2_0_4::Error>(err) {
+        if h2_err.is_go_away()
+            || (h2_err.is_reset() && h2_err.reason() == Some(Reason::REFUSED_STREAM))
+        {
+            return ConnectorError::io;
+        }
+    }
+
+    tracing::warn!(err = %DisplayErrorContext(&err), "unrecognized error from Hyper. If this error should be retried, please file an issue.");
+    |err: BoxError| ConnectorError::other(err, None)
+}
+
+fn find_source<'a, E: Error + 'static>(err: &'a (dyn Error + 'static)) -> Option<&'a E> {
+    let mut next = Some(err);
+    while let Some(err) = next {
+        if let Some(matching_err) = err.downcast_ref::<E>() {
+            return Some(matching_err);
+        }
+        next = err.source();
+    }
+    None
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+struct CacheKey {
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+}
+
+impl From<&HttpConnectorSettings> for CacheKey {
+    fn from(value: &HttpConnectorSettings) -> Self {
+        Self {
+            connect_timeout: value.connect_timeout(),
+            read_timeout: value.read_timeout(),
+        }
+    }
+}
+
+struct HyperClient<F> {
+    connector_cache: RwLock<HashMap<CacheKey, SharedHttpConnector>>,
+    client_builder: client::Builder,
+    http2_settings: Option<Http2Settings>,
+    tcp_connector_fn: F,
+}
+
+impl<F> fmt::Debug for HyperClient<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HyperClient")
+            .field("connector_cache", &self.connector_cache)
+            .field("client_builder", &self.client_builder)
+            .field("http2_settings", &self.http2_settings)
+            .finish()
+    }
+}
+
+impl<C, F> HttpClient for HyperClient<F>
+where
+    F: Fn() -> C + Send + Sync,
+    C: Clone + Send + Sync + 'static,
+    C: Service<Uri>,
+    C::Response: Connection + Read + Write + Send + Unpin + 'static,
+    C::Future: Unpin + Send + 'static,
+    C::Error: Into<BoxError>,
+{
+    fn validate_base_client_config(
+        &self,
+        _: &RuntimeComponentsBuilder,
+        _: &ConfigBag,
+    ) -> Result<(), BoxError> {
+        // Initialize the TCP connector at this point so that native certs load
+        // at client initialization time instead of upon first request. We do it
+        // here rather than at construction so that it won't run if this is not
+        // the selected HTTP client for the base config (for example, if this was
+        // the default HTTP client, and it was overridden by a later plugin).
+        let _ = (self.tcp_connector_fn)();
+        Ok(())
+    }
+
+    fn http_connector(
+        &self,
+        settings: &HttpConnectorSettings,
+        components: &RuntimeComponents,
+    ) -> SharedHttpConnector {
+        let key = CacheKey::from(settings);
+        let mut connector = self.connector_cache.read().unwrap().get(&key).cloned();
+        if connector.is_none() {
+            let mut cache = self.connector_cache.write().unwrap();
+            // Short-circuit if another thread already wrote a connector to the cache for this key
+            if !cache.contains_key(&key) {
+                let mut builder = HyperConnector::builder()
+                    .hyper_builder(self.client_builder.clone())
+                    .connector_settings(settings.clone());
+                builder
+                    .set_sleep_impl(components.sleep_impl())
+                    .set_time_source(components.time_source())
+                    .set_http2_settings(self.http2_settings.clone());
+
+                let start = components.time_source().map(|ts| ts.now());
+                let tcp_connector = (self.tcp_connector_fn)();
+                let end = components.time_source().map(|ts| ts.now());
+                if let (Some(start), Some(end)) = (start, end) {
+                    if let Ok(elapsed) = end.duration_since(start) {
+                        tracing::debug!("new TCP connector created in {:?}", elapsed);
+                    }
+                }
+                let connector = SharedHttpConnector::new(builder.build(tcp_connector));
+                cache.insert(key.clone(), connector);
+            }
+            connector = cache.get(&key).cloned();
+        }
+
+        connector.expect("cache populated above")
+    }
+}
+
+/// Builder for a hyper 1.x-backed [`HttpClient`] implementation.
+///
+/// This builder can be used to customize the underlying TCP connector used, as well as
+/// hyper client configuration.
+///
+/// # Examples
+///
+/// Construct a Hyper client with the default TLS implementation.
+/// This can be useful when you want to share a Hyper connector between multiple
+/// generated Smithy clients.
+///
+/// ```no_run,ignore
+/// use aws_smithy_runtime::client::http::hyper_1::HyperClientBuilder;
+///
+/// let http_client = HyperClientBuilder::new().build_https();
+///
+/// // This connector can then be given to a generated service Config
+/// let config = my_service_client::Config::builder()
+///     .endpoint_url("http://localhost:1234")
+///     .http_client(http_client)
+///     .build();
+/// let client = my_service_client::Client::from_conf(config);
+/// ```
+///
+/// ## Choose a TLS implementation
+///
+/// With both the `tls-rustls-hyper-1-x` and `tls-native-tls-hyper-1-x` features enabled,
+/// rustls is used by default. The platform's native TLS implementation can be selected instead:
+///
+/// ```no_run,ignore
+/// use aws_smithy_runtime::client::http::hyper_1::{tls, HyperClientBuilder};
+///
+/// let http_client = HyperClientBuilder::new()
+///     .tls_provider(tls::Provider::NativeTls)
+///     .build_https();
+/// ```
+///
+/// ## Use a custom DNS resolver
+///
+/// ```no_run,ignore
+/// use aws_smithy_runtime::client::http::hyper_1::HyperClientBuilder;
+///
+/// let http_client = HyperClientBuilder::new().build_with_resolver(my_resolver);
+/// ```
+///
+/// ## Use a custom TCP connector
+///
+/// Any connector that hyper-util's legacy client accepts can be used, for example to change
+/// the trusted root certificates:
+///
+/// ```no_run,ignore
+/// use aws_smithy_runtime::client::http::hyper_1::HyperClientBuilder;
+///
+/// let https_connector = hyper_rustls::HttpsConnectorBuilder::new()
+///     .with_webpki_roots()
+///     .https_only()
+///     .enable_http1()
+///     .enable_http2()
+///     .build();
+/// let http_client = HyperClientBuilder::new().build(https_connector);
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct HyperClientBuilder {
+    client_builder: Option<client::Builder>,
+    tls_provider: Option<tls::Provider>,
+    http2_settings: Option<Http2Settings>,
+}
+
+impl HyperClientBuilder {
+    /// Creates a new builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Override the Hyper client [`Builder`](client::Builder) used to construct this client.
+    ///
+    /// This enables changing settings like forcing HTTP2 and modifying other default client behavior.
+    pub fn hyper_builder(mut self, hyper_builder: client::Builder) -> Self {
+        self.client_builder = Some(hyper_builder);
+        self
+    }
+
+    /// Override the Hyper client [`Builder`](client::Builder) used to construct this client.
+    ///
+    /// This enables changing settings like forcing HTTP2 and modifying other default client behavior.
+    pub fn set_hyper_builder(&mut self, hyper_builder: Option<client::Builder>) -> &mut Self {
+        self.client_builder = hyper_builder;
+        self
+    }
+
+    /// Select the TLS implementation used by `build_https` and `build_with_resolver`.
+    pub fn tls_provider(mut self, tls_provider: tls::Provider) -> Self {
+        self.tls_provider = Some(tls_provider);
+        self
+    }
+
+    /// Select the TLS implementation used by `build_https` and `build_with_resolver`.
+    pub fn set_tls_provider(&mut self, tls_provider: Option<tls::Provider>) -> &mut Self {
+        self.tls_provider = tls_provider;
+        self
+    }
+
+    /// Configure HTTP/2 for this client.
+    ///
+    /// These settings are applied on top of the [`hyper_builder`](Self::hyper_builder).
+    pub fn http2_settings(mut self, http2_settings: Http2Settings) -> Self {
+        self.http2_settings = Some(http2_settings);
+        self
+    }
+
+    /// Configure HTTP/2 for this client.
+    ///
+    /// These settings are applied on top of the [`hyper_builder`](Self::hyper_builder).
+    pub fn set_http2_settings(&mut self, http2_settings: Option<Http2Settings>) -> &mut Self {
+        self.http2_settings = http2_settings;
+        self
+    }
+
+    /// Create a hyper client with the selected HTTPS implementation, or the default one if none
+    /// was selected.
+    ///
+    /// The trusted certificates will be loaded later when this becomes the selected
+    /// HTTP client for a Smithy client.
+    #[cfg(any(feature = "tls-rustls-hyper-1-x", feature = "tls-native-tls-hyper-1-x"))]
+    pub fn build_https(self) -> SharedHttpClient {
+        self.build_with_resolver_fn(dns::GaiResolver::new)
+    }
+
+    /// Create a hyper client with the selected HTTPS implementation that uses a custom DNS
+    /// resolver.
+    #[cfg(any(feature = "tls-rustls-hyper-1-x", feature = "tls-native-tls-hyper-1-x"))]
+    pub fn build_with_resolver(
+        self,
+        resolver: impl aws_smithy_runtime_api::client::dns::ResolveDns + Clone + 'static,
+    ) -> SharedHttpClient {
+        self.build_with_resolver_fn(move || dns::HyperUtilResolver {
+            resolver: resolver.clone(),
+        })
+    }
+
+    #[cfg(any(feature = "tls-rustls-hyper-1-x", feature = "tls-native-tls-hyper-1-x"))]
+    fn build_with_resolver_fn<R, F>(self, resolver_fn: F) -> SharedHttpClient
+    where
+        F: Fn() -> R + Send + Sync + 'static,
+        R: Service<dns::Name> + Clone + Send + Sync + 'static,
+        R::Response: Iterator<Item = std::net::SocketAddr>,
+        R::Future: Send,
+        R::Error: Into<BoxError>,
+    {
+        match self.tls_provider.unwrap_or_default() {
+            #[cfg(feature = "tls-rustls-hyper-1-x")]
+            tls::Provider::Rustls => self.build_with_fn(move || {
+                tls::rustls_provider::https(dns::http_connector(resolver_fn()))
+            }),
+            #[cfg(feature = "tls-native-tls-hyper-1-x")]
+            tls::Provider::NativeTls => self.build_with_fn(move || {
+                tls::native_tls_provider::https(dns::http_connector(resolver_fn()))
+            }),
+        }
+    }
+
+    /// Create a [`SharedHttpClient`] from this builder and a given connector.
+    ///
+    #[cfg_attr(
+        any(feature = "tls-rustls-hyper-1-x", feature = "tls-native-tls-hyper-1-x"),
+        doc = "Use [`build_https`](HyperClientBuilder::build_https) if you don't want to provide a custom TCP connector."
+    )]
+    pub fn build<C>(self, tcp_connector: C) -> SharedHttpClient
+    where
+        C: Clone + Send + Sync + 'static,
+        C: Service<Uri>,
+        C::Response: Connection + Read + Write + Send + Unpin + 'static,
+        C::Future: Unpin + Send + 'static,
+        C::Error: Into<BoxError>,
+    {
+        self.build_with_fn(move || tcp_connector.clone())
+    }
+
+    fn build_with_fn<C, F>(self, tcp_connector_fn: F) -> SharedHttpClient
+    where
+        F: Fn() -> C + Send + Sync + 'static,
+        C: Clone + Send + Sync + 'static,
+        C: Service<Uri>,
+        C::Response: Connection + Read + Write + Send + Unpin + 'static,
+        C::Future: Unpin + Send + 'static,
+        C::Error: Into<BoxError>,
+    {
+        SharedHttpClient::new(HyperClient {
+            connector_cache: RwLock::new(HashMap::new()),
+            client_builder: self
+                .client_builder
+                .unwrap_or_else(|| client::Builder::new(TokioExecutor::new())),
+            http2_settings: self.http2_settings,
+            tcp_connector_fn,
+        })
+    }
+}
+
+mod connect_timing {
+    use aws_smithy_async::time::SharedTimeSource;
+    use aws_smithy_runtime_api::box_error::BoxError;
+    use http_1x::Uri;
+    use hyper_1::rt::{Read, ReadBufCursor, Write};
+    use hyper_util::client::legacy::connect::{Connected, Connection};
+    use pin_project_lite::pin_project;
+    use std::future::Future;
+    use std::io::IoSlice;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use std::time::{Duration, SystemTime};
+
+    /// Connection extra recording when a connection finished being established.
+    #[derive(Clone, Copy, Debug)]
+    pub(super) struct ConnectionEstablished(SystemTime);
+
+    impl ConnectionEstablished {
+        /// How long a request that started at `request_started` waited for this connection.
+        ///
+        /// Connections that were established before the request started were reused from the pool,
+        /// so the request didn't wait for them at all.
+        pub(super) fn acquire_duration(&self, request_started: SystemTime) -> Duration {
+            self.0.duration_since(request_started).unwrap_or_default()
+        }
+    }
+
+    /// Connector wrapper that tags every new connection with the time it was established.
+    #[derive(Clone, Debug)]
+    pub(super) struct TimedConnector<C> {
+        inner: C,
+        time_source: SharedTimeSource,
+    }
+
+    impl<C> TimedConnector<C> {
+        pub(super) fn new(inner: C, time_source: SharedTimeSource) -> Self {
+            Self { inner, time_source }
+        }
+    }
+
+    impl<C> tower_service::Service<Uri> for TimedConnector<C>
+    where
+        C: tower_service::Service<Uri>,
+        C::Error: Into<BoxError>,
+    {
+        type Response = TimedConnection<C::Response>;
+        type Error = C::Error;
+        type Future = TimedConnectFuture<C::Future>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.inner.poll_ready(cx)
+        }
+
+        fn call(&mut self, req: Uri) -> Self::Future {
+            TimedConnectFuture {
+                inner: self.inner.call(req),
+                time_source: self.time_source.clone(),
+            }
+        }
+    }
+
+    pin_project! {
+        pub(super) struct TimedConnectFuture<F> {
+            #[pin]
+            inner: F,
+            time_source: SharedTimeSource,
+        }
+    }
+
+    impl<F, T, E> Future for TimedConnectFuture<F>
+    where
+        F: Future<Output = Result<T, E>>,
+    {
+        type Output = Result<TimedConnection<T>, E>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = self.project();
+            let time_source = this.time_source;
+            this.inner.poll(cx).map_ok(|inner| TimedConnection {
+                inner,
+                established: ConnectionEstablished(time_source.now()),
+            })
+        }
+    }
+
+    pin_project! {
+        pub(super) struct TimedConnection<T> {
+            #[pin]
+            inner: T,
+            established: ConnectionEstablished,
+        }
+    }
+
+    impl<T: Connection> Connection for TimedConnection<T> {
+        fn connected(&self) -> Connected {
+            self.inner.connected().extra(self.established)
+        }
+    }
+
+    impl<T: Read> Read for TimedConnection<T> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: ReadBufCursor<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            self.project().inner.poll_read(cx, buf)
+        }
+    }
+
+    impl<T: Write> Write for TimedConnection<T> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            self.project().inner.poll_write(cx, buf)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            self.project().inner.poll_flush(cx)
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            self.project().inner.poll_shutdown(cx)
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            self.inner.is_write_vectored()
+        }
+
+        fn poll_write_vectored(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[IoSlice<'_>],
+        ) -> Poll<std::io::Result<usize>> {
+            self.project().inner.poll_write_vectored(cx, bufs)
+        }
+    }
+}
+
+mod timeout_middleware {
+    use aws_smithy_async::future::timeout::{TimedOutError, Timeout};
+    use aws_smithy_async::rt::sleep::Sleep;
+    use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep};
+    use aws_smithy_runtime_api::box_error::BoxError;
+    use http_1x::Uri;
+    use pin_project_lite::pin_project;
+    use std::error::Error;
+    use std::fmt::Formatter;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use std::time::Duration;
+
+    #[derive(Debug)]
+    pub(crate) struct HttpTimeoutError {
+        kind: &'static str,
+        duration: Duration,
+    }
+
+    impl std::fmt::Display for HttpTimeoutError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(
+                f,
+                "{} timeout occurred after {:?}",
+                self.kind, self.duration
+            )
+        }
+    }
+
+    impl Error for HttpTimeoutError {
+        // We implement the `source` function as returning a `TimedOutError` because when `downcast_error`
+        // or `find_source` is called with an `HttpTimeoutError` (or another error wrapping an `HttpTimeoutError`)
+        // this method will be checked to determine if it's a timeout-related error.
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&TimedOutError)
+        }
+    }
+
+    /// Timeout wrapper that will timeout on the initial TCP connection
+    ///
+    /// # Stability
+    /// This interface is unstable.
+    #[derive(Clone, Debug)]
+    pub(super) struct ConnectTimeout<I> {
+        inner: I,
+        timeout: Option<(SharedAsyncSleep, Duration)>,
+    }
+
+    impl<I> ConnectTimeout<I> {
+        /// Create a new `ConnectTimeout` around `inner`.
+        ///
+        /// Typically, `I` will implement [`hyper_util::client::legacy::connect::Connect`].
+        pub(crate) fn new(inner: I, sleep: SharedAsyncSleep, timeout: Duration) -> Self {
+            Self {
+                inner,
+                timeout: Some((sleep, timeout)),
+            }
+        }
+
+        pub(crate) fn no_timeout(inner: I) -> Self {
+            Self {
+                inner,
+                timeout: None,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub(crate) struct HttpReadTimeout<I> {
+        inner: I,
+        timeout: Option<(SharedAsyncSleep, Duration)>,
+    }
+
+    impl<I> HttpReadTimeout<I> {
+        /// Create a new `HttpReadTimeout` around `inner`.
+        ///
+        /// Typically, `I` will implement [`tower_service::Service<http_1x::Request<SdkBody>>`](tower_service::Service).
+        pub(crate) fn new(inner: I, sleep: SharedAsyncSleep, timeout: Duration) -> Self {
+            Self {
+                inner,
+                timeout: Some((sleep, timeout)),
+            }
+        }
+
+        pub(crate) fn no_timeout(inner: I) -> Self {
+            Self {
+                inner,
+                timeout: None,
+            }
+        }
+    }
+
+    pin_project! {
+        /// Timeout future for Tower services
+        ///
+        /// Timeout future to handle timing out, mapping errors, and the possibility of not timing out
+        /// without incurring an additional allocation for each timeout layer.
+        #[project = MaybeTimeoutFutureProj]
+        pub enum MaybeTimeoutFuture<F> {
+            Timeout {
+                #[pin]
+                timeout: Timeout<F, Sleep>,
+                error_type: &'static str,
+                duration: Duration,
+            },
+            NoTimeout {
+                #[pin]
+                future: F
+            }
+        }
+    }
+
+    impl<F, T, E> Future for MaybeTimeoutFuture<F>
+    where
+        F: Future<Output = Result<T, E>>,
+        E: Into<BoxError>,
+    {
+        type Output = Result<T, BoxError>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let (timeout_future, kind, &mut duration) = match self.project() {
+                MaybeTimeoutFutureProj::NoTimeout { future } => {
+                    return future.poll(cx).map_err(|err| err.into());
+                }
+                MaybeTimeoutFutureProj::Timeout {
+                    timeout,
+                    error_type,
+                    duration,
+                } => (timeout, error_type, duration),
+            };
+            match timeout_future.poll(cx) {
+                Poll::Ready(Ok(response)) => Poll::Ready(response.map_err(|err| err.into())),
+                Poll::Ready(Err(_timeout)) => {
+                    Poll::Ready(Err(HttpTimeoutError { kind, duration }.into()))
+                }
+                Poll::Pending => Poll::Pending,
+            }
+        }
+    }
+
+    impl<I> tower_service::Service<Uri> for ConnectTimeout<I>
+    where
+        I: tower_service::Service<Uri>,
+        I::Error: Into<BoxError>,
+    {
+        type Response = I::Response;
+        type Error = BoxError;
+        type Future = MaybeTimeoutFuture<I::Future>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.inner.poll_ready(cx).map_err(|err| err.into())
+        }
+
+        fn call(&mut self, req: Uri) -> Self::Future {
+            match &self.timeout {
+                Some((sleep, duration)) => {
+                    let sleep = sleep.sleep(*duration);
+                    MaybeTimeoutFuture::Timeout {
+                        timeout: Timeout::new(self.inner.call(req), sleep),
+                        error_type: "HTTP connect",
+                        duration: *duration,
+                    }
+                }
+                None => MaybeTimeoutFuture::NoTimeout {
+                    future: self.inner.call(req),
+                },
+            }
+        }
+    }
+
+    impl<I, B> tower_service::Service<http_1x::Request<B>> for HttpReadTimeout<I>
+    where
+        I: tower_service::Service<http_1x::Request<B>>,
+        I::Error: Send + Sync + Error + 'static,
+    {
+        type Response = I::Response;
+        type Error = BoxError;
+        type Future = MaybeTimeoutFuture<I::Future>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.inner.poll_ready(cx).map_err(|err| err.into())
+        }
+
+        fn call(&mut self, req: http_1x::Request<B>) -> Self::Future {
+            match &self.timeout {
+                Some((sleep, duration)) => {
+                    let sleep = sleep.sleep(*duration);
+                    MaybeTimeoutFuture::Timeout {
+                        timeout: Timeout::new(self.inner.call(req), sleep),
+                        error_type: "HTTP read",
+                        duration: *duration,
+                    }
+                }
+                None => MaybeTimeoutFuture::NoTimeout {
+                    future: self.inner.call(req),
+                },
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::super::*;
+        use aws_smithy_async::assert_elapsed;
+        use aws_smithy_async::future::never::Never;
+        use aws_smithy_async::rt::sleep::{SharedAsyncSleep, TokioSleep};
+        use aws_smithy_types::error::display::DisplayErrorContext;
+        use hyper_1::rt::ReadBufCursor;
+        use hyper_util::client::legacy::connect::Connected;
+        use hyper_util::rt::TokioIo;
+        use std::future::Future;
+        use std::pin::Pin;
+        use std::task::{Context, Poll};
+        use std::time::Duration;
+        use tokio::net::TcpStream;
+
+        #[allow(unused)]
+        fn connect_timeout_is_correct<T: Send + Sync + Clone + 'static>() {
+            is_send_sync::<super::ConnectTimeout<T>>();
+        }
+
+        #[allow(unused)]
+        fn is_send_sync<T: Send + Sync>() {}
+
+        /// A service that will never return whatever it is you want
+        ///
+        /// Returned futures will return Pending forever
+        #[non_exhaustive]
+        #[derive(Clone, Default, Debug)]
+        struct NeverConnects;
+        impl tower_service::Service<Uri> for NeverConnects {
+            type Response = TokioIo<TcpStream>;
+            type Error = ConnectorError;
+            type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+            fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn call(&mut self, _uri: Uri) -> Self::Future {
+                Box::pin(async move {
+                    Never::new().await;
+                    unreachable!()
+                })
+            }
+        }
+
+        /// A service that will connect but never send any data
+        #[derive(Clone, Debug, Default)]
+        struct NeverReplies;
+        impl tower_service::Service<Uri> for NeverReplies {
+            type Response = EmptyStream;
+            type Error = BoxError;
+            type Future = std::future::Ready<Result<Self::Response, Self::Error>>;
+
+            fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn call(&mut self, _req: Uri) -> Self::Future {
+                std::future::ready(Ok(EmptyStream))
+            }
+        }
+
+        /// A stream that will never return or accept any data
+        #[non_exhaustive]
+        #[derive(Debug, Default)]
+        struct EmptyStream;
+        impl Read for EmptyStream {
+            fn poll_read(
+                self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+                _buf: ReadBufCursor<'_>,
+            ) -> Poll<Result<(), std::io::Error>> {
+                Poll::Pending
+            }
+        }
+        impl Write for EmptyStream {
+            fn poll_write(
+                self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+                _buf: &[u8],
+            ) -> Poll<Result<usize, std::io::Error>> {
+                Poll::Pending
+            }
+
+            fn poll_flush(
+                self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+            ) -> Poll<Result<(), std::io::Error>> {
+                Poll::Pending
+            }
+
+            fn poll_shutdown(
+                self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+            ) -> Poll<Result<(), std::io::Error>> {
+                Poll::Pending
+            }
+        }
+        impl Connection for EmptyStream {
+            fn connected(&self) -> Connected {
+                Connected::new()
+            }
+        }
+
+        #[tokio::test]
+        async fn http_connect_timeout_works() {
+            let tcp_connector = NeverConnects::default();
+            let connector_settings = HttpConnectorSettings::builder()
+                .connect_timeout(Duration::from_secs(1))
+                .build();
+            let hyper = HyperConnector::builder()
+                .connector_settings(connector_settings)
+                .sleep_impl(SharedAsyncSleep::new(TokioSleep::new()))
+                .build(tcp_connector)
+                .adapter;
+            let now = tokio::time::Instant::now();
+            tokio::time::pause();
+            let resp = hyper
+                .call(HttpRequest::get("https://static-uri.com").unwrap())
+                .await
+                .unwrap_err();
+            assert!(
+                resp.is_timeout(),
+                "expected resp.is_timeout() to be true but it was false, resp == {:?}",
+                resp
+            );
+            let message = DisplayErrorContext(&resp).to_string();
+            let expected =
+                "timeout: client error (Connect): HTTP connect timeout occurred after 1s";
+            assert!(
+                message.contains(expected),
+                "expected '{message}' to contain '{expected}'"
+            );
+            assert_elapsed!(now, Duration::from_secs(1));
+        }
+
+        #[tokio::test]
+        async fn http_read_timeout_works() {
+            let tcp_connector = NeverReplies;
+            let connector_settings = HttpConnectorSettings::builder()
+                .connect_timeout(Duration::from_secs(1))
+                .read_timeout(Duration::from_secs(2))
+                .build();
+            let hyper = HyperConnector::builder()
+                .connector_settings(connector_settings)
+                .sleep_impl(SharedAsyncSleep::new(TokioSleep::new()))
+                .build(tcp_connector)
+                .adapter;
+            let now = tokio::time::Instant::now();
+            tokio::time::pause();
+            let err = hyper
+                .call(HttpRequest::get("https://fake-uri.com").unwrap())
+                .await
+                .unwrap_err();
+            assert!(
+                err.is_timeout(),
+                "expected err.is_timeout() to be true but it was false, err == {err:?}",
+            );
+            let message = format!("{}", DisplayErrorContext(&err));
+            let expected = "timeout: HTTP read timeout occurred after 2s";
+            assert!(
+                message.contains(expected),
+                "expected '{message}' to contain '{expected}'"
+            );
+            assert_elapsed!(now, Duration::from_secs(2));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod test {
+    use super::*;
+    use crate::client::http::test_util::NeverTcpConnector;
+    use aws_smithy_async::time::SystemTimeSource;
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+    use hyper_1::rt::ReadBufCursor;
+    use hyper_util::client::legacy::connect::Connected;
+    use std::io::{Error, ErrorKind};
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Waker};
+
+    #[tokio::test]
+    async fn connector_selection() {
+        // Create a client that increments a count every time it creates a new HyperConnector
+        let creation_count = Arc::new(AtomicU32::new(0));
+        let http_client = HyperClientBuilder::new().build_with_fn({
+            let count = creation_count.clone();
+            move || {
+                count.fetch_add(1, Ordering::Relaxed);
+                NeverTcpConnector::new()
+            }
+        });
+
+        // This configuration should result in 4 separate connectors with different timeout settings
+        let settings = [
+            HttpConnectorSettings::builder()
+                .connect_timeout(Duration::from_secs(3))
+                .build(),
+            HttpConnectorSettings::builder()
+                .read_timeout(Duration::from_secs(3))
+                .build(),
+            HttpConnectorSettings::builder()
+                .connect_timeout(Duration::from_secs(3))
+                .read_timeout(Duration::from_secs(3))
+                .build(),
+            HttpConnectorSettings::builder()
+                .connect_timeout(Duration::from_secs(5))
+                .read_timeout(Duration::from_secs(3))
+                .build(),
+        ];
+
+        // Kick off thousands of parallel tasks that will try to create a connector
+        let components = RuntimeComponentsBuilder::for_tests()
+            .with_time_source(Some(SystemTimeSource::new()))
+            .build()
+            .unwrap();
+        let mut handles = Vec::new();
+        for setting in &settings {
+            for _ in 0..1000 {
+                let client = http_client.clone();
+                handles.push(tokio::spawn({
+                    let setting = setting.clone();
+                    let components = components.clone();
+                    async move {
+                        let _ = client.http_connector(&setting, &components);
+                    }
+                }));
+            }
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        // Verify only 4 connectors were created amidst the chaos
+        assert_eq!(4, creation_count.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn hyper_io_error() {
+        let connector = TestConnection {
+            inner: HangupStream,
+        };
+        let adapter = HyperConnector::builder().build(connector).adapter;
+        let err = adapter
+            .call(HttpRequest::get("https://socket-hangup.com").unwrap())
+            .await
+            .expect_err("socket hangup");
+        assert!(err.is_io(), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn connection_metadata_can_poison_the_connection() {
+        let connector = TestConnection {
+            inner: CannedResponseStream::default(),
+        };
+        let adapter = HyperConnector::builder().build(connector).adapter;
+        let capture = CaptureSmithyConnection::new();
+        let mut request = HttpRequest::get("http://example.com").unwrap();
+        request.add_extension(capture.clone());
+        let response = adapter.call(request).await.expect("success");
+        assert_eq!(200, response.status().as_u16());
+
+        let metadata = capture
+            .get()
+            .expect("the connection should have been captured");
+        assert!(metadata.acquire_duration().is_some());
+        assert_eq!(Some(HttpVersion::Http11), metadata.http_version());
+        metadata.poison();
+    }
+
+    #[cfg(any(feature = "tls-rustls-hyper-1-x", feature = "tls-native-tls-hyper-1-x"))]
+    #[tokio::test]
+    async fn custom_dns_resolver_is_used() {
+        use aws_smithy_runtime_api::client::dns::{DnsFuture, ResolveDns};
+        use std::net::{IpAddr, Ipv4Addr};
+        use tokio::net::TcpListener;
+
+        #[derive(Clone, Debug)]
+        struct StaticResolver(IpAddr);
+
+        impl ResolveDns for StaticResolver {
+            fn resolve_dns<'a>(&'a self, _name: &'a str) -> DnsFuture<'a> {
+                DnsFuture::ready(Ok(vec![self.0]))
+            }
+        }
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 1024];
+            let _ = stream.read(&mut buf).await.unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+        });
+
+        let resolver = StaticResolver(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let connector = HyperConnector::builder()
+            .build(dns::http_connector(dns::HyperUtilResolver { resolver }));
+        let response = connector
+            .call(HttpRequest::get(format!("http://does-not-exist.invalid:{port}")).unwrap())
+            .await
+            .expect("the custom resolver should resolve to the local server");
+        assert_eq!(200, response.status().as_u16());
+        server.await.unwrap();
+    }
+
+    #[cfg(any(feature = "tls-rustls-hyper-1-x", feature = "tls-native-tls-hyper-1-x"))]
+    #[tokio::test]
+    async fn connection_refused_is_an_io_error() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let connector =
+            HyperConnector::builder().build(dns::http_connector(dns::GaiResolver::new()));
+        let err = connector
+            .call(HttpRequest::get(format!("http://127.0.0.1:{port}")).unwrap())
+            .await
+            .expect_err("nothing is listening");
+        assert!(err.is_io(), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn http2_prior_knowledge() {
+        use hyper_0_14::service::{make_service_fn, service_fn};
+        use hyper_0_14::{Body, Response, Server};
+        use std::convert::Infallible;
+
+        // A plaintext server that only speaks HTTP/2
+        let make_service = make_service_fn(|_conn| async {
+            Ok::<_, Infallible>(service_fn(|_req| async {
+                Ok::<_, Infallible>(Response::new(Body::from("hello")))
+            }))
+        });
+        let server = Server::bind(&([127, 0, 0, 1], 0).into())
+            .http2_only(true)
+            .serve(make_service);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let adapter = HyperConnector::builder()
+            .http2_settings(Http2Settings::builder().prior_knowledge(true).build())
+            .build(hyper_util::client::legacy::connect::HttpConnector::new())
+            .adapter;
+        let capture = CaptureSmithyConnection::new();
+        let mut request = HttpRequest::get(format!("http://{addr}")).unwrap();
+        request.add_extension(capture.clone());
+        let response = adapter.call(request).await.expect("success");
+        assert_eq!(200, response.status().as_u16());
+
+        let metadata = capture
+            .get()
+            .expect("the connection should have been captured");
+        assert_eq!(Some(HttpVersion::Http2), metadata.http_version());
+    }
+
+    // ---- machinery to make a Hyper connector that responds with an IO Error
+    #[derive(Clone)]
+    struct HangupStream;
+
+    impl Connection for HangupStream {
+        fn connected(&self) -> Connected {
+            Connected::new()
+        }
+    }
+
+    impl Read for HangupStream {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: ReadBufCursor<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Err(Error::new(
+                ErrorKind::ConnectionReset,
+                "connection reset",
+            )))
+        }
+    }
+
+    impl Write for HangupStream {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: &[u8],
+        ) -> Poll<Result<usize, Error>> {
+            Poll::Pending
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+            Poll::Pending
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+            Poll::Pending
+        }
+    }
+
+    // ---- machinery to make a Hyper connector that responds to every request with an empty 200
+    #[derive(Clone, Default)]
+    struct CannedResponseStream {
+        state: Arc<Mutex<CannedResponseState>>,
+    }
+
+    #[derive(Default)]
+    struct CannedResponseState {
+        requested: bool,
+        responded: bool,
+        read_waker: Option<Waker>,
+    }
+
+    impl Connection for CannedResponseStream {
+        fn connected(&self) -> Connected {
+            Connected::new()
+        }
+    }
+
+    impl Read for CannedResponseStream {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            mut buf: ReadBufCursor<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let mut state = self.state.lock().unwrap();
+            if state.requested && !state.responded {
+                state.responded = true;
+                buf.put_slice(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n");
+                Poll::Ready(Ok(()))
+            } else {
+                state.read_waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    impl Write for CannedResponseStream {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<Result<usize, Error>> {
+            let mut state = self.state.lock().unwrap();
+            state.requested = true;
+            if let Some(waker) = state.read_waker.take() {
+                waker.wake();
+            }
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[derive(Clone)]
+    struct TestConnection<T> {
+        inner: T,
+    }
+
+    impl<T> Service<Uri> for TestConnection<T>
+    where
+        T: Clone + Connection,
+    {
+        type Response = T;
+        type Error = BoxError;
+        type Future = std::future::Ready<Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _req: Uri) -> Self::Future {
+            std::future::ready(Ok(self.inner.clone()))
+        }
+    }
+}
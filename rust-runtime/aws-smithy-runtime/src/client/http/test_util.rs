@@ -29,10 +29,14 @@
 //! - [`NeverClient`]: Useful for testing timeouts, where you want the client to never respond.
 //!
 #![cfg_attr(
-    feature = "connector-hyper-0-14-x",
+    any(feature = "connector-hyper-0-14-x", feature = "connector-hyper-1-x"),
     doc = "
 There is also the [`NeverTcpConnector`], which makes it easy to test connect/read timeouts.
-
+"
+)]
+#![cfg_attr(
+    feature = "connector-hyper-0-14-x",
+    doc = "
 Finally, for socket-level mocking, see the [`wire`] module.
 "
 )]
@@ -51,7 +55,7 @@ pub use infallible::infallible_client_fn;
 mod never;
 pub use never::NeverClient;
 
-#[cfg(feature = "connector-hyper-0-14-x")]
+#[cfg(any(feature = "connector-hyper-0-14-x", feature = "connector-hyper-1-x"))]
 pub use never::NeverTcpConnector;
 
 #[cfg(all(feature = "connector-hyper-0-14-x", feature = "wire-mock"))]
@@ -56,12 +56,12 @@ impl HttpClient for NeverClient {
 }
 
 /// A TCP connector that never connects.
-// In the future, this can be available for multiple hyper version feature flags, with the impls gated between individual features
-#[cfg(feature = "connector-hyper-0-14-x")]
+// This is available for multiple hyper version feature flags, with the impls gated between individual features
+#[cfg(any(feature = "connector-hyper-0-14-x", feature = "connector-hyper-1-x"))]
 #[derive(Clone, Debug, Default)]
 pub struct NeverTcpConnector;
 
-#[cfg(feature = "connector-hyper-0-14-x")]
+#[cfg(any(feature = "connector-hyper-0-14-x", feature = "connector-hyper-1-x"))]
 impl NeverTcpConnector {
     /// Creates a new `NeverTcpConnector`.
     pub fn new() -> Self {
@@ -140,6 +140,77 @@ mod connection {
     }
 }
 
+#[cfg(feature = "connector-hyper-1-x")]
+impl tower_service::Service<http_1x::Uri> for NeverTcpConnector {
+    type Response = connection_1x::NeverTcpConnection;
+    type Error = aws_smithy_runtime_api::box_error::BoxError;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send + Sync>,
+    >;
+
+    fn poll_ready(
+        &mut self,
+        _: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _: http_1x::Uri) -> Self::Future {
+        Box::pin(async {
+            Never::new().await;
+            unreachable!()
+        })
+    }
+}
+
+#[cfg(feature = "connector-hyper-1-x")]
+mod connection_1x {
+    use hyper_1::rt::{Read, ReadBufCursor, Write};
+    use hyper_util::client::legacy::connect::{Connected, Connection};
+    use std::io::Error;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    /// A connection type that appeases hyper 1.x's trait bounds for a TCP connector, but will panic if any of its traits are used.
+    #[non_exhaustive]
+    #[derive(Debug, Default)]
+    pub struct NeverTcpConnection;
+
+    impl Connection for NeverTcpConnection {
+        fn connected(&self) -> Connected {
+            unreachable!()
+        }
+    }
+
+    impl Read for NeverTcpConnection {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: ReadBufCursor<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            unreachable!()
+        }
+    }
+
+    impl Write for NeverTcpConnection {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: &[u8],
+        ) -> Poll<Result<usize, Error>> {
+            unreachable!()
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+            unreachable!()
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+            unreachable!()
+        }
+    }
+}
+
 #[cfg(all(test, feature = "connector-hyper-0-14-x"))]
 #[tokio::test]
 async fn never_tcp_connector_plugs_into_hyper_014() {
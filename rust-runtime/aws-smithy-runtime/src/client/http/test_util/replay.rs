@@ -269,11 +269,11 @@ mod test {
     #[test]
     fn create_from_either_http_type() {
         let _client = StaticReplayClient::new(vec![ReplayEvent::new(
-            http1::Request::builder()
+            http_1x::Request::builder()
                 .uri("test")
                 .body(SdkBody::from("hello"))
                 .unwrap(),
-            http1::Response::builder()
+            http_1x::Response::builder()
                 .status(200)
                 .body(SdkBody::from("hello"))
                 .unwrap(),
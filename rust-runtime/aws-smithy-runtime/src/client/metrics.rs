@@ -0,0 +1,530 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Metrics for the client orchestrator.
+//!
+//! The [`MetricsInterceptor`] records the following metrics for every operation invocation
+//! into a [`Meter`]:
+//!
+//! | Name | Kind | Description |
+//! |------|------|-------------|
+//! | [`CALL_DURATION`] | histogram (seconds) | Overall time taken to invoke the operation, including retries |
+//! | [`ATTEMPT_DURATION`] | histogram (seconds) | Time taken by a single request attempt |
+//! | [`SERIALIZATION_DURATION`] | histogram (seconds) | Time taken to serialize the request |
+//! | [`DESERIALIZATION_DURATION`] | histogram (seconds) | Time taken to deserialize the response |
+//! | [`CONNECTION_ACQUIRE_DURATION`] | histogram (seconds) | Time an attempt waited for an HTTP connection |
+//! | [`ATTEMPTS`] | counter | Number of request attempts made |
+//! | [`RETRIES`] | counter | Number of request attempts that were retries |
+//! | [`ERRORS`] | counter | Number of operation invocations that failed |
+//!
+//! Every value is recorded with the `rpc.service` and `rpc.method` attributes, which are taken from
+//! the operation's [`Metadata`]. [`ERRORS`] is also recorded with a `phase` attribute naming the
+//! phase in which the invocation failed: `before_serialization`, `serialization`,
+//! `before_transmit`, `transmit`, `before_deserialization`, `deserialization`, or
+//! `after_deserialization`.
+//!
+//! Durations are measured with the client's [`TimeSource`](aws_smithy_async::time::TimeSource),
+//! and nothing is recorded for them if the client has no time source. The connection acquisition
+//! time is only known when the HTTP connector reports it through
+//! [`ConnectionMetadata::acquire_duration`], as the built-in hyper connector does. This relies on
+//! the [`ConnectionPoisoningInterceptor`](crate::client::http::connection_poisoning::ConnectionPoisoningInterceptor),
+//! which is installed by default, to capture the connection.
+//!
+//! The interceptor can be installed with the [`MetricsRuntimePlugin`], or by adding a
+//! [`MetricsInterceptor`] to a
+//! [`RuntimeComponentsBuilder`].
+//!
+//! With the `metrics-opentelemetry` feature enabled, the [`opentelemetry`] module provides a
+//! [`Meter`] that records into an OpenTelemetry meter.
+//!
+//! [`ConnectionMetadata::acquire_duration`]: aws_smithy_runtime_api::client::connection::ConnectionMetadata::acquire_duration
+
+use crate::client::http::connection_poisoning::CaptureSmithyConnection;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::interceptors::context::{
+    AfterDeserializationInterceptorContextRef, BeforeDeserializationInterceptorContextRef,
+    BeforeSerializationInterceptorContextRef, BeforeTransmitInterceptorContextRef,
+    FinalizerInterceptorContextRef,
+};
+use aws_smithy_runtime_api::client::interceptors::{Intercept, SharedInterceptor};
+use aws_smithy_runtime_api::client::orchestrator::Metadata;
+use aws_smithy_runtime_api::client::runtime_components::{
+    RuntimeComponents, RuntimeComponentsBuilder,
+};
+use aws_smithy_runtime_api::client::runtime_plugin::RuntimePlugin;
+use aws_smithy_runtime_api::impl_shared_conversions;
+use aws_smithy_runtime_api::shared::IntoShared;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// Records metrics into an OpenTelemetry meter.
+#[cfg(feature = "metrics-opentelemetry")]
+pub mod opentelemetry;
+
+/// A [`Meter`] that keeps recorded values in memory, for use in tests.
+#[cfg(feature = "test-util")]
+pub mod test_util;
+
+/// Overall time taken to invoke an operation, in seconds.
+pub const CALL_DURATION: &str = "smithy.client.call.duration";
+/// Time taken by a single request attempt, in seconds.
+pub const ATTEMPT_DURATION: &str = "smithy.client.call.attempt_duration";
+/// Time taken to serialize a request, in seconds.
+pub const SERIALIZATION_DURATION: &str = "smithy.client.call.serialization_duration";
+/// Time taken to deserialize a response, in seconds.
+pub const DESERIALIZATION_DURATION: &str = "smithy.client.call.deserialization_duration";
+/// Time a request attempt waited for an HTTP connection, in seconds.
+pub const CONNECTION_ACQUIRE_DURATION: &str = "smithy.client.http.connections.acquire_duration";
+/// Number of request attempts made.
+pub const ATTEMPTS: &str = "smithy.client.call.attempts";
+/// Number of request attempts that were retries.
+pub const RETRIES: &str = "smithy.client.call.retries";
+/// Number of operation invocations that failed.
+pub const ERRORS: &str = "smithy.client.call.errors";
+
+/// Records client metrics.
+///
+/// Attributes are given as `(key, value)` pairs.
+pub trait Meter: Send + Sync + fmt::Debug {
+    /// Records `value` in the histogram called `name`.
+    fn record_histogram(&self, name: &'static str, value: f64, attributes: &[(&'static str, &str)]);
+
+    /// Adds `value` to the monotonic counter called `name`.
+    fn add_to_counter(&self, name: &'static str, value: u64, attributes: &[(&'static str, &str)]);
+}
+
+/// A shared meter.
+#[derive(Clone, Debug)]
+pub struct SharedMeter(Arc<dyn Meter>);
+
+impl SharedMeter {
+    /// Creates a new `SharedMeter` from the given meter.
+    pub fn new(meter: impl Meter + 'static) -> Self {
+        Self(Arc::new(meter))
+    }
+}
+
+impl Meter for SharedMeter {
+    fn record_histogram(
+        &self,
+        name: &'static str,
+        value: f64,
+        attributes: &[(&'static str, &str)],
+    ) {
+        self.0.record_histogram(name, value, attributes)
+    }
+
+    fn add_to_counter(&self, name: &'static str, value: u64, attributes: &[(&'static str, &str)]) {
+        self.0.add_to_counter(name, value, attributes)
+    }
+}
+
+impl_shared_conversions!(convert SharedMeter from Meter using SharedMeter::new);
+
+/// Timestamps for the operation invocation that is currently in progress.
+#[derive(Clone, Debug, Default)]
+struct CallTimings {
+    call_start: Option<SystemTime>,
+    attempt_start: Option<SystemTime>,
+    deserialization_start: Option<SystemTime>,
+    attempts: u32,
+    /// The phase the invocation was in when the interceptor last ran, if it has reached serialization.
+    phase: Option<&'static str>,
+}
+
+impl Storable for CallTimings {
+    type Storer = StoreReplace<Self>;
+}
+
+fn update_timings(cfg: &mut ConfigBag, update: impl FnOnce(&mut CallTimings)) {
+    let mut timings = cfg.load::<CallTimings>().cloned().unwrap_or_default();
+    update(&mut timings);
+    cfg.interceptor_state().store_put(timings);
+}
+
+fn now(runtime_components: &RuntimeComponents) -> Option<SystemTime> {
+    runtime_components
+        .time_source()
+        .map(|time_source| time_source.now())
+}
+
+fn elapsed(start: Option<SystemTime>, runtime_components: &RuntimeComponents) -> Option<Duration> {
+    let (start, now) = (start?, now(runtime_components)?);
+    Some(now.duration_since(start).unwrap_or_default())
+}
+
+/// Interceptor that records client metrics into a [`Meter`].
+///
+/// See the [module docs](crate::client::metrics) for the metrics that are recorded.
+#[derive(Debug)]
+pub struct MetricsInterceptor {
+    meter: SharedMeter,
+}
+
+impl MetricsInterceptor {
+    /// Creates a new `MetricsInterceptor` that records into the given meter.
+    pub fn new(meter: impl Meter + 'static) -> Self {
+        Self {
+            meter: meter.into_shared(),
+        }
+    }
+
+    fn record_duration(&self, name: &'static str, duration: Option<Duration>, cfg: &ConfigBag) {
+        if let Some(duration) = duration {
+            self.meter
+                .record_histogram(name, duration.as_secs_f64(), &attributes(cfg));
+        }
+    }
+}
+
+fn attributes(cfg: &ConfigBag) -> [(&'static str, &str); 2] {
+    let metadata = cfg.load::<Metadata>();
+    [
+        ("rpc.service", metadata.map(Metadata::service).unwrap_or("")),
+        ("rpc.method", metadata.map(Metadata::name).unwrap_or("")),
+    ]
+}
+
+impl Intercept for MetricsInterceptor {
+    fn name(&self) -> &'static str {
+        "MetricsInterceptor"
+    }
+
+    fn read_before_serialization(
+        &self,
+        _context: &BeforeSerializationInterceptorContextRef<'_>,
+        runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let call_start = now(runtime_components);
+        cfg.interceptor_state().store_put(CallTimings {
+            call_start,
+            phase: Some("serialization"),
+            ..Default::default()
+        });
+        Ok(())
+    }
+
+    fn read_after_serialization(
+        &self,
+        _context: &BeforeTransmitInterceptorContextRef<'_>,
+        runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        update_timings(cfg, |timings| timings.phase = Some("before_transmit"));
+        let call_start = cfg.load::<CallTimings>().and_then(|t| t.call_start);
+        self.record_duration(
+            SERIALIZATION_DURATION,
+            elapsed(call_start, runtime_components),
+            cfg,
+        );
+        Ok(())
+    }
+
+    fn read_before_attempt(
+        &self,
+        _context: &BeforeTransmitInterceptorContextRef<'_>,
+        runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let attempt_start = now(runtime_components);
+        let mut attempts = 0;
+        update_timings(cfg, |timings| {
+            timings.attempt_start = attempt_start;
+            timings.deserialization_start = None;
+            timings.attempts += 1;
+            timings.phase = Some("before_transmit");
+            attempts = timings.attempts;
+        });
+        let attributes = attributes(cfg);
+        self.meter.add_to_counter(ATTEMPTS, 1, &attributes);
+        if attempts > 1 {
+            self.meter.add_to_counter(RETRIES, 1, &attributes);
+        }
+        Ok(())
+    }
+
+    fn read_before_transmit(
+        &self,
+        _context: &BeforeTransmitInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        update_timings(cfg, |timings| timings.phase = Some("transmit"));
+        Ok(())
+    }
+
+    fn read_after_transmit(
+        &self,
+        _context: &BeforeDeserializationInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        update_timings(cfg, |timings| {
+            timings.phase = Some("before_deserialization")
+        });
+        let acquire_duration = cfg
+            .load::<CaptureSmithyConnection>()
+            .and_then(CaptureSmithyConnection::get)
+            .and_then(|connection| connection.acquire_duration());
+        self.record_duration(CONNECTION_ACQUIRE_DURATION, acquire_duration, cfg);
+        Ok(())
+    }
+
+    fn read_before_deserialization(
+        &self,
+        _context: &BeforeDeserializationInterceptorContextRef<'_>,
+        runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let deserialization_start = now(runtime_components);
+        update_timings(cfg, |timings| {
+            timings.deserialization_start = deserialization_start;
+            timings.phase = Some("deserialization");
+        });
+        Ok(())
+    }
+
+    fn read_after_deserialization(
+        &self,
+        _context: &AfterDeserializationInterceptorContextRef<'_>,
+        runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        update_timings(cfg, |timings| timings.phase = Some("after_deserialization"));
+        let deserialization_start = cfg
+            .load::<CallTimings>()
+            .and_then(|t| t.deserialization_start);
+        self.record_duration(
+            DESERIALIZATION_DURATION,
+            elapsed(deserialization_start, runtime_components),
+            cfg,
+        );
+        Ok(())
+    }
+
+    fn read_after_attempt(
+        &self,
+        _context: &FinalizerInterceptorContextRef<'_>,
+        runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let attempt_start = cfg.load::<CallTimings>().and_then(|t| t.attempt_start);
+        self.record_duration(
+            ATTEMPT_DURATION,
+            elapsed(attempt_start, runtime_components),
+            cfg,
+        );
+        Ok(())
+    }
+
+    fn read_after_execution(
+        &self,
+        context: &FinalizerInterceptorContextRef<'_>,
+        runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let call_start = cfg.load::<CallTimings>().and_then(|t| t.call_start);
+        self.record_duration(CALL_DURATION, elapsed(call_start, runtime_components), cfg);
+        if context.inner().is_failed() {
+            let phase = cfg
+                .load::<CallTimings>()
+                .and_then(|t| t.phase)
+                .unwrap_or("before_serialization");
+            let [service, method] = attributes(cfg);
+            self.meter
+                .add_to_counter(ERRORS, 1, &[service, method, ("phase", phase)]);
+        }
+        Ok(())
+    }
+}
+
+/// Runtime plugin that installs a [`MetricsInterceptor`].
+#[derive(Debug)]
+pub struct MetricsRuntimePlugin {
+    runtime_components: RuntimeComponentsBuilder,
+}
+
+impl MetricsRuntimePlugin {
+    /// Creates a new `MetricsRuntimePlugin` that records into the given meter.
+    pub fn new(meter: impl Meter + 'static) -> Self {
+        Self {
+            runtime_components: RuntimeComponentsBuilder::new("MetricsRuntimePlugin")
+                .with_interceptor(SharedInterceptor::new(MetricsInterceptor::new(meter))),
+        }
+    }
+}
+
+impl RuntimePlugin for MetricsRuntimePlugin {
+    fn runtime_components(
+        &self,
+        _: &RuntimeComponentsBuilder,
+    ) -> Cow<'_, RuntimeComponentsBuilder> {
+        Cow::Borrowed(&self.runtime_components)
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::test_util::InMemoryMeter;
+    use super::*;
+    use crate::client::http::test_util::infallible_client_fn;
+    use crate::client::orchestrator::operation::Operation;
+    use crate::client::retries::classifiers::HttpStatusCodeClassifier;
+    use aws_smithy_async::test_util::instant_time_and_sleep;
+    use aws_smithy_runtime_api::client::http::{
+        http_client_fn, HttpConnector, HttpConnectorFuture,
+    };
+    use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, OrchestratorError};
+    use aws_smithy_runtime_api::client::result::ConnectorError;
+    use aws_smithy_runtime_api::client::runtime_plugin::StaticRuntimePlugin;
+    use aws_smithy_types::body::SdkBody;
+    use aws_smithy_types::config_bag::Layer;
+    use aws_smithy_types::retry::RetryConfig;
+    use aws_smithy_types::timeout::TimeoutConfig;
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Generated clients store the operation's metadata in the config bag
+    fn metadata() -> StaticRuntimePlugin {
+        let mut layer = Layer::new("metadata");
+        layer.store_put(Metadata::new("TestOperation", "test-service"));
+        StaticRuntimePlugin::new().with_config(layer.freeze())
+    }
+
+    #[tokio::test]
+    async fn records_call_metrics() {
+        let (time_source, sleep) = instant_time_and_sleep(SystemTime::UNIX_EPOCH);
+        let requests = Arc::new(AtomicUsize::new(0));
+        let http_client = infallible_client_fn({
+            let (requests, time_source) = (requests.clone(), time_source.clone());
+            move |_| {
+                time_source.advance(Duration::from_millis(100));
+                let status = match requests.fetch_add(1, Ordering::SeqCst) {
+                    0 => 503,
+                    _ => 200,
+                };
+                http::Response::builder()
+                    .status(status)
+                    .body(SdkBody::empty())
+                    .unwrap()
+            }
+        });
+        let meter = InMemoryMeter::new();
+        let operation = Operation::builder()
+            .service_name("test-service")
+            .operation_name("TestOperation")
+            .http_client(http_client)
+            .endpoint_url("http://localhost:1234")
+            .no_auth()
+            .standard_retry(
+                &RetryConfig::standard()
+                    .with_max_attempts(3)
+                    .with_initial_backoff(Duration::from_secs(1))
+                    .with_use_static_exponential_base(true),
+            )
+            .retry_classifier(HttpStatusCodeClassifier::default())
+            .timeout_config(TimeoutConfig::disabled())
+            .sleep_impl(sleep)
+            .time_source(time_source.clone())
+            .runtime_plugin(metadata())
+            .runtime_plugin(MetricsRuntimePlugin::new(meter.clone()))
+            .serializer({
+                let time_source = time_source.clone();
+                move |_: ()| {
+                    time_source.advance(Duration::from_millis(10));
+                    Ok(HttpRequest::empty())
+                }
+            })
+            .deserializer::<(), Infallible>({
+                let time_source = time_source.clone();
+                move |response| {
+                    time_source.advance(Duration::from_millis(20));
+                    if response.status().is_success() {
+                        Ok(())
+                    } else {
+                        Err(OrchestratorError::other("service unavailable"))
+                    }
+                }
+            })
+            .build();
+
+        operation.invoke(()).await.expect("succeeds on retry");
+
+        let attributes = [
+            ("rpc.method", "TestOperation"),
+            ("rpc.service", "test-service"),
+        ];
+        assert_eq!(2, meter.counter(ATTEMPTS, &attributes));
+        assert_eq!(1, meter.counter(RETRIES, &attributes));
+        assert_eq!(0, meter.counter(ERRORS, &attributes));
+        assert_eq!(
+            vec![0.01],
+            meter.histogram(SERIALIZATION_DURATION, &attributes)
+        );
+        assert_eq!(
+            vec![0.02, 0.02],
+            meter.histogram(DESERIALIZATION_DURATION, &attributes)
+        );
+        assert_eq!(
+            vec![0.12, 0.12],
+            meter.histogram(ATTEMPT_DURATION, &attributes)
+        );
+        // 10ms serialization, two 120ms attempts, and a 1s backoff
+        assert_eq!(vec![1.25], meter.histogram(CALL_DURATION, &attributes));
+    }
+
+    #[derive(Debug)]
+    struct ConnectionRefused;
+
+    impl HttpConnector for ConnectionRefused {
+        fn call(&self, _request: HttpRequest) -> HttpConnectorFuture {
+            HttpConnectorFuture::ready(Err(ConnectorError::io("connection refused".into())))
+        }
+    }
+
+    #[tokio::test]
+    async fn records_the_phase_of_failures() {
+        let meter = InMemoryMeter::new();
+        let operation = Operation::builder()
+            .service_name("test-service")
+            .operation_name("TestOperation")
+            .http_client(http_client_fn(|_, _| ConnectionRefused.into_shared()))
+            .endpoint_url("http://localhost:1234")
+            .no_auth()
+            .no_retry()
+            .timeout_config(TimeoutConfig::disabled())
+            .runtime_plugin(metadata())
+            .runtime_plugin(MetricsRuntimePlugin::new(meter.clone()))
+            .serializer(|_: ()| Ok(HttpRequest::empty()))
+            .deserializer::<(), Infallible>(|_| unreachable!("no response is received"))
+            .build();
+
+        operation.invoke(()).await.expect_err("connection refused");
+
+        let attributes = [
+            ("rpc.method", "TestOperation"),
+            ("rpc.service", "test-service"),
+        ];
+        assert_eq!(1, meter.counter(ATTEMPTS, &attributes));
+        assert_eq!(
+            1,
+            meter.counter(
+                ERRORS,
+                &[
+                    ("phase", "transmit"),
+                    ("rpc.method", "TestOperation"),
+                    ("rpc.service", "test-service"),
+                ]
+            )
+        );
+        assert!(meter
+            .histogram(DESERIALIZATION_DURATION, &attributes)
+            .is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::client::metrics::Meter;
+use opentelemetry::metrics::{Counter, Histogram, Unit};
+use opentelemetry::KeyValue;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// A [`Meter`] that records into an OpenTelemetry [`Meter`](opentelemetry::metrics::Meter).
+///
+/// Histograms are created with a unit of seconds, since every histogram recorded by the
+/// [`MetricsInterceptor`](crate::client::metrics::MetricsInterceptor) is a duration. Instruments
+/// are created the first time they are recorded to, and then reused.
+///
+/// The meter is an `opentelemetry` 0.21 meter, which is part of the public API of this crate with
+/// the `metrics-opentelemetry` feature enabled. Upgrading `opentelemetry` to a version that isn't
+/// semver compatible is a breaking change, so the meter must come from the same major version of
+/// `opentelemetry` that this crate depends on.
+#[derive(Debug)]
+pub struct OpenTelemetryMeter {
+    meter: opentelemetry::metrics::Meter,
+    histograms: Mutex<HashMap<&'static str, Histogram<f64>>>,
+    counters: Mutex<HashMap<&'static str, Counter<u64>>>,
+}
+
+impl OpenTelemetryMeter {
+    /// Creates a new `OpenTelemetryMeter` that records into the given OpenTelemetry meter.
+    pub fn new(meter: opentelemetry::metrics::Meter) -> Self {
+        Self {
+            meter,
+            histograms: Default::default(),
+            counters: Default::default(),
+        }
+    }
+}
+
+fn key_values(attributes: &[(&'static str, &str)]) -> Vec<KeyValue> {
+    attributes
+        .iter()
+        .map(|(key, value)| KeyValue::new(*key, value.to_string()))
+        .collect()
+}
+
+impl Meter for OpenTelemetryMeter {
+    fn record_histogram(
+        &self,
+        name: &'static str,
+        value: f64,
+        attributes: &[(&'static str, &str)],
+    ) {
+        let histogram = self
+            .histograms
+            .lock()
+            .unwrap()
+            .entry(name)
+            .or_insert_with(|| {
+                self.meter
+                    .f64_histogram(name)
+                    .with_unit(Unit::new("s"))
+                    .init()
+            })
+            .clone();
+        histogram.record(value, &key_values(attributes));
+    }
+
+    fn add_to_counter(&self, name: &'static str, value: u64, attributes: &[(&'static str, &str)]) {
+        let counter = self
+            .counters
+            .lock()
+            .unwrap()
+            .entry(name)
+            .or_insert_with(|| self.meter.u64_counter(name).init())
+            .clone();
+        counter.add(value, &key_values(attributes));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OpenTelemetryMeter;
+    use crate::client::metrics::{Meter, ATTEMPTS, CALL_DURATION};
+    use opentelemetry::metrics::noop::NoopMeterProvider;
+    use opentelemetry::metrics::MeterProvider;
+
+    #[test]
+    fn instruments_are_reused() {
+        let meter = OpenTelemetryMeter::new(NoopMeterProvider::new().meter("test"));
+        let attributes = [("rpc.service", "test-service")];
+        meter.record_histogram(CALL_DURATION, 0.5, &attributes);
+        meter.record_histogram(CALL_DURATION, 1.5, &attributes);
+        meter.add_to_counter(ATTEMPTS, 1, &attributes);
+        meter.add_to_counter(ATTEMPTS, 2, &attributes);
+        assert_eq!(1, meter.histograms.lock().unwrap().len());
+        assert_eq!(1, meter.counters.lock().unwrap().len());
+    }
+}
@@ -0,0 +1,86 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::client::metrics::Meter;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+type Attributes = Vec<(String, String)>;
+
+fn to_key(name: &str, attributes: &[(&str, &str)]) -> (String, Attributes) {
+    let mut attributes: Attributes = attributes
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    attributes.sort();
+    (name.to_string(), attributes)
+}
+
+#[derive(Debug, Default)]
+struct Recorded {
+    histograms: HashMap<(String, Attributes), Vec<f64>>,
+    counters: HashMap<(String, Attributes), u64>,
+}
+
+/// A [`Meter`] that keeps every recorded value in memory.
+///
+/// Clones of an `InMemoryMeter` share the recorded values, so a clone can be given to a client
+/// and the original used to make assertions. Values are looked up by metric name and the exact
+/// set of attributes they were recorded with, in any order.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryMeter {
+    recorded: Arc<Mutex<Recorded>>,
+}
+
+impl InMemoryMeter {
+    /// Creates a new, empty `InMemoryMeter`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the values recorded in the histogram `name` with the given attributes.
+    pub fn histogram(&self, name: &str, attributes: &[(&str, &str)]) -> Vec<f64> {
+        let recorded = self.recorded.lock().unwrap();
+        recorded
+            .histograms
+            .get(&to_key(name, attributes))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the value of the counter `name` with the given attributes.
+    pub fn counter(&self, name: &str, attributes: &[(&str, &str)]) -> u64 {
+        let recorded = self.recorded.lock().unwrap();
+        recorded
+            .counters
+            .get(&to_key(name, attributes))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl Meter for InMemoryMeter {
+    fn record_histogram(
+        &self,
+        name: &'static str,
+        value: f64,
+        attributes: &[(&'static str, &str)],
+    ) {
+        let mut recorded = self.recorded.lock().unwrap();
+        recorded
+            .histograms
+            .entry(to_key(name, attributes))
+            .or_default()
+            .push(value);
+    }
+
+    fn add_to_counter(&self, name: &'static str, value: u64, attributes: &[(&'static str, &str)]) {
+        let mut recorded = self.recorded.lock().unwrap();
+        *recorded
+            .counters
+            .entry(to_key(name, attributes))
+            .or_default() += value;
+    }
+}
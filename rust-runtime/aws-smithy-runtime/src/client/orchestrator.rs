@@ -4,9 +4,11 @@
  */
 
 use self::auth::orchestrate_auth;
+use crate::client::hedging::{HedgeOutcome, HedgedCall};
 use crate::client::interceptors::Interceptors;
 use crate::client::orchestrator::endpoints::orchestrate_endpoint;
 use crate::client::orchestrator::http::{log_response_body, read_body};
+use crate::client::retries::backoff::PreviousBackoff;
 use crate::client::timeout::{MaybeTimeout, MaybeTimeoutConfig, TimeoutKind};
 use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_runtime_api::box_error::BoxError;
@@ -279,8 +281,18 @@ async fn try_op(
     // Save a request checkpoint before we make the request. This will allow us to "rewind"
     // the request in the case of retry attempts.
     ctx.save_checkpoint();
+    // The retry strategy records the delays its backoff policy calculates here, since policies
+    // like decorrelated jitter base the next delay on the previous one
+    cfg.interceptor_state().store_put(PreviousBackoff::new());
     let mut retry_delay = None;
-    for i in 1u32.. {
+    let mut hedge_snapshot = None;
+    loop {
+        // A hedged request counts as an attempt too, so attempts aren't always consecutive.
+        let i = cfg
+            .load::<RequestAttempts>()
+            .map(RequestAttempts::attempts)
+            .unwrap_or(0)
+            + 1;
         // Break from the loop if we can't rewind the request's state. This will always succeed the
         // first time, but will fail on subsequent iterations if the request body wasn't retryable.
         trace!("checking if context can be rewound for attempt #{i}");
@@ -299,18 +311,58 @@ async fn try_op(
         let attempt_timeout_config =
             MaybeTimeoutConfig::new(runtime_components, cfg, TimeoutKind::OperationAttempt);
         trace!(attempt_timeout_config = ?attempt_timeout_config);
-        let maybe_timeout = async {
-            debug!("beginning attempt #{i}");
-            try_attempt(ctx, cfg, runtime_components, stop_point).await;
-            finally_attempt(ctx, cfg, runtime_components).await;
-            Result::<_, SdkError<Error, HttpResponse>>::Ok(())
+        let hedged_call = match stop_point {
+            StopPoint::None => HedgedCall::from_config(runtime_components, cfg)
+                .and_then(|hedged_call| Some((hedged_call, ctx.fork_attempt()?))),
+            _ => None,
+        };
+        match hedged_call {
+            Some((hedged_call, mut hedge_ctx)) => {
+                // The hedged attempt gets its own interceptor state on top of a snapshot of the
+                // config, which is taken once so that hedging doesn't keep adding layers to it
+                let snapshot = hedge_snapshot.get_or_insert_with(|| cfg.fork("hedge snapshot"));
+                let mut hedge_cfg = snapshot.fork("hedged attempt");
+                hedge_cfg
+                    .interceptor_state()
+                    .store_put(RequestAttempts::new(i + 1));
+                let outcome = hedged_call
+                    .race(
+                        attempt(
+                            ctx,
+                            cfg,
+                            runtime_components,
+                            stop_point,
+                            attempt_timeout_config.clone(),
+                        ),
+                        attempt(
+                            &mut hedge_ctx,
+                            &mut hedge_cfg,
+                            runtime_components,
+                            stop_point,
+                            attempt_timeout_config,
+                        ),
+                    )
+                    .await;
+                if outcome == HedgeOutcome::HedgeWon {
+                    mem::swap(ctx, &mut hedge_ctx);
+                    mem::swap(cfg, &mut hedge_cfg);
+                }
+                if outcome != HedgeOutcome::NotHedged {
+                    cfg.interceptor_state()
+                        .store_put(RequestAttempts::new(i + 1));
+                }
+            }
+            None => {
+                attempt(
+                    ctx,
+                    cfg,
+                    runtime_components,
+                    stop_point,
+                    attempt_timeout_config,
+                )
+                .await;
+            }
         }
-        .maybe_timeout(attempt_timeout_config)
-        .await
-        .map_err(|err| OrchestratorError::timeout(err.into_source().unwrap()));
-
-        // We continue when encountering a timeout error. The retry classifier will decide what to do with it.
-        continue_on_err!([ctx] => maybe_timeout);
 
         // If we got a retry strategy from the bag, ask it what to do.
         // If no strategy was set, we won't retry.
@@ -337,6 +389,33 @@ async fn try_op(
     }
 }
 
+/// Runs a single attempt, returning `true` if it succeeded.
+async fn attempt(
+    ctx: &mut InterceptorContext,
+    cfg: &mut ConfigBag,
+    runtime_components: &RuntimeComponents,
+    stop_point: StopPoint,
+    attempt_timeout_config: MaybeTimeoutConfig,
+) -> bool {
+    let maybe_timeout = async {
+        let i = cfg
+            .load::<RequestAttempts>()
+            .map(RequestAttempts::attempts)
+            .unwrap_or(1);
+        debug!("beginning attempt #{i}");
+        try_attempt(ctx, cfg, runtime_components, stop_point).await;
+        finally_attempt(ctx, cfg, runtime_components).await;
+        Result::<_, SdkError<Error, HttpResponse>>::Ok(())
+    }
+    .maybe_timeout(attempt_timeout_config)
+    .await
+    .map_err(|err| OrchestratorError::timeout(err.into_source().unwrap()));
+
+    // We continue when encountering a timeout error. The retry classifier will decide what to do with it.
+    continue_on_err!([ctx] => maybe_timeout);
+    !ctx.is_failed()
+}
+
 #[instrument(skip_all, level = "debug")]
 async fn try_attempt(
     ctx: &mut InterceptorContext,
@@ -407,6 +486,17 @@ async fn try_attempt(
             let _span = debug_span!("deserialize_streaming").entered();
             response_deserializer.deserialize_streaming(response)
         };
+        let maybe_deserialized = match maybe_deserialized {
+            Some(output_or_error) => Some(output_or_error),
+            None => match response_deserializer.deserialize_incrementally(response) {
+                Some(future) => Some(
+                    future
+                        .instrument(debug_span!("deserialize_incrementally"))
+                        .await,
+                ),
+                None => None,
+            },
+        };
         match maybe_deserialized {
             Some(output_or_error) => output_or_error,
             None => read_body(response)
@@ -472,7 +562,7 @@ mod tests {
         EndpointResolverParams, SharedEndpointResolver,
     };
     use aws_smithy_runtime_api::client::http::{
-        http_client_fn, HttpConnector, HttpConnectorFuture,
+        http_client_fn, HttpConnector, HttpConnectorFuture, SharedHttpConnector,
     };
     use aws_smithy_runtime_api::client::interceptors::context::{
         AfterDeserializationInterceptorContextRef, BeforeDeserializationInterceptorContextMut,
@@ -491,6 +581,7 @@ mod tests {
     use std::borrow::Cow;
     use std::sync::atomic::{AtomicBool, Ordering};
     use std::sync::Arc;
+    use std::time::Duration;
     use tracing_test::traced_test;
 
     fn new_request_serializer() -> CannedRequestSerializer {
@@ -1328,4 +1419,196 @@ mod tests {
             .read_after_execution_called
             .load(Ordering::Relaxed));
     }
+
+    #[tokio::test]
+    async fn incremental_deserializers_read_the_response_body() {
+        use aws_smithy_runtime_api::client::ser_de::{
+            DeserializeResponse, DeserializeResponseFuture,
+        };
+
+        #[derive(Debug)]
+        struct IncrementalDeserializer;
+
+        impl DeserializeResponse for IncrementalDeserializer {
+            fn deserialize_incrementally<'a>(
+                &'a self,
+                response: &'a mut HttpResponse,
+            ) -> Option<DeserializeResponseFuture<'a>> {
+                let body = mem::replace(response.body_mut(), SdkBody::taken());
+                Some(DeserializeResponseFuture::new(async move {
+                    let body = ByteStream::new(body)
+                        .collect()
+                        .await
+                        .map_err(|err| OrchestratorError::other(Box::new(err)))?;
+                    Ok(Output::erase(
+                        String::from_utf8(body.to_vec()).expect("valid utf-8"),
+                    ))
+                }))
+            }
+
+            fn deserialize_nonstreaming(
+                &self,
+                _response: &HttpResponse,
+            ) -> Result<Output, OrchestratorError<Error>> {
+                unreachable!("the response was deserialized incrementally")
+            }
+        }
+
+        #[derive(Debug)]
+        struct HelloConnector;
+
+        impl HttpConnector for HelloConnector {
+            fn call(&self, _request: HttpRequest) -> HttpConnectorFuture {
+                HttpConnectorFuture::ready(Ok(::http::Response::builder()
+                    .status(200)
+                    .body(SdkBody::from("hello"))
+                    .expect("OK response is valid")
+                    .try_into()
+                    .unwrap()))
+            }
+        }
+
+        #[derive(Debug)]
+        struct IncrementalDeserializerRuntimePlugin {
+            builder: RuntimeComponentsBuilder,
+        }
+
+        impl RuntimePlugin for IncrementalDeserializerRuntimePlugin {
+            fn config(&self) -> Option<FrozenLayer> {
+                let mut layer = Layer::new("IncrementalDeserializerRuntimePlugin");
+                layer.store_put(SharedResponseDeserializer::new(IncrementalDeserializer));
+                Some(layer.freeze())
+            }
+
+            fn runtime_components(
+                &self,
+                _: &RuntimeComponentsBuilder,
+            ) -> Cow<'_, RuntimeComponentsBuilder> {
+                Cow::Borrowed(&self.builder)
+            }
+        }
+
+        let runtime_plugins = RuntimePlugins::new()
+            .with_operation_plugin(TestOperationRuntimePlugin::new())
+            .with_operation_plugin(NoAuthRuntimePlugin::new())
+            .with_operation_plugin(IncrementalDeserializerRuntimePlugin {
+                builder: RuntimeComponentsBuilder::new("test")
+                    .with_http_client(Some(http_client_fn(|_, _| HelloConnector.into_shared()))),
+            });
+
+        let context = invoke_with_stop_point(
+            "test",
+            "test",
+            Input::doesnt_matter(),
+            &runtime_plugins,
+            StopPoint::None,
+        )
+        .await
+        .expect("success");
+        let output = context
+            .output_or_error()
+            .expect("deserialized")
+            .expect("success");
+        assert_eq!(
+            Some("hello"),
+            output.downcast_ref::<String>().map(String::as_str)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn hedged_attempts_run_interceptors() {
+        use crate::client::hedging::{HedgingConfig, IdempotentOperation};
+        use aws_smithy_async::rt::sleep::{SharedAsyncSleep, TokioSleep};
+        use std::sync::atomic::AtomicUsize;
+
+        #[derive(Clone, Debug, Default)]
+        struct AttemptCounter {
+            attempts: Arc<AtomicUsize>,
+        }
+
+        impl Intercept for AttemptCounter {
+            fn name(&self) -> &'static str {
+                "AttemptCounter"
+            }
+
+            fn read_before_attempt(
+                &self,
+                _context: &BeforeTransmitInterceptorContextRef<'_>,
+                _rc: &RuntimeComponents,
+                _cfg: &mut ConfigBag,
+            ) -> Result<(), BoxError> {
+                self.attempts.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        }
+
+        /// The first request hangs, so that only the hedged attempt gets a response.
+        #[derive(Debug, Default)]
+        struct SlowFirstConnector {
+            calls: AtomicUsize,
+        }
+
+        impl HttpConnector for SlowFirstConnector {
+            fn call(&self, _request: HttpRequest) -> HttpConnectorFuture {
+                let delay = match self.calls.fetch_add(1, Ordering::SeqCst) {
+                    0 => Duration::from_secs(60),
+                    _ => Duration::ZERO,
+                };
+                HttpConnectorFuture::new(async move {
+                    tokio::time::sleep(delay).await;
+                    Ok(::http::Response::builder()
+                        .status(200)
+                        .body(SdkBody::empty())
+                        .expect("OK response is valid")
+                        .try_into()
+                        .unwrap())
+                })
+            }
+        }
+
+        #[derive(Debug)]
+        struct HedgingRuntimePlugin {
+            builder: RuntimeComponentsBuilder,
+        }
+
+        impl RuntimePlugin for HedgingRuntimePlugin {
+            fn config(&self) -> Option<FrozenLayer> {
+                let mut layer = Layer::new("HedgingRuntimePlugin");
+                layer.store_put(HedgingConfig::enabled(Duration::from_millis(100)));
+                layer.store_put(IdempotentOperation::new());
+                Some(layer.freeze())
+            }
+
+            fn runtime_components(
+                &self,
+                _: &RuntimeComponentsBuilder,
+            ) -> Cow<'_, RuntimeComponentsBuilder> {
+                Cow::Borrowed(&self.builder)
+            }
+        }
+
+        let interceptor = AttemptCounter::default();
+        let connector = SharedHttpConnector::new(SlowFirstConnector::default());
+        let runtime_plugins = RuntimePlugins::new()
+            .with_operation_plugin(TestOperationRuntimePlugin::new())
+            .with_operation_plugin(NoAuthRuntimePlugin::new())
+            .with_operation_plugin(HedgingRuntimePlugin {
+                builder: RuntimeComponentsBuilder::new("test")
+                    .with_interceptor(SharedInterceptor::new(interceptor.clone()))
+                    .with_sleep_impl(Some(SharedAsyncSleep::new(TokioSleep::new())))
+                    .with_http_client(Some(http_client_fn(move |_, _| connector.clone()))),
+            });
+
+        let context = invoke_with_stop_point(
+            "test",
+            "test",
+            Input::doesnt_matter(),
+            &runtime_plugins,
+            StopPoint::None,
+        )
+        .await
+        .expect("success");
+        assert!(context.response().is_some());
+        assert_eq!(2, interceptor.attempts.load(Ordering::SeqCst));
+    }
 }
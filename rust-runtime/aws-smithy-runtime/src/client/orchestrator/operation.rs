@@ -506,4 +506,85 @@ mod tests {
 
         connector.assert_requests_match(&[]);
     }
+
+    #[tokio::test]
+    async fn operation_retries_notify_the_token_bucket_observer() {
+        use crate::client::retries::{
+            ObserveTokenBucket, TokenBucket, TokenBucketEvent, TokenBucketRuntimePlugin,
+        };
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Debug, Default, Clone)]
+        struct RecordingObserver(Arc<Mutex<Vec<TokenBucketEvent>>>);
+
+        impl ObserveTokenBucket for RecordingObserver {
+            fn on_event(&self, event: &TokenBucketEvent, _token_bucket: &TokenBucket) {
+                self.0.lock().unwrap().push(event.clone());
+            }
+        }
+
+        let connector = StaticReplayClient::new(vec![
+            ReplayEvent::new(
+                http::Request::builder()
+                    .uri("http://localhost:1234/")
+                    .body(SdkBody::from(&b"what are you?"[..]))
+                    .unwrap(),
+                http::Response::builder()
+                    .status(503)
+                    .body(SdkBody::from(&b""[..]))
+                    .unwrap(),
+            ),
+            ReplayEvent::new(
+                http::Request::builder()
+                    .uri("http://localhost:1234/")
+                    .body(SdkBody::from(&b"what are you?"[..]))
+                    .unwrap(),
+                http::Response::builder()
+                    .status(418)
+                    .body(SdkBody::from(&b"I'm a teapot!"[..]))
+                    .unwrap(),
+            ),
+        ]);
+        let observer = RecordingObserver::default();
+        let token_bucket = TokenBucket::new(500).with_observer(observer.clone());
+        let operation = Operation::builder()
+            .service_name("test")
+            .operation_name("test")
+            .http_client(connector.clone())
+            .endpoint_url("http://localhost:1234")
+            .no_auth()
+            .standard_retry(&RetryConfig::standard())
+            .retry_classifier(HttpStatusCodeClassifier::default())
+            .timeout_config(TimeoutConfig::disabled())
+            .sleep_impl(SharedAsyncSleep::new(TokioSleep::new()))
+            .runtime_plugin(TokenBucketRuntimePlugin::new(token_bucket.clone()))
+            .serializer(|input: String| Ok(HttpRequest::new(SdkBody::from(input.as_bytes()))))
+            .deserializer::<_, Infallible>(|response| {
+                if u16::from(response.status()) == 503 {
+                    Err(OrchestratorError::connector(ConnectorError::io(
+                        "test".into(),
+                    )))
+                } else {
+                    Ok(std::str::from_utf8(response.body().bytes().unwrap())
+                        .unwrap()
+                        .to_string())
+                }
+            })
+            .build();
+
+        let output = operation
+            .invoke("what are you?".to_string())
+            .await
+            .expect("success");
+        assert_eq!("I'm a teapot!", output);
+
+        assert_eq!(
+            vec![
+                TokenBucketEvent::PermitAcquired { cost: 10 },
+                TokenBucketEvent::PermitRefunded { amount: 10 },
+            ],
+            *observer.0.lock().unwrap()
+        );
+        assert_eq!(500, token_bucket.available_permits());
+    }
 }
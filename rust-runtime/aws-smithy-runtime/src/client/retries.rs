@@ -3,6 +3,8 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+pub mod backoff;
+
 /// Smithy retry classifiers.
 pub mod classifiers;
 
@@ -16,7 +18,10 @@ use aws_smithy_types::config_bag::{Storable, StoreReplace};
 use std::fmt;
 
 pub use client_rate_limiter::ClientRateLimiter;
-pub use token_bucket::TokenBucket;
+pub use token_bucket::{
+    ObserveTokenBucket, SharedTokenBucketObserver, TokenBucket, TokenBucketEvent,
+    TokenBucketRuntimePlugin,
+};
 
 pub use client_rate_limiter::ClientRateLimiterPartition;
 use std::borrow::Cow;
@@ -0,0 +1,401 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Backoff policies determine how long a retry strategy waits before retrying a failed request.
+//!
+//! The [`StandardRetryStrategy`](crate::client::retries::strategy::StandardRetryStrategy) uses
+//! the policy selected by the [`BackoffMode`] of its [`RetryConfig`], which defaults to
+//! [`ExponentialBackoff`]. It can also be configured with any [`BackoffPolicy`], including:
+//! - [`EqualJitterBackoff`]
+//! - [`DecorrelatedJitterBackoff`]
+//! - [`ConstantBackoff`]
+//!
+//! For more information on the jitter strategies, see
+//! ["Exponential Backoff And Jitter"](https://aws.amazon.com/blogs/architecture/exponential-backoff-and-jitter/).
+//!
+//! Backoff policies are given the [`RetryReason`] of the failed request, so a custom policy can
+//! back off differently depending on the kind of error:
+//!
+//! ```
+//! use aws_smithy_runtime::client::retries::backoff::{
+//!     BackoffContext, BackoffPolicy, ConstantBackoff, ExponentialBackoff,
+//! };
+//! use aws_smithy_types::retry::ErrorKind;
+//! use std::time::Duration;
+//!
+//! #[derive(Debug)]
+//! struct SlowDownWhenThrottled;
+//!
+//! impl BackoffPolicy for SlowDownWhenThrottled {
+//!     fn backoff(&self, ctx: &BackoffContext<'_>) -> Duration {
+//!         match ctx.error_kind() {
+//!             Some(ErrorKind::ThrottlingError) => ExponentialBackoff::new().backoff(ctx) * 2,
+//!             _ => ConstantBackoff::new(Duration::from_millis(50)).backoff(ctx),
+//!         }
+//!     }
+//! }
+//! ```
+
+use aws_smithy_runtime_api::client::retries::classifiers::RetryReason;
+use aws_smithy_runtime_api::impl_shared_conversions;
+use aws_smithy_types::config_bag::{Storable, StoreReplace};
+use aws_smithy_types::retry::{BackoffMode, ErrorKind, RetryConfig};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The delay that the backoff policy calculated for the most recent retry.
+///
+/// The orchestrator stores an empty `PreviousBackoff` in the config bag for each operation, and the
+/// [`StandardRetryStrategy`](crate::client::retries::strategy::StandardRetryStrategy) records the
+/// delay calculated by its [`BackoffPolicy`] in it, so that policies that depend on the previous
+/// delay (such as [`DecorrelatedJitterBackoff`]) can use it. Delays that didn't come from the
+/// policy, such as a server-provided `retry_after` or a delay requested by the client rate limiter,
+/// aren't recorded.
+#[derive(Debug, Clone, Default)]
+pub struct PreviousBackoff(Arc<Mutex<Option<Duration>>>);
+
+impl PreviousBackoff {
+    /// Creates a new `PreviousBackoff` that hasn't recorded a delay yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the previous delay, if the backoff policy has calculated one.
+    pub fn get(&self) -> Option<Duration> {
+        *self.0.lock().unwrap()
+    }
+
+    /// Records the delay calculated by the backoff policy.
+    pub fn set(&self, backoff: Duration) {
+        *self.0.lock().unwrap() = Some(backoff);
+    }
+}
+
+impl Storable for PreviousBackoff {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Information about a failed request that a [`BackoffPolicy`] uses to calculate a delay.
+#[derive(Debug)]
+pub struct BackoffContext<'a> {
+    request_attempts: u32,
+    retry_config: &'a RetryConfig,
+    retry_reason: &'a RetryReason,
+    previous_backoff: Option<Duration>,
+}
+
+impl<'a> BackoffContext<'a> {
+    /// Creates a new `BackoffContext`.
+    pub fn new(
+        request_attempts: u32,
+        retry_config: &'a RetryConfig,
+        retry_reason: &'a RetryReason,
+        previous_backoff: Option<Duration>,
+    ) -> Self {
+        Self {
+            request_attempts,
+            retry_config,
+            retry_reason,
+            previous_backoff,
+        }
+    }
+
+    /// The number of requests that have been made so far, including the initial request.
+    pub fn request_attempts(&self) -> u32 {
+        self.request_attempts
+    }
+
+    /// The number of retries that have been made so far, not including the one being calculated.
+    pub fn retry_attempts(&self) -> u32 {
+        self.request_attempts.saturating_sub(1)
+    }
+
+    /// The configured initial backoff.
+    pub fn initial_backoff(&self) -> Duration {
+        self.retry_config.initial_backoff()
+    }
+
+    /// The configured maximum backoff.
+    ///
+    /// The retry strategy will never wait longer than this, regardless of what the policy returns.
+    pub fn max_backoff(&self) -> Duration {
+        self.retry_config.max_backoff()
+    }
+
+    /// The reason the failed request should be retried, as determined by the retry classifiers.
+    pub fn retry_reason(&self) -> &RetryReason {
+        self.retry_reason
+    }
+
+    /// The kind of error that caused the failed request, if known.
+    pub fn error_kind(&self) -> Option<ErrorKind> {
+        match self.retry_reason {
+            RetryReason::RetryableError { kind, .. } => Some(*kind),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The delay that preceded the failed request, if it was a retry.
+    pub fn previous_backoff(&self) -> Option<Duration> {
+        self.previous_backoff
+    }
+
+    /// Returns a random number between 0 and 1 to use for jitter.
+    ///
+    /// When [`RetryConfig::use_static_exponential_base`] is set, this always returns 1.0 so that
+    /// backoff is deterministic.
+    pub fn jitter(&self) -> f64 {
+        if self.retry_config.use_static_exponential_base() {
+            1.0
+        } else {
+            fastrand::f64()
+        }
+    }
+}
+
+/// Calculates how long to wait before retrying a failed request.
+pub trait BackoffPolicy: Send + Sync + fmt::Debug {
+    /// Returns the delay before the next attempt.
+    fn backoff(&self, ctx: &BackoffContext<'_>) -> Duration;
+}
+
+/// A shared backoff policy.
+#[derive(Clone, Debug)]
+pub struct SharedBackoffPolicy(Arc<dyn BackoffPolicy>);
+
+impl SharedBackoffPolicy {
+    /// Creates a new `SharedBackoffPolicy` from the given policy.
+    pub fn new(policy: impl BackoffPolicy + 'static) -> Self {
+        Self(Arc::new(policy))
+    }
+}
+
+impl BackoffPolicy for SharedBackoffPolicy {
+    fn backoff(&self, ctx: &BackoffContext<'_>) -> Duration {
+        self.0.backoff(ctx)
+    }
+}
+
+impl Default for SharedBackoffPolicy {
+    fn default() -> Self {
+        Self::new(ExponentialBackoff::new())
+    }
+}
+
+impl_shared_conversions!(convert SharedBackoffPolicy from BackoffPolicy using SharedBackoffPolicy::new);
+
+/// Returns the built-in policy for the given [`BackoffMode`].
+pub(crate) fn policy_for_mode(backoff_mode: BackoffMode) -> &'static dyn BackoffPolicy {
+    match backoff_mode {
+        BackoffMode::EqualJitter => &EqualJitterBackoff,
+        BackoffMode::DecorrelatedJitter => &DecorrelatedJitterBackoff,
+        _ => &ExponentialBackoff,
+    }
+}
+
+pub(crate) fn calculate_exponential_backoff(
+    base: f64,
+    initial_backoff: f64,
+    retry_attempts: u32,
+) -> f64 {
+    2_u32
+        .checked_pow(retry_attempts)
+        .map(|backoff| (backoff as f64) * base * initial_backoff)
+        .unwrap_or(f64::MAX)
+}
+
+fn duration_from_secs(secs: f64, max_backoff: Duration) -> Duration {
+    Duration::try_from_secs_f64(secs)
+        .unwrap_or(max_backoff)
+        .min(max_backoff)
+}
+
+/// Exponential backoff with "full jitter". This is the default policy.
+///
+/// The delay is a random duration between zero and `initial_backoff * 2^retry_attempts`.
+#[derive(Debug, Default, Clone)]
+#[non_exhaustive]
+pub struct ExponentialBackoff;
+
+impl ExponentialBackoff {
+    /// Creates a new `ExponentialBackoff`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl BackoffPolicy for ExponentialBackoff {
+    fn backoff(&self, ctx: &BackoffContext<'_>) -> Duration {
+        let backoff = calculate_exponential_backoff(
+            ctx.jitter(),
+            ctx.initial_backoff().as_secs_f64(),
+            ctx.retry_attempts(),
+        );
+        duration_from_secs(backoff, ctx.max_backoff())
+    }
+}
+
+/// Exponential backoff with "equal jitter".
+///
+/// Half of the delay is `initial_backoff * 2^retry_attempts / 2`, and the other half is random. This
+/// prevents very short delays at the cost of less spread between clients.
+#[derive(Debug, Default, Clone)]
+#[non_exhaustive]
+pub struct EqualJitterBackoff;
+
+impl EqualJitterBackoff {
+    /// Creates a new `EqualJitterBackoff`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl BackoffPolicy for EqualJitterBackoff {
+    fn backoff(&self, ctx: &BackoffContext<'_>) -> Duration {
+        let max_backoff = ctx.max_backoff().as_secs_f64();
+        let half = calculate_exponential_backoff(
+            1.0,
+            ctx.initial_backoff().as_secs_f64(),
+            ctx.retry_attempts(),
+        )
+        .min(max_backoff)
+            / 2.0;
+        duration_from_secs(half + ctx.jitter() * half, ctx.max_backoff())
+    }
+}
+
+/// Backoff with "decorrelated jitter".
+///
+/// The delay is a random duration between `initial_backoff` and three times the previous delay.
+/// The first retry uses `initial_backoff` as the previous delay.
+#[derive(Debug, Default, Clone)]
+#[non_exhaustive]
+pub struct DecorrelatedJitterBackoff;
+
+impl DecorrelatedJitterBackoff {
+    /// Creates a new `DecorrelatedJitterBackoff`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl BackoffPolicy for DecorrelatedJitterBackoff {
+    fn backoff(&self, ctx: &BackoffContext<'_>) -> Duration {
+        let initial_backoff = ctx.initial_backoff().as_secs_f64();
+        let previous_backoff = ctx
+            .previous_backoff()
+            .map(|backoff| backoff.as_secs_f64())
+            .unwrap_or(initial_backoff)
+            .max(initial_backoff);
+        let upper_bound = previous_backoff * 3.0;
+        let backoff = initial_backoff + ctx.jitter() * (upper_bound - initial_backoff);
+        duration_from_secs(backoff, ctx.max_backoff())
+    }
+}
+
+/// Waits for the same duration before every retry.
+#[derive(Debug, Clone)]
+pub struct ConstantBackoff {
+    delay: Duration,
+}
+
+impl ConstantBackoff {
+    /// Creates a new `ConstantBackoff` that always waits for `delay`.
+    pub fn new(delay: Duration) -> Self {
+        Self { delay }
+    }
+}
+
+impl BackoffPolicy for ConstantBackoff {
+    fn backoff(&self, ctx: &BackoffContext<'_>) -> Duration {
+        self.delay.min(ctx.max_backoff())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BackoffContext, BackoffPolicy, ConstantBackoff, DecorrelatedJitterBackoff,
+        EqualJitterBackoff, ExponentialBackoff,
+    };
+    use aws_smithy_runtime_api::client::retries::classifiers::RetryReason;
+    use aws_smithy_types::retry::{ErrorKind, RetryConfig};
+    use std::time::Duration;
+
+    fn static_config() -> RetryConfig {
+        RetryConfig::standard()
+            .with_use_static_exponential_base(true)
+            .with_initial_backoff(Duration::from_secs(1))
+            .with_max_backoff(Duration::from_secs(20))
+    }
+
+    const REASON: RetryReason = RetryReason::RetryableError {
+        kind: ErrorKind::ServerError,
+        retry_after: None,
+    };
+
+    fn backoffs(policy: &dyn BackoffPolicy, retry_config: &RetryConfig) -> Vec<Duration> {
+        let mut previous_backoff = None;
+        (1..=5)
+            .map(|attempts| {
+                let ctx = BackoffContext::new(attempts, retry_config, &REASON, previous_backoff);
+                let backoff = policy.backoff(&ctx);
+                previous_backoff = Some(backoff);
+                backoff
+            })
+            .collect()
+    }
+
+    fn secs(secs: &[u64]) -> Vec<Duration> {
+        secs.iter().map(|s| Duration::from_secs(*s)).collect()
+    }
+
+    #[test]
+    fn exponential_backoff() {
+        let backoffs = backoffs(&ExponentialBackoff::new(), &static_config());
+        assert_eq!(secs(&[1, 2, 4, 8, 16]), backoffs);
+    }
+
+    #[test]
+    fn equal_jitter_backoff() {
+        let backoffs = backoffs(&EqualJitterBackoff::new(), &static_config());
+        assert_eq!(secs(&[1, 2, 4, 8, 16]), backoffs);
+    }
+
+    #[test]
+    fn decorrelated_jitter_backoff() {
+        let backoffs = backoffs(&DecorrelatedJitterBackoff::new(), &static_config());
+        assert_eq!(secs(&[3, 9, 20, 20, 20]), backoffs);
+    }
+
+    #[test]
+    fn constant_backoff() {
+        let backoffs = backoffs(
+            &ConstantBackoff::new(Duration::from_secs(3)),
+            &static_config(),
+        );
+        assert_eq!(secs(&[3, 3, 3, 3, 3]), backoffs);
+    }
+
+    #[test]
+    fn jittered_backoffs_stay_within_bounds() {
+        let retry_config = static_config().with_use_static_exponential_base(false);
+        for _ in 0..100 {
+            let ctx = BackoffContext::new(3, &retry_config, &REASON, Some(Duration::from_secs(2)));
+            let full = ExponentialBackoff::new().backoff(&ctx);
+            assert!(full <= Duration::from_secs(4), "{full:?}");
+
+            let equal = EqualJitterBackoff::new().backoff(&ctx);
+            assert!(equal >= Duration::from_secs(2), "{equal:?}");
+            assert!(equal <= Duration::from_secs(4), "{equal:?}");
+
+            let decorrelated = DecorrelatedJitterBackoff::new().backoff(&ctx);
+            assert!(decorrelated >= Duration::from_secs(1), "{decorrelated:?}");
+            assert!(decorrelated <= Duration::from_secs(6), "{decorrelated:?}");
+        }
+    }
+}
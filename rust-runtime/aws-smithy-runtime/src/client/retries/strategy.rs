@@ -3,8 +3,10 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+mod adaptive;
 mod never;
 pub(crate) mod standard;
 
+pub use adaptive::AdaptiveRetryStrategy;
 pub use never::NeverRetryStrategy;
 pub use standard::StandardRetryStrategy;
@@ -0,0 +1,310 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::client::retries::backoff::BackoffPolicy;
+use crate::client::retries::client_rate_limiter::ClientRateLimiter;
+use crate::client::retries::strategy::standard::get_seconds_since_unix_epoch;
+use crate::client::retries::strategy::StandardRetryStrategy;
+use crate::client::retries::{ClientRateLimiterPartition, RetryPartition};
+use crate::static_partition_map::StaticPartitionMap;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::interceptors::context::InterceptorContext;
+use aws_smithy_runtime_api::client::retries::{RetryStrategy, ShouldAttempt};
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::config_bag::ConfigBag;
+
+static CLIENT_RATE_LIMITER: StaticPartitionMap<ClientRateLimiterPartition, ClientRateLimiter> =
+    StaticPartitionMap::new();
+
+/// Returns the [`ClientRateLimiter`] shared by every client in the configured [`RetryPartition`].
+pub(super) fn shared_client_rate_limiter(
+    cfg: &ConfigBag,
+    seconds_since_unix_epoch: f64,
+) -> ClientRateLimiter {
+    let retry_partition = cfg.load::<RetryPartition>().expect("set in default config");
+    let client_rate_limiter_partition = ClientRateLimiterPartition::new(retry_partition.clone());
+    CLIENT_RATE_LIMITER.get_or_init(client_rate_limiter_partition, || {
+        ClientRateLimiter::new(seconds_since_unix_epoch)
+    })
+}
+
+/// Retry strategy that adds client-side rate limiting to the [`StandardRetryStrategy`].
+///
+/// In addition to exponential backoff, max attempts, and a token bucket, this strategy
+/// measures the rate at which requests are sent. Once a throttling error is received, a
+/// [`ClientRateLimiter`] starts delaying requests, scaling the allowed send rate down after
+/// each throttling error and back up (following a CUBIC curve) as requests succeed.
+///
+/// By default, the rate limiter state is shared by every client using the same
+/// [`RetryPartition`], so that one client being throttled slows down the others as well.
+/// The [`StandardRetryStrategy`] behaves the same way when the `RetryConfig` has a mode of
+/// [`RetryMode::Adaptive`](aws_smithy_types::retry::RetryMode::Adaptive), so setting that
+/// mode is usually all that's needed to enable adaptive retry.
+#[derive(Debug, Default)]
+pub struct AdaptiveRetryStrategy {
+    standard: StandardRetryStrategy,
+    client_rate_limiter: Option<ClientRateLimiter>,
+}
+
+impl AdaptiveRetryStrategy {
+    /// Create a new adaptive retry strategy that shares its rate limiter with every client in
+    /// the same [`RetryPartition`].
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Use the given rate limiter instead of the one shared by the [`RetryPartition`].
+    pub fn with_client_rate_limiter(mut self, client_rate_limiter: ClientRateLimiter) -> Self {
+        self.client_rate_limiter = Some(client_rate_limiter);
+        self
+    }
+
+    /// Use the given [`BackoffPolicy`] to calculate the delay between attempts.
+    ///
+    /// The rate limiter may still delay a retry for longer than the policy asks for.
+    pub fn with_backoff_policy(mut self, backoff_policy: impl BackoffPolicy + 'static) -> Self {
+        self.standard = self.standard.with_backoff_policy(backoff_policy);
+        self
+    }
+
+    fn client_rate_limiter(
+        &self,
+        runtime_components: &RuntimeComponents,
+        cfg: &ConfigBag,
+    ) -> ClientRateLimiter {
+        match &self.client_rate_limiter {
+            Some(client_rate_limiter) => client_rate_limiter.clone(),
+            None => {
+                shared_client_rate_limiter(cfg, get_seconds_since_unix_epoch(runtime_components))
+            }
+        }
+    }
+}
+
+impl RetryStrategy for AdaptiveRetryStrategy {
+    fn should_attempt_initial_request(
+        &self,
+        runtime_components: &RuntimeComponents,
+        cfg: &ConfigBag,
+    ) -> Result<ShouldAttempt, BoxError> {
+        let rate_limiter = self.client_rate_limiter(runtime_components, cfg);
+        self.standard
+            .should_attempt_initial_request_with_rate_limiter(
+                runtime_components,
+                cfg,
+                Some(&rate_limiter),
+            )
+    }
+
+    fn should_attempt_retry(
+        &self,
+        ctx: &InterceptorContext,
+        runtime_components: &RuntimeComponents,
+        cfg: &ConfigBag,
+    ) -> Result<ShouldAttempt, BoxError> {
+        let rate_limiter = self.client_rate_limiter(runtime_components, cfg);
+        self.standard.should_attempt_retry_with_rate_limiter(
+            ctx,
+            runtime_components,
+            cfg,
+            Some(&rate_limiter),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AdaptiveRetryStrategy;
+    use crate::client::retries::strategy::StandardRetryStrategy;
+    use crate::client::retries::{
+        ClientRateLimiter, ObserveTokenBucket, RetryPartition, TokenBucket, TokenBucketEvent,
+    };
+    use aws_smithy_async::test_util::ManualTimeSource;
+    use aws_smithy_async::time::SharedTimeSource;
+    use aws_smithy_runtime_api::client::interceptors::context::{Input, InterceptorContext};
+    use aws_smithy_runtime_api::client::orchestrator::OrchestratorError;
+    use aws_smithy_runtime_api::client::retries::classifiers::SharedRetryClassifier;
+    use aws_smithy_runtime_api::client::retries::{
+        AlwaysRetry, RequestAttempts, RetryStrategy, ShouldAttempt,
+    };
+    use aws_smithy_runtime_api::client::runtime_components::{
+        RuntimeComponents, RuntimeComponentsBuilder,
+    };
+    use aws_smithy_types::config_bag::{ConfigBag, Layer};
+    use aws_smithy_types::retry::{ErrorKind, RetryConfig};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, SystemTime};
+
+    fn set_up(
+        time_source: &ManualTimeSource,
+        retry_config: RetryConfig,
+        partition: &'static str,
+    ) -> (InterceptorContext, RuntimeComponents, ConfigBag) {
+        let mut ctx = InterceptorContext::new(Input::doesnt_matter());
+        ctx.set_output_or_error(Err(OrchestratorError::other("doesn't matter")));
+        let rc = RuntimeComponentsBuilder::for_tests()
+            .with_retry_classifier(SharedRetryClassifier::new(AlwaysRetry(
+                ErrorKind::ThrottlingError,
+            )))
+            .with_time_source(Some(SharedTimeSource::new(time_source.clone())))
+            .build()
+            .unwrap();
+        let mut layer = Layer::new("test");
+        layer.store_put(RequestAttempts::new(1));
+        layer.store_put(retry_config.with_use_static_exponential_base(true));
+        layer.store_put(RetryPartition::new(partition));
+        (ctx, rc, ConfigBag::of_layers(vec![layer]))
+    }
+
+    #[test]
+    fn initial_requests_are_not_delayed_until_throttled() {
+        let time_source = ManualTimeSource::new(SystemTime::UNIX_EPOCH);
+        let (_, rc, cfg) = set_up(&time_source, RetryConfig::standard(), "not-throttled");
+        let strategy = AdaptiveRetryStrategy::new();
+
+        for _ in 0..10 {
+            let should_attempt = strategy.should_attempt_initial_request(&rc, &cfg).unwrap();
+            assert_eq!(ShouldAttempt::Yes, should_attempt);
+        }
+    }
+
+    #[test]
+    fn throttling_errors_delay_requests_for_every_client_in_the_partition() {
+        let time_source = ManualTimeSource::new(SystemTime::UNIX_EPOCH);
+        let (ctx, rc, cfg) = set_up(&time_source, RetryConfig::standard(), "shared-throttled");
+        let throttled_client = AdaptiveRetryStrategy::new();
+        let other_client = AdaptiveRetryStrategy::new();
+
+        // The throttling error enables the rate limiter. Retrying after a throttling error costs 10
+        // tokens, the bucket is empty, and tokens refill at 0.5/s, so the retry must wait 20s.
+        let should_retry = throttled_client
+            .should_attempt_retry(&ctx, &rc, &cfg)
+            .unwrap();
+        assert_eq!(
+            ShouldAttempt::YesAfterDelay(Duration::from_secs(20)),
+            should_retry
+        );
+
+        // The other client shares the rate limiter, so it's in debt by 10 tokens. Its initial
+        // request costs 1 token, so it needs to wait for 11 tokens to refill.
+        let should_attempt = other_client
+            .should_attempt_initial_request(&rc, &cfg)
+            .unwrap();
+        assert_eq!(
+            ShouldAttempt::YesAfterDelay(Duration::from_secs(22)),
+            should_attempt
+        );
+
+        // Once enough time has passed, requests may be sent without delay again
+        time_source.advance(Duration::from_secs(30));
+        let should_attempt = other_client
+            .should_attempt_initial_request(&rc, &cfg)
+            .unwrap();
+        assert_eq!(ShouldAttempt::Yes, should_attempt);
+    }
+
+    #[test]
+    fn rate_limiters_are_not_shared_between_partitions() {
+        let time_source = ManualTimeSource::new(SystemTime::UNIX_EPOCH);
+        let (ctx, rc, cfg) = set_up(&time_source, RetryConfig::standard(), "partition-a");
+        let (_, _, other_cfg) = set_up(&time_source, RetryConfig::standard(), "partition-b");
+        let strategy = AdaptiveRetryStrategy::new();
+
+        strategy.should_attempt_retry(&ctx, &rc, &cfg).unwrap();
+        let should_attempt = strategy.should_attempt_initial_request(&rc, &cfg).unwrap();
+        assert!(matches!(should_attempt, ShouldAttempt::YesAfterDelay(_)));
+
+        let should_attempt = strategy
+            .should_attempt_initial_request(&rc, &other_cfg)
+            .unwrap();
+        assert_eq!(ShouldAttempt::Yes, should_attempt);
+    }
+
+    #[test]
+    fn explicit_rate_limiter_is_not_shared() {
+        let time_source = ManualTimeSource::new(SystemTime::UNIX_EPOCH);
+        let (ctx, rc, cfg) = set_up(&time_source, RetryConfig::standard(), "explicit");
+        let strategy = AdaptiveRetryStrategy::new().with_client_rate_limiter(
+            ClientRateLimiter::new(time_source.seconds_since_unix_epoch()),
+        );
+        let partition_strategy = AdaptiveRetryStrategy::new();
+
+        strategy.should_attempt_retry(&ctx, &rc, &cfg).unwrap();
+        let should_attempt = strategy.should_attempt_initial_request(&rc, &cfg).unwrap();
+        assert!(matches!(should_attempt, ShouldAttempt::YesAfterDelay(_)));
+
+        let should_attempt = partition_strategy
+            .should_attempt_initial_request(&rc, &cfg)
+            .unwrap();
+        assert_eq!(ShouldAttempt::Yes, should_attempt);
+    }
+
+    #[test]
+    fn standard_strategy_is_adaptive_when_configured() {
+        let time_source = ManualTimeSource::new(SystemTime::UNIX_EPOCH);
+        let (ctx, rc, cfg) = set_up(&time_source, RetryConfig::adaptive(), "adaptive-mode");
+        let strategy = StandardRetryStrategy::new();
+
+        let should_retry = strategy.should_attempt_retry(&ctx, &rc, &cfg).unwrap();
+        assert_eq!(
+            ShouldAttempt::YesAfterDelay(Duration::from_secs(20)),
+            should_retry
+        );
+        let should_attempt = strategy.should_attempt_initial_request(&rc, &cfg).unwrap();
+        assert_eq!(
+            ShouldAttempt::YesAfterDelay(Duration::from_secs(22)),
+            should_attempt
+        );
+    }
+
+    #[test]
+    fn standard_strategy_is_not_adaptive_in_standard_mode() {
+        let time_source = ManualTimeSource::new(SystemTime::UNIX_EPOCH);
+        let (ctx, rc, cfg) = set_up(&time_source, RetryConfig::standard(), "standard-mode");
+        let strategy = StandardRetryStrategy::new();
+
+        let should_retry = strategy.should_attempt_retry(&ctx, &rc, &cfg).unwrap();
+        assert_eq!(
+            ShouldAttempt::YesAfterDelay(Duration::from_secs(1)),
+            should_retry
+        );
+        let should_attempt = strategy.should_attempt_initial_request(&rc, &cfg).unwrap();
+        assert_eq!(ShouldAttempt::Yes, should_attempt);
+    }
+
+    #[derive(Debug, Default, Clone)]
+    struct RecordingObserver(Arc<Mutex<Vec<TokenBucketEvent>>>);
+
+    impl ObserveTokenBucket for RecordingObserver {
+        fn on_event(&self, event: &TokenBucketEvent, _token_bucket: &TokenBucket) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+
+    #[test]
+    fn token_bucket_observer_is_notified_of_rate_limiter_delays() {
+        let time_source = ManualTimeSource::new(SystemTime::UNIX_EPOCH);
+        let (ctx, rc, mut cfg) = set_up(&time_source, RetryConfig::standard(), "observed");
+        let observer = RecordingObserver::default();
+        cfg.interceptor_state()
+            .store_put(TokenBucket::new(500).with_observer(observer.clone()));
+        let strategy = AdaptiveRetryStrategy::new();
+
+        strategy.should_attempt_retry(&ctx, &rc, &cfg).unwrap();
+        strategy.should_attempt_initial_request(&rc, &cfg).unwrap();
+
+        assert_eq!(
+            vec![
+                TokenBucketEvent::RateLimiterDelayApplied {
+                    delay: Duration::from_secs(20)
+                },
+                TokenBucketEvent::RateLimiterDelayApplied {
+                    delay: Duration::from_secs(22)
+                },
+            ],
+            *observer.0.lock().unwrap()
+        );
+    }
+}
@@ -3,14 +3,16 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::client::retries::backoff::{
+    policy_for_mode, BackoffContext, BackoffPolicy, PreviousBackoff, SharedBackoffPolicy,
+};
 use crate::client::retries::classifiers::run_classifiers_on_ctx;
 use crate::client::retries::client_rate_limiter::{ClientRateLimiter, RequestReason};
+use crate::client::retries::strategy::adaptive::shared_client_rate_limiter;
 use crate::client::retries::strategy::standard::ReleaseResult::{
     APermitWasReleased, NoPermitWasReleased,
 };
-use crate::client::retries::token_bucket::TokenBucket;
-use crate::client::retries::{ClientRateLimiterPartition, RetryPartition};
-use crate::static_partition_map::StaticPartitionMap;
+use crate::client::retries::token_bucket::{TokenBucket, TokenBucketEvent};
 use aws_smithy_runtime_api::box_error::BoxError;
 use aws_smithy_runtime_api::client::interceptors::context::InterceptorContext;
 use aws_smithy_runtime_api::client::retries::classifiers::{RetryAction, RetryReason};
@@ -23,13 +25,19 @@ use std::time::{Duration, SystemTime};
 use tokio::sync::OwnedSemaphorePermit;
 use tracing::debug;
 
-static CLIENT_RATE_LIMITER: StaticPartitionMap<ClientRateLimiterPartition, ClientRateLimiter> =
-    StaticPartitionMap::new();
-
 /// Retry strategy with exponential backoff, max attempts, and a token bucket.
+///
+/// The delay between attempts is calculated by the [`BackoffPolicy`] selected by the
+/// [`BackoffMode`](aws_smithy_types::retry::BackoffMode) of the [`RetryConfig`], which defaults to
+/// [`ExponentialBackoff`](crate::client::retries::backoff::ExponentialBackoff). Use
+/// [`StandardRetryStrategy::with_backoff_policy`] to use a different policy.
+///
+/// When the [`RetryConfig`] has a mode of [`RetryMode::Adaptive`], this strategy behaves like
+/// the [`AdaptiveRetryStrategy`](super::AdaptiveRetryStrategy), adding client-side rate limiting.
 #[derive(Debug, Default)]
 pub struct StandardRetryStrategy {
     retry_permit: Mutex<Option<OwnedSemaphorePermit>>,
+    backoff_policy: Option<SharedBackoffPolicy>,
 }
 
 impl Storable for StandardRetryStrategy {
@@ -42,11 +50,21 @@ impl StandardRetryStrategy {
         Default::default()
     }
 
-    fn release_retry_permit(&self) -> ReleaseResult {
+    /// Use the given [`BackoffPolicy`] to calculate the delay between attempts.
+    ///
+    /// This takes precedence over the backoff mode of the [`RetryConfig`]. Regardless of the
+    /// policy, the delay never exceeds the configured max backoff, and a server-provided
+    /// `retry_after` takes precedence over the policy.
+    pub fn with_backoff_policy(mut self, backoff_policy: impl BackoffPolicy + 'static) -> Self {
+        self.backoff_policy = Some(SharedBackoffPolicy::new(backoff_policy));
+        self
+    }
+
+    fn release_retry_permit(&self, token_bucket: &TokenBucket) -> ReleaseResult {
         let mut retry_permit = self.retry_permit.lock().unwrap();
         match retry_permit.take() {
             Some(p) => {
-                drop(p);
+                token_bucket.release(p);
                 APermitWasReleased
             }
             None => NoPermitWasReleased,
@@ -70,19 +88,12 @@ impl StandardRetryStrategy {
         let retry_config = cfg.load::<RetryConfig>().expect("retry config is required");
         if retry_config.mode() == RetryMode::Adaptive {
             if let Some(time_source) = runtime_components.time_source() {
-                let retry_partition = cfg.load::<RetryPartition>().expect("set in default config");
                 let seconds_since_unix_epoch = time_source
                     .now()
                     .duration_since(SystemTime::UNIX_EPOCH)
                     .expect("the present takes place after the UNIX_EPOCH")
                     .as_secs_f64();
-                let client_rate_limiter_partition =
-                    ClientRateLimiterPartition::new(retry_partition.clone());
-                let client_rate_limiter = CLIENT_RATE_LIMITER
-                    .get_or_init(client_rate_limiter_partition, || {
-                        ClientRateLimiter::new(seconds_since_unix_epoch)
-                    });
-                return Some(client_rate_limiter);
+                return Some(shared_client_rate_limiter(cfg, seconds_since_unix_epoch));
             }
         }
         None
@@ -94,6 +105,7 @@ impl StandardRetryStrategy {
         cfg: &ConfigBag,
         retry_cfg: &RetryConfig,
         retry_reason: &RetryAction,
+        rate_limiter: Option<&ClientRateLimiter>,
     ) -> Result<Duration, ShouldAttempt> {
         let request_attempts = cfg
             .load::<RequestAttempts>()
@@ -102,10 +114,12 @@ impl StandardRetryStrategy {
         let token_bucket = cfg.load::<TokenBucket>();
 
         match retry_reason {
-            RetryAction::RetryIndicated(RetryReason::RetryableError { kind, retry_after }) => {
+            RetryAction::RetryIndicated(
+                retry_reason @ RetryReason::RetryableError { kind, retry_after },
+            ) => {
                 update_rate_limiter_if_exists(
                     runtime_components,
-                    cfg,
+                    rate_limiter,
                     *kind == ErrorKind::ThrottlingError,
                 );
 
@@ -114,10 +128,13 @@ impl StandardRetryStrategy {
                     debug!("explicit request from server to delay {delay:?} before retrying");
                     Ok(delay)
                 } else if let Some(delay) =
-                    check_rate_limiter_for_delay(runtime_components, cfg, *kind)
+                    check_rate_limiter_for_delay(runtime_components, rate_limiter, *kind)
                 {
                     let delay = delay.min(retry_cfg.max_backoff());
                     debug!("rate limiter has requested a {delay:?} delay before retrying");
+                    if let Some(tb) = token_bucket {
+                        tb.notify(TokenBucketEvent::RateLimiterDelayApplied { delay });
+                    }
                     Ok(delay)
                 } else {
                     if let Some(tb) = token_bucket {
@@ -130,25 +147,26 @@ impl StandardRetryStrategy {
                         }
                     }
 
-                    let base = if retry_cfg.use_static_exponential_base() {
-                        1.0
-                    } else {
-                        fastrand::f64()
-                    };
-                    let backoff = calculate_exponential_backoff(
-                        // Generate a random base multiplier to create jitter
-                        base,
-                        // Get the backoff time multiplier in seconds (with fractional seconds)
-                        retry_cfg.initial_backoff().as_secs_f64(),
-                        // `self.local.attempts` tracks number of requests made including the initial request
-                        // The initial attempt shouldn't count towards backoff calculations so we subtract it
-                        request_attempts - 1,
+                    let previous_backoff = cfg.load::<PreviousBackoff>();
+                    let backoff_ctx = BackoffContext::new(
+                        request_attempts,
+                        retry_cfg,
+                        retry_reason,
+                        previous_backoff.and_then(PreviousBackoff::get),
                     );
-                    Ok(Duration::from_secs_f64(backoff).min(retry_cfg.max_backoff()))
+                    let backoff = match &self.backoff_policy {
+                        Some(backoff_policy) => backoff_policy.backoff(&backoff_ctx),
+                        None => policy_for_mode(retry_cfg.backoff_mode()).backoff(&backoff_ctx),
+                    };
+                    let backoff = backoff.min(retry_cfg.max_backoff());
+                    if let Some(previous_backoff) = previous_backoff {
+                        previous_backoff.set(backoff);
+                    }
+                    Ok(backoff)
                 }
             }
             RetryAction::RetryForbidden | RetryAction::NoActionIndicated => {
-                update_rate_limiter_if_exists(runtime_components, cfg, false);
+                update_rate_limiter_if_exists(runtime_components, rate_limiter, false);
                 debug!(
                     attempts = request_attempts,
                     max_attempts = retry_cfg.max_attempts(),
@@ -159,25 +177,24 @@ impl StandardRetryStrategy {
             _ => unreachable!("RetryAction is non-exhaustive"),
         }
     }
-}
-
-enum ReleaseResult {
-    APermitWasReleased,
-    NoPermitWasReleased,
-}
 
-impl RetryStrategy for StandardRetryStrategy {
-    fn should_attempt_initial_request(
+    /// Decide whether the initial request may be sent, taking a token from the given rate limiter
+    /// if there is one.
+    pub(super) fn should_attempt_initial_request_with_rate_limiter(
         &self,
         runtime_components: &RuntimeComponents,
         cfg: &ConfigBag,
+        rate_limiter: Option<&ClientRateLimiter>,
     ) -> Result<ShouldAttempt, BoxError> {
-        if let Some(crl) = Self::adaptive_retry_rate_limiter(runtime_components, cfg) {
+        if let Some(crl) = rate_limiter {
             let seconds_since_unix_epoch = get_seconds_since_unix_epoch(runtime_components);
             if let Err(delay) = crl.acquire_permission_to_send_a_request(
                 seconds_since_unix_epoch,
                 RequestReason::InitialRequest,
             ) {
+                if let Some(tb) = cfg.load::<TokenBucket>() {
+                    tb.notify(TokenBucketEvent::RateLimiterDelayApplied { delay });
+                }
                 return Ok(ShouldAttempt::YesAfterDelay(delay));
             }
         } else {
@@ -187,11 +204,14 @@ impl RetryStrategy for StandardRetryStrategy {
         Ok(ShouldAttempt::Yes)
     }
 
-    fn should_attempt_retry(
+    /// Decide whether a failed request should be retried, updating the given rate limiter (if any)
+    /// with the outcome of the request.
+    pub(super) fn should_attempt_retry_with_rate_limiter(
         &self,
         ctx: &InterceptorContext,
         runtime_components: &RuntimeComponents,
         cfg: &ConfigBag,
+        rate_limiter: Option<&ClientRateLimiter>,
     ) -> Result<ShouldAttempt, BoxError> {
         let retry_cfg = cfg.load::<RetryConfig>().expect("retry config is required");
         // Look a the result. If it's OK then we're done; No retry required. Otherwise, we need to inspect it
@@ -203,7 +223,7 @@ impl RetryStrategy for StandardRetryStrategy {
             debug!("request succeeded, no retry necessary");
             if let Some(tb) = token_bucket {
                 // If this retry strategy is holding any permits, release them back to the bucket.
-                if let NoPermitWasReleased = self.release_retry_permit() {
+                if let NoPermitWasReleased = self.release_retry_permit(tb) {
                     // In the event that there was no retry permit to release, we generate new
                     // permits from nothing. We do this to make up for permits we had to "forget".
                     // Otherwise, repeated retries would empty the bucket and nothing could fill it
@@ -211,7 +231,7 @@ impl RetryStrategy for StandardRetryStrategy {
                     tb.regenerate_a_token();
                 }
             }
-            update_rate_limiter_if_exists(runtime_components, cfg, false);
+            update_rate_limiter_if_exists(runtime_components, rate_limiter, false);
 
             return Ok(ShouldAttempt::No);
         }
@@ -222,7 +242,7 @@ impl RetryStrategy for StandardRetryStrategy {
             .expect("at least one request attempt is made before any retry is attempted")
             .attempts();
         if request_attempts >= retry_cfg.max_attempts() {
-            update_rate_limiter_if_exists(runtime_components, cfg, false);
+            update_rate_limiter_if_exists(runtime_components, rate_limiter, false);
 
             debug!(
                 attempts = request_attempts,
@@ -237,12 +257,17 @@ impl RetryStrategy for StandardRetryStrategy {
         let classifier_result = run_classifiers_on_ctx(retry_classifiers, ctx);
 
         // Calculate the appropriate backoff time.
-        let backoff =
-            match self.calculate_backoff(runtime_components, cfg, retry_cfg, &classifier_result) {
-                Ok(value) => value,
-                // In some cases, backoff calculation will decide that we shouldn't retry at all.
-                Err(value) => return Ok(value),
-            };
+        let backoff = match self.calculate_backoff(
+            runtime_components,
+            cfg,
+            retry_cfg,
+            &classifier_result,
+            rate_limiter,
+        ) {
+            Ok(value) => value,
+            // In some cases, backoff calculation will decide that we shouldn't retry at all.
+            Err(value) => return Ok(value),
+        };
         debug!(
             "attempt #{request_attempts} failed with {:?}; retrying after {:?}",
             classifier_result, backoff,
@@ -252,12 +277,47 @@ impl RetryStrategy for StandardRetryStrategy {
     }
 }
 
+enum ReleaseResult {
+    APermitWasReleased,
+    NoPermitWasReleased,
+}
+
+impl RetryStrategy for StandardRetryStrategy {
+    fn should_attempt_initial_request(
+        &self,
+        runtime_components: &RuntimeComponents,
+        cfg: &ConfigBag,
+    ) -> Result<ShouldAttempt, BoxError> {
+        let rate_limiter = Self::adaptive_retry_rate_limiter(runtime_components, cfg);
+        self.should_attempt_initial_request_with_rate_limiter(
+            runtime_components,
+            cfg,
+            rate_limiter.as_ref(),
+        )
+    }
+
+    fn should_attempt_retry(
+        &self,
+        ctx: &InterceptorContext,
+        runtime_components: &RuntimeComponents,
+        cfg: &ConfigBag,
+    ) -> Result<ShouldAttempt, BoxError> {
+        let rate_limiter = Self::adaptive_retry_rate_limiter(runtime_components, cfg);
+        self.should_attempt_retry_with_rate_limiter(
+            ctx,
+            runtime_components,
+            cfg,
+            rate_limiter.as_ref(),
+        )
+    }
+}
+
 fn update_rate_limiter_if_exists(
     runtime_components: &RuntimeComponents,
-    cfg: &ConfigBag,
+    rate_limiter: Option<&ClientRateLimiter>,
     is_throttling_error: bool,
 ) {
-    if let Some(crl) = StandardRetryStrategy::adaptive_retry_rate_limiter(runtime_components, cfg) {
+    if let Some(crl) = rate_limiter {
         let seconds_since_unix_epoch = get_seconds_since_unix_epoch(runtime_components);
         crl.update_rate_limiter(seconds_since_unix_epoch, is_throttling_error);
     }
@@ -265,10 +325,10 @@ fn update_rate_limiter_if_exists(
 
 fn check_rate_limiter_for_delay(
     runtime_components: &RuntimeComponents,
-    cfg: &ConfigBag,
+    rate_limiter: Option<&ClientRateLimiter>,
     kind: ErrorKind,
 ) -> Option<Duration> {
-    if let Some(crl) = StandardRetryStrategy::adaptive_retry_rate_limiter(runtime_components, cfg) {
+    if let Some(crl) = rate_limiter {
         let retry_reason = if kind == ErrorKind::ThrottlingError {
             RequestReason::RetryTimeout
         } else {
@@ -285,14 +345,7 @@ fn check_rate_limiter_for_delay(
     None
 }
 
-fn calculate_exponential_backoff(base: f64, initial_backoff: f64, retry_attempts: u32) -> f64 {
-    2_u32
-        .checked_pow(retry_attempts)
-        .map(|backoff| (backoff as f64) * base * initial_backoff)
-        .unwrap_or(f64::MAX)
-}
-
-fn get_seconds_since_unix_epoch(runtime_components: &RuntimeComponents) -> f64 {
+pub(super) fn get_seconds_since_unix_epoch(runtime_components: &RuntimeComponents) -> f64 {
     let request_time = runtime_components
         .time_source()
         .expect("time source required for retries");
@@ -306,6 +359,7 @@ fn get_seconds_since_unix_epoch(runtime_components: &RuntimeComponents) -> f64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::client::retries::backoff::calculate_exponential_backoff;
     use aws_smithy_runtime_api::client::orchestrator::OrchestratorError;
     use aws_smithy_runtime_api::client::retries::classifiers::{
         ClassifyRetry, RetryAction, SharedRetryClassifier,
@@ -764,6 +818,125 @@ mod tests {
         assert_eq!(token_bucket.available_permits(), 480);
     }
 
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn custom_backoff_policy_receives_retry_reason() {
+        use crate::client::retries::backoff::{BackoffContext, BackoffPolicy};
+
+        #[derive(Debug)]
+        struct LongerWhenThrottled;
+        impl BackoffPolicy for LongerWhenThrottled {
+            fn backoff(&self, ctx: &BackoffContext<'_>) -> Duration {
+                match ctx.error_kind() {
+                    Some(ErrorKind::ThrottlingError) => Duration::from_secs(5),
+                    _ => Duration::from_millis(100),
+                }
+            }
+        }
+
+        let (mut cfg, rc, ctx) = setup_test(
+            vec![
+                RetryAction::server_error(),
+                RetryAction::throttling_error(),
+                RetryAction::throttling_error(),
+            ],
+            RetryConfig::standard()
+                .with_max_attempts(5)
+                .with_max_backoff(Duration::from_secs(3)),
+        );
+        let strategy = StandardRetryStrategy::new().with_backoff_policy(LongerWhenThrottled);
+
+        cfg.interceptor_state().store_put(RequestAttempts::new(1));
+        let should_retry = strategy.should_attempt_retry(&ctx, &rc, &cfg).unwrap();
+        assert_eq!(should_retry.expect_delay(), Duration::from_millis(100));
+
+        // The policy's delay is still capped at the max backoff
+        cfg.interceptor_state().store_put(RequestAttempts::new(2));
+        let should_retry = strategy.should_attempt_retry(&ctx, &rc, &cfg).unwrap();
+        assert_eq!(should_retry.expect_delay(), Duration::from_secs(3));
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn decorrelated_jitter_uses_previous_backoff() {
+        use crate::client::retries::backoff::DecorrelatedJitterBackoff;
+
+        let (mut cfg, rc, ctx) = setup_test(
+            vec![RetryAction::server_error()],
+            RetryConfig::standard()
+                .with_use_static_exponential_base(true)
+                .with_max_attempts(5)
+                .with_max_backoff(Duration::from_secs(20)),
+        );
+        let strategy =
+            StandardRetryStrategy::new().with_backoff_policy(DecorrelatedJitterBackoff::new());
+
+        cfg.interceptor_state().store_put(RequestAttempts::new(1));
+        let should_retry = strategy.should_attempt_retry(&ctx, &rc, &cfg).unwrap();
+        assert_eq!(should_retry.expect_delay(), Duration::from_secs(3));
+
+        cfg.interceptor_state().store_put(RequestAttempts::new(2));
+        let previous_backoff = PreviousBackoff::new();
+        previous_backoff.set(Duration::from_secs(3));
+        cfg.interceptor_state().store_put(previous_backoff);
+        let should_retry = strategy.should_attempt_retry(&ctx, &rc, &cfg).unwrap();
+        assert_eq!(should_retry.expect_delay(), Duration::from_secs(9));
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn backoff_mode_selects_the_backoff_policy() {
+        use aws_smithy_types::retry::BackoffMode;
+
+        let (mut cfg, rc, ctx) = setup_test(
+            vec![RetryAction::server_error()],
+            RetryConfig::standard()
+                .with_use_static_exponential_base(true)
+                .with_max_attempts(5)
+                .with_backoff_mode(BackoffMode::DecorrelatedJitter),
+        );
+        let strategy = StandardRetryStrategy::new();
+
+        cfg.interceptor_state().store_put(RequestAttempts::new(2));
+        let previous_backoff = PreviousBackoff::new();
+        previous_backoff.set(Duration::from_secs(3));
+        cfg.interceptor_state().store_put(previous_backoff);
+        let should_retry = strategy.should_attempt_retry(&ctx, &rc, &cfg).unwrap();
+        assert_eq!(should_retry.expect_delay(), Duration::from_secs(9));
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn previous_backoff_only_records_the_policy_backoff() {
+        let (mut cfg, rc, ctx) = setup_test(
+            vec![
+                RetryAction::server_error(),
+                RetryAction::retryable_error_with_explicit_delay(
+                    ErrorKind::ServerError,
+                    Duration::from_secs(10),
+                ),
+            ],
+            RetryConfig::standard()
+                .with_use_static_exponential_base(true)
+                .with_max_attempts(5)
+                .with_max_backoff(Duration::from_secs(20)),
+        );
+        let strategy = StandardRetryStrategy::new();
+        let previous_backoff = PreviousBackoff::new();
+        cfg.interceptor_state().store_put(previous_backoff.clone());
+
+        cfg.interceptor_state().store_put(RequestAttempts::new(1));
+        let should_retry = strategy.should_attempt_retry(&ctx, &rc, &cfg).unwrap();
+        assert_eq!(should_retry.expect_delay(), Duration::from_secs(1));
+        assert_eq!(previous_backoff.get(), Some(Duration::from_secs(1)));
+
+        // A server-provided delay is used, but it isn't what the policy calculated
+        cfg.interceptor_state().store_put(RequestAttempts::new(2));
+        let should_retry = strategy.should_attempt_retry(&ctx, &rc, &cfg).unwrap();
+        assert_eq!(should_retry.expect_delay(), Duration::from_secs(10));
+        assert_eq!(previous_backoff.get(), Some(Duration::from_secs(1)));
+    }
+
     #[test]
     fn calculate_exponential_backoff_where_initial_backoff_is_one() {
         let initial_backoff = 1.0;
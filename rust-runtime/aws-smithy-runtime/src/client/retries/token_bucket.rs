@@ -3,9 +3,13 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use aws_smithy_types::config_bag::{Storable, StoreReplace};
+use aws_smithy_runtime_api::client::runtime_plugin::RuntimePlugin;
+use aws_smithy_runtime_api::impl_shared_conversions;
+use aws_smithy_types::config_bag::{FrozenLayer, Layer, Storable, StoreReplace};
 use aws_smithy_types::retry::ErrorKind;
+use std::fmt;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tracing::trace;
 
@@ -14,6 +18,65 @@ const RETRY_COST: u32 = 5;
 const RETRY_TIMEOUT_COST: u32 = RETRY_COST * 2;
 const PERMIT_REGENERATION_AMOUNT: usize = 1;
 
+/// An event emitted by a [`TokenBucket`] to its [observer](ObserveTokenBucket).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TokenBucketEvent {
+    /// Permits were taken from the bucket so that a request could be retried.
+    PermitAcquired {
+        /// The number of permits taken from the bucket.
+        cost: u32,
+    },
+    /// Permits were returned to the bucket, either because a retry succeeded or because a
+    /// request succeeded without needing to retry.
+    PermitRefunded {
+        /// The number of permits returned to the bucket.
+        amount: u32,
+    },
+    /// A request was not retried because there weren't enough permits left in the bucket.
+    QuotaExhausted {
+        /// The number of permits the retry would have cost.
+        cost: u32,
+    },
+    /// The client rate limiter delayed a request, as happens with adaptive retry after a
+    /// throttling error.
+    RateLimiterDelayApplied {
+        /// How long the request was delayed.
+        delay: Duration,
+    },
+}
+
+/// Observes the events emitted by a [`TokenBucket`].
+///
+/// Observers are called synchronously while the retry strategy is deciding whether to retry, so
+/// they should return quickly (for example, by updating a metric).
+pub trait ObserveTokenBucket: Send + Sync + fmt::Debug {
+    /// Called for every event emitted by the given `token_bucket`.
+    ///
+    /// The bucket's capacity after the event can be read with
+    /// [`TokenBucket::available_permits`] and [`TokenBucket::max_permits`].
+    fn on_event(&self, event: &TokenBucketEvent, token_bucket: &TokenBucket);
+}
+
+/// A shared token bucket observer.
+#[derive(Clone, Debug)]
+pub struct SharedTokenBucketObserver(Arc<dyn ObserveTokenBucket>);
+
+impl SharedTokenBucketObserver {
+    /// Creates a new `SharedTokenBucketObserver` from the given observer.
+    pub fn new(observer: impl ObserveTokenBucket + 'static) -> Self {
+        Self(Arc::new(observer))
+    }
+}
+
+impl ObserveTokenBucket for SharedTokenBucketObserver {
+    fn on_event(&self, event: &TokenBucketEvent, token_bucket: &TokenBucket) {
+        self.0.on_event(event, token_bucket)
+    }
+}
+
+impl_shared_conversions!(convert SharedTokenBucketObserver from ObserveTokenBucket using SharedTokenBucketObserver::new);
+
 /// Token bucket used for standard and adaptive retry.
 #[derive(Clone, Debug)]
 pub struct TokenBucket {
@@ -21,6 +84,7 @@ pub struct TokenBucket {
     max_permits: usize,
     timeout_retry_cost: u32,
     retry_cost: u32,
+    observer: Option<SharedTokenBucketObserver>,
 }
 
 impl Storable for TokenBucket {
@@ -34,6 +98,7 @@ impl Default for TokenBucket {
             max_permits: DEFAULT_CAPACITY,
             timeout_retry_cost: RETRY_TIMEOUT_COST,
             retry_cost: RETRY_COST,
+            observer: None,
         }
     }
 }
@@ -46,9 +111,18 @@ impl TokenBucket {
             max_permits: initial_quota,
             retry_cost: RETRY_COST,
             timeout_retry_cost: RETRY_TIMEOUT_COST,
+            observer: None,
         }
     }
 
+    /// Notify the given observer of every event emitted by this bucket.
+    ///
+    /// Clones of this bucket share the observer.
+    pub fn with_observer(mut self, observer: impl ObserveTokenBucket + 'static) -> Self {
+        self.observer = Some(SharedTokenBucketObserver::new(observer));
+        self
+    }
+
     pub(crate) fn acquire(&self, err: &ErrorKind) -> Option<OwnedSemaphorePermit> {
         let retry_cost = if err == &ErrorKind::TransientError {
             self.timeout_retry_cost
@@ -56,21 +130,143 @@ impl TokenBucket {
             self.retry_cost
         };
 
-        self.semaphore
-            .clone()
-            .try_acquire_many_owned(retry_cost)
-            .ok()
+        match self.semaphore.clone().try_acquire_many_owned(retry_cost) {
+            Ok(permit) => {
+                self.notify(TokenBucketEvent::PermitAcquired { cost: retry_cost });
+                Some(permit)
+            }
+            Err(_) => {
+                self.notify(TokenBucketEvent::QuotaExhausted { cost: retry_cost });
+                None
+            }
+        }
+    }
+
+    /// Returns a permit acquired from this bucket.
+    pub(crate) fn release(&self, permit: OwnedSemaphorePermit) {
+        let amount = permit.num_permits() as u32;
+        drop(permit);
+        self.notify(TokenBucketEvent::PermitRefunded { amount });
     }
 
     pub(crate) fn regenerate_a_token(&self) {
         if self.semaphore.available_permits() < (self.max_permits) {
             trace!("adding {PERMIT_REGENERATION_AMOUNT} back into the bucket");
-            self.semaphore.add_permits(PERMIT_REGENERATION_AMOUNT)
+            self.semaphore.add_permits(PERMIT_REGENERATION_AMOUNT);
+            self.notify(TokenBucketEvent::PermitRefunded {
+                amount: PERMIT_REGENERATION_AMOUNT as u32,
+            });
         }
     }
 
-    #[cfg(all(test, feature = "test-util"))]
-    pub(crate) fn available_permits(&self) -> usize {
+    pub(crate) fn notify(&self, event: TokenBucketEvent) {
+        if let Some(observer) = &self.observer {
+            observer.on_event(&event, self);
+        }
+    }
+
+    /// Returns the number of permits currently available in the bucket.
+    pub fn available_permits(&self) -> usize {
         self.semaphore.available_permits()
     }
+
+    /// Returns the maximum number of permits the bucket can hold.
+    pub fn max_permits(&self) -> usize {
+        self.max_permits
+    }
+}
+
+/// A [`RuntimePlugin`] that gives the retry strategy a [`TokenBucket`] to take retry permits from.
+///
+/// This is how an observed bucket is set on a client. For example, with a generated client:
+///
+/// ```ignore
+/// let token_bucket = TokenBucket::new(500).with_observer(MyObserver::new());
+/// let config = my_service::Config::builder()
+///     .runtime_plugin(TokenBucketRuntimePlugin::new(token_bucket))
+///     // ...
+///     .build();
+/// ```
+///
+/// Every operation invoked by the client shares the given bucket.
+#[derive(Debug)]
+pub struct TokenBucketRuntimePlugin {
+    config: FrozenLayer,
+}
+
+impl TokenBucketRuntimePlugin {
+    /// Creates a new `TokenBucketRuntimePlugin` that uses the given `token_bucket`.
+    pub fn new(token_bucket: TokenBucket) -> Self {
+        let mut layer = Layer::new("TokenBucketRuntimePlugin");
+        layer.store_put(token_bucket);
+        Self {
+            config: layer.freeze(),
+        }
+    }
+}
+
+impl RuntimePlugin for TokenBucketRuntimePlugin {
+    fn config(&self) -> Option<FrozenLayer> {
+        Some(self.config.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ObserveTokenBucket, TokenBucket, TokenBucketEvent};
+    use aws_smithy_types::retry::ErrorKind;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Default, Clone)]
+    struct RecordingObserver(Arc<Mutex<Vec<(TokenBucketEvent, usize)>>>);
+
+    impl ObserveTokenBucket for RecordingObserver {
+        fn on_event(&self, event: &TokenBucketEvent, token_bucket: &TokenBucket) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((event.clone(), token_bucket.available_permits()));
+        }
+    }
+
+    #[test]
+    fn observer_receives_events_with_capacity() {
+        let observer = RecordingObserver::default();
+        let token_bucket = TokenBucket::new(12).with_observer(observer.clone());
+        assert_eq!(12, token_bucket.max_permits());
+
+        let permit = token_bucket.acquire(&ErrorKind::ServerError).unwrap();
+        let timeout_permit = token_bucket.acquire(&ErrorKind::TransientError);
+        assert!(timeout_permit.is_none());
+        token_bucket.release(permit);
+        token_bucket.regenerate_a_token();
+
+        assert_eq!(
+            vec![
+                (TokenBucketEvent::PermitAcquired { cost: 5 }, 7),
+                (TokenBucketEvent::QuotaExhausted { cost: 10 }, 7),
+                (TokenBucketEvent::PermitRefunded { amount: 5 }, 12),
+            ],
+            *observer.0.lock().unwrap()
+        );
+    }
+
+    #[test]
+    fn clones_share_the_observer() {
+        let observer = RecordingObserver::default();
+        let token_bucket = TokenBucket::new(5).with_observer(observer.clone());
+        let clone = token_bucket.clone();
+
+        let permit = clone.acquire(&ErrorKind::ThrottlingError).unwrap();
+        permit.forget();
+        token_bucket.regenerate_a_token();
+
+        assert_eq!(
+            vec![
+                (TokenBucketEvent::PermitAcquired { cost: 5 }, 0),
+                (TokenBucketEvent::PermitRefunded { amount: 1 }, 1),
+            ],
+            *observer.0.lock().unwrap()
+        );
+    }
 }
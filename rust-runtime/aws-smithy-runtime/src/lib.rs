@@ -10,8 +10,17 @@
 //!
 //! # Crate Features
 //!
+//! - `connector-hyper-1-x`: Enables an HTTP client built on hyper 1.x. See
+//!   [`hyper_1`](crate::client::http::hyper_1).
+//! - `tls-rustls-hyper-1-x`: Enables HTTPS for the hyper 1.x client using rustls.
+//! - `tls-native-tls-hyper-1-x`: Enables HTTPS for the hyper 1.x client using the platform's
+//!   native TLS implementation.
 //! - `http-auth`: Enables auth scheme and identity resolver implementations for HTTP API Key,
 //!   Basic Auth, Bearer Token, and Digest Auth.
+//! - `metrics`: Enables the [`MetricsInterceptor`](crate::client::metrics::MetricsInterceptor) for
+//!   recording client metrics.
+//! - `metrics-opentelemetry`: Enables recording client metrics into an OpenTelemetry meter. The
+//!   meter type comes from `opentelemetry` 0.21, which makes that version part of the public API.
 //! - `test-util`: Enables utilities for unit tests. DO NOT ENABLE IN PRODUCTION.
 
 #![warn(
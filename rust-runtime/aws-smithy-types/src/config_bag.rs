@@ -708,6 +708,27 @@ impl ConfigBag {
         self.with_fn(name, |_| {})
     }
 
+    /// Create a second bag that shares the layers of this bag.
+    ///
+    /// The interceptor state of this bag is frozen into a shared layer, and both bags get a new,
+    /// empty interceptor state. Changes made to either bag after forking aren't visible in the other.
+    /// An empty interceptor state isn't frozen, so repeatedly forking a bag that was itself returned
+    /// by `fork` (and not modified since) always shares the same layers.
+    ///
+    /// This is used by the orchestrator to run hedged attempts, and isn't part of the public API.
+    #[doc(hidden)]
+    pub fn fork(&mut self, name: impl Into<Cow<'static, str>>) -> ConfigBag {
+        if !self.interceptor_state.is_empty() {
+            let head_name = self.interceptor_state.name.clone();
+            let head = std::mem::replace(&mut self.interceptor_state, Layer::new(head_name));
+            self.tail.push(head.freeze());
+        }
+        ConfigBag {
+            interceptor_state: Layer::new(name),
+            tail: self.tail.clone(),
+        }
+    }
+
     /// Return a value (or values) of type `T` depending on how it has been stored in a `ConfigBag`
     ///
     /// It flexibly chooses to return a single value vs. an iterator of values depending on how
@@ -957,6 +978,54 @@ mod test {
         assert_eq!(bag_1.load::<Foo>(), Some(&Foo(3)));
     }
 
+    #[test]
+    fn forking() {
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        struct Foo(usize);
+        impl Storable for Foo {
+            type Storer = StoreReplace<Foo>;
+        }
+
+        let mut layer = Layer::new("layer");
+        layer.store_put(Foo(0));
+        let mut bag = ConfigBag::of_layers(vec![layer]);
+        bag.interceptor_state().store_put(Foo(1));
+
+        let mut fork = bag.fork("fork");
+        assert_eq!(bag.load::<Foo>(), Some(&Foo(1)));
+        assert_eq!(fork.load::<Foo>(), Some(&Foo(1)));
+
+        fork.interceptor_state().store_put(Foo(2));
+        assert_eq!(bag.load::<Foo>(), Some(&Foo(1)));
+        assert_eq!(fork.load::<Foo>(), Some(&Foo(2)));
+
+        bag.interceptor_state().unset::<Foo>();
+        assert_eq!(bag.load::<Foo>(), None);
+        assert_eq!(fork.load::<Foo>(), Some(&Foo(2)));
+    }
+
+    #[test]
+    fn forking_a_snapshot_does_not_add_layers() {
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        struct Foo(usize);
+        impl Storable for Foo {
+            type Storer = StoreReplace<Foo>;
+        }
+
+        let mut bag = ConfigBag::of_layers(vec![Layer::new("layer")]);
+        bag.interceptor_state().store_put(Foo(1));
+        let mut snapshot = bag.fork("snapshot");
+        let layers = snapshot.tail.len();
+
+        for i in 0..3 {
+            let mut fork = snapshot.fork("fork");
+            fork.interceptor_state().store_put(Foo(i));
+            assert_eq!(fork.tail.len(), layers);
+        }
+        assert_eq!(snapshot.tail.len(), layers);
+        assert_eq!(snapshot.load::<Foo>(), Some(&Foo(1)));
+    }
+
     #[test]
     fn get_mut_or_else() {
         #[derive(Clone, Debug, PartialEq, Eq, Default)]
@@ -28,6 +28,8 @@ pub mod error;
 pub mod event_stream;
 pub mod primitive;
 pub mod retry;
+#[cfg(all(aws_sdk_unstable, feature = "serde-serialize"))]
+pub mod sensitive;
 pub mod timeout;
 
 /// Utilities for type erasure.
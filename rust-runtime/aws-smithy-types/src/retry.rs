@@ -122,6 +122,27 @@ impl FromStr for RetryMode {
     }
 }
 
+/// Specifies how the delay between retry attempts is calculated.
+///
+/// See [exponential backoff and jitter](https://aws.amazon.com/blogs/architecture/exponential-backoff-and-jitter/)
+/// for a comparison of these approaches.
+#[non_exhaustive]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Default)]
+pub enum BackoffMode {
+    /// Exponential backoff with "full jitter": a random delay between zero and
+    /// `initial_backoff * 2^retry_attempts`. This is the default.
+    #[default]
+    Exponential,
+
+    /// Exponential backoff with "equal jitter": half of the exponential delay, plus a random
+    /// amount up to the other half.
+    EqualJitter,
+
+    /// Backoff with "decorrelated jitter": a random delay between `initial_backoff` and three
+    /// times the previous delay.
+    DecorrelatedJitter,
+}
+
 /// Failure to parse a `RetryMode` from string.
 #[derive(Debug)]
 pub struct RetryModeParseError {
@@ -157,6 +178,7 @@ pub struct RetryConfigBuilder {
     initial_backoff: Option<Duration>,
     max_backoff: Option<Duration>,
     reconnect_mode: Option<ReconnectMode>,
+    backoff_mode: Option<BackoffMode>,
 }
 
 impl RetryConfigBuilder {
@@ -237,6 +259,18 @@ impl RetryConfigBuilder {
         self
     }
 
+    /// Set the [`BackoffMode`] used to calculate the delay between attempts.
+    pub fn set_backoff_mode(&mut self, backoff_mode: Option<BackoffMode>) -> &mut Self {
+        self.backoff_mode = backoff_mode;
+        self
+    }
+
+    /// Set the [`BackoffMode`] used to calculate the delay between attempts.
+    pub fn backoff_mode(mut self, backoff_mode: BackoffMode) -> Self {
+        self.set_backoff_mode(Some(backoff_mode));
+        self
+    }
+
     /// Merge two builders together. Values from `other` will only be used as a fallback for values
     /// from `self` Useful for merging configs from different sources together when you want to
     /// handle "precedence" per value instead of at the config level
@@ -260,6 +294,7 @@ impl RetryConfigBuilder {
             initial_backoff: self.initial_backoff.or(other.initial_backoff),
             max_backoff: self.max_backoff.or(other.max_backoff),
             reconnect_mode: self.reconnect_mode.or(other.reconnect_mode),
+            backoff_mode: self.backoff_mode.or(other.backoff_mode),
         }
     }
 
@@ -275,6 +310,7 @@ impl RetryConfigBuilder {
                 .reconnect_mode
                 .unwrap_or(ReconnectMode::ReconnectOnTransientError),
             max_backoff: self.max_backoff.unwrap_or_else(|| Duration::from_secs(20)),
+            backoff_mode: self.backoff_mode.unwrap_or_default(),
             use_static_exponential_base: false,
         }
     }
@@ -289,6 +325,7 @@ pub struct RetryConfig {
     initial_backoff: Duration,
     max_backoff: Duration,
     reconnect_mode: ReconnectMode,
+    backoff_mode: BackoffMode,
     use_static_exponential_base: bool,
 }
 
@@ -325,6 +362,7 @@ impl RetryConfig {
             initial_backoff: Duration::from_secs(1),
             reconnect_mode: ReconnectMode::ReconnectOnTransientError,
             max_backoff: Duration::from_secs(20),
+            backoff_mode: BackoffMode::Exponential,
             use_static_exponential_base: false,
         }
     }
@@ -337,6 +375,7 @@ impl RetryConfig {
             initial_backoff: Duration::from_secs(1),
             reconnect_mode: ReconnectMode::ReconnectOnTransientError,
             max_backoff: Duration::from_secs(20),
+            backoff_mode: BackoffMode::Exponential,
             use_static_exponential_base: false,
         }
     }
@@ -399,6 +438,15 @@ impl RetryConfig {
         self
     }
 
+    /// Set the [`BackoffMode`] used to calculate the delay between attempts.
+    ///
+    /// A retry strategy may be configured with its own backoff policy, which takes precedence
+    /// over this setting.
+    pub fn with_backoff_mode(mut self, backoff_mode: BackoffMode) -> Self {
+        self.backoff_mode = backoff_mode;
+        self
+    }
+
     /// Hint to the retry strategy whether to use a static exponential base.
     ///
     /// When a retry strategy uses exponential backoff, it calculates a random base. This causes the
@@ -423,6 +471,11 @@ impl RetryConfig {
         self.reconnect_mode
     }
 
+    /// Returns the [`BackoffMode`]
+    pub fn backoff_mode(&self) -> BackoffMode {
+        self.backoff_mode
+    }
+
     /// Returns the max attempts.
     pub fn max_attempts(&self) -> u32 {
         self.max_attempts
@@ -454,7 +507,7 @@ impl RetryConfig {
 
 #[cfg(test)]
 mod tests {
-    use crate::retry::{RetryConfigBuilder, RetryMode};
+    use crate::retry::{BackoffMode, RetryConfigBuilder, RetryMode};
     use std::str::FromStr;
 
     #[test]
@@ -471,6 +524,24 @@ mod tests {
         assert_eq!(retry_config.mode, RetryMode::Adaptive);
     }
 
+    #[test]
+    fn retry_config_builder_backoff_mode() {
+        assert_eq!(
+            RetryConfigBuilder::new().build().backoff_mode(),
+            BackoffMode::Exponential
+        );
+        let other_builder = RetryConfigBuilder::new().backoff_mode(BackoffMode::EqualJitter);
+        let retry_config = RetryConfigBuilder::new()
+            .backoff_mode(BackoffMode::DecorrelatedJitter)
+            .take_unset_from(other_builder.clone())
+            .build();
+        assert_eq!(retry_config.backoff_mode(), BackoffMode::DecorrelatedJitter);
+        let retry_config = RetryConfigBuilder::new()
+            .take_unset_from(other_builder)
+            .build();
+        assert_eq!(retry_config.backoff_mode(), BackoffMode::EqualJitter);
+    }
+
     #[test]
     fn retry_mode_from_str_parses_valid_strings_regardless_of_casing() {
         assert_eq!(
@@ -0,0 +1,49 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Serde helpers for members modeled with the Smithy `@sensitive` trait.
+//!
+//! Generated types that opt into serde support redact optional sensitive members by skipping them
+//! during serialization. Sensitive members that can't be left out, such as required members or
+//! union variants, refuse to serialize instead of writing out their value.
+
+use serde::ser::Error;
+use serde::Serializer;
+
+/// Serializer function that fails instead of writing out a sensitive value.
+///
+/// This is intended to be used with `#[serde(serialize_with = "...")]` on fields that
+/// are sensitive and cannot be skipped.
+pub fn refuse_serialize<T, S>(_value: &T, _serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized,
+    S: Serializer,
+{
+    Err(S::Error::custom(
+        "refusing to serialize a value modeled as `@sensitive`",
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Credentials {
+        user: String,
+        #[serde(serialize_with = "super::refuse_serialize")]
+        password: String,
+    }
+
+    #[test]
+    fn sensitive_member_is_not_serialized() {
+        let credentials = Credentials {
+            user: "user".into(),
+            password: "hunter2".into(),
+        };
+        let err = serde_json::to_string(&credentials).expect_err("sensitive member");
+        assert!(err.to_string().contains("@sensitive"), "{err}");
+    }
+}
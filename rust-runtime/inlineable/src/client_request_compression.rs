@@ -0,0 +1,232 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::borrow::Cow;
+
+use aws_smithy_compression::body::CompressedBody;
+use aws_smithy_compression::{
+    CompressionAlgorithm, CompressionOptions, DEFAULT_MIN_COMPRESSION_SIZE_BYTES,
+};
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::interceptors::context::BeforeTransmitInterceptorContextMut;
+use aws_smithy_runtime_api::client::interceptors::{Intercept, SharedInterceptor};
+use aws_smithy_runtime_api::client::runtime_components::{
+    RuntimeComponents, RuntimeComponentsBuilder,
+};
+use aws_smithy_runtime_api::client::runtime_plugin::RuntimePlugin;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+
+/// Whether request compression is disabled. Stored in the config bag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DisableRequestCompression(pub(crate) bool);
+
+impl Storable for DisableRequestCompression {
+    type Storer = StoreReplace<Self>;
+}
+
+/// The minimum size, in bytes, of request payloads that are compressed. Stored in the config bag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RequestMinCompressionSizeBytes(pub(crate) u32);
+
+impl Storable for RequestMinCompressionSizeBytes {
+    type Storer = StoreReplace<Self>;
+}
+
+#[derive(Debug)]
+pub(crate) struct RequestCompressionRuntimePlugin {
+    runtime_components: RuntimeComponentsBuilder,
+}
+
+impl RequestCompressionRuntimePlugin {
+    /// Creates a new plugin that compresses requests with the first of `encodings` that is
+    /// supported. Requests aren't compressed if none of them are.
+    pub(crate) fn new(encodings: &[&str]) -> Self {
+        let mut runtime_components =
+            RuntimeComponentsBuilder::new("RequestCompressionRuntimePlugin");
+        if let Some(algorithm) = encodings
+            .iter()
+            .find_map(|encoding| encoding.parse::<CompressionAlgorithm>().ok())
+        {
+            runtime_components.push_interceptor(SharedInterceptor::new(
+                RequestCompressionInterceptor { algorithm },
+            ));
+        }
+        Self { runtime_components }
+    }
+}
+
+impl RuntimePlugin for RequestCompressionRuntimePlugin {
+    fn runtime_components(
+        &self,
+        _: &RuntimeComponentsBuilder,
+    ) -> Cow<'_, RuntimeComponentsBuilder> {
+        Cow::Borrowed(&self.runtime_components)
+    }
+}
+
+/// Compresses request payloads, and appends the algorithm to the `Content-Encoding` header.
+///
+/// Payloads that are smaller than [`RequestMinCompressionSizeBytes`] are sent uncompressed,
+/// except for streaming payloads, whose size isn't known in advance.
+#[derive(Debug)]
+struct RequestCompressionInterceptor {
+    algorithm: CompressionAlgorithm,
+}
+
+impl Intercept for RequestCompressionInterceptor {
+    fn name(&self) -> &'static str {
+        "RequestCompressionInterceptor"
+    }
+
+    fn modify_before_signing(
+        &self,
+        context: &mut BeforeTransmitInterceptorContextMut<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        if let Some(DisableRequestCompression(true)) = cfg.load::<DisableRequestCompression>() {
+            return Ok(());
+        }
+        let min_compression_size_bytes = cfg
+            .load::<RequestMinCompressionSizeBytes>()
+            .map_or(DEFAULT_MIN_COMPRESSION_SIZE_BYTES, |min| min.0);
+        let options = CompressionOptions::default()
+            .with_min_compression_size_bytes(min_compression_size_bytes)?;
+
+        let algorithm = self.algorithm;
+        let request = context.request_mut();
+        if let Some(bytes) = request.body().bytes() {
+            if bytes.len() < min_compression_size_bytes as usize {
+                return Ok(());
+            }
+            let compressed = algorithm.compress_bytes(bytes, &options)?;
+            if request.headers().contains_key("content-length") {
+                request
+                    .headers_mut()
+                    .insert("content-length", compressed.len().to_string());
+            }
+            *request.body_mut() = SdkBody::from(compressed);
+        } else {
+            let body = std::mem::replace(request.body_mut(), SdkBody::taken());
+            *request.body_mut() = body.map(move |body| {
+                SdkBody::from_body_0_4(CompressedBody::new(body, algorithm.encoder(&options)))
+            });
+            request.headers_mut().remove("content-length");
+        }
+
+        let content_encoding = match request.headers().get("content-encoding") {
+            Some(encoding) => format!("{encoding}, {algorithm}"),
+            None => algorithm.to_string(),
+        };
+        request
+            .headers_mut()
+            .insert("content-encoding", content_encoding);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_smithy_compression::MAX_MIN_COMPRESSION_SIZE_BYTES;
+    use aws_smithy_runtime_api::client::interceptors::context::{Input, InterceptorContext};
+    use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+    use aws_smithy_types::config_bag::Layer;
+
+    const BODY: &str = "Hello, world! Hello, world! Hello, world! Hello, world!";
+
+    fn compress(request: HttpRequest, cfg: &mut ConfigBag) -> HttpRequest {
+        let mut context = InterceptorContext::new(Input::doesnt_matter());
+        context.enter_serialization_phase();
+        context.set_request(request);
+        let _ = context.take_input();
+        context.enter_before_transmit_phase();
+        let runtime_components = RuntimeComponentsBuilder::for_tests().build().unwrap();
+        let interceptor = RequestCompressionInterceptor {
+            algorithm: CompressionAlgorithm::Gzip,
+        };
+        let mut context = BeforeTransmitInterceptorContextMut::from(&mut context);
+        interceptor
+            .modify_before_signing(&mut context, &runtime_components, cfg)
+            .unwrap();
+        context.request_mut().try_clone().unwrap()
+    }
+
+    fn cfg(min_compression_size_bytes: u32) -> ConfigBag {
+        let mut layer = Layer::new("test");
+        layer.store_put(RequestMinCompressionSizeBytes(min_compression_size_bytes));
+        ConfigBag::of_layers(vec![layer])
+    }
+
+    fn request(body: &'static str) -> HttpRequest {
+        let mut request = HttpRequest::new(SdkBody::from(body));
+        request
+            .headers_mut()
+            .insert("content-length", body.len().to_string());
+        request
+    }
+
+    #[test]
+    fn payloads_are_compressed() {
+        let mut request = request(BODY);
+        request.headers_mut().insert("content-encoding", "custom");
+        let request = compress(request, &mut cfg(0));
+
+        assert_eq!(
+            Some("custom, gzip"),
+            request.headers().get("content-encoding")
+        );
+        let compressed = request.body().bytes().unwrap();
+        assert_eq!(
+            Some(compressed.len().to_string().as_str()),
+            request.headers().get("content-length")
+        );
+        let decompressed = CompressionAlgorithm::Gzip
+            .decompress_bytes(compressed)
+            .unwrap();
+        assert_eq!(BODY.as_bytes(), &decompressed[..]);
+    }
+
+    #[test]
+    fn small_payloads_are_not_compressed() {
+        let request = compress(request(BODY), &mut cfg(BODY.len() as u32 + 1));
+        assert_eq!(None, request.headers().get("content-encoding"));
+        assert_eq!(BODY.as_bytes(), request.body().bytes().unwrap());
+    }
+
+    #[test]
+    fn compression_can_be_disabled() {
+        let mut cfg = cfg(0);
+        let mut layer = Layer::new("disable");
+        layer.store_put(DisableRequestCompression(true));
+        cfg.push_layer(layer);
+        let request = compress(request(BODY), &mut cfg);
+        assert_eq!(None, request.headers().get("content-encoding"));
+        assert_eq!(BODY.as_bytes(), request.body().bytes().unwrap());
+    }
+
+    #[test]
+    fn streaming_payloads_are_always_compressed() {
+        let request = HttpRequest::new(SdkBody::retryable(|| {
+            SdkBody::from_body_0_4(SdkBody::from(BODY))
+        }));
+        let request = compress(request, &mut cfg(MAX_MIN_COMPRESSION_SIZE_BYTES));
+        assert_eq!(Some("gzip"), request.headers().get("content-encoding"));
+        assert_eq!(None, request.headers().get("content-length"));
+        assert!(request.body().bytes().is_none());
+    }
+
+    #[test]
+    fn unsupported_encodings_are_ignored() {
+        let plugin = RequestCompressionRuntimePlugin::new(&["br"]);
+        let components = plugin.runtime_components(&RuntimeComponentsBuilder::new("test"));
+        assert_eq!(0, components.interceptors().count());
+        let plugin = RequestCompressionRuntimePlugin::new(&["br", "gzip"]);
+        let components = plugin.runtime_components(&RuntimeComponentsBuilder::new("test"));
+        assert_eq!(1, components.interceptors().count());
+    }
+}
@@ -13,6 +13,8 @@ mod client_http_checksum_required;
 #[allow(dead_code)]
 mod client_idempotency_token;
 #[allow(unused)]
+mod client_request_compression;
+#[allow(unused)]
 mod constrained;
 #[allow(dead_code)]
 mod ec2_query_errors;